@@ -2,18 +2,28 @@
 //!
 //! Detects problematic state transitions and alerts operators to issues.
 
-use std::collections::VecDeque;
+use crate::format::{format_ada, format_duration};
+use crate::hardfork::HardFork;
+use crate::metrics::FetchError;
+use crate::opcert::{counter_status, CounterStatus};
+use crate::release::LatestRelease;
+use crate::storage::DiskUsage;
+use crate::timezone::DisplayTimezone;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::PathBuf;
 use tracing::debug;
 
 /// Alert severity levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AlertSeverity {
-    #[allow(dead_code)]
+    #[serde(alias = "info")]
     Info,
+    #[serde(alias = "warning")]
     Warning,
+    #[serde(alias = "critical")]
     Critical,
 }
 
@@ -28,14 +38,17 @@ impl std::fmt::Display for AlertSeverity {
 }
 
 /// A single alert event
-#[derive(Debug, Clone)]
-
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Alert {
     pub timestamp: u64,
     pub node_name: String,
     pub severity: AlertSeverity,
     pub title: String,
     pub message: String,
+    /// For an event ending an earlier alert: that alert's severity, so the
+    /// event reaches the channels the alert went to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolves: Option<AlertSeverity>,
 }
 
 impl Alert {
@@ -45,8 +58,8 @@ impl Alert {
     }
 
     /// Format alert for file logging
-    pub fn log_format(&self) -> String {
-        let datetime = timestamp_to_iso8601(self.timestamp);
+    pub fn log_format(&self, timezone: DisplayTimezone) -> String {
+        let datetime = timezone.iso8601(self.timestamp);
         format!(
             "{} | {} | {} | {} | {}",
             datetime, self.node_name, self.severity, self.title, self.message
@@ -58,16 +71,54 @@ impl Alert {
 pub struct AlertManager {
     node_name: String,
     log_file: Option<PathBuf>,
+    /// Timezone for log file timestamps
+    timezone: DisplayTimezone,
     recent_alerts: VecDeque<Alert>,
     max_recent: usize,
+    /// Alerts raised since the last `take_new` (awaiting persistence)
+    new_alerts: Vec<Alert>,
 
     // State tracking for deduplication
     last_kes_warning: Option<u64>,
     last_peer_warning: Option<u64>,
     last_sync_warning: Option<u64>,
     last_height_stall_warning: Option<u64>,
+    last_lag_warning: Option<u64>,
+    last_density_warning: Option<u64>,
+    last_storage_warning: Option<u64>,
+    /// Epoch for which the boundary countdown alert was raised
+    last_epoch_alert: Option<u64>,
+    last_spike_warning: Option<u64>,
+    last_concentration_warning: Option<u64>,
+    last_fork_warning: Option<u64>,
+    last_connection_error_warning: Option<u64>,
+    last_opcert_warning: Option<u64>,
+    /// Hard fork the countdown alert was raised for
+    last_hard_fork_alert: Option<String>,
+    last_upgrade_warning: Option<u64>,
+    /// Release the update-available alert was raised for
+    last_release_alert: Option<String>,
+    /// An unreachable alert was raised and recovery hasn't been reported yet
+    unreachable_alerted: bool,
+    /// Condition alerts still firing, by title, with their latest severity
+    active: HashMap<String, AlertSeverity>,
+    /// Slow moving average of incoming connections
+    incoming_baseline: Option<f64>,
 }
 
+/// Incoming connections must exceed the baseline by this factor to count as a spike
+const SPIKE_FACTOR: f64 = 2.0;
+/// ... and by at least this many connections
+const SPIKE_MIN_DELTA: f64 = 20.0;
+/// Weight of each new sample in the incoming connection baseline
+const BASELINE_ALPHA: f64 = 0.02;
+/// Seconds connection errors must stay elevated before alerting
+const CONNECTION_ERROR_SUSTAIN_SECS: u64 = 300;
+/// Share of the expected chain density below which density is low
+pub const DENSITY_WARNING_RATIO: f64 = 0.8;
+/// ... and below which block production is failing network-wide
+pub const DENSITY_CRITICAL_RATIO: f64 = 0.5;
+
 impl AlertManager {
     /// Create a new alert manager for a node
     pub fn new(node_name: &str) -> Self {
@@ -76,13 +127,30 @@ impl AlertManager {
         Self {
             node_name: node_name.to_string(),
             log_file,
+            timezone: DisplayTimezone::default(),
             recent_alerts: VecDeque::new(),
             max_recent: 50, // Keep last 50 alerts in memory
+            new_alerts: Vec::new(),
 
             last_kes_warning: None,
             last_peer_warning: None,
             last_sync_warning: None,
             last_height_stall_warning: None,
+            last_lag_warning: None,
+            last_density_warning: None,
+            last_storage_warning: None,
+            last_epoch_alert: None,
+            last_spike_warning: None,
+            last_concentration_warning: None,
+            last_fork_warning: None,
+            last_connection_error_warning: None,
+            last_opcert_warning: None,
+            last_hard_fork_alert: None,
+            last_upgrade_warning: None,
+            last_release_alert: None,
+            unreachable_alerted: false,
+            active: HashMap::new(),
+            incoming_baseline: None,
         }
     }
 
@@ -111,10 +179,16 @@ impl AlertManager {
                         "KES periods remaining: {} (renew certificate immediately)",
                         remaining
                     ),
+                    resolves: None,
                 };
 
-                self.add_alert(alert);
+                self.fire(alert);
                 self.last_kes_warning = Some(now);
+            } else {
+                self.resolve(
+                    "KES Expiry Critical",
+                    format!("KES periods remaining: {}", remaining),
+                );
             }
         }
     }
@@ -145,10 +219,13 @@ impl AlertManager {
                     },
                     title: "Low Peer Count".to_string(),
                     message: format!("Only {} peer(s) connected", count),
+                    resolves: None,
                 };
 
-                self.add_alert(alert);
+                self.fire(alert);
                 self.last_peer_warning = Some(now);
+            } else {
+                self.resolve("Low Peer Count", format!("{} peers connected", count));
             }
         }
     }
@@ -179,11 +256,113 @@ impl AlertManager {
                     },
                     title: "Sync Progress Degraded".to_string(),
                     message: format!("Node is {:.2}% synced", progress),
+                    resolves: None,
                 };
 
-                self.add_alert(alert);
+                self.fire(alert);
                 self.last_sync_warning = Some(now);
+            } else {
+                self.resolve(
+                    "Sync Progress Degraded",
+                    format!("Node is {:.2}% synced", progress),
+                );
+            }
+        }
+    }
+
+    /// Check how far the node trails the reference network tip
+    ///
+    /// `lag` is (blocks, seconds); `max_blocks` of zero disables the alert.
+    /// An unknown lag (no reference, or the node is down) resolves it, so
+    /// a stale warning doesn't linger.
+    pub fn check_network_lag(&mut self, lag: Option<(u64, u64)>, max_blocks: u64) {
+        let Some((blocks, secs)) = lag else {
+            self.resolve(
+                "Behind Network Tip",
+                "Lag behind the network tip is unknown".to_string(),
+            );
+            return;
+        };
+        if max_blocks > 0 && blocks >= max_blocks {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+
+            if let Some(last_warn) = self.last_lag_warning {
+                if now - last_warn < 600 {
+                    // 10 min cooldown
+                    return;
+                }
             }
+
+            let alert = Alert {
+                timestamp: now,
+                node_name: self.node_name.clone(),
+                severity: AlertSeverity::Warning,
+                title: "Behind Network Tip".to_string(),
+                message: format!("{} blocks ({}s) behind the network tip", blocks, secs),
+                resolves: None,
+            };
+
+            self.fire(alert);
+            self.last_lag_warning = Some(now);
+        } else {
+            self.resolve(
+                "Behind Network Tip",
+                format!("{} blocks behind the network tip", blocks),
+            );
+        }
+    }
+
+    /// Check chain density against the active slot coefficient
+    ///
+    /// Density well below `expected` means slot leaders across the network
+    /// aren't producing blocks, or the node follows a sparse fork.
+    pub fn check_density(&mut self, density: Option<f64>, expected: f64) {
+        let Some(density) = density else {
+            return;
+        };
+        let ratio = density / expected;
+        if ratio < DENSITY_WARNING_RATIO {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+
+            if let Some(last_warn) = self.last_density_warning {
+                if now - last_warn < 600 {
+                    // 10 min cooldown
+                    return;
+                }
+            }
+
+            let severity = if ratio < DENSITY_CRITICAL_RATIO {
+                AlertSeverity::Critical
+            } else {
+                AlertSeverity::Warning
+            };
+            let alert = Alert {
+                timestamp: now,
+                node_name: self.node_name.clone(),
+                severity,
+                title: "Low Chain Density".to_string(),
+                message: format!(
+                    "Density {:.4} is {:.0}% of the expected {}",
+                    density,
+                    ratio * 100.0,
+                    expected
+                ),
+                resolves: None,
+            };
+
+            self.fire(alert);
+            self.last_density_warning = Some(now);
+        } else {
+            self.resolve(
+                "Low Chain Density",
+                format!("Density back to {:.4}", density),
+            );
         }
     }
 
@@ -220,24 +399,589 @@ impl AlertManager {
                         age,
                         current_height.unwrap_or(0)
                     ),
+                    resolves: None,
                 };
 
-                self.add_alert(alert);
+                self.fire(alert);
                 self.last_height_stall_warning = Some(now);
+            } else {
+                self.resolve(
+                    "Block Height Stalled",
+                    format!(
+                        "New blocks are arriving again (height: {})",
+                        current_height.unwrap_or(0)
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Check sview's data directory against its quota and the filesystem's free space
+    ///
+    /// Warns at 90% of the quota or when free space drops below `min_free_bytes`;
+    /// escalates to critical once the quota is exceeded or free space falls
+    /// below a quarter of the minimum, before snapshot writes start failing.
+    pub fn check_storage(
+        &mut self,
+        usage: &DiskUsage,
+        quota_bytes: Option<u64>,
+        min_free_bytes: u64,
+    ) {
+        let mut findings: Vec<(AlertSeverity, String)> = Vec::new();
+
+        if let Some(quota) = quota_bytes.filter(|q| *q > 0) {
+            let percent = usage.data_dir_bytes as f64 / quota as f64 * 100.0;
+            if percent >= 90.0 {
+                let severity = if percent >= 100.0 {
+                    AlertSeverity::Critical
+                } else {
+                    AlertSeverity::Warning
+                };
+                findings.push((
+                    severity,
+                    format!(
+                        "Data directory at {:.0}% of quota ({} / {} MB)",
+                        percent,
+                        usage.data_dir_bytes / (1024 * 1024),
+                        quota / (1024 * 1024)
+                    ),
+                ));
+            }
+        }
+
+        if let Some(available) = usage.fs_available_bytes {
+            if available < min_free_bytes {
+                let severity = if available < min_free_bytes / 4 {
+                    AlertSeverity::Critical
+                } else {
+                    AlertSeverity::Warning
+                };
+                findings.push((
+                    severity,
+                    format!(
+                        "Only {} MB free on data filesystem",
+                        available / (1024 * 1024)
+                    ),
+                ));
+            }
+        }
+
+        let Some(severity) = findings.iter().map(|(s, _)| *s).max() else {
+            self.resolve(
+                "Storage Low",
+                "Data directory is within its quota and free space limits".to_string(),
+            );
+            return;
+        };
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        if let Some(last_warn) = self.last_storage_warning {
+            if now - last_warn < 3600 {
+                // 1 hour cooldown
+                return;
+            }
+        }
+
+        let message = findings
+            .into_iter()
+            .map(|(_, msg)| msg)
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity,
+            title: "Storage Low".to_string(),
+            message,
+            resolves: None,
+        };
+
+        self.fire(alert);
+        self.last_storage_warning = Some(now);
+    }
+
+    /// Raise an info alert once per epoch when the boundary is within `lead_secs`
+    pub fn check_epoch_boundary(
+        &mut self,
+        epoch: Option<u64>,
+        secs_remaining: Option<u64>,
+        lead_secs: u64,
+    ) {
+        let (Some(epoch), Some(remaining)) = (epoch, secs_remaining) else {
+            return;
+        };
+        if lead_secs == 0 || remaining > lead_secs || self.last_epoch_alert == Some(epoch) {
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Info,
+            title: "Epoch Boundary Approaching".to_string(),
+            message: format!(
+                "Epoch {} ends in {}h {}m",
+                epoch,
+                remaining / 3600,
+                (remaining % 3600) / 60
+            ),
+            resolves: None,
+        };
+
+        self.add_alert(alert);
+        self.last_epoch_alert = Some(epoch);
+    }
+
+    /// Check for an abnormal jump in incoming connections
+    ///
+    /// Compares against a slow moving average, so a sustained rise is
+    /// absorbed into the baseline while a sudden flood stands out.
+    pub fn check_connection_spike(&mut self, incoming: Option<u64>) {
+        let Some(current) = incoming.map(|c| c as f64) else {
+            return;
+        };
+        let Some(baseline) = self.incoming_baseline else {
+            self.incoming_baseline = Some(current);
+            return;
+        };
+        self.incoming_baseline = Some(baseline + BASELINE_ALPHA * (current - baseline));
+
+        if current < baseline * SPIKE_FACTOR || current - baseline < SPIKE_MIN_DELTA {
+            self.resolve(
+                "Incoming Connection Spike",
+                format!(
+                    "{} incoming connections (usually ~{:.0})",
+                    current, baseline
+                ),
+            );
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        if let Some(last_warn) = self.last_spike_warning {
+            if now - last_warn < 600 {
+                // 10 min cooldown
+                return;
+            }
+        }
+
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Warning,
+            title: "Incoming Connection Spike".to_string(),
+            message: format!(
+                "{} incoming connections (usually ~{:.0})",
+                current, baseline
+            ),
+            resolves: None,
+        };
+
+        self.fire(alert);
+        self.last_spike_warning = Some(now);
+    }
+
+    /// Alert on incoming connections concentrated in one subnet or network
+    pub fn check_peer_concentration(&mut self, warning: Option<String>) {
+        let Some(message) = warning else {
+            self.resolve(
+                "Connection Concentration",
+                "Incoming connections are no longer concentrated".to_string(),
+            );
+            return;
+        };
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        if let Some(last_warn) = self.last_concentration_warning {
+            if now - last_warn < 600 {
+                // 10 min cooldown
+                return;
+            }
+        }
+
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Warning,
+            title: "Connection Concentration".to_string(),
+            message,
+            resolves: None,
+        };
+
+        self.fire(alert);
+        self.last_concentration_warning = Some(now);
+    }
+
+    /// Alert when the on-disk opcert counter isn't valid for the chain's
+    /// (neither equal to it nor one ahead), so forged blocks would be rejected
+    pub fn check_opcert_counter(&mut self, on_disk: u64, on_chain: Option<u64>) {
+        if counter_status(on_disk, on_chain) != CounterStatus::Mismatch {
+            self.resolve(
+                "OpCert Counter Mismatch",
+                format!("On-disk counter {} is valid for the chain", on_disk),
+            );
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        if let Some(last_warn) = self.last_opcert_warning {
+            if now - last_warn < 3600 {
+                // Only warn once per hour
+                return;
+            }
+        }
+
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Critical,
+            title: "OpCert Counter Mismatch".to_string(),
+            message: format!(
+                "On-disk counter {} doesn't match on-chain {}; forged blocks will be rejected",
+                on_disk,
+                on_chain.map(|c| c.to_string()).unwrap_or_default()
+            ),
+            resolves: None,
+        };
+
+        self.fire(alert);
+        self.last_opcert_warning = Some(now);
+    }
+
+    /// Announce an upcoming hard fork once, and warn daily while the node's
+    /// version is below the minimum the fork requires
+    pub fn check_hard_fork(
+        &mut self,
+        fork: &HardFork,
+        secs_until: Option<u64>,
+        version: Option<&str>,
+    ) {
+        let Some(secs_until) = secs_until else {
+            return;
+        };
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let description = fork.describe();
+
+        if self.last_hard_fork_alert.as_deref() != Some(description.as_str()) {
+            let alert = Alert {
+                timestamp: now,
+                node_name: self.node_name.clone(),
+                severity: AlertSeverity::Info,
+                title: "Hard Fork Scheduled".to_string(),
+                message: format!("{} in {}", description, format_duration(secs_until)),
+                resolves: None,
+            };
+            self.add_alert(alert);
+            self.last_hard_fork_alert = Some(description.clone());
+        }
+
+        if !fork.requires_upgrade(version) {
+            self.resolve(
+                "Node Upgrade Required",
+                format!(
+                    "Running {}, ready for {}",
+                    version.unwrap_or_default(),
+                    description
+                ),
+            );
+            return;
+        }
+        if let Some(last_warn) = self.last_upgrade_warning {
+            if now - last_warn < 86400 {
+                // Only warn once per day
+                return;
+            }
+        }
+
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Warning,
+            title: "Node Upgrade Required".to_string(),
+            message: format!(
+                "Running {}, {} needs {} or later ({} left)",
+                version.unwrap_or_default(),
+                description,
+                fork.min_node_version.as_deref().unwrap_or_default(),
+                format_duration(secs_until)
+            ),
+            resolves: None,
+        };
+        self.fire(alert);
+        self.last_upgrade_warning = Some(now);
+    }
+
+    /// Note once per release when a newer cardano-node version is out
+    pub fn check_node_update(&mut self, version: Option<&str>, latest: Option<&LatestRelease>) {
+        let (Some(version), Some(latest)) = (version, latest) else {
+            return;
+        };
+        if !latest.is_newer_than(version)
+            || self.last_release_alert.as_deref() == Some(latest.version.as_str())
+        {
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Info,
+            title: "Node Update Available".to_string(),
+            message: format!(
+                "Running cardano-node {}, latest release is {}",
+                version, latest.version
+            ),
+            resolves: None,
+        };
+        self.add_alert(alert);
+        self.last_release_alert = Some(latest.version.clone());
+    }
+
+    /// Alert when the node switched forks `threshold` or more times in the last hour
+    pub fn check_fork_rate(&mut self, forks_last_hour: u64, threshold: u64) {
+        if threshold == 0 || forks_last_hour < threshold {
+            self.resolve(
+                "Frequent Forks",
+                format!("{} forks in the last hour", forks_last_hour),
+            );
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        if let Some(last_warn) = self.last_fork_warning {
+            if now - last_warn < 3600 {
+                // Only warn once per hour
+                return;
+            }
+        }
+
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Warning,
+            title: "Frequent Forks".to_string(),
+            message: format!(
+                "{} forks in the last hour; possible network instability near the node",
+                forks_last_hour
+            ),
+            resolves: None,
+        };
+
+        self.fire(alert);
+        self.last_fork_warning = Some(now);
+    }
+
+    /// Alert when connection errors have run at `threshold` per minute or
+    /// more for `elevated_secs` (0 disables)
+    pub fn check_connection_errors(
+        &mut self,
+        per_minute: Option<f64>,
+        elevated_secs: u64,
+        threshold: f64,
+    ) {
+        let Some(rate) = per_minute else {
+            return;
+        };
+        if threshold <= 0.0 || rate < threshold {
+            self.resolve(
+                "Connection Errors",
+                format!("{:.1} connection errors/min", rate),
+            );
+            return;
+        }
+        if elevated_secs < CONNECTION_ERROR_SUSTAIN_SECS {
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        if let Some(last_warn) = self.last_connection_error_warning {
+            if now - last_warn < 3600 {
+                // Only warn once per hour
+                return;
+            }
+        }
+
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Warning,
+            title: "Connection Errors".to_string(),
+            message: format!(
+                "{:.1} connection errors/min for {}; check upstream peers and firewall state",
+                rate,
+                format_duration(elevated_secs)
+            ),
+            resolves: None,
+        };
+
+        self.fire(alert);
+        self.last_connection_error_warning = Some(now);
+    }
+
+    /// Alert when the pool's live stake moved by `threshold` lovelace or more
+    /// between two chain API lookups (0 disables)
+    pub fn check_delegation_change(&mut self, previous: u64, current: u64, threshold: u64) {
+        let change = current.abs_diff(previous);
+        if threshold == 0 || change < threshold {
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let (severity, title, direction) = if current > previous {
+            (AlertSeverity::Info, "Delegation Inflow", "rose")
+        } else {
+            (AlertSeverity::Warning, "Delegation Outflow", "fell")
+        };
+        let alert = Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity,
+            title: title.to_string(),
+            message: format!(
+                "Live stake {} by {} to {}",
+                direction,
+                format_ada(Some(change)),
+                format_ada(Some(current))
+            ),
+            resolves: None,
+        };
+
+        self.add_alert(alert);
+    }
+
+    /// Alert once scraping has failed `threshold` times in a row and the
+    /// outage has lasted `grace_secs` (once per outage)
+    pub fn check_node_down(
+        &mut self,
+        failures: u32,
+        down_secs: u64,
+        threshold: u32,
+        grace_secs: u64,
+        error: Option<&FetchError>,
+    ) {
+        if threshold == 0 || failures < threshold || down_secs < grace_secs {
+            return;
+        }
+        if self.unreachable_alerted {
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.unreachable_alerted = true;
+        self.fire(Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Critical,
+            // An endpoint that answers with errors is usually a config
+            // problem rather than a node that went down
+            title: match error {
+                Some(error) if !error.is_unreachable() => "Metrics Endpoint Error",
+                _ => "Node Unreachable",
             }
+            .to_string(),
+            message: match error {
+                Some(error) => format!(
+                    "{} consecutive scrapes failed over {}: {}{}",
+                    failures,
+                    format_duration(down_secs),
+                    error,
+                    error
+                        .hint()
+                        .map(|h| format!(" ({})", h))
+                        .unwrap_or_default()
+                ),
+                None => format!(
+                    "{} consecutive scrapes failed over {}",
+                    failures,
+                    format_duration(down_secs)
+                ),
+            },
+            resolves: None,
+        });
+    }
+
+    /// Report the end of an outage that raised a Node Unreachable alert
+    pub fn check_node_recovered(&mut self, downtime_secs: u64) {
+        if !self.unreachable_alerted {
+            return;
         }
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.unreachable_alerted = false;
+        let resolves = ["Node Unreachable", "Metrics Endpoint Error"]
+            .iter()
+            .filter_map(|title| self.active.remove(*title))
+            .max();
+        self.add_alert(Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Info,
+            title: "Node Reachable".to_string(),
+            message: format!(
+                "Metrics scrapes are succeeding again after {} down",
+                format_duration(downtime_secs)
+            ),
+            resolves,
+        });
     }
 
-    /// Get the most recent critical alert (if any)
+    /// Get the most recent critical alert whose condition hasn't resolved
     pub fn latest_critical(&self) -> Option<&Alert> {
         self.recent_alerts
             .iter()
             .rev()
-            .find(|a| a.severity == AlertSeverity::Critical)
+            .find(|a| a.severity == AlertSeverity::Critical && self.active.contains_key(&a.title))
     }
 
     /// Get all alerts since timestamp
-    #[allow(dead_code)]
     pub fn alerts_since(&self, timestamp: u64) -> Vec<&Alert> {
         self.recent_alerts
             .iter()
@@ -245,6 +989,32 @@ impl AlertManager {
             .collect()
     }
 
+    /// Raise an alert for a condition that can later resolve
+    fn fire(&mut self, alert: Alert) {
+        self.active.insert(alert.title.clone(), alert.severity);
+        self.add_alert(alert);
+    }
+
+    /// Report that the condition behind `title` is healthy again, once per
+    /// time it fired
+    fn resolve(&mut self, title: &str, message: String) {
+        let Some(severity) = self.active.remove(title) else {
+            return;
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.add_alert(Alert {
+            timestamp: now,
+            node_name: self.node_name.clone(),
+            severity: AlertSeverity::Info,
+            title: format!("{} Resolved", title),
+            message,
+            resolves: Some(severity),
+        });
+    }
+
     /// Add an alert and log it
     fn add_alert(&mut self, alert: Alert) {
         debug!("Alert: {}", alert.display());
@@ -252,17 +1022,28 @@ impl AlertManager {
         // Log to file
         if let Some(ref log_path) = self.log_file {
             if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(log_path) {
-                let _ = writeln!(file, "{}", alert.log_format());
+                let _ = writeln!(file, "{}", alert.log_format(self.timezone));
             }
         }
 
         // Keep in memory
+        self.new_alerts.push(alert.clone());
         self.recent_alerts.push_back(alert);
         if self.recent_alerts.len() > self.max_recent {
             self.recent_alerts.pop_front();
         }
     }
 
+    /// Set the timezone used for log file timestamps
+    pub fn set_timezone(&mut self, timezone: DisplayTimezone) {
+        self.timezone = timezone;
+    }
+
+    /// Alerts raised since the last call
+    pub fn take_new(&mut self) -> Vec<Alert> {
+        std::mem::take(&mut self.new_alerts)
+    }
+
     /// Clear all alerts (for testing)
     #[allow(dead_code)]
     pub fn clear(&mut self) {
@@ -270,63 +1051,69 @@ impl AlertManager {
     }
 }
 
-/// Get the alerts log file path for a node
-fn get_alerts_log_path(node_name: &str) -> Option<PathBuf> {
-    dirs::data_dir().map(|p| {
-        p.join("sview").join("alerts").join(format!(
-            "{}.log",
-            node_name.replace(" ", "_").to_lowercase()
-        ))
-    })
+/// Alert counts for one node and alert type over a report period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertCount {
+    pub node_name: String,
+    pub title: String,
+    /// Highest severity seen in the period
+    pub severity: AlertSeverity,
+    /// Occurrences in the period
+    pub count: usize,
+    /// Occurrences in the period before it (same length)
+    pub previous: usize,
+    /// Timestamp of the latest occurrence
+    pub last_seen: u64,
 }
 
-/// Convert Unix timestamp to ISO8601 datetime string
-fn timestamp_to_iso8601(ts: u64) -> String {
-    let seconds_in_day = ts % 86400;
-    let days_since_epoch = ts / 86400;
-
-    // Simple conversion (approximate for readability)
-    let mut remaining_days = days_since_epoch as i64;
-    let mut year = 1970;
+/// Count alerts by node and type for the `period_secs` ending at `now`
+///
+/// Alert types that only occurred in the preceding period are included
+/// with a zero count, so improvements show up too.
+pub fn summarize_alerts(alerts: &[Alert], now: u64, period_secs: u64) -> Vec<AlertCount> {
+    let start = now.saturating_sub(period_secs);
+    let previous_start = start.saturating_sub(period_secs);
 
-    loop {
-        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-        if remaining_days < days_in_year {
-            break;
+    let mut counts: HashMap<(String, String), AlertCount> = HashMap::new();
+    for alert in alerts {
+        if alert.timestamp < previous_start || alert.timestamp > now {
+            continue;
         }
-        remaining_days -= days_in_year;
-        year += 1;
-    }
-
-    let days_in_months = if is_leap_year(year) {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-
-    let mut month = 1;
-    for days in days_in_months {
-        if remaining_days < days as i64 {
-            break;
+        let entry = counts
+            .entry((alert.node_name.clone(), alert.title.clone()))
+            .or_insert_with(|| AlertCount {
+                node_name: alert.node_name.clone(),
+                title: alert.title.clone(),
+                severity: alert.severity,
+                count: 0,
+                previous: 0,
+                last_seen: 0,
+            });
+        if alert.timestamp >= start {
+            entry.count += 1;
+            entry.severity = entry.severity.max(alert.severity);
+        } else {
+            entry.previous += 1;
         }
-        remaining_days -= days as i64;
-        month += 1;
+        entry.last_seen = entry.last_seen.max(alert.timestamp);
     }
 
-    let day = remaining_days + 1;
-    let hour = seconds_in_day / 3600;
-    let minute = (seconds_in_day % 3600) / 60;
-    let second = seconds_in_day % 60;
-
-    format!(
-        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
-        year, month, day, hour, minute, second
-    )
+    let mut counts: Vec<AlertCount> = counts.into_values().collect();
+    counts.sort_by(|a, b| {
+        a.node_name
+            .cmp(&b.node_name)
+            .then_with(|| b.count.cmp(&a.count))
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    counts
 }
 
-#[allow(clippy::manual_is_multiple_of)]
-fn is_leap_year(year: u64) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
+/// Get the alerts log file path for a node
+fn get_alerts_log_path(node_name: &str) -> Option<PathBuf> {
+    Some(crate::storage::get_data_dir().join("alerts").join(format!(
+        "{}.log",
+        node_name.replace(" ", "_").to_lowercase()
+    )))
 }
 
 #[cfg(test)]
@@ -357,6 +1144,189 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_opcert_counter_alert() {
+        let mut manager = AlertManager::new("Test BP");
+        manager.check_opcert_counter(6, Some(6));
+        manager.check_opcert_counter(7, Some(6));
+        manager.check_opcert_counter(0, None);
+        assert!(manager.latest_critical().is_none());
+
+        manager.check_opcert_counter(5, Some(6));
+        manager.check_opcert_counter(5, Some(6));
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].title, "OpCert Counter Mismatch");
+    }
+
+    #[test]
+    fn test_fork_rate_alert() {
+        let mut manager = AlertManager::new("Test Relay");
+        manager.check_fork_rate(4, 5);
+        manager.check_fork_rate(9, 0);
+        assert!(manager.take_new().is_empty());
+
+        manager.check_fork_rate(5, 5);
+        manager.check_fork_rate(6, 5);
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].title, "Frequent Forks");
+    }
+
+    #[test]
+    fn test_connection_error_alert() {
+        let mut manager = AlertManager::new("Test Errors");
+        // Elevated, but not for long enough
+        manager.check_connection_errors(Some(25.0), 120, 10.0);
+        // Long enough, but below the threshold
+        manager.check_connection_errors(Some(4.0), 600, 10.0);
+        manager.check_connection_errors(None, 600, 10.0);
+        // Disabled
+        manager.check_connection_errors(Some(25.0), 600, 0.0);
+        assert!(manager.alerts_since(0).is_empty());
+
+        manager.check_connection_errors(Some(25.0), 300, 10.0);
+        let alerts = manager.alerts_since(0);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].title, "Connection Errors");
+        assert!(alerts[0].message.contains("25.0"));
+
+        manager.check_connection_errors(Some(30.0), 900, 10.0);
+        assert_eq!(manager.alerts_since(0).len(), 1);
+    }
+
+    #[test]
+    fn test_delegation_change_alert() {
+        let mut manager = AlertManager::new("Test BP");
+        let ada = 1_000_000;
+        manager.check_delegation_change(10_000_000 * ada, 10_050_000 * ada, 100_000 * ada);
+        manager.check_delegation_change(10_000_000 * ada, 9_000_000 * ada, 0);
+        assert!(manager.take_new().is_empty());
+
+        manager.check_delegation_change(10_000_000 * ada, 11_500_000 * ada, 100_000 * ada);
+        manager.check_delegation_change(11_500_000 * ada, 11_000_000 * ada, 100_000 * ada);
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].title, "Delegation Inflow");
+        assert_eq!(alerts[0].severity, AlertSeverity::Info);
+        assert_eq!(alerts[0].message, "Live stake rose by 1.50M ₳ to 11.50M ₳");
+        assert_eq!(alerts[1].title, "Delegation Outflow");
+        assert_eq!(alerts[1].severity, AlertSeverity::Warning);
+    }
+
+    #[test]
+    fn test_unreachable_alert_after_threshold() {
+        let mut manager = AlertManager::new("Test Relay");
+        let error = Some(&FetchError::ConnectionRefused);
+        manager.check_node_down(1, 0, 3, 30, error);
+        manager.check_node_down(2, 2, 3, 30, error);
+        // Enough failures, but still within the grace period
+        manager.check_node_down(3, 4, 3, 30, error);
+        assert!(manager.latest_critical().is_none());
+
+        manager.check_node_down(15, 30, 3, 30, error);
+        manager.check_node_down(16, 32, 3, 30, error);
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].title, "Node Unreachable");
+        assert!(alerts[0].message.contains("connection refused"));
+        assert!(alerts[0].message.contains("hasPrometheus"));
+
+        manager.check_node_recovered(125);
+        manager.check_node_recovered(2);
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].severity, AlertSeverity::Info);
+        assert_eq!(alerts[0].resolves, Some(AlertSeverity::Critical));
+        assert!(alerts[0].message.contains("2m 5s"));
+
+        let mut manager = AlertManager::new("Test Relay");
+        manager.check_node_down(3, 30, 3, 30, Some(&FetchError::Http(404)));
+        assert_eq!(manager.take_new()[0].title, "Metrics Endpoint Error");
+    }
+
+    #[test]
+    fn test_alert_resolution() {
+        let mut manager = AlertManager::new("Test Relay");
+        manager.check_peer_count(Some(8));
+        assert!(manager.take_new().is_empty());
+
+        manager.check_peer_count(Some(0));
+        assert!(manager.latest_critical().is_some());
+        manager.check_peer_count(Some(6));
+        manager.check_peer_count(Some(7));
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[1].title, "Low Peer Count Resolved");
+        assert_eq!(alerts[1].severity, AlertSeverity::Info);
+        assert_eq!(alerts[1].resolves, Some(AlertSeverity::Critical));
+        assert_eq!(alerts[1].message, "6 peers connected");
+        // The header no longer shows the resolved alert
+        assert!(manager.latest_critical().is_none());
+
+        // Firing again allows one more resolution
+        manager.check_kes_expiry(Some(3));
+        manager.check_kes_expiry(Some(62));
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[1].title, "KES Expiry Critical Resolved");
+    }
+
+    #[test]
+    fn test_network_lag_alert() {
+        let mut manager = AlertManager::new("Test Relay");
+        manager.check_network_lag(Some((2, 40)), 3);
+        manager.check_network_lag(None, 3);
+        assert!(manager.take_new().is_empty());
+
+        manager.check_network_lag(Some((5, 100)), 3);
+        manager.check_network_lag(Some((0, 0)), 3);
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].title, "Behind Network Tip");
+        assert_eq!(alerts[0].message, "5 blocks (100s) behind the network tip");
+        assert_eq!(alerts[1].title, "Behind Network Tip Resolved");
+
+        // No longer measurable: resolved rather than left active
+        let mut manager = AlertManager::new("Test Relay");
+        manager.check_network_lag(Some((5, 100)), 3);
+        manager.check_network_lag(None, 3);
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[1].message, "Lag behind the network tip is unknown");
+
+        // Disabled
+        manager.check_network_lag(Some((50, 1000)), 0);
+        assert!(manager.take_new().is_empty());
+    }
+
+    #[test]
+    fn test_density_alert() {
+        let mut manager = AlertManager::new("Test Relay");
+        manager.check_density(Some(0.046), 0.05);
+        manager.check_density(None, 0.05);
+        assert!(manager.take_new().is_empty());
+
+        manager.check_density(Some(0.035), 0.05);
+        manager.check_density(Some(0.049), 0.05);
+        let alerts = manager.take_new();
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].title, "Low Chain Density");
+        assert_eq!(alerts[0].severity, AlertSeverity::Warning);
+        assert_eq!(
+            alerts[0].message,
+            "Density 0.0350 is 70% of the expected 0.05"
+        );
+        assert_eq!(alerts[1].title, "Low Chain Density Resolved");
+
+        let mut manager = AlertManager::new("Test Relay");
+        manager.check_density(Some(0.02), 0.05);
+        assert_eq!(
+            manager.latest_critical().unwrap().title,
+            "Low Chain Density"
+        );
+    }
+
     #[test]
     fn test_sync_alert() {
         let mut manager = AlertManager::new("Test Node");
@@ -364,6 +1334,172 @@ mod tests {
         assert!(manager.latest_critical().is_some());
     }
 
+    #[test]
+    fn test_storage_alert() {
+        const MB: u64 = 1024 * 1024;
+
+        let mut manager = AlertManager::new("Test Storage");
+        let usage = DiskUsage {
+            data_dir_bytes: 50 * MB,
+            fs_available_bytes: Some(10 * MB),
+        };
+        manager.check_storage(&usage, None, 1024 * MB);
+        let alert = manager.latest_critical().unwrap();
+        assert_eq!(alert.title, "Storage Low");
+
+        let mut manager = AlertManager::new("Test Storage");
+        let usage = DiskUsage {
+            data_dir_bytes: 95 * MB,
+            fs_available_bytes: Some(4096 * MB),
+        };
+        manager.check_storage(&usage, Some(100 * MB), 1024 * MB);
+        assert!(manager.latest_critical().is_none());
+        assert_eq!(manager.alerts_since(0).len(), 1);
+        assert_eq!(manager.alerts_since(0)[0].severity, AlertSeverity::Warning);
+    }
+
+    #[test]
+    fn test_epoch_boundary_alert_once_per_epoch() {
+        let mut manager = AlertManager::new("Test Epoch");
+        manager.check_epoch_boundary(Some(450), Some(30000), 21600);
+        assert!(manager.alerts_since(0).is_empty());
+
+        manager.check_epoch_boundary(Some(450), Some(20000), 21600);
+        manager.check_epoch_boundary(Some(450), Some(10000), 21600);
+        assert_eq!(manager.alerts_since(0).len(), 1);
+        assert_eq!(manager.alerts_since(0)[0].severity, AlertSeverity::Info);
+
+        // Disabled with a zero lead time
+        let mut manager = AlertManager::new("Test Epoch");
+        manager.check_epoch_boundary(Some(450), Some(100), 0);
+        assert!(manager.alerts_since(0).is_empty());
+    }
+
+    #[test]
+    fn test_hard_fork_alerts() {
+        let fork = HardFork {
+            epoch: Some(537),
+            slot: None,
+            protocol: Some("10.0".to_string()),
+            min_node_version: Some("10.1.4".to_string()),
+            source: crate::hardfork::HardForkSource::Config,
+        };
+        let mut manager = AlertManager::new("Test Fork");
+        manager.check_hard_fork(&fork, None, Some("10.1.3"));
+        assert!(manager.alerts_since(0).is_empty());
+
+        manager.check_hard_fork(&fork, Some(2 * 86400 + 3600), Some("10.1.4"));
+        manager.check_hard_fork(&fork, Some(2 * 86400), Some("10.1.4"));
+        let alerts = manager.alerts_since(0);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].severity, AlertSeverity::Info);
+        assert_eq!(alerts[0].message, "protocol 10.0 at epoch 537 in 2d 1h");
+
+        // An outdated node is warned once per day
+        manager.check_hard_fork(&fork, Some(86400), Some("10.1.3"));
+        manager.check_hard_fork(&fork, Some(86000), Some("10.1.3"));
+        let alerts = manager.alerts_since(0);
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[1].severity, AlertSeverity::Warning);
+        assert_eq!(alerts[1].title, "Node Upgrade Required");
+    }
+
+    #[test]
+    fn test_node_update_alert_once_per_release() {
+        let release = |version: &str| LatestRelease {
+            version: version.to_string(),
+            url: None,
+            checked_at: 0,
+        };
+        let mut manager = AlertManager::new("Test Release");
+        manager.check_node_update(Some("10.1.4"), Some(&release("10.1.4")));
+        manager.check_node_update(None, Some(&release("10.2.0")));
+        assert!(manager.alerts_since(0).is_empty());
+
+        manager.check_node_update(Some("10.1.4"), Some(&release("10.2.0")));
+        manager.check_node_update(Some("10.1.4"), Some(&release("10.2.0")));
+        assert_eq!(manager.alerts_since(0).len(), 1);
+        manager.check_node_update(Some("10.1.4"), Some(&release("10.2.1")));
+        assert_eq!(manager.alerts_since(0).len(), 2);
+    }
+
+    #[test]
+    fn test_connection_spike_alert() {
+        let mut manager = AlertManager::new("Test Spike");
+        for _ in 0..10 {
+            manager.check_connection_spike(Some(30));
+        }
+        manager.check_connection_spike(Some(45));
+        assert!(manager.alerts_since(0).is_empty());
+
+        manager.check_connection_spike(Some(120));
+        let alerts = manager.alerts_since(0);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].title, "Incoming Connection Spike");
+
+        // Cooldown suppresses repeats
+        manager.check_connection_spike(Some(150));
+        assert_eq!(manager.alerts_since(0).len(), 1);
+    }
+
+    #[test]
+    fn test_summarize_alerts() {
+        let alert = |node: &str, title: &str, severity, timestamp| Alert {
+            timestamp,
+            node_name: node.to_string(),
+            severity,
+            title: title.to_string(),
+            message: String::new(),
+            resolves: None,
+        };
+        let week = 7 * 86400;
+        let now = 100 * 86400;
+        let alerts = vec![
+            alert("relay", "Low Peer Count", AlertSeverity::Warning, now - 100),
+            alert(
+                "relay",
+                "Low Peer Count",
+                AlertSeverity::Critical,
+                now - 200,
+            ),
+            alert(
+                "relay",
+                "Low Peer Count",
+                AlertSeverity::Warning,
+                now - week - 10,
+            ),
+            alert(
+                "relay",
+                "Block Height Stalled",
+                AlertSeverity::Warning,
+                now - week - 20,
+            ),
+            alert(
+                "bp",
+                "KES Expiry Critical",
+                AlertSeverity::Critical,
+                now - 50,
+            ),
+            // Outside both periods
+            alert(
+                "bp",
+                "KES Expiry Critical",
+                AlertSeverity::Critical,
+                now - 3 * week,
+            ),
+        ];
+
+        let summary = summarize_alerts(&alerts, now, week);
+        assert_eq!(summary.len(), 3);
+        assert_eq!(summary[0].node_name, "bp");
+        assert_eq!((summary[0].count, summary[0].previous), (1, 0));
+        assert_eq!(summary[1].title, "Low Peer Count");
+        assert_eq!((summary[1].count, summary[1].previous), (2, 1));
+        assert_eq!(summary[1].severity, AlertSeverity::Critical);
+        assert_eq!(summary[1].last_seen, now - 100);
+        assert_eq!((summary[2].count, summary[2].previous), (0, 1));
+    }
+
     #[test]
     fn test_no_alert_threshold() {
         let mut manager = AlertManager::new("Test Node");
@@ -375,5 +1511,12 @@ mod tests {
 
         manager.check_sync_progress(Some(99.9));
         assert!(manager.latest_critical().is_none());
+
+        let usage = DiskUsage {
+            data_dir_bytes: 1024,
+            fs_available_bytes: Some(u64::MAX),
+        };
+        manager.check_storage(&usage, Some(1024 * 1024), 1024);
+        assert!(manager.alerts_since(0).is_empty());
     }
 }
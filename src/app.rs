@@ -3,21 +3,110 @@
 //! This module contains the core application state and logic.
 //! Supports both single-node and multi-node monitoring modes.
 
-use crate::alerts::AlertManager;
-use crate::config::{AppConfig, Config, NodeRole, NodeRuntimeConfig};
-use crate::geoip::GeoIPService;
+use crate::agent::{AgentClient, AgentConfig, AgentSnapshot};
+use crate::alerts::{Alert, AlertManager, DENSITY_CRITICAL_RATIO, DENSITY_WARNING_RATIO};
+use crate::benchmark::{KpiWindow, NodeBenchmark};
+use crate::blocklist::Blocklist;
+use crate::chain_api::{ChainApiProvider, EpochReward, PoolInfo};
+use crate::cncli::{CncliSnapshot, LeaderWatch};
+use crate::command::Command;
+use crate::config::{
+    unix_socket_path, AppConfig, Config, CustomMetric, HealthThresholds, NodeRole,
+    NodeRuntimeConfig, PanelKind, RttProbeMode, ThresholdField,
+};
+use crate::epochs::{EpochSummary, EpochTracker};
+use crate::forks::ForkTracker;
+use crate::geoip::{BatchAnswer, GeoIPService, GeoLocation};
+use crate::hardfork::HardFork;
+use crate::heartbeat::Heartbeat;
 use crate::history::MetricsHistory;
-use crate::metrics::{MetricsClient, NodeMetrics};
-use crate::peers::PeerMonitor;
-use crate::sockets::PeerConnection;
-use crate::storage::StorageManager;
+use crate::influx::InfluxSink;
+use crate::mempool::MempoolSnapshot;
+use crate::metrics::{FetchError, MetricsClient, NodeMetrics, NodeType, PeerTargets};
+use crate::mqtt::MqttPublisher;
+use crate::node_form::{FormStep, NodeForm};
+use crate::notify::Notifier;
+use crate::opcert::KesPeriodInfo;
+use crate::peers::{ConnectionAges, PeerGrouping};
+use crate::pooltool::{PooltoolClient, Tip};
+use crate::propagation::{LogTail, PropagationTracker};
+use crate::reference_tip::{ReferenceSource, ReferenceTip};
+use crate::release::LatestRelease;
+use crate::restarts::{self, RestartHistory};
+use crate::session::Session;
+use crate::sockets::{InterfaceCounters, PeerConnection, PeerFilter, ProbeProgress};
+use crate::startup::StartupTracker;
+use crate::storage::{DiskUsage, Outage, StakeSnapshot, StorageManager};
 use crate::themes::Theme;
-use std::collections::HashMap;
-use std::time::Instant;
+use crate::tunnel::SshTunnel;
+use crate::web::WebServer;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use tracing::{debug, warn};
 
+/// How often the data directory size and free space are checked
+const STORAGE_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How often the cncli database is re-read
+const CNCLI_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Slots a leader slot's result stays in the header
+const LEADER_RESULT_SLOTS: u64 = 300;
+
+/// Slots before the next assigned slot its countdown replaces a shown result
+const LEADER_COUNTDOWN_LEAD_SLOTS: u64 = 60;
+
+/// How often `cardano-cli query kes-period-info` runs for the opcert check
+const OPCERT_CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Number of recent scrape round-trip times kept per node
+const SCRAPE_RTT_SAMPLES: usize = 30;
+
+/// Scrape RTT above which time-sensitive values are annotated with the delay
+pub const LATENCY_ANNOTATE_THRESHOLD: Duration = Duration::from_millis(100);
+
+/// How often the selected node's peers are scanned for connection concentration
+const PEER_SCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often a running peer refresh is checked and its progress redrawn
+const PEER_REFRESH_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a reverse DNS answer is reused before the peer is looked up again
+const HOSTNAME_TTL: Duration = Duration::from_secs(3600);
+
+/// How often the config file is checked for changes
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How long footer status messages stay visible
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(5);
+
+/// Observation time needed before the hourly blocks/min and density are shown
+const MIN_CHAIN_RATE_SPAN_SECS: f64 = 300.0;
+
+/// Observation time needed before the hourly density is judged; a few
+/// minutes hold too few blocks to tell a slow stretch from a real drop
+const MIN_DENSITY_SPAN_SECS: f64 = 1800.0;
+
+/// Observation time needed before the slot rate is shown
+const MIN_SLOT_RATE_SPAN_SECS: f64 = 60.0;
+
+/// Allowed deviation of the slot rate from one slot per slot length
+const SLOT_RATE_TOLERANCE: f64 = 0.25;
+
+/// How recent a critical alert must be to count as active in the fleet strip
+const FLEET_ALERT_WINDOW_SECS: u64 = 3600;
+
+/// Days of stake history kept for the delegation trend
+const STAKE_TREND_DAYS: u64 = 30;
+
+/// Selectable time windows for the node comparison (hours)
+pub const BENCHMARK_WINDOWS_HOURS: [u64; 4] = [1, 6, 12, 24];
+
 /// UI mode for the application
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum AppMode {
     #[default]
     Normal,
@@ -28,6 +117,28 @@ pub enum AppMode {
     PeerDetail,
     /// Historical graphs view
     Graphs,
+    /// Per-epoch summary statistics
+    EpochSummary,
+    /// Health threshold editor
+    Settings,
+    /// Node comparison over a time window
+    Benchmark,
+    /// Form for adding a node at runtime
+    AddNode,
+    /// Mempool transaction list (via local socket)
+    Mempool,
+    /// Per-peer block propagation ranking (from the node log)
+    Propagation,
+    /// Fork and rollback events
+    Incidents,
+    /// `:` command line in the footer
+    Command,
+    /// Single dashboard panel expanded with all its raw metrics
+    Focus,
+    /// First-run setup: the add-node form, writing a new config file
+    Setup,
+    /// Explanation of the selected dashboard metric
+    MetricInfo,
 }
 
 /// Peer data availability mode
@@ -40,57 +151,303 @@ pub enum PeerDataMode {
     PrometheusOnly,
 }
 
-/// Health status indicators
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Health status indicators, ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum HealthStatus {
     Good,
     Warning,
     Critical,
 }
 
+/// Connection state shown in the header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Metrics are being scraped
+    Online,
+    /// The last few scrapes failed, fewer than the offline threshold
+    Degraded,
+    /// Metrics endpoint unreachable, but the node's health endpoint responds
+    MetricsDown,
+    /// Nothing reachable
+    Offline,
+}
+
+/// Result of one scrape
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrapeOutcome {
+    /// The node sent a new sample
+    New,
+    /// The node answered but repeated its last sample
+    Unchanged,
+    Failed,
+}
+
+/// Status of all monitored nodes at a glance (multi-node fleet strip)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FleetSummary {
+    /// Nodes with metrics being scraped
+    pub online: usize,
+    pub total: usize,
+    /// Highest block height across nodes
+    pub max_tip: Option<u64>,
+    /// Node furthest behind the highest tip on its own network, and by how
+    /// many blocks
+    pub worst_lag: Option<(String, u64)>,
+    /// Connected peers summed over all nodes
+    pub total_peers: u64,
+    /// Nodes with a critical alert in the last hour
+    pub critical_nodes: usize,
+}
+
+/// An entry in the node tab bar
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeTab {
+    /// An ungrouped node, or a member of an expanded group
+    Node(usize),
+    /// A group heading with its member node indices
+    Group {
+        name: String,
+        nodes: Vec<usize>,
+        collapsed: bool,
+    },
+}
+
+/// Peers and host stats from one sample, or the agent's error
+type HostSample = Result<AgentSnapshot, String>;
+
+/// Per-epoch rewards by (network, pool_id)
+type PoolRewards = HashMap<(String, String), Vec<EpochReward>>;
+
+/// A peer list refresh running in the background
+struct PeerRefresh {
+    started: Instant,
+    progress: Arc<ProbeProgress>,
+    task: tokio::task::JoinHandle<HostSample>,
+}
+
+impl Drop for PeerRefresh {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// A slow lookup running in the background, so it doesn't hold up the refresh
+struct BackgroundTask<T>(tokio::task::JoinHandle<T>);
+
+impl<T: Send + 'static> BackgroundTask<T> {
+    fn spawn(future: impl std::future::Future<Output = T> + Send + 'static) -> Self {
+        Self(tokio::spawn(future))
+    }
+}
+
+impl<T> Drop for BackgroundTask<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Take the result of `task` once it has finished
+async fn take_finished<T>(task: &mut Option<BackgroundTask<T>>) -> Option<T> {
+    if !task.as_ref()?.0.is_finished() {
+        return None;
+    }
+    let mut finished = task.take()?;
+    match (&mut finished.0).await {
+        Ok(result) => Some(result),
+        Err(e) => {
+            warn!("Background task failed: {}", e);
+            None
+        }
+    }
+}
+
 /// State for a single monitored node
 pub struct NodeState {
     /// Node configuration
     pub config: Config,
     /// Node role (for display hints)
     pub role: NodeRole,
-    /// Metrics client for fetching data
-    metrics_client: MetricsClient,
+    /// Tab bar group (`group = "..."` in the node config)
+    pub group: Option<String>,
+    /// Peer selection targets from the node config file
+    config_peer_targets: Option<PeerTargets>,
+    /// Metrics client for fetching data (or why it couldn't be created)
+    metrics_client: Result<MetricsClient, String>,
+    /// SSH port forward to the metrics endpoint (if configured)
+    tunnel: Option<SshTunnel>,
     /// Current node metrics
     pub metrics: NodeMetrics,
     /// Historical metrics for sparklines
     pub history: MetricsHistory,
     /// Persistent storage manager
     storage: StorageManager,
-    /// Peer monitor for tracking peer statistics
-    pub peer_monitor: PeerMonitor,
     /// Last fetch error (if any)
-    pub last_error: Option<String>,
+    pub last_error: Option<FetchError>,
+    /// Scrapes failed in a row since the last success
+    pub consecutive_failures: u32,
+    /// Health endpoint result from the last failed scrape (None if not configured)
+    health_ok: Option<bool>,
     /// Fetch count
     pub fetch_count: u64,
     /// Last successful metrics fetch time
     pub last_fetch_time: Option<Instant>,
+    /// Recent scrape round-trip times (request sent to body received)
+    scrape_rtts: VecDeque<Duration>,
     /// Last observed block height (for tip age tracking)
     last_block_height: Option<u64>,
     /// Time when block height last changed
     last_block_time: Option<Instant>,
     /// Discovered peer connections (from socket inspection)
     pub peer_connections: Vec<PeerConnection>,
+    /// When each connection was first seen by socket scans
+    pub peer_ages: ConnectionAges,
+    /// `[nodes.agent]` the agent client was created from
+    agent_config: Option<AgentConfig>,
+    /// `sview agent` on the node's host, for remote peers and host stats
+    agent: Option<AgentClient>,
+    /// Last agent request error (if any)
+    pub agent_error: Option<String>,
+    /// Peer list refresh in progress (`start_peer_refresh`)
+    peer_refresh: Option<PeerRefresh>,
+    /// Time the peer byte counters were last sampled
+    last_peer_sample: Option<Instant>,
+    /// Previous interface counter sample
+    interface_sample: Option<(Instant, InterfaceCounters)>,
+    /// Host network I/O rates in bytes/s: (receive, transmit)
+    pub interface_rates: Option<(f64, f64)>,
+    /// Logical CPUs of the node's host, once a host sample has landed
+    cpu_cores: Option<usize>,
+    /// Highest combined host network I/O rate seen, in bytes/s
+    pub interface_peak_rate: f64,
     /// Alert manager for critical notifications
     pub alert_manager: AlertManager,
+    /// Alerts raised since the last `take_outbox`, for external publishers
+    outbox: Vec<Alert>,
     /// Peer data availability mode (full vs prometheus-only)
     pub peer_data_mode: PeerDataMode,
+    /// Bech32 pool ID (for chain API lookups)
+    pub pool_id: Option<String>,
+    /// Pool information from the chain API (if pool_id is configured)
+    pub pool_info: Option<PoolInfo>,
+    /// Rewards for the pool's last epochs from the chain API (newest first)
+    pub rewards: Vec<EpochReward>,
+    /// Recorded stake changes over the last `STAKE_TREND_DAYS` (block producers)
+    pub stake_history: Vec<StakeSnapshot>,
+    /// Live stake change in ADA that raises a delegation alert (0 disables)
+    delegation_alert_ada: u64,
+    /// Sustained connection errors per minute that raise an alert (0 disables)
+    connection_error_alert_per_min: f64,
+    /// When the connection error rate last rose to the alert threshold
+    connection_errors_since: Option<Instant>,
+    /// Data directory size budget in bytes (if configured)
+    storage_quota_bytes: Option<u64>,
+    /// Minimum free space on the data filesystem in bytes
+    min_free_disk_bytes: u64,
+    /// Data directory usage at the last storage check
+    pub disk_usage: Option<DiskUsage>,
+    /// Size of this node's history at the last storage check
+    pub history_bytes: Option<u64>,
+    /// Path to the node's local socket (if configured)
+    pub socket_path: Option<PathBuf>,
+    /// Last mempool snapshot from the local socket
+    pub mempool: Option<MempoolSnapshot>,
+    /// Last mempool query error (if any)
+    pub mempool_error: Option<String>,
+    /// Path to cncli's database (if configured)
+    cncli_db: Option<PathBuf>,
+    /// Leader schedule and block outcomes from cncli
+    pub cncli: Option<CncliSnapshot>,
+    /// Last cncli database read error (if any)
+    pub cncli_error: Option<String>,
+    /// Time of last cncli database read
+    last_cncli_refresh: Option<Instant>,
+    /// Next (or just passed) leader slot, for the header countdown
+    pub leader_watch: Option<LeaderWatch>,
+    /// cardano-cli binary for the opcert check
+    cli_path: Option<PathBuf>,
+    /// Operational certificate to check (if configured)
+    opcert_path: Option<PathBuf>,
+    /// Last `kes-period-info` result
+    pub opcert: Option<KesPeriodInfo>,
+    /// Last opcert check error (if any)
+    pub opcert_error: Option<String>,
+    /// Time of last opcert check
+    last_opcert_check: Option<Instant>,
+    /// Running opcert check
+    opcert_task: Option<BackgroundTask<anyhow::Result<KesPeriodInfo>>>,
+    /// Node JSON log path (enables block propagation stats)
+    pub log_file: Option<PathBuf>,
+    /// Tail of the node's JSON log
+    log_tail: Option<LogTail>,
+    /// Block arrival times per peer, from the node log
+    pub propagation: PropagationTracker,
+    /// Last node log read error (if any)
+    pub log_error: Option<String>,
+    /// Newest tip adopted according to the node log: (block hash, slot)
+    log_tip: Option<(String, u64)>,
+    /// Tip submission to pooltool.io (if configured)
+    pub pooltool: Option<PooltoolClient>,
+    /// Slot of the last tip sent to pooltool
+    pooltool_sent_slot: Option<u64>,
+    /// Notification channels this node's alerts go to (`None`: all)
+    notify_route: Option<Vec<String>>,
+    /// Epoch boundary tracker for per-epoch statistics
+    pub epoch_tracker: EpochTracker,
+    /// Recorded summaries of completed epochs (oldest first)
+    pub epoch_summaries: Vec<EpochSummary>,
+    /// Chain switches seen by the node, for the incidents view
+    pub forks: ForkTracker,
+    /// Recorded node starts, for the restart counts and timeline
+    pub restarts: RestartHistory,
+    /// Forks within an hour that raise an alert (0 disables)
+    fork_alert_per_hour: u64,
+    /// Ledger replay and other startup work after a node restart
+    pub startup: StartupTracker,
+    /// Upcoming hard fork on this node's network, if one is scheduled
+    pub hard_fork: Option<HardFork>,
+    /// Network tip to measure lag against (`[global.reference_tip]`)
+    pub reference_tip: Option<ReferenceTip>,
+    /// Blocks behind the reference tip that raise an alert (0 disables)
+    max_lag_blocks: u64,
+    /// Newest cardano-node release (`check_node_release`)
+    pub latest_release: Option<LatestRelease>,
+    /// Version from the config, for nodes without build info
+    configured_version: Option<String>,
+    /// Lead time for the epoch boundary alert in seconds (0 disables)
+    epoch_alert_secs: u64,
+    /// Active peer latency probing
+    peer_rtt_probe: RttProbeMode,
+    /// ICMP ping fallback for peers without an RTT
+    peer_ping: bool,
+    /// Incoming connections from one /24 that raise an alert
+    peer_subnet_alert: usize,
+    /// Share of incoming connections from one network that raises an alert
+    peer_network_alert_percent: u8,
+    /// Share of outgoing connections to one network above which an alert is raised
+    peer_outgoing_network_alert_percent: u8,
+    /// Consecutive failed scrapes before the node counts as offline
+    offline_after_failures: u32,
+    /// Seconds unreachable before the node-down alert
+    node_down_grace_secs: u64,
+    /// Health indicator thresholds (editable at runtime)
+    pub thresholds: HealthThresholds,
+    /// Extra metric rows from `[[nodes.custom_metrics]]`
+    pub custom_metrics: Vec<CustomMetric>,
+    /// Per-minute KPI history for node comparisons
+    pub kpis: KpiWindow,
 }
 
 impl NodeState {
     /// Create a new node state
     pub fn new(node_config: &NodeRuntimeConfig, app_config: &AppConfig) -> Self {
         let config = Config::from_node(node_config, app_config);
-        let metrics_client = MetricsClient::new(config.metrics_url(), config.prom_timeout());
+        let (tunnel, metrics_client) = connect_metrics(&config);
         let mut history = MetricsHistory::new(config.history_length);
 
         // Initialize storage and load historical data
-        let storage = StorageManager::new(&config.node_name);
+        let storage = StorageManager::new(&config.node_name)
+            .with_policy(node_config.retention)
+            .with_sink(InfluxSink::new(&app_config.influx, &config.node_name));
 
         // Try to load historical data to backfill sparklines
         match storage.populate_history(&mut history, config.history_length) {
@@ -117,32 +474,548 @@ impl NodeState {
             );
         }
 
+        if let Err(e) = storage.migrate_files() {
+            warn!(
+                "Failed to migrate history files for '{}': {}",
+                config.node_name, e
+            );
+        }
+
+        let epoch_summaries = storage.load_epoch_summaries().unwrap_or_else(|e| {
+            warn!(
+                "Failed to load epoch summaries for '{}': {}",
+                config.node_name, e
+            );
+            Vec::new()
+        });
+
+        let starts = storage.load_starts().unwrap_or_else(|e| {
+            warn!(
+                "Failed to load restart history for '{}': {}",
+                config.node_name, e
+            );
+            Vec::new()
+        });
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let stake_since = now.saturating_sub(STAKE_TREND_DAYS * 86400);
+        let stake_history = storage.load_stake(stake_since).unwrap_or_else(|e| {
+            warn!(
+                "Failed to load stake history for '{}': {}",
+                config.node_name, e
+            );
+            Vec::new()
+        });
+
+        let agent = connect_agent(node_config.agent.as_ref(), &config);
+
         // Create alert manager before moving config
-        let alert_manager = AlertManager::new(&config.node_name);
+        let mut alert_manager = AlertManager::new(&config.node_name);
+        alert_manager.set_timezone(app_config.timezone);
 
         Self {
             config,
             role: node_config.role,
+            group: node_config.group.clone(),
+            config_peer_targets: node_config.peer_targets,
             metrics_client,
+            tunnel,
             metrics: NodeMetrics::default(),
             history,
             storage,
-            peer_monitor: PeerMonitor::new(),
             last_error: None,
+            consecutive_failures: 0,
+            health_ok: None,
             fetch_count: 0,
             last_fetch_time: None,
+            scrape_rtts: VecDeque::with_capacity(SCRAPE_RTT_SAMPLES),
             last_block_height: None,
             last_block_time: None,
             peer_connections: Vec::new(),
+            peer_ages: ConnectionAges::default(),
+            agent_config: node_config.agent.clone(),
+            agent,
+            agent_error: None,
+            peer_refresh: None,
+            last_peer_sample: None,
+            interface_sample: None,
+            interface_rates: None,
+            cpu_cores: None,
+            interface_peak_rate: 0.0,
             alert_manager,
+            outbox: Vec::new(),
             peer_data_mode: PeerDataMode::Full, // Will be determined on first refresh
+            pool_id: node_config.pool_id.clone(),
+            pool_info: None,
+            rewards: Vec::new(),
+            stake_history,
+            delegation_alert_ada: app_config.delegation_alert_ada,
+            connection_error_alert_per_min: app_config.connection_error_alert_per_min,
+            connection_errors_since: None,
+            storage_quota_bytes: app_config.storage_quota_bytes,
+            min_free_disk_bytes: app_config.min_free_disk_bytes,
+            disk_usage: None,
+            history_bytes: None,
+            socket_path: node_config.socket_path.clone(),
+            mempool: None,
+            mempool_error: None,
+            cncli_db: node_config.cncli_db.clone(),
+            log_file: node_config.log_file.clone(),
+            log_tail: node_config.log_file.as_deref().map(LogTail::new),
+            propagation: PropagationTracker::default(),
+            log_error: None,
+            log_tip: None,
+            pooltool: node_config.pooltool.as_ref().and_then(PooltoolClient::new),
+            pooltool_sent_slot: None,
+            notify_route: node_config.notify.clone(),
+            cncli: None,
+            cncli_error: None,
+            last_cncli_refresh: None,
+            leader_watch: None,
+            cli_path: node_config.cli_path.clone(),
+            opcert_path: node_config.opcert_path.clone(),
+            opcert: None,
+            opcert_error: None,
+            last_opcert_check: None,
+            opcert_task: None,
+            epoch_tracker: EpochTracker::default(),
+            epoch_summaries,
+            forks: ForkTracker::default(),
+            restarts: RestartHistory::new(starts),
+            fork_alert_per_hour: app_config.fork_alert_per_hour,
+            startup: StartupTracker::default(),
+            hard_fork: app_config.hard_fork.scheduled(),
+            reference_tip: None,
+            max_lag_blocks: app_config.reference_tip.max_lag_blocks,
+            latest_release: None,
+            configured_version: node_config.version.clone(),
+            epoch_alert_secs: app_config.epoch_alert_lead.as_secs(),
+            peer_rtt_probe: app_config.peer_rtt_probe,
+            peer_ping: app_config.peer_ping,
+            peer_subnet_alert: app_config.peer_subnet_alert,
+            peer_network_alert_percent: app_config.peer_network_alert_percent,
+            peer_outgoing_network_alert_percent: app_config.peer_outgoing_network_alert_percent,
+            offline_after_failures: app_config.offline_after_failures,
+            node_down_grace_secs: app_config.node_down_grace_secs,
+            thresholds: node_config.thresholds,
+            custom_metrics: node_config.custom_metrics.clone(),
+            kpis: KpiWindow::default(),
+        }
+    }
+
+    /// Whether this state monitors the endpoint described by `node_config`
+    fn same_endpoint(&self, node_config: &NodeRuntimeConfig) -> bool {
+        self.config.node_name == node_config.name
+            && self.config.prom_host == node_config.host
+            && self.config.prom_port == node_config.port
+    }
+
+    /// Apply a reloaded configuration, keeping metrics and history
+    fn apply_config(&mut self, node_config: &NodeRuntimeConfig, app_config: &AppConfig) {
+        let config = Config::from_node(node_config, app_config);
+        if config.prom_timeout_secs != self.config.prom_timeout_secs
+            || config.endpoint != self.config.endpoint
+            || config.raw_metrics != self.config.raw_metrics
+        {
+            (self.tunnel, self.metrics_client) = connect_metrics(&config);
+        }
+        if self.agent_config != node_config.agent
+            || config.prom_timeout_secs != self.config.prom_timeout_secs
+        {
+            self.agent_config = node_config.agent.clone();
+            self.agent = connect_agent(node_config.agent.as_ref(), &config);
+            self.agent_error = None;
+            self.peer_refresh = None;
+        }
+        self.config = config;
+        self.role = node_config.role;
+        self.group = node_config.group.clone();
+        self.config_peer_targets = node_config.peer_targets;
+        if self.pool_id != node_config.pool_id {
+            self.pool_id = node_config.pool_id.clone();
+            self.pool_info = None;
+            self.rewards.clear();
+        }
+        if self.socket_path != node_config.socket_path {
+            self.socket_path = node_config.socket_path.clone();
+            self.mempool = None;
+            self.mempool_error = None;
+        }
+        if self.cncli_db != node_config.cncli_db {
+            self.cncli_db = node_config.cncli_db.clone();
+            self.cncli = None;
+            self.cncli_error = None;
+            self.last_cncli_refresh = None;
+            self.leader_watch = None;
+        }
+        if self.cli_path != node_config.cli_path || self.opcert_path != node_config.opcert_path {
+            self.cli_path = node_config.cli_path.clone();
+            self.opcert_path = node_config.opcert_path.clone();
+            self.opcert = None;
+            self.opcert_error = None;
+            self.last_opcert_check = None;
+            self.opcert_task = None;
+        }
+        if self.log_file != node_config.log_file {
+            self.log_file = node_config.log_file.clone();
+            self.log_tail = node_config.log_file.as_deref().map(LogTail::new);
+            self.propagation = PropagationTracker::default();
+            self.log_error = None;
+            self.log_tip = None;
+        }
+        let pooltool_changed = match (&self.pooltool, &node_config.pooltool) {
+            (Some(client), Some(config)) => !client.is_for(config),
+            (None, None) => false,
+            _ => true,
+        };
+        if pooltool_changed {
+            self.pooltool = node_config.pooltool.as_ref().and_then(PooltoolClient::new);
+        }
+        self.notify_route = node_config.notify.clone();
+        self.thresholds = node_config.thresholds;
+        self.custom_metrics = node_config.custom_metrics.clone();
+        self.storage.set_policy(node_config.retention);
+        self.storage
+            .set_sink(InfluxSink::new(&app_config.influx, &self.config.node_name));
+        self.alert_manager.set_timezone(app_config.timezone);
+        self.storage_quota_bytes = app_config.storage_quota_bytes;
+        self.min_free_disk_bytes = app_config.min_free_disk_bytes;
+        self.epoch_alert_secs = app_config.epoch_alert_lead.as_secs();
+        self.peer_rtt_probe = app_config.peer_rtt_probe;
+        self.peer_ping = app_config.peer_ping;
+        self.peer_subnet_alert = app_config.peer_subnet_alert;
+        self.peer_network_alert_percent = app_config.peer_network_alert_percent;
+        self.peer_outgoing_network_alert_percent = app_config.peer_outgoing_network_alert_percent;
+        self.offline_after_failures = app_config.offline_after_failures;
+        self.node_down_grace_secs = app_config.node_down_grace_secs;
+        self.fork_alert_per_hour = app_config.fork_alert_per_hour;
+        self.delegation_alert_ada = app_config.delegation_alert_ada;
+        self.connection_error_alert_per_min = app_config.connection_error_alert_per_min;
+        self.hard_fork = app_config.hard_fork.scheduled();
+        self.max_lag_blocks = app_config.reference_tip.max_lag_blocks;
+        self.configured_version = node_config.version.clone();
+        if !app_config.check_node_release {
+            self.latest_release = None;
+        }
+    }
+
+    /// Read new lines from the node log and record block arrivals
+    async fn ingest_log(&mut self) {
+        let Some(mut tail) = self.log_tail.take() else {
+            return;
+        };
+        let result = tokio::task::spawn_blocking(move || {
+            let lines = tail.read_lines();
+            (tail, lines)
+        })
+        .await;
+        match result {
+            Ok((tail, lines)) => {
+                self.log_tail = Some(tail);
+                match lines {
+                    Ok(lines) => {
+                        let now = std::time::SystemTime::now()
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .unwrap_or_default()
+                            .as_secs();
+                        for line in &lines {
+                            if let Some(event) = crate::propagation::parse_log_line(line) {
+                                self.propagation.record(event);
+                            } else if let Some(tip) = crate::pooltool::parse_new_tip(line) {
+                                self.log_tip = Some(tip);
+                            } else if let Some(event) = crate::startup::parse_log_line(line) {
+                                self.startup.observe(event, now);
+                            }
+                        }
+                        self.log_error = None;
+                    }
+                    Err(e) => self.log_error = Some(format!("{:#}", e)),
+                }
+            }
+            Err(e) => self.log_error = Some(e.to_string()),
+        }
+    }
+
+    /// Report a new tip to pooltool once the metrics and log agree on its slot
+    fn send_pooltool_tip(&mut self) {
+        let (Some(client), Some((hash, slot))) = (&self.pooltool, &self.log_tip) else {
+            return;
+        };
+        let (Some(block_no), Some(slot_no)) = (self.metrics.block_height, self.metrics.slot_num)
+        else {
+            return;
+        };
+        if slot_no != *slot || self.pooltool_sent_slot == Some(slot_no) {
+            return;
+        }
+        let tip = Tip {
+            block_no,
+            slot_no,
+            block_hash: hash.clone(),
+        };
+        client.send(&tip, self.node_version().unwrap_or_default());
+        self.pooltool_sent_slot = Some(slot_no);
+    }
+
+    /// Follow the next assigned slot: record the Forge counters before it,
+    /// settle its result after it, then move on to the following slot
+    fn update_leader_watch(&mut self) {
+        let (Some(cncli), Some(current)) = (&self.cncli, self.network_slot()) else {
+            self.leader_watch = None;
+            return;
+        };
+        let adopted = self.metrics.blocks_adopted;
+        let didnt_adopt = self.metrics.blocks_didnt_adopt;
+        if let Some(watch) = &mut self.leader_watch {
+            watch.observe(current, adopted, didnt_adopt);
+        }
+        let next = cncli.next_slot(current);
+        let move_on = match self.leader_watch.as_ref().map(|w| w.result) {
+            None => true,
+            Some(None) => false,
+            Some(Some((_, settled))) => {
+                current >= settled + LEADER_RESULT_SLOTS
+                    || next.is_some_and(|next| next <= current + LEADER_COUNTDOWN_LEAD_SLOTS)
+            }
+        };
+        if move_on {
+            self.leader_watch = next.map(|slot| LeaderWatch::new(slot, adopted, didnt_adopt));
+        }
+    }
+
+    /// Re-read the leader schedule from the cncli database, if due
+    async fn refresh_cncli(&mut self) {
+        let Some(path) = self.cncli_db.clone() else {
+            return;
+        };
+        let due = self
+            .last_cncli_refresh
+            .map(|t| t.elapsed() >= CNCLI_REFRESH_INTERVAL)
+            .unwrap_or(true);
+        if !due {
+            return;
+        }
+        self.last_cncli_refresh = Some(Instant::now());
+
+        let epoch = self.metrics.epoch;
+        let result =
+            tokio::task::spawn_blocking(move || crate::cncli::read_snapshot(&path, epoch)).await;
+        match result {
+            Ok(Ok(snapshot)) => {
+                self.cncli = Some(snapshot);
+                self.cncli_error = None;
+            }
+            Ok(Err(e)) => {
+                debug!("cncli read failed for '{}': {:#}", self.config.node_name, e);
+                self.cncli = None;
+                self.cncli_error = Some(format!("{:#}", e));
+            }
+            Err(e) => self.cncli_error = Some(e.to_string()),
+        }
+    }
+
+    /// Collect a finished cardano-cli opcert check, and start the next one
+    /// if configured and due
+    async fn refresh_opcert(&mut self) {
+        match take_finished(&mut self.opcert_task).await {
+            Some(Ok(info)) => {
+                self.opcert = Some(info);
+                self.opcert_error = None;
+            }
+            Some(Err(e)) => {
+                debug!(
+                    "opcert check failed for '{}': {:#}",
+                    self.config.node_name, e
+                );
+                self.opcert = None;
+                self.opcert_error = Some(format!("{:#}", e));
+            }
+            None => {}
+        }
+
+        let Some(opcert_path) = self.opcert_path.clone() else {
+            return;
+        };
+        if self.opcert_task.is_some() {
+            return;
+        }
+        let due = self
+            .last_opcert_check
+            .map(|t| t.elapsed() >= OPCERT_CHECK_INTERVAL)
+            .unwrap_or(true);
+        if !due {
+            return;
+        }
+        self.last_opcert_check = Some(Instant::now());
+
+        let Some(socket_path) = self.socket_path.clone() else {
+            self.opcert_error = Some("opcert_path needs socket_path".to_string());
+            return;
+        };
+        let Some(magic) = crate::node_socket::network_magic(&self.config.network) else {
+            self.opcert_error = Some(format!("Unknown network '{}'", self.config.network));
+            return;
+        };
+        let cli_path = self
+            .cli_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("cardano-cli"));
+        self.opcert_task = Some(BackgroundTask::spawn(async move {
+            crate::opcert::query_kes_period_info(&cli_path, &opcert_path, &socket_path, magic).await
+        }));
+    }
+
+    /// OpCert counters (on disk, on chain) from the node's metrics, or from
+    /// the cardano-cli check when the node doesn't export them
+    pub fn op_cert_counters(&self) -> Option<(u64, Option<u64>)> {
+        match (
+            self.metrics.op_cert_counter_disk,
+            self.metrics.op_cert_counter_chain,
+        ) {
+            (Some(disk), Some(chain)) => Some((disk, Some(chain))),
+            _ => self
+                .opcert
+                .as_ref()
+                .map(|info| (info.counter_on_disk, info.counter_on_chain)),
+        }
+    }
+
+    /// Query the mempool contents via the local socket
+    pub async fn refresh_mempool(&mut self) {
+        let Some(socket_path) = &self.socket_path else {
+            self.mempool_error = Some("No socket_path configured for this node".to_string());
+            return;
+        };
+        let Some(magic) = crate::node_socket::network_magic(&self.config.network) else {
+            self.mempool_error = Some(format!("Unknown network '{}'", self.config.network));
+            return;
+        };
+
+        match crate::mempool::query_mempool(socket_path, magic).await {
+            Ok(snapshot) => {
+                self.mempool = Some(snapshot);
+                self.mempool_error = None;
+            }
+            Err(e) => {
+                debug!(
+                    "Mempool query failed for '{}': {:#}",
+                    self.config.node_name, e
+                );
+                self.mempool_error = Some(format!("{:#}", e));
+            }
+        }
+    }
+
+    /// Peers and host interface counters, from the agent if one is configured
+    async fn sample_host(&mut self) -> (Vec<PeerConnection>, Option<InterfaceCounters>) {
+        match sample_host(self.agent.as_ref(), &self.peer_filter()).await {
+            Ok(sample) => {
+                if self.agent.is_some() {
+                    self.agent_error = None;
+                }
+                self.cpu_cores = sample.cpu_cores.or(self.cpu_cores);
+                (sample.peers, sample.interface)
+            }
+            Err(e) => {
+                debug!("Agent for '{}' failed: {}", self.config.node_name, e);
+                self.agent_error = Some(e);
+                (Vec::new(), None)
+            }
+        }
+    }
+
+    /// Which of the host's connections belong to this node
+    fn peer_filter(&self) -> PeerFilter {
+        PeerFilter {
+            prom_port: self.config.prom_port,
+            p2p_ports: self.config.p2p_ports.clone(),
         }
     }
 
-    /// Refresh peer connections via socket inspection
-    /// Sets peer_data_mode based on whether socket inspection succeeds
-    pub fn refresh_peer_connections(&mut self) {
-        self.peer_connections = crate::sockets::discover_peers(self.config.prom_port);
+    /// Start refreshing the peer list in the background: socket inspection
+    /// (or the node's agent), then RTT probes
+    ///
+    /// The current list stays in place until `finish_peer_refresh` swaps in
+    /// the new one. Does nothing while a refresh is already running.
+    pub fn start_peer_refresh(&mut self) {
+        if self.peer_refresh.is_some() {
+            return;
+        }
+        let agent = self.agent.clone();
+        let filter = self.peer_filter();
+        let rtt_probe = self.peer_rtt_probe;
+        let ping = self.peer_ping;
+        let progress = Arc::new(ProbeProgress::default());
+        let task_progress = progress.clone();
+        let task = tokio::spawn(async move {
+            let mut sample = sample_host(agent.as_ref(), &filter).await?;
+            // Probes from here would measure this machine's path, not the node's
+            if agent.is_none() {
+                crate::sockets::probe_tcp_rtt(&mut sample.peers, rtt_probe, &task_progress).await;
+                if ping {
+                    crate::sockets::probe_icmp_rtt(&mut sample.peers, &task_progress).await;
+                }
+            }
+            Ok(sample)
+        });
+        self.peer_refresh = Some(PeerRefresh {
+            started: Instant::now(),
+            progress,
+            task,
+        });
+    }
+
+    /// Whether a background peer refresh is running
+    pub fn peer_refreshing(&self) -> bool {
+        self.peer_refresh.is_some()
+    }
+
+    /// Time since the running peer refresh started and its (finished,
+    /// started) RTT probes
+    pub fn peer_refresh_progress(&self) -> Option<(Duration, (usize, usize))> {
+        let refresh = self.peer_refresh.as_ref()?;
+        Some((refresh.started.elapsed(), refresh.progress.counts()))
+    }
+
+    /// Swap in the result of a finished background peer refresh, returning
+    /// whether one landed
+    ///
+    /// Sets peer_data_mode based on whether socket inspection succeeds.
+    pub async fn finish_peer_refresh(&mut self) -> bool {
+        let Some(refresh) = &mut self.peer_refresh else {
+            return false;
+        };
+        if !refresh.task.is_finished() {
+            return false;
+        }
+        let result = (&mut refresh.task).await;
+        self.peer_refresh = None;
+        let (peers, counters) = match result {
+            Ok(Ok(sample)) => {
+                if self.agent.is_some() {
+                    self.agent_error = None;
+                }
+                self.peer_ages.update(&sample.peers, Instant::now());
+                self.cpu_cores = sample.cpu_cores.or(self.cpu_cores);
+                (sample.peers, sample.interface)
+            }
+            Ok(Err(e)) => {
+                debug!("Agent for '{}' failed: {}", self.config.node_name, e);
+                self.agent_error = Some(e);
+                (Vec::new(), None)
+            }
+            Err(e) => {
+                warn!("Peer refresh for '{}' failed: {}", self.config.node_name, e);
+                return true;
+            }
+        };
+        self.peer_connections = peers;
+        crate::peers::classify_connections(&mut self.peer_connections, &self.propagation);
+        self.last_peer_sample = Some(Instant::now());
+        self.sample_interfaces(counters);
 
         // Determine data mode: if socket inspection found peers, we have full data
         // If no peers found but we have Prometheus connection data, we're in remote mode
@@ -164,6 +1037,63 @@ impl NodeState {
                 self.peer_data_mode = PeerDataMode::Full;
             }
         }
+        true
+    }
+
+    /// Re-read peer byte counters to update per-peer throughput
+    ///
+    /// Unlike a full refresh this keeps the peer list as-is (no re-probing),
+    /// so it is cheap enough to run on every refresh while the list is open.
+    pub async fn update_peer_traffic(&mut self) {
+        if self.peer_data_mode != PeerDataMode::Full || self.peer_connections.is_empty() {
+            return;
+        }
+        let (latest, counters) = self.sample_host().await;
+        let now = Instant::now();
+        if !latest.is_empty() {
+            self.peer_ages.update(&latest, now);
+        }
+        if let Some(prev) = self.last_peer_sample {
+            crate::sockets::update_traffic(&mut self.peer_connections, &latest, now - prev);
+        }
+        crate::peers::classify_connections(&mut self.peer_connections, &self.propagation);
+        self.last_peer_sample = Some(now);
+        self.sample_interfaces(counters);
+    }
+
+    /// Alert if incoming connections cluster in one subnet or network, or
+    /// outgoing connections in one network
+    pub fn check_peer_concentration(
+        &mut self,
+        peers: &[PeerConnection],
+        networks: &HashMap<String, String>,
+    ) {
+        let warning = crate::peers::concentration_warning(
+            peers,
+            networks,
+            self.peer_subnet_alert,
+            self.peer_network_alert_percent,
+            self.peer_outgoing_network_alert_percent,
+        );
+        self.alert_manager.check_peer_concentration(warning);
+    }
+
+    /// Record host interface counters and update the network I/O rates
+    fn sample_interfaces(&mut self, counters: Option<InterfaceCounters>) {
+        let Some(counters) = counters else {
+            return;
+        };
+        let now = Instant::now();
+        if let Some((prev_time, prev)) = self.interface_sample {
+            let secs = (now - prev_time).as_secs_f64();
+            if secs > 0.0 {
+                let rx = counters.rx_bytes.saturating_sub(prev.rx_bytes) as f64 / secs;
+                let tx = counters.tx_bytes.saturating_sub(prev.tx_bytes) as f64 / secs;
+                self.interface_peak_rate = self.interface_peak_rate.max(rx + tx);
+                self.interface_rates = Some((rx, tx));
+            }
+        }
+        self.interface_sample = Some((now, counters));
     }
 
     /// Run alert checks on current metrics
@@ -172,19 +1102,101 @@ impl NodeState {
             .check_kes_expiry(self.metrics.kes_remaining);
         self.alert_manager
             .check_peer_count(self.metrics.peers_connected);
+        self.alert_manager
+            .check_connection_spike(self.metrics.incoming_connections);
+        let error_rate = self.connection_errors_per_min();
+        let threshold = self.connection_error_alert_per_min;
+        if threshold > 0.0 && error_rate.is_some_and(|rate| rate >= threshold) {
+            let since = *self
+                .connection_errors_since
+                .get_or_insert_with(Instant::now);
+            self.alert_manager.check_connection_errors(
+                error_rate,
+                since.elapsed().as_secs(),
+                threshold,
+            );
+        } else {
+            self.connection_errors_since = None;
+            self.alert_manager
+                .check_connection_errors(error_rate, 0, threshold);
+        }
         self.alert_manager
             .check_sync_progress(self.metrics.sync_progress);
+        self.alert_manager
+            .check_density(self.recent_density(), self.config.active_slots_coeff);
         self.alert_manager.check_block_stall(
             self.metrics.block_height,
             self.last_block_height,
             self.tip_age_secs(),
         );
+        if let Some((disk, chain)) = self.op_cert_counters() {
+            self.alert_manager.check_opcert_counter(disk, chain);
+        }
+        self.alert_manager.check_epoch_boundary(
+            self.metrics.epoch,
+            self.epoch_time_remaining(),
+            self.epoch_alert_secs,
+        );
+        // Releases and hard-fork minimums are cardano-node versions
+        let version = self.cardano_node_version().map(String::from);
+        self.alert_manager
+            .check_node_update(version.as_deref(), self.latest_release.as_ref());
+        if let Some(fork) = &self.hard_fork {
+            self.alert_manager
+                .check_hard_fork(fork, self.hard_fork_secs(), version.as_deref());
+        }
     }
 
-    /// Fetch metrics from this node
-    pub async fn fetch_metrics(&mut self) {
-        match self.metrics_client.fetch().await {
-            Ok(metrics) => {
+    /// Take a storage measurement, alerting only when `alert` is set (the
+    /// first node on a shared data directory)
+    fn apply_storage(&mut self, usage: DiskUsage, history_bytes: u64, alert: bool) {
+        if alert {
+            self.alert_manager.check_storage(
+                &usage,
+                self.storage_quota_bytes,
+                self.min_free_disk_bytes,
+            );
+            self.persist_alerts();
+        }
+        self.disk_usage = Some(usage);
+        self.history_bytes = Some(history_bytes);
+    }
+
+    /// Scrape the node once, updating only its metrics and connection state
+    ///
+    /// Returns whether the node sent a new sample, repeated its last one or
+    /// didn't answer. `fetch_metrics` builds history, alerts and the rest on
+    /// top; `sview status` uses this alone, so a status check records and
+    /// sends nothing.
+    pub async fn scrape(&mut self) -> ScrapeOutcome {
+        let started = Instant::now();
+        let tunnel_status = match &mut self.tunnel {
+            Some(tunnel) => Some(tunnel.ensure_ready(self.config.prom_timeout()).await),
+            None => None,
+        };
+        if let Some(tunnel) = &mut self.tunnel {
+            if tunnel.take_port_changed() {
+                self.metrics_client = metrics_client(&self.config, Some(tunnel));
+            }
+        }
+        let result = match (&self.metrics_client, tunnel_status) {
+            (Err(e), _) => Err(FetchError::Other(e.clone())),
+            (_, Some(Err(e))) => Err(FetchError::Tunnel(format!("{:#}", e))),
+            (Ok(client), _) => client.fetch().await,
+        };
+        match result {
+            Ok(sample) => {
+                let outcome = if sample.is_new() {
+                    ScrapeOutcome::New
+                } else {
+                    ScrapeOutcome::Unchanged
+                };
+                let metrics = sample.into_metrics();
+                if self.scrape_rtts.len() >= SCRAPE_RTT_SAMPLES {
+                    self.scrape_rtts.pop_front();
+                }
+                self.scrape_rtts.push_back(started.elapsed());
+
                 // Track tip age: detect when block height changes
                 if let Some(new_height) = metrics.block_height {
                     let height_changed = self
@@ -198,62 +1210,298 @@ impl NodeState {
                     }
                 }
 
-                self.metrics = metrics.clone();
-                self.history.update(&self.metrics);
-
-                // Update peer monitor with current peer statistics
-                self.peer_monitor.update_from_metrics(
-                    self.metrics.p2p.hot_peers,
-                    self.metrics.p2p.warm_peers,
-                    self.metrics.p2p.cold_peers,
-                    self.metrics.incoming_connections,
-                    self.metrics.outgoing_connections,
-                    self.metrics.full_duplex_connections,
-                );
-
+                self.metrics = metrics;
                 self.last_error = None;
+                self.consecutive_failures = 0;
+                self.health_ok = None;
                 self.fetch_count += 1;
                 self.last_fetch_time = Some(Instant::now());
-
-                // Save snapshot to persistent storage (hourly sampling)
-                if let Err(e) = self.storage.save_snapshot(&self.metrics) {
-                    debug!("Failed to save metric snapshot: {}", e);
-                }
-
-                // Run alert checks
-                self.check_alerts();
+                outcome
             }
             Err(e) => {
                 self.metrics.connected = false;
-                self.last_error = Some(e.to_string());
+                self.last_error = Some(e);
+                self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+
+                // Tell a down metrics endpoint from a down node
+                self.health_ok = match &self.metrics_client {
+                    Ok(client) if client.has_health_check() => {
+                        Some(client.check_health().await.is_ok())
+                    }
+                    _ => None,
+                };
+                ScrapeOutcome::Failed
             }
         }
     }
 
-    /// Get the storage manager for this node
-    #[allow(dead_code)]
-    pub fn storage(&self) -> &StorageManager {
-        &self.storage
-    }
+    /// Fetch metrics from this node
+    pub async fn fetch_metrics(&mut self) {
+        self.ingest_log().await;
+        let outcome = self.scrape().await;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        // Reachable, but a repeated payload is no new sample: feeding it on
+        // would stall rates and read a frozen uptime as a restart
+        if outcome == ScrapeOutcome::Unchanged {
+            if let Some(downtime) = self.end_outage(now) {
+                self.alert_manager.check_node_recovered(downtime);
+                self.persist_alerts();
+            }
+            return;
+        }
+        if outcome == ScrapeOutcome::New {
+            self.history.update(&self.metrics);
+            self.kpis.record(&self.metrics, self.tip_age_secs(), now);
 
-    /// Get seconds since last block was received
-    pub fn tip_age_secs(&self) -> Option<u64> {
-        self.last_block_time.map(|t| t.elapsed().as_secs())
-    }
+            // Save snapshot to persistent storage (hourly sampling)
+            let tps = self
+                .history
+                .tps_stats
+                .avg_1h()
+                .or_else(|| self.history.tps.current());
+            if let Err(e) = self.storage.save_snapshot(
+                &self.metrics,
+                tps,
+                self.tip_age_secs(),
+                self.cpu_percent(),
+            ) {
+                debug!("Failed to save metric snapshot: {}", e);
+            }
 
-    /// Get seconds since last successful metrics fetch
-    #[allow(dead_code)]
-    pub fn last_fetch_age_secs(&self) -> Option<u64> {
-        self.last_fetch_time.map(|t| t.elapsed().as_secs())
+            // Record a summary when an epoch boundary is crossed
+            if let Some(summary) = self.epoch_tracker.update(&self.metrics, now) {
+                if let Err(e) = self.storage.save_epoch_summary(&summary) {
+                    warn!("Failed to save epoch summary: {}", e);
+                }
+                self.epoch_summaries.retain(|s| s.epoch != summary.epoch);
+                self.epoch_summaries.push(summary);
+            }
+
+            if let Some(start) = restarts::start_time(&self.metrics, now)
+                .and_then(|start_time| self.restarts.observe(start_time))
+            {
+                if start.is_restart() {
+                    debug!("Node '{}' restarted", self.config.node_name);
+                }
+                if let Err(e) = self.storage.record_start(&start) {
+                    warn!("Failed to record node start: {}", e);
+                }
+            }
+
+            let starting = self.startup.update(&self.metrics, now);
+            if let Some(event) = self.forks.update(&self.metrics, now) {
+                debug!(
+                    "Fork on '{}': {} new, height {:?} -> {:?}",
+                    self.config.node_name, event.new_forks, event.height_before, event.height_after
+                );
+                self.alert_manager.check_fork_rate(
+                    self.forks.count_since(now.saturating_sub(3600)),
+                    self.fork_alert_per_hour,
+                );
+            }
+
+            self.refresh_opcert().await;
+
+            // Run alert checks
+            if let Some(downtime) = self.end_outage(now) {
+                self.alert_manager.check_node_recovered(downtime);
+            }
+            // Missing chain metrics are expected while the ledger replays
+            if !starting {
+                self.check_alerts();
+            }
+            self.refresh_cncli().await;
+            self.update_leader_watch();
+            self.send_pooltool_tip();
+            self.persist_alerts();
+        } else {
+            self.kpis.scrape_failed(now);
+            self.startup.update(&self.metrics, now);
+            self.alert_manager.check_node_down(
+                self.consecutive_failures,
+                self.kpis.down_for(now).unwrap_or(0),
+                self.offline_after_failures,
+                self.node_down_grace_secs,
+                self.last_error.as_ref(),
+            );
+            self.persist_alerts();
+        }
     }
 
-    /// Get the health status for peer count
-    pub fn peer_health(&self) -> HealthStatus {
-        match self.metrics.peers_connected {
-            Some(peers) if peers >= 5 => HealthStatus::Good,
-            Some(peers) if peers >= 2 => HealthStatus::Warning,
-            Some(_) => HealthStatus::Critical,
-            None => HealthStatus::Warning,
+    /// End the ongoing outage at `now` and store it, returning its length
+    fn end_outage(&mut self, now: u64) -> Option<u64> {
+        let downtime = self.kpis.scrape_succeeded(now)?;
+        let outage = Outage {
+            start: now.saturating_sub(downtime),
+            end: now,
+        };
+        if let Err(e) = self.storage.record_outage(&outage) {
+            warn!("Failed to record outage: {}", e);
+        }
+        Some(downtime)
+    }
+
+    /// Store a chain API lookup, tracking stake changes on block producers
+    fn set_pool_info(&mut self, info: PoolInfo) {
+        if let (NodeRole::Bp, Some(live_stake)) = (self.role, info.live_stake) {
+            let snapshot = StakeSnapshot {
+                timestamp: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                live_stake,
+                active_stake: info.active_stake,
+                live_delegators: info.live_delegators,
+            };
+            let changed = self.stake_history.last().is_none_or(|last| {
+                (last.live_stake, last.active_stake, last.live_delegators)
+                    != (live_stake, info.active_stake, info.live_delegators)
+            });
+            if changed {
+                if let Some(last) = self.stake_history.last() {
+                    self.alert_manager.check_delegation_change(
+                        last.live_stake,
+                        live_stake,
+                        self.delegation_alert_ada.saturating_mul(1_000_000),
+                    );
+                    self.persist_alerts();
+                }
+                if let Err(e) = self.storage.record_stake(&snapshot) {
+                    warn!("Failed to record stake: {}", e);
+                }
+                let cutoff = snapshot.timestamp.saturating_sub(STAKE_TREND_DAYS * 86400);
+                self.stake_history.retain(|s| s.timestamp >= cutoff);
+                self.stake_history.push(snapshot);
+            }
+        }
+        self.pool_info = Some(info);
+    }
+
+    /// Record newly raised alerts in the node's alert history
+    fn persist_alerts(&mut self) {
+        let alerts = self.alert_manager.take_new();
+        if let Err(e) = self.storage.record_alerts(&alerts) {
+            warn!("Failed to record alerts: {}", e);
+        }
+        self.outbox.extend(alerts);
+    }
+
+    /// Alerts raised since the last call
+    pub fn take_outbox(&mut self) -> Vec<Alert> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    /// Get the connection state, using the health endpoint when metrics are down
+    ///
+    /// A node that was online stays "degraded" for the first few failed
+    /// scrapes, so one dropped request doesn't mark it offline.
+    pub fn connection_state(&self) -> ConnectionState {
+        if self.metrics.connected {
+            ConnectionState::Online
+        } else if self.fetch_count > 0 && self.consecutive_failures < self.offline_after() {
+            ConnectionState::Degraded
+        } else if self.health_ok == Some(true) {
+            ConnectionState::MetricsDown
+        } else {
+            ConnectionState::Offline
+        }
+    }
+
+    /// Seconds since scrapes started failing, if the node is unreachable
+    pub fn down_for(&self) -> Option<u64> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.kpis.down_for(now)
+    }
+
+    /// Consecutive failed scrapes after which the node counts as offline
+    fn offline_after(&self) -> u32 {
+        self.offline_after_failures.max(1)
+    }
+
+    /// Get the storage manager for this node
+    pub fn storage(&self) -> &StorageManager {
+        &self.storage
+    }
+
+    /// Get seconds since last block was received
+    pub fn tip_age_secs(&self) -> Option<u64> {
+        self.last_block_time.map(|t| t.elapsed().as_secs())
+    }
+
+    /// Get seconds since last successful metrics fetch
+    #[allow(dead_code)]
+    pub fn last_fetch_age_secs(&self) -> Option<u64> {
+        self.last_fetch_time.map(|t| t.elapsed().as_secs())
+    }
+
+    /// Get the round-trip time of the last successful scrape
+    pub fn scrape_rtt(&self) -> Option<Duration> {
+        self.scrape_rtts.back().copied()
+    }
+
+    /// Get (average, max) scrape round-trip time over recent samples
+    pub fn scrape_rtt_stats(&self) -> Option<(Duration, Duration)> {
+        let max = self.scrape_rtts.iter().max().copied()?;
+        let avg = self.scrape_rtts.iter().sum::<Duration>() / self.scrape_rtts.len() as u32;
+        Some((avg, max))
+    }
+
+    /// Get the scrape delay if it is large enough to skew time-sensitive values
+    pub fn measurement_delay(&self) -> Option<Duration> {
+        self.scrape_rtt()
+            .filter(|rtt| *rtt >= LATENCY_ANNOTATE_THRESHOLD)
+    }
+
+    /// Get the health status for scrape latency, relative to the request timeout
+    pub fn scrape_latency_health(&self) -> HealthStatus {
+        let timeout = self.config.prom_timeout();
+        match self.scrape_rtt() {
+            Some(rtt) if rtt < Duration::from_millis(250) => HealthStatus::Good,
+            Some(rtt) if rtt < timeout / 2 => HealthStatus::Warning,
+            Some(_) => HealthStatus::Critical,
+            None => HealthStatus::Warning,
+        }
+    }
+
+    /// Get the health status for peer count
+    pub fn peer_health(&self) -> HealthStatus {
+        match self.metrics.peers_connected {
+            Some(peers) if peers >= self.thresholds.peers_warning => HealthStatus::Good,
+            Some(peers) if peers >= self.thresholds.peers_critical => HealthStatus::Warning,
+            Some(_) => HealthStatus::Critical,
+            None => HealthStatus::Warning,
+        }
+    }
+
+    /// Peer selection targets: exported by the node, or from its config file
+    pub fn peer_targets(&self) -> Option<PeerTargets> {
+        let exported = self.metrics.p2p.targets;
+        if exported.is_set() {
+            Some(exported)
+        } else {
+            self.config_peer_targets
+        }
+    }
+
+    /// Health of a peer count against its governor target: within 10% is
+    /// on target, within 50% a warning, further off critical
+    pub fn peer_target_health(actual: u64, target: u64) -> HealthStatus {
+        if target == 0 {
+            return HealthStatus::Good;
+        }
+        let deviation = (actual as f64 - target as f64).abs() / target as f64;
+        if deviation <= 0.1 {
+            HealthStatus::Good
+        } else if deviation <= 0.5 {
+            HealthStatus::Warning
+        } else {
+            HealthStatus::Critical
         }
     }
 
@@ -267,16 +1515,70 @@ impl NodeState {
         }
     }
 
+    /// Connection errors and handshake failures per minute, where the node
+    /// exports either counter
+    pub fn connection_errors_per_min(&self) -> Option<f64> {
+        let errors = self.history.connection_error_rate.per_minute();
+        let failures = self.history.handshake_failure_rate.per_minute();
+        match (errors, failures) {
+            (None, None) => None,
+            (errors, failures) => Some(errors.unwrap_or(0.0) + failures.unwrap_or(0.0)),
+        }
+    }
+
+    /// Get the health status for the connection error rate
+    pub fn connection_error_health(&self) -> HealthStatus {
+        let threshold = self.connection_error_alert_per_min;
+        match self.connection_errors_per_min() {
+            Some(rate) if threshold > 0.0 && rate >= threshold => HealthStatus::Warning,
+            _ => HealthStatus::Good,
+        }
+    }
+
     /// Get the health status for memory usage
     pub fn memory_health(&self) -> HealthStatus {
         match self.metrics.memory_used {
-            Some(bytes) if bytes < 12_000_000_000 => HealthStatus::Good,
-            Some(bytes) if bytes < 14_000_000_000 => HealthStatus::Warning,
+            Some(bytes) if bytes < self.thresholds.memory_warning_bytes() => HealthStatus::Good,
+            Some(bytes) if bytes < self.thresholds.memory_critical_bytes() => HealthStatus::Warning,
+            Some(_) => HealthStatus::Critical,
+            None => HealthStatus::Good,
+        }
+    }
+
+    /// CPU usage in percent of one core, over the last few refreshes
+    pub fn cpu_percent(&self) -> Option<f64> {
+        self.history.cpu_usage.current()
+    }
+
+    /// CPU usage in percent of all the host's cores (of one core until the
+    /// core count is known)
+    pub fn cpu_load_percent(&self) -> Option<f64> {
+        Some(self.cpu_percent()? / self.cpu_cores.unwrap_or(1) as f64)
+    }
+
+    /// Get the health status for CPU usage
+    pub fn cpu_health(&self) -> HealthStatus {
+        match self.cpu_load_percent() {
+            Some(pct) if pct < self.thresholds.cpu_warning_percent => HealthStatus::Good,
+            Some(pct) if pct < self.thresholds.cpu_critical_percent => HealthStatus::Warning,
             Some(_) => HealthStatus::Critical,
             None => HealthStatus::Good,
         }
     }
 
+    /// Labels and formatted values of the custom metrics pinned to a panel
+    /// (`None` while the node doesn't expose the metric)
+    pub fn custom_metric_values(&self, panel: PanelKind) -> Vec<(&str, Option<String>)> {
+        self.custom_metrics
+            .iter()
+            .filter(|metric| metric.panel() == panel)
+            .map(|metric| {
+                let value = self.metrics.raw_value(&metric.name);
+                (metric.label(), value.map(|v| metric.format.format(v)))
+            })
+            .collect()
+    }
+
     /// Get the health status for KES key expiry
     pub fn kes_health(&self) -> HealthStatus {
         match self.metrics.kes_remaining {
@@ -290,8 +1592,8 @@ impl NodeState {
     /// Get the health status for tip age
     pub fn tip_health(&self) -> HealthStatus {
         match self.tip_age_secs() {
-            Some(age) if age < 60 => HealthStatus::Good,
-            Some(age) if age < 120 => HealthStatus::Warning,
+            Some(age) if age < self.thresholds.tip_age_warning_secs => HealthStatus::Good,
+            Some(age) if age < self.thresholds.tip_age_critical_secs => HealthStatus::Warning,
             Some(_) => HealthStatus::Critical,
             None => HealthStatus::Good,
         }
@@ -299,8 +1601,16 @@ impl NodeState {
 
     /// Get the overall node health
     pub fn overall_health(&self) -> HealthStatus {
-        if !self.metrics.connected {
-            return HealthStatus::Critical;
+        match self.connection_state() {
+            ConnectionState::Online => {}
+            ConnectionState::Degraded | ConnectionState::MetricsDown => {
+                return HealthStatus::Warning
+            }
+            ConnectionState::Offline => return HealthStatus::Critical,
+        }
+        // Chain metrics are missing until the ledger replay completes
+        if self.startup.status().is_some() {
+            return HealthStatus::Warning;
         }
 
         let statuses = [
@@ -320,16 +1630,88 @@ impl NodeState {
         }
     }
 
-    /// Get blocks per minute from history
-    #[allow(dead_code)]
+    /// Blocks per minute over the last hour
     pub fn blocks_per_minute(&self) -> Option<f64> {
-        let trend = self.history.block_height.trend()?;
-        let samples = self.history.block_height.len();
-        if samples < 2 {
+        let rate = &self.history.block_rate_1h;
+        if rate.span_secs() < MIN_CHAIN_RATE_SPAN_SECS {
+            return None;
+        }
+        rate.per_minute()
+    }
+
+    /// Chain density over the last hour (blocks per slot)
+    pub fn density_1h(&self) -> Option<f64> {
+        let blocks = &self.history.block_rate_1h;
+        if blocks.span_secs() < MIN_CHAIN_RATE_SPAN_SECS {
+            return None;
+        }
+        let slots = self.history.slot_rate_1h.per_second()?;
+        (slots > 0.0).then(|| blocks.per_second().unwrap_or(0.0) / slots)
+    }
+
+    /// Density to judge against the active slot coefficient: the node's own
+    /// figure (over the last k blocks), else the last hour once a good part
+    /// of it has been observed. `None` while syncing, when the tip is in an
+    /// older stretch of the chain.
+    pub fn recent_density(&self) -> Option<f64> {
+        if self.metrics.sync_progress? < 99.9 {
+            return None;
+        }
+        if self.metrics.density.is_some() {
+            return self.metrics.density;
+        }
+        if self.history.block_rate_1h.span_secs() < MIN_DENSITY_SPAN_SECS {
+            return None;
+        }
+        self.density_1h()
+    }
+
+    /// Get the health status for chain density relative to the active slot
+    /// coefficient
+    pub fn density_health(&self) -> HealthStatus {
+        match self.recent_density() {
+            Some(density) => {
+                let ratio = density / self.config.active_slots_coeff;
+                if ratio < DENSITY_CRITICAL_RATIO {
+                    HealthStatus::Critical
+                } else if ratio < DENSITY_WARNING_RATIO {
+                    HealthStatus::Warning
+                } else {
+                    HealthStatus::Good
+                }
+            }
+            None => HealthStatus::Good,
+        }
+    }
+
+    /// Tip slots advanced per second over the last few minutes
+    pub fn slot_rate(&self) -> Option<f64> {
+        let rate = &self.history.slot_rate;
+        if rate.span_secs() < MIN_SLOT_RATE_SPAN_SECS {
             return None;
         }
-        let seconds = samples as f64 * self.config.refresh_interval_secs as f64;
-        Some(trend / seconds * 60.0)
+        rate.per_second()
+    }
+
+    /// Slot rate relative to wall-clock slots: below 1 means the tip is
+    /// falling behind, above 1 means the node is catching up
+    pub fn slot_rate_ratio(&self) -> Option<f64> {
+        let expected = 1.0 / self.config.slot_length_secs;
+        self.slot_rate().map(|rate| rate / expected)
+    }
+
+    /// Get the health status for slot progression
+    pub fn slot_rate_health(&self) -> HealthStatus {
+        match self.slot_rate_ratio() {
+            Some(ratio)
+                if (1.0 - SLOT_RATE_TOLERANCE..=1.0 + SLOT_RATE_TOLERANCE).contains(&ratio) =>
+            {
+                HealthStatus::Good
+            }
+            Some(ratio) if ratio < 0.1 => HealthStatus::Critical,
+            Some(_) => HealthStatus::Warning,
+            None => HealthStatus::Good,
+        }
     }
 
     /// Get epoch progress as a percentage
@@ -339,24 +1721,167 @@ impl NodeState {
         Some((slot_in_epoch / epoch_length) * 100.0)
     }
 
-    /// Get estimated time remaining in the current epoch
+    /// Get estimated time remaining in the current epoch (seconds)
     pub fn epoch_time_remaining(&self) -> Option<u64> {
         let slot_in_epoch = self.metrics.slot_in_epoch?;
         let remaining_slots = self.config.epoch_length.saturating_sub(slot_in_epoch);
-        Some(remaining_slots)
+        Some((remaining_slots as f64 * self.config.slot_length_secs) as u64)
+    }
+
+    /// Slots the network has moved on since the node adopted its tip
+    fn slots_since_tip(&self) -> u64 {
+        match self.last_block_time {
+            Some(t) if self.config.slot_length_secs > 0.0 => {
+                (t.elapsed().as_secs_f64() / self.config.slot_length_secs) as u64
+            }
+            _ => 0,
+        }
+    }
+
+    /// Blocks and seconds this node's tip trails the reference network tip
+    pub fn network_lag(&self) -> Option<(u64, u64)> {
+        let reference = self.reference_tip?;
+        if !self.metrics.connected {
+            return None;
+        }
+        let (blocks, slots) = reference.lag(self.metrics.block_height?, self.metrics.slot_num?);
+        Some((blocks, (slots as f64 * self.config.slot_length_secs) as u64))
+    }
+
+    /// Get the health status for the lag behind the reference tip
+    pub fn network_lag_health(&self) -> HealthStatus {
+        match self.network_lag() {
+            Some((blocks, _)) if self.max_lag_blocks > 0 && blocks >= self.max_lag_blocks => {
+                HealthStatus::Warning
+            }
+            _ => HealthStatus::Good,
+        }
+    }
+
+    /// Alert when the node trails the reference tip
+    fn check_network_lag(&mut self) {
+        self.alert_manager
+            .check_network_lag(self.network_lag(), self.max_lag_blocks);
+    }
+
+    /// Current network slot, from the wall clock where the network's genesis
+    /// is known, else the tip slot advanced by the time since it was adopted
+    pub fn network_slot(&self) -> Option<u64> {
+        if let Some(slot_zero) = self.config.slot_zero_time {
+            if self.config.slot_length_secs > 0.0 {
+                let now = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs_f64();
+                let elapsed = (now - slot_zero as f64).max(0.0);
+                return Some((elapsed / self.config.slot_length_secs) as u64);
+            }
+        }
+        Some(self.metrics.slot_num? + self.slots_since_tip())
+    }
+
+    /// The next epoch and the seconds until it starts, counted from the
+    /// current network slot rather than the last scrape
+    pub fn next_epoch_countdown(&self) -> Option<(u64, u64)> {
+        let epoch_length = self.config.epoch_length.max(1);
+        let slot_in_epoch = self.metrics.slot_in_epoch? + self.slots_since_tip();
+        let next_epoch = self.metrics.epoch? + slot_in_epoch / epoch_length + 1;
+        let remaining_slots = epoch_length - slot_in_epoch % epoch_length;
+        Some((
+            next_epoch,
+            (remaining_slots as f64 * self.config.slot_length_secs) as u64,
+        ))
+    }
+
+    /// Node version: detected from build info, else the configured `version`
+    pub fn node_version(&self) -> Option<&str> {
+        self.metrics
+            .build_info
+            .version
+            .as_deref()
+            .or(self.configured_version.as_deref())
+    }
+
+    /// The version to compare with cardano-node releases and hard-fork
+    /// minimums (`None` for other node implementations)
+    pub fn cardano_node_version(&self) -> Option<&str> {
+        if self.metrics.node_type != NodeType::CardanoNode {
+            return None;
+        }
+        self.node_version()
+    }
+
+    /// Seconds until the scheduled hard fork, or `None` if there is none ahead
+    pub fn hard_fork_secs(&self) -> Option<u64> {
+        self.hard_fork.as_ref()?.secs_until(
+            self.metrics.slot_num,
+            self.metrics.epoch,
+            self.epoch_time_remaining(),
+            self.config.epoch_length,
+            self.config.slot_length_secs,
+        )
+    }
+
+    /// The last fetch error with the tunnel it went through and a hint
+    pub fn error_text(&self) -> Option<String> {
+        let error = self.last_error.as_ref()?;
+        let mut text = error.to_string();
+        if let (Some(tunnel), false) = (&self.tunnel, matches!(error, FetchError::Tunnel(_))) {
+            text = format!("{} ({})", text, tunnel.describe());
+        }
+        if let Some(hint) = error.hint() {
+            text = format!("{} ({})", text, hint);
+        }
+        Some(text)
     }
 
     /// Get the status text for display
-    #[allow(dead_code)]
     pub fn status_text(&self) -> &str {
         if self.metrics.connected {
             "Connected"
+        } else if self.connection_state() == ConnectionState::Degraded {
+            "Degraded"
+        } else if self.health_ok == Some(true) {
+            "Metrics Down"
         } else if self.last_error.is_some() {
             "Connection Error"
         } else {
             "Connecting..."
         }
     }
+
+    /// Key values of the node as plain text (for the clipboard)
+    pub fn summary_text(&self) -> String {
+        let m = &self.metrics;
+        let mut lines = vec![format!(
+            "{} ({}): {}",
+            self.config.node_name,
+            self.config.network,
+            self.status_text()
+        )];
+        let mut push = |label: &str, value: Option<String>| {
+            if let Some(value) = value {
+                lines.push(format!("{}: {}", label, value));
+            }
+        };
+        push("Block height", m.block_height.map(|v| v.to_string()));
+        push("Epoch", m.epoch.map(|v| v.to_string()));
+        push("Slot", m.slot_num.map(|v| v.to_string()));
+        push("Tip age", self.tip_age_secs().map(|v| format!("{}s", v)));
+        push("Sync", m.sync_progress.map(|v| format!("{:.2}%", v)));
+        push("Peers", m.peers_connected.map(|v| v.to_string()));
+        push("Density", m.density.map(|v| format!("{:.4}", v)));
+        push("Mempool txs", m.mempool_txs.map(|v| v.to_string()));
+        push(
+            "Memory",
+            m.memory_used
+                .map(|v| format!("{:.2} GB", v as f64 / 1024.0 / 1024.0 / 1024.0)),
+        );
+        push("KES remaining", m.kes_remaining.map(|v| v.to_string()));
+        push("Blocks adopted", m.blocks_adopted.map(|v| v.to_string()));
+        push("Missed slots", m.missed_slots.map(|v| v.to_string()));
+        lines.join("\n")
+    }
 }
 
 /// Main application state supporting multiple nodes
@@ -375,12 +1900,98 @@ pub struct App {
     pub theme: Theme,
     /// GeoIP service for peer location lookups
     geoip_service: GeoIPService,
+    /// Running lookup of uncached peer locations, with the node and peers
+    /// to check for concentration once it lands
+    location_task: Option<BackgroundTask<(usize, Vec<PeerConnection>, BatchAnswer)>>,
     /// Cached peer locations (IP -> "City, CC")
     pub peer_locations: HashMap<String, String>,
+    /// Cached peer networks (IP -> AS or ISP name)
+    pub peer_networks: HashMap<String, String>,
+    /// Cached reverse DNS names (IP -> lookup time and host name, None
+    /// without a PTR record), dropped after `HOSTNAME_TTL`
+    peer_hostnames: HashMap<String, (Instant, Option<String>)>,
+    /// Reverse DNS lookups still running, and the IP of each task
+    hostname_lookups: tokio::task::JoinSet<Option<String>>,
+    hostnames_pending: HashMap<tokio::task::Id, String>,
+    /// Grouping of the peer list
+    pub peer_grouping: PeerGrouping,
+    /// Banned peer IPs
+    pub blocklist: Blocklist,
+    /// Time of the last background peer scan
+    last_peer_scan: Option<Instant>,
     /// Currently selected peer index in peer list
     pub peer_list_selected: usize,
     /// Scroll offset for peer list
     pub peer_list_scroll: usize,
+    /// Scroll offset for mempool list
+    pub mempool_scroll: usize,
+    /// Scroll offset for the propagation ranking
+    pub propagation_scroll: usize,
+    /// Selected threshold in the settings overlay
+    pub settings_selected: usize,
+    /// Result of the last settings write-back (shown in the overlay)
+    pub settings_status: Option<String>,
+    /// Index into `BENCHMARK_WINDOWS_HOURS` for the node comparison
+    pub benchmark_window: usize,
+    /// State of the add-node form
+    pub node_form: NodeForm,
+    /// Text typed on the `:` command line
+    pub command_input: String,
+    /// Display frozen (metrics are still collected)
+    pub paused: bool,
+    /// Index of the expanded panel in the layout (left to right, top to bottom)
+    pub focused_panel: usize,
+    /// Scroll offset for the focus view's metric list
+    pub focus_scroll: usize,
+    /// Label of the dashboard row selected with ↑/↓
+    pub selected_metric: Option<String>,
+    /// Save the next rendered frame as a screenshot
+    pub screenshot_requested: bool,
+    /// Alerts are hidden from the header until this time (`:silence`)
+    alerts_silenced_until: Option<Instant>,
+    /// Node groups collapsed to a single tab
+    pub collapsed_groups: HashSet<String>,
+    /// Node added from the form, awaiting a decision on saving it
+    pending_new_node: Option<NodeRuntimeConfig>,
+    /// Shared chain API provider (Koios/Blockfrost) for all nodes
+    chain_api: ChainApiProvider,
+    /// Time of last chain API refresh
+    last_chain_api_refresh: Option<Instant>,
+    /// Time of last storage quota check
+    last_storage_check: Option<Instant>,
+    /// Running pool info lookup, by (network, pool_id)
+    pool_info_task: Option<BackgroundTask<HashMap<(String, String), PoolInfo>>>,
+    /// Running per-epoch rewards lookup
+    rewards_task: Option<BackgroundTask<PoolRewards>>,
+    /// Running hard fork lookup: what each network that answered has
+    /// ratified, by network
+    hard_fork_task: Option<BackgroundTask<HashMap<String, Option<HardFork>>>>,
+    /// Time of the last reference tip lookup on the chain API
+    last_reference_tip: Option<Instant>,
+    /// Running reference tip lookup: the tip of each network that answered
+    reference_task: Option<BackgroundTask<Vec<(String, ReferenceTip)>>>,
+    /// Time of the last cardano-node release lookup
+    last_release_check: Option<Instant>,
+    /// Running cardano-node release lookup
+    release_task: Option<BackgroundTask<anyhow::Result<LatestRelease>>>,
+    /// MQTT broker connection (`[global.mqtt]`)
+    mqtt: Option<MqttPublisher>,
+    /// Push notification channels (`[[notify]]`)
+    notifier: Option<Notifier>,
+    /// Dead-man switch pings (`[global.heartbeat]`)
+    heartbeat: Option<Heartbeat>,
+    /// Time of the last heartbeat ping
+    last_heartbeat: Option<Instant>,
+    /// Web dashboard (`--web`)
+    web: Option<WebServer>,
+    /// Modification time of the config file when last loaded
+    config_mtime: Option<SystemTime>,
+    /// Time of the last config file change check
+    last_config_check: Instant,
+    /// Refresh interval set with `:refresh`, kept across config reloads
+    refresh_override: Option<Duration>,
+    /// Transient message shown in the footer (e.g. config reload result)
+    status_message: Option<(String, Instant)>,
 }
 
 impl App {
@@ -392,6 +2003,19 @@ impl App {
             .map(|n| NodeState::new(n, &app_config))
             .collect();
 
+        let chain_api = ChainApiProvider::new(
+            app_config.chain_api_backend.clone(),
+            app_config.chain_api_ttl,
+        );
+
+        let config_mtime = file_mtime(app_config.config_path.as_deref());
+        let blocklist = load_blocklist(&app_config.blocklist_path);
+        let mqtt = MqttPublisher::new(&app_config.mqtt);
+        let notifier = Notifier::new(&app_config.notify, app_config.timezone);
+        let heartbeat = Heartbeat::new(&app_config.heartbeat);
+        let geoip_service =
+            GeoIPService::with_cache_file(&app_config.geoip, crate::geoip::cache_path());
+
         Self {
             app_config,
             nodes,
@@ -399,10 +2023,52 @@ impl App {
             last_refresh: Instant::now(),
             mode: AppMode::Normal,
             theme: Theme::default(),
-            geoip_service: GeoIPService::new(),
+            geoip_service,
+            location_task: None,
             peer_locations: HashMap::new(),
+            peer_networks: HashMap::new(),
+            peer_hostnames: HashMap::new(),
+            hostname_lookups: tokio::task::JoinSet::new(),
+            hostnames_pending: HashMap::new(),
+            peer_grouping: PeerGrouping::default(),
+            blocklist,
+            last_peer_scan: None,
             peer_list_selected: 0,
             peer_list_scroll: 0,
+            mempool_scroll: 0,
+            propagation_scroll: 0,
+            settings_selected: 0,
+            settings_status: None,
+            benchmark_window: 1,
+            node_form: NodeForm::default(),
+            command_input: String::new(),
+            paused: false,
+            focused_panel: 0,
+            focus_scroll: 0,
+            selected_metric: None,
+            screenshot_requested: false,
+            alerts_silenced_until: None,
+            collapsed_groups: HashSet::new(),
+            pending_new_node: None,
+            chain_api,
+            last_chain_api_refresh: None,
+            last_storage_check: None,
+            pool_info_task: None,
+            rewards_task: None,
+            hard_fork_task: None,
+            last_reference_tip: None,
+            reference_task: None,
+            last_release_check: None,
+            release_task: None,
+            mqtt,
+            notifier,
+            heartbeat,
+            last_heartbeat: None,
+            web: None,
+            config_mtime,
+            last_config_check: Instant::now(),
+            refresh_override: None,
+            status_message: None,
         }
     }
 
@@ -412,7 +2078,6 @@ impl App {
     }
 
     /// Get the currently selected node mutably
-    #[allow(dead_code)]
     pub fn current_node_mut(&mut self) -> &mut NodeState {
         &mut self.nodes[self.selected_node]
     }
@@ -421,6 +2086,7 @@ impl App {
     pub fn next_node(&mut self) {
         if self.nodes.len() > 1 {
             self.selected_node = (self.selected_node + 1) % self.nodes.len();
+            self.clear_metric_selection();
         }
     }
 
@@ -432,6 +2098,7 @@ impl App {
             } else {
                 self.selected_node - 1
             };
+            self.clear_metric_selection();
         }
     }
 
@@ -439,6 +2106,7 @@ impl App {
     pub fn select_node(&mut self, index: usize) {
         if index < self.nodes.len() {
             self.selected_node = index;
+            self.clear_metric_selection();
         }
     }
 
@@ -447,99 +2115,961 @@ impl App {
         self.nodes.len() > 1
     }
 
-    /// Fetch metrics from all nodes
-    pub async fn fetch_all_metrics(&mut self) {
-        for node in &mut self.nodes {
-            node.fetch_metrics().await;
+    /// Tab bar entries: nodes in config order, with grouped nodes gathered
+    /// under their group's heading where the group first appears
+    pub fn node_tabs(&self) -> Vec<NodeTab> {
+        let mut tabs = Vec::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            let Some(group) = &node.group else {
+                tabs.push(NodeTab::Node(i));
+                continue;
+            };
+            if tabs
+                .iter()
+                .any(|tab| matches!(tab, NodeTab::Group { name, .. } if name == group))
+            {
+                continue;
+            }
+            let members: Vec<usize> = (i..self.nodes.len())
+                .filter(|&j| self.nodes[j].group.as_ref() == Some(group))
+                .collect();
+            let collapsed = self.collapsed_groups.contains(group);
+            tabs.push(NodeTab::Group {
+                name: group.clone(),
+                nodes: members.clone(),
+                collapsed,
+            });
+            if !collapsed {
+                tabs.extend(members.into_iter().map(NodeTab::Node));
+            }
         }
+        tabs
     }
 
-    /// Fetch metrics from the current node only
-    #[allow(dead_code)]
-    pub async fn fetch_current_metrics(&mut self) {
-        self.nodes[self.selected_node].fetch_metrics().await;
+    /// Worst health and number of online nodes among a group's members
+    pub fn group_health(&self, nodes: &[usize]) -> (HealthStatus, usize) {
+        let health = nodes.iter().map(|&i| self.nodes[i].overall_health()).fold(
+            HealthStatus::Good,
+            |worst, health| match (worst, health) {
+                (HealthStatus::Critical, _) | (_, HealthStatus::Critical) => HealthStatus::Critical,
+                (HealthStatus::Warning, _) | (_, HealthStatus::Warning) => HealthStatus::Warning,
+                _ => HealthStatus::Good,
+            },
+        );
+        let online = nodes
+            .iter()
+            .filter(|&&i| self.nodes[i].connection_state() == ConnectionState::Online)
+            .count();
+        (health, online)
     }
 
-    /// Called on each tick to handle periodic updates
-    pub async fn tick(&mut self) {
-        if self.last_refresh.elapsed() >= self.app_config.refresh_interval {
-            self.fetch_all_metrics().await;
-            self.last_refresh = Instant::now();
+    /// Collapse or expand the current node's group in the tab bar
+    pub fn toggle_group(&mut self) {
+        let Some(group) = self.current_node().group.clone() else {
+            self.set_status("This node isn't in a group".to_string());
+            return;
+        };
+        if !self.collapsed_groups.remove(&group) {
+            self.collapsed_groups.insert(group);
         }
     }
 
-    /// Toggle help mode
-    pub fn toggle_help(&mut self) {
-        self.mode = match self.mode {
-            AppMode::Normal => AppMode::Help,
-            AppMode::Help | AppMode::Peers | AppMode::PeerDetail | AppMode::Graphs => {
-                AppMode::Normal
+    /// Summarize all nodes for the fleet strip
+    pub fn fleet_summary(&self) -> FleetSummary {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let max_tip = self
+            .nodes
+            .iter()
+            .filter_map(|n| n.metrics.block_height)
+            .max();
+        // Block heights of different networks can't be compared
+        let mut network_tips: HashMap<&str, u64> = HashMap::new();
+        for node in &self.nodes {
+            if let Some(height) = node.metrics.block_height {
+                let tip = network_tips.entry(&node.config.network).or_insert(height);
+                *tip = (*tip).max(height);
             }
-        };
+        }
+        let worst_lag = self
+            .nodes
+            .iter()
+            .filter_map(|n| {
+                let tip = network_tips.get(n.config.network.as_str())?;
+                Some((n, tip.saturating_sub(n.metrics.block_height?)))
+            })
+            .filter(|(_, lag)| *lag > 0)
+            .max_by_key(|(_, lag)| *lag)
+            .map(|(n, lag)| (n.config.node_name.clone(), lag));
+        FleetSummary {
+            online: self
+                .nodes
+                .iter()
+                .filter(|n| n.connection_state() == ConnectionState::Online)
+                .count(),
+            total: self.nodes.len(),
+            max_tip,
+            worst_lag,
+            total_peers: self
+                .nodes
+                .iter()
+                .filter_map(|n| n.metrics.peers_connected)
+                .sum(),
+            critical_nodes: self
+                .nodes
+                .iter()
+                .filter(|n| {
+                    n.alert_manager
+                        .latest_critical()
+                        .is_some_and(|a| a.timestamp + FLEET_ALERT_WINDOW_SECS >= now)
+                })
+                .count(),
+        }
     }
 
-    /// Toggle peers view
-    pub async fn toggle_peers(&mut self) {
-        self.mode = match self.mode {
-            AppMode::Normal => {
-                // Refresh peer connections when entering peers view
-                self.nodes[self.selected_node].refresh_peer_connections();
-                self.fetch_peer_locations().await;
-                // Reset selection
-                self.peer_list_selected = 0;
-                self.peer_list_scroll = 0;
-                AppMode::Peers
+    /// Fetch metrics from all nodes
+    pub async fn fetch_all_metrics(&mut self) {
+        for node in &mut self.nodes {
+            node.fetch_metrics().await;
+        }
+        if self.app_config.reference_tip.source == Some(ReferenceSource::Node) {
+            self.update_reference_from_node();
+        }
+        for node in &mut self.nodes {
+            node.check_network_lag();
+        }
+        let storage_due = self
+            .last_storage_check
+            .is_none_or(|t| t.elapsed() >= STORAGE_CHECK_INTERVAL);
+        if storage_due {
+            self.check_storage().await;
+            self.last_storage_check = Some(Instant::now());
+        }
+        self.publish();
+    }
+
+    /// Measure each data directory and node history off the async runtime,
+    /// alerting once per data directory rather than once per node
+    async fn check_storage(&mut self) {
+        let dirs: Vec<(PathBuf, PathBuf)> = self
+            .nodes
+            .iter()
+            .map(|n| (n.storage.data_dir().to_path_buf(), n.storage.history_dir()))
+            .collect();
+        let measured = tokio::task::spawn_blocking(move || {
+            let mut usage: HashMap<PathBuf, DiskUsage> = HashMap::new();
+            dirs.into_iter()
+                .map(|(data_dir, history_dir)| {
+                    let dir_usage = *usage
+                        .entry(data_dir.clone())
+                        .or_insert_with(|| crate::storage::disk_usage(&data_dir));
+                    (data_dir, dir_usage, crate::storage::dir_size(&history_dir))
+                })
+                .collect::<Vec<_>>()
+        })
+        .await;
+        let measured = match measured {
+            Ok(measured) => measured,
+            Err(e) => {
+                warn!("Storage check failed: {}", e);
+                return;
             }
-            AppMode::Peers | AppMode::PeerDetail => AppMode::Normal,
-            AppMode::Help | AppMode::Graphs => AppMode::Normal,
         };
+        let mut checked = HashSet::new();
+        for (node, (data_dir, usage, history_bytes)) in self.nodes.iter_mut().zip(measured) {
+            let first = checked.insert(data_dir);
+            node.apply_storage(usage, history_bytes, first);
+        }
     }
 
-    /// Toggle graphs view mode
-    pub fn toggle_graphs(&mut self) {
-        self.mode = match self.mode {
-            AppMode::Normal => AppMode::Graphs,
-            AppMode::Graphs => AppMode::Normal,
-            _ => AppMode::Normal,
+    /// Use the trusted node's tip as the reference for the other nodes on
+    /// its network (`source = "node"`)
+    fn update_reference_from_node(&mut self) {
+        let name = self.app_config.reference_tip.node.as_deref();
+        let Some(trusted) = self
+            .nodes
+            .iter()
+            .find(|n| Some(n.config.node_name.as_str()) == name)
+        else {
+            return;
         };
+        let network = trusted.config.network.clone();
+        let trusted_name = trusted.config.node_name.clone();
+        let tip = match (
+            trusted.metrics.connected,
+            trusted.metrics.block_height,
+            trusted.metrics.slot_num,
+        ) {
+            (true, Some(block_height), Some(slot)) => Some(ReferenceTip { block_height, slot }),
+            _ => None,
+        };
+        for node in self
+            .nodes
+            .iter_mut()
+            .filter(|n| n.config.network == network && n.config.node_name != trusted_name)
+        {
+            node.reference_tip = tip;
+        }
     }
 
-    /// Refresh peer connections for current node
-    pub async fn refresh_peers(&mut self) {
-        self.nodes[self.selected_node].refresh_peer_connections();
-        self.fetch_peer_locations().await;
-        // Reset selection after refresh
-        self.peer_list_selected = 0;
-        self.peer_list_scroll = 0;
+    /// Look up each monitored network's tip on the chain API in the
+    /// background (`source = "api"`)
+    fn start_reference_tip(&mut self) {
+        let networks: BTreeSet<String> = self
+            .nodes
+            .iter()
+            .map(|n| n.config.network.clone())
+            .collect();
+        let chain_api = self.chain_api.clone();
+        self.reference_task = Some(BackgroundTask::spawn(async move {
+            let mut tips = Vec::new();
+            for network in networks {
+                if let Some(tip) = chain_api.tip(&network).await {
+                    tips.push((network, tip));
+                }
+            }
+            tips
+        }));
     }
 
-    /// Fetch geolocation for current peer IPs
-    async fn fetch_peer_locations(&mut self) {
-        let peers = &self.nodes[self.selected_node].peer_connections;
-        let ips: Vec<String> = peers.iter().map(|p| p.ip.clone()).collect();
-
-        if !ips.is_empty() {
-            let locations = self.geoip_service.lookup_batch(&ips).await;
-            for (ip, loc) in locations {
-                self.peer_locations.insert(ip, loc.short());
+    /// Hand the tips of a finished reference lookup to the nodes, returning
+    /// whether one landed
+    async fn finish_reference_tip(&mut self) -> bool {
+        let Some(tips) = take_finished(&mut self.reference_task).await else {
+            return false;
+        };
+        // A failed lookup keeps the previous tip; it can only understate lag
+        for (network, tip) in tips {
+            for node in self
+                .nodes
+                .iter_mut()
+                .filter(|n| n.config.network == network)
+            {
+                node.reference_tip = Some(tip);
             }
         }
+        true
     }
 
-    /// Move selection up in peer list
-    pub fn peer_list_up(&mut self, _visible_rows: usize) {
-        if self.peer_list_selected > 0 {
-            self.peer_list_selected -= 1;
-            // Scroll up if selection goes above visible area
-            if self.peer_list_selected < self.peer_list_scroll {
-                self.peer_list_scroll = self.peer_list_selected;
+    /// Serve the web dashboard from this app's state
+    pub fn serve_web(&mut self, server: WebServer) {
+        server.update(self);
+        self.set_status(format!("Web dashboard on http://{}", server.addr()));
+        self.web = Some(server);
+    }
+
+    /// Send each node's state and new alerts to the configured publishers
+    fn publish(&mut self) {
+        for node in &mut self.nodes {
+            let alerts = node.take_outbox();
+            if let Some(mqtt) = &self.mqtt {
+                mqtt.publish_node(
+                    &node.config.node_name,
+                    node.overall_health(),
+                    node.tip_age_secs(),
+                );
+                for alert in &alerts {
+                    mqtt.publish_alert(alert);
+                }
+            }
+            if let Some(notifier) = &self.notifier {
+                for alert in &alerts {
+                    notifier.send(alert, node.notify_route.as_deref());
+                }
+            }
+            if let Some(web) = &self.web {
+                for alert in &alerts {
+                    web.publish_alert(alert);
+                }
             }
         }
+        if let Some(web) = &self.web {
+            web.update(self);
+        }
     }
 
-    /// Move selection down in peer list
+    /// Fetch metrics from the current node only
+    #[allow(dead_code)]
+    pub async fn fetch_current_metrics(&mut self) {
+        self.nodes[self.selected_node].fetch_metrics().await;
+    }
+
+    /// Called on each tick to handle periodic updates
+    ///
+    /// Returns whether any work ran, i.e. the screen may need redrawing.
+    pub async fn tick(&mut self) -> bool {
+        let mut ran = self.finish_peer_refreshes().await;
+        ran |= self.finish_peer_locations().await;
+        ran |= self.finish_hostname_lookups();
+        if self.last_refresh.elapsed() >= self.app_config.refresh_interval {
+            ran = true;
+            self.fetch_all_metrics().await;
+            if self.mode == AppMode::Mempool {
+                self.nodes[self.selected_node].refresh_mempool().await;
+            }
+            if matches!(self.mode, AppMode::Peers | AppMode::PeerDetail) {
+                self.nodes[self.selected_node].update_peer_traffic().await;
+            }
+            self.last_refresh = Instant::now();
+        }
+
+        if self.last_config_check.elapsed() >= CONFIG_CHECK_INTERVAL {
+            self.last_config_check = Instant::now();
+            let mtime = file_mtime(self.app_config.config_path.as_deref());
+            if mtime.is_some() && mtime != self.config_mtime {
+                self.reload_config();
+                ran = true;
+            }
+        }
+
+        let peer_scan_due = self
+            .last_peer_scan
+            .map(|t| t.elapsed() >= PEER_SCAN_INTERVAL)
+            .unwrap_or(true);
+        if peer_scan_due {
+            self.scan_peers().await;
+            self.last_peer_scan = Some(Instant::now());
+            ran = true;
+        }
+
+        let chain_api_due = self
+            .last_chain_api_refresh
+            .map(|t| t.elapsed() >= self.chain_api.cache_ttl())
+            .unwrap_or(true);
+        ran |= self.finish_pool_info().await;
+        ran |= self.finish_rewards().await;
+        ran |= self.finish_hard_fork().await;
+        if chain_api_due {
+            self.start_pool_info();
+            self.start_rewards();
+            self.start_hard_fork();
+            self.last_chain_api_refresh = Some(Instant::now());
+            ran = true;
+        }
+
+        ran |= self.finish_reference_tip().await;
+        let reference = &self.app_config.reference_tip;
+        let reference_due = reference.source == Some(ReferenceSource::Api)
+            && self.reference_task.is_none()
+            && self
+                .last_reference_tip
+                .is_none_or(|t| t.elapsed() >= Duration::from_secs(reference.interval_secs.max(1)));
+        if reference_due {
+            self.start_reference_tip();
+            self.last_reference_tip = Some(Instant::now());
+        }
+
+        self.send_heartbeat();
+
+        ran |= self.finish_release_check().await;
+        let release_due = self
+            .last_release_check
+            .map(|t| t.elapsed() >= crate::release::CHECK_INTERVAL)
+            .unwrap_or(true);
+        // Releases are only compared with cardano-node versions
+        let cardano_nodes = self
+            .nodes
+            .iter()
+            .any(|n| n.metrics.node_type == NodeType::CardanoNode);
+        if self.app_config.check_node_release
+            && cardano_nodes
+            && release_due
+            && self.release_task.is_none()
+        {
+            self.start_release_check();
+            self.last_release_check = Some(Instant::now());
+        }
+        ran
+    }
+
+    /// Ping the heartbeat URL, if one is configured and a ping is due
+    fn send_heartbeat(&mut self) {
+        let Some(heartbeat) = &self.heartbeat else {
+            return;
+        };
+        if self
+            .last_heartbeat
+            .is_some_and(|t| t.elapsed() < heartbeat.interval())
+        {
+            return;
+        }
+        let critical = self
+            .nodes
+            .iter()
+            .any(|n| n.overall_health() == HealthStatus::Critical);
+        heartbeat.ping(critical, self.heartbeat_summary());
+        self.last_heartbeat = Some(Instant::now());
+    }
+
+    /// One line per node with its health, sent as the heartbeat body
+    fn heartbeat_summary(&self) -> String {
+        self.nodes
+            .iter()
+            .map(|n| {
+                format!(
+                    "{}: {}\n",
+                    n.config.node_name,
+                    crate::status::health_label(n.overall_health())
+                )
+            })
+            .collect()
+    }
+
+    /// Time until the next metrics refresh is due, or until a running peer
+    /// refresh should be checked again
+    pub fn until_next_tick(&self) -> Duration {
+        let next = self
+            .app_config
+            .refresh_interval
+            .saturating_sub(self.last_refresh.elapsed());
+        if self.nodes.iter().any(|n| n.peer_refreshing()) {
+            next.min(PEER_REFRESH_POLL_INTERVAL)
+        } else {
+            next
+        }
+    }
+
+    /// Check the selected node's connections for abnormal concentration
+    ///
+    /// Runs in the background so a relay being hammered is noticed without
+    /// opening the peer list. Only applies where sockets can be inspected,
+    /// and only while a concentration alert is enabled; peer IPs go to the
+    /// GeoIP provider only for the per-network alerts.
+    async fn scan_peers(&mut self) {
+        let node = &mut self.nodes[self.selected_node];
+        let network_alerts =
+            node.peer_network_alert_percent > 0 || node.peer_outgoing_network_alert_percent > 0;
+        if node.peer_data_mode != PeerDataMode::Full
+            || !(network_alerts || node.peer_subnet_alert > 0)
+        {
+            return;
+        }
+        let (peers, _) = node.sample_host().await;
+        if peers.is_empty() {
+            return;
+        }
+        node.peer_ages.update(&peers, Instant::now());
+        if network_alerts {
+            self.start_peer_locations(self.selected_node, peers);
+        } else {
+            node.check_peer_concentration(&peers, &self.peer_networks);
+        }
+    }
+
+    /// (network, pool_id) of every node with a configured pool_id
+    fn pool_requests(&self) -> Vec<(String, String)> {
+        self.nodes
+            .iter()
+            .filter_map(|n| Some((n.config.network.clone(), n.pool_id.clone()?)))
+            .collect()
+    }
+
+    /// Look up pool info for all nodes with a configured pool_id in the
+    /// background
+    ///
+    /// Requests are collected across the fleet so nodes sharing a pool
+    /// (e.g. a BP and its relays) result in a single upstream query.
+    fn start_pool_info(&mut self) {
+        let requests = self.pool_requests();
+        if requests.is_empty() || self.pool_info_task.is_some() {
+            return;
+        }
+        let chain_api = self.chain_api.clone();
+        self.pool_info_task = Some(BackgroundTask::spawn(async move {
+            chain_api.pool_info_batch(&requests).await
+        }));
+    }
+
+    /// Hand a finished pool info lookup to the nodes, returning whether one
+    /// landed
+    async fn finish_pool_info(&mut self) -> bool {
+        let Some(results) = take_finished(&mut self.pool_info_task).await else {
+            return false;
+        };
+        for node in &mut self.nodes {
+            if let Some(pool_id) = &node.pool_id {
+                let key = (node.config.network.clone(), pool_id.clone());
+                if let Some(info) = results.get(&key) {
+                    node.set_pool_info(info.clone());
+                }
+            }
+        }
+        true
+    }
+
+    /// Look up per-epoch rewards for the pools of all nodes in the background
+    fn start_rewards(&mut self) {
+        // Rewards only for a placed panel; one lookup per pool
+        let rewards_panel = self
+            .app_config
+            .layout
+            .iter()
+            .flatten()
+            .any(|p| *p == PanelKind::Rewards);
+        if !rewards_panel || self.rewards_task.is_some() {
+            return;
+        }
+        let pools: BTreeSet<(String, String)> = self.pool_requests().into_iter().collect();
+        if pools.is_empty() {
+            return;
+        }
+        // The newest epoch any node of the network has seen
+        let epochs: HashMap<String, u64> = self
+            .nodes
+            .iter()
+            .filter_map(|n| Some((n.config.network.clone(), n.metrics.epoch?)))
+            .fold(HashMap::new(), |mut epochs, (network, epoch)| {
+                let newest = epochs.entry(network).or_insert(epoch);
+                *newest = (*newest).max(epoch);
+                epochs
+            });
+        let chain_api = self.chain_api.clone();
+        let count = self.app_config.rewards_epochs;
+        self.rewards_task = Some(BackgroundTask::spawn(async move {
+            let mut results = PoolRewards::new();
+            for (network, pool_id) in pools {
+                let epoch = epochs.get(&network).copied();
+                if let Some(rewards) = chain_api
+                    .pool_history(&network, &pool_id, epoch, count)
+                    .await
+                {
+                    results.insert((network, pool_id), rewards);
+                }
+            }
+            results
+        }));
+    }
+
+    /// Hand finished rewards lookups to the nodes, returning whether any
+    /// landed
+    async fn finish_rewards(&mut self) -> bool {
+        let Some(results) = take_finished(&mut self.rewards_task).await else {
+            return false;
+        };
+        for ((network, pool_id), rewards) in results {
+            for node in self.nodes.iter_mut().filter(|n| {
+                n.config.network == network && n.pool_id.as_deref() == Some(pool_id.as_str())
+            }) {
+                node.rewards = rewards.clone();
+            }
+        }
+        true
+    }
+
+    /// Look up the newest cardano-node release in the background (cached
+    /// on disk for a day)
+    fn start_release_check(&mut self) {
+        let now = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.release_task = Some(BackgroundTask::spawn(async move {
+            crate::release::latest_release(&crate::release::cache_path(), now).await
+        }));
+    }
+
+    /// Hand a finished release lookup to the nodes, returning whether one landed
+    async fn finish_release_check(&mut self) -> bool {
+        match take_finished(&mut self.release_task).await {
+            Some(Ok(release)) => {
+                for node in &mut self.nodes {
+                    node.latest_release = Some(release.clone());
+                }
+                true
+            }
+            Some(Err(e)) => {
+                warn!("cardano-node release check failed: {:#}", e);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Look up ratified hard forks for each monitored network (`fetch =
+    /// true`) in the background
+    fn start_hard_fork(&mut self) {
+        if !self.app_config.hard_fork.fetch || self.hard_fork_task.is_some() {
+            return;
+        }
+        let networks: BTreeSet<String> = self
+            .nodes
+            .iter()
+            .map(|n| n.config.network.clone())
+            .collect();
+        let chain_api = self.chain_api.clone();
+        self.hard_fork_task = Some(BackgroundTask::spawn(async move {
+            let mut results = HashMap::new();
+            for network in networks {
+                if let Some(fetched) = chain_api.ratified_hard_fork(&network).await {
+                    results.insert(network, fetched);
+                }
+            }
+            results
+        }));
+    }
+
+    /// Hand finished hard fork lookups to the nodes, returning whether any
+    /// landed
+    ///
+    /// A fork found on chain replaces the configured schedule but keeps its
+    /// minimum node version; without one the configured schedule applies.
+    async fn finish_hard_fork(&mut self) -> bool {
+        let Some(results) = take_finished(&mut self.hard_fork_task).await else {
+            return false;
+        };
+        let config = &self.app_config.hard_fork;
+        for (network, fetched) in results {
+            let fork = match fetched {
+                Some(fork) => Some(HardFork {
+                    min_node_version: config.min_node_version.clone(),
+                    ..fork
+                }),
+                None => config.scheduled(),
+            };
+            for node in self
+                .nodes
+                .iter_mut()
+                .filter(|n| n.config.network == network)
+            {
+                node.hard_fork = fork.clone();
+            }
+        }
+        true
+    }
+
+    /// Toggle help mode
+    pub fn toggle_help(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal => AppMode::Help,
+            AppMode::Help
+            | AppMode::Peers
+            | AppMode::PeerDetail
+            | AppMode::Graphs
+            | AppMode::EpochSummary
+            | AppMode::Settings
+            | AppMode::Benchmark
+            | AppMode::AddNode
+            | AppMode::Mempool
+            | AppMode::Propagation
+            | AppMode::Incidents
+            | AppMode::Command
+            | AppMode::Focus
+            | AppMode::Setup
+            | AppMode::MetricInfo => AppMode::Normal,
+        };
+    }
+
+    /// Toggle peers view
+    pub async fn toggle_peers(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal => {
+                // Refresh peer connections when entering peers view; the
+                // last list shows until the new one lands
+                self.nodes[self.selected_node].start_peer_refresh();
+                // Reset selection
+                self.peer_list_selected = 0;
+                self.peer_list_scroll = 0;
+                AppMode::Peers
+            }
+            AppMode::Peers | AppMode::PeerDetail => AppMode::Normal,
+            AppMode::Help
+            | AppMode::Graphs
+            | AppMode::EpochSummary
+            | AppMode::Settings
+            | AppMode::Benchmark
+            | AppMode::AddNode
+            | AppMode::Mempool
+            | AppMode::Propagation
+            | AppMode::Incidents
+            | AppMode::Command
+            | AppMode::Focus
+            | AppMode::Setup
+            | AppMode::MetricInfo => AppMode::Normal,
+        };
+    }
+
+    /// Toggle graphs view mode
+    pub fn toggle_graphs(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal => AppMode::Graphs,
+            AppMode::Graphs => AppMode::Normal,
+            _ => AppMode::Normal,
+        };
+    }
+
+    /// Toggle epoch summary view
+    pub fn toggle_epoch_summary(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal => AppMode::EpochSummary,
+            _ => AppMode::Normal,
+        };
+    }
+
+    /// Toggle the incidents view
+    pub fn toggle_incidents(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal => AppMode::Incidents,
+            _ => AppMode::Normal,
+        };
+    }
+
+    /// Toggle the node comparison view
+    pub fn toggle_benchmark(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal => AppMode::Benchmark,
+            _ => AppMode::Normal,
+        };
+    }
+
+    /// Compare nodes over a longer window
+    pub fn benchmark_window_longer(&mut self) {
+        if self.benchmark_window + 1 < BENCHMARK_WINDOWS_HOURS.len() {
+            self.benchmark_window += 1;
+        }
+    }
+
+    /// Compare nodes over a shorter window
+    pub fn benchmark_window_shorter(&mut self) {
+        self.benchmark_window = self.benchmark_window.saturating_sub(1);
+    }
+
+    /// Length of the selected comparison window in hours
+    pub fn benchmark_hours(&self) -> u64 {
+        BENCHMARK_WINDOWS_HOURS[self.benchmark_window]
+    }
+
+    /// KPI summaries for all nodes over the selected window, best first
+    pub fn benchmark_ranking(&self) -> Vec<(usize, NodeBenchmark)> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let window_secs = self.benchmark_hours() * 3600;
+
+        let mut ranking: Vec<(usize, NodeBenchmark)> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (i, node.kpis.summarize(now, window_secs)))
+            .collect();
+        ranking.sort_by(|(_, a), (_, b)| a.rank_cmp(b));
+        ranking
+    }
+
+    /// Toggle the threshold settings overlay
+    pub fn toggle_settings(&mut self) {
+        if self.mode == AppMode::Normal && self.deny_when_following() {
+            return;
+        }
+        self.mode = match self.mode {
+            AppMode::Normal => {
+                self.settings_status = None;
+                AppMode::Settings
+            }
+            _ => AppMode::Normal,
+        };
+    }
+
+    /// Move the settings selection up
+    pub fn settings_up(&mut self) {
+        self.settings_selected = self.settings_selected.saturating_sub(1);
+    }
+
+    /// Move the settings selection down
+    pub fn settings_down(&mut self) {
+        if self.settings_selected + 1 < ThresholdField::ALL.len() {
+            self.settings_selected += 1;
+        }
+    }
+
+    /// Adjust the selected threshold for the current node (takes effect immediately)
+    pub fn settings_adjust(&mut self, steps: i64) {
+        let field = ThresholdField::ALL[self.settings_selected];
+        self.nodes[self.selected_node]
+            .thresholds
+            .adjust(field, steps);
+        self.settings_status = None;
+    }
+
+    /// Write the current node's thresholds back to the config file
+    pub fn save_settings(&mut self) {
+        let node = &self.nodes[self.selected_node];
+        let Some(path) = &self.app_config.config_path else {
+            self.settings_status = Some("No config file path available".to_string());
+            return;
+        };
+
+        self.settings_status = Some(
+            match crate::config::write_thresholds(path, &node.config.node_name, &node.thresholds) {
+                Ok(()) => {
+                    self.config_mtime = file_mtime(Some(path));
+                    format!("Saved to {}", path.display())
+                }
+                Err(e) => format!("Save failed: {:#}", e),
+            },
+        );
+    }
+
+    /// Toggle mempool view
+    pub async fn toggle_mempool(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal => {
+                self.nodes[self.selected_node].refresh_mempool().await;
+                self.mempool_scroll = 0;
+                AppMode::Mempool
+            }
+            _ => AppMode::Normal,
+        };
+    }
+
+    /// Toggle the block propagation ranking
+    pub fn toggle_propagation(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal => {
+                self.propagation_scroll = 0;
+                AppMode::Propagation
+            }
+            _ => AppMode::Normal,
+        };
+    }
+
+    /// Scroll the propagation ranking up
+    pub fn propagation_scroll_up(&mut self) {
+        self.propagation_scroll = self.propagation_scroll.saturating_sub(1);
+    }
+
+    /// Scroll the propagation ranking down
+    pub fn propagation_scroll_down(&mut self) {
+        let rows = self.nodes[self.selected_node].propagation.ranking().len();
+        if self.propagation_scroll + 1 < rows {
+            self.propagation_scroll += 1;
+        }
+    }
+
+    /// Scroll the mempool list up
+    pub fn mempool_scroll_up(&mut self) {
+        self.mempool_scroll = self.mempool_scroll.saturating_sub(1);
+    }
+
+    /// Scroll the mempool list down
+    pub fn mempool_scroll_down(&mut self) {
+        let tx_count = self.nodes[self.selected_node]
+            .mempool
+            .as_ref()
+            .map(|m| m.txs.len())
+            .unwrap_or(0);
+        if self.mempool_scroll + 1 < tx_count {
+            self.mempool_scroll += 1;
+        }
+    }
+
+    /// Refresh peer connections for current node in the background
+    pub fn refresh_peers(&mut self) {
+        self.nodes[self.selected_node].start_peer_refresh();
+    }
+
+    /// Apply finished background peer refreshes, returning whether any is
+    /// still running or just landed (the progress or list needs redrawing)
+    async fn finish_peer_refreshes(&mut self) -> bool {
+        let mut changed = false;
+        for i in 0..self.nodes.len() {
+            if !self.nodes[i].peer_refreshing() {
+                continue;
+            }
+            changed = true;
+            if self.nodes[i].finish_peer_refresh().await && i == self.selected_node {
+                let peers = self.nodes[i].peer_connections.clone();
+                self.start_peer_locations(i, peers);
+                // Keep the selection in range of the new list
+                let len = self.peer_list_len();
+                self.peer_list_selected = self.peer_list_selected.min(len.saturating_sub(1));
+                self.peer_list_scroll = self.peer_list_scroll.min(self.peer_list_selected);
+            }
+        }
+        changed
+    }
+
+    /// Resolve locations and networks for a node's peers, then check their
+    /// concentration
+    ///
+    /// Cached locations apply at once; uncached ones are looked up in the
+    /// background and the check waits for `finish_peer_locations`. While a
+    /// lookup is running only the cache is used.
+    fn start_peer_locations(&mut self, node: usize, peers: Vec<PeerConnection>) {
+        let ips: Vec<String> = peers.iter().map(|p| p.ip.clone()).collect();
+        let (cached, lookup) = if self.location_task.is_some() {
+            (self.geoip_service.cached(&ips), None)
+        } else {
+            self.geoip_service.lookup_batch(&ips)
+        };
+        self.record_locations(cached);
+        match lookup {
+            Some(lookup) => {
+                self.location_task = Some(BackgroundTask::spawn(async move {
+                    (node, peers, lookup.run().await)
+                }));
+            }
+            None => self.nodes[node].check_peer_concentration(&peers, &self.peer_networks),
+        }
+    }
+
+    /// Store the locations of a finished background lookup and check the
+    /// concentration of the peers it was for, returning whether one landed
+    async fn finish_peer_locations(&mut self) -> bool {
+        let Some((node, peers, answer)) = take_finished(&mut self.location_task).await else {
+            return false;
+        };
+        let locations = self.geoip_service.store(answer);
+        self.record_locations(locations);
+        // Nodes may have been removed by a config reload in the meantime
+        if let Some(node) = self.nodes.get_mut(node) {
+            node.check_peer_concentration(&peers, &self.peer_networks);
+        }
+        true
+    }
+
+    /// Remember peer locations and networks for display and grouping
+    fn record_locations(&mut self, locations: HashMap<String, GeoLocation>) {
+        for (ip, loc) in locations {
+            if let Some(network) = loc.network() {
+                self.peer_networks.insert(ip.clone(), network.to_string());
+            }
+            self.peer_locations.insert(ip, loc.short());
+        }
+    }
+
+    /// Number of rows in the peer list (connections or groups)
+    fn peer_list_len(&self) -> usize {
+        let peers = &self.nodes[self.selected_node].peer_connections;
+        match self.peer_grouping {
+            PeerGrouping::None => peers.len(),
+            grouping => crate::peers::group_peers(peers, grouping, &self.peer_networks).len(),
+        }
+    }
+
+    /// Cycle the peer list between per-connection, subnet and network views
+    pub fn cycle_peer_grouping(&mut self) {
+        self.peer_grouping = self.peer_grouping.next();
+        self.peer_list_selected = 0;
+        self.peer_list_scroll = 0;
+    }
+
+    /// Move selection up in peer list
+    pub fn peer_list_up(&mut self, _visible_rows: usize) {
+        if self.peer_list_selected > 0 {
+            self.peer_list_selected -= 1;
+            // Scroll up if selection goes above visible area
+            if self.peer_list_selected < self.peer_list_scroll {
+                self.peer_list_scroll = self.peer_list_selected;
+            }
+        }
+    }
+
+    /// Move selection down in peer list
     pub fn peer_list_down(&mut self, visible_rows: usize) {
-        let peer_count = self.nodes[self.selected_node].peer_connections.len();
+        let peer_count = self.peer_list_len();
         if peer_count > 0 && self.peer_list_selected < peer_count - 1 {
             self.peer_list_selected += 1;
             // Scroll down if selection goes below visible area
@@ -552,8 +3082,98 @@ impl App {
     /// Show details for selected peer
     pub fn show_peer_detail(&mut self) {
         let peer_count = self.nodes[self.selected_node].peer_connections.len();
-        if peer_count > 0 && self.peer_list_selected < peer_count {
+        if self.peer_grouping == PeerGrouping::None
+            && peer_count > 0
+            && self.peer_list_selected < peer_count
+        {
             self.mode = AppMode::PeerDetail;
+            self.lookup_selected_hostname();
+        }
+    }
+
+    /// Start a reverse DNS lookup for the selected peer unless it is cached
+    fn lookup_selected_hostname(&mut self) {
+        let Some(peer) = self.selected_peer() else {
+            return;
+        };
+        let cached = self
+            .peer_hostnames
+            .get(&peer.ip)
+            .is_some_and(|(looked_up, _)| looked_up.elapsed() < HOSTNAME_TTL);
+        if cached || self.hostname_pending(&peer.ip) {
+            return;
+        }
+        let (Ok(runtime), Ok(ip)) = (
+            tokio::runtime::Handle::try_current(),
+            peer.ip.parse::<std::net::IpAddr>(),
+        ) else {
+            return;
+        };
+        let task = self
+            .hostname_lookups
+            .spawn_on(crate::sockets::reverse_dns(ip), &runtime);
+        self.hostnames_pending.insert(task.id(), peer.ip);
+    }
+
+    /// Store finished reverse DNS lookups and drop expired ones, returning
+    /// whether any landed
+    ///
+    /// A lookup that panicked or was cancelled stops being pending without
+    /// an answer, so the next visit to the peer tries again.
+    fn finish_hostname_lookups(&mut self) -> bool {
+        let mut changed = false;
+        while let Some(result) = self.hostname_lookups.try_join_next_with_id() {
+            let (id, hostname) = match result {
+                Ok((id, hostname)) => (id, Some(hostname)),
+                Err(e) => {
+                    warn!("Reverse DNS lookup failed: {}", e);
+                    (e.id(), None)
+                }
+            };
+            let Some(ip) = self.hostnames_pending.remove(&id) else {
+                continue;
+            };
+            if let Some(hostname) = hostname {
+                self.peer_hostnames.insert(ip, (Instant::now(), hostname));
+            }
+            changed = true;
+        }
+        self.peer_hostnames
+            .retain(|_, (looked_up, _)| looked_up.elapsed() < HOSTNAME_TTL);
+        changed
+    }
+
+    /// Cached reverse DNS name of `ip`
+    pub fn peer_hostname(&self, ip: &str) -> Option<&str> {
+        self.peer_hostnames.get(ip)?.1.as_deref()
+    }
+
+    /// Whether the reverse DNS lookup for `ip` is still running
+    pub fn hostname_pending(&self, ip: &str) -> bool {
+        self.hostnames_pending.values().any(|pending| pending == ip)
+    }
+
+    /// Ban the selected peer's IP, or lift the ban if it is already banned
+    pub fn toggle_ban_selected_peer(&mut self) {
+        let Some(peer) = self.selected_peer() else {
+            return;
+        };
+        let result = if self.blocklist.contains(&peer.ip) {
+            self.blocklist
+                .unban(&peer.ip)
+                .map(|_| format!("Removed {} from the blocklist", peer.ip))
+        } else {
+            self.blocklist.ban(&peer.ip).map(|_| {
+                format!(
+                    "Banned {}; to block it run: {}",
+                    peer.ip,
+                    self.app_config.firewall.ban_command(&peer.ip)
+                )
+            })
+        };
+        match result {
+            Ok(message) => self.set_status(message),
+            Err(e) => self.set_status(format!("Blocklist update failed: {:#}", e)),
         }
     }
 
@@ -588,8 +3208,646 @@ impl App {
         sorted_peers.get(self.peer_list_selected).cloned()
     }
 
+    /// Reload the config file, adding, removing and updating nodes in place
+    ///
+    /// Nodes whose name and metrics endpoint are unchanged keep their state
+    /// (metrics, history, alerts); changed or new nodes start fresh.
+    pub fn reload_config(&mut self) {
+        if self.deny_when_following() {
+            return;
+        }
+        self.config_mtime = file_mtime(self.app_config.config_path.as_deref());
+        let new_config = match self.app_config.reload() {
+            Ok(config) => config,
+            Err(e) => {
+                self.set_status(format!("Config reload failed: {:#}", e));
+                return;
+            }
+        };
+
+        let selected_name = self.current_node().config.node_name.clone();
+        let mut old_nodes: Vec<Option<NodeState>> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .map(Some)
+            .collect();
+        let (mut added, mut kept) = (0, 0);
+
+        self.nodes = new_config
+            .nodes
+            .iter()
+            .map(|node_config| {
+                let existing = old_nodes
+                    .iter_mut()
+                    .find(|n| n.as_ref().is_some_and(|n| n.same_endpoint(node_config)))
+                    .and_then(Option::take);
+                match existing {
+                    Some(mut node) => {
+                        node.apply_config(node_config, &new_config);
+                        kept += 1;
+                        node
+                    }
+                    None => {
+                        added += 1;
+                        NodeState::new(node_config, &new_config)
+                    }
+                }
+            })
+            .collect();
+        let removed = old_nodes.iter().flatten().count();
+
+        self.selected_node = self
+            .nodes
+            .iter()
+            .position(|n| n.config.node_name == selected_name)
+            .unwrap_or(0);
+        self.chain_api = ChainApiProvider::new(
+            new_config.chain_api_backend.clone(),
+            new_config.chain_api_ttl,
+        );
+        self.last_chain_api_refresh = None;
+        self.pool_info_task = None;
+        self.rewards_task = None;
+        self.hard_fork_task = None;
+        self.last_reference_tip = None;
+        self.reference_task = None;
+        if new_config.reference_tip != self.app_config.reference_tip {
+            for node in &mut self.nodes {
+                node.reference_tip = None;
+            }
+        }
+        // Re-read the cached release for added nodes
+        self.last_release_check = None;
+        if new_config.blocklist_path != self.app_config.blocklist_path {
+            self.blocklist = load_blocklist(&new_config.blocklist_path);
+        }
+        if !self
+            .mqtt
+            .as_ref()
+            .is_some_and(|m| m.is_for(&new_config.mqtt))
+        {
+            self.mqtt = MqttPublisher::new(&new_config.mqtt);
+        }
+        if new_config.geoip != self.app_config.geoip {
+            self.geoip_service.set_provider(&new_config.geoip);
+            self.peer_locations.clear();
+            self.peer_networks.clear();
+        }
+        if !self
+            .notifier
+            .as_ref()
+            .is_some_and(|n| n.is_for(&new_config.notify, new_config.timezone))
+        {
+            self.notifier = Notifier::new(&new_config.notify, new_config.timezone);
+        }
+        if !self
+            .heartbeat
+            .as_ref()
+            .is_some_and(|h| h.is_for(&new_config.heartbeat))
+        {
+            self.heartbeat = Heartbeat::new(&new_config.heartbeat);
+            self.last_heartbeat = None;
+        }
+        self.app_config = new_config;
+        if let Some(interval) = self.refresh_override {
+            self.set_refresh_interval(interval);
+        }
+
+        self.set_status(format!(
+            "Config reloaded: {} kept, {} added, {} removed",
+            kept, added, removed
+        ));
+    }
+
+    /// Scrape every node every `interval`
+    fn set_refresh_interval(&mut self, interval: Duration) {
+        self.app_config.refresh_interval = interval;
+        for node in &mut self.nodes {
+            node.config.refresh_interval_secs = interval.as_secs();
+        }
+    }
+
+    /// Open the add-node form
+    pub fn open_node_form(&mut self) {
+        if self.mode == AppMode::Normal && !self.deny_when_following() {
+            self.node_form = NodeForm::default();
+            self.pending_new_node = None;
+            self.mode = AppMode::AddNode;
+        }
+    }
+
+    /// Close the add-node form without saving
+    pub fn close_node_form(&mut self) {
+        if let Some(node) = self.pending_new_node.take() {
+            self.set_status(format!("Added node '{}' (not saved)", node.name));
+        }
+        self.mode = AppMode::Normal;
+    }
+
+    /// Validate the form, then start monitoring the new node
+    pub async fn submit_node_form(&mut self) {
+        let existing: Vec<&str> = self
+            .nodes
+            .iter()
+            .map(|n| n.config.node_name.as_str())
+            .collect();
+        let input = match self.node_form.validate(&existing) {
+            Ok(input) => input,
+            Err(e) => {
+                self.node_form.error = Some(e);
+                return;
+            }
+        };
+
+        let node_config = self
+            .app_config
+            .new_node(input.name, input.host, input.port, input.role);
+        let mut node = NodeState::new(&node_config, &self.app_config);
+        node.fetch_metrics().await;
+        self.nodes.push(node);
+        self.selected_node = self.nodes.len() - 1;
+        self.app_config.nodes.push(node_config.clone());
+
+        if self.app_config.config_path.is_some() {
+            self.pending_new_node = Some(node_config);
+            self.node_form.step = FormStep::ConfirmSave;
+        } else {
+            self.set_status(format!("Added node '{}'", node_config.name));
+            self.mode = AppMode::Normal;
+        }
+    }
+
+    /// Show the first-run setup screen in place of the dashboard
+    pub fn open_setup(&mut self) {
+        self.node_form = NodeForm::default();
+        self.mode = AppMode::Setup;
+    }
+
+    /// Leave setup and keep monitoring the default endpoint
+    pub fn close_setup(&mut self) {
+        self.set_status("Setup skipped".to_string());
+        self.mode = AppMode::Normal;
+    }
+
+    /// Test the node from the setup form, then make it the only node and
+    /// write it to a new config file
+    ///
+    /// A node that doesn't answer is only saved when submitted a second time
+    /// unchanged, so a typo isn't written silently.
+    pub async fn submit_setup(&mut self) {
+        let input = match self.node_form.validate(&[]) {
+            Ok(input) => input,
+            Err(e) => {
+                self.node_form.error = Some(e);
+                return;
+            }
+        };
+
+        let node_config = self.app_config.new_node(
+            input.name.clone(),
+            input.host.clone(),
+            input.port,
+            input.role,
+        );
+        let mut node = NodeState::new(&node_config, &self.app_config);
+        node.fetch_metrics().await;
+        let confirmed = self.node_form.unreachable.as_ref() == Some(&input);
+        if !node.metrics.connected && !confirmed {
+            let error = node.error_text().unwrap_or_else(|| "no answer".to_string());
+            self.node_form.error = Some(format!("{} — Enter again to save anyway", error));
+            self.node_form.unreachable = Some(input);
+            return;
+        }
+
+        self.nodes = vec![node];
+        self.selected_node = 0;
+        self.app_config.nodes = vec![node_config.clone()];
+        match self.app_config.config_path.clone() {
+            Some(path) => match crate::config::append_node(&path, &node_config) {
+                Ok(()) => {
+                    self.config_mtime = file_mtime(Some(&path));
+                    self.set_status(format!(
+                        "Saved node '{}' to {}",
+                        node_config.name,
+                        path.display()
+                    ));
+                }
+                Err(e) => self.set_status(format!("Failed to save node: {:#}", e)),
+            },
+            None => self.set_status(format!("Monitoring '{}' (not saved)", node_config.name)),
+        }
+        self.mode = AppMode::Normal;
+    }
+
+    /// Append the node added from the form to the config file
+    pub fn save_new_node(&mut self) {
+        let (Some(node), Some(path)) = (
+            self.pending_new_node.take(),
+            self.app_config.config_path.clone(),
+        ) else {
+            self.mode = AppMode::Normal;
+            return;
+        };
+
+        match crate::config::append_node(&path, &node) {
+            Ok(()) => {
+                // Our own write shouldn't trigger a reload
+                self.config_mtime = file_mtime(Some(&path));
+                self.set_status(format!("Saved node '{}' to {}", node.name, path.display()));
+            }
+            Err(e) => self.set_status(format!("Failed to save node: {:#}", e)),
+        }
+        self.mode = AppMode::Normal;
+    }
+
+    /// Whether this app mirrors another sview (`--follow`), whose
+    /// configuration it can't change; says so in the footer
+    fn deny_when_following(&mut self) -> bool {
+        let Some(leader) = &self.app_config.follow else {
+            return false;
+        };
+        self.set_status(format!("Read-only: following the sview at {}", leader));
+        true
+    }
+
+    /// Show a transient message in the footer
+    pub fn set_status(&mut self, message: String) {
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// Current footer status message, if it hasn't expired
+    pub fn status_message(&self) -> Option<&str> {
+        self.status_message
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < STATUS_MESSAGE_DURATION)
+            .map(|(msg, _)| msg.as_str())
+    }
+
     /// Cycle to the next color theme
     pub fn cycle_theme(&mut self) {
         self.theme = self.theme.next();
     }
+
+    /// Expand a dashboard panel, or return to the dashboard
+    pub fn toggle_focus(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal => {
+                self.focus_scroll = 0;
+                AppMode::Focus
+            }
+            _ => AppMode::Normal,
+        };
+    }
+
+    /// Move the dashboard row selection by `step` among `labels` (the rows
+    /// on screen, top to bottom); the first step selects the first or last row
+    pub fn select_metric(&mut self, labels: &[String], step: isize) {
+        let current = self
+            .selected_metric
+            .as_ref()
+            .and_then(|selected| labels.iter().position(|label| label == selected));
+        let index = match current {
+            Some(i) => (i as isize + step).clamp(0, labels.len() as isize - 1) as usize,
+            None if step < 0 => labels.len().saturating_sub(1),
+            None => 0,
+        };
+        self.selected_metric = labels.get(index).cloned();
+    }
+
+    /// Clear the dashboard row selection
+    pub fn clear_metric_selection(&mut self) {
+        self.selected_metric = None;
+        if self.mode == AppMode::MetricInfo {
+            self.mode = AppMode::Normal;
+        }
+    }
+
+    /// Open or close the explanation of the selected row
+    pub fn toggle_metric_info(&mut self) {
+        self.mode = match self.mode {
+            AppMode::Normal if self.selected_metric.is_some() => AppMode::MetricInfo,
+            _ => AppMode::Normal,
+        };
+    }
+
+    /// Panel shown in the focus view
+    pub fn focused_panel_kind(&self) -> PanelKind {
+        let panels: Vec<PanelKind> = self.app_config.layout.iter().flatten().copied().collect();
+        panels[self.focused_panel % panels.len()]
+    }
+
+    /// Focus the next (or previous, for negative `step`) panel in the layout
+    pub fn focus_cycle(&mut self, step: isize) {
+        let count = self.app_config.layout.iter().flatten().count() as isize;
+        self.focused_panel = (self.focused_panel as isize + step).rem_euclid(count) as usize;
+        self.focus_scroll = 0;
+    }
+
+    /// Scroll the focus view's metric list
+    pub fn focus_scroll_up(&mut self) {
+        self.focus_scroll = self.focus_scroll.saturating_sub(1);
+    }
+
+    /// Scroll the focus view's metric list
+    pub fn focus_scroll_down(&mut self) {
+        if self.focus_scroll + 1 < self.focused_raw_metrics().len() {
+            self.focus_scroll += 1;
+        }
+    }
+
+    /// Raw metrics of the current node belonging to the focused panel,
+    /// sorted by name
+    pub fn focused_raw_metrics(&self) -> Vec<(&str, f64)> {
+        let panel = self.focused_panel_kind();
+        let mut metrics: Vec<(&str, f64)> = self
+            .current_node()
+            .metrics
+            .raw
+            .iter()
+            .filter(|(name, _)| crate::metrics::metric_panel(name) == panel)
+            .map(|(name, value)| (name.as_ref(), *value))
+            .collect();
+        metrics.sort_by(|a, b| a.0.cmp(b.0));
+        metrics
+    }
+
+    /// Freeze or resume display updates
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    /// Copy the selected peer's IP (peer views) or the current node's key
+    /// values to the clipboard
+    pub fn copy_to_clipboard(&mut self) {
+        let (text, what) = match self.mode {
+            AppMode::Peers | AppMode::PeerDetail => match self.selected_peer() {
+                Some(peer) => (peer.ip.clone(), peer.ip),
+                None => {
+                    self.set_status("No peer selected".to_string());
+                    return;
+                }
+            },
+            _ => (
+                self.current_node().summary_text(),
+                format!("{} values", self.current_node().config.node_name),
+            ),
+        };
+        match crate::share::copy_to_clipboard(&text) {
+            Ok(()) => self.set_status(format!("Copied {} to clipboard", what)),
+            Err(e) => self.set_status(format!("Copy failed: {:#}", e)),
+        }
+    }
+
+    /// Write a rendered frame to the screenshots directory
+    pub fn save_screenshot(&mut self, buffer: &ratatui::buffer::Buffer) {
+        self.screenshot_requested = false;
+        let node_name = self.current_node().config.node_name.clone();
+        match crate::share::save_screenshot(buffer, &node_name) {
+            Ok(path) => self.set_status(format!("Screenshot saved to {}", path.display())),
+            Err(e) => self.set_status(format!("Screenshot failed: {:#}", e)),
+        }
+    }
+
+    /// Open the `:` command line
+    pub fn open_command_line(&mut self) {
+        if self.mode == AppMode::Normal {
+            self.command_input.clear();
+            self.mode = AppMode::Command;
+        }
+    }
+
+    /// Close the command line without running it
+    pub fn close_command_line(&mut self) {
+        self.command_input.clear();
+        self.mode = AppMode::Normal;
+    }
+
+    /// Delete the last character, closing the command line when empty
+    pub fn command_backspace(&mut self) {
+        if self.command_input.pop().is_none() {
+            self.close_command_line();
+        }
+    }
+
+    /// Run the typed command, returning true when it asks to quit
+    pub fn run_command(&mut self) -> bool {
+        let input = std::mem::take(&mut self.command_input);
+        self.mode = AppMode::Normal;
+        let command = match crate::command::parse(&input) {
+            Ok(command) => command,
+            Err(e) => {
+                self.set_status(e);
+                return false;
+            }
+        };
+
+        match command {
+            Command::Node(name) => {
+                let index = match name.parse::<usize>() {
+                    Ok(n) if n >= 1 && n <= self.nodes.len() => Some(n - 1),
+                    _ => self
+                        .nodes
+                        .iter()
+                        .position(|n| n.config.node_name.eq_ignore_ascii_case(&name)),
+                };
+                match index {
+                    Some(index) => self.select_node(index),
+                    None => self.set_status(format!("No node named '{}'", name)),
+                }
+            }
+            Command::Theme(theme) => self.theme = theme,
+            Command::Refresh(interval) => {
+                self.refresh_override = Some(interval);
+                self.set_refresh_interval(interval);
+                self.set_status(format!("Refreshing every {}s", interval.as_secs()));
+            }
+            Command::Export(path) => {
+                let node = self.current_node();
+                let message = match node
+                    .storage()
+                    .export_to_csv(&path, self.app_config.timezone)
+                {
+                    Ok(count) => format!("Exported {} snapshots to {}", count, path.display()),
+                    Err(e) => format!("Export failed: {:#}", e),
+                };
+                self.set_status(message);
+            }
+            Command::Silence(Some(duration)) => {
+                self.alerts_silenced_until = Some(Instant::now() + duration);
+                self.set_status(format!(
+                    "Alerts silenced for {} min",
+                    duration.as_secs().div_ceil(60)
+                ));
+            }
+            Command::Silence(None) => {
+                self.alerts_silenced_until = None;
+                self.set_status("Alerts unsilenced".to_string());
+            }
+            Command::Quit => return true,
+        }
+        false
+    }
+
+    /// Remaining time alerts are hidden from the header (`:silence`)
+    pub fn alerts_silenced_for(&self) -> Option<Duration> {
+        self.alerts_silenced_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+    }
+
+    /// Store the outages still open on exit, so reports don't lose them
+    pub fn close_outages(&mut self) {
+        let now = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        for node in &mut self.nodes {
+            node.end_outage(now);
+        }
+    }
+
+    /// UI state to save on exit
+    pub fn session(&self) -> Session {
+        let now = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut collapsed_groups: Vec<String> = self.collapsed_groups.iter().cloned().collect();
+        collapsed_groups.sort();
+        Session {
+            node: Some(self.current_node().config.node_name.clone()),
+            theme: self.theme,
+            mode: crate::session::restored_mode(self.mode),
+            focused_panel: self.focused_panel,
+            peer_grouping: self.peer_grouping,
+            benchmark_window: self.benchmark_window,
+            collapsed_groups,
+            alerts_silenced_until: self.alerts_silenced_for().map(|left| now + left.as_secs()),
+        }
+    }
+
+    /// Restore the UI state saved by an earlier run; a node that is no longer
+    /// configured leaves the first one selected
+    ///
+    /// The saved view is reopened separately with `open_saved_view`, once
+    /// there are metrics to show.
+    pub fn restore_session(&mut self, session: Session) {
+        let now = SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if let Some(index) = session
+            .node
+            .and_then(|name| self.nodes.iter().position(|n| n.config.node_name == name))
+        {
+            self.selected_node = index;
+        }
+        self.theme = session.theme;
+        self.focused_panel = session.focused_panel;
+        self.peer_grouping = session.peer_grouping;
+        self.benchmark_window = session
+            .benchmark_window
+            .min(BENCHMARK_WINDOWS_HOURS.len() - 1);
+        self.collapsed_groups = session.collapsed_groups.into_iter().collect();
+        self.alerts_silenced_until = session
+            .alerts_silenced_until
+            .filter(|&until| until > now)
+            .map(|until| Instant::now() + Duration::from_secs(until - now));
+    }
+
+    /// Reopen the view that was open when the session was saved
+    ///
+    /// Views open the way their keys do, so they load what they show.
+    pub async fn open_saved_view(&mut self, mode: AppMode) {
+        match crate::session::restored_mode(mode) {
+            AppMode::Peers => self.toggle_peers().await,
+            AppMode::Mempool => self.toggle_mempool().await,
+            AppMode::Propagation => self.toggle_propagation(),
+            AppMode::Focus => self.toggle_focus(),
+            mode => self.mode = mode,
+        }
+    }
+}
+
+/// Prepare a node's SSH tunnel (if configured) and metrics client, keeping
+/// any error for display
+fn connect_metrics(config: &Config) -> (Option<SshTunnel>, Result<MetricsClient, String>) {
+    if let Some(profile) = crate::demo::profile(&config.prom_host) {
+        let client = MetricsClient::demo(profile).with_raw_filter(config.raw_metrics.clone());
+        return (None, Ok(client));
+    }
+    if let Some(leader) = crate::follow::leader(&config.prom_host) {
+        let client = MetricsClient::follow(leader, &config.node_name, config.prom_timeout())
+            .map_err(|e| format!("{:#}", e));
+        return (None, client);
+    }
+    let tunnel = match &config.endpoint.ssh_tunnel {
+        Some(tunnel_config) => {
+            match SshTunnel::new(tunnel_config, &config.prom_host, config.prom_port) {
+                Ok(tunnel) => Some(tunnel),
+                Err(e) => return (None, Err(format!("{:#}", e))),
+            }
+        }
+        None => None,
+    };
+    let client = metrics_client(config, tunnel.as_ref());
+    (tunnel, client)
+}
+
+/// A node's metrics client, through its SSH tunnel if it has one
+fn metrics_client(config: &Config, tunnel: Option<&SshTunnel>) -> Result<MetricsClient, String> {
+    match tunnel {
+        Some(tunnel) => tunnel.metrics_client(config.prom_timeout(), &config.endpoint),
+        None => MetricsClient::new(
+            config.metrics_url(),
+            config.prom_timeout(),
+            &config.endpoint,
+            unix_socket_path(&config.prom_host),
+        ),
+    }
+    .map(|client| client.with_raw_filter(config.raw_metrics.clone()))
+    .map_err(|e| format!("{:#}", e))
+}
+
+/// Peers and host interface counters, from the agent if one is given,
+/// else from this machine
+async fn sample_host(agent: Option<&AgentClient>, filter: &PeerFilter) -> HostSample {
+    let Some(agent) = agent else {
+        // Socket inspection runs `ss`/`lsof`, which blocks
+        let filter = filter.clone();
+        return tokio::task::spawn_blocking(move || AgentSnapshot::sample(&filter))
+            .await
+            .map_err(|e| e.to_string());
+    };
+    agent
+        .snapshot()
+        .await
+        .map(|mut snapshot| {
+            // The agent only knows the metrics port
+            filter.retain(&mut snapshot.peers);
+            snapshot
+        })
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Create the client for a node's agent, if one is configured
+fn connect_agent(agent: Option<&AgentConfig>, config: &Config) -> Option<AgentClient> {
+    match AgentClient::new(agent?, config.prom_timeout()) {
+        Ok(client) => Some(client),
+        Err(e) => {
+            warn!("Agent for '{}' disabled: {:#}", config.node_name, e);
+            None
+        }
+    }
+}
+
+/// Modification time of a file, if it exists
+fn file_mtime(path: Option<&Path>) -> Option<SystemTime> {
+    std::fs::metadata(path?).ok()?.modified().ok()
+}
+
+/// Load the peer blocklist, starting empty if it can't be read
+fn load_blocklist(path: &Path) -> Blocklist {
+    Blocklist::load(path).unwrap_or_else(|e| {
+        warn!("{:#}", e);
+        Blocklist::empty(path)
+    })
 }
@@ -0,0 +1,321 @@
+//! Node comparison over a time window
+//!
+//! Each node folds its scrape-derived KPIs (tip age, block propagation CDFs,
+//! peer counts, restarts) into per-minute buckets covering the last 24 hours,
+//! and keeps the outages (runs of failed scrapes) of the same period.
+//! Summaries over any window up to that can then be ranked side by side to
+//! spot underperforming relays.
+
+use crate::metrics::NodeMetrics;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+
+/// How much history each node keeps for comparisons (24 hours)
+pub const KPI_WINDOW_SECS: u64 = 24 * 3600;
+
+/// Running mean of optional readings
+#[derive(Debug, Clone, Copy, Default)]
+struct Mean {
+    sum: f64,
+    count: u32,
+}
+
+impl Mean {
+    fn add(&mut self, value: Option<f64>) {
+        if let Some(v) = value {
+            self.sum += v;
+            self.count += 1;
+        }
+    }
+
+    fn merge(&mut self, other: &Mean) {
+        self.sum += other.sum;
+        self.count += other.count;
+    }
+
+    fn value(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+/// KPI readings folded into one minute
+#[derive(Debug, Clone, Default)]
+struct MinuteKpis {
+    minute: u64,
+    tip_age: Mean,
+    peers: Mean,
+    min_peers: Option<u64>,
+    cdf_1s: Mean,
+    cdf_3s: Mean,
+    cdf_5s: Mean,
+    restarts: u32,
+}
+
+/// Rolling per-minute KPI history for one node
+#[derive(Debug, Clone, Default)]
+pub struct KpiWindow {
+    /// Buckets, oldest first
+    minutes: VecDeque<MinuteKpis>,
+    /// Uptime at the previous sample (to detect restarts)
+    last_uptime: Option<f64>,
+    /// Ended outages as (start, end) unix timestamps, oldest first
+    outages: VecDeque<(u64, u64)>,
+    /// Start of the ongoing outage
+    down_since: Option<u64>,
+    /// Time of the last successful scrape
+    last_success: Option<u64>,
+}
+
+/// KPI summary for one node over a window
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeBenchmark {
+    /// Average seconds since the last block, sampled at every scrape
+    pub avg_tip_age: Option<f64>,
+    /// Average fraction of blocks fetched within 1s / 3s / 5s
+    pub cdf_1s: Option<f64>,
+    pub cdf_3s: Option<f64>,
+    pub cdf_5s: Option<f64>,
+    /// Average and minimum connected peers
+    pub avg_peers: Option<f64>,
+    pub min_peers: Option<u64>,
+    /// Node restarts detected in the window
+    pub restarts: u32,
+    /// Seconds the node couldn't be scraped in the window
+    pub downtime_secs: u64,
+    /// Outages overlapping the window
+    pub outages: u32,
+    /// Minutes with at least one sample
+    pub minutes_covered: usize,
+}
+
+impl KpiWindow {
+    /// Record one scrape's KPIs at a unix timestamp
+    pub fn record(&mut self, metrics: &NodeMetrics, tip_age_secs: Option<u64>, now: u64) {
+        let minute = now - now % 60;
+        if self.minutes.back().is_none_or(|m| m.minute < minute) {
+            self.minutes.push_back(MinuteKpis {
+                minute,
+                ..Default::default()
+            });
+        }
+        let Some(bucket) = self.minutes.back_mut() else {
+            return;
+        };
+
+        bucket.tip_age.add(tip_age_secs.map(|s| s as f64));
+        bucket.peers.add(metrics.peers_connected.map(|p| p as f64));
+        if let Some(peers) = metrics.peers_connected {
+            bucket.min_peers = Some(bucket.min_peers.map_or(peers, |m| m.min(peers)));
+        }
+        bucket.cdf_1s.add(metrics.block_delay_cdf_1s);
+        bucket.cdf_3s.add(metrics.block_delay_cdf_3s);
+        bucket.cdf_5s.add(metrics.block_delay_cdf_5s);
+
+        // A drop in uptime means the node restarted
+        if let (Some(prev), Some(cur)) = (self.last_uptime, metrics.uptime_seconds) {
+            if cur < prev {
+                bucket.restarts += 1;
+            }
+        }
+        self.last_uptime = metrics.uptime_seconds.or(self.last_uptime);
+
+        let cutoff = now.saturating_sub(KPI_WINDOW_SECS);
+        while self.minutes.front().is_some_and(|m| m.minute < cutoff) {
+            self.minutes.pop_front();
+        }
+    }
+
+    /// Note a failed scrape (starts an outage if none is ongoing)
+    ///
+    /// The node was last seen up at the previous successful scrape, so the
+    /// outage starts there rather than a refresh interval later.
+    pub fn scrape_failed(&mut self, now: u64) {
+        let start = self.last_success.unwrap_or(now);
+        self.down_since.get_or_insert(start);
+    }
+
+    /// Note a successful scrape, returning the length of the outage it ends
+    pub fn scrape_succeeded(&mut self, now: u64) -> Option<u64> {
+        self.last_success = Some(now);
+        let start = self.down_since.take()?;
+        self.outages.push_back((start, now));
+        let cutoff = now.saturating_sub(KPI_WINDOW_SECS);
+        while self.outages.front().is_some_and(|(_, end)| *end < cutoff) {
+            self.outages.pop_front();
+        }
+        Some(now.saturating_sub(start))
+    }
+
+    /// Seconds since the ongoing outage started
+    pub fn down_for(&self, now: u64) -> Option<u64> {
+        self.down_since.map(|start| now.saturating_sub(start))
+    }
+
+    /// Summarize the last `window_secs` seconds
+    pub fn summarize(&self, now: u64, window_secs: u64) -> NodeBenchmark {
+        let cutoff = now.saturating_sub(window_secs);
+        let mut tip_age = Mean::default();
+        let mut peers = Mean::default();
+        let mut cdf_1s = Mean::default();
+        let mut cdf_3s = Mean::default();
+        let mut cdf_5s = Mean::default();
+        let mut summary = NodeBenchmark::default();
+
+        for bucket in self.minutes.iter().filter(|m| m.minute >= cutoff) {
+            tip_age.merge(&bucket.tip_age);
+            peers.merge(&bucket.peers);
+            cdf_1s.merge(&bucket.cdf_1s);
+            cdf_3s.merge(&bucket.cdf_3s);
+            cdf_5s.merge(&bucket.cdf_5s);
+            summary.min_peers = match (summary.min_peers, bucket.min_peers) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            summary.restarts += bucket.restarts;
+            summary.minutes_covered += 1;
+        }
+
+        for (start, end) in self
+            .outages
+            .iter()
+            .copied()
+            .chain(self.down_since.map(|start| (start, now)))
+            .filter(|(_, end)| *end >= cutoff)
+        {
+            summary.downtime_secs += end.saturating_sub(start.max(cutoff));
+            summary.outages += 1;
+        }
+
+        summary.avg_tip_age = tip_age.value();
+        summary.avg_peers = peers.value();
+        summary.cdf_1s = cdf_1s.value();
+        summary.cdf_3s = cdf_3s.value();
+        summary.cdf_5s = cdf_5s.value();
+        summary
+    }
+}
+
+impl NodeBenchmark {
+    /// Order two nodes best-first: faster block propagation (3s CDF), then
+    /// fresher tip, fewer restarts and more peers. Missing data ranks last.
+    pub fn rank_cmp(&self, other: &Self) -> Ordering {
+        higher_first(self.cdf_3s, other.cdf_3s)
+            .then_with(|| lower_first(self.avg_tip_age, other.avg_tip_age))
+            .then_with(|| self.restarts.cmp(&other.restarts))
+            .then_with(|| higher_first(self.avg_peers, other.avg_peers))
+    }
+}
+
+/// Compare so that larger values come first and `None` comes last
+fn higher_first(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => b.total_cmp(&a),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Compare so that smaller values come first and `None` comes last
+fn lower_first(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        _ => higher_first(a, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(peers: u64, cdf_3s: f64, uptime: f64) -> NodeMetrics {
+        NodeMetrics {
+            peers_connected: Some(peers),
+            block_delay_cdf_3s: Some(cdf_3s),
+            uptime_seconds: Some(uptime),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_summarize_window() {
+        let mut window = KpiWindow::default();
+        let start = 1_700_000_000 - 1_700_000_000 % 60;
+        // Two hours ago: poor propagation, outside a 1h window
+        window.record(&sample(2, 0.5, 100.0), Some(90), start);
+        window.record(&sample(10, 0.9, 7300.0), Some(10), start + 7200);
+        window.record(&sample(6, 0.7, 30.0), Some(20), start + 7230);
+
+        let last_hour = window.summarize(start + 7230, 3600);
+        assert_eq!(last_hour.minutes_covered, 1);
+        assert_eq!(last_hour.avg_tip_age, Some(15.0));
+        assert_eq!(last_hour.avg_peers, Some(8.0));
+        assert_eq!(last_hour.min_peers, Some(6));
+        assert!((last_hour.cdf_3s.unwrap() - 0.8).abs() < 1e-9);
+        assert_eq!(last_hour.restarts, 1);
+
+        let all = window.summarize(start + 7230, KPI_WINDOW_SECS);
+        assert_eq!(all.minutes_covered, 2);
+        assert_eq!(all.min_peers, Some(2));
+    }
+
+    #[test]
+    fn test_old_minutes_expire() {
+        let mut window = KpiWindow::default();
+        window.record(&sample(5, 0.9, 10.0), Some(5), 1000);
+        window.record(&sample(5, 0.9, 20.0), Some(5), 1000 + KPI_WINDOW_SECS + 120);
+        assert_eq!(window.minutes.len(), 1);
+    }
+
+    #[test]
+    fn test_downtime() {
+        let mut window = KpiWindow::default();
+        let now = 1_700_000_000;
+        window.scrape_failed(now - 7200);
+        assert_eq!(window.scrape_succeeded(now - 7000), Some(200));
+        assert_eq!(window.scrape_succeeded(now - 6990), None);
+        assert_eq!(window.scrape_succeeded(now - 102), None);
+        // The outage starts at the last successful scrape
+        window.scrape_failed(now - 100);
+        window.scrape_failed(now - 98);
+        assert_eq!(window.down_for(now), Some(102));
+
+        let last_hour = window.summarize(now, 3600);
+        assert_eq!((last_hour.downtime_secs, last_hour.outages), (102, 1));
+        let day = window.summarize(now, KPI_WINDOW_SECS);
+        assert_eq!((day.downtime_secs, day.outages), (302, 2));
+        // An outage straddling the window start only counts inside it
+        let partial = window.summarize(now, 7100);
+        assert_eq!(partial.downtime_secs, 202);
+    }
+
+    #[test]
+    fn test_rank_order() {
+        let fast = NodeBenchmark {
+            cdf_3s: Some(0.95),
+            avg_tip_age: Some(30.0),
+            ..Default::default()
+        };
+        let slow = NodeBenchmark {
+            cdf_3s: Some(0.80),
+            avg_tip_age: Some(10.0),
+            ..Default::default()
+        };
+        let unknown = NodeBenchmark::default();
+
+        let mut nodes = [unknown.clone(), slow.clone(), fast.clone()];
+        nodes.sort_by(NodeBenchmark::rank_cmp);
+        assert_eq!(nodes, [fast, slow, unknown]);
+
+        // Same propagation: fresher tip wins
+        let a = NodeBenchmark {
+            avg_tip_age: Some(12.0),
+            ..Default::default()
+        };
+        let b = NodeBenchmark {
+            avg_tip_age: Some(40.0),
+            ..Default::default()
+        };
+        assert_eq!(a.rank_cmp(&b), Ordering::Less);
+    }
+}
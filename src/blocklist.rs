@@ -0,0 +1,178 @@
+//! Peer blocklist
+//!
+//! Operators dealing with abusive peers can ban an IP from the peer detail
+//! view. sview only records the IP in a plain-text blocklist file (one IP per
+//! line, `#` comments allowed) and shows the matching firewall command; it
+//! never changes firewall rules itself. Banned IPs are highlighted in the
+//! peer list if they reconnect.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Firewall the suggested ban command is written for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Firewall {
+    #[default]
+    Iptables,
+    Nft,
+}
+
+impl Firewall {
+    /// Command that drops traffic from `ip`
+    pub fn ban_command(&self, ip: &str) -> String {
+        let v6 = ip.contains(':');
+        match (self, v6) {
+            (Firewall::Iptables, false) => format!("sudo iptables -I INPUT -s {} -j DROP", ip),
+            (Firewall::Iptables, true) => format!("sudo ip6tables -I INPUT -s {} -j DROP", ip),
+            (Firewall::Nft, false) => {
+                format!("sudo nft add rule inet filter input ip saddr {} drop", ip)
+            }
+            (Firewall::Nft, true) => {
+                format!("sudo nft add rule inet filter input ip6 saddr {} drop", ip)
+            }
+        }
+    }
+}
+
+/// Banned IPs, backed by a blocklist file
+#[derive(Debug, Clone)]
+pub struct Blocklist {
+    path: PathBuf,
+    ips: HashSet<String>,
+}
+
+impl Blocklist {
+    /// Load the blocklist (a missing file is an empty list)
+    pub fn load(path: &Path) -> Result<Self> {
+        let ips = match std::fs::read_to_string(path) {
+            Ok(content) => parse_blocklist(&content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read blocklist {}", path.display()))
+            }
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            ips,
+        })
+    }
+
+    /// Empty blocklist for `path` (used when the file can't be read)
+    pub fn empty(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            ips: HashSet::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn contains(&self, ip: &str) -> bool {
+        self.ips.contains(ip)
+    }
+
+    /// Append `ip` to the blocklist file
+    pub fn ban(&mut self, ip: &str) -> Result<()> {
+        if self.contains(ip) {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open blocklist {}", self.path.display()))?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        writeln!(file, "{} # banned by sview at {}", ip, now)?;
+        self.ips.insert(ip.to_string());
+        Ok(())
+    }
+
+    /// Remove `ip` from the blocklist file, keeping other lines intact
+    pub fn unban(&mut self, ip: &str) -> Result<()> {
+        if !self.ips.remove(ip) {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read blocklist {}", self.path.display()))?;
+        let kept: String = content
+            .lines()
+            .filter(|line| entry_ip(line) != Some(ip))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        std::fs::write(&self.path, kept)
+            .with_context(|| format!("Failed to write blocklist {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+/// IP on a blocklist line, ignoring comments and blank lines
+fn entry_ip(line: &str) -> Option<&str> {
+    line.split('#').next()?.split_whitespace().next()
+}
+
+fn parse_blocklist(content: &str) -> HashSet<String> {
+    content
+        .lines()
+        .filter_map(entry_ip)
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_ban_and_unban() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("blocklist.txt");
+        std::fs::write(&path, "# manual entries\n198.51.100.7\n").unwrap();
+
+        let mut list = Blocklist::load(&path).unwrap();
+        assert!(list.contains("198.51.100.7"));
+
+        list.ban("203.0.113.9").unwrap();
+        list.ban("203.0.113.9").unwrap();
+        let reloaded = Blocklist::load(&path).unwrap();
+        assert!(reloaded.contains("203.0.113.9"));
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.matches("203.0.113.9").count(), 1);
+
+        list.unban("198.51.100.7").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# manual entries\n"));
+        assert!(!content.contains("198.51.100.7"));
+        assert!(!Blocklist::load(&path).unwrap().contains("198.51.100.7"));
+
+        assert!(!Blocklist::load(&dir.path().join("missing.txt"))
+            .unwrap()
+            .contains("203.0.113.9"));
+    }
+
+    #[test]
+    fn test_ban_command() {
+        assert_eq!(
+            Firewall::Iptables.ban_command("203.0.113.9"),
+            "sudo iptables -I INPUT -s 203.0.113.9 -j DROP"
+        );
+        assert_eq!(
+            Firewall::Nft.ban_command("2001:db8::1"),
+            "sudo nft add rule inet filter input ip6 saddr 2001:db8::1 drop"
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Minimal CBOR encoding/decoding for node-to-client mini-protocols
+//!
+//! Only what sview needs to talk to a local cardano-node: a generic value
+//! decoder that reports incomplete input (so callers can wait for more mux
+//! segments) and a few encoding helpers for building protocol messages.
+
+/// CBOR decoding errors
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CborError {
+    /// Input ended before the item was complete
+    Incomplete,
+    /// Input is not well-formed CBOR
+    Invalid(&'static str),
+}
+
+impl std::fmt::Display for CborError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CborError::Incomplete => write!(f, "incomplete CBOR input"),
+            CborError::Invalid(reason) => write!(f, "invalid CBOR: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for CborError {}
+
+/// A decoded CBOR data item
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Uint(u64),
+    /// Negative integer, stored as the encoded argument (value is -1 - n)
+    Nint(u64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    Tag(u64, Box<Value>),
+    Bool(bool),
+    Null,
+    Undefined,
+    Float(f64),
+    Simple(u8),
+}
+
+impl Value {
+    /// Get as unsigned integer
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Uint(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Get as array items
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Get as byte string
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Look up a map entry by unsigned integer key
+    pub fn map_get(&self, key: u64) -> Option<&Value> {
+        match self {
+            Value::Map(entries) => entries
+                .iter()
+                .find(|(k, _)| k.as_u64() == Some(key))
+                .map(|(_, v)| v),
+            _ => None,
+        }
+    }
+}
+
+/// Streaming decoder over a byte slice
+pub struct Decoder<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+/// Nesting limit to guard against malicious/corrupt input
+const MAX_DEPTH: usize = 64;
+
+/// Marker returned by `header` for indefinite-length items
+const INDEFINITE: u8 = 31;
+
+impl<'a> Decoder<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Current byte offset into the input
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    fn byte(&mut self) -> Result<u8, CborError> {
+        let b = *self.buf.get(self.pos).ok_or(CborError::Incomplete)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn take(&mut self, n: u64) -> Result<&'a [u8], CborError> {
+        let n = usize::try_from(n).map_err(|_| CborError::Invalid("length overflow"))?;
+        let end = self.pos.checked_add(n).ok_or(CborError::Incomplete)?;
+        let slice = self.buf.get(self.pos..end).ok_or(CborError::Incomplete)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn uint_arg(&mut self, info: u8) -> Result<u64, CborError> {
+        match info {
+            0..=23 => Ok(info as u64),
+            24 => Ok(self.byte()? as u64),
+            25 => Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()) as u64),
+            26 => Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()) as u64),
+            27 => Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap())),
+            _ => Err(CborError::Invalid("reserved additional info")),
+        }
+    }
+
+    /// Read an item header, returning (major type, additional info, argument)
+    fn header(&mut self) -> Result<(u8, u8, u64), CborError> {
+        let initial = self.byte()?;
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+        if info == INDEFINITE || major == 7 {
+            return Ok((major, info, 0));
+        }
+        Ok((major, info, self.uint_arg(info)?))
+    }
+
+    /// Consume a break marker if it is next
+    fn at_break(&mut self) -> Result<bool, CborError> {
+        match self.buf.get(self.pos) {
+            Some(0xff) => {
+                self.pos += 1;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => Err(CborError::Incomplete),
+        }
+    }
+
+    /// Read an array header, returning the length (None if indefinite)
+    pub fn array(&mut self) -> Result<Option<u64>, CborError> {
+        match self.header()? {
+            (4, INDEFINITE, _) => Ok(None),
+            (4, _, len) => Ok(Some(len)),
+            _ => Err(CborError::Invalid("expected array")),
+        }
+    }
+
+    /// Decode the next complete data item
+    pub fn value(&mut self) -> Result<Value, CborError> {
+        self.value_at_depth(0)
+    }
+
+    fn value_at_depth(&mut self, depth: usize) -> Result<Value, CborError> {
+        if depth > MAX_DEPTH {
+            return Err(CborError::Invalid("nesting too deep"));
+        }
+
+        let (major, info, arg) = self.header()?;
+        match major {
+            0 => Ok(Value::Uint(arg)),
+            1 => Ok(Value::Nint(arg)),
+            2 | 3 => {
+                let bytes = if info == INDEFINITE {
+                    let mut joined = Vec::new();
+                    while !self.at_break()? {
+                        let (chunk_major, chunk_info, len) = self.header()?;
+                        if chunk_major != major || chunk_info == INDEFINITE {
+                            return Err(CborError::Invalid("bad string chunk"));
+                        }
+                        joined.extend_from_slice(self.take(len)?);
+                    }
+                    joined
+                } else {
+                    self.take(arg)?.to_vec()
+                };
+                if major == 2 {
+                    Ok(Value::Bytes(bytes))
+                } else {
+                    String::from_utf8(bytes)
+                        .map(Value::Text)
+                        .map_err(|_| CborError::Invalid("text is not UTF-8"))
+                }
+            }
+            4 => {
+                let mut items = Vec::new();
+                if info == INDEFINITE {
+                    while !self.at_break()? {
+                        items.push(self.value_at_depth(depth + 1)?);
+                    }
+                } else {
+                    for _ in 0..arg {
+                        items.push(self.value_at_depth(depth + 1)?);
+                    }
+                }
+                Ok(Value::Array(items))
+            }
+            5 => {
+                let mut entries = Vec::new();
+                if info == INDEFINITE {
+                    while !self.at_break()? {
+                        let k = self.value_at_depth(depth + 1)?;
+                        let v = self.value_at_depth(depth + 1)?;
+                        entries.push((k, v));
+                    }
+                } else {
+                    for _ in 0..arg {
+                        let k = self.value_at_depth(depth + 1)?;
+                        let v = self.value_at_depth(depth + 1)?;
+                        entries.push((k, v));
+                    }
+                }
+                Ok(Value::Map(entries))
+            }
+            6 => {
+                if info == INDEFINITE {
+                    return Err(CborError::Invalid("indefinite tag"));
+                }
+                let inner = self.value_at_depth(depth + 1)?;
+                Ok(Value::Tag(arg, Box::new(inner)))
+            }
+            _ => match info {
+                20 => Ok(Value::Bool(false)),
+                21 => Ok(Value::Bool(true)),
+                22 => Ok(Value::Null),
+                23 => Ok(Value::Undefined),
+                0..=19 => Ok(Value::Simple(info)),
+                24 => Ok(Value::Simple(self.byte()?)),
+                25 => {
+                    let bits = u16::from_be_bytes(self.take(2)?.try_into().unwrap());
+                    Ok(Value::Float(half_to_f64(bits)))
+                }
+                26 => {
+                    let bits = u32::from_be_bytes(self.take(4)?.try_into().unwrap());
+                    Ok(Value::Float(f32::from_bits(bits) as f64))
+                }
+                27 => {
+                    let bits = u64::from_be_bytes(self.take(8)?.try_into().unwrap());
+                    Ok(Value::Float(f64::from_bits(bits)))
+                }
+                _ => Err(CborError::Invalid("unexpected break")),
+            },
+        }
+    }
+}
+
+/// Decode one item from the start of `buf`, returning it with the bytes consumed
+pub fn decode(buf: &[u8]) -> Result<(Value, usize), CborError> {
+    let mut decoder = Decoder::new(buf);
+    let value = decoder.value()?;
+    Ok((value, decoder.position()))
+}
+
+/// Convert an IEEE 754 half-precision float to f64
+fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((bits >> 10) & 0x1f) as i32;
+    let mant = (bits & 0x3ff) as f64;
+    let magnitude = match exp {
+        0 => mant * 2f64.powi(-24),
+        31 if mant == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + mant / 1024.0) * 2f64.powi(exp - 15),
+    };
+    sign * magnitude
+}
+
+/// Append an item header with the shortest argument encoding
+pub fn encode_header(out: &mut Vec<u8>, major: u8, arg: u64) {
+    let major = major << 5;
+    if arg < 24 {
+        out.push(major | arg as u8);
+    } else if arg <= u8::MAX as u64 {
+        out.extend_from_slice(&[major | 24, arg as u8]);
+    } else if arg <= u16::MAX as u64 {
+        out.push(major | 25);
+        out.extend_from_slice(&(arg as u16).to_be_bytes());
+    } else if arg <= u32::MAX as u64 {
+        out.push(major | 26);
+        out.extend_from_slice(&(arg as u32).to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&arg.to_be_bytes());
+    }
+}
+
+/// Append an unsigned integer
+pub fn encode_uint(out: &mut Vec<u8>, n: u64) {
+    encode_header(out, 0, n);
+}
+
+/// Append an array header
+pub fn encode_array(out: &mut Vec<u8>, len: u64) {
+    encode_header(out, 4, len);
+}
+
+/// Append a map header
+pub fn encode_map(out: &mut Vec<u8>, len: u64) {
+    encode_header(out, 5, len);
+}
+
+/// Append a boolean
+pub fn encode_bool(out: &mut Vec<u8>, b: bool) {
+    out.push(if b { 0xf5 } else { 0xf4 });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_uints() {
+        for n in [0u64, 23, 24, 255, 256, 65535, 65536, u32::MAX as u64 + 1] {
+            let mut out = Vec::new();
+            encode_uint(&mut out, n);
+            assert_eq!(decode(&out).unwrap(), (Value::Uint(n), out.len()));
+        }
+    }
+
+    #[test]
+    fn test_decode_nested() {
+        // [6, [5, 24(h'8100')]]
+        let bytes = [0x82, 0x06, 0x82, 0x05, 0xd8, 0x18, 0x42, 0x81, 0x00];
+        let (value, used) = decode(&bytes).unwrap();
+        assert_eq!(used, bytes.len());
+        let items = value.as_array().unwrap();
+        assert_eq!(items[0].as_u64(), Some(6));
+        assert_eq!(
+            items[1].as_array().unwrap()[1],
+            Value::Tag(24, Box::new(Value::Bytes(vec![0x81, 0x00])))
+        );
+    }
+
+    #[test]
+    fn test_decode_indefinite_and_map() {
+        // [_ {2: 170000}, true]
+        let bytes = [0x9f, 0xa1, 0x02, 0x1a, 0x00, 0x02, 0x98, 0x10, 0xf5, 0xff];
+        let (value, _) = decode(&bytes).unwrap();
+        let items = value.as_array().unwrap();
+        assert_eq!(items[0].map_get(2).and_then(Value::as_u64), Some(170000));
+        assert_eq!(items[1], Value::Bool(true));
+    }
+
+    #[test]
+    fn test_incomplete_input() {
+        assert_eq!(decode(&[0x82, 0x01]), Err(CborError::Incomplete));
+        assert_eq!(decode(&[0x44, 0x00, 0x01]), Err(CborError::Incomplete));
+        assert_eq!(decode(&[]), Err(CborError::Incomplete));
+    }
+}
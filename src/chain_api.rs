@@ -0,0 +1,697 @@
+//! External chain data providers (Koios / Blockfrost)
+//!
+//! Provides a shared provider layer for querying public Cardano APIs.
+//! A single instance is shared by all monitored nodes so that nodes referencing
+//! the same pool/network only trigger one upstream request. Responses are cached
+//! with a TTL and identical requests within a refresh are coalesced into one
+//! batched query, keeping fleets within the public API rate limits. Clones
+//! share the caches and the request spacing, so lookups can run in background
+//! tasks without holding up the UI.
+
+use crate::hardfork::{parse_koios_proposals, HardFork};
+use crate::reference_tip::{parse_blockfrost_tip, parse_koios_tip, ReferenceTip};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Minimum spacing between outbound requests (Blockfrost free tier: 10 req/s)
+const MIN_REQUEST_SPACING: Duration = Duration::from_millis(150);
+
+/// Maximum number of pool IDs per Koios batch request
+const KOIOS_BATCH_LIMIT: usize = 50;
+
+/// Which public API backend to query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainApiBackend {
+    /// Koios (free, no API key required). Optional base URL override.
+    Koios { base_url: Option<String> },
+    /// Blockfrost (requires a project ID)
+    Blockfrost { project_id: String },
+}
+
+impl ChainApiBackend {
+    /// Get the API base URL for a network
+    pub fn base_url(&self, network: &str) -> String {
+        match self {
+            ChainApiBackend::Koios {
+                base_url: Some(url),
+            } => url.trim_end_matches('/').to_string(),
+            ChainApiBackend::Koios { base_url: None } => match network {
+                "mainnet" => "https://api.koios.rest/api/v1".to_string(),
+                other => format!("https://{}.koios.rest/api/v1", other),
+            },
+            ChainApiBackend::Blockfrost { .. } => {
+                format!("https://cardano-{}.blockfrost.io/api/v0", network)
+            }
+        }
+    }
+}
+
+/// Pool information returned by the chain API
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PoolInfo {
+    /// Bech32 pool ID
+    pub pool_id: String,
+    /// Pool ticker (from registered metadata)
+    pub ticker: Option<String>,
+    /// Live stake in lovelace
+    pub live_stake: Option<u64>,
+    /// Active stake in lovelace (stake used for leader election this epoch)
+    pub active_stake: Option<u64>,
+    /// Number of live delegators
+    pub live_delegators: Option<u64>,
+    /// Lifetime blocks minted
+    pub block_count: Option<u64>,
+}
+
+/// One epoch of a pool's history from the chain API
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EpochReward {
+    pub epoch: u64,
+    /// Active stake in lovelace
+    pub active_stake: Option<u64>,
+    /// Blocks minted in the epoch
+    pub blocks: Option<u64>,
+    /// Rewards paid to delegators in lovelace (after pool fees)
+    pub delegator_rewards: Option<u64>,
+    /// Fixed cost and margin kept by the pool in lovelace
+    pub pool_fees: Option<u64>,
+    /// Annualized return on stake for delegators, in percent
+    pub ros: Option<f64>,
+}
+
+/// Epochs per year, for annualizing per-epoch returns (5-day epochs)
+const EPOCHS_PER_YEAR: f64 = 73.0;
+
+/// Cache key: (network, pool_id)
+type CacheKey = (String, String);
+
+/// A pool's per-epoch history, the network's epoch at the time and when it
+/// was fetched
+type HistoryEntry = (Vec<EpochReward>, Option<u64>, Instant);
+
+/// Cached API response
+struct CacheEntry {
+    /// None records a failed/unknown lookup so it isn't retried until the TTL expires
+    value: Option<PoolInfo>,
+    fetched_at: Instant,
+}
+
+/// Shared provider for external chain data with caching and request coalescing
+#[derive(Clone)]
+pub struct ChainApiProvider {
+    backend: ChainApiBackend,
+    client: reqwest::Client,
+    cache: Arc<Mutex<HashMap<CacheKey, CacheEntry>>>,
+    /// Per-epoch history by pool
+    history_cache: Arc<Mutex<HashMap<CacheKey, HistoryEntry>>>,
+    cache_ttl: Duration,
+    /// Time the latest request was scheduled for
+    last_request: Arc<Mutex<Option<Instant>>>,
+}
+
+impl ChainApiProvider {
+    /// Create a new provider for the given backend
+    pub fn new(backend: ChainApiBackend, cache_ttl: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .expect("Failed to create HTTP client for chain API");
+
+        Self {
+            backend,
+            client,
+            cache: Arc::default(),
+            history_cache: Arc::default(),
+            cache_ttl,
+            last_request: Arc::default(),
+        }
+    }
+
+    /// Get the cache TTL
+    pub fn cache_ttl(&self) -> Duration {
+        self.cache_ttl
+    }
+
+    /// Get a fresh cached pool entry (outer None = not cached or expired)
+    fn cached_pool(&self, key: &CacheKey) -> Option<Option<PoolInfo>> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .filter(|e| e.fetched_at.elapsed() < self.cache_ttl)
+            .map(|e| e.value.clone())
+    }
+
+    /// Look up pool info for a set of (network, pool_id) requests
+    ///
+    /// Duplicate requests are coalesced, cached entries are served without
+    /// touching the network, and the remainder is fetched in one batch per network.
+    pub async fn pool_info_batch(
+        &self,
+        requests: &[(String, String)],
+    ) -> HashMap<CacheKey, PoolInfo> {
+        let mut results = HashMap::new();
+        let pending = coalesce_requests(requests);
+
+        // Serve from cache and group the rest by network
+        let mut to_fetch: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for key in pending {
+            match self.cached_pool(&key) {
+                Some(Some(info)) => {
+                    results.insert(key, info);
+                }
+                Some(None) => {}
+                None => to_fetch.entry(key.0).or_default().push(key.1),
+            }
+        }
+
+        for (network, pool_ids) in to_fetch {
+            let fetched = match self.backend.clone() {
+                ChainApiBackend::Koios { .. } => self.fetch_koios_pools(&network, &pool_ids).await,
+                ChainApiBackend::Blockfrost { project_id } => {
+                    self.fetch_blockfrost_pools(&network, &project_id, &pool_ids)
+                        .await
+                }
+            };
+
+            let Some(fetched) = fetched else {
+                // Request failed entirely - don't cache so we retry next cycle
+                continue;
+            };
+
+            let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            for pool_id in pool_ids {
+                let key = (network.clone(), pool_id.clone());
+                let value = fetched.get(&pool_id).cloned();
+                if let Some(info) = &value {
+                    results.insert(key.clone(), info.clone());
+                }
+                cache.insert(
+                    key,
+                    CacheEntry {
+                        value,
+                        fetched_at: Instant::now(),
+                    },
+                );
+            }
+        }
+
+        results
+    }
+
+    /// Per-epoch rewards of a pool for its last `epochs` epochs (newest first)
+    ///
+    /// Served from the cache within the TTL while the network is still in
+    /// `current_epoch`, so a newly finished epoch is fetched right away;
+    /// `None` if the lookup failed.
+    pub async fn pool_history(
+        &self,
+        network: &str,
+        pool_id: &str,
+        current_epoch: Option<u64>,
+        epochs: usize,
+    ) -> Option<Vec<EpochReward>> {
+        let key = (network.to_string(), pool_id.to_string());
+        if let Some((history, epoch, fetched_at)) = self
+            .history_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+        {
+            if *epoch == current_epoch
+                && fetched_at.elapsed() < self.cache_ttl
+                && history.len() >= epochs
+            {
+                return Some(history[..epochs].to_vec());
+            }
+        }
+
+        self.throttle().await;
+        let base = self.backend.base_url(network);
+        let request = match &self.backend {
+            ChainApiBackend::Koios { .. } => self.client.get(format!(
+                "{}/pool_history?_pool_bech32={}&order=epoch_no.desc&limit={}",
+                base, pool_id, epochs
+            )),
+            ChainApiBackend::Blockfrost { project_id } => self
+                .client
+                .get(format!(
+                    "{}/pools/{}/history?order=desc&count={}",
+                    base, pool_id, epochs
+                ))
+                .header("project_id", project_id),
+        };
+        let response = match request.send().await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Pool history request failed: {}", e);
+                return None;
+            }
+        };
+        if !response.status().is_success() {
+            warn!("Pool history returned HTTP {}", response.status());
+            return None;
+        }
+        let items = match response.json::<Vec<serde_json::Value>>().await {
+            Ok(items) => items,
+            Err(e) => {
+                warn!("Failed to parse pool history response: {}", e);
+                return None;
+            }
+        };
+        let parse = match self.backend {
+            ChainApiBackend::Koios { .. } => parse_koios_pool_history,
+            ChainApiBackend::Blockfrost { .. } => parse_blockfrost_pool_history,
+        };
+        let mut history: Vec<EpochReward> = items.iter().filter_map(parse).collect();
+        history.sort_by_key(|r| std::cmp::Reverse(r.epoch));
+        history.truncate(epochs);
+        self.history_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, (history.clone(), current_epoch, Instant::now()));
+        Some(history)
+    }
+
+    /// Wait until the minimum request spacing has elapsed
+    ///
+    /// Each caller reserves the next free slot, so concurrent lookups from
+    /// clones are spaced too.
+    async fn throttle(&self) {
+        let wait = {
+            let mut last = self.last_request.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let slot = last.map_or(now, |last| (last + MIN_REQUEST_SPACING).max(now));
+            *last = Some(slot);
+            slot - now
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Look up a ratified hard fork on a network (Koios `/proposal_list`)
+    ///
+    /// Returns `None` if the lookup failed or the backend can't answer it,
+    /// `Some(None)` if no hard fork is pending.
+    pub async fn ratified_hard_fork(&self, network: &str) -> Option<Option<HardFork>> {
+        if !matches!(self.backend, ChainApiBackend::Koios { .. }) {
+            debug!("Hard fork lookup is only supported with Koios");
+            return None;
+        }
+        self.throttle().await;
+        let url = format!(
+            "{}/proposal_list?proposal_type=eq.HardForkInitiation",
+            self.backend.base_url(network)
+        );
+        let response = match self.client.get(&url).send().await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Koios proposal_list request failed: {}", e);
+                return None;
+            }
+        };
+        if !response.status().is_success() {
+            warn!("Koios proposal_list returned HTTP {}", response.status());
+            return None;
+        }
+        match response.json::<Vec<serde_json::Value>>().await {
+            Ok(items) => Some(parse_koios_proposals(&items)),
+            Err(e) => {
+                warn!("Failed to parse Koios proposal_list response: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Current tip of a network (Koios `/tip`, Blockfrost `/blocks/latest`)
+    ///
+    /// Not cached: callers poll at their own interval. `None` if the lookup failed.
+    pub async fn tip(&self, network: &str) -> Option<ReferenceTip> {
+        self.throttle().await;
+        let base = self.backend.base_url(network);
+        let request = match &self.backend {
+            ChainApiBackend::Koios { .. } => self.client.get(format!("{}/tip", base)),
+            ChainApiBackend::Blockfrost { project_id } => self
+                .client
+                .get(format!("{}/blocks/latest", base))
+                .header("project_id", project_id),
+        };
+        let response = match request.send().await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Network tip request failed: {}", e);
+                return None;
+            }
+        };
+        if !response.status().is_success() {
+            warn!("Network tip returned HTTP {}", response.status());
+            return None;
+        }
+        let json = match response.json::<serde_json::Value>().await {
+            Ok(json) => json,
+            Err(e) => {
+                warn!("Failed to parse network tip response: {}", e);
+                return None;
+            }
+        };
+        let tip = match self.backend {
+            ChainApiBackend::Koios { .. } => parse_koios_tip(&json),
+            ChainApiBackend::Blockfrost { .. } => parse_blockfrost_tip(&json),
+        };
+        if tip.is_none() {
+            warn!("Network tip response had no block height or slot");
+        }
+        tip
+    }
+
+    /// Fetch pool info from Koios using the batched POST /pool_info endpoint
+    async fn fetch_koios_pools(
+        &self,
+        network: &str,
+        pool_ids: &[String],
+    ) -> Option<HashMap<String, PoolInfo>> {
+        let url = format!("{}/pool_info", self.backend.base_url(network));
+        let mut results = HashMap::new();
+
+        for chunk in pool_ids.chunks(KOIOS_BATCH_LIMIT) {
+            self.throttle().await;
+            debug!(
+                "Koios pool_info batch for {} pool(s) on {}",
+                chunk.len(),
+                network
+            );
+
+            let body = serde_json::json!({ "_pool_bech32_ids": chunk });
+            let response = match self.client.post(&url).json(&body).send().await {
+                Ok(r) => r,
+                Err(e) => {
+                    warn!("Koios pool_info request failed: {}", e);
+                    return None;
+                }
+            };
+
+            if !response.status().is_success() {
+                warn!("Koios pool_info returned HTTP {}", response.status());
+                return None;
+            }
+
+            match response.json::<Vec<serde_json::Value>>().await {
+                Ok(items) => {
+                    for item in &items {
+                        if let Some(info) = parse_koios_pool_info(item) {
+                            results.insert(info.pool_id.clone(), info);
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to parse Koios pool_info response: {}", e);
+                    return None;
+                }
+            }
+        }
+
+        Some(results)
+    }
+
+    /// Fetch pool info from Blockfrost (one request per pool, no batch endpoint)
+    async fn fetch_blockfrost_pools(
+        &self,
+        network: &str,
+        project_id: &str,
+        pool_ids: &[String],
+    ) -> Option<HashMap<String, PoolInfo>> {
+        let base = self.backend.base_url(network);
+        let mut results = HashMap::new();
+
+        for pool_id in pool_ids {
+            self.throttle().await;
+            let response = match self
+                .client
+                .get(format!("{}/pools/{}", base, pool_id))
+                .header("project_id", project_id)
+                .send()
+                .await
+            {
+                Ok(r) => r,
+                Err(e) => {
+                    warn!("Blockfrost pool request failed: {}", e);
+                    return None;
+                }
+            };
+
+            if !response.status().is_success() {
+                debug!(
+                    "Blockfrost returned HTTP {} for pool {}",
+                    response.status(),
+                    pool_id
+                );
+                continue;
+            }
+
+            let Ok(json) = response.json::<serde_json::Value>().await else {
+                continue;
+            };
+            let Some(mut info) = parse_blockfrost_pool(&json) else {
+                continue;
+            };
+
+            // Ticker lives in a separate metadata endpoint
+            self.throttle().await;
+            if let Ok(meta) = self
+                .client
+                .get(format!("{}/pools/{}/metadata", base, pool_id))
+                .header("project_id", project_id)
+                .send()
+                .await
+            {
+                if let Ok(meta_json) = meta.json::<serde_json::Value>().await {
+                    info.ticker = meta_json
+                        .get("ticker")
+                        .and_then(|v| v.as_str())
+                        .map(String::from);
+                }
+            }
+
+            results.insert(pool_id.clone(), info);
+        }
+
+        Some(results)
+    }
+}
+
+/// Deduplicate requests, dropping empty pool IDs
+fn coalesce_requests(requests: &[(String, String)]) -> BTreeSet<CacheKey> {
+    requests
+        .iter()
+        .filter(|(_, pool_id)| !pool_id.is_empty())
+        .cloned()
+        .collect()
+}
+
+/// Parse a JSON value that may be a number or a numeric string (lovelace amounts)
+fn json_u64(value: Option<&serde_json::Value>) -> Option<u64> {
+    match value? {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Parse a single entry from Koios /pool_info
+fn parse_koios_pool_info(json: &serde_json::Value) -> Option<PoolInfo> {
+    let pool_id = json.get("pool_id_bech32")?.as_str()?.to_string();
+
+    Some(PoolInfo {
+        pool_id,
+        ticker: json
+            .get("meta_json")
+            .and_then(|m| m.get("ticker"))
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        live_stake: json_u64(json.get("live_stake")),
+        active_stake: json_u64(json.get("active_stake")),
+        live_delegators: json_u64(json.get("live_delegators")),
+        block_count: json_u64(json.get("block_count")),
+    })
+}
+
+/// Parse Blockfrost /pools/{pool_id}
+fn parse_blockfrost_pool(json: &serde_json::Value) -> Option<PoolInfo> {
+    let pool_id = json.get("pool_id")?.as_str()?.to_string();
+
+    Some(PoolInfo {
+        pool_id,
+        ticker: None,
+        live_stake: json_u64(json.get("live_stake")),
+        active_stake: json_u64(json.get("active_stake")),
+        live_delegators: json_u64(json.get("live_delegators")),
+        block_count: json_u64(json.get("blocks_minted")),
+    })
+}
+
+/// Parse an entry from Koios /pool_history
+fn parse_koios_pool_history(json: &serde_json::Value) -> Option<EpochReward> {
+    Some(EpochReward {
+        epoch: json_u64(json.get("epoch_no"))?,
+        active_stake: json_u64(json.get("active_stake")),
+        blocks: json_u64(json.get("block_cnt")),
+        delegator_rewards: json_u64(json.get("deleg_rewards")),
+        pool_fees: json_u64(json.get("pool_fees")),
+        ros: json.get("epoch_ros").and_then(|v| match v {
+            serde_json::Value::String(s) => s.parse().ok(),
+            other => other.as_f64(),
+        }),
+    })
+}
+
+/// Parse an entry from Blockfrost /pools/{pool_id}/history
+///
+/// Blockfrost reports total rewards including the pool's fees, and no ROS.
+fn parse_blockfrost_pool_history(json: &serde_json::Value) -> Option<EpochReward> {
+    let active_stake = json_u64(json.get("active_stake"));
+    let pool_fees = json_u64(json.get("fees"));
+    let delegator_rewards =
+        json_u64(json.get("rewards")).map(|rewards| rewards.saturating_sub(pool_fees.unwrap_or(0)));
+    Some(EpochReward {
+        epoch: json_u64(json.get("epoch"))?,
+        active_stake,
+        blocks: json_u64(json.get("blocks")),
+        delegator_rewards,
+        pool_fees,
+        ros: annualized_ros(delegator_rewards, active_stake),
+    })
+}
+
+/// Annualized return on stake in percent from one epoch's rewards
+pub fn annualized_ros(rewards: Option<u64>, active_stake: Option<u64>) -> Option<f64> {
+    let stake = active_stake.filter(|s| *s > 0)? as f64;
+    Some(rewards? as f64 / stake * EPOCHS_PER_YEAR * 100.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coalesce_requests() {
+        let requests = vec![
+            ("mainnet".to_string(), "pool1abc".to_string()),
+            ("mainnet".to_string(), "pool1abc".to_string()),
+            ("preprod".to_string(), "pool1abc".to_string()),
+            ("mainnet".to_string(), String::new()),
+        ];
+        let pending = coalesce_requests(&requests);
+        assert_eq!(pending.len(), 2);
+    }
+
+    #[test]
+    fn test_backend_base_url() {
+        let koios = ChainApiBackend::Koios { base_url: None };
+        assert_eq!(koios.base_url("mainnet"), "https://api.koios.rest/api/v1");
+        assert_eq!(
+            koios.base_url("preprod"),
+            "https://preprod.koios.rest/api/v1"
+        );
+
+        let custom = ChainApiBackend::Koios {
+            base_url: Some("http://localhost:8053/api/v1/".to_string()),
+        };
+        assert_eq!(custom.base_url("mainnet"), "http://localhost:8053/api/v1");
+    }
+
+    #[test]
+    fn test_parse_koios_pool_info() {
+        let json = serde_json::json!({
+            "pool_id_bech32": "pool1xyz",
+            "meta_json": { "ticker": "SAND", "name": "Sandstone" },
+            "live_stake": "12345678901234",
+            "active_stake": "12000000000000",
+            "live_delegators": 321,
+            "block_count": 1500
+        });
+        let info = parse_koios_pool_info(&json).unwrap();
+        assert_eq!(info.pool_id, "pool1xyz");
+        assert_eq!(info.ticker, Some("SAND".to_string()));
+        assert_eq!(info.live_stake, Some(12345678901234));
+        assert_eq!(info.live_delegators, Some(321));
+        assert_eq!(info.block_count, Some(1500));
+    }
+
+    #[test]
+    fn test_parse_blockfrost_pool() {
+        let json = serde_json::json!({
+            "pool_id": "pool1xyz",
+            "live_stake": "6900000000",
+            "active_stake": "4200000000",
+            "live_delegators": 12,
+            "blocks_minted": 69
+        });
+        let info = parse_blockfrost_pool(&json).unwrap();
+        assert_eq!(info.live_stake, Some(6900000000));
+        assert_eq!(info.block_count, Some(69));
+        assert_eq!(info.ticker, None);
+    }
+
+    #[test]
+    fn test_parse_pool_history() {
+        let koios = serde_json::json!({
+            "epoch_no": 480,
+            "active_stake": "12000000000000",
+            "block_cnt": 9,
+            "deleg_rewards": "7800000000",
+            "pool_fees": "420000000",
+            "epoch_ros": 4.745
+        });
+        let reward = parse_koios_pool_history(&koios).unwrap();
+        assert_eq!(reward.epoch, 480);
+        assert_eq!(reward.blocks, Some(9));
+        assert_eq!(reward.delegator_rewards, Some(7_800_000_000));
+        assert_eq!(reward.ros, Some(4.745));
+
+        let blockfrost = serde_json::json!({
+            "epoch": 480,
+            "blocks": 9,
+            "active_stake": "12000000000000",
+            "rewards": "8220000000",
+            "fees": "420000000",
+            "delegators_count": 321
+        });
+        let reward = parse_blockfrost_pool_history(&blockfrost).unwrap();
+        assert_eq!(reward.delegator_rewards, Some(7_800_000_000));
+        assert_eq!(reward.pool_fees, Some(420_000_000));
+        assert!((reward.ros.unwrap() - 4.745).abs() < 1e-9);
+
+        assert_eq!(annualized_ros(Some(1), Some(0)), None);
+        assert_eq!(parse_koios_pool_history(&serde_json::json!({})), None);
+    }
+
+    #[tokio::test]
+    async fn test_cached_entries_skip_network() {
+        let provider = ChainApiProvider::new(
+            ChainApiBackend::Koios {
+                // Unroutable URL: any network access would fail the lookup
+                base_url: Some("http://127.0.0.1:9".to_string()),
+            },
+            Duration::from_secs(60),
+        );
+        let key = ("mainnet".to_string(), "pool1xyz".to_string());
+        provider.cache.lock().unwrap().insert(
+            key.clone(),
+            CacheEntry {
+                value: Some(PoolInfo {
+                    pool_id: "pool1xyz".to_string(),
+                    ticker: Some("SAND".to_string()),
+                    ..Default::default()
+                }),
+                fetched_at: Instant::now(),
+            },
+        );
+
+        let results = provider.pool_info_batch(&[key.clone(), key.clone()]).await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[&key].ticker, Some("SAND".to_string()));
+    }
+}
@@ -0,0 +1,472 @@
+//! Config validation (`sview check`, `sview config validate`)
+//!
+//! Parses the config file strictly (reporting syntax errors and unknown keys
+//! instead of falling back to defaults), validates each node's settings and,
+//! for `sview check`, tries one metrics fetch per node, then prints a
+//! pass/fail report.
+
+use crate::config::{
+    self, AppConfig, CliArgs, FileConfig, HealthThresholds, NodeRuntimeConfig, Scheme,
+};
+use crate::metrics::{MetricsClient, Sample};
+use crate::tunnel::SshTunnel;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How long to wait for an SSH tunnel to come up
+const TUNNEL_READY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckLevel {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl CheckLevel {
+    fn symbol(self) -> &'static str {
+        match self {
+            CheckLevel::Pass => "✓",
+            CheckLevel::Warn => "⚠",
+            CheckLevel::Fail => "✗",
+        }
+    }
+}
+
+/// One line of the report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    pub level: CheckLevel,
+    pub message: String,
+}
+
+impl Finding {
+    fn pass(message: impl Into<String>) -> Self {
+        Self {
+            level: CheckLevel::Pass,
+            message: message.into(),
+        }
+    }
+
+    fn warn(message: impl Into<String>) -> Self {
+        Self {
+            level: CheckLevel::Warn,
+            message: message.into(),
+        }
+    }
+
+    fn fail(message: impl Into<String>) -> Self {
+        Self {
+            level: CheckLevel::Fail,
+            message: message.into(),
+        }
+    }
+}
+
+/// Validate the configuration and print a report; returns true if no check failed
+///
+/// With `probe_endpoints`, each node's metrics endpoint is fetched once too.
+pub async fn run(args: CliArgs, probe_endpoints: bool) -> bool {
+    let mut sections: Vec<(String, Vec<Finding>)> = Vec::new();
+
+    // Config file
+    let config_path = args.config.clone().or_else(config::default_config_path);
+    let mut file_findings = Vec::new();
+    let file_config = match config_path.as_deref() {
+        Some(path) if path.exists() => {
+            let (parsed, findings) = check_file(path);
+            file_findings.extend(findings);
+            parsed
+        }
+        Some(path) => {
+            file_findings.push(Finding::warn(format!(
+                "{} not found - using CLI single-node mode",
+                path.display()
+            )));
+            Some(FileConfig::default())
+        }
+        None => {
+            file_findings.push(Finding::warn(
+                "No config directory - using CLI single-node mode",
+            ));
+            Some(FileConfig::default())
+        }
+    };
+
+    let resolved = file_config.and_then(|file_config| {
+        match AppConfig::resolve(args, config_path.clone(), file_config) {
+            Ok(resolved) => Some(resolved),
+            Err(e) => {
+                file_findings.push(Finding::fail(format!("{:#}", e)));
+                None
+            }
+        }
+    });
+    if let Some(app_config) = &resolved {
+        if let Err(e) = crate::geoip::provider_from_config(&app_config.geoip) {
+            file_findings.push(Finding::fail(format!("GeoIP: {:#}", e)));
+        }
+    }
+    let title = match &config_path {
+        Some(path) => format!("Config file: {}", path.display()),
+        None => "Config file".to_string(),
+    };
+    sections.push((title, file_findings));
+
+    // Nodes
+    if let Some(app_config) = &resolved {
+        for (i, node) in app_config.nodes.iter().enumerate() {
+            let duplicate = app_config.nodes[..i].iter().any(|n| n.name == node.name);
+            let mut findings = check_node(node, duplicate);
+            if probe_endpoints {
+                findings.extend(check_metrics(node, app_config).await);
+            }
+            let title = format!(
+                "Node {}: {} — {} ({}, {})",
+                i + 1,
+                node.name,
+                node.endpoint_label(),
+                node.role,
+                node.network
+            );
+            sections.push((title, findings));
+        }
+    }
+
+    print_report(&sections)
+}
+
+/// Parse the config file strictly, reporting syntax errors and unknown keys
+fn check_file(path: &Path) -> (Option<FileConfig>, Vec<Finding>) {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            return (
+                None,
+                vec![Finding::fail(format!("Cannot read file: {}", e))],
+            )
+        }
+    };
+    check_toml(&content)
+}
+
+/// Parse config TOML, collecting keys that sview doesn't recognise
+fn check_toml(content: &str) -> (Option<FileConfig>, Vec<Finding>) {
+    let mut unknown = Vec::new();
+    let result: Result<FileConfig, _> =
+        serde_ignored::deserialize(toml::Deserializer::new(content), |path| {
+            unknown.push(path.to_string())
+        });
+
+    match result {
+        Ok(file_config) => {
+            let mut findings = vec![Finding::pass(format!(
+                "Parsed successfully ({} node(s) defined)",
+                file_config.nodes.len()
+            ))];
+            findings.extend(
+                unknown
+                    .into_iter()
+                    .map(|key| Finding::warn(format!("Unknown key `{}` is ignored", key))),
+            );
+            (Some(file_config), findings)
+        }
+        Err(e) => (
+            None,
+            vec![Finding::fail(format!(
+                "Invalid TOML: {}",
+                e.to_string().trim_end()
+            ))],
+        ),
+    }
+}
+
+/// Validate a node's settings (everything except reachability)
+fn check_node(node: &NodeRuntimeConfig, duplicate: bool) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    if duplicate {
+        findings.push(Finding::fail(format!(
+            "Duplicate node name '{}' (history and alerts are stored per name)",
+            node.name
+        )));
+    }
+    if let Some(path) = config::unix_socket_path(&node.host) {
+        if !path.exists() {
+            findings.push(Finding::warn(format!(
+                "Metrics socket {} doesn't exist on this machine",
+                path.display()
+            )));
+        }
+    } else if node.host.trim().is_empty() {
+        findings.push(Finding::fail("Host is empty"));
+    } else if node.host.contains("://") || node.host.contains('/') {
+        findings.push(Finding::fail(format!(
+            "Host '{}' should be a hostname or IP, without scheme or path",
+            node.host
+        )));
+    }
+    if node.port == 0 {
+        findings.push(Finding::fail("Port must be between 1 and 65535"));
+    }
+    if crate::node_socket::network_magic(&node.network).is_none() {
+        findings.push(Finding::warn(format!(
+            "Unknown network '{}' (pool lookups and mempool view need mainnet, preprod, preview or sanchonet)",
+            node.network
+        )));
+    }
+    if let Some(pool_id) = &node.pool_id {
+        if !pool_id.starts_with("pool1") {
+            findings.push(Finding::warn(format!(
+                "pool_id '{}' doesn't look like a bech32 pool ID (pool1...)",
+                pool_id
+            )));
+        }
+    }
+    if let Some(pooltool) = &node.pooltool {
+        if node.log_file.is_none() {
+            findings.push(Finding::warn(
+                "pooltool needs log_file for the block hashes of new tips",
+            ));
+        }
+        if pooltool.pool_id.len() != 56 || !pooltool.pool_id.chars().all(|c| c.is_ascii_hexdigit())
+        {
+            findings.push(Finding::warn(format!(
+                "pooltool pool_id '{}' should be the 56-character hex pool ID",
+                pooltool.pool_id
+            )));
+        }
+    }
+    if let Some(cncli_db) = &node.cncli_db {
+        if !cncli_db.is_file() {
+            findings.push(Finding::warn(format!(
+                "cncli_db {} doesn't exist on this machine",
+                cncli_db.display()
+            )));
+        }
+    }
+    if let Some(opcert) = &node.opcert_path {
+        if !opcert.is_file() {
+            findings.push(Finding::warn(format!(
+                "opcert_path {} doesn't exist on this machine",
+                opcert.display()
+            )));
+        }
+        if node.socket_path.is_none() {
+            findings.push(Finding::warn(
+                "opcert_path needs socket_path for cardano-cli to query the node",
+            ));
+        }
+    }
+    if let Some(log_file) = &node.log_file {
+        if !log_file.is_file() {
+            findings.push(Finding::warn(format!(
+                "log_file {} doesn't exist on this machine",
+                log_file.display()
+            )));
+        }
+    }
+    if let Some(socket) = &node.socket_path {
+        if !socket.exists() {
+            findings.push(Finding::warn(format!(
+                "Socket {} doesn't exist on this machine",
+                socket.display()
+            )));
+        }
+    }
+    if node.endpoint.scheme == Scheme::Http
+        && (node.endpoint.basic_auth.is_some() || node.endpoint.bearer_token.is_some())
+    {
+        findings.push(Finding::warn(
+            "Credentials are sent over plain http (set scheme = \"https\")",
+        ));
+    }
+    if node.endpoint.insecure_skip_verify {
+        findings.push(Finding::warn(
+            "insecure_skip_verify is set: TLS certificates are not verified",
+        ));
+    }
+    findings.extend(check_thresholds(&node.thresholds));
+
+    if findings.is_empty() {
+        findings.push(Finding::pass("Settings valid"));
+    }
+    findings
+}
+
+/// Check that warning thresholds come before critical ones
+fn check_thresholds(t: &HealthThresholds) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if t.peers_critical > t.peers_warning {
+        findings.push(Finding::fail(format!(
+            "thresholds: peers_critical ({}) is above peers_warning ({})",
+            t.peers_critical, t.peers_warning
+        )));
+    }
+    if t.memory_critical_gb < t.memory_warning_gb {
+        findings.push(Finding::fail(format!(
+            "thresholds: memory_critical_gb ({}) is below memory_warning_gb ({})",
+            t.memory_critical_gb, t.memory_warning_gb
+        )));
+    }
+    if t.tip_age_critical_secs < t.tip_age_warning_secs {
+        findings.push(Finding::fail(format!(
+            "thresholds: tip_age_critical_secs ({}) is below tip_age_warning_secs ({})",
+            t.tip_age_critical_secs, t.tip_age_warning_secs
+        )));
+    }
+    findings
+}
+
+/// Try one metrics fetch (and health check, if configured) from the node
+async fn check_metrics(node: &NodeRuntimeConfig, app_config: &AppConfig) -> Vec<Finding> {
+    // Keep the tunnel alive until the fetch has finished
+    let mut tunnel = None;
+    let client = match &node.endpoint.ssh_tunnel {
+        Some(tunnel_config) => match SshTunnel::new(tunnel_config, &node.host, node.port) {
+            Ok(t) => {
+                let t = tunnel.insert(t);
+                match t.ensure_ready(TUNNEL_READY_TIMEOUT).await {
+                    Ok(()) => t.metrics_client(app_config.timeout, &node.endpoint),
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        },
+        None => MetricsClient::new(
+            node.metrics_url(),
+            app_config.timeout,
+            &node.endpoint,
+            config::unix_socket_path(&node.host),
+        ),
+    };
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => return vec![Finding::fail(format!("{:#}", e))],
+    };
+    let started = Instant::now();
+    let metrics = match client.fetch().await.map(Sample::into_metrics) {
+        Ok(metrics) => {
+            let elapsed = started.elapsed().as_millis();
+            match metrics.block_height {
+                Some(height) => Finding::pass(format!(
+                    "Metrics reachable (block {} in {} ms)",
+                    height, elapsed
+                )),
+                None => Finding::warn(format!(
+                    "Metrics reachable in {} ms but no chain metrics yet (node starting?)",
+                    elapsed
+                )),
+            }
+        }
+        Err(e) => Finding::fail(match e.hint() {
+            Some(hint) => format!("Cannot fetch {}: {} ({})", node.endpoint_label(), e, hint),
+            None => format!("Cannot fetch {}: {}", node.endpoint_label(), e),
+        }),
+    };
+
+    let mut findings = vec![metrics];
+    if client.has_health_check() {
+        findings.push(match client.check_health().await {
+            Ok(()) => Finding::pass("Health endpoint responding"),
+            Err(e) => Finding::fail(format!("Health endpoint: {:#}", e)),
+        });
+    }
+    findings
+}
+
+/// Print the report and return true if nothing failed
+fn print_report(sections: &[(String, Vec<Finding>)]) -> bool {
+    println!("sview config check");
+
+    for (title, findings) in sections {
+        println!();
+        println!("{}", title);
+        for finding in findings {
+            println!("  {} {}", finding.level.symbol(), finding.message);
+        }
+    }
+
+    let count = |level| {
+        sections
+            .iter()
+            .flat_map(|(_, f)| f)
+            .filter(|f| f.level == level)
+            .count()
+    };
+    let (errors, warnings) = (count(CheckLevel::Fail), count(CheckLevel::Warn));
+
+    println!();
+    println!(
+        "Result: {} — {} error(s), {} warning(s)",
+        if errors == 0 { "PASS" } else { "FAIL" },
+        errors,
+        warnings
+    );
+    errors == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_toml_unknown_keys() {
+        let (config, findings) = check_toml(
+            r#"
+[global]
+refresh_intervall_secs = 5
+
+[[nodes]]
+name = "Relay"
+prot = 12798
+"#,
+        );
+        assert_eq!(config.unwrap().nodes.len(), 1);
+        let warnings: Vec<&str> = findings
+            .iter()
+            .filter(|f| f.level == CheckLevel::Warn)
+            .map(|f| f.message.as_str())
+            .collect();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("global.refresh_intervall_secs"));
+        assert!(warnings[1].contains("prot"));
+    }
+
+    #[test]
+    fn test_check_toml_syntax_error() {
+        let (config, findings) = check_toml("[[nodes]\nname = \"Relay\"\n");
+        assert!(config.is_none());
+        assert_eq!(findings[0].level, CheckLevel::Fail);
+
+        let (config, findings) = check_toml("[[nodes]]\nname = \"BP\"\nrole = \"producer\"\n");
+        assert!(config.is_none());
+        assert!(findings[0].message.contains("producer"));
+    }
+
+    #[test]
+    fn test_check_node_and_thresholds() {
+        let mut node = AppConfig::resolve(
+            <CliArgs as clap::Parser>::parse_from(["sview"]),
+            None,
+            FileConfig::default(),
+        )
+        .unwrap()
+        .nodes
+        .remove(0);
+        assert!(check_node(&node, false)
+            .iter()
+            .all(|f| f.level == CheckLevel::Pass));
+
+        node.host = "http://10.0.0.1".to_string();
+        node.thresholds.peers_critical = 10;
+        let findings = check_node(&node, true);
+        let failures = findings
+            .iter()
+            .filter(|f| f.level == CheckLevel::Fail)
+            .count();
+        assert_eq!(failures, 3);
+    }
+}
@@ -0,0 +1,330 @@
+//! cncli database integration
+//!
+//! cncli keeps its leader schedule (`cncli leaderlog`) and the chain headers
+//! it follows (`cncli sync`) in a sqlite database. Reading both gives the
+//! pool's assigned slots for the epoch and whether each one produced an
+//! adopted block, without re-implementing the VRF leader check.
+//!
+//! The database has no block arrival times and `cncli sendtip` stores
+//! nothing in it; propagation comes from the node log (`propagation`) and tip
+//! submission from `pooltool` instead.
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Slots after a leader slot the Forge metrics are given to move before the
+/// slot counts as having produced no block
+const FORGE_RESULT_SLOTS: u64 = 30;
+
+/// What happened to an assigned slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotOutcome {
+    /// Not reached yet (or not yet synced by cncli)
+    Upcoming,
+    /// Our block is on the chain
+    Adopted,
+    /// Our block was forged but lost to a fork
+    Orphaned,
+    /// Slot passed without a block from us (missed or lost a slot battle)
+    Missed,
+}
+
+/// Leader schedule and outcomes for one epoch, read from the cncli database
+#[derive(Debug, Clone, PartialEq)]
+pub struct CncliSnapshot {
+    /// Epoch of the leader schedule
+    pub epoch: u64,
+    /// Hex pool ID the schedule was calculated for
+    pub pool_id: String,
+    /// Assigned slots with their outcome, in slot order
+    pub slots: Vec<(u64, SlotOutcome)>,
+    /// Latest block cncli has synced: (block number, slot)
+    pub tip: Option<(u64, u64)>,
+}
+
+impl CncliSnapshot {
+    /// Number of assigned slots with the given outcome
+    pub fn count(&self, outcome: SlotOutcome) -> usize {
+        self.slots.iter().filter(|(_, o)| *o == outcome).count()
+    }
+
+    /// First assigned slot after `current_slot`
+    pub fn next_slot(&self, current_slot: u64) -> Option<u64> {
+        self.slots
+            .iter()
+            .map(|(slot, _)| *slot)
+            .find(|slot| *slot > current_slot)
+    }
+}
+
+/// What the Forge metrics showed for a watched leader slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeResult {
+    /// `Forge_adopted` went up: our block is on the node's chain
+    Adopted,
+    /// `Forge_didnt_adopt` went up: the block was forged but not adopted
+    NotAdopted,
+    /// Neither counter moved: no block was forged
+    NoBlock,
+}
+
+/// Follows one assigned slot from the countdown through the slot itself to
+/// the node's Forge counters confirming (or not) that a block was adopted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaderWatch {
+    pub slot: u64,
+    /// Forge counters (adopted, didn't adopt) last seen before the slot
+    baseline: (u64, u64),
+    /// The result and the slot it was settled at
+    pub result: Option<(ForgeResult, u64)>,
+}
+
+impl LeaderWatch {
+    pub fn new(slot: u64, adopted: Option<u64>, didnt_adopt: Option<u64>) -> Self {
+        Self {
+            slot,
+            // The node only exports Forge counters once they are non-zero
+            baseline: (adopted.unwrap_or(0), didnt_adopt.unwrap_or(0)),
+            result: None,
+        }
+    }
+
+    /// Whether the slot has started and its result isn't known yet
+    pub fn forging(&self, current_slot: u64) -> bool {
+        self.result.is_none() && current_slot >= self.slot
+    }
+
+    /// Update with the current slot and the node's Forge counters
+    pub fn observe(&mut self, current_slot: u64, adopted: Option<u64>, didnt_adopt: Option<u64>) {
+        if self.result.is_some() {
+            return;
+        }
+        let counters = (adopted.unwrap_or(0), didnt_adopt.unwrap_or(0));
+        if current_slot < self.slot {
+            self.baseline = counters;
+            return;
+        }
+        let result = if counters.0 > self.baseline.0 {
+            ForgeResult::Adopted
+        } else if counters.1 > self.baseline.1 {
+            ForgeResult::NotAdopted
+        } else if current_slot >= self.slot + FORGE_RESULT_SLOTS {
+            ForgeResult::NoBlock
+        } else {
+            return;
+        };
+        self.result = Some((result, current_slot));
+    }
+}
+
+/// Read the leader schedule for `epoch` (or the latest one) and its outcomes
+pub fn read_snapshot(path: &Path, epoch: Option<u64>) -> Result<CncliSnapshot> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("Failed to open cncli database {}", path.display()))?;
+
+    let schedule: Option<(u64, String, String)> = match epoch {
+        Some(epoch) => conn
+            .query_row(
+                "SELECT epoch, pool_id, slots FROM slots WHERE epoch = ?1 ORDER BY id DESC LIMIT 1",
+                [epoch],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?,
+        None => conn
+            .query_row(
+                "SELECT epoch, pool_id, slots FROM slots ORDER BY epoch DESC, id DESC LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?,
+    };
+    let Some((epoch, pool_id, slots_json)) = schedule else {
+        match epoch {
+            Some(epoch) => anyhow::bail!("No cncli leaderlog for epoch {}", epoch),
+            None => anyhow::bail!("No cncli leaderlog found"),
+        }
+    };
+    let mut assigned: Vec<u64> = serde_json::from_str(&slots_json)
+        .with_context(|| format!("Invalid slot list for epoch {} in cncli database", epoch))?;
+    assigned.sort_unstable();
+
+    let tip: Option<(u64, u64)> = conn
+        .query_row(
+            "SELECT block_number, slot_number FROM chain WHERE orphaned = 0 ORDER BY slot_number DESC LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    // Blocks at our assigned slots: (slot, node_vkey, orphaned)
+    let (first, last) = match (assigned.first(), assigned.last()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => (0, 0),
+    };
+    let mut stmt = conn.prepare(
+        "SELECT slot_number, node_vkey, orphaned FROM chain WHERE slot_number BETWEEN ?1 AND ?2",
+    )?;
+    let mut blocks: HashMap<u64, Vec<(String, bool)>> = HashMap::new();
+    for row in stmt.query_map([first, last], |row| {
+        Ok((
+            row.get::<_, u64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)? != 0,
+        ))
+    })? {
+        let (slot, vkey, orphaned) = row?;
+        blocks.entry(slot).or_default().push((vkey, orphaned));
+    }
+
+    let tip_slot = tip.map(|(_, slot)| slot);
+    let slots = assigned
+        .into_iter()
+        .map(|slot| {
+            let ours: Vec<bool> = blocks
+                .get(&slot)
+                .into_iter()
+                .flatten()
+                .filter(|(vkey, _)| pool_id_from_vkey(vkey).as_deref() == Some(pool_id.as_str()))
+                .map(|(_, orphaned)| *orphaned)
+                .collect();
+            let outcome = if ours.contains(&false) {
+                SlotOutcome::Adopted
+            } else if !ours.is_empty() {
+                SlotOutcome::Orphaned
+            } else if tip_slot.is_some_and(|tip| slot <= tip) {
+                SlotOutcome::Missed
+            } else {
+                SlotOutcome::Upcoming
+            };
+            (slot, outcome)
+        })
+        .collect();
+
+    Ok(CncliSnapshot {
+        epoch,
+        pool_id,
+        slots,
+        tip,
+    })
+}
+
+/// Hex pool ID (blake2b-224 of the cold verification key) for a block issuer
+fn pool_id_from_vkey(vkey_hex: &str) -> Option<String> {
+    let vkey = hex_decode(vkey_hex)?;
+    let hash = blake2b_simd::Params::new().hash_length(28).hash(&vkey);
+    Some(
+        hash.as_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect(),
+    )
+}
+
+fn hex_decode(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(s.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const OUR_VKEY: &str = "aa11";
+    const OTHER_VKEY: &str = "bb22";
+
+    fn create_db(path: &Path) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE chain (id INTEGER PRIMARY KEY, block_number INTEGER, slot_number INTEGER, node_vkey TEXT, orphaned INTEGER DEFAULT 0);
+             CREATE TABLE slots (id INTEGER PRIMARY KEY, epoch INTEGER, pool_id TEXT, slot_qty INTEGER, slots TEXT, hash TEXT);",
+        )
+        .unwrap();
+        let pool_id = pool_id_from_vkey(OUR_VKEY).unwrap();
+        conn.execute(
+            "INSERT INTO slots (epoch, pool_id, slot_qty, slots) VALUES (500, ?1, 5, '[1500,1100,1200,1300,1400]')",
+            [&pool_id],
+        )
+        .unwrap();
+        for (block, slot, vkey, orphaned) in [
+            (10, 1100, OUR_VKEY, 0),
+            (11, 1200, OUR_VKEY, 1),
+            (11, 1200, OTHER_VKEY, 0),
+            (12, 1300, OTHER_VKEY, 0),
+            (13, 1350, OTHER_VKEY, 0),
+        ] {
+            conn.execute(
+                "INSERT INTO chain (block_number, slot_number, node_vkey, orphaned) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![block, slot, vkey, orphaned],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_read_snapshot_outcomes() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("cncli.db");
+        create_db(&path);
+
+        let snapshot = read_snapshot(&path, Some(500)).unwrap();
+        assert_eq!(snapshot.tip, Some((13, 1350)));
+        assert_eq!(
+            snapshot.slots,
+            vec![
+                (1100, SlotOutcome::Adopted),
+                (1200, SlotOutcome::Orphaned),
+                (1300, SlotOutcome::Missed),
+                (1400, SlotOutcome::Upcoming),
+                (1500, SlotOutcome::Upcoming),
+            ]
+        );
+        assert_eq!(snapshot.count(SlotOutcome::Upcoming), 2);
+        assert_eq!(snapshot.next_slot(1350), Some(1400));
+
+        assert_eq!(read_snapshot(&path, None).unwrap().epoch, 500);
+        assert!(read_snapshot(&path, Some(501)).is_err());
+    }
+
+    #[test]
+    fn test_leader_watch() {
+        let mut watch = LeaderWatch::new(1000, None, None);
+        watch.observe(990, Some(4), None);
+        assert!(!watch.forging(990));
+        watch.observe(1001, Some(4), None);
+        assert!(watch.forging(1001));
+        watch.observe(1003, Some(5), None);
+        assert_eq!(watch.result, Some((ForgeResult::Adopted, 1003)));
+        assert!(!watch.forging(1003));
+
+        let mut watch = LeaderWatch::new(1000, Some(5), None);
+        watch.observe(1002, Some(5), Some(1));
+        assert_eq!(watch.result, Some((ForgeResult::NotAdopted, 1002)));
+
+        let mut watch = LeaderWatch::new(1000, Some(5), None);
+        watch.observe(1010, Some(5), None);
+        assert_eq!(watch.result, None);
+        watch.observe(1000 + FORGE_RESULT_SLOTS, Some(5), None);
+        assert_eq!(
+            watch.result,
+            Some((ForgeResult::NoBlock, 1000 + FORGE_RESULT_SLOTS))
+        );
+    }
+
+    #[test]
+    fn test_pool_id_from_vkey() {
+        assert_eq!(pool_id_from_vkey("zz"), None);
+        assert_eq!(pool_id_from_vkey("abc"), None);
+        assert_eq!(pool_id_from_vkey(OUR_VKEY).unwrap().len(), 56);
+    }
+}
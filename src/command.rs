@@ -0,0 +1,162 @@
+//! Command line (`:`) for actions without a dedicated key
+//!
+//! Like the command mode of vim, htop or k9s: `:node bp1`, `:theme dark-teal`,
+//! `:refresh 5`, `:export /tmp/out.csv`, `:silence 30m`.
+
+use crate::themes::Theme;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Available commands with their arguments, for hints and help
+pub const COMMANDS: &[(&str, &str)] = &[
+    ("node", "<name|number>"),
+    ("theme", "<name>"),
+    ("refresh", "<seconds>"),
+    ("export", "<path.csv>"),
+    ("silence", "<duration|off>"),
+    ("quit", ""),
+];
+
+/// A parsed command
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Select a node by name or 1-based number
+    Node(String),
+    Theme(Theme),
+    /// Change the refresh interval
+    Refresh(Duration),
+    /// Export the current node's history to CSV
+    Export(PathBuf),
+    /// Hide alerts in the header for a while (`None` ends the silence)
+    Silence(Option<Duration>),
+    Quit,
+}
+
+/// Parse a command line (without the leading `:`)
+///
+/// Command names may be abbreviated to any unique prefix.
+pub fn parse(input: &str) -> Result<Command, String> {
+    let input = input.trim();
+    let (name, arg) = match input.split_once(char::is_whitespace) {
+        Some((name, arg)) => (name, arg.trim()),
+        None => (input, ""),
+    };
+    if name.is_empty() {
+        return Err("Empty command".to_string());
+    }
+
+    let matches: Vec<&str> = COMMANDS
+        .iter()
+        .map(|(command, _)| *command)
+        .filter(|command| command.starts_with(name))
+        .collect();
+    let command = match matches.as_slice() {
+        [command] => *command,
+        [] => return Err(format!("Unknown command '{}'", name)),
+        _ => {
+            return Err(format!(
+                "Ambiguous command '{}' ({})",
+                name,
+                matches.join(", ")
+            ))
+        }
+    };
+
+    let require_arg = || {
+        if arg.is_empty() {
+            let usage = COMMANDS
+                .iter()
+                .find(|(c, _)| *c == command)
+                .map(|(_, usage)| *usage)
+                .unwrap_or_default();
+            Err(format!("Usage: :{} {}", command, usage))
+        } else {
+            Ok(arg)
+        }
+    };
+
+    match command {
+        "node" => Ok(Command::Node(require_arg()?.to_string())),
+        "theme" => {
+            let name = require_arg()?;
+            Theme::from_name(name)
+                .map(Command::Theme)
+                .ok_or_else(|| format!("Unknown theme '{}'", name))
+        }
+        "refresh" => match require_arg()?.trim_end_matches('s').parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(Command::Refresh(Duration::from_secs(secs))),
+            _ => Err(format!("Invalid refresh interval '{}'", arg)),
+        },
+        "export" => Ok(Command::Export(PathBuf::from(require_arg()?))),
+        "silence" => match require_arg()? {
+            "off" => Ok(Command::Silence(None)),
+            duration => parse_duration(duration)
+                .map(|d| Command::Silence(Some(d)))
+                .ok_or_else(|| format!("Invalid duration '{}' (e.g. 30m, 2h)", duration)),
+        },
+        _ => Ok(Command::Quit),
+    }
+}
+
+/// Parse a duration like `90s`, `30m`, `2h` or `1d` (bare numbers are minutes)
+fn parse_duration(s: &str) -> Option<Duration> {
+    let (num, unit_secs) = match s.char_indices().last()? {
+        (i, 's') => (&s[..i], 1),
+        (i, 'm') => (&s[..i], 60),
+        (i, 'h') => (&s[..i], 3600),
+        (i, 'd') => (&s[..i], 86400),
+        _ => (s, 60),
+    };
+    match num.parse::<u64>().ok()? {
+        0 => None,
+        n => Some(Duration::from_secs(n * unit_secs)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse("node bp1"), Ok(Command::Node("bp1".to_string())));
+        assert_eq!(
+            parse("theme dark-teal"),
+            Ok(Command::Theme(Theme::DarkTeal))
+        );
+        assert_eq!(
+            parse("refresh 5"),
+            Ok(Command::Refresh(Duration::from_secs(5)))
+        );
+        assert_eq!(
+            parse("export /tmp/out.csv"),
+            Ok(Command::Export(PathBuf::from("/tmp/out.csv")))
+        );
+        assert_eq!(
+            parse("silence 30m"),
+            Ok(Command::Silence(Some(Duration::from_secs(1800))))
+        );
+        assert_eq!(parse("silence off"), Ok(Command::Silence(None)));
+        assert_eq!(parse("q"), Ok(Command::Quit));
+        // Unique prefixes abbreviate
+        assert_eq!(parse("th monokai"), Ok(Command::Theme(Theme::Monokai)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse("").is_err());
+        assert!(parse("frobnicate").is_err());
+        assert!(parse("node").unwrap_err().starts_with("Usage"));
+        assert!(parse("theme solarized").is_err());
+        assert!(parse("refresh 0").is_err());
+        assert!(parse("silence soon").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("15"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_duration("0m"), None);
+    }
+}
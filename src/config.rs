@@ -7,56 +7,310 @@
 //!
 //! When a config file exists and no CLI host/port is specified, multi-node mode is used.
 
-use clap::Parser;
-use serde::Deserialize;
+use crate::agent::AgentConfig;
+use crate::blocklist::Firewall;
+use crate::chain_api::ChainApiBackend;
+use crate::format::{format_bytes, format_duration, format_number};
+use crate::genesis::{self, NetworkParams, DEFAULT_ACTIVE_SLOTS_COEFF};
+use crate::geoip::GeoIpConfig;
+use crate::hardfork::HardForkConfig;
+use crate::heartbeat::HeartbeatConfig;
+use crate::import::{ImportRequest, ImportSource};
+use crate::influx::InfluxConfig;
+use crate::keys::KeyBindings;
+use crate::metrics::{PeerTargets, RawMetricFilter};
+use crate::mqtt::MqttConfig;
+use crate::notify::NotifyChannel;
+use crate::pooltool::PooltoolConfig;
+use crate::reference_tip::{ReferenceSource, ReferenceTipConfig};
+use crate::report::{ReportFormat, ReportKind, ReportRequest};
+use crate::storage::RetentionPolicy;
+use crate::timezone::DisplayTimezone;
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+/// Shortest chain API cache lifetime, so a small `chain_api_ttl_secs` can't
+/// query the API on every tick
+const MIN_CHAIN_API_TTL_SECS: u64 = 60;
+
 /// A TUI for monitoring Cardano nodes
 #[derive(Parser, Debug, Clone)]
 #[command(name = "sview")]
 #[command(author, version, about, long_about = None)]
 pub struct CliArgs {
     /// Display name for the node
-    #[arg(short, long, env = "NODE_NAME")]
+    #[arg(short, long, env = "NODE_NAME", global = true)]
     pub node_name: Option<String>,
 
     /// Cardano network (mainnet, preprod, preview, etc.)
-    #[arg(long, env = "CARDANO_NETWORK")]
+    #[arg(long, env = "CARDANO_NETWORK", global = true)]
     pub network: Option<String>,
 
     /// Prometheus metrics host
-    #[arg(long, env = "PROM_HOST")]
+    #[arg(long, env = "PROM_HOST", global = true)]
     pub prom_host: Option<String>,
 
     /// Prometheus metrics port
-    #[arg(short, long, env = "PROM_PORT")]
+    #[arg(short, long, env = "PROM_PORT", global = true)]
     pub prom_port: Option<u16>,
 
-    /// Request timeout in seconds
-    #[arg(long, env = "PROM_TIMEOUT", default_value_t = 3)]
-    pub prom_timeout_secs: u64,
+    /// Request timeout in seconds [default: 3, or `timeout_secs` from the config file]
+    #[arg(long, env = "PROM_TIMEOUT", global = true)]
+    pub prom_timeout_secs: Option<u64>,
 
-    /// Refresh interval in seconds
-    #[arg(short, long, env = "REFRESH_INTERVAL", default_value_t = 2)]
-    pub refresh_interval_secs: u64,
+    /// Refresh interval in seconds [default: 2, or `refresh_interval_secs` from the config file]
+    #[arg(short, long, env = "REFRESH_INTERVAL", global = true)]
+    pub refresh_interval_secs: Option<u64>,
 
     /// History length for sparklines (number of data points to keep)
-    #[arg(long, env = "HISTORY_LENGTH", default_value_t = 60)]
+    #[arg(long, env = "HISTORY_LENGTH", default_value_t = 60, global = true)]
     pub history_length: usize,
 
-    /// Epoch length in slots (432000 for mainnet, 86400 for testnets)
-    #[arg(long, env = "EPOCH_LENGTH", default_value_t = 432000)]
-    pub epoch_length: u64,
+    /// Epoch length in slots [default: the network's, or 432000]
+    #[arg(long, env = "EPOCH_LENGTH", global = true)]
+    pub epoch_length: Option<u64>,
+
+    /// Path to the node's local socket (enables mempool inspection)
+    #[arg(long, env = "CARDANO_NODE_SOCKET_PATH", global = true)]
+    pub socket_path: Option<PathBuf>,
+
+    /// Shelley genesis file or node config.json (sets epoch and slot lengths)
+    #[arg(long, env = "SVIEW_GENESIS_FILE", global = true)]
+    pub genesis_file: Option<PathBuf>,
 
     /// Path to config file (default: ~/.config/sview/config.toml)
-    #[arg(short, long, env = "SVIEW_CONFIG")]
+    #[arg(short, long, env = "SVIEW_CONFIG", global = true)]
     pub config: Option<PathBuf>,
 
-    /// Export collected metrics to CSV file and exit
-    #[arg(long, value_name = "FILE")]
+    /// Same as `sview export FILE`
+    #[arg(long, value_name = "FILE", hide = true)]
     pub export: Option<PathBuf>,
+
+    /// Same as `sview alert-report PERIOD`
+    #[arg(long, value_name = "PERIOD", value_parser = parse_report_period, hide = true)]
+    pub alert_report: Option<Duration>,
+
+    /// Same as `sview report KIND`
+    #[arg(long, value_enum, value_name = "KIND", hide = true)]
+    pub report: Option<ReportKind>,
+
+    /// Output format for --report
+    #[arg(
+        long,
+        value_enum,
+        default_value = "text",
+        requires = "report",
+        hide = true
+    )]
+    pub format: ReportFormat,
+
+    /// Add or override a node: NAME=HOST:PORT[:ROLE] (repeatable; matches config nodes by name)
+    #[arg(
+        long = "node",
+        value_name = "NAME=HOST:PORT[:ROLE]",
+        env = "SVIEW_NODES",
+        value_delimiter = ',',
+        global = true
+    )]
+    pub nodes: Vec<NodeOverride>,
+
+    /// Same as `sview check`
+    #[arg(long, hide = true)]
+    pub check_config: bool,
+
+    /// Same as `sview prune`
+    #[arg(long, hide = true)]
+    pub prune: bool,
+
+    /// Same as `sview import SOURCE`
+    #[arg(long, value_enum, value_name = "SOURCE", requires = "url", hide = true)]
+    pub import: Option<ImportSource>,
+
+    /// Server URL for --import
+    #[arg(long, requires = "import", hide = true)]
+    pub url: Option<String>,
+
+    /// Label selector for --import
+    #[arg(long, value_name = "SELECTOR", requires = "import", hide = true)]
+    pub query: Option<String>,
+
+    /// How far back --import and --report reach
+    #[arg(long, value_name = "PERIOD", value_parser = parse_report_period, default_value = "30d", hide = true)]
+    pub range: Duration,
+
+    /// Same as `sview discover`
+    #[arg(long, hide = true)]
+    pub discover: bool,
+
+    /// Delete the cached peer geolocations and exit
+    #[arg(long)]
+    pub flush_geoip_cache: bool,
+
+    /// Run against simulated nodes instead of real ones (ignores the config file)
+    #[arg(long, global = true)]
+    pub demo: bool,
+
+    /// Serve a read-only web dashboard on this address while the TUI runs (e.g. 127.0.0.1:8080)
+    #[arg(long, value_name = "ADDR", env = "SVIEW_WEB", global = true)]
+    pub web: Option<SocketAddr>,
+
+    /// Mirror the nodes of an sview running with --web at this address, read-only
+    #[arg(long, value_name = "HOST:PORT", conflicts_with = "demo")]
+    pub follow: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// What to do; without a subcommand sview starts the TUI (`monitor`)
+///
+/// The subcommands replace the older `--export`, `--report`,
+/// `--alert-report`, `--check-config`, `--import`, `--prune` and `--discover`
+/// flags, which still work. Before
+/// anything else runs, `CliArgs::apply_command` maps a subcommand onto those
+/// flags.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Monitor the configured nodes in the TUI (the default)
+    Monitor,
+    /// Export collected metrics to a CSV file
+    Export {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    /// Print a report from stored history
+    Report {
+        #[arg(value_enum)]
+        kind: ReportKind,
+        /// Output format
+        #[arg(long, value_enum, default_value = "text")]
+        format: ReportFormat,
+        /// How far back the report reaches (e.g. 30d, 12w)
+        #[arg(long, value_name = "PERIOD", value_parser = parse_report_period, default_value = "30d")]
+        range: Duration,
+    },
+    /// Summarize the alerts recorded over a period
+    AlertReport {
+        /// How far back to look (e.g. 24h, 7d, 2w)
+        #[arg(value_name = "PERIOD", value_parser = parse_report_period)]
+        period: Duration,
+    },
+    /// Fetch every node once and print its health (exit code 0 ok, 1 warning, 2 critical)
+    Status {
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Validate the config file and try each node's metrics endpoint
+    Check,
+    /// Work with the config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Backfill history from an external source
+    Import {
+        #[arg(value_enum)]
+        source: ImportSource,
+        /// Server URL (e.g. http://prom:9090)
+        #[arg(long)]
+        url: String,
+        /// Label selector for the node's series (e.g. 'instance="relay1:12798"')
+        #[arg(long, value_name = "SELECTOR")]
+        query: Option<String>,
+        /// How far back to import (e.g. 30d, 12w)
+        #[arg(long, value_name = "PERIOD", value_parser = parse_report_period, default_value = "30d")]
+        range: Duration,
+    },
+    /// Remove history beyond each node's retention period
+    Prune,
+    /// Scan localhost and `discover_subnets` for node metrics endpoints and offer to add them
+    Discover,
+    /// Print a shell completion script
+    Completions {
+        #[arg(value_enum)]
+        shell: clap_complete::Shell,
+    },
+    /// Print the man page (roff)
+    Man,
+    /// Serve this host's peers and network counters to remote sview instances
+    Agent {
+        /// Address to listen on (this machine only by default)
+        #[arg(long, value_name = "ADDR", default_value_t = SocketAddr::from(([127, 0, 0, 1], crate::agent::DEFAULT_PORT)))]
+        listen: SocketAddr,
+        /// Token clients must send (`[nodes.agent] token`); visible to other
+        /// users in the process list, so prefer the variable or --token-file
+        #[arg(long, env = "SVIEW_AGENT_TOKEN", hide_env_values = true)]
+        token: Option<String>,
+        /// Read the token from the first line of this file
+        #[arg(long, value_name = "FILE", conflicts_with = "token")]
+        token_file: Option<PathBuf>,
+        /// Serve HTTPS with this certificate chain (PEM)
+        #[arg(long, value_name = "FILE", requires = "tls_key")]
+        tls_cert: Option<PathBuf>,
+        /// Private key for --tls-cert (PEM)
+        #[arg(long, value_name = "FILE", requires = "tls_cert")]
+        tls_key: Option<PathBuf>,
+        /// Require client certificates signed by this CA (PEM)
+        #[arg(long, value_name = "FILE", requires = "tls_cert")]
+        client_ca: Option<PathBuf>,
+    },
+}
+
+/// `sview config` actions
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigAction {
+    /// Check the config file without contacting any node
+    Validate,
+}
+
+impl CliArgs {
+    /// Set the flags equivalent to `command`, so the rest of sview only
+    /// reads flags
+    ///
+    /// `status`, `config`, `completions`, `man` and `agent` have no flag form
+    /// and are left to the caller.
+    pub fn apply_command(&mut self, command: Command) {
+        match command {
+            Command::Export { file } => self.export = Some(file),
+            Command::Report {
+                kind,
+                format,
+                range,
+            } => {
+                self.report = Some(kind);
+                self.format = format;
+                self.range = range;
+            }
+            Command::AlertReport { period } => self.alert_report = Some(period),
+            Command::Check => self.check_config = true,
+            Command::Import {
+                source,
+                url,
+                query,
+                range,
+            } => {
+                self.import = Some(source);
+                self.url = Some(url);
+                self.query = query;
+                self.range = range;
+            }
+            Command::Prune => self.prune = true,
+            Command::Discover => self.discover = true,
+            Command::Monitor
+            | Command::Status { .. }
+            | Command::Config { .. }
+            | Command::Completions { .. }
+            | Command::Man
+            | Command::Agent { .. } => {}
+        }
+    }
 }
 
 /// Configuration file structure (TOML)
@@ -69,6 +323,25 @@ pub struct FileConfig {
     /// Node definitions (array of tables: [[nodes]] in TOML)
     #[serde(default)]
     pub nodes: Vec<NodeConfig>,
+
+    /// Keybindings (`[keys]`): action name → key or list of keys
+    #[serde(default)]
+    pub keys: KeyBindings,
+
+    /// Alert notification channels (array of tables: [[notify]] in TOML)
+    #[serde(default)]
+    pub notify: Vec<NotifyChannel>,
+
+    /// Settings shared by the nodes of a group (`[groups.<name>]`)
+    #[serde(default)]
+    pub groups: HashMap<String, GroupConfig>,
+}
+
+/// `[groups.<name>]`: settings for every node with `group = "<name>"`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct GroupConfig {
+    /// Notification channels the group's alerts go to (default: all)
+    pub notify: Option<Vec<String>>,
 }
 
 /// Global settings in config file
@@ -98,6 +371,170 @@ pub struct GlobalConfig {
     /// Color theme for TUI
     #[serde(default = "default_theme")]
     pub theme: String,
+
+    /// Koios API base URL override (defaults to the public endpoint per network)
+    pub koios_url: Option<String>,
+
+    /// Blockfrost project ID (uses Blockfrost instead of Koios when set)
+    pub blockfrost_project_id: Option<String>,
+
+    /// How long chain API responses are cached (seconds)
+    #[serde(default = "default_chain_api_ttl")]
+    pub chain_api_ttl_secs: u64,
+
+    /// Epochs shown in the Rewards panel
+    #[serde(default = "default_rewards_epochs")]
+    pub rewards_epochs: usize,
+
+    /// Dashboard layout: one entry per column, each a panel name or a list of
+    /// panels stacked vertically (e.g. `["chain", ["network", "forging"], "resources"]`)
+    #[serde(default)]
+    pub layout: Vec<LayoutColumn>,
+
+    /// Size budget for sview's data directory in MB (no quota check when unset)
+    pub storage_quota_mb: Option<u64>,
+
+    /// Warn when free space on the data directory's filesystem drops below this (MB)
+    #[serde(default = "default_min_free_disk")]
+    pub min_free_disk_mb: u64,
+
+    /// Hours before the epoch boundary to raise an info alert (0 disables)
+    #[serde(default = "default_epoch_alert_hours")]
+    pub epoch_alert_hours: u64,
+
+    /// Health indicator thresholds for all nodes (`[global.thresholds]`)
+    #[serde(default)]
+    pub thresholds: HealthThresholds,
+
+    /// Actively measure peer latency with TCP connects: "off", "missing" or "all"
+    #[serde(default)]
+    pub peer_rtt_probe: RttProbeMode,
+
+    /// Fall back to ICMP ping for peers still without an RTT
+    #[serde(default)]
+    pub peer_ping: bool,
+
+    /// Alert when this many incoming connections share a /24 subnet (0 disables)
+    #[serde(default = "default_peer_subnet_alert")]
+    pub peer_subnet_alert: usize,
+
+    /// Alert when one network (ASN) holds this share of incoming connections (0 disables)
+    #[serde(default = "default_peer_network_alert_percent")]
+    pub peer_network_alert_percent: u8,
+
+    /// Alert when one network (ASN) holds this share of outgoing connections
+    /// (0 disables)
+    #[serde(default = "default_peer_outgoing_network_alert_percent")]
+    pub peer_outgoing_network_alert_percent: u8,
+
+    /// Consecutive failed scrapes before a node counts as offline and alerts (0 disables the alert)
+    #[serde(default = "default_offline_after_failures")]
+    pub offline_after_failures: u32,
+
+    /// Seconds a node must stay unreachable before the Node Unreachable alert fires
+    #[serde(default = "default_node_down_grace_secs")]
+    pub node_down_grace_secs: u64,
+
+    /// Alert when the node switches forks this many times within an hour (0 disables)
+    #[serde(default = "default_fork_alert_per_hour")]
+    pub fork_alert_per_hour: u64,
+
+    /// Alert when a block producer's live stake moves by this many ADA between lookups (0 disables)
+    #[serde(default = "default_delegation_alert_ada")]
+    pub delegation_alert_ada: u64,
+
+    /// Alert when connection errors and handshake failures stay at or above
+    /// this many per minute for five minutes (0 disables)
+    #[serde(default = "default_connection_error_alert_per_min")]
+    pub connection_error_alert_per_min: f64,
+
+    /// Upcoming hard fork to count down to (`[global.hard_fork]`)
+    #[serde(default)]
+    pub hard_fork: HardForkConfig,
+
+    /// Push history to InfluxDB or VictoriaMetrics (`[global.influx]`)
+    #[serde(default)]
+    pub influx: InfluxConfig,
+
+    /// Publish node health and alerts to an MQTT broker (`[global.mqtt]`)
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+
+    /// Dead-man switch pings to a monitoring service (`[global.heartbeat]`)
+    #[serde(default)]
+    pub heartbeat: HeartbeatConfig,
+
+    /// Network tip that node tips are compared with (`[global.reference_tip]`)
+    #[serde(default)]
+    pub reference_tip: ReferenceTipConfig,
+
+    /// Where peer locations are looked up (`[global.geoip]`)
+    #[serde(default)]
+    pub geoip: GeoIpConfig,
+
+    /// Look up the latest cardano-node release on GitHub once a day
+    #[serde(default)]
+    pub check_node_release: bool,
+
+    /// Raw metrics kept for the focus view: names or prefixes ending in `*`
+    #[serde(default = "default_raw_metrics")]
+    pub raw_metrics: Vec<String>,
+
+    /// File banned peer IPs are appended to (defaults to the data directory)
+    pub blocklist_file: Option<PathBuf>,
+
+    /// Firewall the suggested ban command targets: "iptables" or "nft"
+    #[serde(default)]
+    pub firewall: Firewall,
+
+    /// Days of metric history and alert events kept on disk
+    #[serde(default = "default_retention_days")]
+    pub retention_days: u64,
+
+    /// Minimum seconds between saved history samples
+    #[serde(default = "default_sample_interval")]
+    pub sample_interval_secs: u64,
+
+    /// Timezone for displayed and exported timestamps: "local", "utc" or an offset like "+02:00"
+    #[serde(default)]
+    pub timezone: DisplayTimezone,
+
+    /// Show the clock, network slot and epoch countdown in the footer
+    #[serde(default = "default_status_clock")]
+    pub status_clock: bool,
+
+    /// Subnets scanned by `sview discover` in addition to localhost (e.g. "10.0.0.0/24")
+    #[serde(default)]
+    pub discover_subnets: Vec<String>,
+
+    /// Metrics ports probed by `sview discover`
+    #[serde(default = "default_discover_ports")]
+    pub discover_ports: Vec<u16>,
+}
+
+fn default_raw_metrics() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+fn default_status_clock() -> bool {
+    true
+}
+
+fn default_discover_ports() -> Vec<u16> {
+    crate::discover::DEFAULT_PORTS.to_vec()
+}
+
+/// When peer latency is measured actively instead of read from kernel stats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RttProbeMode {
+    /// Only use kernel RTT from `ss`
+    #[default]
+    Off,
+    /// Probe peers that have no kernel RTT (idle connections, macOS)
+    Missing,
+    /// Probe every peer
+    All,
 }
 
 impl Default for GlobalConfig {
@@ -109,6 +546,41 @@ impl Default for GlobalConfig {
             history_length: default_history(),
             epoch_length: default_epoch_length(),
             theme: default_theme(),
+            koios_url: None,
+            blockfrost_project_id: None,
+            chain_api_ttl_secs: default_chain_api_ttl(),
+            rewards_epochs: default_rewards_epochs(),
+            layout: Vec::new(),
+            storage_quota_mb: None,
+            min_free_disk_mb: default_min_free_disk(),
+            epoch_alert_hours: default_epoch_alert_hours(),
+            thresholds: HealthThresholds::default(),
+            peer_rtt_probe: RttProbeMode::default(),
+            peer_ping: false,
+            peer_subnet_alert: default_peer_subnet_alert(),
+            peer_network_alert_percent: default_peer_network_alert_percent(),
+            peer_outgoing_network_alert_percent: default_peer_outgoing_network_alert_percent(),
+            offline_after_failures: default_offline_after_failures(),
+            node_down_grace_secs: default_node_down_grace_secs(),
+            fork_alert_per_hour: default_fork_alert_per_hour(),
+            delegation_alert_ada: default_delegation_alert_ada(),
+            connection_error_alert_per_min: default_connection_error_alert_per_min(),
+            hard_fork: HardForkConfig::default(),
+            influx: InfluxConfig::default(),
+            mqtt: MqttConfig::default(),
+            heartbeat: HeartbeatConfig::default(),
+            reference_tip: ReferenceTipConfig::default(),
+            geoip: GeoIpConfig::default(),
+            check_node_release: false,
+            raw_metrics: default_raw_metrics(),
+            blocklist_file: None,
+            firewall: Firewall::default(),
+            retention_days: default_retention_days(),
+            sample_interval_secs: default_sample_interval(),
+            timezone: DisplayTimezone::default(),
+            status_clock: default_status_clock(),
+            discover_subnets: Vec::new(),
+            discover_ports: default_discover_ports(),
         }
     }
 }
@@ -127,17 +599,201 @@ pub struct NodeConfig {
     #[serde(default = "default_port")]
     pub port: u16,
 
+    /// P2P listening port(s), for telling the node's connections apart in
+    /// the peer list (`p2p_port = 3001` or `p2p_port = [3001, 3002]`)
+    pub p2p_port: Option<P2pPorts>,
+
     /// Node role (relay, bp/block-producer)
     #[serde(default)]
     pub role: NodeRole,
 
+    /// Group shown together in the tab bar (e.g. a pool's BP and relays)
+    pub group: Option<String>,
+
+    /// Notification channels (by name) this node's alerts go to; overrides
+    /// the group's list (default: all channels)
+    pub notify: Option<Vec<String>>,
+
     /// Network override for this node
     pub network: Option<String>,
+
+    /// Bech32 pool ID (enables pool info lookups via Koios/Blockfrost)
+    pub pool_id: Option<String>,
+
+    /// Path to the node's local socket (enables mempool inspection)
+    pub socket_path: Option<PathBuf>,
+
+    /// Path to cncli's sqlite database (enables leader schedule and block outcomes)
+    pub cncli_db: Option<PathBuf>,
+
+    /// cardano-cli binary for the opcert check (defaults to `cardano-cli` on PATH)
+    pub cli_path: Option<PathBuf>,
+
+    /// Operational certificate checked with `cardano-cli query kes-period-info` (needs socket_path)
+    pub opcert_path: Option<PathBuf>,
+
+    /// Path to the node's JSON log (enables per-peer block propagation stats)
+    pub log_file: Option<PathBuf>,
+
+    /// Node version for nodes that don't export build info (e.g. "10.1.4")
+    pub version: Option<String>,
+
+    /// Health thresholds for this node (overrides `[global.thresholds]`)
+    pub thresholds: Option<HealthThresholds>,
+
+    /// History retention in days (overrides the global setting)
+    pub retention_days: Option<u64>,
+
+    /// Seconds between saved history samples (overrides the global setting)
+    pub sample_interval_secs: Option<u64>,
+
+    /// Shelley genesis file or node config.json (sets epoch and slot lengths)
+    pub genesis_file: Option<PathBuf>,
+
+    /// Byron or Shelley genesis hash of a public network (alternative to genesis_file)
+    pub genesis_hash: Option<String>,
+
+    /// Metrics endpoint scheme (http or https)
+    #[serde(default)]
+    pub scheme: Scheme,
+
+    /// PEM CA certificate to trust for https endpoints
+    pub ca_cert: Option<PathBuf>,
+
+    /// Accept invalid or self-signed TLS certificates
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+
+    /// HTTP basic auth credentials for the metrics endpoint
+    pub basic_auth: Option<BasicAuth>,
+
+    /// Bearer token for the metrics endpoint
+    pub bearer_token: Option<String>,
+
+    /// HTTP or SOCKS5 proxy for the metrics endpoint (e.g. "socks5h://127.0.0.1:1080")
+    pub proxy: Option<String>,
+
+    /// Reach the metrics endpoint through an SSH local port forward
+    pub ssh_tunnel: Option<SshTunnelConfig>,
+
+    /// Health or readiness endpoint: a full URL, or a path on the metrics host (e.g. "/health")
+    pub health_url: Option<String>,
+
+    /// `sview agent` on the node's host, for peers and host stats (`[nodes.agent]`)
+    pub agent: Option<AgentConfig>,
+
+    /// Tip submission to pooltool.io (`[nodes.pooltool]`, needs `log_file`)
+    pub pooltool: Option<PooltoolConfig>,
+
+    /// Extra metric rows to show in the dashboard panels (`[[nodes.custom_metrics]]`)
+    #[serde(default)]
+    pub custom_metrics: Vec<CustomMetric>,
 }
 
-/// Node role for display/behavior hints
+/// A node's P2P listening port, or several
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum P2pPorts {
+    One(u16),
+    Many(Vec<u16>),
+}
+
+impl P2pPorts {
+    pub fn ports(&self) -> Vec<u16> {
+        match self {
+            P2pPorts::One(port) => vec![*port],
+            P2pPorts::Many(ports) => ports.clone(),
+        }
+    }
+}
+
+/// URL scheme of a node's metrics endpoint
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
 #[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    #[default]
+    Http,
+    Https,
+}
+
+impl std::fmt::Display for Scheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scheme::Http => write!(f, "http"),
+            Scheme::Https => write!(f, "https"),
+        }
+    }
+}
+
+/// HTTP basic auth credentials
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BasicAuth {
+    pub user: String,
+    #[serde(default)]
+    pub pass: String,
+}
+
+/// SSH port forward to a metrics endpoint behind a bastion host
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SshTunnelConfig {
+    /// ssh destination, e.g. "user@bastion.example.com" or a Host alias from ~/.ssh/config
+    pub destination: String,
+    /// ssh port on the bastion (default from ssh config, usually 22)
+    pub ssh_port: Option<u16>,
+    /// Private key to authenticate with
+    pub identity_file: Option<PathBuf>,
+    /// Local port to forward (default: any free port)
+    pub local_port: Option<u16>,
+}
+
+/// Connection settings for a node's metrics endpoint (TLS, auth, proxying)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EndpointOptions {
+    pub scheme: Scheme,
+    pub ca_cert: Option<PathBuf>,
+    pub insecure_skip_verify: bool,
+    pub basic_auth: Option<BasicAuth>,
+    pub bearer_token: Option<String>,
+    pub proxy: Option<String>,
+    pub ssh_tunnel: Option<SshTunnelConfig>,
+    pub health_url: Option<String>,
+}
+
+impl EndpointOptions {
+    /// Endpoint settings from a `[[nodes]]` entry
+    fn from_node_config(node: &NodeConfig) -> Result<Self> {
+        if node.basic_auth.is_some() && node.bearer_token.is_some() {
+            anyhow::bail!("basic_auth and bearer_token are mutually exclusive");
+        }
+        if node.proxy.is_some() && node.ssh_tunnel.is_some() {
+            anyhow::bail!("proxy and ssh_tunnel are mutually exclusive");
+        }
+        if unix_socket_path(&node.host).is_some()
+            && (node.proxy.is_some() || node.ssh_tunnel.is_some())
+        {
+            anyhow::bail!("proxy and ssh_tunnel can't be used with a unix socket host");
+        }
+        if let Some(ca_cert) = &node.ca_cert {
+            if !ca_cert.is_file() {
+                anyhow::bail!("ca_cert {} not found", ca_cert.display());
+            }
+        }
+        Ok(Self {
+            scheme: node.scheme,
+            ca_cert: node.ca_cert.clone(),
+            insecure_skip_verify: node.insecure_skip_verify,
+            basic_auth: node.basic_auth.clone(),
+            bearer_token: node.bearer_token.clone(),
+            proxy: node.proxy.clone(),
+            ssh_tunnel: node.ssh_tunnel.clone(),
+            health_url: node.health_url.clone(),
+        })
+    }
+}
+
+/// Node role for display/behavior hints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum NodeRole {
     #[default]
     Relay,
@@ -145,6 +801,372 @@ pub enum NodeRole {
     Bp,
 }
 
+/// Dashboard panels that can be placed in the layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PanelKind {
+    /// Epoch gauge + chain metrics
+    Chain,
+    /// Sync gauge + network and peer metrics
+    Network,
+    /// Memory gauge + resource metrics
+    Resources,
+    /// KES, OpCert and block production metrics
+    Forging,
+    /// Per-epoch pool rewards and ROS from the chain API
+    Rewards,
+    /// Peer sharing, big-ledger and bootstrap peers and churn
+    Governance,
+}
+
+/// A single dashboard column in the layout config
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum LayoutColumn {
+    /// A column containing a single panel
+    Single(PanelKind),
+    /// A column with several panels stacked top to bottom
+    Stack(Vec<PanelKind>),
+}
+
+impl PanelKind {
+    /// Panel title
+    pub fn title(&self) -> &'static str {
+        match self {
+            PanelKind::Chain => "Chain",
+            PanelKind::Network => "Network",
+            PanelKind::Resources => "Resources",
+            PanelKind::Forging => "Block Production",
+            PanelKind::Rewards => "Rewards",
+            PanelKind::Governance => "P2P Governance",
+        }
+    }
+}
+
+impl LayoutColumn {
+    /// Get the panels in this column
+    pub fn panels(&self) -> Vec<PanelKind> {
+        match self {
+            LayoutColumn::Single(panel) => vec![*panel],
+            LayoutColumn::Stack(panels) => panels.clone(),
+        }
+    }
+}
+
+/// A raw Prometheus metric pinned to a dashboard panel
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomMetric {
+    /// Metric name as exposed by the node (the `cardano_node_metrics_` prefix may be left off)
+    pub name: String,
+    /// Row label (defaults to the metric name)
+    pub label: Option<String>,
+    /// How the value is displayed
+    #[serde(default)]
+    pub format: MetricFormat,
+    /// Panel to show the row in (defaults to the panel the metric name suggests)
+    pub panel: Option<PanelKind>,
+}
+
+impl CustomMetric {
+    /// Row label
+    pub fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Panel the row is shown in
+    pub fn panel(&self) -> PanelKind {
+        self.panel
+            .unwrap_or_else(|| crate::metrics::metric_panel(&self.name))
+    }
+}
+
+/// Display format of a custom metric value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricFormat {
+    /// Whole number with thousands separators
+    #[default]
+    Int,
+    /// Number with two decimals
+    Float,
+    /// Byte count (KB/MB/GB)
+    Bytes,
+    /// Ratio between 0 and 1 shown as a percentage
+    Percent,
+    /// Seconds shown as days, hours and minutes
+    Duration,
+}
+
+impl MetricFormat {
+    /// Format a raw metric value
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            MetricFormat::Int => {
+                let grouped = format_number(value.abs().round() as u64);
+                if value.round() < 0.0 {
+                    format!("-{}", grouped)
+                } else {
+                    grouped
+                }
+            }
+            MetricFormat::Float => format!("{:.2}", value),
+            MetricFormat::Bytes => format_bytes(Some(value.max(0.0) as u64)),
+            MetricFormat::Percent => format!("{:.1}%", value * 100.0),
+            MetricFormat::Duration => format_duration(value.max(0.0) as u64),
+        }
+    }
+}
+
+/// Default dashboard layout (forging metrics are shown inside the Chain panel)
+fn default_layout() -> Vec<Vec<PanelKind>> {
+    vec![
+        vec![PanelKind::Chain],
+        vec![PanelKind::Network],
+        vec![PanelKind::Resources],
+    ]
+}
+
+/// Health indicator thresholds (values at which indicators turn yellow/red)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthThresholds {
+    /// Peer count below which peers are shown as warning
+    pub peers_warning: u64,
+    /// Peer count below which peers are shown as critical
+    pub peers_critical: u64,
+    /// Memory usage (GB) at which memory is shown as warning
+    pub memory_warning_gb: f64,
+    /// Memory usage (GB) at which memory is shown as critical
+    pub memory_critical_gb: f64,
+    /// CPU usage (percent of all the host's cores) at which CPU is shown as
+    /// warning
+    pub cpu_warning_percent: f64,
+    /// CPU usage (percent of all the host's cores) at which CPU is shown as
+    /// critical
+    pub cpu_critical_percent: f64,
+    /// Tip age (seconds) at which the tip is shown as warning
+    pub tip_age_warning_secs: u64,
+    /// Tip age (seconds) at which the tip is shown as critical
+    pub tip_age_critical_secs: u64,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            peers_warning: 5,
+            peers_critical: 2,
+            memory_warning_gb: 12.0,
+            memory_critical_gb: 14.0,
+            cpu_warning_percent: 80.0,
+            cpu_critical_percent: 95.0,
+            tip_age_warning_secs: 60,
+            tip_age_critical_secs: 120,
+        }
+    }
+}
+
+/// A single editable threshold (for the settings overlay)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThresholdField {
+    PeersWarning,
+    PeersCritical,
+    MemoryWarning,
+    MemoryCritical,
+    CpuWarning,
+    CpuCritical,
+    TipAgeWarning,
+    TipAgeCritical,
+}
+
+impl ThresholdField {
+    /// All fields in display order
+    pub const ALL: [ThresholdField; 8] = [
+        ThresholdField::PeersWarning,
+        ThresholdField::PeersCritical,
+        ThresholdField::MemoryWarning,
+        ThresholdField::MemoryCritical,
+        ThresholdField::CpuWarning,
+        ThresholdField::CpuCritical,
+        ThresholdField::TipAgeWarning,
+        ThresholdField::TipAgeCritical,
+    ];
+
+    /// Display label
+    pub fn label(&self) -> &'static str {
+        match self {
+            ThresholdField::PeersWarning => "Peers warning below",
+            ThresholdField::PeersCritical => "Peers critical below",
+            ThresholdField::MemoryWarning => "Memory warning at",
+            ThresholdField::MemoryCritical => "Memory critical at",
+            ThresholdField::CpuWarning => "CPU warning at",
+            ThresholdField::CpuCritical => "CPU critical at",
+            ThresholdField::TipAgeWarning => "Tip age warning at",
+            ThresholdField::TipAgeCritical => "Tip age critical at",
+        }
+    }
+}
+
+impl HealthThresholds {
+    /// Format a field's current value for display
+    pub fn display(&self, field: ThresholdField) -> String {
+        match field {
+            ThresholdField::PeersWarning => self.peers_warning.to_string(),
+            ThresholdField::PeersCritical => self.peers_critical.to_string(),
+            ThresholdField::MemoryWarning => format!("{:.1} GB", self.memory_warning_gb),
+            ThresholdField::MemoryCritical => format!("{:.1} GB", self.memory_critical_gb),
+            ThresholdField::CpuWarning => format!("{:.0}%", self.cpu_warning_percent),
+            ThresholdField::CpuCritical => format!("{:.0}%", self.cpu_critical_percent),
+            ThresholdField::TipAgeWarning => format!("{}s", self.tip_age_warning_secs),
+            ThresholdField::TipAgeCritical => format!("{}s", self.tip_age_critical_secs),
+        }
+    }
+
+    /// Step a field up (`steps > 0`) or down, keeping warning/critical consistent
+    pub fn adjust(&mut self, field: ThresholdField, steps: i64) {
+        let step_u64 = |v: u64, step: u64| v.saturating_add_signed(steps * step as i64);
+        match field {
+            ThresholdField::PeersWarning => {
+                self.peers_warning = step_u64(self.peers_warning, 1).max(self.peers_critical);
+            }
+            ThresholdField::PeersCritical => {
+                self.peers_critical = step_u64(self.peers_critical, 1).min(self.peers_warning);
+            }
+            ThresholdField::MemoryWarning => {
+                self.memory_warning_gb = (self.memory_warning_gb + steps as f64 * 0.5)
+                    .clamp(0.5, self.memory_critical_gb);
+            }
+            ThresholdField::MemoryCritical => {
+                self.memory_critical_gb =
+                    (self.memory_critical_gb + steps as f64 * 0.5).max(self.memory_warning_gb);
+            }
+            ThresholdField::CpuWarning => {
+                self.cpu_warning_percent = (self.cpu_warning_percent + steps as f64 * 5.0)
+                    .clamp(5.0, self.cpu_critical_percent);
+            }
+            ThresholdField::CpuCritical => {
+                self.cpu_critical_percent =
+                    (self.cpu_critical_percent + steps as f64 * 5.0).max(self.cpu_warning_percent);
+            }
+            ThresholdField::TipAgeWarning => {
+                self.tip_age_warning_secs =
+                    step_u64(self.tip_age_warning_secs, 10).clamp(10, self.tip_age_critical_secs);
+            }
+            ThresholdField::TipAgeCritical => {
+                self.tip_age_critical_secs =
+                    step_u64(self.tip_age_critical_secs, 10).max(self.tip_age_warning_secs);
+            }
+        }
+    }
+
+    /// Memory warning threshold in bytes
+    pub fn memory_warning_bytes(&self) -> u64 {
+        (self.memory_warning_gb * 1_000_000_000.0) as u64
+    }
+
+    /// Memory critical threshold in bytes
+    pub fn memory_critical_bytes(&self) -> u64 {
+        (self.memory_critical_gb * 1_000_000_000.0) as u64
+    }
+
+    /// Convert to a TOML table for writing back to the config file
+    fn to_toml_table(self) -> toml_edit::Table {
+        let mut table = toml_edit::Table::new();
+        table["peers_warning"] = toml_edit::value(self.peers_warning as i64);
+        table["peers_critical"] = toml_edit::value(self.peers_critical as i64);
+        table["memory_warning_gb"] = toml_edit::value(self.memory_warning_gb);
+        table["memory_critical_gb"] = toml_edit::value(self.memory_critical_gb);
+        table["cpu_warning_percent"] = toml_edit::value(self.cpu_warning_percent);
+        table["cpu_critical_percent"] = toml_edit::value(self.cpu_critical_percent);
+        table["tip_age_warning_secs"] = toml_edit::value(self.tip_age_warning_secs as i64);
+        table["tip_age_critical_secs"] = toml_edit::value(self.tip_age_critical_secs as i64);
+        table
+    }
+}
+
+/// Write thresholds back to the config file, preserving its other contents
+///
+/// Updates the matching `[[nodes]]` entry's `thresholds` table when the node is
+/// defined in the file, otherwise `[global.thresholds]`.
+pub fn write_thresholds(path: &Path, node_name: &str, thresholds: &HealthThresholds) -> Result<()> {
+    let mut doc = read_config_document(path)?;
+
+    let node_table = doc
+        .get_mut("nodes")
+        .and_then(|n| n.as_array_of_tables_mut())
+        .and_then(|nodes| {
+            nodes
+                .iter_mut()
+                .find(|t| t.get("name").and_then(|n| n.as_str()) == Some(node_name))
+        });
+
+    match node_table {
+        Some(table) => {
+            table.insert(
+                "thresholds",
+                toml_edit::Item::Table(thresholds.to_toml_table()),
+            );
+        }
+        None => {
+            let global = doc
+                .entry("global")
+                .or_insert(toml_edit::table())
+                .as_table_mut()
+                .context("[global] is not a table")?;
+            global.insert(
+                "thresholds",
+                toml_edit::Item::Table(thresholds.to_toml_table()),
+            );
+        }
+    }
+
+    write_config_document(path, &doc)
+}
+
+/// Append a `[[nodes]]` entry to the config file, preserving its other contents
+pub fn append_node(path: &Path, node: &NodeRuntimeConfig) -> Result<()> {
+    let mut doc = read_config_document(path)?;
+
+    let nodes = doc
+        .entry("nodes")
+        .or_insert(toml_edit::Item::ArrayOfTables(
+            toml_edit::ArrayOfTables::new(),
+        ))
+        .as_array_of_tables_mut()
+        .context("`nodes` is not an array of tables")?;
+
+    let mut table = toml_edit::Table::new();
+    table["name"] = toml_edit::value(node.name.as_str());
+    table["host"] = toml_edit::value(node.host.as_str());
+    table["port"] = toml_edit::value(node.port as i64);
+    table["role"] = toml_edit::value(match node.role {
+        NodeRole::Relay => "relay",
+        NodeRole::Bp => "bp",
+    });
+    nodes.push(table);
+
+    write_config_document(path, &doc)
+}
+
+/// Read the config file for editing (an empty document if it doesn't exist)
+fn read_config_document(path: &Path) -> Result<toml_edit::DocumentMut> {
+    let existing = if path.exists() {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?
+    } else {
+        String::new()
+    };
+    existing
+        .parse()
+        .with_context(|| format!("Failed to parse {:?}", path))
+}
+
+/// Write an edited config document, creating the config directory if needed
+fn write_config_document(path: &Path, doc: &toml_edit::DocumentMut) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+    fs::write(path, doc.to_string()).with_context(|| format!("Failed to write {:?}", path))
+}
+
 impl std::fmt::Display for NodeRole {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -154,6 +1176,85 @@ impl std::fmt::Display for NodeRole {
     }
 }
 
+/// A node added or overridden from the command line (`--node NAME=HOST:PORT[:ROLE]`)
+///
+/// For nodes already in the config file, empty parts keep the file's value,
+/// e.g. `--node "BP=:12799"` only changes the port.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeOverride {
+    pub name: String,
+    pub host: Option<String>,
+    pub port: Option<u16>,
+    pub role: Option<NodeRole>,
+}
+
+impl NodeOverride {
+    /// Apply the specified parts to a node
+    fn apply(&self, node: &mut NodeRuntimeConfig) {
+        if let Some(host) = &self.host {
+            node.host = host.clone();
+        }
+        if let Some(port) = self.port {
+            node.port = port;
+        }
+        if let Some(role) = self.role {
+            node.role = role;
+        }
+    }
+}
+
+impl std::str::FromStr for NodeOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (name, target) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected NAME=HOST:PORT[:ROLE], got '{}'", s))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("node name must not be empty".to_string());
+        }
+
+        // Split from the right so bracketed IPv6 hosts and unix: paths keep their colons
+        let mut rest = target.trim();
+        let mut role = None;
+        if let Some((head, last)) = rest.rsplit_once(':') {
+            let parsed = match last.to_lowercase().as_str() {
+                "relay" => Some(NodeRole::Relay),
+                "bp" | "block-producer" => Some(NodeRole::Bp),
+                _ => None,
+            };
+            if parsed.is_some() {
+                role = parsed;
+                rest = head;
+            }
+        }
+
+        let (host, port) = match rest.rsplit_once(':') {
+            _ if unix_socket_path(rest).is_some() => (rest, None),
+            Some((host, port)) if !host.ends_with(':') => {
+                let port = if port.is_empty() {
+                    None
+                } else {
+                    Some(
+                        port.parse::<u16>()
+                            .map_err(|_| format!("invalid port '{}' for node '{}'", port, name))?,
+                    )
+                };
+                (host, port)
+            }
+            _ => (rest, None),
+        };
+
+        Ok(Self {
+            name: name.to_string(),
+            host: (!host.is_empty()).then(|| host.to_string()),
+            port,
+            role,
+        })
+    }
+}
+
 // Default value functions for serde
 fn default_network() -> String {
     "mainnet".to_string()
@@ -179,6 +1280,58 @@ fn default_port() -> u16 {
 fn default_theme() -> String {
     "dark-default".to_string()
 }
+fn default_chain_api_ttl() -> u64 {
+    600
+}
+fn default_rewards_epochs() -> usize {
+    10
+}
+fn default_min_free_disk() -> u64 {
+    1024
+}
+fn default_epoch_alert_hours() -> u64 {
+    6
+}
+
+fn default_peer_subnet_alert() -> usize {
+    5
+}
+
+fn default_peer_network_alert_percent() -> u8 {
+    50
+}
+
+fn default_peer_outgoing_network_alert_percent() -> u8 {
+    50
+}
+
+fn default_offline_after_failures() -> u32 {
+    3
+}
+
+fn default_node_down_grace_secs() -> u64 {
+    30
+}
+
+fn default_fork_alert_per_hour() -> u64 {
+    5
+}
+
+fn default_delegation_alert_ada() -> u64 {
+    100_000
+}
+
+fn default_connection_error_alert_per_min() -> f64 {
+    10.0
+}
+
+fn default_retention_days() -> u64 {
+    crate::storage::DEFAULT_RETENTION_DAYS
+}
+
+fn default_sample_interval() -> u64 {
+    crate::storage::DEFAULT_SAMPLE_INTERVAL_SECS
+}
 
 /// Runtime configuration for a single node
 #[derive(Debug, Clone)]
@@ -188,13 +1341,117 @@ pub struct NodeRuntimeConfig {
     pub port: u16,
     pub role: NodeRole,
     pub network: String,
+    pub pool_id: Option<String>,
+    pub socket_path: Option<PathBuf>,
+    pub cncli_db: Option<PathBuf>,
+    pub cli_path: Option<PathBuf>,
+    pub opcert_path: Option<PathBuf>,
+    pub log_file: Option<PathBuf>,
+    /// Configured version, used when the node doesn't report one
+    pub version: Option<String>,
+    pub thresholds: HealthThresholds,
+    pub retention: RetentionPolicy,
+    pub network_params: NetworkParams,
+    pub endpoint: EndpointOptions,
+    pub custom_metrics: Vec<CustomMetric>,
+    pub group: Option<String>,
+    /// Notification channels the node's alerts go to (`None`: all)
+    pub notify: Option<Vec<String>>,
+    /// Peer selection targets from the node config given as `genesis_file`
+    pub peer_targets: Option<PeerTargets>,
+    /// Agent that reports the node host's peers and network counters
+    pub agent: Option<AgentConfig>,
+    /// pooltool.io tip submission
+    pub pooltool: Option<PooltoolConfig>,
+    /// P2P listening ports the peer list is limited to (empty: guess)
+    pub p2p_ports: Vec<u16>,
 }
 
 impl NodeRuntimeConfig {
     /// Get the full Prometheus metrics URL
-    #[allow(dead_code)]
     pub fn metrics_url(&self) -> String {
-        format!("http://{}:{}/metrics", self.host, self.port)
+        metrics_url(self.endpoint.scheme, &self.host, self.port)
+    }
+
+    /// Metrics endpoint for display: `host:port`, or the socket path
+    pub fn endpoint_label(&self) -> String {
+        match unix_socket_path(&self.host) {
+            Some(path) => path.display().to_string(),
+            None => format!("{}:{}", self.host, self.port),
+        }
+    }
+}
+
+/// Notification channels a node's alerts go to: its own `notify` list, else
+/// its group's; every name must be a `[[notify]]` channel
+fn notify_route(node: &NodeConfig, file_config: &FileConfig) -> Result<Option<Vec<String>>> {
+    let route = node.notify.clone().or_else(|| {
+        let group = node.group.as_ref()?;
+        file_config.groups.get(group)?.notify.clone()
+    });
+    for name in route.iter().flatten() {
+        if !file_config.notify.iter().any(|c| c.label() == name) {
+            let channels: Vec<&str> = file_config.notify.iter().map(|c| c.label()).collect();
+            anyhow::bail!(
+                "unknown notify channel '{}' (channels: {})",
+                name,
+                if channels.is_empty() {
+                    "none configured".to_string()
+                } else {
+                    channels.join(", ")
+                }
+            );
+        }
+    }
+    Ok(route)
+}
+
+/// A node-sourced tip reference must name one of the monitored nodes
+fn check_reference_node(reference: &ReferenceTipConfig, nodes: &[NodeRuntimeConfig]) -> Result<()> {
+    if reference.source != Some(ReferenceSource::Node) {
+        return Ok(());
+    }
+    let Some(name) = &reference.node else {
+        anyhow::bail!("reference_tip source \"node\" needs `node = \"<node name>\"`");
+    };
+    if !nodes.iter().any(|n| &n.name == name) {
+        anyhow::bail!("reference_tip node '{}' is not a configured node", name);
+    }
+    Ok(())
+}
+
+/// Parse a report period such as `7d`, `24h` or `2w` (bare numbers are days)
+fn parse_report_period(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let (num, unit_secs) = match s.char_indices().last() {
+        Some((i, 'h')) => (&s[..i], 3600),
+        Some((i, 'd')) => (&s[..i], 86400),
+        Some((i, 'w')) => (&s[..i], 7 * 86400),
+        _ => (s, 86400),
+    };
+    match num.parse::<u64>() {
+        Ok(n) if n > 0 => Ok(Duration::from_secs(n * unit_secs)),
+        _ => Err(format!(
+            "invalid period '{}' (expected e.g. 24h, 7d or 2w)",
+            s
+        )),
+    }
+}
+
+/// Socket path of a `host = "unix:/path/to/metrics.sock"` target
+pub fn unix_socket_path(host: &str) -> Option<&Path> {
+    host.strip_prefix("unix:").map(Path::new)
+}
+
+/// Prometheus metrics URL for a host and port
+///
+/// Unix socket targets get a placeholder authority; the connection itself
+/// goes to the socket.
+pub fn metrics_url(scheme: Scheme, host: &str, port: u16) -> String {
+    if unix_socket_path(host).is_some() {
+        format!("{}://localhost/metrics", scheme)
+    } else {
+        format!("{}://{}:{}/metrics", scheme, host, port)
     }
 }
 
@@ -208,79 +1465,504 @@ pub struct AppConfig {
     pub timeout: Duration,
     pub refresh_interval: Duration,
     pub history_length: usize,
-    pub epoch_length: u64,
+
+    /// External chain API backend (shared by all nodes)
+    pub chain_api_backend: ChainApiBackend,
+    /// Chain API cache TTL
+    pub chain_api_ttl: Duration,
+    /// Epochs shown in the Rewards panel
+    pub rewards_epochs: usize,
+
+    /// Dashboard layout (columns of panels, left to right)
+    pub layout: Vec<Vec<PanelKind>>,
+
+    /// Data directory size budget in bytes (if configured)
+    pub storage_quota_bytes: Option<u64>,
+    /// Minimum free space on the data directory's filesystem in bytes
+    pub min_free_disk_bytes: u64,
+    /// Lead time for the epoch boundary alert (zero disables)
+    pub epoch_alert_lead: Duration,
+    /// Active peer latency probing
+    pub peer_rtt_probe: RttProbeMode,
+    /// ICMP ping fallback for peer latency
+    pub peer_ping: bool,
+    /// Incoming connections from one /24 that raise an alert (zero disables)
+    pub peer_subnet_alert: usize,
+    /// Share of incoming connections from one network that raises an alert (zero disables)
+    pub peer_network_alert_percent: u8,
+    /// Share of outgoing connections to one network above which an alert is raised (zero disables)
+    pub peer_outgoing_network_alert_percent: u8,
+    /// Consecutive failed scrapes before a node is offline (zero disables the alert)
+    pub offline_after_failures: u32,
+    /// Seconds unreachable before the node-down alert
+    pub node_down_grace_secs: u64,
+    /// Forks within an hour that raise an alert (zero disables)
+    pub fork_alert_per_hour: u64,
+    /// Live stake change in ADA that raises a delegation alert (zero disables)
+    pub delegation_alert_ada: u64,
+    /// Sustained connection errors per minute that raise an alert (zero disables)
+    pub connection_error_alert_per_min: f64,
+    /// Configured hard fork and whether to look one up on Koios
+    pub hard_fork: HardForkConfig,
+    /// Time-series database that history is pushed to
+    pub influx: InfluxConfig,
+    /// MQTT broker that health and alerts are published to
+    pub mqtt: MqttConfig,
+    /// Service pinged while sview runs
+    pub heartbeat: HeartbeatConfig,
+    /// Network tip reference for lag in blocks
+    pub reference_tip: ReferenceTipConfig,
+    /// Peer location lookup service
+    pub geoip: GeoIpConfig,
+    /// Compare node versions with the latest cardano-node release
+    pub check_node_release: bool,
+    /// Raw metric name patterns to keep
+    pub raw_metrics: Vec<String>,
+    /// Peer blocklist file
+    pub blocklist_path: PathBuf,
+    /// Firewall for suggested ban commands
+    pub firewall: Firewall,
+    /// Timezone for displayed and exported timestamps
+    pub timezone: DisplayTimezone,
+    /// Show the live clock and slot ticker in the footer
+    pub status_clock: bool,
+    /// Active keybindings
+    pub keys: KeyBindings,
+    /// Channels that new alerts are sent to
+    pub notify: Vec<NotifyChannel>,
+
+    /// Config file path (target for settings write-back)
+    pub config_path: Option<PathBuf>,
 
     /// Export path (if --export was specified)
     pub export_path: Option<PathBuf>,
+
+    /// Alert report period (if --alert-report was specified)
+    pub alert_report: Option<Duration>,
+
+    /// Prune history and exit (if --prune was specified)
+    pub prune: bool,
+
+    /// Delete the GeoIP cache and exit (if --flush-geoip-cache was specified)
+    pub flush_geoip_cache: bool,
+
+    /// History backfill (if --import was specified)
+    pub import: Option<ImportRequest>,
+
+    /// Report to print (if --report was specified)
+    pub report: Option<ReportRequest>,
+
+    /// Scan for nodes and exit (if --discover was specified)
+    pub discover: bool,
+    /// No config file and no node given on the command line (offer setup)
+    pub first_run: bool,
+    /// Address of the web dashboard (if --web was specified)
+    pub web: Option<SocketAddr>,
+    /// sview instance mirrored read-only (if --follow was specified)
+    pub follow: Option<String>,
+    /// Subnets and ports scanned by --discover
+    pub discover_subnets: Vec<String>,
+    pub discover_ports: Vec<u16>,
+
+    /// Original CLI arguments (re-applied on config reload)
+    cli_args: CliArgs,
+
+    /// Network and thresholds for nodes added at runtime
+    default_network: String,
+    default_epoch_length: u64,
+    default_thresholds: HealthThresholds,
+    default_retention: RetentionPolicy,
 }
 
 impl AppConfig {
-    /// Load configuration from CLI, environment, and config file
-    pub fn load() -> Self {
-        let args = CliArgs::parse();
+    /// Load configuration from parsed CLI arguments and the config file
+    ///
+    /// A missing config file means CLI single-node mode; a file that exists
+    /// but can't be read or parsed is a fatal error rather than being
+    /// silently replaced by defaults.
+    pub fn load(args: CliArgs) -> Result<Self> {
+        if args.demo {
+            let mut config = Self::resolve(args, None, crate::demo::file_config())?;
+            // Demo nodes run on their own clock, not mainnet's
+            for node in &mut config.nodes {
+                node.network_params.slot_zero_time = None;
+            }
+            return Ok(config);
+        }
 
         // Determine config file path
         let config_path = args.config.clone().or_else(default_config_path);
 
-        // Try to load config file
-        let file_config = config_path
-            .and_then(|p| fs::read_to_string(&p).ok())
-            .and_then(|s| toml::from_str::<FileConfig>(&s).ok())
-            .unwrap_or_default();
+        let file_config = match config_path.as_deref().filter(|p| p.exists()) {
+            Some(path) => read_file_config(path)
+                .context("Invalid config file (run `sview check` for a full report)")?,
+            None => FileConfig::default(),
+        };
+        let first_run = config_path.as_deref().is_some_and(|p| !p.exists())
+            && args.prom_host.is_none()
+            && args.prom_port.is_none()
+            && args.nodes.is_empty();
+
+        let mut config = Self::resolve(args, config_path, file_config)?;
+        config.first_run = first_run;
+        Ok(config)
+    }
+
+    /// Re-read the config file with the original CLI arguments
+    ///
+    /// Unlike startup, a missing or malformed file is an error here so a
+    /// half-written edit doesn't replace the running configuration.
+    pub fn reload(&self) -> Result<Self> {
+        let path = self
+            .config_path
+            .as_ref()
+            .context("No config file path available")?;
+        let file_config = read_file_config(path)?;
+
+        Self::resolve(self.cli_args.clone(), Some(path.clone()), file_config)
+    }
+
+    /// Combine CLI arguments and the parsed config file
+    pub fn resolve(
+        args: CliArgs,
+        config_path: Option<PathBuf>,
+        file_config: FileConfig,
+    ) -> Result<Self> {
+        for channel in &file_config.notify {
+            if !channel.kind.is_local() && channel.url.is_empty() {
+                anyhow::bail!("notify channel '{}' has no url", channel.label());
+            }
+            #[cfg(not(feature = "desktop-notify"))]
+            if channel.kind == crate::notify::NotifyKind::Desktop {
+                anyhow::bail!(
+                    "notify channel '{}' needs sview built with the desktop-notify feature",
+                    channel.label()
+                );
+            }
+        }
+        // 0 would remove all stored history at the next start
+        if file_config.global.retention_days == 0 {
+            anyhow::bail!("retention_days must be at least 1");
+        }
+        if let Some(node) = file_config
+            .nodes
+            .iter()
+            .find(|n| n.retention_days == Some(0))
+        {
+            anyhow::bail!("Node '{}': retention_days must be at least 1", node.name);
+        }
+        let cli_args = args.clone();
+        let import = args.import.map(|source| ImportRequest {
+            source,
+            url: args.url.clone().unwrap_or_default(),
+            selector: args.query.clone(),
+            range: args.range,
+            node: args.node_name.clone(),
+        });
+        let report = args.report.map(|kind| ReportRequest {
+            kind,
+            format: args.format,
+            range: args.range,
+        });
 
         // Check if we should use CLI single-node mode or config file multi-node mode
         let cli_node_specified = args.prom_host.is_some() || args.prom_port.is_some();
+        let default_retention = RetentionPolicy {
+            retention_days: file_config.global.retention_days,
+            sample_interval_secs: file_config.global.sample_interval_secs,
+        };
 
-        let nodes = if cli_node_specified || file_config.nodes.is_empty() {
+        let mut nodes = if cli_node_specified
+            || (file_config.nodes.is_empty() && args.nodes.is_empty())
+        {
             // Single-node mode from CLI
+            let (network, network_params) = resolve_network(
+                args.network.as_deref(),
+                args.genesis_file.as_deref(),
+                None,
+                &file_config.global.network,
+                args.epoch_length,
+                file_config.global.epoch_length,
+            )?;
             vec![NodeRuntimeConfig {
                 name: args.node_name.unwrap_or_else(|| "Cardano Node".to_string()),
                 host: args.prom_host.unwrap_or_else(|| "127.0.0.1".to_string()),
                 port: args.prom_port.unwrap_or(12798),
                 role: NodeRole::Relay,
-                network: args
-                    .network
-                    .unwrap_or_else(|| file_config.global.network.clone()),
+                network,
+                pool_id: None,
+                socket_path: args.socket_path,
+                cncli_db: None,
+                cli_path: None,
+                opcert_path: None,
+                log_file: None,
+                version: None,
+                thresholds: file_config.global.thresholds,
+                retention: default_retention,
+                network_params,
+                endpoint: EndpointOptions::default(),
+                custom_metrics: Vec::new(),
+                group: None,
+                notify: None,
+                peer_targets: args
+                    .genesis_file
+                    .as_deref()
+                    .and_then(genesis::load_peer_targets),
+                agent: None,
+                pooltool: None,
+                p2p_ports: Vec::new(),
             }]
         } else {
             // Multi-node mode from config file
-            let configured_nodes: Vec<NodeRuntimeConfig> = file_config
+            let configured_nodes = file_config
                 .nodes
                 .iter()
-                .map(|n| NodeRuntimeConfig {
-                    name: n.name.clone(),
-                    host: n.host.clone(),
-                    port: n.port,
-                    role: n.role,
-                    network: n
-                        .network
-                        .clone()
-                        .unwrap_or_else(|| file_config.global.network.clone()),
+                .map(|n| {
+                    let (network, network_params) = resolve_network(
+                        n.network.as_deref(),
+                        n.genesis_file.as_deref(),
+                        n.genesis_hash.as_deref(),
+                        &file_config.global.network,
+                        args.epoch_length,
+                        file_config.global.epoch_length,
+                    )
+                    .with_context(|| format!("Node '{}'", n.name))?;
+                    let endpoint = EndpointOptions::from_node_config(n)
+                        .with_context(|| format!("Node '{}'", n.name))?;
+                    let notify = notify_route(n, &file_config)
+                        .with_context(|| format!("Node '{}'", n.name))?;
+                    Ok(NodeRuntimeConfig {
+                        name: n.name.clone(),
+                        host: n.host.clone(),
+                        port: n.port,
+                        role: n.role,
+                        network,
+                        pool_id: n.pool_id.clone(),
+                        socket_path: n.socket_path.clone(),
+                        cncli_db: n.cncli_db.clone(),
+                        cli_path: n.cli_path.clone(),
+                        opcert_path: n.opcert_path.clone(),
+                        log_file: n.log_file.clone(),
+                        version: n.version.clone(),
+                        thresholds: n.thresholds.unwrap_or(file_config.global.thresholds),
+                        retention: RetentionPolicy {
+                            retention_days: n
+                                .retention_days
+                                .unwrap_or(default_retention.retention_days),
+                            sample_interval_secs: n
+                                .sample_interval_secs
+                                .unwrap_or(default_retention.sample_interval_secs),
+                        },
+                        network_params,
+                        endpoint,
+                        custom_metrics: n.custom_metrics.clone(),
+                        group: n.group.clone(),
+                        notify,
+                        peer_targets: n
+                            .genesis_file
+                            .as_deref()
+                            .and_then(genesis::load_peer_targets),
+                        agent: n.agent.clone(),
+                        pooltool: n.pooltool.clone(),
+                        p2p_ports: n.p2p_port.as_ref().map(P2pPorts::ports).unwrap_or_default(),
+                    })
                 })
-                .collect();
+                .collect::<Result<Vec<NodeRuntimeConfig>>>()?;
 
-            if configured_nodes.is_empty() {
-                eprintln!("Error: Configuration has no nodes defined");
-                eprintln!("Please add at least one [[nodes]] section to your config file");
-                std::process::exit(1);
+            if configured_nodes.is_empty() && args.nodes.is_empty() {
+                anyhow::bail!(
+                    "Configuration has no nodes defined\nPlease add at least one [[nodes]] section to your config file"
+                );
             }
 
             configured_nodes
         };
 
+        // Apply --node overrides: update nodes with the same name, add the rest
+        for node_override in &args.nodes {
+            match nodes.iter_mut().find(|n| n.name == node_override.name) {
+                Some(node) => node_override.apply(node),
+                None => {
+                    let (network, network_params) = resolve_network(
+                        args.network.as_deref(),
+                        None,
+                        None,
+                        &file_config.global.network,
+                        args.epoch_length,
+                        file_config.global.epoch_length,
+                    )?;
+                    let mut node = NodeRuntimeConfig {
+                        name: node_override.name.clone(),
+                        host: "127.0.0.1".to_string(),
+                        port: 12798,
+                        role: NodeRole::Relay,
+                        network,
+                        pool_id: None,
+                        socket_path: None,
+                        cncli_db: None,
+                        cli_path: None,
+                        opcert_path: None,
+                        log_file: None,
+                        version: None,
+                        thresholds: file_config.global.thresholds,
+                        retention: default_retention,
+                        network_params,
+                        endpoint: EndpointOptions::default(),
+                        custom_metrics: Vec::new(),
+                        group: None,
+                        notify: None,
+                        peer_targets: None,
+                        agent: None,
+                        pooltool: None,
+                        p2p_ports: Vec::new(),
+                    };
+                    node_override.apply(&mut node);
+                    nodes.push(node);
+                }
+            }
+        }
+        check_reference_node(&file_config.global.reference_tip, &nodes)?;
+
         // Use CLI args for global settings, with file config as fallback
-        let timeout_secs = args.prom_timeout_secs;
-        let refresh_secs = args.refresh_interval_secs;
+        let timeout_secs = args
+            .prom_timeout_secs
+            .unwrap_or(file_config.global.timeout_secs);
+        let refresh_secs = args
+            .refresh_interval_secs
+            .unwrap_or(file_config.global.refresh_interval_secs);
         let history_length = args.history_length;
-        let epoch_length = args.epoch_length;
 
-        Self {
+        let chain_api_backend = match &file_config.global.blockfrost_project_id {
+            Some(project_id) => ChainApiBackend::Blockfrost {
+                project_id: project_id.clone(),
+            },
+            None => ChainApiBackend::Koios {
+                base_url: file_config.global.koios_url.clone(),
+            },
+        };
+
+        let layout: Vec<Vec<PanelKind>> = file_config
+            .global
+            .layout
+            .iter()
+            .map(LayoutColumn::panels)
+            .filter(|panels| !panels.is_empty())
+            .collect();
+        let layout = if layout.is_empty() {
+            default_layout()
+        } else {
+            layout
+        };
+
+        Ok(Self {
             nodes,
             timeout: Duration::from_secs(timeout_secs),
             refresh_interval: Duration::from_secs(refresh_secs),
             history_length,
-            epoch_length,
+            chain_api_backend,
+            chain_api_ttl: Duration::from_secs(
+                file_config
+                    .global
+                    .chain_api_ttl_secs
+                    .max(MIN_CHAIN_API_TTL_SECS),
+            ),
+            rewards_epochs: file_config.global.rewards_epochs.max(1),
+            layout,
+            storage_quota_bytes: file_config
+                .global
+                .storage_quota_mb
+                .map(|mb| mb * 1024 * 1024),
+            min_free_disk_bytes: file_config.global.min_free_disk_mb * 1024 * 1024,
+            epoch_alert_lead: Duration::from_secs(file_config.global.epoch_alert_hours * 3600),
+            peer_rtt_probe: file_config.global.peer_rtt_probe,
+            peer_ping: file_config.global.peer_ping,
+            peer_subnet_alert: file_config.global.peer_subnet_alert,
+            peer_network_alert_percent: file_config.global.peer_network_alert_percent,
+            peer_outgoing_network_alert_percent: file_config
+                .global
+                .peer_outgoing_network_alert_percent,
+            offline_after_failures: file_config.global.offline_after_failures,
+            node_down_grace_secs: file_config.global.node_down_grace_secs,
+            fork_alert_per_hour: file_config.global.fork_alert_per_hour,
+            delegation_alert_ada: file_config.global.delegation_alert_ada,
+            connection_error_alert_per_min: file_config.global.connection_error_alert_per_min,
+            hard_fork: file_config.global.hard_fork.clone(),
+            influx: file_config.global.influx.clone(),
+            mqtt: file_config.global.mqtt.clone(),
+            heartbeat: file_config.global.heartbeat.clone(),
+            reference_tip: file_config.global.reference_tip.clone(),
+            geoip: file_config.global.geoip.clone(),
+            check_node_release: file_config.global.check_node_release,
+            raw_metrics: file_config.global.raw_metrics.clone(),
+            blocklist_path: file_config
+                .global
+                .blocklist_file
+                .clone()
+                .unwrap_or_else(|| crate::storage::get_data_dir().join("blocklist.txt")),
+            firewall: file_config.global.firewall,
+            timezone: file_config.global.timezone,
+            status_clock: file_config.global.status_clock,
+            keys: file_config.keys,
+            notify: file_config.notify,
+            config_path,
             export_path: args.export,
+            alert_report: args.alert_report,
+            prune: args.prune,
+            flush_geoip_cache: args.flush_geoip_cache,
+            import,
+            report,
+            discover: args.discover,
+            first_run: false,
+            web: args.web,
+            follow: args.follow,
+            discover_subnets: file_config.global.discover_subnets.clone(),
+            discover_ports: file_config.global.discover_ports.clone(),
+            cli_args,
+            default_network: args
+                .network
+                .unwrap_or_else(|| file_config.global.network.clone()),
+            default_epoch_length: file_config.global.epoch_length,
+            default_thresholds: file_config.global.thresholds,
+            default_retention,
+        })
+    }
+
+    /// Runtime config for a node added from the TUI, using global defaults
+    pub fn new_node(
+        &self,
+        name: String,
+        host: String,
+        port: u16,
+        role: NodeRole,
+    ) -> NodeRuntimeConfig {
+        NodeRuntimeConfig {
+            name,
+            host,
+            port,
+            role,
+            network: self.default_network.clone(),
+            pool_id: None,
+            socket_path: None,
+            cncli_db: None,
+            cli_path: None,
+            opcert_path: None,
+            log_file: None,
+            version: None,
+            thresholds: self.default_thresholds,
+            retention: self.default_retention,
+            network_params: default_params(
+                &self.default_network,
+                self.cli_args.epoch_length,
+                self.default_epoch_length,
+            ),
+            endpoint: EndpointOptions::default(),
+            custom_metrics: Vec::new(),
+            group: None,
+            notify: None,
+            peer_targets: None,
+            agent: None,
+            pooltool: None,
+            p2p_ports: Vec::new(),
         }
     }
 
@@ -291,8 +1973,60 @@ impl AppConfig {
     }
 }
 
+/// Resolve a node's network name and slot arithmetic parameters
+///
+/// A genesis file takes precedence over a genesis hash; without either, see
+/// `default_params`. When no network is set explicitly, the one identified
+/// by the genesis is preferred over the global default.
+fn resolve_network(
+    network: Option<&str>,
+    genesis_file: Option<&Path>,
+    genesis_hash: Option<&str>,
+    default_network: &str,
+    cli_epoch_length: Option<u64>,
+    epoch_length: u64,
+) -> Result<(String, NetworkParams)> {
+    let params = match (genesis_file, genesis_hash) {
+        (Some(path), _) => genesis::load_genesis_file(path)?,
+        (None, Some(hash)) => genesis::params_for_genesis_hash(hash)
+            .with_context(|| format!("Unknown genesis hash '{}'", hash))?,
+        (None, None) => default_params(
+            network.unwrap_or(default_network),
+            cli_epoch_length,
+            epoch_length,
+        ),
+    };
+
+    let network = network
+        .or(params.network_name())
+        .unwrap_or(default_network)
+        .to_string();
+    Ok((network, params))
+}
+
+/// Parameters for a node without a genesis: `--epoch-length` if given, else
+/// those of the public network it's on, else the global `epoch_length`
+fn default_params(
+    network: &str,
+    cli_epoch_length: Option<u64>,
+    epoch_length: u64,
+) -> NetworkParams {
+    match cli_epoch_length {
+        Some(epoch_length) => NetworkParams::with_epoch_length(epoch_length),
+        None => genesis::params_for_network(network)
+            .unwrap_or_else(|| NetworkParams::with_epoch_length(epoch_length)),
+    }
+}
+
+/// Read and parse a config file
+pub fn read_file_config(path: &Path) -> Result<FileConfig> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
 /// Get the default config file path
-fn default_config_path() -> Option<PathBuf> {
+pub fn default_config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|p| p.join("sview").join("config.toml"))
 }
 
@@ -307,6 +2041,16 @@ pub struct Config {
     pub refresh_interval_secs: u64,
     pub history_length: usize,
     pub epoch_length: u64,
+    pub slot_length_secs: f64,
+    /// Expected chain density (blocks per slot)
+    pub active_slots_coeff: f64,
+    /// Unix time of slot 0 at the current slot length (if known)
+    pub slot_zero_time: Option<u64>,
+    pub endpoint: EndpointOptions,
+    /// Raw metrics to keep (the allowlist plus custom metric names)
+    pub raw_metrics: RawMetricFilter,
+    /// P2P listening ports for peer discovery (empty: guess)
+    pub p2p_ports: Vec<u16>,
 }
 
 impl Config {
@@ -320,7 +2064,20 @@ impl Config {
             prom_timeout_secs: app_config.timeout.as_secs(),
             refresh_interval_secs: app_config.refresh_interval.as_secs(),
             history_length: app_config.history_length,
-            epoch_length: app_config.epoch_length,
+            epoch_length: node.network_params.epoch_length,
+            slot_length_secs: node.network_params.slot_length_secs,
+            active_slots_coeff: node.network_params.active_slots_coeff,
+            slot_zero_time: node.network_params.slot_zero_time,
+            endpoint: node.endpoint.clone(),
+            raw_metrics: node
+                .custom_metrics
+                .iter()
+                .fold(
+                    RawMetricFilter::new(app_config.raw_metrics.iter().cloned()),
+                    |filter, metric| filter.with_name(&metric.name),
+                )
+                .with_exact(crate::glossary::prometheus_names()),
+            p2p_ports: node.p2p_ports.clone(),
         }
     }
 
@@ -337,7 +2094,15 @@ impl Config {
 
     /// Get the full Prometheus metrics URL
     pub fn metrics_url(&self) -> String {
-        format!("http://{}:{}/metrics", self.prom_host, self.prom_port)
+        metrics_url(self.endpoint.scheme, &self.prom_host, self.prom_port)
+    }
+
+    /// "host:port", or the socket path for unix socket targets
+    pub fn endpoint_label(&self) -> String {
+        match unix_socket_path(&self.prom_host) {
+            Some(path) => path.display().to_string(),
+            None => format!("{}:{}", self.prom_host, self.prom_port),
+        }
     }
 }
 
@@ -352,6 +2117,12 @@ impl Default for Config {
             refresh_interval_secs: 2,
             history_length: 60,
             epoch_length: 432000,
+            slot_length_secs: 1.0,
+            active_slots_coeff: DEFAULT_ACTIVE_SLOTS_COEFF,
+            slot_zero_time: None,
+            endpoint: EndpointOptions::default(),
+            raw_metrics: RawMetricFilter::default(),
+            p2p_ports: Vec::new(),
         }
     }
 }
@@ -401,6 +2172,719 @@ role = "bp"
         assert_eq!(config.nodes[1].role, NodeRole::Bp);
     }
 
+    #[test]
+    fn test_parse_node_override() {
+        let full: NodeOverride = "Relay 3=10.0.0.3:3001:relay".parse().unwrap();
+        assert_eq!(full.name, "Relay 3");
+        assert_eq!(full.host.as_deref(), Some("10.0.0.3"));
+        assert_eq!(full.port, Some(3001));
+        assert_eq!(full.role, Some(NodeRole::Relay));
+
+        let port_only: NodeOverride = "BP=:12799".parse().unwrap();
+        assert_eq!(port_only.host, None);
+        assert_eq!(port_only.port, Some(12799));
+        assert_eq!(port_only.role, None);
+
+        let ipv6: NodeOverride = "v6=[::1]:12798:bp".parse().unwrap();
+        assert_eq!(ipv6.host.as_deref(), Some("[::1]"));
+        assert_eq!(ipv6.role, Some(NodeRole::Bp));
+
+        let uds: NodeOverride = "local=unix:/run/cardano/metrics.sock:bp".parse().unwrap();
+        assert_eq!(uds.host.as_deref(), Some("unix:/run/cardano/metrics.sock"));
+        assert_eq!(uds.port, None);
+        assert_eq!(uds.role, Some(NodeRole::Bp));
+
+        assert!("no-equals".parse::<NodeOverride>().is_err());
+        assert!("x=host:notaport".parse::<NodeOverride>().is_err());
+    }
+
+    #[test]
+    fn test_node_overrides_merge_with_file() {
+        let file_config: FileConfig =
+            toml::from_str("[[nodes]]\nname = \"BP\"\nhost = \"10.0.0.2\"\nrole = \"bp\"\n")
+                .unwrap();
+        let args = CliArgs::parse_from([
+            "sview",
+            "--node",
+            "BP=:12799",
+            "--node",
+            "Incident Relay=10.9.9.9:3001",
+        ]);
+        let config = AppConfig::resolve(args, None, file_config).unwrap();
+
+        assert_eq!(config.nodes.len(), 2);
+        assert_eq!(config.nodes[0].host, "10.0.0.2");
+        assert_eq!(config.nodes[0].port, 12799);
+        assert_eq!(config.nodes[0].role, NodeRole::Bp);
+        assert_eq!(config.nodes[1].name, "Incident Relay");
+        assert_eq!(config.nodes[1].port, 3001);
+
+        // Without a config file, --node replaces the default single node
+        let args = CliArgs::parse_from(["sview", "--node", "A=10.0.0.1:12798"]);
+        let config = AppConfig::resolve(args, None, FileConfig::default()).unwrap();
+        assert_eq!(config.nodes.len(), 1);
+        assert_eq!(config.nodes[0].name, "A");
+    }
+
+    #[test]
+    fn test_cli_subcommands() {
+        use clap::CommandFactory;
+        CliArgs::command().debug_assert();
+
+        let args = CliArgs::parse_from(["sview", "completions", "zsh"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Completions {
+                shell: clap_complete::Shell::Zsh
+            })
+        ));
+        assert!(matches!(
+            CliArgs::parse_from(["sview", "man"]).command,
+            Some(Command::Man)
+        ));
+        assert!(CliArgs::parse_from(["sview"]).command.is_none());
+    }
+
+    #[test]
+    fn test_subcommands_set_legacy_flags() {
+        let parse = |argv: &[&str]| {
+            let mut args = CliArgs::parse_from(argv);
+            if let Some(command) = args.command.take() {
+                args.apply_command(command);
+            }
+            args
+        };
+
+        let args = parse(&[
+            "sview", "report", "sla", "--format", "json", "--range", "7d",
+        ]);
+        assert_eq!(args.report, Some(ReportKind::Sla));
+        assert_eq!(args.format, ReportFormat::Json);
+        assert_eq!(args.range, Duration::from_secs(7 * 86400));
+        // The old flag spelling means the same
+        let legacy = parse(&[
+            "sview", "--report", "sla", "--format", "json", "--range", "7d",
+        ]);
+        assert_eq!((legacy.report, legacy.format), (args.report, args.format));
+        assert_eq!(
+            parse(&["sview", "alert-report", "2w"]).alert_report,
+            Some(Duration::from_secs(14 * 86400))
+        );
+        assert_eq!(
+            parse(&["sview", "--alert-report", "2w"]).alert_report,
+            Some(Duration::from_secs(14 * 86400))
+        );
+
+        // Connection options are accepted after the subcommand too
+        let args = parse(&["sview", "check", "--config", "/tmp/sview.toml"]);
+        assert!(args.check_config);
+        assert_eq!(args.config, Some(PathBuf::from("/tmp/sview.toml")));
+        assert!(parse(&["sview", "--check-config"]).check_config);
+
+        let args = parse(&["sview", "import", "prometheus", "--url", "http://prom:9090"]);
+        assert_eq!(args.import, Some(ImportSource::Prometheus));
+        assert_eq!(args.url.as_deref(), Some("http://prom:9090"));
+        assert_eq!(
+            parse(&["sview", "export", "out.csv"]).export,
+            Some(PathBuf::from("out.csv"))
+        );
+        assert!(parse(&["sview", "prune"]).prune);
+        assert!(parse(&["sview", "monitor", "--demo"]).demo);
+        assert_eq!(
+            parse(&["sview", "--follow", "10.0.0.5:8080"])
+                .follow
+                .as_deref(),
+            Some("10.0.0.5:8080")
+        );
+        assert!(matches!(
+            CliArgs::parse_from(["sview", "config", "validate"]).command,
+            Some(Command::Config {
+                action: ConfigAction::Validate
+            })
+        ));
+    }
+
+    #[test]
+    fn test_endpoint_options() {
+        let file_config: FileConfig = toml::from_str(
+            r#"
+[[nodes]]
+name = "BP"
+host = "metrics.example.com"
+port = 443
+scheme = "https"
+insecure_skip_verify = true
+basic_auth = { user = "sview", pass = "secret" }
+"#,
+        )
+        .unwrap();
+        let config = AppConfig::resolve(CliArgs::parse_from(["sview"]), None, file_config).unwrap();
+        let node = &config.nodes[0];
+        assert_eq!(
+            node.metrics_url(),
+            "https://metrics.example.com:443/metrics"
+        );
+        assert!(node.endpoint.insecure_skip_verify);
+        assert_eq!(node.endpoint.basic_auth.as_ref().unwrap().user, "sview");
+
+        let both: FileConfig = toml::from_str(
+            r#"
+[[nodes]]
+name = "BP"
+bearer_token = "abc"
+basic_auth = { user = "sview" }
+"#,
+        )
+        .unwrap();
+        let err = AppConfig::resolve(CliArgs::parse_from(["sview"]), None, both).unwrap_err();
+        assert!(format!("{:#}", err).contains("mutually exclusive"));
+    }
+
+    #[test]
+    fn test_unix_socket_host() {
+        let file_config: FileConfig = toml::from_str(
+            "[[nodes]]\nname = \"Local\"\nhost = \"unix:/run/cardano/metrics.sock\"\n",
+        )
+        .unwrap();
+        let config = AppConfig::resolve(CliArgs::parse_from(["sview"]), None, file_config).unwrap();
+        let node = &config.nodes[0];
+        assert_eq!(
+            unix_socket_path(&node.host),
+            Some(Path::new("/run/cardano/metrics.sock"))
+        );
+        assert_eq!(node.metrics_url(), "http://localhost/metrics");
+        assert_eq!(node.endpoint_label(), "/run/cardano/metrics.sock");
+        assert_eq!(unix_socket_path("10.0.0.1"), None);
+    }
+
+    #[test]
+    fn test_proxy_and_ssh_tunnel() {
+        let file_config: FileConfig = toml::from_str(
+            r#"
+[[nodes]]
+name = "Relay"
+proxy = "socks5h://127.0.0.1:1080"
+
+[[nodes]]
+name = "BP"
+host = "10.0.0.2"
+ssh_tunnel = { destination = "cardano@bastion.example.com", ssh_port = 2222 }
+"#,
+        )
+        .unwrap();
+        let config = AppConfig::resolve(CliArgs::parse_from(["sview"]), None, file_config).unwrap();
+        assert_eq!(
+            config.nodes[0].endpoint.proxy.as_deref(),
+            Some("socks5h://127.0.0.1:1080")
+        );
+        let tunnel = config.nodes[1].endpoint.ssh_tunnel.as_ref().unwrap();
+        assert_eq!(tunnel.destination, "cardano@bastion.example.com");
+        assert_eq!(tunnel.ssh_port, Some(2222));
+    }
+
+    #[test]
+    fn test_node_agent() {
+        let file_config: FileConfig = toml::from_str(
+            r#"
+[[nodes]]
+name = "Relay 2"
+host = "10.0.0.5"
+
+[nodes.agent]
+url = "http://10.0.0.5:12800"
+token = "change-me"
+"#,
+        )
+        .unwrap();
+        let config = AppConfig::resolve(CliArgs::parse_from(["sview"]), None, file_config).unwrap();
+        let agent = config.nodes[0].agent.as_ref().unwrap();
+        assert_eq!(agent.url, "http://10.0.0.5:12800");
+        assert_eq!(agent.token.as_deref(), Some("change-me"));
+
+        let args = CliArgs::parse_from(["sview", "agent", "--token", "s3cret"]);
+        match args.command {
+            Some(Command::Agent {
+                listen,
+                token,
+                tls_cert,
+                ..
+            }) => {
+                assert_eq!(listen.port(), crate::agent::DEFAULT_PORT);
+                assert!(listen.ip().is_loopback());
+                assert_eq!(token.as_deref(), Some("s3cret"));
+                assert_eq!(tls_cert, None);
+            }
+            _ => panic!("expected the agent command"),
+        }
+    }
+
+    #[test]
+    fn test_append_node() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("sview").join("config.toml");
+        let node = NodeRuntimeConfig {
+            name: "New Relay".to_string(),
+            host: "10.0.0.9".to_string(),
+            port: 12799,
+            role: NodeRole::Relay,
+            network: "mainnet".to_string(),
+            pool_id: None,
+            socket_path: None,
+            cncli_db: None,
+            cli_path: None,
+            opcert_path: None,
+            log_file: None,
+            version: None,
+            thresholds: HealthThresholds::default(),
+            retention: RetentionPolicy::default(),
+            network_params: NetworkParams::with_epoch_length(432000),
+            endpoint: EndpointOptions::default(),
+            custom_metrics: Vec::new(),
+            group: None,
+            notify: None,
+            peer_targets: None,
+            agent: None,
+            pooltool: None,
+            p2p_ports: Vec::new(),
+        };
+
+        // Creates the file when missing, then appends to it
+        append_node(&path, &node).unwrap();
+        append_node(
+            &path,
+            &NodeRuntimeConfig {
+                name: "BP".to_string(),
+                role: NodeRole::Bp,
+                ..node
+            },
+        )
+        .unwrap();
+
+        let config: FileConfig = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(config.nodes.len(), 2);
+        assert_eq!(config.nodes[0].host, "10.0.0.9");
+        assert_eq!(config.nodes[0].port, 12799);
+        assert_eq!(config.nodes[1].role, NodeRole::Bp);
+    }
+
+    #[test]
+    fn test_reload_rereads_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[[nodes]]\nname = \"Relay 1\"\n").unwrap();
+
+        let args = CliArgs::parse_from(["sview", "--config", path.to_str().unwrap()]);
+        let file_config = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let config = AppConfig::resolve(args, Some(path.clone()), file_config).unwrap();
+        assert_eq!(config.nodes.len(), 1);
+        assert_eq!(config.refresh_interval, Duration::from_secs(2));
+
+        fs::write(
+            &path,
+            "[global]\nrefresh_interval_secs = 5\n\n[[nodes]]\nname = \"Relay 1\"\n\n[[nodes]]\nname = \"Relay 2\"\nport = 12799\n",
+        )
+        .unwrap();
+        let reloaded = config.reload().unwrap();
+        assert_eq!(reloaded.nodes.len(), 2);
+        assert_eq!(reloaded.nodes[1].port, 12799);
+        assert_eq!(reloaded.refresh_interval, Duration::from_secs(5));
+
+        // A half-written file is reported instead of silently using defaults
+        fs::write(&path, "[[nodes]\nname = ").unwrap();
+        assert!(config.reload().is_err());
+    }
+
+    #[test]
+    fn test_resolve_network_per_node() {
+        let toml = r#"
+[global]
+network = "mainnet"
+
+[[nodes]]
+name = "BP"
+
+[[nodes]]
+name = "Preview Relay"
+genesis_hash = "363498d1024f84bb39d3fa9593ce391483cb40d479b87233f868d6e57c3a400d"
+
+[[nodes]]
+name = "Preview BP"
+network = "preview"
+
+[[nodes]]
+name = "Devnet"
+network = "devnet"
+"#;
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        let resolve = |n: &NodeConfig| {
+            resolve_network(
+                n.network.as_deref(),
+                n.genesis_file.as_deref(),
+                n.genesis_hash.as_deref(),
+                &config.global.network,
+                None,
+                432000,
+            )
+        };
+
+        let (network, params) = resolve(&config.nodes[0]).unwrap();
+        assert_eq!(network, "mainnet");
+        assert_eq!(params.epoch_length, 432000);
+
+        let (network, params) = resolve(&config.nodes[1]).unwrap();
+        assert_eq!(network, "preview");
+        assert_eq!(params.epoch_length, 86400);
+
+        // Without a genesis, a public network's own parameters are used
+        let (network, params) = resolve(&config.nodes[2]).unwrap();
+        assert_eq!(network, "preview");
+        assert_eq!(params.epoch_length, 86400);
+        assert_eq!(params.network_magic, Some(2));
+        let (network, params) = resolve(&config.nodes[3]).unwrap();
+        assert_eq!(network, "devnet");
+        assert_eq!(params.epoch_length, 432000);
+        let (_, params) =
+            resolve_network(Some("preview"), None, None, "mainnet", Some(3600), 432000).unwrap();
+        assert_eq!(params.epoch_length, 3600);
+
+        let unknown = resolve_network(None, None, Some("abc"), "mainnet", None, 432000);
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn test_parse_pool_id_and_chain_api() {
+        let toml = r#"
+[global]
+blockfrost_project_id = "mainnetabc123"
+chain_api_ttl_secs = 300
+
+[[nodes]]
+name = "BP"
+role = "bp"
+pool_id = "pool1xyz"
+"#;
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        assert_eq!(
+            config.global.blockfrost_project_id.as_deref(),
+            Some("mainnetabc123")
+        );
+        assert_eq!(config.global.chain_api_ttl_secs, 300);
+        assert_eq!(config.nodes[0].pool_id.as_deref(), Some("pool1xyz"));
+    }
+
+    #[test]
+    fn test_parse_hard_fork() {
+        let toml = r#"
+[global.hard_fork]
+epoch = 537
+protocol = "10.0"
+min_node_version = "10.1.4"
+"#;
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        let fork = config.global.hard_fork.scheduled().unwrap();
+        assert_eq!(fork.epoch, Some(537));
+        assert_eq!(fork.min_node_version.as_deref(), Some("10.1.4"));
+        assert!(!config.global.hard_fork.fetch);
+        assert!(GlobalConfig::default().hard_fork.scheduled().is_none());
+    }
+
+    #[test]
+    fn test_parse_layout() {
+        let toml = r#"
+[global]
+layout = ["chain", ["network", "forging", "governance"], ["resources", "rewards"]]
+"#;
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        let columns: Vec<Vec<PanelKind>> = config
+            .global
+            .layout
+            .iter()
+            .map(LayoutColumn::panels)
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                vec![PanelKind::Chain],
+                vec![
+                    PanelKind::Network,
+                    PanelKind::Forging,
+                    PanelKind::Governance
+                ],
+                vec![PanelKind::Resources, PanelKind::Rewards],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_storage_limits() {
+        let config: FileConfig = toml::from_str("[global]\nstorage_quota_mb = 200\n").unwrap();
+        assert_eq!(config.global.storage_quota_mb, Some(200));
+        assert_eq!(config.global.min_free_disk_mb, 1024);
+    }
+
+    #[test]
+    fn test_retention_per_node_override() {
+        let toml = r#"
+[global]
+retention_days = 90
+
+[[nodes]]
+name = "Relay"
+
+[[nodes]]
+name = "BP"
+retention_days = 365
+sample_interval_secs = 300
+"#;
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        let nodes = AppConfig::resolve(
+            <CliArgs as clap::Parser>::parse_from(["sview"]),
+            None,
+            config,
+        )
+        .unwrap()
+        .nodes;
+        assert_eq!(nodes[0].retention.retention_days, 90);
+        assert_eq!(nodes[0].retention.sample_interval_secs, 0);
+        assert_eq!(nodes[1].retention.retention_days, 365);
+        assert_eq!(nodes[1].retention.sample_interval_secs, 300);
+
+        // 0 would delete everything, so it's rejected
+        for toml in [
+            "[global]\nretention_days = 0\n[[nodes]]\nname = \"Relay\"",
+            "[[nodes]]\nname = \"Relay\"\nretention_days = 0",
+        ] {
+            let config: FileConfig = toml::from_str(toml).unwrap();
+            let error = AppConfig::resolve(
+                <CliArgs as clap::Parser>::parse_from(["sview"]),
+                None,
+                config,
+            )
+            .unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("retention_days must be at least 1"));
+        }
+    }
+
+    #[test]
+    fn test_parse_p2p_port() {
+        let toml = r#"
+[[nodes]]
+name = "Relay"
+p2p_port = 3001
+
+[[nodes]]
+name = "Dual"
+p2p_port = [3001, 3002]
+
+[[nodes]]
+name = "Default"
+"#;
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        let nodes = AppConfig::resolve(
+            <CliArgs as clap::Parser>::parse_from(["sview"]),
+            None,
+            config,
+        )
+        .unwrap()
+        .nodes;
+        assert_eq!(nodes[0].p2p_ports, vec![3001]);
+        assert_eq!(nodes[1].p2p_ports, vec![3001, 3002]);
+        assert!(nodes[2].p2p_ports.is_empty());
+    }
+
+    #[test]
+    fn test_notify_routes() {
+        let toml = r#"
+[[notify]]
+name = "team-a"
+kind = "ntfy"
+url = "https://ntfy.sh/team-a"
+
+[[notify]]
+name = "oncall"
+kind = "home_assistant"
+url = "http://ha.local:8123/api/webhook/oncall"
+
+[groups.pool-A]
+notify = ["team-a"]
+
+[[nodes]]
+name = "A Relay"
+group = "pool-A"
+
+[[nodes]]
+name = "A BP"
+group = "pool-A"
+notify = ["team-a", "oncall"]
+
+[[nodes]]
+name = "Shared"
+"#;
+        let args = || <CliArgs as clap::Parser>::parse_from(["sview"]);
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        let nodes = AppConfig::resolve(args(), None, config).unwrap().nodes;
+        assert_eq!(nodes[0].notify, Some(vec!["team-a".to_string()]));
+        assert_eq!(
+            nodes[1].notify,
+            Some(vec!["team-a".to_string(), "oncall".to_string()])
+        );
+        assert_eq!(nodes[2].notify, None);
+
+        let typo = toml.replace(r#"notify = ["team-a"]"#, r#"notify = ["team-b"]"#);
+        let config: FileConfig = toml::from_str(&typo).unwrap();
+        let error = AppConfig::resolve(args(), None, config).unwrap_err();
+        assert!(format!("{:#}", error).contains("unknown notify channel 'team-b'"));
+
+        let no_url = toml.replace(r#"url = "https://ntfy.sh/team-a""#, "");
+        let config: FileConfig = toml::from_str(&no_url).unwrap();
+        let error = AppConfig::resolve(args(), None, config).unwrap_err();
+        assert!(format!("{:#}", error).contains("notify channel 'team-a' has no url"));
+    }
+
+    #[test]
+    fn test_reference_node() {
+        let toml = r#"
+[global.reference_tip]
+source = "node"
+node = "Trusted"
+
+[[nodes]]
+name = "Relay"
+
+[[nodes]]
+name = "Trusted"
+"#;
+        let args = || <CliArgs as clap::Parser>::parse_from(["sview"]);
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        let resolved = AppConfig::resolve(args(), None, config).unwrap();
+        assert_eq!(resolved.reference_tip.node.as_deref(), Some("Trusted"));
+
+        let typo = toml.replace(r#"node = "Trusted""#, r#"node = "Trusty""#);
+        let config: FileConfig = toml::from_str(&typo).unwrap();
+        let error = AppConfig::resolve(args(), None, config).unwrap_err();
+        assert!(format!("{:#}", error).contains("'Trusty' is not a configured node"));
+    }
+
+    #[test]
+    fn test_parse_thresholds() {
+        let toml = r#"
+[global.thresholds]
+peers_warning = 8
+
+[[nodes]]
+name = "Relay"
+
+[[nodes]]
+name = "BP"
+thresholds = { memory_critical_gb = 20.0 }
+"#;
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.global.thresholds.peers_warning, 8);
+        assert_eq!(config.global.thresholds.peers_critical, 2);
+        assert!(config.nodes[0].thresholds.is_none());
+        let bp = config.nodes[1].thresholds.unwrap();
+        assert_eq!(bp.memory_critical_gb, 20.0);
+        assert_eq!(bp.memory_warning_gb, 12.0);
+    }
+
+    #[test]
+    fn test_parse_node_groups() {
+        let toml = r#"
+[[nodes]]
+name = "BP"
+role = "bp"
+group = "pool-A"
+
+[[nodes]]
+name = "Relay"
+group = "pool-A"
+
+[[nodes]]
+name = "Lab"
+"#;
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.nodes[0].group.as_deref(), Some("pool-A"));
+        assert_eq!(config.nodes[1].group.as_deref(), Some("pool-A"));
+        assert!(config.nodes[2].group.is_none());
+    }
+
+    #[test]
+    fn test_parse_custom_metrics() {
+        let toml = r#"
+[[nodes]]
+name = "Relay"
+
+[[nodes.custom_metrics]]
+name = "cardano_node_metrics_served_header_counter"
+label = "Headers Served"
+
+[[nodes.custom_metrics]]
+name = "rts_gc_bytes_copied"
+format = "bytes"
+panel = "chain"
+"#;
+        let config: FileConfig = toml::from_str(toml).unwrap();
+        let metrics = &config.nodes[0].custom_metrics;
+        assert_eq!(metrics.len(), 2);
+        assert_eq!(metrics[0].label(), "Headers Served");
+        assert_eq!(metrics[0].format, MetricFormat::Int);
+        assert_eq!(metrics[0].panel(), PanelKind::Network);
+        assert_eq!(metrics[1].label(), "rts_gc_bytes_copied");
+        assert_eq!(metrics[1].format, MetricFormat::Bytes);
+        assert_eq!(metrics[1].panel(), PanelKind::Chain);
+    }
+
+    #[test]
+    fn test_metric_format() {
+        assert_eq!(MetricFormat::Int.format(1234567.0), "1,234,567");
+        assert_eq!(MetricFormat::Int.format(-1500.0), "-1,500");
+        assert_eq!(MetricFormat::Float.format(0.12345), "0.12");
+        assert_eq!(MetricFormat::Bytes.format(1536.0), "1.50 KB");
+        assert_eq!(MetricFormat::Percent.format(0.25), "25.0%");
+        assert_eq!(MetricFormat::Duration.format(3720.0), "1h 2m");
+    }
+
+    #[test]
+    fn test_threshold_adjust_keeps_order() {
+        let mut t = HealthThresholds::default();
+        t.adjust(ThresholdField::PeersCritical, 10);
+        assert_eq!(t.peers_critical, t.peers_warning);
+        t.adjust(ThresholdField::TipAgeWarning, -100);
+        assert_eq!(t.tip_age_warning_secs, 10);
+        t.adjust(ThresholdField::MemoryWarning, 10);
+        assert_eq!(t.memory_warning_gb, t.memory_critical_gb);
+        t.adjust(ThresholdField::CpuCritical, -10);
+        assert_eq!(t.cpu_critical_percent, t.cpu_warning_percent);
+    }
+
+    #[test]
+    fn test_write_thresholds_preserves_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "# my nodes\n[global]\nnetwork = \"preprod\"\n\n[[nodes]]\nname = \"BP\"\nport = 12799\n",
+        )
+        .unwrap();
+
+        let thresholds = HealthThresholds {
+            peers_warning: 9,
+            ..Default::default()
+        };
+        write_thresholds(&path, "BP", &thresholds).unwrap();
+        write_thresholds(&path, "Other", &thresholds).unwrap();
+
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# my nodes"));
+        let config: FileConfig = toml::from_str(&written).unwrap();
+        assert_eq!(config.global.network, "preprod");
+        assert_eq!(config.global.thresholds.peers_warning, 9);
+        assert_eq!(config.nodes[0].port, 12799);
+        assert_eq!(config.nodes[0].thresholds.unwrap().peers_warning, 9);
+    }
+
     #[test]
     fn test_node_role_aliases() {
         let toml = r#"
@@ -411,4 +2895,26 @@ role = "block-producer"
         let config: FileConfig = toml::from_str(toml).unwrap();
         assert_eq!(config.nodes[0].role, NodeRole::Bp);
     }
+
+    #[test]
+    fn test_parse_report_period() {
+        assert_eq!(
+            parse_report_period("7d").unwrap(),
+            Duration::from_secs(7 * 86400)
+        );
+        assert_eq!(
+            parse_report_period("24h").unwrap(),
+            Duration::from_secs(86400)
+        );
+        assert_eq!(
+            parse_report_period("2w").unwrap(),
+            Duration::from_secs(14 * 86400)
+        );
+        assert_eq!(
+            parse_report_period("3").unwrap(),
+            Duration::from_secs(3 * 86400)
+        );
+        assert!(parse_report_period("0d").is_err());
+        assert!(parse_report_period("week").is_err());
+    }
 }
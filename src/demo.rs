@@ -0,0 +1,264 @@
+//! Demo mode (`sview --demo`)
+//!
+//! Runs against simulated nodes instead of real ones, so every view and theme
+//! can be tried without a node. Each simulated node renders Prometheus text
+//! that goes through the regular parser. The text is a pure function of the
+//! node's profile and the seconds since start, so tests can render any moment
+//! deterministically.
+//!
+//! The simulation mints a block roughly every 20 seconds, lets hot peers drop
+//! for a minute every quarter hour (a Peer Drop alert), records a fork every
+//! ten minutes, and gives the block producer a KES key with few periods left.
+
+use crate::config::FileConfig;
+use anyhow::{bail, Result};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::Instant;
+
+/// Host prefix selecting a simulated node (`host = "demo:relay"`)
+const HOST_PREFIX: &str = "demo:";
+
+/// Nodes shown by `sview --demo`
+const DEMO_CONFIG: &str = r#"
+[global]
+network = "mainnet"
+
+[[nodes]]
+name = "Demo BP"
+host = "demo:bp"
+role = "bp"
+group = "demo"
+
+[[nodes]]
+name = "Demo Relay 1"
+host = "demo:relay1"
+group = "demo"
+
+[[nodes]]
+name = "Demo Relay 2"
+host = "demo:relay2"
+group = "demo"
+
+[[nodes]]
+name = "Demo Offline"
+host = "demo:offline"
+"#;
+
+/// Chain position at the start of the demo
+const START_SLOT: u64 = 140_000_000;
+const START_EPOCH: u64 = 530;
+const START_SLOT_IN_EPOCH: u64 = 280_000;
+const START_BLOCK: u64 = 11_200_000;
+/// The node has been running for two days when the demo starts
+const START_UPTIME_SECS: u64 = 2 * 86400;
+
+/// Behaviour of a simulated node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DemoProfile {
+    /// Block producer with forging and KES metrics
+    BlockProducer,
+    /// Relay; the number shifts its timing so relays differ slightly
+    Relay(u64),
+    /// Never answers, to show the offline state and alerts
+    Offline,
+}
+
+/// The demo's config file
+pub fn file_config() -> FileConfig {
+    toml::from_str(DEMO_CONFIG).expect("built-in demo config is valid")
+}
+
+/// Data directory for demo mode, so simulated history stays out of the real one
+pub fn data_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("sview-demo-{}", std::process::id()))
+}
+
+/// The simulated node a host names, if it is a `demo:` host
+pub fn profile(host: &str) -> Option<DemoProfile> {
+    match host.strip_prefix(HOST_PREFIX)? {
+        "bp" => Some(DemoProfile::BlockProducer),
+        "offline" => Some(DemoProfile::Offline),
+        other => Some(DemoProfile::Relay(
+            other.trim_start_matches("relay").parse().unwrap_or(1),
+        )),
+    }
+}
+
+/// A simulated node, timed from its creation
+#[derive(Debug, Clone)]
+pub struct DemoNode {
+    profile: DemoProfile,
+    started: Instant,
+}
+
+impl DemoNode {
+    pub fn new(profile: DemoProfile) -> Self {
+        Self {
+            profile,
+            started: Instant::now(),
+        }
+    }
+
+    /// Current metrics as Prometheus text
+    pub fn scrape(&self) -> Result<String> {
+        render(self.profile, self.started.elapsed().as_secs())
+    }
+}
+
+/// Prometheus text for a simulated node `elapsed` seconds into the demo
+pub fn render(profile: DemoProfile, elapsed: u64) -> Result<String> {
+    let offset = match profile {
+        DemoProfile::Offline => bail!("connection refused (demo node is offline)"),
+        DemoProfile::BlockProducer => 0,
+        DemoProfile::Relay(n) => n,
+    };
+
+    let slot = START_SLOT + elapsed;
+    let slot_in_epoch = (START_SLOT_IN_EPOCH + elapsed) % 432_000;
+    let epoch = START_EPOCH + (START_SLOT_IN_EPOCH + elapsed) / 432_000;
+    // Relays see each block a little later than the block producer
+    let blocks = elapsed.saturating_sub(offset) / 20;
+    let block = START_BLOCK + blocks;
+    let forks = elapsed / 600;
+
+    // Hot peers drop for the first minute of every quarter hour
+    let minute = elapsed / 60;
+    let dropped = elapsed % 900 < 60 && elapsed >= 900;
+    let hot = if dropped {
+        6
+    } else {
+        20 + noise(minute, offset) % 4
+    };
+    let warm = 12 + noise(minute + 1, offset) % 5;
+    let cold = 40 + noise(minute + 2, offset) % 10;
+    let incoming = 8 + noise(minute + 3, offset) % 6;
+
+    let uptime_ns = (START_UPTIME_SECS + elapsed) * 1_000_000_000;
+    let live_bytes = 5_600_000_000 + (elapsed % 3600) * 100_000;
+    let mempool_txs = noise(elapsed / 4, offset) % 40;
+    let delay = 0.4 + (noise(blocks, offset) % 80) as f64 / 100.0;
+
+    let mut text = String::new();
+    let mut metric = |name: &str, value: f64| {
+        let _ = writeln!(text, "cardano_node_metrics_{} {}", name, value);
+    };
+    metric("blockNum_int", block as f64);
+    metric("slotNum_int", slot as f64);
+    metric("epoch_int", epoch as f64);
+    metric("slotInEpoch_int", slot_in_epoch as f64);
+    metric("density_real", 0.048);
+    metric("forks_int", forks as f64);
+    metric("txsProcessedNum_int", (90_000 + elapsed * 3) as f64);
+    metric("upTime_ns", uptime_ns as f64);
+    metric("RTS_gcLiveBytes_int", live_bytes as f64);
+    metric("RTS_gcHeapBytes_int", (live_bytes + 1_500_000_000) as f64);
+    metric("RTS_gcMinorNum_int", (120_000 + elapsed * 5) as f64);
+    metric("RTS_gcMajorNum_int", (900 + elapsed / 60) as f64);
+    metric("RTS_cpuNs_int", (elapsed * 350_000_000) as f64);
+    metric("txsInMempool_int", mempool_txs as f64);
+    metric("mempoolBytes_int", (mempool_txs * 1_100) as f64);
+    metric("blockfetchclient_blockdelay_real", delay);
+    metric("blockfetchclient_blockdelay_cdfOne_real", 0.82);
+    metric("blockfetchclient_blockdelay_cdfThree_real", 0.97);
+    metric("blockfetchclient_blockdelay_cdfFive_real", 0.99);
+    metric("served_block_count_int", (blocks * 6) as f64);
+    metric("served_header_counter_int", (blocks * 30) as f64);
+    metric("connectedPeers_int", (hot + warm) as f64);
+    metric("connectionManager_incomingConns", incoming as f64);
+    metric("connectionManager_outgoingConns", (hot + warm) as f64);
+    metric("connectionManager_duplexConns", 4.0);
+    metric("peerSelection_hot", hot as f64);
+    metric("peerSelection_warm", warm as f64);
+    metric("peerSelection_cold", cold as f64);
+    metric("peerSelection_targetNumberOfKnownPeers", 85.0);
+    metric("peerSelection_targetNumberOfEstablishedPeers", 40.0);
+    metric("peerSelection_targetNumberOfActivePeers", 20.0);
+
+    if profile == DemoProfile::BlockProducer {
+        // One period every 36 hours; the key is close to expiry
+        let kes_period = 420 + elapsed / 129_600;
+        metric("currentKESPeriod_int", kes_period as f64);
+        metric(
+            "remainingKESPeriods_int",
+            7u64.saturating_sub(elapsed / 129_600) as f64,
+        );
+        metric("operationalCertificateStartKESPeriod_int", 365.0);
+        metric("operationalCertificateExpiryKESPeriod_int", 427.0);
+        metric("opCertCounterOnDisk_int", 8.0);
+        metric("opCertCounterOnChain_int", 8.0);
+        metric("forging_enabled_int", 1.0);
+        // A block of our own every half hour
+        let leader = (elapsed / 1800) as f64;
+        metric("Forge_node_is_leader_int", leader);
+        metric("Forge_forge_about_to_lead_int", (elapsed / 20) as f64);
+        metric("Forge_adopted_int", leader);
+        metric("Forge_didnt_adopt_int", 0.0);
+    }
+
+    text.push_str(
+        "cardano_node_metrics_cardano_build_info {version=\"10.1.4\",revision=\"1f63dbf2ab39e0b32bf6901dc203866d3e37de08\",compiler_name=\"ghc\",compiler_version=\"9.6.6\",architecture=\"x86_64\",os_name=\"linux\"} 1\n",
+    );
+    Ok(text)
+}
+
+/// Deterministic pseudo-random number (splitmix64)
+fn noise(step: u64, salt: u64) -> u64 {
+    let mut z = step
+        .wrapping_add(salt.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::parse_prometheus_metrics;
+
+    #[test]
+    fn test_demo_config() {
+        let config = file_config();
+        assert_eq!(config.nodes.len(), 4);
+        assert_eq!(
+            profile(&config.nodes[0].host),
+            Some(DemoProfile::BlockProducer)
+        );
+        assert_eq!(profile("demo:relay2"), Some(DemoProfile::Relay(2)));
+        assert_eq!(profile("demo:offline"), Some(DemoProfile::Offline));
+        assert_eq!(profile("10.0.0.1"), None);
+    }
+
+    #[test]
+    fn test_render_is_deterministic() {
+        let bp = parse_prometheus_metrics(&render(DemoProfile::BlockProducer, 600).unwrap());
+        assert_eq!(bp.block_height, Some(START_BLOCK + 30));
+        assert_eq!(bp.slot_num, Some(START_SLOT + 600));
+        assert_eq!(bp.forks, Some(1));
+        assert_eq!(bp.kes_remaining, Some(7));
+        assert_eq!(bp.build_info.version.as_deref(), Some("10.1.4"));
+        assert_eq!(
+            render(DemoProfile::Relay(1), 1234).unwrap(),
+            render(DemoProfile::Relay(1), 1234).unwrap()
+        );
+
+        let relay = parse_prometheus_metrics(&render(DemoProfile::Relay(2), 600).unwrap());
+        assert_eq!(relay.block_height, Some(START_BLOCK + 29));
+        assert_eq!(relay.kes_remaining, None);
+        assert!(render(DemoProfile::Offline, 0).is_err());
+    }
+
+    #[test]
+    fn test_peer_drop() {
+        let hot = |elapsed| {
+            parse_prometheus_metrics(&render(DemoProfile::Relay(1), elapsed).unwrap())
+                .p2p
+                .hot_peers
+                .unwrap()
+        };
+        assert!(hot(899) >= 20);
+        assert_eq!(hot(900), 6);
+        assert!(hot(960) >= 20);
+    }
+}
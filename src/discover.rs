@@ -0,0 +1,314 @@
+//! Node discovery (`sview discover`)
+//!
+//! Probes the usual metrics ports on localhost and the subnets listed in
+//! `discover_subnets` for a Prometheus endpoint serving Cardano node metrics,
+//! then offers the nodes it finds for appending to the config file.
+
+use crate::config::{self, AppConfig, NodeRole};
+use crate::metrics::{parse_prometheus_metrics, NodeType};
+use anyhow::{bail, Context, Result};
+use std::io::{self, BufRead, Write};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Ports probed when `discover_ports` isn't set (cardano-node's default
+/// Prometheus port and the one used by many guild setups)
+pub const DEFAULT_PORTS: [u16; 2] = [12798, 12788];
+
+/// How long a single probe may take
+const PROBE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Probes in flight at once
+const MAX_CONCURRENT_PROBES: usize = 64;
+
+/// Smallest subnet prefix accepted (a /20 is 4096 hosts)
+const MIN_PREFIX_LEN: u8 = 20;
+
+/// A node answering on a metrics port
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredNode {
+    pub host: String,
+    pub port: u16,
+    pub node_type: NodeType,
+    pub version: Option<String>,
+    pub block_height: Option<u64>,
+    /// Block producer if the endpoint exposes KES metrics
+    pub role: NodeRole,
+}
+
+impl DiscoveredNode {
+    /// Name used for the config entry
+    pub fn name(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
+
+/// Scan, print what was found and append the chosen nodes to the config file
+pub async fn run(app_config: &AppConfig) -> Result<()> {
+    let mut hosts = vec![IpAddr::V4(Ipv4Addr::LOCALHOST)];
+    for subnet in &app_config.discover_subnets {
+        for host in expand_subnet(subnet)? {
+            if !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+    }
+    let ports = &app_config.discover_ports;
+    println!(
+        "Probing {} host(s) on port(s) {}...",
+        hosts.len(),
+        ports
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let found = scan(&hosts, ports).await;
+    if found.is_empty() {
+        println!("No Cardano node metrics endpoints found.");
+        if app_config.discover_subnets.is_empty() {
+            println!("Add subnets to scan with `discover_subnets` under [global].");
+        }
+        return Ok(());
+    }
+
+    // Nodes already in the config file aren't offered again
+    let configured: Vec<(String, u16)> = app_config
+        .config_path
+        .as_deref()
+        .filter(|path| path.exists())
+        .and_then(|path| config::read_file_config(path).ok())
+        .map(|file| file.nodes.into_iter().map(|n| (n.host, n.port)).collect())
+        .unwrap_or_default();
+    let is_configured = |node: &DiscoveredNode| {
+        configured.iter().any(|(host, port)| {
+            *port == node.port
+                && (*host == node.host || (is_loopback(host) && is_loopback(&node.host)))
+        })
+    };
+
+    let new: Vec<&DiscoveredNode> = found.iter().filter(|n| !is_configured(n)).collect();
+    println!();
+    for node in &found {
+        let (marker, note) = match new.iter().position(|n| *n == node) {
+            Some(i) => (format!("{:>3}.", i + 1), ""),
+            None => ("   -".to_string(), "  (already configured)"),
+        };
+        println!(
+            "{} {:<22} {:<12} {:<8} {:<6} block {}{}",
+            marker,
+            node.name(),
+            node.node_type.to_string(),
+            node.version.as_deref().unwrap_or("?"),
+            match node.role {
+                NodeRole::Bp => "bp",
+                NodeRole::Relay => "relay",
+            },
+            node.block_height
+                .map(|b| b.to_string())
+                .unwrap_or_else(|| "—".to_string()),
+            note
+        );
+    }
+    println!();
+
+    if new.is_empty() {
+        println!("All discovered nodes are already configured.");
+        return Ok(());
+    }
+    let Some(path) = app_config.config_path.clone() else {
+        println!("No config file location; add the nodes above as [[nodes]] entries manually.");
+        return Ok(());
+    };
+
+    print!(
+        "Add to {}? [a]ll, numbers (e.g. 1,3) or [n]one: ",
+        path.display()
+    );
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    let Some(selected) = parse_selection(&answer, new.len()) else {
+        bail!("Invalid selection '{}'", answer.trim());
+    };
+
+    for index in &selected {
+        let node = new[*index];
+        let entry = app_config.new_node(node.name(), node.host.clone(), node.port, node.role);
+        config::append_node(&path, &entry)
+            .with_context(|| format!("Failed to add '{}'", entry.name))?;
+        println!("Added '{}'", entry.name);
+    }
+    if selected.is_empty() {
+        println!("Nothing added.");
+    }
+    Ok(())
+}
+
+/// Probe every host/port pair, returning the nodes that answered, in order
+pub async fn scan(hosts: &[IpAddr], ports: &[u16]) -> Vec<DiscoveredNode> {
+    let client = match reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .no_proxy()
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Failed to create HTTP client: {}", e);
+            return Vec::new();
+        }
+    };
+    let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_PROBES));
+    let mut probes = JoinSet::new();
+    for (order, (host, port)) in hosts
+        .iter()
+        .flat_map(|host| ports.iter().map(move |port| (*host, *port)))
+        .enumerate()
+    {
+        let client = client.clone();
+        let permits = Arc::clone(&permits);
+        probes.spawn(async move {
+            let _permit = permits.acquire_owned().await.ok()?;
+            probe(&client, host, port).await.map(|node| (order, node))
+        });
+    }
+
+    let mut found = Vec::new();
+    while let Some(result) = probes.join_next().await {
+        if let Ok(Some(node)) = result {
+            found.push(node);
+        }
+    }
+    found.sort_by_key(|(order, _)| *order);
+    found.into_iter().map(|(_, node)| node).collect()
+}
+
+/// Fetch `/metrics` from one endpoint and check it belongs to a Cardano node
+async fn probe(client: &reqwest::Client, host: IpAddr, port: u16) -> Option<DiscoveredNode> {
+    let host = host.to_string();
+    let url = if host.contains(':') {
+        format!("http://[{}]:{}/metrics", host, port)
+    } else {
+        format!("http://{}:{}/metrics", host, port)
+    };
+    let response = client.get(&url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    let metrics = parse_prometheus_metrics(&response.text().await.ok()?);
+    if metrics.node_type == NodeType::Unknown {
+        return None;
+    }
+    Some(DiscoveredNode {
+        host,
+        port,
+        node_type: metrics.node_type,
+        version: metrics.build_info.short_version(),
+        block_height: metrics.block_height,
+        role: if metrics.kes_remaining.is_some() || metrics.kes_period.is_some() {
+            NodeRole::Bp
+        } else {
+            NodeRole::Relay
+        },
+    })
+}
+
+/// Hosts in a subnet like `10.0.0.0/24` (a bare address is a single host)
+///
+/// IPv4 only; network and broadcast addresses are skipped.
+pub fn expand_subnet(subnet: &str) -> Result<Vec<IpAddr>> {
+    let (addr, prefix) = match subnet.trim().split_once('/') {
+        Some((addr, prefix)) => (addr, prefix),
+        None => (subnet.trim(), "32"),
+    };
+    let addr: Ipv4Addr = addr
+        .parse()
+        .with_context(|| format!("Invalid subnet '{}' (expected e.g. 10.0.0.0/24)", subnet))?;
+    let prefix: u8 = prefix
+        .parse()
+        .ok()
+        .filter(|p| *p <= 32)
+        .with_context(|| format!("Invalid prefix length in '{}'", subnet))?;
+    if prefix < MIN_PREFIX_LEN {
+        bail!(
+            "Subnet '{}' is too large to scan (use /{} or smaller)",
+            subnet,
+            MIN_PREFIX_LEN
+        );
+    }
+
+    let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+    let network = u32::from(addr) & mask;
+    let broadcast = network | !mask;
+    let hosts = if prefix >= 31 {
+        network..=broadcast
+    } else {
+        network + 1..=broadcast - 1
+    };
+    Ok(hosts.map(|ip| IpAddr::V4(Ipv4Addr::from(ip))).collect())
+}
+
+/// Parse the answer to the "add which nodes?" prompt into 0-based indices
+fn parse_selection(input: &str, count: usize) -> Option<Vec<usize>> {
+    match input.trim().to_ascii_lowercase().as_str() {
+        "" | "n" | "none" => Some(Vec::new()),
+        "a" | "all" => Some((0..count).collect()),
+        list => {
+            let mut selected = Vec::new();
+            for part in list.split([',', ' ']).filter(|p| !p.is_empty()) {
+                let n: usize = part.parse().ok()?;
+                if n == 0 || n > count {
+                    return None;
+                }
+                if !selected.contains(&(n - 1)) {
+                    selected.push(n - 1);
+                }
+            }
+            Some(selected)
+        }
+    }
+}
+
+fn is_loopback(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .parse::<IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_subnet() {
+        let hosts = expand_subnet("192.168.1.77/24").unwrap();
+        assert_eq!(hosts.len(), 254);
+        assert_eq!(hosts[0], "192.168.1.1".parse::<IpAddr>().unwrap());
+        assert_eq!(hosts[253], "192.168.1.254".parse::<IpAddr>().unwrap());
+
+        assert_eq!(
+            expand_subnet("10.0.0.5").unwrap(),
+            vec!["10.0.0.5".parse::<IpAddr>().unwrap()]
+        );
+        assert_eq!(expand_subnet("10.0.0.4/31").unwrap().len(), 2);
+        assert!(expand_subnet("10.0.0.0/8").is_err());
+        assert!(expand_subnet("10.0.0/24").is_err());
+        assert!(expand_subnet("10.0.0.0/40").is_err());
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("\n", 3), Some(vec![]));
+        assert_eq!(parse_selection("all", 3), Some(vec![0, 1, 2]));
+        assert_eq!(parse_selection("1,3", 3), Some(vec![0, 2]));
+        assert_eq!(parse_selection("2 2", 3), Some(vec![1]));
+        assert_eq!(parse_selection("4", 3), None);
+        assert_eq!(parse_selection("x", 3), None);
+    }
+}
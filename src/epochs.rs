@@ -0,0 +1,198 @@
+//! Epoch boundary tracking
+//!
+//! Accumulates per-epoch statistics from successive metric samples and emits
+//! a summary when the node crosses into a new epoch.
+
+use crate::metrics::NodeMetrics;
+use serde::{Deserialize, Serialize};
+
+/// Summary statistics for one epoch, as observed by sview
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EpochSummary {
+    /// Epoch number
+    pub epoch: u64,
+    /// Unix timestamp of the first sample in this epoch
+    pub first_seen: u64,
+    /// Unix timestamp of the last sample in this epoch
+    pub last_seen: u64,
+    /// Blocks added to the chain while observed
+    pub blocks_seen: u64,
+    /// Blocks forged by this node (block producers)
+    pub blocks_forged: u64,
+    /// Slots where this node was leader but missed
+    pub missed_slots: u64,
+    /// Average connected peers
+    pub avg_peers: Option<f64>,
+    /// Node restarts detected during the epoch
+    pub restarts: u32,
+    /// True if sview didn't observe the whole epoch
+    pub partial: bool,
+}
+
+/// Running accumulator for the epoch currently in progress
+#[derive(Debug, Clone)]
+struct EpochAccumulator {
+    summary: EpochSummary,
+    last_block_height: Option<u64>,
+    last_forged: Option<u64>,
+    last_missed: Option<u64>,
+    last_uptime: Option<f64>,
+    peer_sum: f64,
+    peer_samples: u32,
+}
+
+/// Tracks epoch transitions for a single node
+#[derive(Debug, Clone, Default)]
+pub struct EpochTracker {
+    current: Option<EpochAccumulator>,
+}
+
+impl EpochTracker {
+    /// Feed a metrics sample; returns the finished summary when a new epoch begins
+    pub fn update(&mut self, metrics: &NodeMetrics, now: u64) -> Option<EpochSummary> {
+        let epoch = metrics.epoch?;
+
+        let mut finished = None;
+        let mut acc = match self.current.take() {
+            Some(acc) if acc.summary.epoch == epoch => acc,
+            Some(prev) => {
+                // Epoch advanced by more than one: we missed whole epochs in between
+                let contiguous = epoch == prev.summary.epoch + 1;
+                let next = EpochAccumulator::new(epoch, now, !contiguous).continuing(&prev);
+                let mut summary = prev.summary;
+                summary.partial |= !contiguous;
+                finished = Some(summary);
+                next
+            }
+            // Joined mid-epoch: the first epoch we see is always partial
+            None => EpochAccumulator::new(epoch, now, true),
+        };
+        acc.observe(metrics, now);
+        self.current = Some(acc);
+
+        finished
+    }
+
+    /// Summary of the epoch in progress so far
+    pub fn current(&self) -> Option<EpochSummary> {
+        self.current.as_ref().map(|acc| acc.summary.clone())
+    }
+}
+
+impl EpochAccumulator {
+    fn new(epoch: u64, now: u64, partial: bool) -> Self {
+        Self {
+            summary: EpochSummary {
+                epoch,
+                first_seen: now,
+                last_seen: now,
+                blocks_seen: 0,
+                blocks_forged: 0,
+                missed_slots: 0,
+                avg_peers: None,
+                restarts: 0,
+                partial,
+            },
+            last_block_height: None,
+            last_forged: None,
+            last_missed: None,
+            last_uptime: None,
+            peer_sum: 0.0,
+            peer_samples: 0,
+        }
+    }
+
+    /// Carry counter baselines over from the previous epoch so activity
+    /// between the last and first samples around the boundary isn't lost
+    fn continuing(mut self, prev: &EpochAccumulator) -> Self {
+        self.last_block_height = prev.last_block_height;
+        self.last_forged = prev.last_forged;
+        self.last_missed = prev.last_missed;
+        self.last_uptime = prev.last_uptime;
+        self
+    }
+
+    fn observe(&mut self, metrics: &NodeMetrics, now: u64) {
+        self.summary.last_seen = now;
+
+        // A drop in uptime means the node restarted; its counters reset too
+        let restarted = matches!(
+            (self.last_uptime, metrics.uptime_seconds),
+            (Some(prev), Some(cur)) if cur < prev
+        );
+        if restarted {
+            self.summary.restarts += 1;
+            self.last_forged = None;
+            self.last_missed = None;
+        }
+        self.last_uptime = metrics.uptime_seconds.or(self.last_uptime);
+
+        self.summary.blocks_seen +=
+            counter_delta(&mut self.last_block_height, metrics.block_height);
+        self.summary.blocks_forged += counter_delta(&mut self.last_forged, metrics.blocks_adopted);
+        self.summary.missed_slots += counter_delta(&mut self.last_missed, metrics.missed_slots);
+
+        if let Some(peers) = metrics.peers_connected {
+            self.peer_sum += peers as f64;
+            self.peer_samples += 1;
+            self.summary.avg_peers = Some(self.peer_sum / self.peer_samples as f64);
+        }
+    }
+}
+
+/// Increase of a monotonic counter since the last sample (0 on first sample or reset)
+fn counter_delta(last: &mut Option<u64>, current: Option<u64>) -> u64 {
+    let Some(current) = current else {
+        return 0;
+    };
+    let delta = last.map(|prev| current.saturating_sub(prev)).unwrap_or(0);
+    *last = Some(current);
+    delta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(epoch: u64, height: u64, forged: u64, uptime: f64) -> NodeMetrics {
+        NodeMetrics {
+            epoch: Some(epoch),
+            block_height: Some(height),
+            blocks_adopted: Some(forged),
+            uptime_seconds: Some(uptime),
+            peers_connected: Some(10),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_epoch_summary_on_boundary() {
+        let mut tracker = EpochTracker::default();
+        assert!(tracker.update(&sample(450, 100, 1, 10.0), 1000).is_none());
+        assert!(tracker.update(&sample(450, 120, 3, 20.0), 1100).is_none());
+        // Restart: uptime and forged counter reset
+        assert!(tracker.update(&sample(450, 130, 0, 5.0), 1200).is_none());
+        assert!(tracker.update(&sample(450, 140, 2, 15.0), 1300).is_none());
+
+        let summary = tracker.update(&sample(451, 150, 2, 25.0), 1400).unwrap();
+        assert_eq!(summary.epoch, 450);
+        assert_eq!(summary.blocks_seen, 40);
+        assert_eq!(summary.blocks_forged, 4);
+        assert_eq!(summary.restarts, 1);
+        assert_eq!(summary.avg_peers, Some(10.0));
+        assert!(summary.partial, "first epoch was joined mid-way");
+
+        // The following epoch was observed from its start
+        let next = tracker.update(&sample(452, 200, 2, 35.0), 1500).unwrap();
+        assert_eq!(next.epoch, 451);
+        assert_eq!(next.blocks_seen, 10, "blocks across the boundary count");
+        assert!(!next.partial);
+    }
+
+    #[test]
+    fn test_no_epoch_no_tracking() {
+        let mut tracker = EpochTracker::default();
+        assert!(tracker.update(&NodeMetrics::default(), 1000).is_none());
+        assert!(tracker.current().is_none());
+    }
+}
@@ -0,0 +1,318 @@
+//! Read-only follower mode (`sview --follow host:port`)
+//!
+//! An sview running with `--web` serves every node's latest scrape at
+//! `/state`. A follower started with that address takes its node list from
+//! there and polls `/state` instead of scraping the nodes, so a second team
+//! member can watch the same live state without doubling the load on the
+//! nodes' metrics endpoints.
+//!
+//! The follower keeps its own history from the time it started, in a
+//! throwaway data directory, and sends no notifications. Actions that change
+//! the configuration (settings, adding nodes, reloading) are disabled.
+
+use crate::app::{App, NodeState};
+use crate::config::{AppConfig, CliArgs, FileConfig, HealthThresholds, NodeConfig, NodeRole};
+use crate::genesis::NetworkParams;
+use crate::metrics::{FetchError, NodeMetrics};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Host prefix of a node mirrored from another sview (`follow:host:port`)
+const HOST_PREFIX: &str = "follow:";
+
+/// Current `/state` format version
+pub const STATE_VERSION: u32 = 1;
+
+/// Longest the first `/state` request may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long one `/state` request answers the scrapes of the leader's nodes;
+/// a refresh scrapes them one after another, well within this
+const STATE_REUSE: Duration = Duration::from_millis(500);
+
+/// Every node's latest state, as served at `/state`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedState {
+    pub version: u32,
+    pub nodes: Vec<SharedNode>,
+}
+
+/// One node's configuration and latest scrape
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedNode {
+    pub name: String,
+    pub role: NodeRole,
+    pub network: String,
+    pub group: Option<String>,
+    pub network_params: NetworkParams,
+    pub thresholds: HealthThresholds,
+    /// Last scrape error, while the node isn't answering
+    pub error: Option<FetchError>,
+    pub metrics: NodeMetrics,
+}
+
+impl SharedState {
+    /// State of every node in `app`
+    pub fn from_app(app: &App) -> Self {
+        Self {
+            version: STATE_VERSION,
+            nodes: app.nodes.iter().map(SharedNode::from_node).collect(),
+        }
+    }
+
+    /// The metrics of the node named `name`, or the error the serving sview
+    /// last saw for it
+    pub fn node_metrics(&self, name: &str) -> Result<NodeMetrics, FetchError> {
+        let node = self
+            .nodes
+            .iter()
+            .find(|node| node.name == name)
+            .ok_or_else(|| FetchError::Other(format!("'{}' is no longer served", name)))?;
+        match &node.error {
+            Some(error) => Err(error.clone()),
+            None if !node.metrics.connected => {
+                Err(FetchError::Other("not scraped yet".to_string()))
+            }
+            None => Ok(node.metrics.clone()),
+        }
+    }
+}
+
+impl SharedNode {
+    fn from_node(node: &NodeState) -> Self {
+        let mut metrics = node.metrics.clone();
+        // NaN and infinity don't survive JSON
+        metrics.raw.retain(|_, value| value.is_finite());
+        Self {
+            name: node.config.node_name.clone(),
+            role: node.role,
+            network: node.config.network.clone(),
+            group: node.group.clone(),
+            network_params: NetworkParams {
+                epoch_length: node.config.epoch_length,
+                slot_length_secs: node.config.slot_length_secs,
+                network_magic: None,
+                active_slots_coeff: node.config.active_slots_coeff,
+                slot_zero_time: node.config.slot_zero_time,
+            },
+            thresholds: node.thresholds,
+            error: node.last_error.clone(),
+            metrics,
+        }
+    }
+}
+
+/// A followed sview, whose `/state` is fetched once for all of its nodes
+pub struct Leader {
+    client: reqwest::Client,
+    url: String,
+    /// Last fetch and when it finished
+    latest: tokio::sync::Mutex<Option<(Instant, StateResult)>>,
+}
+
+type StateResult = Result<Arc<SharedState>, FetchError>;
+
+impl Leader {
+    /// The sview at `addr`, shared by the metrics clients of its nodes
+    pub fn get(addr: &str, timeout: Duration) -> Result<Arc<Self>> {
+        static LEADERS: OnceLock<Mutex<HashMap<String, Arc<Leader>>>> = OnceLock::new();
+        let mut leaders = LEADERS
+            .get_or_init(Mutex::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(leader) = leaders.get(addr) {
+            return Ok(leader.clone());
+        }
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("Failed to create HTTP client")?;
+        let leader = Arc::new(Self {
+            client,
+            url: state_url(addr),
+            latest: tokio::sync::Mutex::default(),
+        });
+        leaders.insert(addr.to_string(), leader.clone());
+        Ok(leader)
+    }
+
+    /// The leader's state, fetched unless another node's scrape just did
+    pub async fn state(&self) -> StateResult {
+        let mut latest = self.latest.lock().await;
+        if let Some((fetched, state)) = latest.as_ref() {
+            if fetched.elapsed() < STATE_REUSE {
+                return state.clone();
+            }
+        }
+        let state = fetch_state(&self.client, &self.url)
+            .await
+            .map(Arc::new)
+            .map_err(|e| match e.downcast::<reqwest::Error>() {
+                Ok(e) => FetchError::from(e),
+                Err(e) => FetchError::Other(format!("{:#}", e)),
+            });
+        *latest = Some((Instant::now(), state.clone()));
+        state
+    }
+}
+
+/// The sview a `follow:` host mirrors, if it is one
+pub fn leader(host: &str) -> Option<&str> {
+    host.strip_prefix(HOST_PREFIX)
+}
+
+/// URL of the `/state` endpoint of the sview at `addr` (`host:port` or a URL)
+pub fn state_url(addr: &str) -> String {
+    let (scheme, addr) = addr.split_once("://").unwrap_or(("http", addr));
+    let addr = addr.trim_end_matches('/').trim_end_matches("/state");
+    format!("{}://{}/state", scheme, addr)
+}
+
+/// Fetch and check the state served at `url`
+pub async fn fetch_state(client: &reqwest::Client, url: &str) -> Result<SharedState> {
+    let state: SharedState = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    if state.version != STATE_VERSION {
+        bail!(
+            "state format {} isn't supported (expected {}); run the same sview version",
+            state.version,
+            STATE_VERSION
+        );
+    }
+    Ok(state)
+}
+
+/// Config mirroring the nodes of the sview at `addr`; globals come from the
+/// command line and defaults, not from a config file
+pub async fn app_config(args: CliArgs, addr: &str) -> Result<AppConfig> {
+    let client = reqwest::Client::builder()
+        .timeout(CONNECT_TIMEOUT)
+        .build()?;
+    let state = fetch_state(&client, &state_url(addr))
+        .await
+        .with_context(|| format!("Failed to read the state of the sview at {}", addr))?;
+    if state.nodes.is_empty() {
+        bail!("The sview at {} has no nodes", addr);
+    }
+    resolve(args, addr, &state)
+}
+
+fn resolve(args: CliArgs, addr: &str, state: &SharedState) -> Result<AppConfig> {
+    let nodes = state
+        .nodes
+        .iter()
+        .map(|node| {
+            serde_json::from_value::<NodeConfig>(serde_json::json!({
+                "name": node.name,
+                "host": format!("{}{}", HOST_PREFIX, addr),
+                "role": node.role,
+                "group": node.group,
+                "network": node.network,
+            }))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let file_config = FileConfig {
+        nodes,
+        ..Default::default()
+    };
+
+    let mut config = AppConfig::resolve(args, None, file_config)?;
+    for (runtime, node) in config.nodes.iter_mut().zip(&state.nodes) {
+        runtime.network_params = node.network_params;
+        runtime.thresholds = node.thresholds;
+    }
+    Ok(config)
+}
+
+/// Data directory for follower mode, so the mirrored history stays out of
+/// the real one (which the followed sview may be writing to)
+pub fn data_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("sview-follow-{}", std::process::id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    fn shared_node(name: &str, metrics: NodeMetrics, error: Option<FetchError>) -> SharedNode {
+        SharedNode {
+            name: name.to_string(),
+            role: NodeRole::Bp,
+            network: "preview".to_string(),
+            group: Some("pool".to_string()),
+            network_params: NetworkParams::with_epoch_length(86400),
+            thresholds: HealthThresholds::default(),
+            error,
+            metrics,
+        }
+    }
+
+    #[test]
+    fn test_state_roundtrip() {
+        let mut metrics = NodeMetrics {
+            block_height: Some(2_500_000),
+            connected: true,
+            ..Default::default()
+        };
+        metrics
+            .raw
+            .insert("cardano_node_metrics_density_real".into(), 0.05);
+        let state = SharedState {
+            version: STATE_VERSION,
+            nodes: vec![
+                shared_node("BP", metrics, None),
+                shared_node("Relay", NodeMetrics::default(), Some(FetchError::Timeout)),
+                shared_node("New", NodeMetrics::default(), None),
+            ],
+        };
+
+        let json = serde_json::to_string(&state).unwrap();
+        let state: SharedState = serde_json::from_str(&json).unwrap();
+        let bp = state.node_metrics("BP").unwrap();
+        assert_eq!(bp.block_height, Some(2_500_000));
+        assert_eq!(bp.raw_value("density_real"), Some(0.05));
+        assert_eq!(
+            state.node_metrics("Relay").unwrap_err(),
+            FetchError::Timeout
+        );
+        assert!(state.node_metrics("New").is_err());
+        assert!(state.node_metrics("Gone").is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let state = SharedState {
+            version: STATE_VERSION,
+            nodes: vec![shared_node("BP", NodeMetrics::default(), None)],
+        };
+        let config = resolve(CliArgs::parse_from(["sview"]), "10.0.0.5:8080", &state).unwrap();
+        assert_eq!(config.nodes.len(), 1);
+        let node = &config.nodes[0];
+        assert_eq!(node.name, "BP");
+        assert_eq!(node.role, NodeRole::Bp);
+        assert_eq!(node.network, "preview");
+        assert_eq!(node.network_params.epoch_length, 86400);
+        assert_eq!(leader(&node.host), Some("10.0.0.5:8080"));
+        assert!(config.notify.is_empty());
+
+        assert_eq!(state_url("10.0.0.5:8080"), "http://10.0.0.5:8080/state");
+        assert_eq!(
+            state_url("http://10.0.0.5:8080/"),
+            "http://10.0.0.5:8080/state"
+        );
+        assert_eq!(
+            state_url("https://sview.example.com/state"),
+            "https://sview.example.com/state"
+        );
+    }
+}
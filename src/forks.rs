@@ -0,0 +1,126 @@
+//! Fork and rollback tracking
+//!
+//! The node's `forks` counter increments whenever it switches to a competing
+//! chain. Each increment is recorded with the block heights around it, so a
+//! switch that rolled the tip back shows its depth in the incidents view.
+
+use crate::metrics::NodeMetrics;
+use std::collections::VecDeque;
+
+/// Fork events kept in memory
+const MAX_FORK_EVENTS: usize = 200;
+
+/// One or more chain switches seen between two scrapes
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForkEvent {
+    /// Unix timestamp of the scrape that saw the switch
+    pub timestamp: u64,
+    /// Forks counted since the previous scrape
+    pub new_forks: u64,
+    /// Node's fork counter after the switch
+    pub total_forks: u64,
+    /// Block height at the previous scrape
+    pub height_before: Option<u64>,
+    /// Block height after the switch
+    pub height_after: Option<u64>,
+    /// Slot of the new tip
+    pub slot: Option<u64>,
+}
+
+impl ForkEvent {
+    /// Blocks the tip moved back by, if the switch rolled it back
+    pub fn rollback_depth(&self) -> Option<u64> {
+        match (self.height_before, self.height_after) {
+            (Some(before), Some(after)) if after < before => Some(before - after),
+            _ => None,
+        }
+    }
+}
+
+/// Watches a node's fork counter across scrapes
+#[derive(Debug, Clone, Default)]
+pub struct ForkTracker {
+    last_forks: Option<u64>,
+    last_height: Option<u64>,
+    events: VecDeque<ForkEvent>,
+}
+
+impl ForkTracker {
+    /// Feed a metrics sample; returns the event if the fork counter went up
+    pub fn update(&mut self, metrics: &NodeMetrics, now: u64) -> Option<ForkEvent> {
+        let height_before = self.last_height;
+        if metrics.block_height.is_some() {
+            self.last_height = metrics.block_height;
+        }
+        let forks = metrics.forks?;
+        let previous = self.last_forks.replace(forks);
+
+        // First sample, or the counter reset with a node restart
+        let new_forks = forks.checked_sub(previous?).filter(|n| *n > 0)?;
+        let event = ForkEvent {
+            timestamp: now,
+            new_forks,
+            total_forks: forks,
+            height_before,
+            height_after: metrics.block_height,
+            slot: metrics.slot_num,
+        };
+        if self.events.len() == MAX_FORK_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(event.clone());
+        Some(event)
+    }
+
+    /// Recorded events, newest first
+    pub fn recent(&self) -> impl Iterator<Item = &ForkEvent> {
+        self.events.iter().rev()
+    }
+
+    /// Forks counted at or after `since`
+    pub fn count_since(&self, since: u64) -> u64 {
+        self.events
+            .iter()
+            .filter(|e| e.timestamp >= since)
+            .map(|e| e.new_forks)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(forks: u64, height: u64) -> NodeMetrics {
+        NodeMetrics {
+            forks: Some(forks),
+            block_height: Some(height),
+            slot_num: Some(height * 20),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_fork_events() {
+        let mut tracker = ForkTracker::default();
+        assert_eq!(tracker.update(&sample(3, 100), 1000), None);
+        assert_eq!(tracker.update(&sample(3, 101), 1010), None);
+
+        let event = tracker.update(&sample(4, 100), 1020).unwrap();
+        assert_eq!(event.new_forks, 1);
+        assert_eq!(event.total_forks, 4);
+        assert_eq!(event.height_before, Some(101));
+        assert_eq!(event.rollback_depth(), Some(1));
+
+        let event = tracker.update(&sample(6, 102), 1030).unwrap();
+        assert_eq!(event.new_forks, 2);
+        assert_eq!(event.rollback_depth(), None);
+
+        // A restart resets the counter without recording a fork
+        assert_eq!(tracker.update(&sample(0, 102), 1040), None);
+        assert_eq!(tracker.update(&sample(1, 103), 1050).unwrap().new_forks, 1);
+
+        assert_eq!(tracker.count_since(1025), 3);
+        assert_eq!(tracker.recent().next().unwrap().timestamp, 1050);
+    }
+}
@@ -0,0 +1,87 @@
+//! Value formatting shared by the TUI, alerts and reports
+//!
+//! Kept out of `ui` so modules that only build text (alert messages,
+//! reports, notifications) don't depend on the rendering code.
+
+/// A length of time in its two largest units: "45s", "4m 12s", "2h 5m", "3d 4h"
+pub fn format_duration(secs: u64) -> String {
+    match secs {
+        s if s >= 86400 => format!("{}d {}h", s / 86400, (s % 86400) / 3600),
+        s if s >= 3600 => format!("{}h {}m", s / 3600, (s % 3600) / 60),
+        s if s >= 60 => format!("{}m {}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    }
+}
+
+/// Format a lovelace amount as ADA with K/M suffixes
+pub fn format_ada(lovelace: Option<u64>) -> String {
+    match lovelace {
+        Some(l) => {
+            let ada = l as f64 / 1_000_000.0;
+            if ada >= 1_000_000.0 {
+                format!("{:.2}M ₳", ada / 1_000_000.0)
+            } else if ada >= 1_000.0 {
+                format!("{:.1}K ₳", ada / 1_000.0)
+            } else {
+                format!("{:.0} ₳", ada)
+            }
+        }
+        None => "—".to_string(),
+    }
+}
+
+/// A count with thousands separators: "1,234,567"
+pub fn format_number(n: u64) -> String {
+    let s = n.to_string();
+    let mut result = String::new();
+    for (i, c) in s.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result.chars().rev().collect()
+}
+
+/// A size in B, KB, MB or GB (binary units)
+pub fn format_bytes(bytes: Option<u64>) -> String {
+    match bytes {
+        Some(b) if b >= 1_073_741_824 => format!("{:.2} GB", b as f64 / 1_073_741_824.0),
+        Some(b) if b >= 1_048_576 => format!("{:.2} MB", b as f64 / 1_048_576.0),
+        Some(b) if b >= 1024 => format!("{:.2} KB", b as f64 / 1024.0),
+        Some(b) => format!("{} B", b),
+        None => "—".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(252), "4m 12s");
+        assert_eq!(format_duration(7500), "2h 5m");
+        assert_eq!(format_duration(3 * 86400 + 4 * 3600 + 59), "3d 4h");
+    }
+
+    #[test]
+    fn test_format_number_and_bytes() {
+        assert_eq!(format_number(999), "999");
+        assert_eq!(format_number(1_234_567), "1,234,567");
+        assert_eq!(format_bytes(Some(512)), "512 B");
+        assert_eq!(format_bytes(Some(1536)), "1.50 KB");
+        assert_eq!(format_bytes(Some(3 * 1_073_741_824)), "3.00 GB");
+        assert_eq!(format_bytes(None), "—");
+    }
+
+    #[test]
+    fn test_format_ada() {
+        assert_eq!(format_ada(None), "—");
+        assert_eq!(format_ada(Some(450_000_000)), "450 ₳");
+        assert_eq!(format_ada(Some(12_345_000_000)), "12.3K ₳");
+        assert_eq!(format_ada(Some(2_500_000_000_000)), "2.50M ₳");
+    }
+}
@@ -0,0 +1,350 @@
+//! Per-network slot arithmetic parameters
+//!
+//! Epoch length and slot duration differ between networks (preview epochs are
+//! a fifth of mainnet's), so nodes on different networks can't share a single
+//! global `epoch_length`. Parameters are read from a node's Shelley genesis
+//! file (or the node's `config.json`, which points at it), or looked up from
+//! the well-known genesis hashes of the public networks.
+
+use crate::metrics::PeerTargets;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Active slot coefficient of all the public networks
+pub const DEFAULT_ACTIVE_SLOTS_COEFF: f64 = 0.05;
+
+/// Slot arithmetic parameters for one network
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NetworkParams {
+    /// Slots per epoch
+    pub epoch_length: u64,
+    /// Slot duration in seconds
+    pub slot_length_secs: f64,
+    /// Network magic (if known)
+    pub network_magic: Option<u64>,
+    /// Share of slots expected to hold a block (`activeSlotsCoeff`)
+    pub active_slots_coeff: f64,
+    /// Unix time slot 0 would have started at the current slot length, so
+    /// the wall clock gives the network slot (if known)
+    #[serde(default)]
+    pub slot_zero_time: Option<u64>,
+}
+
+impl NetworkParams {
+    /// Parameters with the given epoch length and 1-second slots
+    pub fn with_epoch_length(epoch_length: u64) -> Self {
+        Self {
+            epoch_length,
+            slot_length_secs: 1.0,
+            network_magic: None,
+            active_slots_coeff: DEFAULT_ACTIVE_SLOTS_COEFF,
+            slot_zero_time: None,
+        }
+    }
+
+    /// Well-known network name for this network's magic
+    pub fn network_name(&self) -> Option<&'static str> {
+        let magic = self.network_magic?;
+        KNOWN_NETWORKS
+            .iter()
+            .find(|n| n.magic == magic)
+            .map(|n| n.name)
+    }
+}
+
+/// A public network with its genesis hashes
+struct KnownNetwork {
+    name: &'static str,
+    magic: u64,
+    epoch_length: u64,
+    /// `slot_zero_time`: the Shelley-era start less its first slot, as
+    /// Byron's 20-second slots came before it on mainnet and preprod
+    slot_zero_time: Option<u64>,
+    byron_genesis_hash: Option<&'static str>,
+    shelley_genesis_hash: Option<&'static str>,
+}
+
+const KNOWN_NETWORKS: [KnownNetwork; 4] = [
+    KnownNetwork {
+        name: "mainnet",
+        magic: 764824073,
+        epoch_length: 432000,
+        // Shelley started at slot 4492800, 2020-07-29T21:44:51Z
+        slot_zero_time: Some(1_591_566_291),
+        byron_genesis_hash: Some(
+            "5f20df933584822601f9e3f8c024eb5eb252fe8cefb24d1317dc3d432e940ebb",
+        ),
+        shelley_genesis_hash: Some(
+            "1a3be38bcbb7911969283716ad7aa550250226b76a61fc51cc9a9a35d9276d81",
+        ),
+    },
+    KnownNetwork {
+        name: "preprod",
+        magic: 1,
+        epoch_length: 432000,
+        // Shelley started at slot 86400, 2022-06-21T00:00:00Z
+        slot_zero_time: Some(1_655_683_200),
+        byron_genesis_hash: Some(
+            "d4b8de7a11d929a323373cbab6c1a9bdc931beffff11db111cf9d57356ee1937",
+        ),
+        shelley_genesis_hash: Some(
+            "162d29c4e1cf6b8a84f2d692e67a3ac6bc7851bc3e6e4afe64d15778bed8bd86",
+        ),
+    },
+    KnownNetwork {
+        name: "preview",
+        magic: 2,
+        epoch_length: 86400,
+        // No Byron era: slot 0 at systemStart, 2022-10-25T00:00:00Z
+        slot_zero_time: Some(1_666_656_000),
+        byron_genesis_hash: Some(
+            "83de1d7302569ad56cf9139a41e2e11346d4cb4a31c00142557b6ab3fa550761",
+        ),
+        shelley_genesis_hash: Some(
+            "363498d1024f84bb39d3fa9593ce391483cb40d479b87233f868d6e57c3a400d",
+        ),
+    },
+    KnownNetwork {
+        name: "sanchonet",
+        magic: 4,
+        epoch_length: 86400,
+        slot_zero_time: None,
+        byron_genesis_hash: None,
+        shelley_genesis_hash: None,
+    },
+];
+
+impl KnownNetwork {
+    fn params(&self) -> NetworkParams {
+        NetworkParams {
+            epoch_length: self.epoch_length,
+            slot_length_secs: 1.0,
+            network_magic: Some(self.magic),
+            active_slots_coeff: DEFAULT_ACTIVE_SLOTS_COEFF,
+            slot_zero_time: self.slot_zero_time,
+        }
+    }
+}
+
+/// Parameters of the public network called `name`
+pub fn params_for_network(name: &str) -> Option<NetworkParams> {
+    KNOWN_NETWORKS
+        .iter()
+        .find(|n| n.name.eq_ignore_ascii_case(name))
+        .map(KnownNetwork::params)
+}
+
+/// Look up a public network by its Byron or Shelley genesis hash
+pub fn params_for_genesis_hash(hash: &str) -> Option<NetworkParams> {
+    let hash = hash.trim().to_lowercase();
+    KNOWN_NETWORKS
+        .iter()
+        .find(|n| {
+            n.byron_genesis_hash == Some(hash.as_str())
+                || n.shelley_genesis_hash == Some(hash.as_str())
+        })
+        .map(KnownNetwork::params)
+}
+
+/// Fields of interest from a Shelley genesis file
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ShelleyGenesis {
+    epoch_length: u64,
+    #[serde(default = "default_slot_length")]
+    slot_length: f64,
+    network_magic: Option<u64>,
+    #[serde(default = "default_active_slots_coeff")]
+    active_slots_coeff: f64,
+    system_start: Option<String>,
+}
+
+fn default_slot_length() -> f64 {
+    1.0
+}
+
+fn default_active_slots_coeff() -> f64 {
+    DEFAULT_ACTIVE_SLOTS_COEFF
+}
+
+/// Fields of interest from a cardano-node `config.json`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NodeConfigFile {
+    shelley_genesis_file: Option<String>,
+    shelley_genesis_hash: Option<String>,
+    byron_genesis_hash: Option<String>,
+    target_number_of_known_peers: Option<u64>,
+    target_number_of_established_peers: Option<u64>,
+    target_number_of_active_peers: Option<u64>,
+}
+
+/// Peer selection targets set in a node `config.json`
+///
+/// `None` for a Shelley genesis file or a config without `TargetNumberOf*`
+/// settings.
+pub fn load_peer_targets(path: &Path) -> Option<PeerTargets> {
+    let content = fs::read_to_string(path).ok()?;
+    let config: NodeConfigFile = serde_json::from_str(&content).ok()?;
+    let targets = PeerTargets {
+        known: config.target_number_of_known_peers,
+        established: config.target_number_of_established_peers,
+        active: config.target_number_of_active_peers,
+    };
+    targets.is_set().then_some(targets)
+}
+
+/// Load network parameters from a Shelley genesis file or a node `config.json`
+///
+/// A node config's `ShelleyGenesisFile` is resolved relative to the config's
+/// directory, as cardano-node does; if it can't be read, the genesis hashes in
+/// the config are matched against the public networks instead.
+pub fn load_genesis_file(path: &Path) -> Result<NetworkParams> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read genesis file {}", path.display()))?;
+
+    if let Ok(genesis) = serde_json::from_str::<ShelleyGenesis>(&content) {
+        if genesis.slot_length <= 0.0 {
+            bail!("Invalid slotLength in {}", path.display());
+        }
+        if !(genesis.active_slots_coeff > 0.0 && genesis.active_slots_coeff <= 1.0) {
+            bail!("Invalid activeSlotsCoeff in {}", path.display());
+        }
+        // A public network's Byron era shifts its slots; a private one
+        // without it starts slot 0 at systemStart
+        let known = genesis
+            .network_magic
+            .and_then(|magic| KNOWN_NETWORKS.iter().find(|n| n.magic == magic));
+        let slot_zero_time = match known {
+            Some(network) => network.slot_zero_time,
+            None => genesis
+                .system_start
+                .as_deref()
+                .and_then(|start| chrono::DateTime::parse_from_rfc3339(start).ok())
+                .and_then(|start| u64::try_from(start.timestamp()).ok()),
+        };
+        return Ok(NetworkParams {
+            epoch_length: genesis.epoch_length,
+            slot_length_secs: genesis.slot_length,
+            network_magic: genesis.network_magic,
+            active_slots_coeff: genesis.active_slots_coeff,
+            slot_zero_time,
+        });
+    }
+
+    let node_config: NodeConfigFile = serde_json::from_str(&content).with_context(|| {
+        format!(
+            "{} is neither a Shelley genesis file nor a node config",
+            path.display()
+        )
+    })?;
+
+    if let Some(shelley_file) = &node_config.shelley_genesis_file {
+        let shelley_path = path
+            .parent()
+            .map(|dir| dir.join(shelley_file))
+            .unwrap_or_else(|| shelley_file.into());
+        if shelley_path.is_file() {
+            return load_genesis_file(&shelley_path);
+        }
+    }
+
+    [
+        node_config.shelley_genesis_hash,
+        node_config.byron_genesis_hash,
+    ]
+    .iter()
+    .flatten()
+    .find_map(|hash| params_for_genesis_hash(hash))
+    .with_context(|| {
+        format!(
+            "Could not determine network parameters from {}",
+            path.display()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_params_for_genesis_hash() {
+        let preview = params_for_genesis_hash(
+            "363498D1024F84BB39D3FA9593CE391483CB40D479B87233F868D6E57C3A400D",
+        )
+        .unwrap();
+        assert_eq!(preview.epoch_length, 86400);
+        assert_eq!(preview.network_name(), Some("preview"));
+
+        let mainnet = params_for_genesis_hash(
+            "5f20df933584822601f9e3f8c024eb5eb252fe8cefb24d1317dc3d432e940ebb",
+        )
+        .unwrap();
+        assert_eq!(mainnet.epoch_length, 432000);
+
+        assert!(params_for_genesis_hash("deadbeef").is_none());
+    }
+
+    #[test]
+    fn test_load_shelley_genesis() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("shelley-genesis.json");
+        fs::write(
+            &path,
+            r#"{"epochLength": 500, "slotLength": 0.2, "activeSlotsCoeff": 0.1, "networkMagic": 42, "systemStart": "2024-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+
+        let params = load_genesis_file(&path).unwrap();
+        assert_eq!(params.epoch_length, 500);
+        assert_eq!(params.slot_length_secs, 0.2);
+        assert_eq!(params.network_magic, Some(42));
+        assert_eq!(params.active_slots_coeff, 0.1);
+        assert_eq!(params.network_name(), None);
+        assert_eq!(params.slot_zero_time, Some(1_704_067_200));
+    }
+
+    #[test]
+    fn test_load_node_config() {
+        let dir = TempDir::new().unwrap();
+        fs::write(
+            dir.path().join("shelley-genesis.json"),
+            r#"{"epochLength": 86400, "networkMagic": 2}"#,
+        )
+        .unwrap();
+        let config = dir.path().join("config.json");
+        fs::write(
+            &config,
+            r#"{"ShelleyGenesisFile": "shelley-genesis.json", "Protocol": "Cardano"}"#,
+        )
+        .unwrap();
+        let params = load_genesis_file(&config).unwrap();
+        assert_eq!(params.epoch_length, 86400);
+        assert_eq!(params.active_slots_coeff, 0.05);
+        assert_eq!(params.network_name(), Some("preview"));
+        assert_eq!(params.slot_zero_time, Some(1_666_656_000));
+
+        // Genesis file missing: fall back to the hash in the config
+        let config = dir.path().join("preprod-config.json");
+        fs::write(
+            &config,
+            r#"{"ShelleyGenesisFile": "missing.json", "ShelleyGenesisHash": "162d29c4e1cf6b8a84f2d692e67a3ac6bc7851bc3e6e4afe64d15778bed8bd86"}"#,
+        )
+        .unwrap();
+        let params = load_genesis_file(&config).unwrap();
+        assert_eq!(params.network_name(), Some("preprod"));
+        assert_eq!(load_peer_targets(&config), None);
+
+        fs::write(
+            &config,
+            r#"{"TargetNumberOfKnownPeers": 150, "TargetNumberOfEstablishedPeers": 40, "TargetNumberOfActivePeers": 20}"#,
+        )
+        .unwrap();
+        let targets = load_peer_targets(&config).unwrap();
+        assert_eq!(targets.by_state(), (Some(20), Some(20), Some(110)));
+    }
+}
@@ -1,14 +1,399 @@
 //! IP Geolocation for peer analysis
 //!
-//! Provides IP-to-location lookup using ip-api.com (free, no API key required).
-//! Supports batch queries and caching to minimize API calls.
-
+//! Provides IP-to-location lookup through a `GeoProvider` chosen under
+//! `[global.geoip]`: ip-api.com (free, no API key required, the default),
+//! ipinfo.io (optional access token) or local MaxMind MMDB databases, for
+//! networks that can't reach either service. Lookups are batched and cached
+//! to minimize API calls. The cache is kept in the data directory, so
+//! restarts don't query every peer again.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, warn};
 
+/// How long a looked-up location is trusted (addresses rarely move); the
+/// in-memory cache used to last an hour
+const CACHE_TTL: Duration = Duration::from_secs(7 * 86400);
+
+/// How long an address the provider had no location for is left alone
+/// before asking again (a failed lookup shouldn't blank a peer for a week)
+const MISSING_TTL: Duration = Duration::from_secs(3600);
+
+/// Longest a single request to a lookup service may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+const IP_API_BATCH_URL: &str = "http://ip-api.com/batch";
+
+const IP_API_FIELDS: &str = "status,country,countryCode,region,city,lat,lon,isp,as";
+
+const IPINFO_URL: &str = "https://ipinfo.io";
+
+/// `[global.geoip]` in the config file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct GeoIpConfig {
+    /// Where peer locations come from
+    #[serde(default)]
+    pub provider: GeoProviderKind,
+    /// ipinfo.io access token (lookups without one are limited)
+    pub token: Option<String>,
+    /// GeoLite2/GeoIP2 City database, required by the mmdb provider
+    pub city_db: Option<PathBuf>,
+    /// GeoLite2/GeoIP2 ASN database for the mmdb provider's network names
+    pub asn_db: Option<PathBuf>,
+}
+
+impl GeoIpConfig {
+    /// Which provider (and database) a cached location came from; locations
+    /// from another source are looked up again
+    pub fn source(&self) -> String {
+        match self.provider {
+            GeoProviderKind::IpApi => "ip-api".to_string(),
+            GeoProviderKind::IpInfo => "ipinfo".to_string(),
+            GeoProviderKind::Mmdb => format!(
+                "mmdb:{}",
+                self.city_db.as_deref().unwrap_or(Path::new("")).display()
+            ),
+        }
+    }
+}
+
+/// Location lookup services
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum GeoProviderKind {
+    #[default]
+    #[serde(rename = "ip-api")]
+    IpApi,
+    #[serde(rename = "ipinfo")]
+    IpInfo,
+    #[serde(rename = "mmdb")]
+    Mmdb,
+}
+
+/// Locations for the addresses a provider answered for; `None` where the
+/// provider has no location. Addresses missing from the map are retried.
+pub type LookupResult = Result<HashMap<String, Option<GeoLocation>>>;
+
+type LookupFuture<'a> = Pin<Box<dyn Future<Output = LookupResult> + Send + 'a>>;
+
+/// A source of IP locations
+pub trait GeoProvider: Send + Sync {
+    /// Most addresses to look up in one call
+    fn batch_limit(&self) -> usize;
+
+    /// Shortest time between calls, to stay within the service's rate limit
+    fn min_interval(&self) -> Duration;
+
+    /// Look up at most `batch_limit` public addresses
+    fn lookup<'a>(&'a self, ips: &'a [String]) -> LookupFuture<'a>;
+}
+
+/// Build the provider selected in `config`
+pub fn provider_from_config(config: &GeoIpConfig) -> Result<Arc<dyn GeoProvider>> {
+    Ok(match config.provider {
+        GeoProviderKind::IpApi => Arc::new(IpApiProvider::new(IP_API_BATCH_URL)?),
+        GeoProviderKind::IpInfo => Arc::new(IpInfoProvider::new(IPINFO_URL, config.token.clone())?),
+        GeoProviderKind::Mmdb => {
+            let Some(city_db) = &config.city_db else {
+                bail!("the mmdb GeoIP provider needs city_db");
+            };
+            Arc::new(MmdbProvider::open(city_db, config.asn_db.as_deref())?)
+        }
+    })
+}
+
+fn http_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("sview/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .context("Failed to create HTTP client for GeoIP")
+}
+
+/// ip-api.com: free, no key, batches of 100 and 45 requests a minute
+pub struct IpApiProvider {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl IpApiProvider {
+    fn new(url: &str) -> Result<Self> {
+        Ok(Self {
+            client: http_client()?,
+            url: url.to_string(),
+        })
+    }
+}
+
+impl GeoProvider for IpApiProvider {
+    fn batch_limit(&self) -> usize {
+        100
+    }
+
+    fn min_interval(&self) -> Duration {
+        Duration::from_millis(1500)
+    }
+
+    fn lookup<'a>(&'a self, ips: &'a [String]) -> LookupFuture<'a> {
+        Box::pin(async move {
+            let batch_query: Vec<serde_json::Value> = ips
+                .iter()
+                .map(|ip| serde_json::json!({ "query": ip, "fields": IP_API_FIELDS }))
+                .collect();
+            let json_array = self
+                .client
+                .post(&self.url)
+                .json(&batch_query)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Vec<serde_json::Value>>()
+                .await?;
+            Ok(ips
+                .iter()
+                .cloned()
+                .zip(json_array.iter().map(parse_ip_api))
+                .collect())
+        })
+    }
+}
+
+/// ipinfo.io: one request per address, run concurrently
+pub struct IpInfoProvider {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl IpInfoProvider {
+    fn new(url: &str, token: Option<String>) -> Result<Self> {
+        Ok(Self {
+            client: http_client()?,
+            url: url.to_string(),
+            token: token.filter(|t| !t.is_empty()),
+        })
+    }
+}
+
+impl GeoProvider for IpInfoProvider {
+    fn batch_limit(&self) -> usize {
+        25
+    }
+
+    fn min_interval(&self) -> Duration {
+        Duration::from_millis(1500)
+    }
+
+    fn lookup<'a>(&'a self, ips: &'a [String]) -> LookupFuture<'a> {
+        Box::pin(async move {
+            let mut requests = tokio::task::JoinSet::new();
+            for ip in ips {
+                let mut request = self.client.get(format!("{}/{}/json", self.url, ip));
+                if let Some(token) = &self.token {
+                    request = request.bearer_auth(token);
+                }
+                let ip = ip.clone();
+                requests.spawn(async move {
+                    let result = async {
+                        request
+                            .send()
+                            .await?
+                            .error_for_status()?
+                            .json::<serde_json::Value>()
+                            .await
+                    }
+                    .await;
+                    (ip, result)
+                });
+            }
+
+            let mut results = HashMap::new();
+            let mut last_error = None;
+            while let Some(joined) = requests.join_next().await {
+                match joined {
+                    Ok((ip, Ok(json))) => {
+                        results.insert(ip, parse_ipinfo(&json));
+                    }
+                    Ok((ip, Err(e))) => {
+                        debug!("ipinfo.io lookup failed for {}: {}", ip, e);
+                        last_error = Some(e.to_string());
+                    }
+                    Err(e) => last_error = Some(e.to_string()),
+                }
+            }
+            match last_error {
+                Some(e) if results.is_empty() => Err(anyhow!(e)),
+                _ => Ok(results),
+            }
+        })
+    }
+}
+
+/// Local MaxMind (GeoLite2 or GeoIP2) City and optional ASN databases
+pub struct MmdbProvider {
+    city: maxminddb::Reader<Vec<u8>>,
+    asn: Option<maxminddb::Reader<Vec<u8>>>,
+}
+
+impl MmdbProvider {
+    fn open(city_db: &Path, asn_db: Option<&Path>) -> Result<Self> {
+        let open = |path: &Path| {
+            maxminddb::Reader::open_readfile(path)
+                .map_err(|e| anyhow!("{}", e))
+                .with_context(|| format!("Failed to open GeoIP database {}", path.display()))
+        };
+        Ok(Self {
+            city: open(city_db)?,
+            asn: asn_db.map(open).transpose()?,
+        })
+    }
+
+    fn locate(&self, ip: IpAddr) -> Option<GeoLocation> {
+        use maxminddb::geoip2;
+
+        let city: geoip2::City = self.city.lookup(ip).ok()?;
+        let english = |names: Option<&std::collections::BTreeMap<&str, &str>>| {
+            names.and_then(|n| n.get("en")).map(|s| s.to_string())
+        };
+        let country = city.country.as_ref();
+        let (asn, isp) = match self
+            .asn
+            .as_ref()
+            .and_then(|r| r.lookup::<geoip2::Asn>(ip).ok())
+        {
+            Some(asn) => {
+                let org = asn.autonomous_system_organization.map(String::from);
+                let number = asn.autonomous_system_number.map(|n| match &org {
+                    Some(org) => format!("AS{} {}", n, org),
+                    None => format!("AS{}", n),
+                });
+                (number, org)
+            }
+            None => (None, None),
+        };
+        Some(GeoLocation {
+            city: english(city.city.as_ref().and_then(|c| c.names.as_ref()))
+                .unwrap_or_else(|| "?".to_string()),
+            country_code: country.and_then(|c| c.iso_code).unwrap_or("??").to_string(),
+            country: english(country.and_then(|c| c.names.as_ref()))
+                .unwrap_or_else(|| "Unknown".to_string()),
+            region: english(
+                city.subdivisions
+                    .as_ref()
+                    .and_then(|s| s.first())
+                    .and_then(|s| s.names.as_ref()),
+            )
+            .unwrap_or_default(),
+            isp,
+            asn,
+            lat: city.location.as_ref().and_then(|l| l.latitude),
+            lon: city.location.as_ref().and_then(|l| l.longitude),
+        })
+    }
+}
+
+impl GeoProvider for MmdbProvider {
+    fn batch_limit(&self) -> usize {
+        usize::MAX
+    }
+
+    fn min_interval(&self) -> Duration {
+        Duration::ZERO
+    }
+
+    fn lookup<'a>(&'a self, ips: &'a [String]) -> LookupFuture<'a> {
+        Box::pin(async move {
+            Ok(ips
+                .iter()
+                .filter_map(|ip| {
+                    let addr: IpAddr = ip.parse().ok()?;
+                    Some((ip.clone(), self.locate(addr)))
+                })
+                .collect())
+        })
+    }
+}
+
+/// Parse one ip-api.com result
+fn parse_ip_api(json: &serde_json::Value) -> Option<GeoLocation> {
+    let status = json.get("status")?.as_str()?;
+    if status != "success" {
+        return None;
+    }
+
+    Some(GeoLocation {
+        city: json
+            .get("city")
+            .and_then(|v| v.as_str())
+            .unwrap_or("?")
+            .to_string(),
+        country_code: json
+            .get("countryCode")
+            .and_then(|v| v.as_str())
+            .unwrap_or("??")
+            .to_string(),
+        country: json
+            .get("country")
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown")
+            .to_string(),
+        region: json
+            .get("region")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string(),
+        isp: json.get("isp").and_then(|v| v.as_str()).map(String::from),
+        asn: json
+            .get("as")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from),
+        lat: json.get("lat").and_then(|v| v.as_f64()),
+        lon: json.get("lon").and_then(|v| v.as_f64()),
+    })
+}
+
+/// Parse an ipinfo.io response; `org` is "AS15169 Google LLC" and `loc`
+/// is "lat,lon". ipinfo.io only gives the country code.
+fn parse_ipinfo(json: &serde_json::Value) -> Option<GeoLocation> {
+    if json.get("bogon").and_then(|b| b.as_bool()) == Some(true) {
+        return None;
+    }
+    let field = |name: &str| {
+        json.get(name)
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+    };
+    let country_code = field("country")?.to_string();
+    let org = field("org");
+    let (lat, lon) = field("loc")
+        .and_then(|loc| loc.split_once(','))
+        .map_or((None, None), |(lat, lon)| {
+            (lat.trim().parse().ok(), lon.trim().parse().ok())
+        });
+    Some(GeoLocation {
+        city: field("city").unwrap_or("?").to_string(),
+        country: country_code.clone(),
+        country_code,
+        region: field("region").unwrap_or_default().to_string(),
+        isp: org.map(|org| match org.split_once(' ') {
+            Some((asn, name)) if asn.starts_with("AS") => name.to_string(),
+            _ => org.to_string(),
+        }),
+        asn: org.filter(|org| org.starts_with("AS")).map(String::from),
+        lat,
+        lon,
+    })
+}
+
 /// Geolocation information for an IP address
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct GeoLocation {
     /// City name
@@ -21,6 +406,8 @@ pub struct GeoLocation {
     pub region: String,
     /// ISP name
     pub isp: Option<String>,
+    /// Autonomous system (e.g. "AS24940 Hetzner Online GmbH")
+    pub asn: Option<String>,
     /// Latitude
     pub lat: Option<f64>,
     /// Longitude
@@ -29,6 +416,11 @@ pub struct GeoLocation {
 
 #[allow(dead_code)]
 impl GeoLocation {
+    /// Network the address belongs to: the AS if known, else the ISP
+    pub fn network(&self) -> Option<&str> {
+        self.asn.as_deref().or(self.isp.as_deref())
+    }
+
     /// Format as short string (City, CC)
     pub fn short(&self) -> String {
         if self.city.is_empty() || self.city == "?" {
@@ -40,10 +432,72 @@ impl GeoLocation {
 }
 
 /// Cached geolocation entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 struct CacheEntry {
     location: Option<GeoLocation>,
-    fetched_at: Instant,
+    /// Unix timestamp of the lookup
+    fetched_at: u64,
+    /// `GeoIpConfig::source` of the provider that answered
+    #[serde(default)]
+    source: String,
+}
+
+impl CacheEntry {
+    /// Whether the entry is younger than `ttl`, or than `MISSING_TTL` if
+    /// it has no location
+    fn is_fresh(&self, ttl: Duration) -> bool {
+        let ttl = match self.location {
+            Some(_) => ttl,
+            None => ttl.min(MISSING_TTL),
+        };
+        unix_now().saturating_sub(self.fetched_at) < ttl.as_secs()
+    }
+}
+
+/// Where the location cache is kept
+pub fn cache_path() -> PathBuf {
+    crate::storage::get_data_dir().join("geoip_cache.json")
+}
+
+/// Delete the location cache file, returning whether there was one
+pub fn flush_cache(path: &Path) -> Result<bool> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("Failed to remove {}", path.display())),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// A batch of uncached addresses for the provider, from
+/// [`GeoIPService::lookup_batch`]
+pub struct BatchLookup {
+    provider: Arc<dyn GeoProvider>,
+    source: String,
+    ips: Vec<String>,
+}
+
+impl BatchLookup {
+    /// Ask the provider; hand the answer to [`GeoIPService::store`]
+    pub async fn run(self) -> BatchAnswer {
+        BatchAnswer {
+            result: self.provider.lookup(&self.ips).await,
+            source: self.source,
+        }
+    }
+}
+
+/// What the provider of a [`BatchLookup`] answered
+pub struct BatchAnswer {
+    source: String,
+    result: LookupResult,
 }
 
 /// Geolocation service with caching
@@ -51,92 +505,147 @@ struct CacheEntry {
 pub struct GeoIPService {
     cache: HashMap<String, CacheEntry>,
     cache_ttl: Duration,
-    client: reqwest::Client,
-    /// Rate limiting: max queries per batch
-    batch_limit: usize,
+    /// File the cache is loaded from and saved to after new lookups
+    cache_file: Option<PathBuf>,
+    /// Where uncached locations are looked up; none if it failed to start
+    provider: Option<Arc<dyn GeoProvider>>,
+    /// `GeoIpConfig::source` of `provider`
+    source: String,
     /// Track last batch time for rate limiting
     last_batch: Option<Instant>,
 }
 
 impl Default for GeoIPService {
     fn default() -> Self {
-        Self::new()
+        Self::new(&GeoIpConfig::default())
     }
 }
 
 #[allow(dead_code)]
 impl GeoIPService {
-    /// Create a new GeoIP service
-    pub fn new() -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(5))
-            .build()
-            .expect("Failed to create HTTP client for GeoIP");
-
-        Self {
+    /// Create a new GeoIP service using the provider selected in `config`
+    pub fn new(config: &GeoIpConfig) -> Self {
+        let mut service = Self {
             cache: HashMap::new(),
-            cache_ttl: Duration::from_secs(3600), // 1 hour cache
-            client,
-            batch_limit: 100, // ip-api.com allows 100 per batch
+            cache_ttl: CACHE_TTL,
+            cache_file: None,
+            provider: None,
+            source: String::new(),
             last_batch: None,
-        }
+        };
+        service.set_provider(config);
+        service
     }
 
-    /// Check if an IP is private/local (not suitable for geolocation)
-    pub fn is_private_ip(ip: &str) -> bool {
-        if ip == "127.0.0.1" || ip == "::1" || ip == "localhost" {
-            return true;
+    /// Create a service whose cache is persisted in `path`, starting with
+    /// the entries there that haven't expired
+    pub fn with_cache_file(config: &GeoIpConfig, path: PathBuf) -> Self {
+        let mut service = Self::new(config);
+        match std::fs::read_to_string(&path) {
+            Ok(json) => match serde_json::from_str::<HashMap<String, CacheEntry>>(&json) {
+                Ok(cache) => {
+                    let (ttl, source) = (service.cache_ttl, &service.source);
+                    service.cache = cache;
+                    service
+                        .cache
+                        .retain(|_, entry| entry.is_fresh(ttl) && entry.source == *source);
+                    debug!("Loaded {} cached peer locations", service.cache.len());
+                }
+                Err(e) => warn!("Ignoring GeoIP cache {}: {}", path.display(), e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to read GeoIP cache {}: {}", path.display(), e),
         }
+        service.cache_file = Some(path);
+        service
+    }
 
-        // Check IPv4 private ranges
-        if let Some(first_octet) = ip.split('.').next() {
-            if let Ok(octet) = first_octet.parse::<u8>() {
-                // 10.x.x.x, 172.16-31.x.x, 192.168.x.x
-                if octet == 10 || octet == 127 {
-                    return true;
-                }
-                if octet == 172 {
-                    if let Some(second) = ip.split('.').nth(1) {
-                        if let Ok(second_octet) = second.parse::<u8>() {
-                            if (16..=31).contains(&second_octet) {
-                                return true;
-                            }
-                        }
-                    }
-                }
-                if octet == 192 {
-                    if let Some(second) = ip.split('.').nth(1) {
-                        if second == "168" {
-                            return true;
-                        }
-                    }
-                }
+    /// Switch to the provider selected in `config`, dropping the locations
+    /// another provider found; peer locations are unavailable if it can't
+    /// be started
+    pub fn set_provider(&mut self, config: &GeoIpConfig) {
+        self.provider = match provider_from_config(config) {
+            Ok(provider) => Some(provider),
+            Err(e) => {
+                warn!("GeoIP lookups disabled: {:#}", e);
+                None
             }
+        };
+        self.last_batch = None;
+        let source = config.source();
+        if source != self.source {
+            self.cache.retain(|_, entry| entry.source == source);
+            self.source = source;
+            self.save();
         }
+    }
 
-        // IPv6 private ranges
-        if ip.starts_with("fe80:") || ip.starts_with("fc") || ip.starts_with("fd") {
-            return true;
+    /// Write the unexpired entries to the cache file, if there is one
+    fn save(&self) {
+        let Some(path) = &self.cache_file else {
+            return;
+        };
+        let fresh: HashMap<&String, &CacheEntry> = self
+            .cache
+            .iter()
+            .filter(|(_, entry)| entry.is_fresh(self.cache_ttl))
+            .collect();
+        let result = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| {
+                let json = serde_json::to_string(&fresh)?;
+                std::fs::write(path, json)
+            });
+        if let Err(e) = result {
+            warn!("Failed to write GeoIP cache {}: {}", path.display(), e);
         }
+    }
 
-        false
+    /// Check if an IP is private/local (not suitable for geolocation)
+    ///
+    /// Covers the IPv4 private, loopback, link-local and shared (CGNAT)
+    /// ranges, and for IPv6 loopback, unique local (fc00::/7), link-local
+    /// (fe80::/10), site-local (fec0::/10) and multicast addresses.
+    /// IPv4-mapped IPv6 addresses are checked as IPv4.
+    pub fn is_private_ip(ip: &str) -> bool {
+        if ip == "localhost" {
+            return true;
+        }
+        // Zone index of a link-local address: fe80::1%eth0
+        let ip = ip.split('%').next().unwrap_or(ip);
+        match ip.parse::<IpAddr>() {
+            Ok(IpAddr::V4(v4)) => is_private_v4(v4),
+            Ok(IpAddr::V6(v6)) => match v6.to_ipv4_mapped() {
+                Some(v4) => is_private_v4(v4),
+                None => is_private_v6(v6),
+            },
+            Err(_) => false,
+        }
     }
 
     /// Get cached location for an IP (returns None if not cached or expired)
     pub fn get_cached(&self, ip: &str) -> Option<&GeoLocation> {
         if let Some(entry) = self.cache.get(ip) {
-            if entry.fetched_at.elapsed() < self.cache_ttl {
+            if entry.is_fresh(self.cache_ttl) {
                 return entry.location.as_ref();
             }
         }
         None
     }
 
+    /// Cached locations for `ips`, without looking up the rest
+    pub fn cached(&self, ips: &[String]) -> HashMap<String, GeoLocation> {
+        ips.iter()
+            .filter_map(|ip| Some((ip.clone(), self.get_cached(ip)?.clone())))
+            .collect()
+    }
+
     /// Lookup a single IP (async)
     pub async fn lookup(&mut self, ip: &str) -> Option<GeoLocation> {
         // Check cache first
         if let Some(entry) = self.cache.get(ip) {
-            if entry.fetched_at.elapsed() < self.cache_ttl {
+            if entry.is_fresh(self.cache_ttl) {
                 return entry.location.clone();
             }
         }
@@ -146,36 +655,36 @@ impl GeoIPService {
             return None;
         }
 
-        // Fetch from API
-        let url = format!(
-            "http://ip-api.com/json/{}?fields=status,country,countryCode,region,city,lat,lon,isp",
-            ip
-        );
-
-        match self.client.get(&url).send().await {
-            Ok(response) => {
-                if let Ok(json) = response.json::<serde_json::Value>().await {
-                    let location = self.parse_response(&json);
-                    self.cache.insert(
-                        ip.to_string(),
-                        CacheEntry {
-                            location: location.clone(),
-                            fetched_at: Instant::now(),
-                        },
-                    );
-                    return location;
-                }
+        let provider = self.provider.as_ref()?;
+        let ips = [ip.to_string()];
+        match provider.lookup(&ips).await {
+            Ok(mut found) => {
+                let location = found.remove(ip)?;
+                self.cache.insert(
+                    ip.to_string(),
+                    CacheEntry {
+                        location: location.clone(),
+                        fetched_at: unix_now(),
+                        source: self.source.clone(),
+                    },
+                );
+                self.save();
+                location
             }
             Err(e) => {
-                warn!("GeoIP lookup failed for {}: {}", ip, e);
+                warn!("GeoIP lookup failed for {}: {:#}", ip, e);
+                None
             }
         }
-
-        None
     }
 
-    /// Batch lookup multiple IPs (more efficient for many IPs)
-    pub async fn lookup_batch(&mut self, ips: &[String]) -> HashMap<String, GeoLocation> {
+    /// Cached locations for `ips`, and a lookup of the uncached ones to
+    /// run in the background (none if all are cached or the provider's rate
+    /// limit doesn't allow another batch yet)
+    pub fn lookup_batch(
+        &mut self,
+        ips: &[String],
+    ) -> (HashMap<String, GeoLocation>, Option<BatchLookup>) {
         let mut results = HashMap::new();
         let mut to_fetch: Vec<String> = Vec::new();
 
@@ -186,7 +695,7 @@ impl GeoIPService {
             }
 
             if let Some(entry) = self.cache.get(ip) {
-                if entry.fetched_at.elapsed() < self.cache_ttl {
+                if entry.is_fresh(self.cache_ttl) {
                     if let Some(loc) = &entry.location {
                         results.insert(ip.clone(), loc.clone());
                     }
@@ -197,105 +706,69 @@ impl GeoIPService {
             to_fetch.push(ip.clone());
         }
 
+        let Some(provider) = self.provider.as_ref() else {
+            return (results, None);
+        };
+
         // Limit batch size
-        to_fetch.truncate(self.batch_limit);
+        to_fetch.truncate(provider.batch_limit());
 
         if to_fetch.is_empty() {
-            return results;
+            return (results, None);
         }
 
-        // Rate limit: 45 requests per minute for ip-api.com
+        // Rate limit (ip-api.com allows 45 requests per minute)
         if let Some(last) = self.last_batch {
-            let elapsed = last.elapsed();
-            if elapsed < Duration::from_millis(1500) {
+            if last.elapsed() < provider.min_interval() {
                 // Wait a bit before next batch
                 debug!("GeoIP rate limiting, skipping batch");
-                return results;
+                return (results, None);
             }
         }
         self.last_batch = Some(Instant::now());
 
-        // Build batch request
-        let batch_query: Vec<serde_json::Value> = to_fetch
-            .iter()
-            .map(|ip| {
-                serde_json::json!({
-                    "query": ip,
-                    "fields": "status,country,countryCode,region,city,lat,lon,isp"
-                })
-            })
-            .collect();
+        let lookup = BatchLookup {
+            provider: provider.clone(),
+            source: self.source.clone(),
+            ips: to_fetch,
+        };
+        (results, Some(lookup))
+    }
 
-        // Fetch batch
-        match self
-            .client
-            .post("http://ip-api.com/batch")
-            .json(&batch_query)
-            .send()
-            .await
-        {
-            Ok(response) => {
-                if let Ok(json_array) = response.json::<Vec<serde_json::Value>>().await {
-                    for (i, json) in json_array.iter().enumerate() {
-                        if i < to_fetch.len() {
-                            let ip = &to_fetch[i];
-                            let location = self.parse_response(json);
-                            self.cache.insert(
-                                ip.clone(),
-                                CacheEntry {
-                                    location: location.clone(),
-                                    fetched_at: Instant::now(),
-                                },
-                            );
-                            if let Some(loc) = location {
-                                results.insert(ip.clone(), loc);
-                            }
-                        }
+    /// Cache the answer to a finished [`BatchLookup`], returning the
+    /// locations it found; answers from a provider switched away from since
+    /// are dropped
+    pub fn store(&mut self, answer: BatchAnswer) -> HashMap<String, GeoLocation> {
+        let mut results = HashMap::new();
+        if answer.source != self.source {
+            debug!("Dropping GeoIP answer from {}", answer.source);
+            return results;
+        }
+        match answer.result {
+            Ok(found) => {
+                let fetched_at = unix_now();
+                for (ip, location) in found {
+                    if let Some(loc) = &location {
+                        results.insert(ip.clone(), loc.clone());
                     }
+                    self.cache.insert(
+                        ip,
+                        CacheEntry {
+                            location,
+                            fetched_at,
+                            source: self.source.clone(),
+                        },
+                    );
                 }
+                self.save();
             }
             Err(e) => {
-                warn!("GeoIP batch lookup failed: {}", e);
+                warn!("GeoIP batch lookup failed: {:#}", e);
             }
         }
-
         results
     }
 
-    /// Parse ip-api.com JSON response
-    fn parse_response(&self, json: &serde_json::Value) -> Option<GeoLocation> {
-        let status = json.get("status")?.as_str()?;
-        if status != "success" {
-            return None;
-        }
-
-        Some(GeoLocation {
-            city: json
-                .get("city")
-                .and_then(|v| v.as_str())
-                .unwrap_or("?")
-                .to_string(),
-            country_code: json
-                .get("countryCode")
-                .and_then(|v| v.as_str())
-                .unwrap_or("??")
-                .to_string(),
-            country: json
-                .get("country")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown")
-                .to_string(),
-            region: json
-                .get("region")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string(),
-            isp: json.get("isp").and_then(|v| v.as_str()).map(String::from),
-            lat: json.get("lat").and_then(|v| v.as_f64()),
-            lon: json.get("lon").and_then(|v| v.as_f64()),
-        })
-    }
-
     /// Clear the cache
     #[allow(dead_code)]
     pub fn clear_cache(&mut self) {
@@ -309,12 +782,35 @@ impl GeoIPService {
         let valid = self
             .cache
             .values()
-            .filter(|e| e.fetched_at.elapsed() < self.cache_ttl)
+            .filter(|e| e.is_fresh(self.cache_ttl))
             .count();
         (valid, total)
     }
 }
 
+/// Non-routable IPv4 ranges for [`GeoIPService::is_private_ip`]
+fn is_private_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, _, _] = ip.octets();
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        // Shared address space for carrier-grade NAT, 100.64.0.0/10
+        || (a == 100 && (b & 0xc0) == 64)
+}
+
+/// Non-routable IPv6 ranges for [`GeoIPService::is_private_ip`]
+fn is_private_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first & 0xffc0) == 0xfec0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,6 +825,172 @@ mod tests {
         assert!(!GeoIPService::is_private_ip("8.8.8.8"));
         assert!(!GeoIPService::is_private_ip("1.1.1.1"));
         assert!(!GeoIPService::is_private_ip("172.32.0.1")); // Not private
+        assert!(GeoIPService::is_private_ip("169.254.10.1"));
+        assert!(GeoIPService::is_private_ip("100.64.0.1"));
+        assert!(!GeoIPService::is_private_ip("100.128.0.1"));
+
+        assert!(GeoIPService::is_private_ip("::1"));
+        assert!(GeoIPService::is_private_ip("fd12:3456::1"));
+        assert!(GeoIPService::is_private_ip("fc00::1"));
+        assert!(GeoIPService::is_private_ip("fe80::1%eth0"));
+        assert!(GeoIPService::is_private_ip("febf::1")); // Still fe80::/10
+        assert!(GeoIPService::is_private_ip("ff02::1"));
+        assert!(GeoIPService::is_private_ip("::ffff:192.168.1.1"));
+        assert!(!GeoIPService::is_private_ip("::ffff:8.8.8.8"));
+        assert!(!GeoIPService::is_private_ip("2001:4860:4860::8888"));
+        // Not an address, even if it starts like one
+        assert!(!GeoIPService::is_private_ip("fcc0.example"));
+    }
+
+    #[test]
+    fn test_cache_file_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("geoip_cache.json");
+        let location = GeoLocation {
+            city: "Helsinki".to_string(),
+            country_code: "FI".to_string(),
+            country: "Finland".to_string(),
+            region: "Uusimaa".to_string(),
+            isp: Some("Hetzner Online GmbH".to_string()),
+            asn: Some("AS24940 Hetzner Online GmbH".to_string()),
+            lat: Some(60.17),
+            lon: Some(24.94),
+        };
+
+        let config = GeoIpConfig::default();
+        let mut service = GeoIPService::with_cache_file(&config, path.clone());
+        service.cache.insert(
+            "95.216.1.1".to_string(),
+            CacheEntry {
+                location: Some(location.clone()),
+                fetched_at: unix_now(),
+                source: config.source(),
+            },
+        );
+        // Expired entries are neither saved nor loaded
+        service.cache.insert(
+            "95.216.1.2".to_string(),
+            CacheEntry {
+                location: Some(location.clone()),
+                fetched_at: unix_now() - CACHE_TTL.as_secs() - 1,
+                source: config.source(),
+            },
+        );
+        // Addresses without a location are asked about again sooner
+        service.cache.insert(
+            "95.216.1.3".to_string(),
+            CacheEntry {
+                location: None,
+                fetched_at: unix_now() - MISSING_TTL.as_secs() - 1,
+                source: config.source(),
+            },
+        );
+        service.save();
+
+        let mut service = GeoIPService::with_cache_file(&config, path.clone());
+        assert_eq!(service.get_cached("95.216.1.1"), Some(&location));
+        assert_eq!(service.cache_stats(), (1, 1));
+
+        // Another provider's locations aren't used
+        let ipinfo = GeoIpConfig {
+            provider: GeoProviderKind::IpInfo,
+            ..GeoIpConfig::default()
+        };
+        let other = GeoIPService::with_cache_file(&ipinfo, path.clone());
+        assert_eq!(other.cache_stats(), (0, 0));
+        service.set_provider(&ipinfo);
+        assert_eq!(service.get_cached("95.216.1.1"), None);
+        service.set_provider(&config);
+
+        assert!(flush_cache(&path).unwrap());
+        assert!(!flush_cache(&path).unwrap());
+        let service = GeoIPService::with_cache_file(&config, path);
+        assert_eq!(service.cache_stats(), (0, 0));
+    }
+
+    #[test]
+    fn test_parse_ipinfo() {
+        let json = serde_json::json!({
+            "ip": "95.216.1.1",
+            "city": "Helsinki",
+            "region": "Uusimaa",
+            "country": "FI",
+            "loc": "60.1695,24.9354",
+            "org": "AS24940 Hetzner Online GmbH"
+        });
+        let loc = parse_ipinfo(&json).unwrap();
+        assert_eq!(loc.short(), "Helsinki, FI");
+        assert_eq!(loc.region, "Uusimaa");
+        assert_eq!(loc.asn.as_deref(), Some("AS24940 Hetzner Online GmbH"));
+        assert_eq!(loc.isp.as_deref(), Some("Hetzner Online GmbH"));
+        assert_eq!(loc.lat, Some(60.1695));
+        assert_eq!(loc.lon, Some(24.9354));
+
+        let bogon = serde_json::json!({"ip": "10.0.0.1", "bogon": true});
+        assert_eq!(parse_ipinfo(&bogon), None);
+    }
+
+    /// Answers every address with no location
+    struct NoLocations;
+
+    impl GeoProvider for NoLocations {
+        fn batch_limit(&self) -> usize {
+            2
+        }
+
+        fn min_interval(&self) -> Duration {
+            Duration::from_secs(60)
+        }
+
+        fn lookup<'a>(&'a self, ips: &'a [String]) -> LookupFuture<'a> {
+            Box::pin(async move { Ok(ips.iter().map(|ip| (ip.clone(), None)).collect()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lookup_batch() {
+        let mut service = GeoIPService::new(&GeoIpConfig::default());
+        service.provider = Some(Arc::new(NoLocations));
+        let ips: Vec<String> = ["10.0.0.1", "95.216.1.1", "95.216.1.2", "95.216.1.3"]
+            .iter()
+            .map(|ip| ip.to_string())
+            .collect();
+
+        let (cached, lookup) = service.lookup_batch(&ips);
+        assert!(cached.is_empty());
+        let lookup = lookup.unwrap();
+        // Private addresses are skipped and the batch is capped
+        assert_eq!(lookup.ips, ["95.216.1.1", "95.216.1.2"]);
+        assert!(service.store(lookup.run().await).is_empty());
+        assert_eq!(service.cache_stats(), (2, 2));
+
+        // The next batch waits for the provider's rate limit
+        assert!(service.lookup_batch(&ips).1.is_none());
+    }
+
+    #[test]
+    fn test_geoip_config() {
+        let config: GeoIpConfig = toml::from_str(
+            r#"
+            provider = "mmdb"
+            city_db = "/var/lib/GeoIP/GeoLite2-City.mmdb"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.provider, GeoProviderKind::Mmdb);
+        let err = provider_from_config(&config).err().unwrap();
+        assert!(format!("{:#}", err).contains("GeoLite2-City.mmdb"));
+
+        let config = GeoIpConfig {
+            provider: GeoProviderKind::Mmdb,
+            ..Default::default()
+        };
+        assert!(provider_from_config(&config).is_err());
+
+        let config: GeoIpConfig = toml::from_str(r#"provider = "ipinfo""#).unwrap();
+        assert_eq!(config.provider, GeoProviderKind::IpInfo);
+        assert!(provider_from_config(&config).is_ok());
+        assert!(provider_from_config(&GeoIpConfig::default()).is_ok());
     }
 
     #[test]
@@ -339,6 +1001,7 @@ mod tests {
             country: "Australia".to_string(),
             region: "NSW".to_string(),
             isp: None,
+            asn: None,
             lat: None,
             lon: None,
         };
@@ -0,0 +1,494 @@
+//! Descriptions of the dashboard's metric rows
+//!
+//! Shown when a row is selected with ↑/↓ on the dashboard and opened with
+//! Enter: what the value means, what a healthy node shows and which
+//! Prometheus metrics it comes from. Rows are matched by their label, so a
+//! label change in the UI needs the same change here (the UI tests check
+//! that every built-in row has an entry).
+
+use crate::history::{MetricHistory, MetricsHistory};
+
+/// Help text for one dashboard row
+pub struct MetricHelp {
+    /// Row label as shown in the panel
+    pub label: &'static str,
+    /// What the value means
+    pub description: &'static str,
+    /// What a healthy node shows
+    pub healthy: &'static str,
+    /// Prometheus metrics the row is computed from (first name if the
+    /// node exports several spellings); empty for values sview derives
+    /// from other sources
+    pub prometheus: &'static [&'static str],
+    /// The in-memory history kept for the row, for min/max/avg
+    pub history: Option<fn(&MetricsHistory) -> &MetricHistory>,
+}
+
+/// Help for the row with this label
+pub fn lookup(label: &str) -> Option<&'static MetricHelp> {
+    METRICS.iter().find(|help| help.label == label)
+}
+
+/// Every Prometheus metric a row's help names
+pub fn prometheus_names() -> impl Iterator<Item = &'static str> {
+    METRICS
+        .iter()
+        .flat_map(|help| help.prometheus.iter().copied())
+}
+
+const METRICS: &[MetricHelp] = &[
+    // Chain
+    MetricHelp {
+        label: "Block Height",
+        description: "Number of the latest block in the node's chain. It grows by one for every block adopted, about every 20 seconds on mainnet.",
+        healthy: "Rising steadily and equal to other nodes and explorers.",
+        prometheus: &["cardano_node_metrics_blockNum_int"],
+        history: Some(|h| &h.block_height),
+    },
+    MetricHelp {
+        label: "Tip Age",
+        description: "Time since the slot of the latest block. Blocks are produced at random, so gaps of a minute or two happen; a tip that keeps ageing means the node stopped following the chain.",
+        healthy: "Under a minute most of the time.",
+        prometheus: &["cardano_node_metrics_slotNum_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Network Lag",
+        description: "How far the node's tip trails the reference network tip from `[global.reference_tip]` (the chain API or a trusted node), in blocks and in seconds of slots.",
+        healthy: "At the tip, or a block behind while a new block propagates.",
+        prometheus: &[],
+        history: None,
+    },
+    MetricHelp {
+        label: "Slot",
+        description: "Absolute slot number of the node's tip. One slot passes every second on mainnet.",
+        healthy: "Close to the current wall-clock slot.",
+        prometheus: &["cardano_node_metrics_slotNum_int"],
+        history: Some(|h| &h.slot_num),
+    },
+    MetricHelp {
+        label: "Slot in Epoch",
+        description: "Position of the tip's slot within the current epoch (432,000 slots, five days, on mainnet).",
+        healthy: "Advancing with the epoch progress bar.",
+        prometheus: &["cardano_node_metrics_slotInEpoch_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Slot Rate",
+        description: "How fast the tip's slot advanced over the last few minutes, compared with the network's slot length.",
+        healthy: "About 1.0× the expected rate; much lower means the node is falling behind, higher that it is catching up.",
+        prometheus: &["cardano_node_metrics_slotNum_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Blocks/min",
+        description: "Blocks adopted per minute over the last hour.",
+        healthy: "Around 3 on mainnet (one block every 20 seconds on average).",
+        prometheus: &["cardano_node_metrics_blockNum_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Density",
+        description: "Chain density: the share of recent slots that hold a block, as the node reports it, plus the density of the last hour. With the active slot coefficient of 0.05, about one slot in twenty has a block.",
+        healthy: "Close to 5%. Below 80% of the active slot coefficient is a warning, below 50% critical: blocks are going missing across the network, or the node is on a sparse fork.",
+        prometheus: &["cardano_node_metrics_density_real"],
+        history: None,
+    },
+    MetricHelp {
+        label: "TX Processed",
+        description: "Transactions the node has accepted into its mempool since it started.",
+        healthy: "Increasing on a node with peers; a relay that never processes transactions may not be receiving any.",
+        prometheus: &["cardano_node_metrics_txsProcessedNum_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "TPS",
+        description: "Transactions processed per second since the previous refresh, with the 1-hour average and 24-hour peak.",
+        healthy: "Varies with network load; zero for long periods on mainnet is unusual.",
+        prometheus: &["cardano_node_metrics_txsProcessedNum_int"],
+        history: Some(|h| &h.tps),
+    },
+    MetricHelp {
+        label: "Forks",
+        description: "Chain switches since the node started: each time it rolled back to adopt a different, longer fork.",
+        healthy: "Low and growing slowly; short forks happen when two pools make a block for nearby slots.",
+        prometheus: &["cardano_node_metrics_forks_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Pool",
+        description: "Ticker and lifetime block count of the pool, from the chain API.",
+        healthy: "Matches the pool's page on an explorer.",
+        prometheus: &[],
+        history: None,
+    },
+    MetricHelp {
+        label: "Live Stake",
+        description: "Stake currently delegated to the pool, from the chain API.",
+        healthy: "Informational.",
+        prometheus: &[],
+        history: None,
+    },
+    MetricHelp {
+        label: "Stake Trend",
+        description: "Change of the pool's live stake over the stored snapshots.",
+        healthy: "Informational.",
+        prometheus: &[],
+        history: None,
+    },
+    MetricHelp {
+        label: "Pooltool",
+        description: "Result of the last tip sent to pooltool.io, with the pool's rank among those that reported the block when pooltool returns one.",
+        healthy: "✓ with a recent block number; a rank near the top means blocks reach the pool quickly.",
+        prometheus: &[],
+        history: None,
+    },
+    // Forging
+    MetricHelp {
+        label: "KES Remaining",
+        description: "KES periods left before the operational certificate's hot key expires. A block producer with an expired KES key cannot forge blocks.",
+        healthy: "20 or more periods; rotate the KES key and issue a new certificate well before it reaches 0.",
+        prometheus: &["cardano_node_metrics_remainingKESPeriods_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "OpCert",
+        description: "Operational certificate counter on disk compared with the last one seen on chain.",
+        healthy: "✓: the disk counter equals the chain counter or is one higher after a rotation.",
+        prometheus: &[
+            "cardano_node_metrics_opCertCounterOnDisk_int",
+            "cardano_node_metrics_opCertCounterOnChain_int",
+        ],
+        history: None,
+    },
+    MetricHelp {
+        label: "Forging",
+        description: "Whether the node runs with block forging credentials.",
+        healthy: "Enabled on block producers, Disabled on relays.",
+        prometheus: &["cardano_node_metrics_forging_enabled_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Blocks Forged",
+        description: "Blocks this node forged and adopted since it started.",
+        healthy: "Grows with the pool's leader slots.",
+        prometheus: &["cardano_node_metrics_Forge_adopted_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Missed Slots",
+        description: "Leader checks the node could not run in time, usually because it was busy (e.g. garbage collection at an epoch boundary).",
+        healthy: "0; a few per epoch are common, a growing count costs blocks.",
+        prometheus: &["cardano_node_metrics_slotsMissedNum_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Leader Slots",
+        description: "Slots the pool leads in the current epoch and how many of them ended up adopted or lost, from cncli.",
+        healthy: "No lost blocks.",
+        prometheus: &[],
+        history: None,
+    },
+    MetricHelp {
+        label: "Next Slot",
+        description: "Time until the pool's next leader slot, from the cncli schedule.",
+        healthy: "Informational; avoid restarts shortly before it.",
+        prometheus: &[],
+        history: None,
+    },
+    MetricHelp {
+        label: "cncli",
+        description: "Why the cncli leader schedule or block outcomes couldn't be read.",
+        healthy: "Not shown.",
+        prometheus: &[],
+        history: None,
+    },
+    // Network
+    MetricHelp {
+        label: "Connected",
+        description: "Peers the node is connected to, with the trend over the kept history.",
+        healthy: "At or above the peer thresholds; relays usually hold dozens of peers.",
+        prometheus: &["cardano_node_metrics_connectedPeers_int"],
+        history: Some(|h| &h.peers_connected),
+    },
+    MetricHelp {
+        label: "Scrape RTT",
+        description: "Time sview's metrics request took, with average and maximum. It covers the network path and how quickly the node answers.",
+        healthy: "Under 250 ms.",
+        prometheus: &[],
+        history: None,
+    },
+    MetricHelp {
+        label: "Incoming",
+        description: "Connections other nodes opened to this one (connection manager).",
+        healthy: "Above zero on a public relay; zero on a block producer behind a firewall.",
+        prometheus: &["cardano_node_metrics_connectionManager_incomingConns"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Outgoing",
+        description: "Connections this node opened to its peers (connection manager).",
+        healthy: "Close to the active and established peer targets.",
+        prometheus: &["cardano_node_metrics_connectionManager_outgoingConns"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Duplex",
+        description: "Connections used in both directions: each side runs the mini-protocols as client and server.",
+        healthy: "Informational; common between P2P relays.",
+        prometheus: &["cardano_node_metrics_connectionManager_fullDuplexConns"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Prunable",
+        description: "Inbound connections the connection manager may close when it needs room.",
+        healthy: "Informational.",
+        prometheus: &["cardano_node_metrics_connectionManager_prunableConns"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Conn Errors",
+        description: "Connection failures since the node started, with the recent rate: the sum of the connection manager counters mentioning errors, where the node exports them.",
+        healthy: "A low, steady rate; a sudden rise points at network or firewall problems.",
+        prometheus: &[],
+        history: None,
+    },
+    MetricHelp {
+        label: "Handshake Fails",
+        description: "Handshakes rejected since the node started, e.g. peers on another network or an incompatible version.",
+        healthy: "A low, steady rate.",
+        prometheus: &[],
+        history: None,
+    },
+    MetricHelp {
+        label: "Hot Peers",
+        description: "Peers the node actively follows the chain from, against the governor's active target.",
+        healthy: "At the target.",
+        prometheus: &["cardano_node_metrics_p2p_hotPeersCount_int"],
+        history: Some(|h| &h.p2p_hot_peers),
+    },
+    MetricHelp {
+        label: "Warm Peers",
+        description: "Peers with an open connection but no chain sync, kept ready for promotion, against the established target.",
+        healthy: "At the target.",
+        prometheus: &["cardano_node_metrics_p2p_warmPeersCount_int"],
+        history: Some(|h| &h.p2p_warm_peers),
+    },
+    MetricHelp {
+        label: "Cold Peers",
+        description: "Known peers without a connection, against the known-peers target.",
+        healthy: "At the target.",
+        prometheus: &["cardano_node_metrics_p2p_coldPeersCount_int"],
+        history: Some(|h| &h.p2p_cold_peers),
+    },
+    MetricHelp {
+        label: "Peer Dist",
+        description: "Hot, warm and cold peer counts, shown when the node exports no peer targets.",
+        healthy: "Hot peers present on a synced P2P node.",
+        prometheus: &[
+            "cardano_node_metrics_p2p_hotPeersCount_int",
+            "cardano_node_metrics_p2p_warmPeersCount_int",
+            "cardano_node_metrics_p2p_coldPeersCount_int",
+        ],
+        history: None,
+    },
+    MetricHelp {
+        label: "Block Delay",
+        description: "Time from a block's slot to when this node fetched it.",
+        healthy: "Under a second; several seconds means the block reached this node late.",
+        prometheus: &["cardano_node_metrics_blockfetchclient_blockdelay_s"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Blks Served",
+        description: "Blocks this node sent to downstream peers since it started, with the recent rate.",
+        healthy: "Growing on a relay with incoming peers.",
+        prometheus: &["cardano_node_metrics_served_block_count_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Hdrs Served",
+        description: "Block headers this node announced to downstream peers over chain sync since it started, with the recent rate. Peers fetch the blocks they don't have after seeing the header.",
+        healthy: "Growing with every block on a relay with incoming peers, usually faster than blocks served.",
+        prometheus: &["cardano_node_metrics_served_header_counter_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Blks Late",
+        description: "Blocks fetched more than 5 seconds after their slot, with the rate over the last minute. The color follows the rate: yellow while blocks arrive late, red at one or more a minute.",
+        healthy: "No new late blocks; each late block is a block other pools may build on first.",
+        prometheus: &["cardano_node_metrics_blockfetchclient_lateblocks"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Prop ≤1s",
+        description: "Block propagation CDF: the share of recent blocks this node fetched within 1 second of their slot.",
+        healthy: "Well above 80% on a well-connected relay.",
+        prometheus: &["cardano_node_metrics_blockfetchclient_blockdelay_cdfOne"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Prop ≤3s",
+        description: "Block propagation CDF: the share of recent blocks fetched within 3 seconds of their slot.",
+        healthy: "Above 95%.",
+        prometheus: &["cardano_node_metrics_blockfetchclient_blockdelay_cdfThree"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Prop ≤5s",
+        description: "Block propagation CDF: the share of recent blocks fetched within 5 seconds of their slot.",
+        healthy: "Close to 100%.",
+        prometheus: &["cardano_node_metrics_blockfetchclient_blockdelay_cdfFive"],
+        history: None,
+    },
+    MetricHelp {
+        label: "cncli Tip",
+        description: "Block height of cncli's synced database compared with the node's tip.",
+        healthy: "synced, or at most 2 blocks behind.",
+        prometheus: &[],
+        history: None,
+    },
+    // Resources
+    MetricHelp {
+        label: "Uptime",
+        description: "Time since the node process started.",
+        healthy: "Informational; unexpected resets mean the node restarted.",
+        prometheus: &["cardano_node_metrics_nodeStartTime_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Restarts",
+        description: "Node restarts in the last 7 and 30 days, from the start times sview has recorded, including restarts while sview wasn't running. The incidents view (i) has a 30-day timeline.",
+        healthy: "Zero outside planned upgrades. Repeated restarts point to crashes, out-of-memory kills or an unstable host.",
+        prometheus: &["cardano_node_metrics_nodeStartTime_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Memory Used",
+        description: "Live data on the node's Haskell heap after the last garbage collection.",
+        healthy: "Below the memory thresholds and stable between epochs.",
+        prometheus: &["cardano_node_metrics_RTS_gcLiveBytes_int"],
+        history: Some(|h| &h.memory_used),
+    },
+    MetricHelp {
+        label: "Memory Heap",
+        description: "Total heap the runtime has reserved, including space not currently in use.",
+        healthy: "Comfortably below the machine's RAM.",
+        prometheus: &["cardano_node_metrics_RTS_gcHeapBytes_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "CPU",
+        description: "CPU time the node used per second of wall time, over the last 10 seconds, in percent of one core. The node runs several threads, so it can exceed 100%.",
+        healthy: "Below the CPU thresholds; high while syncing or replaying the chain.",
+        prometheus: &["cardano_node_metrics_RTS_cpuNs_int", "rts_gc_cpu_ms"],
+        history: Some(|h| &h.cpu_usage),
+    },
+    MetricHelp {
+        label: "GC Minor",
+        description: "Minor (young generation) garbage collections since start, with the recent rate.",
+        healthy: "Informational; minor collections are frequent and cheap.",
+        prometheus: &["cardano_node_metrics_RTS_gcMinorNum_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "GC Major",
+        description: "Major (full heap) garbage collections since start, with the recent rate. Each one pauses the node briefly.",
+        healthy: "A low rate; frequent major collections point at memory pressure.",
+        prometheus: &["cardano_node_metrics_RTS_gcMajorNum_int"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Mempool TXs",
+        description: "Transactions waiting in the mempool for a block.",
+        healthy: "Low and regularly emptied by new blocks.",
+        prometheus: &["cardano_node_metrics_txsInMempool_int"],
+        history: Some(|h| &h.mempool_txs),
+    },
+    MetricHelp {
+        label: "Mempool Size",
+        description: "Size of the transactions waiting in the mempool.",
+        healthy: "Below the mempool capacity (twice the block size limit).",
+        prometheus: &["cardano_node_metrics_mempoolBytes_int"],
+        history: None,
+    },
+    // P2P Governance
+    MetricHelp {
+        label: "Peer Sharing",
+        description: "Known, established and active peers the node learned through peer sharing, i.e. from other nodes rather than its topology or the ledger.",
+        healthy: "Some known peers on a relay with peer sharing enabled; none learned suggests peer sharing is off.",
+        prometheus: &["cardano_node_metrics_peerSelection_KnownSharedPeers"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Big Ledger",
+        description: "Known, established and active big-ledger peers: the large stake pools the node syncs from when far behind the tip.",
+        healthy: "Known peers present; active ones mostly while syncing.",
+        prometheus: &["cardano_node_metrics_peerSelection_KnownBigLedgerPeers"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Bootstrap",
+        description: "Known, established and active bootstrap peers, the trusted relays used until the node reaches the tip in Genesis mode.",
+        healthy: "None once the node is synced.",
+        prometheus: &["cardano_node_metrics_peerSelection_KnownBootstrapPeers"],
+        history: None,
+    },
+    MetricHelp {
+        label: "Shared Peers",
+        description: "Peer sharing requests sent to other nodes and the peers received in reply.",
+        healthy: "Some peers received; requests answered with nothing mean the other side has peer sharing off or blocked.",
+        prometheus: &[
+            "cardano_node_metrics_peerSelection_PeerSharingRequested",
+            "cardano_node_metrics_peerSelection_PeerSharingReceived",
+        ],
+        history: None,
+    },
+    MetricHelp {
+        label: "Known Churn",
+        description: "Known peers added and forgotten by the governor's churn, big-ledger peers included.",
+        healthy: "Both moving a little every churn cycle.",
+        prometheus: &[
+            "cardano_node_metrics_peerSelection_churn_IncreasedKnownPeers",
+            "cardano_node_metrics_peerSelection_churn_DecreasedKnownPeers",
+        ],
+        history: None,
+    },
+    MetricHelp {
+        label: "Established Churn",
+        description: "Connections opened and closed by the governor's churn, big-ledger peers included.",
+        healthy: "Both moving a little every churn cycle.",
+        prometheus: &[
+            "cardano_node_metrics_peerSelection_churn_IncreasedEstablishedPeers",
+            "cardano_node_metrics_peerSelection_churn_DecreasedEstablishedPeers",
+        ],
+        history: None,
+    },
+    MetricHelp {
+        label: "Active Churn",
+        description: "Peers promoted to and demoted from hot by the governor's churn, big-ledger peers included.",
+        healthy: "Both moving a little every churn cycle; demotions without promotions leave the node short of hot peers.",
+        prometheus: &[
+            "cardano_node_metrics_peerSelection_churn_IncreasedActivePeers",
+            "cardano_node_metrics_peerSelection_churn_DecreasedActivePeers",
+        ],
+        history: None,
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_lookup() {
+        let density = lookup("Density").unwrap();
+        assert_eq!(density.prometheus, ["cardano_node_metrics_density_real"]);
+        assert!(lookup("Connected").unwrap().history.is_some());
+        assert!(lookup("No Such Row").is_none());
+
+        let labels: HashSet<&str> = METRICS.iter().map(|help| help.label).collect();
+        assert_eq!(labels.len(), METRICS.len(), "duplicate labels");
+    }
+}
@@ -0,0 +1,243 @@
+//! Upcoming hard fork countdown
+//!
+//! A hard fork takes effect at an epoch boundary (or, on test networks
+//! configured with `Test*HardForkAtEpoch`, a known slot). Nodes still running
+//! a version without support for the new protocol stop following the chain at
+//! that point, so sview counts down to it and warns about nodes below the
+//! minimum version. The fork comes from `[global.hard_fork]` or, with
+//! `fetch = true`, from ratified `HardForkInitiation` governance actions on
+//! Koios.
+
+use serde::Deserialize;
+use std::cmp::Ordering;
+
+/// Where the hard fork schedule came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HardForkSource {
+    Config,
+    Koios,
+}
+
+/// `[global.hard_fork]` in the config file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct HardForkConfig {
+    /// First epoch of the new protocol version
+    pub epoch: Option<u64>,
+    /// Slot the fork happens at (instead of an epoch)
+    pub slot: Option<u64>,
+    /// Protocol version after the fork (e.g. "10.0"), for display
+    pub protocol: Option<String>,
+    /// Oldest node version that supports the fork (e.g. "10.1.4")
+    pub min_node_version: Option<String>,
+    /// Look up ratified hard forks on Koios
+    #[serde(default)]
+    pub fetch: bool,
+}
+
+impl HardForkConfig {
+    /// The configured fork, if an epoch or slot is set
+    pub fn scheduled(&self) -> Option<HardFork> {
+        if self.epoch.is_none() && self.slot.is_none() {
+            return None;
+        }
+        Some(HardFork {
+            epoch: self.epoch,
+            slot: self.slot,
+            protocol: self.protocol.clone(),
+            min_node_version: self.min_node_version.clone(),
+            source: HardForkSource::Config,
+        })
+    }
+}
+
+/// A scheduled hard fork
+#[derive(Debug, Clone, PartialEq)]
+pub struct HardFork {
+    pub epoch: Option<u64>,
+    pub slot: Option<u64>,
+    /// Protocol version after the fork
+    pub protocol: Option<String>,
+    /// Oldest node version that supports the fork
+    pub min_node_version: Option<String>,
+    pub source: HardForkSource,
+}
+
+impl HardFork {
+    /// Short description, e.g. "protocol 10.0 at epoch 537"
+    pub fn describe(&self) -> String {
+        let at = match (self.epoch, self.slot) {
+            (Some(epoch), _) => format!("epoch {}", epoch),
+            (None, Some(slot)) => format!("slot {}", slot),
+            (None, None) => "unknown point".to_string(),
+        };
+        match &self.protocol {
+            Some(protocol) => format!("protocol {} at {}", protocol, at),
+            None => format!("hard fork at {}", at),
+        }
+    }
+
+    /// Seconds until the fork, or `None` once it has passed
+    ///
+    /// `epoch_remaining_secs` is the time left in the current epoch.
+    pub fn secs_until(
+        &self,
+        current_slot: Option<u64>,
+        current_epoch: Option<u64>,
+        epoch_remaining_secs: Option<u64>,
+        epoch_length: u64,
+        slot_length_secs: f64,
+    ) -> Option<u64> {
+        if let (Some(fork_slot), Some(slot)) = (self.slot, current_slot) {
+            let slots = fork_slot.checked_sub(slot).filter(|s| *s > 0)?;
+            return Some((slots as f64 * slot_length_secs) as u64);
+        }
+        let (fork_epoch, epoch, remaining) = (self.epoch?, current_epoch?, epoch_remaining_secs?);
+        let full_epochs = fork_epoch.checked_sub(epoch)?.checked_sub(1)?;
+        let epoch_secs = (epoch_length as f64 * slot_length_secs) as u64;
+        Some(remaining + full_epochs * epoch_secs)
+    }
+
+    /// Whether `version` is older than the minimum required for the fork
+    pub fn requires_upgrade(&self, version: Option<&str>) -> bool {
+        match (version, &self.min_node_version) {
+            (Some(version), Some(minimum)) => compare_versions(version, minimum) == Ordering::Less,
+            _ => false,
+        }
+    }
+}
+
+/// Compare dotted version numbers ("10.1.4" < "10.2"); missing parts count as 0
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parts = |v: &str| -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split('.')
+            .map(|p| {
+                p.chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0)
+            })
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Pick a ratified, not yet enacted hard fork from Koios `/proposal_list`
+///
+/// A ratified action is enacted at the next epoch boundary.
+pub fn parse_koios_proposals(items: &[serde_json::Value]) -> Option<HardFork> {
+    items
+        .iter()
+        .filter(|p| p.get("proposal_type").and_then(|t| t.as_str()) == Some("HardForkInitiation"))
+        .filter(|p| {
+            ["enacted_epoch", "dropped_epoch", "expired_epoch"]
+                .iter()
+                .all(|field| p.get(field).is_none_or(|v| v.is_null()))
+        })
+        .filter_map(|p| {
+            let ratified = p.get("ratified_epoch")?.as_u64()?;
+            let version = p
+                .get("proposal_description")
+                .and_then(|d| d.get("contents"))
+                .and_then(|c| c.as_array())
+                .and_then(|c| c.last())
+                .and_then(|v| Some(format!("{}.{}", v.get("major")?, v.get("minor")?)));
+            Some(HardFork {
+                epoch: Some(ratified + 1),
+                slot: None,
+                protocol: version,
+                min_node_version: None,
+                source: HardForkSource::Koios,
+            })
+        })
+        .min_by_key(|fork| fork.epoch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fork(epoch: Option<u64>, slot: Option<u64>) -> HardFork {
+        HardFork {
+            epoch,
+            slot,
+            protocol: Some("10.0".to_string()),
+            min_node_version: Some("10.1.4".to_string()),
+            source: HardForkSource::Config,
+        }
+    }
+
+    #[test]
+    fn test_secs_until() {
+        // 100 s left in epoch 535, then all of epoch 536
+        let by_epoch = fork(Some(537), None);
+        assert_eq!(
+            by_epoch.secs_until(None, Some(535), Some(100), 432000, 1.0),
+            Some(432100)
+        );
+        assert_eq!(
+            by_epoch.secs_until(None, Some(536), Some(100), 432000, 1.0),
+            Some(100)
+        );
+        assert_eq!(
+            by_epoch.secs_until(None, Some(537), Some(100), 432000, 1.0),
+            None
+        );
+
+        let by_slot = fork(None, Some(1000));
+        assert_eq!(
+            by_slot.secs_until(Some(900), None, None, 432000, 2.0),
+            Some(200)
+        );
+        assert_eq!(
+            by_slot.secs_until(Some(1000), None, None, 432000, 2.0),
+            None
+        );
+        assert_eq!(by_epoch.describe(), "protocol 10.0 at epoch 537");
+    }
+
+    #[test]
+    fn test_version_check() {
+        assert_eq!(compare_versions("10.1.3", "10.1.4"), Ordering::Less);
+        assert_eq!(compare_versions("10.2", "10.1.4"), Ordering::Greater);
+        assert_eq!(compare_versions("v10.1.4", "10.1.4"), Ordering::Equal);
+        assert_eq!(compare_versions("9.10.0", "10.0"), Ordering::Less);
+
+        let fork = fork(Some(537), None);
+        assert!(fork.requires_upgrade(Some("10.1.3")));
+        assert!(!fork.requires_upgrade(Some("10.1.4")));
+        assert!(!fork.requires_upgrade(None));
+    }
+
+    #[test]
+    fn test_parse_koios_proposals() {
+        let items: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[
+                {"proposal_type": "HardForkInitiation", "ratified_epoch": 500,
+                 "enacted_epoch": 501, "dropped_epoch": null, "expired_epoch": null,
+                 "proposal_description": {"tag": "HardForkInitiation",
+                   "contents": [null, {"major": 9, "minor": 0}]}},
+                {"proposal_type": "HardForkInitiation", "ratified_epoch": 536,
+                 "enacted_epoch": null, "dropped_epoch": null, "expired_epoch": null,
+                 "proposal_description": {"tag": "HardForkInitiation",
+                   "contents": [{"txId": "ab", "govActionIx": 0}, {"major": 10, "minor": 0}]}},
+                {"proposal_type": "HardForkInitiation", "ratified_epoch": null,
+                 "enacted_epoch": null, "dropped_epoch": null, "expired_epoch": null}
+            ]"#,
+        )
+        .unwrap();
+        let fork = parse_koios_proposals(&items).unwrap();
+        assert_eq!(fork.epoch, Some(537));
+        assert_eq!(fork.protocol, Some("10.0".to_string()));
+        assert_eq!(fork.source, HardForkSource::Koios);
+        assert_eq!(parse_koios_proposals(&items[..1]), None);
+    }
+}
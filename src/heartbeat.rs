@@ -0,0 +1,143 @@
+//! Dead-man switch pings (`[global.heartbeat]`)
+//!
+//! While sview runs it requests the configured URL every `interval_secs`,
+//! the way cron jobs report to healthchecks.io or Uptime Kuma push monitors.
+//! When the pings stop because sview or its host died, that service alerts,
+//! which sview can't do for itself. The request body is a one-line summary of
+//! the nodes' health. With `fail_on_critical`, a ping made while any node is
+//! critical goes to `{url}/fail` instead, healthchecks.io's failure signal.
+//!
+//! Pings run in the background; a failed ping is logged and not retried.
+
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Longest a single ping may take
+const PING_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `[global.heartbeat]` in the config file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct HeartbeatConfig {
+    /// Ping URL; heartbeats are off without it
+    pub url: Option<String>,
+    /// Seconds between pings
+    #[serde(default = "default_interval")]
+    pub interval_secs: u64,
+    /// Ping `{url}/fail` while any node is critical
+    #[serde(default)]
+    pub fail_on_critical: bool,
+}
+
+fn default_interval() -> u64 {
+    60
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            interval_secs: default_interval(),
+            fail_on_critical: false,
+        }
+    }
+}
+
+impl HeartbeatConfig {
+    /// The URL to ping, given whether any node is critical
+    pub fn ping_url(&self, critical: bool) -> Option<String> {
+        let url = self.url.as_deref()?;
+        Some(if critical && self.fail_on_critical {
+            format!("{}/fail", url.trim_end_matches('/'))
+        } else {
+            url.to_string()
+        })
+    }
+}
+
+/// Pings the configured URL
+pub struct Heartbeat {
+    client: reqwest::Client,
+    config: HeartbeatConfig,
+}
+
+impl Heartbeat {
+    /// A heartbeat for `config`, or `None` when no URL is set
+    pub fn new(config: &HeartbeatConfig) -> Option<Self> {
+        config.url.as_ref()?;
+        let client = match reqwest::Client::builder()
+            .timeout(PING_TIMEOUT)
+            .user_agent(concat!("sview/", env!("CARGO_PKG_VERSION")))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Heartbeat disabled: {}", e);
+                return None;
+            }
+        };
+        Some(Self {
+            client,
+            config: config.clone(),
+        })
+    }
+
+    /// Whether this heartbeat was created from `config`
+    pub fn is_for(&self, config: &HeartbeatConfig) -> bool {
+        self.config == *config
+    }
+
+    /// Time between pings
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.config.interval_secs.max(1))
+    }
+
+    /// Ping in the background with a status summary as the body
+    pub fn ping(&self, critical: bool, summary: String) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let Some(url) = self.config.ping_url(critical) else {
+            return;
+        };
+        let request = self.client.post(&url).body(summary);
+        runtime.spawn(async move {
+            match request.send().await.and_then(|r| r.error_for_status()) {
+                Ok(_) => debug!("Heartbeat sent to {}", url),
+                Err(e) => warn!("Heartbeat to {} failed: {}", url, e),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ping_url() {
+        let config: HeartbeatConfig =
+            toml::from_str(r#"url = "https://hc-ping.com/abc-123/""#).unwrap();
+        assert_eq!(config.interval_secs, 60);
+        assert_eq!(
+            config.ping_url(true).as_deref(),
+            Some("https://hc-ping.com/abc-123/")
+        );
+
+        let config = HeartbeatConfig {
+            fail_on_critical: true,
+            ..config
+        };
+        assert_eq!(
+            config.ping_url(false).as_deref(),
+            Some("https://hc-ping.com/abc-123/")
+        );
+        assert_eq!(
+            config.ping_url(true).as_deref(),
+            Some("https://hc-ping.com/abc-123/fail")
+        );
+
+        assert!(HeartbeatConfig::default().ping_url(false).is_none());
+        assert!(Heartbeat::new(&HeartbeatConfig::default()).is_none());
+    }
+}
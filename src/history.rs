@@ -4,6 +4,31 @@
 //! used to generate sparkline visualizations in the TUI.
 
 use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Window covered by the TPS tracker's per-minute buckets (24 hours)
+const TPS_WINDOW_SECS: u64 = 86400;
+
+/// Minimum spacing between tx_processed samples used for a TPS reading
+const MIN_TPS_SAMPLE_SECS: f64 = 0.5;
+
+/// Default window over which counter rates are averaged (bursty counters like
+/// blocks served would otherwise flip between zero and spikes every refresh)
+const RATE_WINDOW_SECS: f64 = 60.0;
+
+/// Window for the slot rate (the tip slot only advances when a block arrives,
+/// about every 20s on mainnet, so shorter windows are too noisy)
+const SLOT_RATE_WINDOW_SECS: f64 = 300.0;
+
+/// Window for hourly chain figures (blocks per minute, density)
+const CHAIN_WINDOW_SECS: f64 = 3600.0;
+
+/// Window for CPU usage (CPU time only advances in clock ticks, so single
+/// refresh intervals jitter)
+const CPU_RATE_WINDOW_SECS: f64 = 10.0;
+
+/// Number of rate readings kept for trends
+const RATE_HISTORY_LEN: usize = 30;
 
 /// A ring buffer for storing historical metric values
 #[derive(Debug, Clone)]
@@ -37,26 +62,27 @@ impl MetricHistory {
         self.values.iter().map(|v| *v as u64).collect()
     }
 
+    /// Get the raw values (oldest first), for fractional metrics
+    pub fn as_slice_f64(&self) -> Vec<f64> {
+        self.values.iter().copied().collect()
+    }
+
     /// Get the current (most recent) value
-    #[allow(dead_code)]
     pub fn current(&self) -> Option<f64> {
         self.values.back().copied()
     }
 
     /// Get the minimum value in the history
-    #[allow(dead_code)]
     pub fn min(&self) -> Option<f64> {
         self.values.iter().copied().reduce(f64::min)
     }
 
     /// Get the maximum value in the history
-    #[allow(dead_code)]
     pub fn max(&self) -> Option<f64> {
         self.values.iter().copied().reduce(f64::max)
     }
 
     /// Get the average value in the history
-    #[allow(dead_code)]
     pub fn avg(&self) -> Option<f64> {
         if self.values.is_empty() {
             None
@@ -93,6 +119,177 @@ impl MetricHistory {
     }
 }
 
+/// Transactions-per-second statistics derived from `tx_processed` deltas
+///
+/// Readings are folded into per-minute buckets covering the last 24 hours,
+/// which back the 1h average and 24h peak independent of sparkline length.
+#[derive(Debug, Clone, Default)]
+pub struct TpsTracker {
+    /// Previous (tx_processed, unix time in seconds) sample
+    last_sample: Option<(u64, f64)>,
+    /// (minute start, sum of readings, reading count), oldest first
+    minutes: VecDeque<(u64, f64, u32)>,
+}
+
+impl TpsTracker {
+    /// Feed a tx_processed counter value, returning the TPS since the previous sample
+    pub fn sample(&mut self, tx_processed: u64, now: f64) -> Option<f64> {
+        let previous = self.last_sample;
+        match previous {
+            Some((_, last_time)) if now - last_time < MIN_TPS_SAMPLE_SECS => return None,
+            _ => self.last_sample = Some((tx_processed, now)),
+        }
+
+        let (last_count, last_time) = previous?;
+        // Counter reset (node restart) - start over from this sample
+        let delta = tx_processed.checked_sub(last_count)?;
+        let tps = delta as f64 / (now - last_time);
+        self.record(now as u64, tps);
+        Some(tps)
+    }
+
+    /// Record a TPS reading at a unix timestamp
+    pub fn record(&mut self, timestamp: u64, tps: f64) {
+        let minute = timestamp - timestamp % 60;
+        match self.minutes.back_mut() {
+            Some((m, sum, count)) if *m == minute => {
+                *sum += tps;
+                *count += 1;
+            }
+            Some((m, _, _)) if *m > minute => {
+                // Out-of-order (backfilled) reading - keep buckets sorted
+                let idx = self.minutes.partition_point(|(m, _, _)| *m < minute);
+                match self.minutes.get_mut(idx) {
+                    Some((m, sum, count)) if *m == minute => {
+                        *sum += tps;
+                        *count += 1;
+                    }
+                    _ => self.minutes.insert(idx, (minute, tps, 1)),
+                }
+            }
+            _ => self.minutes.push_back((minute, tps, 1)),
+        }
+
+        let cutoff = timestamp.saturating_sub(TPS_WINDOW_SECS);
+        while self.minutes.front().is_some_and(|(m, _, _)| *m < cutoff) {
+            self.minutes.pop_front();
+        }
+    }
+
+    /// Average TPS over the last `window_secs` seconds
+    pub fn average(&self, now: u64, window_secs: u64) -> Option<f64> {
+        let cutoff = now.saturating_sub(window_secs);
+        let (sum, count) = self
+            .minutes
+            .iter()
+            .filter(|(m, _, _)| *m >= cutoff)
+            .fold((0.0, 0u32), |(s, c), (_, sum, count)| (s + sum, c + count));
+        (count > 0).then(|| sum / count as f64)
+    }
+
+    /// Peak per-minute average TPS over the last `window_secs` seconds
+    pub fn peak(&self, now: u64, window_secs: u64) -> Option<f64> {
+        let cutoff = now.saturating_sub(window_secs);
+        self.minutes
+            .iter()
+            .filter(|(m, _, _)| *m >= cutoff)
+            .map(|(_, sum, count)| sum / *count as f64)
+            .reduce(f64::max)
+    }
+
+    /// Average TPS over the last hour
+    pub fn avg_1h(&self) -> Option<f64> {
+        self.average(unix_now() as u64, 3600)
+    }
+
+    /// Peak TPS over the last 24 hours
+    pub fn peak_24h(&self) -> Option<f64> {
+        self.peak(unix_now() as u64, TPS_WINDOW_SECS)
+    }
+}
+
+/// Rate of change of a monotonic counter (blocks served, GC runs, ...)
+#[derive(Debug, Clone)]
+pub struct RateTracker {
+    /// Seconds of samples the rate is averaged over
+    window_secs: f64,
+    /// (unix time in seconds, counter value) samples within the rate window
+    samples: VecDeque<(f64, f64)>,
+    /// Per-second rate readings, oldest first
+    rates: MetricHistory,
+}
+
+impl Default for RateTracker {
+    fn default() -> Self {
+        Self::with_window(RATE_WINDOW_SECS)
+    }
+}
+
+impl RateTracker {
+    /// Create a tracker averaging over the given number of seconds
+    pub fn with_window(window_secs: f64) -> Self {
+        Self {
+            window_secs,
+            samples: VecDeque::new(),
+            rates: MetricHistory::new(RATE_HISTORY_LEN),
+        }
+    }
+
+    /// Feed a counter value, returning the per-second rate over the window
+    pub fn sample(&mut self, value: f64, now: f64) -> Option<f64> {
+        match self.samples.back() {
+            Some((last_time, _)) if now - last_time < MIN_TPS_SAMPLE_SECS => return None,
+            // Counter reset (node restart) - start over from this sample
+            Some((_, last_value)) if value < *last_value => self.samples.clear(),
+            _ => {}
+        }
+        self.samples.push_back((now, value));
+        // Keep the newest sample at or before the window start as the baseline
+        while self.samples.len() > 2 && self.samples[1].0 <= now - self.window_secs {
+            self.samples.pop_front();
+        }
+
+        let (first_time, first_value) = *self.samples.front()?;
+        if self.samples.len() < 2 {
+            return None;
+        }
+        let rate = (value - first_value) / (now - first_time);
+        self.rates.push(rate);
+        Some(rate)
+    }
+
+    /// Seconds covered by the samples behind the latest rate
+    pub fn span_secs(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some((first, _)), Some((last, _))) => last - first,
+            _ => 0.0,
+        }
+    }
+
+    /// Latest rate per second
+    pub fn per_second(&self) -> Option<f64> {
+        self.rates.current()
+    }
+
+    /// Latest rate per minute
+    pub fn per_minute(&self) -> Option<f64> {
+        self.per_second().map(|r| r * 60.0)
+    }
+
+    /// Change in the per-minute rate across the kept readings
+    pub fn trend(&self) -> Option<f64> {
+        self.rates.trend().map(|t| t * 60.0)
+    }
+}
+
+/// Current unix time in (fractional) seconds
+fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
 /// Collection of metric histories for all tracked metrics
 #[derive(Debug, Clone)]
 pub struct MetricsHistory {
@@ -100,8 +297,29 @@ pub struct MetricsHistory {
     pub slot_num: MetricHistory,
     pub peers_connected: MetricHistory,
     pub memory_used: MetricHistory,
+    /// CPU usage in percent of one core (derived from cpu_ms)
+    pub cpu_usage: MetricHistory,
     pub mempool_txs: MetricHistory,
     pub sync_progress: MetricHistory,
+    /// Transactions per second (derived from tx_processed)
+    pub tps: MetricHistory,
+    /// Rolling 1h/24h TPS statistics
+    pub tps_stats: TpsTracker,
+    // Counter rates
+    pub blocks_served_rate: RateTracker,
+    pub headers_served_rate: RateTracker,
+    pub blocks_late_rate: RateTracker,
+    pub gc_minor_rate: RateTracker,
+    pub gc_major_rate: RateTracker,
+    pub connection_error_rate: RateTracker,
+    pub handshake_failure_rate: RateTracker,
+    /// CPU milliseconds used per second
+    pub cpu_rate: RateTracker,
+    /// Tip slot progression over the last few minutes
+    pub slot_rate: RateTracker,
+    /// Block and slot progression over the last hour, sampled together
+    pub block_rate_1h: RateTracker,
+    pub slot_rate_1h: RateTracker,
     // P2P metrics
     pub p2p_hot_peers: MetricHistory,
     pub p2p_warm_peers: MetricHistory,
@@ -116,8 +334,22 @@ impl MetricsHistory {
             slot_num: MetricHistory::new(capacity),
             peers_connected: MetricHistory::new(capacity),
             memory_used: MetricHistory::new(capacity),
+            cpu_usage: MetricHistory::new(capacity),
             mempool_txs: MetricHistory::new(capacity),
             sync_progress: MetricHistory::new(capacity),
+            tps: MetricHistory::new(capacity),
+            tps_stats: TpsTracker::default(),
+            blocks_served_rate: RateTracker::default(),
+            headers_served_rate: RateTracker::default(),
+            blocks_late_rate: RateTracker::default(),
+            gc_minor_rate: RateTracker::default(),
+            gc_major_rate: RateTracker::default(),
+            connection_error_rate: RateTracker::default(),
+            handshake_failure_rate: RateTracker::default(),
+            cpu_rate: RateTracker::with_window(CPU_RATE_WINDOW_SECS),
+            slot_rate: RateTracker::with_window(SLOT_RATE_WINDOW_SECS),
+            block_rate_1h: RateTracker::with_window(CHAIN_WINDOW_SECS),
+            slot_rate_1h: RateTracker::with_window(CHAIN_WINDOW_SECS),
             p2p_hot_peers: MetricHistory::new(capacity),
             p2p_warm_peers: MetricHistory::new(capacity),
             p2p_cold_peers: MetricHistory::new(capacity),
@@ -126,6 +358,11 @@ impl MetricsHistory {
 
     /// Update all histories with new metric values
     pub fn update(&mut self, metrics: &crate::metrics::NodeMetrics) {
+        self.update_at(metrics, unix_now());
+    }
+
+    /// Update all histories with metric values sampled at a unix time
+    fn update_at(&mut self, metrics: &crate::metrics::NodeMetrics, now: f64) {
         if let Some(v) = metrics.block_height {
             self.block_height.push(v as f64);
         }
@@ -144,6 +381,35 @@ impl MetricsHistory {
         if let Some(v) = metrics.sync_progress {
             self.sync_progress.push(v);
         }
+        if let Some(v) = metrics.tx_processed {
+            if let Some(tps) = self.tps_stats.sample(v, now) {
+                self.tps.push(tps);
+            }
+        }
+        for (value, tracker) in [
+            (metrics.blocks_served, &mut self.blocks_served_rate),
+            (metrics.headers_served, &mut self.headers_served_rate),
+            (metrics.blocks_late, &mut self.blocks_late_rate),
+            (metrics.gc_minor, &mut self.gc_minor_rate),
+            (metrics.gc_major, &mut self.gc_major_rate),
+            (metrics.connection_errors, &mut self.connection_error_rate),
+            (metrics.handshake_failures, &mut self.handshake_failure_rate),
+            (metrics.slot_num, &mut self.slot_rate),
+        ] {
+            if let Some(v) = value {
+                tracker.sample(v as f64, now);
+            }
+        }
+        if let Some(v) = metrics.cpu_ms {
+            if let Some(ms_per_sec) = self.cpu_rate.sample(v as f64, now) {
+                self.cpu_usage.push(ms_per_sec / 10.0);
+            }
+        }
+        // Density needs blocks and slots over exactly the same samples
+        if let (Some(block), Some(slot)) = (metrics.block_height, metrics.slot_num) {
+            self.block_rate_1h.sample(block as f64, now);
+            self.slot_rate_1h.sample(slot as f64, now);
+        }
         // P2P metrics
         if let Some(v) = metrics.p2p.hot_peers {
             self.p2p_hot_peers.push(v as f64);
@@ -190,4 +456,79 @@ mod tests {
         assert_eq!(history.max(), Some(30.0));
         assert_eq!(history.avg(), Some(20.0));
     }
+
+    #[test]
+    fn test_tps_from_tx_deltas() {
+        let mut tracker = TpsTracker::default();
+        let start = 1_700_000_000.0;
+        assert_eq!(tracker.sample(1000, start), None);
+        assert_eq!(tracker.sample(1020, start + 10.0), Some(2.0));
+        // Counter reset after node restart yields no reading
+        assert_eq!(tracker.sample(5, start + 20.0), None);
+        assert_eq!(tracker.sample(35, start + 30.0), Some(3.0));
+    }
+
+    #[test]
+    fn test_tps_windows() {
+        let mut tracker = TpsTracker::default();
+        let now = 1_700_000_000;
+        tracker.record(now - 7200, 10.0); // outside 1h, inside 24h
+        tracker.record(now - 600, 1.0);
+        tracker.record(now - 60, 3.0);
+        assert_eq!(tracker.average(now, 3600), Some(2.0));
+        assert_eq!(tracker.peak(now, 86400), Some(10.0));
+
+        // Readings older than 24h are pruned
+        tracker.record(now + 86400, 0.5);
+        assert_eq!(tracker.peak(now + 86400, 86400), Some(0.5));
+    }
+
+    #[test]
+    fn test_counter_rate() {
+        let mut tracker = RateTracker::default();
+        let start = 1_700_000_000.0;
+        assert_eq!(tracker.sample(100.0, start), None);
+        assert_eq!(tracker.sample(110.0, start + 10.0), Some(1.0));
+        assert_eq!(tracker.per_minute(), Some(60.0));
+        // Averaged over the window rather than the last interval
+        assert_eq!(tracker.sample(110.0, start + 20.0), Some(0.5));
+        assert_eq!(tracker.trend(), Some(-30.0));
+
+        // Samples older than the window stop counting
+        tracker.sample(130.0, start + 70.0);
+        tracker.sample(130.0, start + 80.0);
+        assert_eq!(tracker.per_second(), Some(20.0 / 60.0));
+
+        assert_eq!(tracker.span_secs(), 60.0);
+
+        // Counter reset starts over
+        assert_eq!(tracker.sample(5.0, start + 90.0), None);
+        assert_eq!(tracker.sample(8.0, start + 93.0), Some(1.0));
+
+        let mut hourly = RateTracker::with_window(3600.0);
+        hourly.sample(0.0, start);
+        hourly.sample(180.0, start + 3600.0);
+        hourly.sample(181.0, start + 3610.0);
+        assert_eq!(hourly.span_secs(), 3610.0);
+    }
+
+    #[test]
+    fn test_cpu_usage() {
+        let mut history = MetricsHistory::new(10);
+        let start = 1_700_000_000.0;
+        let metrics = |cpu_ms| crate::metrics::NodeMetrics {
+            cpu_ms: Some(cpu_ms),
+            ..Default::default()
+        };
+        history.update_at(&metrics(1_000), start);
+        assert_eq!(history.cpu_usage.current(), None);
+
+        // 1.5s of CPU time over 5s of wall time
+        history.update_at(&metrics(2_500), start + 5.0);
+        assert_eq!(history.cpu_usage.current(), Some(30.0));
+
+        // Several busy threads use more than one core
+        history.update_at(&metrics(22_500), start + 15.0);
+        assert_eq!(history.cpu_usage.current(), Some(200.0));
+    }
 }
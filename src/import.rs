@@ -0,0 +1,264 @@
+//! Backfill history from external sources
+//!
+//! `sview import prometheus --url http://prom:9090 --range 30d` reads the
+//! cardano-node metrics an existing Prometheus server has recorded and stores
+//! them as history, so a new sview install starts with months of data.
+//!
+//! Every metric series is fetched at a 5-minute step, each step is rebuilt
+//! into the node's metrics text format and parsed like a live scrape, and the
+//! resulting snapshots are written into the aggregated storage tiers.
+
+use crate::metrics::parse_prometheus_metrics;
+use crate::storage::{MetricSnapshot, StorageManager};
+use crate::timezone::DisplayTimezone;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Resolution of imported samples
+const IMPORT_STEP_SECS: u64 = 300;
+
+/// Range fetched per request (keeps responses under Prometheus' 11,000
+/// points per series limit and bounded in size)
+const CHUNK_SECS: u64 = 7 * 86400;
+
+/// Metrics fetched from Prometheus
+const METRIC_NAME_PATTERN: &str = "cardano_node_metrics_.+|rts_gc_cpu_ms";
+
+/// External history source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportSource {
+    /// A Prometheus server that scraped the node
+    Prometheus,
+}
+
+/// A backfill requested on the command line
+#[derive(Debug, Clone)]
+pub struct ImportRequest {
+    pub source: ImportSource,
+    /// Server base URL
+    pub url: String,
+    /// Label selector narrowing the series to one node (e.g. `instance="relay1:12798"`)
+    pub selector: Option<String>,
+    /// How far back to import
+    pub range: Duration,
+    /// Node to import into (defaults to the first configured node)
+    pub node: Option<String>,
+}
+
+/// Import `range` of history from Prometheus into `storage`
+///
+/// Returns the number of stored samples.
+pub async fn import_prometheus(
+    url: &str,
+    selector: Option<&str>,
+    range: Duration,
+    storage: &StorageManager,
+    timezone: DisplayTimezone,
+) -> Result<usize> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(120))
+        .build()?;
+    let endpoint = format!("{}/api/v1/query_range", url.trim_end_matches('/'));
+    let query = build_query(selector);
+
+    let end = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let start = end.saturating_sub(range.as_secs());
+
+    let mut stored = 0;
+    let mut chunk_start = start;
+    while chunk_start < end {
+        let chunk_end = (chunk_start + CHUNK_SECS).min(end);
+        let body: serde_json::Value = client
+            .get(&endpoint)
+            .query(&[
+                ("query", query.as_str()),
+                ("start", &chunk_start.to_string()),
+                ("end", &chunk_end.to_string()),
+                ("step", &IMPORT_STEP_SECS.to_string()),
+            ])
+            .send()
+            .await
+            .with_context(|| format!("Failed to query {}", endpoint))?
+            .error_for_status()
+            .with_context(|| format!("Prometheus rejected query {}", query))?
+            .json()
+            .await
+            .context("Failed to parse Prometheus response")?;
+
+        let samples = parse_range_response(&body)?;
+        let snapshots = snapshots_from_samples(&samples);
+        stored += storage.import_snapshots(snapshots)?;
+        println!(
+            "  {} .. {}: {} step(s)",
+            timezone.datetime(chunk_start),
+            timezone.datetime(chunk_end),
+            samples.len()
+        );
+
+        // Prometheus includes both ends of the range
+        chunk_start = chunk_end + IMPORT_STEP_SECS;
+    }
+
+    Ok(stored)
+}
+
+/// PromQL selecting every cardano-node metric, narrowed by `selector`
+fn build_query(selector: Option<&str>) -> String {
+    let selector = selector
+        .map(|s| {
+            s.trim()
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .trim()
+        })
+        .filter(|s| !s.is_empty());
+    match selector {
+        Some(labels) => format!("{{__name__=~\"{}\",{}}}", METRIC_NAME_PATTERN, labels),
+        None => format!("{{__name__=~\"{}\"}}", METRIC_NAME_PATTERN),
+    }
+}
+
+/// Metric values by timestamp from a `query_range` matrix response
+///
+/// Fails when a metric matches several series (e.g. more than one node is
+/// scraped), since mixing them would corrupt the history.
+fn parse_range_response(body: &serde_json::Value) -> Result<BTreeMap<u64, Vec<(String, f64)>>> {
+    if body["status"] != "success" {
+        anyhow::bail!(
+            "Prometheus query failed: {}",
+            body["error"].as_str().unwrap_or("unknown error")
+        );
+    }
+    let series = body["data"]["result"]
+        .as_array()
+        .context("Prometheus response has no result matrix")?;
+
+    let mut samples: BTreeMap<u64, Vec<(String, f64)>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+    let mut duplicates = Vec::new();
+    for entry in series {
+        let Some(name) = entry["metric"]["__name__"].as_str() else {
+            continue;
+        };
+        // Build info labels change with node upgrades; not part of history
+        if name.contains("build_info") {
+            continue;
+        }
+        if !seen.insert(name.to_string()) {
+            duplicates.push(name.to_string());
+            continue;
+        }
+        for point in entry["values"].as_array().into_iter().flatten() {
+            let timestamp = point[0].as_f64().map(|t| t as u64);
+            let value = point[1].as_str().and_then(|v| v.parse::<f64>().ok());
+            if let (Some(timestamp), Some(value)) = (timestamp, value) {
+                if value.is_finite() {
+                    samples
+                        .entry(timestamp)
+                        .or_default()
+                        .push((name.to_string(), value));
+                }
+            }
+        }
+    }
+
+    if !duplicates.is_empty() {
+        anyhow::bail!(
+            "{} metric(s) match several series (e.g. {}); narrow them to one node with --query 'instance=\"host:port\"'",
+            duplicates.len(),
+            duplicates[0]
+        );
+    }
+    Ok(samples)
+}
+
+/// Rebuild each step as a metrics scrape and convert it to a snapshot
+fn snapshots_from_samples(samples: &BTreeMap<u64, Vec<(String, f64)>>) -> Vec<MetricSnapshot> {
+    samples
+        .iter()
+        .map(|(&timestamp, values)| {
+            let text: String = values
+                .iter()
+                .map(|(name, value)| format!("{} {}\n", name, value))
+                .collect();
+            let metrics = parse_prometheus_metrics(&text);
+            let mut snapshot = MetricSnapshot::from_metrics(&metrics);
+            snapshot.timestamp = timestamp;
+            // Both are derived from the current time when parsing a live scrape
+            snapshot.sync_progress = None;
+            snapshot.uptime_seconds = metrics
+                .node_start_time
+                .map(|start| timestamp.saturating_sub(start) as f64);
+            snapshot
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_query() {
+        assert_eq!(
+            build_query(None),
+            "{__name__=~\"cardano_node_metrics_.+|rts_gc_cpu_ms\"}"
+        );
+        assert_eq!(
+            build_query(Some("{instance=\"relay1:12798\"}")),
+            "{__name__=~\"cardano_node_metrics_.+|rts_gc_cpu_ms\",instance=\"relay1:12798\"}"
+        );
+    }
+
+    #[test]
+    fn test_parse_range_response() {
+        let body = serde_json::json!({
+            "status": "success",
+            "data": {
+                "resultType": "matrix",
+                "result": [
+                    {
+                        "metric": {"__name__": "cardano_node_metrics_blockNum_int", "instance": "relay1"},
+                        "values": [[1700000000, "10500000"], [1700000300, "10500015"]]
+                    },
+                    {
+                        "metric": {"__name__": "cardano_node_metrics_connectedPeers_int", "instance": "relay1"},
+                        "values": [[1700000000, "12"]]
+                    },
+                    {
+                        "metric": {"__name__": "cardano_node_metrics_nodeStartTime_int", "instance": "relay1"},
+                        "values": [[1700000000, "1699990000"]]
+                    }
+                ]
+            }
+        });
+        let samples = parse_range_response(&body).unwrap();
+        assert_eq!(samples.len(), 2);
+
+        let snapshots = snapshots_from_samples(&samples);
+        assert_eq!(snapshots[0].timestamp, 1700000000);
+        assert_eq!(snapshots[0].block_height, Some(10500000));
+        assert_eq!(snapshots[0].peers_connected, Some(12));
+        assert_eq!(snapshots[0].uptime_seconds, Some(10000.0));
+        assert_eq!(snapshots[0].sync_progress, None);
+        assert_eq!(snapshots[1].block_height, Some(10500015));
+
+        // A second node's series is rejected instead of mixed in
+        let mut mixed = body.clone();
+        mixed["data"]["result"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({
+                "metric": {"__name__": "cardano_node_metrics_blockNum_int", "instance": "relay2"},
+                "values": [[1700000000, "10499999"]]
+            }));
+        assert!(parse_range_response(&mixed).is_err());
+
+        let error = serde_json::json!({"status": "error", "error": "bad query"});
+        assert!(parse_range_response(&error).is_err());
+    }
+}
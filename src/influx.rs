@@ -0,0 +1,254 @@
+//! Push history to InfluxDB or VictoriaMetrics (`[global.influx]`)
+//!
+//! Every snapshot written to the local history is also sent as InfluxDB line
+//! protocol to `{url}/api/v2/write`, which both InfluxDB 2.x and
+//! VictoriaMetrics accept, so operators can keep long-term history in the
+//! time-series database they already run. Each snapshot field becomes a field
+//! of one `cardano_node` point tagged with the node name. Writes run in the
+//! background; a failed write is logged and the snapshot is not retried.
+
+use crate::storage::MetricSnapshot;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Longest a single write may take
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Snapshot fields that aren't metrics
+const SKIPPED_FIELDS: &[&str] = &["version", "timestamp", "span_secs", "min", "max"];
+
+/// `[global.influx]` in the config file
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct InfluxConfig {
+    /// Server base URL (e.g. "http://influx:8086"); pushing is off without it
+    pub url: Option<String>,
+    /// API token, sent as `Authorization: Token ...`
+    pub token: Option<String>,
+    /// InfluxDB organization
+    pub org: Option<String>,
+    /// InfluxDB bucket
+    pub bucket: Option<String>,
+    /// Measurement name [default: "cardano_node"]
+    pub measurement: Option<String>,
+}
+
+impl InfluxConfig {
+    /// The write endpoint with org, bucket and precision, if a URL is set
+    pub fn write_url(&self) -> Option<String> {
+        let base = self.url.as_deref()?.trim_end_matches('/');
+        let mut url = format!("{}/api/v2/write?precision=s", base);
+        if let Some(org) = &self.org {
+            url.push_str(&format!("&org={}", query_escape(org)));
+        }
+        if let Some(bucket) = &self.bucket {
+            url.push_str(&format!("&bucket={}", query_escape(bucket)));
+        }
+        Some(url)
+    }
+}
+
+/// Sends one node's snapshots to the configured server
+#[derive(Debug, Clone)]
+pub struct InfluxSink {
+    client: reqwest::Client,
+    write_url: String,
+    token: Option<String>,
+    measurement: String,
+    node: String,
+}
+
+impl InfluxSink {
+    /// A sink for `node`, or `None` when pushing is not configured
+    pub fn new(config: &InfluxConfig, node: &str) -> Option<Self> {
+        let write_url = config.write_url()?;
+        let client = match reqwest::Client::builder()
+            .timeout(WRITE_TIMEOUT)
+            .user_agent(concat!("sview/", env!("CARGO_PKG_VERSION")))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("InfluxDB push disabled: {}", e);
+                return None;
+            }
+        };
+        Some(Self {
+            client,
+            write_url,
+            token: config.token.clone(),
+            measurement: config
+                .measurement
+                .clone()
+                .unwrap_or_else(|| "cardano_node".to_string()),
+            node: node.to_string(),
+        })
+    }
+
+    /// Write a snapshot in the background
+    pub fn push(&self, snapshot: &MetricSnapshot) {
+        let Some(line) = line_protocol(&self.measurement, &self.node, snapshot) else {
+            return;
+        };
+        // Storage is also used outside the runtime (tests, exports)
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let mut request = self
+            .client
+            .post(&self.write_url)
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(line);
+        if let Some(token) = &self.token {
+            request = request.header("Authorization", format!("Token {}", token));
+        }
+        let node = self.node.clone();
+        runtime.spawn(async move {
+            match request.send().await.and_then(|r| r.error_for_status()) {
+                Ok(_) => debug!("Pushed snapshot for '{}' to InfluxDB", node),
+                Err(e) => warn!("Failed to push snapshot for '{}': {}", node, e),
+            }
+        });
+    }
+}
+
+/// One line-protocol point for a snapshot, or `None` if it has no values
+pub fn line_protocol(measurement: &str, node: &str, snapshot: &MetricSnapshot) -> Option<String> {
+    let value = serde_json::to_value(snapshot).ok()?;
+    let fields: Vec<String> = value
+        .as_object()?
+        .iter()
+        .filter(|(name, _)| !SKIPPED_FIELDS.contains(&name.as_str()))
+        .filter_map(|(name, value)| {
+            let number = value.as_number()?;
+            let value = if number.is_f64() {
+                number.to_string()
+            } else {
+                format!("{}i", number)
+            };
+            Some(format!("{}={}", escape(name), value))
+        })
+        .collect();
+    if fields.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{},node={} {} {}\n",
+        escape(measurement),
+        escape(node),
+        fields.join(","),
+        snapshot.timestamp
+    ))
+}
+
+/// Escape a measurement, tag or field key/value for line protocol
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, ',' | '=' | ' ') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Percent-encode a query parameter value
+fn query_escape(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_protocol() {
+        let snapshot = MetricSnapshot {
+            timestamp: 1_700_000_000,
+            block_height: Some(11_200_000),
+            peers_connected: Some(25),
+            sync_progress: Some(100.0),
+            tps: Some(1.5),
+            ..Default::default()
+        };
+        let line = line_protocol("cardano_node", "Relay 1", &snapshot).unwrap();
+        assert!(line.starts_with("cardano_node,node=Relay\\ 1 "));
+        assert!(line.ends_with(" 1700000000\n"));
+        assert!(line.contains("block_height=11200000i"));
+        assert!(line.contains("peers_connected=25i"));
+        assert!(line.contains("sync_progress=100.0"));
+        assert!(line.contains("tps=1.5"));
+        assert!(!line.contains("version="));
+        assert!(!line.contains("kes_remaining"));
+
+        let empty = MetricSnapshot::default();
+        assert_eq!(line_protocol("cardano_node", "bp", &empty), None);
+    }
+
+    #[tokio::test]
+    async fn test_push_writes_line_protocol() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let received = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            // Headers and body may arrive in separate reads
+            while !String::from_utf8_lossy(&request).ends_with(" 1700000000\n") {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let config = InfluxConfig {
+            url: Some(format!("http://127.0.0.1:{}", port)),
+            token: Some("secret".to_string()),
+            bucket: Some("cardano".to_string()),
+            ..Default::default()
+        };
+        let sink = InfluxSink::new(&config, "bp").unwrap();
+        sink.push(&MetricSnapshot {
+            timestamp: 1_700_000_000,
+            block_height: Some(42),
+            ..Default::default()
+        });
+
+        let request = received.await.unwrap();
+        assert!(request.starts_with("POST /api/v2/write?precision=s&bucket=cardano "));
+        assert!(request
+            .to_lowercase()
+            .contains("authorization: token secret"));
+        assert!(request.ends_with("cardano_node,node=bp block_height=42i 1700000000\n"));
+    }
+
+    #[test]
+    fn test_write_url() {
+        let config = InfluxConfig {
+            url: Some("http://influx:8086/".to_string()),
+            org: Some("Sandstone Pool".to_string()),
+            bucket: Some("cardano".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.write_url().unwrap(),
+            "http://influx:8086/api/v2/write?precision=s&org=Sandstone%20Pool&bucket=cardano"
+        );
+        assert_eq!(InfluxConfig::default().write_url(), None);
+        assert!(InfluxSink::new(&InfluxConfig::default(), "bp").is_none());
+    }
+}
@@ -0,0 +1,545 @@
+//! Configurable keybindings for dashboard actions
+//!
+//! The `[keys]` config section maps action names to a key or a list of keys:
+//!
+//! ```toml
+//! [keys]
+//! quit = ["q", "Ctrl+c"]
+//! peers = "P"
+//! next_node = ["Tab", "l"]
+//! ```
+//!
+//! Actions not listed keep their default keys. A key given to an action is
+//! taken away from any default that used it, except that keys of actions
+//! used inside a single overlay (`ban`, `save_settings`) leave the dashboard
+//! defaults alone.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// An action that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Refresh,
+    Help,
+    Theme,
+    Peers,
+    Graphs,
+    Mempool,
+    Propagation,
+    Epochs,
+    Settings,
+    Compare,
+    ReloadConfig,
+    AddNode,
+    CommandLine,
+    Pause,
+    Copy,
+    Screenshot,
+    Focus,
+    NextNode,
+    PrevNode,
+    ToggleGroup,
+    Incidents,
+    Ban,
+    SaveSettings,
+}
+
+impl Action {
+    /// All actions, in help order
+    pub const ALL: [Action; 24] = [
+        Action::Quit,
+        Action::Refresh,
+        Action::Help,
+        Action::CommandLine,
+        Action::Pause,
+        Action::Copy,
+        Action::Screenshot,
+        Action::Theme,
+        Action::Focus,
+        Action::Peers,
+        Action::Graphs,
+        Action::Mempool,
+        Action::Propagation,
+        Action::Epochs,
+        Action::Incidents,
+        Action::Settings,
+        Action::Compare,
+        Action::ReloadConfig,
+        Action::AddNode,
+        Action::NextNode,
+        Action::PrevNode,
+        Action::ToggleGroup,
+        Action::Ban,
+        Action::SaveSettings,
+    ];
+
+    /// Name used in the `[keys]` config section
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Refresh => "refresh",
+            Action::Help => "help",
+            Action::Theme => "theme",
+            Action::Peers => "peers",
+            Action::Graphs => "graphs",
+            Action::Mempool => "mempool",
+            Action::Propagation => "propagation",
+            Action::Epochs => "epochs",
+            Action::Settings => "settings",
+            Action::Compare => "compare",
+            Action::ReloadConfig => "reload_config",
+            Action::AddNode => "add_node",
+            Action::CommandLine => "command_line",
+            Action::Pause => "pause",
+            Action::Copy => "copy",
+            Action::Screenshot => "screenshot",
+            Action::Focus => "focus",
+            Action::NextNode => "next_node",
+            Action::PrevNode => "prev_node",
+            Action::ToggleGroup => "toggle_group",
+            Action::Incidents => "incidents",
+            Action::Ban => "ban",
+            Action::SaveSettings => "save_settings",
+        }
+    }
+
+    /// Description for the help overlay
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit / Close overlay",
+            Action::Refresh => "Force refresh metrics",
+            Action::Help => "Toggle this help",
+            Action::Theme => "Cycle color theme",
+            Action::Peers => "Toggle peer connections",
+            Action::Graphs => "Toggle historical graphs",
+            Action::Mempool => "Toggle mempool transactions (needs socket)",
+            Action::Propagation => "Toggle block propagation (needs log_file)",
+            Action::Epochs => "Toggle epoch summaries",
+            Action::Settings => "Edit health thresholds",
+            Action::Compare => "Compare nodes over a time window",
+            Action::ReloadConfig => "Reload config file",
+            Action::AddNode => "Add a node",
+            Action::CommandLine => "Command line (:node, :theme, :refresh, :export, :silence)",
+            Action::Pause => "Pause / resume display updates",
+            Action::Copy => "Copy peer IP / node values to clipboard",
+            Action::Screenshot => "Save screenshot (text + ANSI)",
+            Action::Focus => "Expand a panel full-screen (all its metrics)",
+            Action::NextNode => "Next node",
+            Action::PrevNode => "Previous node",
+            Action::ToggleGroup => "Collapse / expand the node's group",
+            Action::Incidents => "Toggle incidents (restarts, forks, rollbacks)",
+            Action::Ban => "Ban / unban peer (details)",
+            Action::SaveSettings => "Write thresholds to the config (settings)",
+        }
+    }
+
+    /// Keys bound when the config doesn't say otherwise
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "Esc"],
+            Action::Refresh => &["r"],
+            Action::Help => &["?"],
+            Action::Theme => &["t"],
+            Action::Peers => &["p"],
+            Action::Graphs => &["g"],
+            Action::Mempool => &["m"],
+            Action::Propagation => &["f"],
+            Action::Epochs => &["e"],
+            Action::Settings => &["s"],
+            Action::Compare => &["b"],
+            Action::ReloadConfig => &["R"],
+            Action::AddNode => &["n"],
+            Action::CommandLine => &[":"],
+            Action::Pause => &["Space"],
+            Action::Copy => &["y"],
+            Action::Screenshot => &["S"],
+            Action::Focus => &["z", "Enter"],
+            Action::NextNode => &["Tab", "Right"],
+            Action::PrevNode => &["Shift+Tab", "Left"],
+            Action::ToggleGroup => &["c"],
+            Action::Incidents => &["i"],
+            // Only used inside their overlays, so they may share dashboard keys
+            Action::Ban => &["b"],
+            Action::SaveSettings => &["w"],
+        }
+    }
+
+    /// Whether the action only applies inside one overlay
+    ///
+    /// Their keys don't trigger anything on the dashboard, so a default key
+    /// may also be a dashboard key (`b` bans in the peer details and opens
+    /// the comparison on the dashboard).
+    pub fn overlay_only(&self) -> bool {
+        matches!(self, Action::Ban | Action::SaveSettings)
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == s)
+            .ok_or_else(|| format!("unknown action '{}'", s))
+    }
+}
+
+/// A key with the modifiers that matter for bindings
+///
+/// Shift is folded into the character (`R`, `?`), so only Ctrl and Alt are
+/// tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    ctrl: bool,
+    alt: bool,
+}
+
+impl Key {
+    /// Normalize a terminal key event
+    pub fn from_event(event: &KeyEvent) -> Self {
+        let code = match event.code {
+            KeyCode::Tab if event.modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        Self {
+            code,
+            ctrl: event.modifiers.contains(KeyModifiers::CONTROL),
+            alt: event.modifiers.contains(KeyModifiers::ALT),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    /// Parse `q`, `R`, `Esc`, `Shift+Tab`, `F5`, `Ctrl+x`, `Alt+Left`, ...
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid key '{}'", s);
+        let mut key = Key {
+            code: KeyCode::Null,
+            ctrl: false,
+            alt: false,
+        };
+        let mut rest = s;
+        // A lone "+" is a key, not a separator
+        while let Some((modifier, tail)) = rest
+            .split_once('+')
+            .filter(|(m, t)| !m.is_empty() && !t.is_empty())
+        {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => key.ctrl = true,
+                "alt" => key.alt = true,
+                "shift" if tail.eq_ignore_ascii_case("tab") => {
+                    key.code = KeyCode::BackTab;
+                    return Ok(key);
+                }
+                _ => return Err(invalid()),
+            }
+            rest = tail;
+        }
+
+        let mut chars = rest.chars();
+        key.code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "backspace" => KeyCode::Backspace,
+                "space" => KeyCode::Char(' '),
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                "delete" | "del" => KeyCode::Delete,
+                "insert" | "ins" => KeyCode::Insert,
+                f => match f.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(invalid()),
+                },
+            },
+        };
+        Ok(key)
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "Shift+Tab"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// A single key or a list of keys in the config file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Active action → keys mapping
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "HashMap<String, KeyList>")]
+pub struct KeyBindings {
+    /// Keys per action, in `Action::ALL` order
+    bindings: Vec<(Action, Vec<Key>)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .into_iter()
+            .map(|action| {
+                let keys = action
+                    .default_keys()
+                    .iter()
+                    .map(|k| k.parse().expect("valid default key"))
+                    .collect();
+                (action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl TryFrom<HashMap<String, KeyList>> for KeyBindings {
+    type Error = String;
+
+    fn try_from(config: HashMap<String, KeyList>) -> Result<Self, Self::Error> {
+        let mut overrides: HashMap<Action, Vec<Key>> = HashMap::new();
+        // Keys of configured dashboard actions, and of all configured actions
+        let mut owners: HashMap<Key, Action> = HashMap::new();
+        let mut claimed: HashSet<Key> = HashSet::new();
+        for (name, list) in config {
+            let action: Action = name.parse()?;
+            let names = match list {
+                KeyList::One(key) => vec![key],
+                KeyList::Many(keys) => keys,
+            };
+            let mut keys = Vec::new();
+            for name in names {
+                let key: Key = name.parse()?;
+                claimed.insert(key);
+                if action.overlay_only() {
+                    keys.push(key);
+                    continue;
+                }
+                if let Some(other) = owners.insert(key, action).filter(|a| *a != action) {
+                    return Err(format!(
+                        "key '{}' is bound to both '{}' and '{}'",
+                        name,
+                        other.name(),
+                        action.name()
+                    ));
+                }
+                keys.push(key);
+            }
+            overrides.insert(action, keys);
+        }
+
+        let mut bindings = KeyBindings::default();
+        for (action, keys) in &mut bindings.bindings {
+            match overrides.remove(action) {
+                Some(custom) => *keys = custom,
+                // Defaults give up keys claimed by a configured action; an
+                // overlay's keys don't reach the dashboard, so they only
+                // displace other overlay defaults
+                None if action.overlay_only() => keys.retain(|key| !claimed.contains(key)),
+                None => keys.retain(|key| !owners.contains_key(key)),
+            }
+        }
+        Ok(bindings)
+    }
+}
+
+impl KeyBindings {
+    /// The dashboard action bound to a key press, if any
+    ///
+    /// Overlay-only actions are left out; check those with `is`.
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        let key = Key::from_event(event);
+        self.bindings
+            .iter()
+            .find(|(action, keys)| !action.overlay_only() && keys.contains(&key))
+            .map(|(action, _)| *action)
+    }
+
+    /// Whether a key press triggers `action`
+    pub fn is(&self, event: &KeyEvent, action: Action) -> bool {
+        self.keys(action).contains(&Key::from_event(event))
+    }
+
+    /// Whether a key press closes an overlay opened with `action`
+    ///
+    /// Esc always closes, as do the quit keys and the overlay's own key.
+    pub fn closes(&self, event: &KeyEvent, action: Action) -> bool {
+        event.code == KeyCode::Esc
+            || matches!(self.action(event), Some(a) if a == action || a == Action::Quit)
+    }
+
+    /// Keys bound to an action, for display (e.g. `q, Esc`)
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action)
+            .iter()
+            .map(Key::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// First key bound to an action, for compact hints (empty when unbound)
+    pub fn short_label(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map(Key::to_string)
+            .unwrap_or_default()
+    }
+
+    fn keys(&self, action: Action) -> &[Key] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!("q".parse::<Key>().map(|k| k.code), Ok(KeyCode::Char('q')));
+        assert_eq!("+".parse::<Key>().map(|k| k.code), Ok(KeyCode::Char('+')));
+        assert_eq!("F5".parse::<Key>().map(|k| k.code), Ok(KeyCode::F(5)));
+        assert_eq!(
+            "Shift+Tab".parse::<Key>().map(|k| k.code),
+            Ok(KeyCode::BackTab)
+        );
+        let ctrl_c: Key = "Ctrl+c".parse().unwrap();
+        assert!(ctrl_c.ctrl);
+        assert_eq!(ctrl_c.to_string(), "Ctrl+c");
+        assert!("Hyper+x".parse::<Key>().is_err());
+        assert!("F13".parse::<Key>().is_err());
+        assert!("nope".parse::<Key>().is_err());
+    }
+
+    #[test]
+    fn test_default_bindings() {
+        let keys = KeyBindings::default();
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            keys.action(&press(KeyCode::Char('q'), none)),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            keys.action(&press(KeyCode::Char('R'), KeyModifiers::SHIFT)),
+            Some(Action::ReloadConfig)
+        );
+        assert_eq!(
+            keys.action(&press(KeyCode::Tab, KeyModifiers::SHIFT)),
+            Some(Action::PrevNode)
+        );
+        assert_eq!(keys.action(&press(KeyCode::Char('x'), none)), None);
+        assert_eq!(keys.label(Action::Quit), "q, Esc");
+        assert!(keys.closes(&press(KeyCode::Char('g'), none), Action::Graphs));
+        assert!(!keys.closes(&press(KeyCode::Char('p'), none), Action::Graphs));
+
+        // Overlay keys may share dashboard keys without taking them over
+        let b = press(KeyCode::Char('b'), none);
+        assert_eq!(keys.action(&b), Some(Action::Compare));
+        assert!(keys.is(&b, Action::Ban));
+        assert!(keys.is(&press(KeyCode::Char('w'), none), Action::SaveSettings));
+        assert_eq!(keys.action(&press(KeyCode::Char('w'), none)), None);
+    }
+
+    #[test]
+    fn test_configured_bindings() {
+        let config: toml::Value = toml::from_str(
+            r#"
+quit = ["x", "Ctrl+c"]
+peers = "q"
+"#,
+        )
+        .unwrap();
+        let keys = KeyBindings::deserialize(config).unwrap();
+        let none = KeyModifiers::NONE;
+
+        assert_eq!(
+            keys.action(&press(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        // "q" moved to peers; the unconfigured graphs key is unchanged
+        assert_eq!(
+            keys.action(&press(KeyCode::Char('q'), none)),
+            Some(Action::Peers)
+        );
+        assert_eq!(keys.action(&press(KeyCode::Char('p'), none)), None);
+        assert_eq!(
+            keys.action(&press(KeyCode::Char('g'), none)),
+            Some(Action::Graphs)
+        );
+        assert_eq!(keys.short_label(Action::Quit), "x");
+
+        let conflict: toml::Value = toml::from_str("quit = \"x\"\npeers = \"x\"").unwrap();
+        assert!(KeyBindings::deserialize(conflict).is_err());
+        let unknown: toml::Value = toml::from_str("launch = \"l\"").unwrap();
+        assert!(KeyBindings::deserialize(unknown).is_err());
+    }
+
+    #[test]
+    fn test_overlay_bindings() {
+        let none = KeyModifiers::NONE;
+        // A dashboard key takes the key from an overlay default
+        let config: toml::Value = toml::from_str("settings = \"w\"").unwrap();
+        let keys = KeyBindings::deserialize(config).unwrap();
+        let w = press(KeyCode::Char('w'), none);
+        assert!(keys.closes(&w, Action::Settings));
+        assert!(!keys.is(&w, Action::SaveSettings));
+        assert_eq!(keys.short_label(Action::SaveSettings), "");
+
+        // An overlay key leaves the dashboard defaults alone
+        let config: toml::Value = toml::from_str("ban = \"c\"\ncompare = \"x\"").unwrap();
+        let keys = KeyBindings::deserialize(config).unwrap();
+        let c = press(KeyCode::Char('c'), none);
+        assert!(keys.is(&c, Action::Ban));
+        assert_eq!(keys.action(&c), Some(Action::ToggleGroup));
+        assert!(!keys.is(&press(KeyCode::Char('b'), none), Action::Ban));
+    }
+}
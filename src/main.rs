@@ -5,34 +5,76 @@
 //!
 //! Supports monitoring multiple nodes via config file (~/.config/sview/config.toml).
 
+mod agent;
 mod alerts;
 mod app;
+mod benchmark;
+mod blocklist;
+mod cbor;
+mod chain_api;
+mod check;
+mod cncli;
+mod command;
 mod config;
+mod demo;
+mod discover;
+mod epochs;
+mod follow;
+mod forks;
+mod format;
+mod genesis;
 mod geoip;
+mod glossary;
+mod hardfork;
+mod heartbeat;
 mod history;
+mod import;
+mod influx;
+mod keys;
+mod mempool;
 mod metrics;
+mod mqtt;
+mod node_form;
+mod node_socket;
+mod notify;
+mod opcert;
 mod peers;
+mod pooltool;
+mod propagation;
+mod reference_tip;
+mod release;
+mod report;
+mod restarts;
+mod session;
+mod share;
 mod sockets;
+mod startup;
+mod status;
 mod storage;
+mod suspend;
 mod themes;
+mod timezone;
+mod tunnel;
 mod ui;
+mod web;
 
 use anyhow::Result;
-use crossterm::{
-    event::{
-        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
-    },
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use ratatui::prelude::*;
 use std::io;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use app::{App, AppMode};
-use config::AppConfig;
+use clap::{CommandFactory, Parser};
+use config::{AppConfig, CliArgs, Command, ConfigAction};
+use keys::Action;
+use node_form::{FormField, FormStep};
 use storage::StorageManager;
 
+/// Longest the screen goes without a redraw, so relative times ("Updated 3s
+/// ago", countdowns) stay current between refreshes
+const CLOCK_REDRAW_INTERVAL: Duration = Duration::from_secs(1);
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize tracing for logging (respects RUST_LOG env var)
@@ -41,53 +83,231 @@ async fn main() -> Result<()> {
         .with_target(false)
         .init();
 
-    // Load configuration from CLI, environment, and config file
-    let app_config = AppConfig::load();
+    let mut args = CliArgs::parse();
+
+    // Keep simulated history and alerts out of the real data directory
+    let demo = args.demo;
+    if demo {
+        storage::set_data_dir(demo::data_dir());
+    }
+
+    // Subcommands without a flag form run here; the others set their flags
+    match args.command.take() {
+        Some(command @ (Command::Completions { .. } | Command::Man)) => {
+            return print_cli_docs(&command);
+        }
+        Some(Command::Config {
+            action: ConfigAction::Validate,
+        }) => {
+            let passed = check::run(args, false).await;
+            std::process::exit(if passed { 0 } else { 1 });
+        }
+        Some(Command::Agent {
+            listen,
+            token,
+            token_file,
+            tls_cert,
+            tls_key,
+            client_ca,
+        }) => {
+            let tls = tls_cert.zip(tls_key).map(|(cert, key)| agent::ServerTls {
+                cert,
+                key,
+                client_ca,
+            });
+            let token = agent::resolve_token(token, token_file.as_deref())?;
+            return agent::run(listen, token, args.prom_port.unwrap_or(12798), tls).await;
+        }
+        Some(Command::Status { json }) => {
+            let app_config = AppConfig::load(args)?;
+            let code = status::run(&app_config, json).await?;
+            std::process::exit(code);
+        }
+        Some(
+            command @ (Command::Monitor
+            | Command::Export { .. }
+            | Command::Report { .. }
+            | Command::AlertReport { .. }
+            | Command::Check
+            | Command::Import { .. }
+            | Command::Prune
+            | Command::Discover),
+        ) => args.apply_command(command),
+        None => {}
+    }
+
+    // Handle `sview check` (--check-config): validate, test each node and exit
+    if args.check_config {
+        let passed = check::run(args, true).await;
+        std::process::exit(if passed { 0 } else { 1 });
+    }
 
-    // Handle --export flag: export to CSV and exit
+    // Load configuration from CLI, environment, and config file, or mirror
+    // another sview's nodes, keeping their history out of its data directory
+    let follow = args.follow.is_some();
+    let app_config = match args.follow.clone() {
+        Some(addr) => {
+            storage::set_data_dir(follow::data_dir());
+            follow::app_config(args, &addr).await?
+        }
+        None => AppConfig::load(args)?,
+    };
+
+    // Handle `sview export` (--export): export to CSV and exit
     if let Some(export_path) = &app_config.export_path {
         return export_metrics(&app_config, export_path);
     }
 
+    // Handle `sview import` (--import): backfill history and exit
+    if let Some(request) = &app_config.import {
+        return import_history(&app_config, request).await;
+    }
+
+    // Handle `sview prune` (--prune): remove expired history and exit
+    if app_config.prune {
+        return prune_history(&app_config);
+    }
+
+    // Handle --flush-geoip-cache: delete the peer location cache and exit
+    if app_config.flush_geoip_cache {
+        let path = geoip::cache_path();
+        if geoip::flush_cache(&path)? {
+            println!("Removed {}", path.display());
+        } else {
+            println!("No GeoIP cache at {}", path.display());
+        }
+        return Ok(());
+    }
+
+    // Handle `sview discover` (--discover): scan for nodes, offer to add them and exit
+    if app_config.discover {
+        return discover::run(&app_config).await;
+    }
+
+    // Handle `sview report` (--report): print a report from stored history and exit
+    if let Some(request) = &app_config.report {
+        return report::run(&app_config, request);
+    }
+
+    // Handle `sview alert-report` (--alert-report): summarize recorded alerts and exit
+    if let Some(period) = app_config.alert_report {
+        return alert_report(&app_config, period);
+    }
+
+    // Start the web dashboard first, so a busy address fails before the TUI opens
+    let web = app_config.web.map(web::WebServer::bind).transpose()?;
+
     // Setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
+    suspend::enter_terminal()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     // Create app state and run
     let mut app = App::new(app_config);
-    let result = run_app(&mut terminal, &mut app).await;
+    if let Some(web) = web {
+        app.serve_web(web);
+    }
+    let saved_session = session::load(&session::path());
+    let saved_mode = saved_session.mode;
+    app.restore_session(saved_session);
+    let result = run_app(&mut terminal, &mut app, saved_mode).await;
+    app.close_outages();
+    let saved = session::save(&session::path(), &app.session());
 
     // Restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
+    suspend::leave_terminal()?;
     terminal.show_cursor()?;
 
+    if demo {
+        let _ = std::fs::remove_dir_all(demo::data_dir());
+    }
+    if follow {
+        let _ = std::fs::remove_dir_all(follow::data_dir());
+    }
+
     if let Err(err) = result {
         eprintln!("Error: {err:?}");
     }
+    if let Err(err) = saved {
+        eprintln!("Failed to save the session: {err:#}");
+    }
 
     Ok(())
 }
 
-async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
-    // Initial metrics fetch for all nodes
+async fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    app: &mut App,
+    saved_mode: AppMode,
+) -> Result<()> {
+    // Initial metrics fetch for all nodes, then the view open at the last exit
     app.fetch_all_metrics().await;
+    app.open_saved_view(saved_mode).await;
+
+    // Nothing configured and nothing on the default port: ask for a node
+    if app.app_config.first_run && !app.current_node().metrics.connected {
+        app.open_setup();
+    }
+
+    let job_control = suspend::JobControl::install();
+
+    // The screen is only redrawn after input, a resize or new data, plus
+    // once a second for relative times. While paused, only input and
+    // resizes redraw it.
+    let mut dirty = true;
+    let mut last_draw = Instant::now();
+    // The frame on screen while paused, for screenshots
+    let mut frozen_frame = None;
 
     loop {
         // Draw UI
-        terminal.draw(|frame| ui::draw(frame, app))?;
+        let clock_due = !app.paused && last_draw.elapsed() >= CLOCK_REDRAW_INTERVAL;
+        if dirty || clock_due {
+            let frame = terminal.draw(|frame| ui::draw(frame, app))?;
+            if app.screenshot_requested {
+                app.save_screenshot(frame.buffer);
+            }
+            frozen_frame = app.paused.then(|| frame.buffer.clone());
+            dirty = false;
+            last_draw = Instant::now();
+        }
 
-        // Handle input with timeout for periodic refresh
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
+        // Sleep until input arrives, the next refresh is due or the clock
+        // needs redrawing (never while paused)
+        let mut timeout = app.until_next_tick();
+        if !app.paused {
+            timeout = timeout.min(CLOCK_REDRAW_INTERVAL.saturating_sub(last_draw.elapsed()));
+        }
+        if event::poll(timeout)? {
+            let event = event::read()?;
+            dirty = true;
+            if let Event::Key(key) = event {
                 if key.kind == KeyEventKind::Press {
+                    // Ctrl+Z suspends from any mode
+                    if key.code == KeyCode::Char('z')
+                        && key.modifiers.contains(KeyModifiers::CONTROL)
+                    {
+                        job_control.suspend(terminal)?;
+                        continue;
+                    }
+
+                    // Bindings can change on config reload
+                    let keys = app.app_config.keys.clone();
+
+                    // Screenshots work in any view except while typing
+                    let typing = matches!(app.mode, AppMode::Command | AppMode::Setup)
+                        || (app.mode == AppMode::AddNode
+                            && app.node_form.step != FormStep::ConfirmSave);
+                    if !typing && keys.is(&key, Action::Screenshot) {
+                        // While paused, capture what is on screen rather than
+                        // a redraw with newer data
+                        match &frozen_frame {
+                            Some(frame) => app.save_screenshot(frame),
+                            None => app.screenshot_requested = true,
+                        }
+                        continue;
+                    }
+
                     // In help mode, any key closes help
                     if app.mode == AppMode::Help {
                         app.toggle_help();
@@ -97,12 +317,11 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
                     // In peer detail mode, handle specific keys
                     if app.mode == AppMode::PeerDetail {
                         match key.code {
-                            KeyCode::Char('q') | KeyCode::Esc => {
-                                app.toggle_peers().await;
-                            }
-                            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('p') => {
-                                app.back_to_peer_list();
-                            }
+                            _ if keys.is(&key, Action::Ban) => app.toggle_ban_selected_peer(),
+                            KeyCode::Backspace | KeyCode::Left => app.back_to_peer_list(),
+                            _ if keys.is(&key, Action::Copy) => app.copy_to_clipboard(),
+                            _ if keys.is(&key, Action::Peers) => app.back_to_peer_list(),
+                            _ if keys.closes(&key, Action::Quit) => app.toggle_peers().await,
                             _ => {}
                         }
                         continue;
@@ -111,16 +330,13 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
                     // In peers mode, handle specific keys
                     if app.mode == AppMode::Peers {
                         match key.code {
-                            KeyCode::Char('q') | KeyCode::Esc => {
-                                app.toggle_peers().await;
-                            }
-                            KeyCode::Char('p') => {
-                                app.toggle_peers().await;
-                            }
-                            KeyCode::Char('r') => app.refresh_peers().await,
+                            _ if keys.is(&key, Action::Refresh) => app.refresh_peers(),
+                            _ if keys.is(&key, Action::Graphs) => app.cycle_peer_grouping(),
                             KeyCode::Up | KeyCode::Char('k') => app.peer_list_up(20),
                             KeyCode::Down | KeyCode::Char('j') => app.peer_list_down(20),
                             KeyCode::Enter | KeyCode::Right => app.show_peer_detail(),
+                            _ if keys.is(&key, Action::Copy) => app.copy_to_clipboard(),
+                            _ if keys.closes(&key, Action::Peers) => app.toggle_peers().await,
                             _ => {}
                         }
                         continue;
@@ -128,59 +344,392 @@ async fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Resul
 
                     // In graphs mode, handle specific keys
                     if app.mode == AppMode::Graphs {
+                        if keys.closes(&key, Action::Graphs) {
+                            app.toggle_graphs();
+                        }
+                        continue;
+                    }
+
+                    // In epoch summary mode, handle specific keys
+                    if app.mode == AppMode::EpochSummary {
+                        if keys.closes(&key, Action::Epochs) {
+                            app.toggle_epoch_summary();
+                        }
+                        continue;
+                    }
+
+                    // In incidents mode, handle specific keys
+                    if app.mode == AppMode::Incidents {
+                        if keys.closes(&key, Action::Incidents) {
+                            app.toggle_incidents();
+                        }
+                        continue;
+                    }
+
+                    // In add-node mode, keys go to the form
+                    if app.mode == AppMode::AddNode {
+                        if app.node_form.step == FormStep::ConfirmSave {
+                            match key.code {
+                                KeyCode::Char('y') | KeyCode::Char('Y') => app.save_new_node(),
+                                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                                    app.close_node_form();
+                                }
+                                _ => {}
+                            }
+                            continue;
+                        }
+                        match key.code {
+                            KeyCode::Esc => app.close_node_form(),
+                            KeyCode::Enter => app.submit_node_form().await,
+                            code => edit_node_form(app, code),
+                        }
+                        continue;
+                    }
+
+                    // First-run setup uses the same form
+                    if app.mode == AppMode::Setup {
+                        match key.code {
+                            KeyCode::Esc => app.close_setup(),
+                            KeyCode::Enter => app.submit_setup().await,
+                            code => edit_node_form(app, code),
+                        }
+                        continue;
+                    }
+
+                    // In benchmark mode, handle specific keys
+                    if app.mode == AppMode::Benchmark {
                         match key.code {
-                            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('g') => {
-                                app.toggle_graphs();
+                            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char('+') => {
+                                app.benchmark_window_longer();
+                            }
+                            KeyCode::Left | KeyCode::Char('h') | KeyCode::Char('-') => {
+                                app.benchmark_window_shorter();
                             }
+                            _ if keys.closes(&key, Action::Compare) => app.toggle_benchmark(),
                             _ => {}
                         }
                         continue;
                     }
 
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
-                        KeyCode::Char('r') => app.fetch_all_metrics().await,
-                        KeyCode::Char('?') => app.toggle_help(),
-                        KeyCode::Char('t') => app.cycle_theme(),
-                        KeyCode::Char('p') => app.toggle_peers().await,
-                        KeyCode::Char('g') => app.toggle_graphs(),
-
-                        // Node switching
-                        KeyCode::Tab if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                            app.prev_node();
+                    // In settings mode, handle specific keys
+                    if app.mode == AppMode::Settings {
+                        match key.code {
+                            _ if keys.is(&key, Action::SaveSettings) => app.save_settings(),
+                            KeyCode::Up | KeyCode::Char('k') => app.settings_up(),
+                            KeyCode::Down | KeyCode::Char('j') => app.settings_down(),
+                            KeyCode::Right | KeyCode::Char('+') | KeyCode::Char('l') => {
+                                app.settings_adjust(1);
+                            }
+                            KeyCode::Left | KeyCode::Char('-') | KeyCode::Char('h') => {
+                                app.settings_adjust(-1);
+                            }
+                            _ if keys.closes(&key, Action::Settings) => app.toggle_settings(),
+                            _ => {}
                         }
-                        KeyCode::Tab => {
-                            app.next_node();
+                        continue;
+                    }
+
+                    // In propagation mode, handle specific keys
+                    if app.mode == AppMode::Propagation {
+                        match key.code {
+                            KeyCode::Up | KeyCode::Char('k') => app.propagation_scroll_up(),
+                            KeyCode::Down | KeyCode::Char('j') => app.propagation_scroll_down(),
+                            _ if keys.closes(&key, Action::Propagation) => {
+                                app.toggle_propagation();
+                            }
+                            _ => {}
                         }
-                        KeyCode::BackTab => {
-                            app.prev_node();
+                        continue;
+                    }
+
+                    // In focus mode, switch or scroll the expanded panel
+                    if app.mode == AppMode::Focus {
+                        match key.code {
+                            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => {
+                                app.focus_cycle(1);
+                            }
+                            KeyCode::Left | KeyCode::Char('h') | KeyCode::BackTab => {
+                                app.focus_cycle(-1);
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => app.focus_scroll_up(),
+                            KeyCode::Down | KeyCode::Char('j') => app.focus_scroll_down(),
+                            _ if keys.closes(&key, Action::Focus) => app.toggle_focus(),
+                            _ if keys.is(&key, Action::Pause) => app.toggle_pause(),
+                            _ => {}
                         }
+                        continue;
+                    }
 
-                        // Number keys to select nodes directly (1-9)
-                        KeyCode::Char(c) if c.is_ascii_digit() && c != '0' => {
-                            let index = (c as usize) - ('1' as usize);
-                            app.select_node(index);
+                    // On the command line, keys edit the command
+                    if app.mode == AppMode::Command {
+                        match key.code {
+                            KeyCode::Esc => app.close_command_line(),
+                            KeyCode::Enter => {
+                                let quit = app.run_command();
+                                if quit {
+                                    return Ok(());
+                                }
+                            }
+                            KeyCode::Backspace => app.command_backspace(),
+                            KeyCode::Char(c) => app.command_input.push(c),
+                            _ => {}
                         }
+                        continue;
+                    }
 
-                        // Left/Right arrow keys for node switching
-                        KeyCode::Left => {
-                            app.prev_node();
+                    // In mempool mode, handle specific keys
+                    if app.mode == AppMode::Mempool {
+                        match key.code {
+                            _ if keys.is(&key, Action::Refresh) => {
+                                app.current_node_mut().refresh_mempool().await
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => app.mempool_scroll_up(),
+                            KeyCode::Down | KeyCode::Char('j') => app.mempool_scroll_down(),
+                            _ if keys.closes(&key, Action::Mempool) => app.toggle_mempool().await,
+                            _ => {}
                         }
-                        KeyCode::Right => {
-                            app.next_node();
+                        continue;
+                    }
+
+                    // In metric info mode, step through the rows or close
+                    if app.mode == AppMode::MetricInfo {
+                        match key.code {
+                            KeyCode::Up | KeyCode::Down => {
+                                let step = if key.code == KeyCode::Up { -1 } else { 1 };
+                                app.select_metric(&ui::metric_labels(app), step);
+                            }
+                            KeyCode::Enter | KeyCode::Backspace => app.toggle_metric_info(),
+                            _ if keys.closes(&key, Action::Quit) => app.toggle_metric_info(),
+                            _ => {}
                         }
+                        continue;
+                    }
 
+                    // Unbound arrows select a metric row; Enter and Esc then
+                    // explain it or clear the selection instead of their actions
+                    match key.code {
+                        KeyCode::Up | KeyCode::Down if keys.action(&key).is_none() => {
+                            let step = if key.code == KeyCode::Up { -1 } else { 1 };
+                            app.select_metric(&ui::metric_labels(app), step);
+                            continue;
+                        }
+                        KeyCode::Enter if app.selected_metric.is_some() => {
+                            app.toggle_metric_info();
+                            continue;
+                        }
+                        KeyCode::Esc if app.selected_metric.is_some() => {
+                            app.clear_metric_selection();
+                            continue;
+                        }
                         _ => {}
                     }
+
+                    match keys.action(&key) {
+                        Some(Action::Quit) => return Ok(()),
+                        Some(Action::Refresh) => app.fetch_all_metrics().await,
+                        Some(Action::Help) => app.toggle_help(),
+                        Some(Action::Theme) => app.cycle_theme(),
+                        Some(Action::Peers) => app.toggle_peers().await,
+                        Some(Action::Graphs) => app.toggle_graphs(),
+                        Some(Action::Mempool) => app.toggle_mempool().await,
+                        Some(Action::Propagation) => app.toggle_propagation(),
+                        Some(Action::Epochs) => app.toggle_epoch_summary(),
+                        Some(Action::Incidents) => app.toggle_incidents(),
+                        Some(Action::Settings) => app.toggle_settings(),
+                        Some(Action::Compare) => app.toggle_benchmark(),
+                        Some(Action::ReloadConfig) => app.reload_config(),
+                        Some(Action::AddNode) => app.open_node_form(),
+                        Some(Action::CommandLine) => app.open_command_line(),
+                        Some(Action::Pause) => app.toggle_pause(),
+                        Some(Action::Copy) => app.copy_to_clipboard(),
+                        Some(Action::Focus) => app.toggle_focus(),
+                        // Handled before the view-specific keys
+                        Some(Action::Screenshot) => {}
+                        Some(Action::NextNode) => app.next_node(),
+                        Some(Action::PrevNode) => app.prev_node(),
+                        Some(Action::ToggleGroup) => app.toggle_group(),
+                        // Only matched inside their overlays
+                        Some(Action::Ban | Action::SaveSettings) => {}
+                        None => {
+                            // Number keys to select nodes directly (1-9)
+                            if let KeyCode::Char(c @ '1'..='9') = key.code {
+                                app.select_node((c as usize) - ('1' as usize));
+                            }
+                        }
+                    }
                 }
             }
         }
 
+        // Suspend/resume requested by signals from outside the TUI
+        if job_control.take_stop() {
+            job_control.suspend(terminal)?;
+            dirty = true;
+        } else if job_control.take_continued() {
+            suspend::resume(terminal)?;
+            dirty = true;
+        }
+
         // Periodic refresh
-        app.tick().await;
+        if app.tick().await && !app.paused {
+            dirty = true;
+        }
     }
 }
 
+/// Write a completion script or the man page, generated from `CliArgs`, to stdout
+fn print_cli_docs(command: &Command) -> Result<()> {
+    let mut cli = CliArgs::command();
+    let mut stdout = io::stdout();
+    match command {
+        Command::Completions { shell } => {
+            clap_complete::generate(*shell, &mut cli, "sview", &mut stdout);
+        }
+        Command::Man => clap_mangen::Man::new(cli).render(&mut stdout)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Move between and type into the fields of the node form
+fn edit_node_form(app: &mut App, code: KeyCode) {
+    match code {
+        KeyCode::Tab | KeyCode::Down => app.node_form.focus_next(),
+        KeyCode::BackTab | KeyCode::Up => app.node_form.focus_prev(),
+        KeyCode::Backspace => app.node_form.backspace(),
+        KeyCode::Left | KeyCode::Right if app.node_form.focused_field() == FormField::Role => {
+            app.node_form.toggle_role();
+        }
+        KeyCode::Char(c) => app.node_form.input(c),
+        _ => {}
+    }
+}
+
+/// Backfill one node's history from an external source
+async fn import_history(app_config: &AppConfig, request: &import::ImportRequest) -> Result<()> {
+    let node = match &request.node {
+        Some(name) => app_config
+            .nodes
+            .iter()
+            .find(|n| &n.name == name)
+            .ok_or_else(|| anyhow::anyhow!("No node named '{}' in the configuration", name))?,
+        None => &app_config.nodes[0],
+    };
+
+    let retention = Duration::from_secs(node.retention.retention_days * 86400);
+    let range = if request.range > retention {
+        println!(
+            "Limiting import to the {}-day retention period of '{}'",
+            node.retention.retention_days, node.name
+        );
+        retention
+    } else {
+        request.range
+    };
+
+    println!("Importing history for '{}' from {}", node.name, request.url);
+    let storage = StorageManager::new(&node.name).with_policy(node.retention);
+    let stored = match request.source {
+        import::ImportSource::Prometheus => {
+            import::import_prometheus(
+                &request.url,
+                request.selector.as_deref(),
+                range,
+                &storage,
+                app_config.timezone,
+            )
+            .await?
+        }
+    };
+    println!("Stored {} samples", stored);
+    Ok(())
+}
+
+/// Remove history and alerts beyond each node's retention period
+fn prune_history(app_config: &AppConfig) -> Result<()> {
+    for node in &app_config.nodes {
+        let storage = StorageManager::new(&node.name).with_policy(node.retention);
+        let before = storage.history_size();
+        match storage.cleanup_old_data() {
+            Ok(removed) => println!(
+                "  [{}] Removed {} file(s) older than {} days, history {} -> {} KB",
+                node.name,
+                removed,
+                node.retention.retention_days,
+                before / 1024,
+                storage.history_size() / 1024
+            ),
+            Err(e) => eprintln!("  [{}] Prune failed: {}", node.name, e),
+        }
+    }
+    Ok(())
+}
+
+/// Print alert counts by node and type for the last `period`, compared
+/// with the period before it
+fn alert_report(app_config: &AppConfig, period: Duration) -> Result<()> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let period_secs = period.as_secs();
+    let days = period_secs as f64 / 86400.0;
+    println!(
+        "Alert report: last {} (previous period in PREV)",
+        if period_secs.is_multiple_of(86400) {
+            format!("{} day(s)", period_secs / 86400)
+        } else {
+            format!("{} hour(s)", period_secs / 3600)
+        }
+    );
+
+    let mut total = 0;
+    for node in &app_config.nodes {
+        let storage = StorageManager::new(&node.name);
+        let alerts = match storage.load_alerts(now.saturating_sub(2 * period_secs)) {
+            Ok(alerts) => alerts,
+            Err(e) => {
+                eprintln!("  [{}] Failed to load alerts: {}", node.name, e);
+                continue;
+            }
+        };
+        let counts = alerts::summarize_alerts(&alerts, now, period_secs);
+        let node_total: usize = counts.iter().map(|c| c.count).sum();
+        let node_previous: usize = counts.iter().map(|c| c.previous).sum();
+        total += node_total;
+
+        println!();
+        println!(
+            "[{}] {} alert(s), {} previous period ({:.1}/day)",
+            node.name,
+            node_total,
+            node_previous,
+            node_total as f64 / days
+        );
+        if counts.is_empty() {
+            continue;
+        }
+        println!(
+            "  {:<28} {:<4} {:>6} {:>6}  LAST SEEN",
+            "ALERT", "SEV", "COUNT", "PREV"
+        );
+        for count in counts {
+            println!(
+                "  {:<28} {:<4} {:>6} {:>6}  {}",
+                count.title,
+                count.severity,
+                count.count,
+                count.previous,
+                app_config.timezone.datetime(count.last_seen)
+            );
+        }
+    }
+
+    println!();
+    println!("Total: {} alert(s)", total);
+    Ok(())
+}
+
 /// Export historical metrics to CSV file
 fn export_metrics(app_config: &AppConfig, export_path: &std::path::Path) -> Result<()> {
     use std::path::PathBuf;
@@ -212,7 +761,7 @@ fn export_metrics(app_config: &AppConfig, export_path: &std::path::Path) -> Resu
             export_path.to_path_buf()
         };
 
-        match storage.export_to_csv(&output_path) {
+        match storage.export_to_csv(&output_path, app_config.timezone) {
             Ok(count) => {
                 println!(
                     "  [{}] Exported {} snapshots to {}",
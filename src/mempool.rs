@@ -0,0 +1,203 @@
+//! Mempool inspection via the LocalTxMonitor mini-protocol
+//!
+//! Prometheus only exposes the mempool tx count and byte total. When a node
+//! socket is configured, this module acquires a mempool snapshot from the node
+//! and lists the individual transactions (hash, size, fee) for diagnosing
+//! congestion.
+
+use crate::cbor::{self, Decoder, Value};
+use crate::node_socket::NodeConnection;
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+use std::time::Duration;
+
+/// LocalTxMonitor mini-protocol number
+const PROTOCOL_TX_MONITOR: u16 = 9;
+
+/// Maximum number of transactions fetched per snapshot
+pub const MEMPOOL_TX_LIMIT: usize = 500;
+
+/// Overall time budget for one mempool query
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A transaction currently in the mempool
+#[derive(Debug, Clone, PartialEq)]
+pub struct MempoolTx {
+    /// Transaction ID (hex blake2b-256 of the tx body)
+    pub tx_hash: String,
+    /// Serialized transaction size in bytes
+    pub size: usize,
+    /// Fee in lovelace (if the body could be parsed)
+    pub fee: Option<u64>,
+}
+
+impl MempoolTx {
+    /// Fee per byte in lovelace
+    pub fn fee_per_byte(&self) -> Option<f64> {
+        match self.fee {
+            Some(fee) if self.size > 0 => Some(fee as f64 / self.size as f64),
+            _ => None,
+        }
+    }
+}
+
+/// Snapshot of the node's mempool
+#[derive(Debug, Clone, Default)]
+pub struct MempoolSnapshot {
+    /// Slot at which the snapshot was acquired
+    pub slot: u64,
+    /// Mempool capacity in bytes
+    pub capacity_bytes: Option<u64>,
+    /// Current mempool size in bytes
+    pub size_bytes: Option<u64>,
+    /// Number of transactions in the mempool
+    pub tx_count: Option<u64>,
+    /// Transactions in mempool order
+    pub txs: Vec<MempoolTx>,
+    /// True if more transactions exist than were fetched
+    pub truncated: bool,
+}
+
+/// Query the node's mempool over its local socket
+pub async fn query_mempool(socket_path: &Path, magic: u64) -> Result<MempoolSnapshot> {
+    tokio::time::timeout(QUERY_TIMEOUT, query_mempool_inner(socket_path, magic))
+        .await
+        .context("Mempool query timed out")?
+}
+
+async fn query_mempool_inner(socket_path: &Path, magic: u64) -> Result<MempoolSnapshot> {
+    let mut conn = NodeConnection::connect(socket_path, magic).await?;
+
+    // MsgAcquire -> MsgAcquired [2, slot]
+    send_simple(&mut conn, 1).await?;
+    let reply = expect_reply(&mut conn, 2).await?;
+    let slot = reply.get(1).and_then(Value::as_u64).unwrap_or(0);
+
+    let mut snapshot = MempoolSnapshot {
+        slot,
+        ..Default::default()
+    };
+
+    // MsgGetSizes -> MsgReplyGetSizes [10, [capacity, size, count]]
+    send_simple(&mut conn, 9).await?;
+    let reply = expect_reply(&mut conn, 10).await?;
+    if let Some(sizes) = reply.get(1).and_then(Value::as_array) {
+        snapshot.capacity_bytes = sizes.first().and_then(Value::as_u64);
+        snapshot.size_bytes = sizes.get(1).and_then(Value::as_u64);
+        snapshot.tx_count = sizes.get(2).and_then(Value::as_u64);
+    }
+
+    // MsgNextTx -> MsgReplyNextTx [6] (no more) or [6, tx]
+    loop {
+        if snapshot.txs.len() >= MEMPOOL_TX_LIMIT {
+            snapshot.truncated = true;
+            break;
+        }
+        send_simple(&mut conn, 5).await?;
+        let reply = expect_reply(&mut conn, 6).await?;
+        match reply.get(1) {
+            Some(tx) => snapshot.txs.push(parse_mempool_tx(tx)?),
+            None => break,
+        }
+    }
+
+    // MsgRelease, then MsgDone
+    send_simple(&mut conn, 3).await?;
+    let _ = send_simple(&mut conn, 0).await;
+
+    Ok(snapshot)
+}
+
+/// Send a message consisting of just its tag, e.g. `[5]`
+async fn send_simple(conn: &mut NodeConnection, tag: u64) -> Result<()> {
+    let mut msg = Vec::new();
+    cbor::encode_array(&mut msg, 1);
+    cbor::encode_uint(&mut msg, tag);
+    conn.send(PROTOCOL_TX_MONITOR, &msg).await
+}
+
+/// Receive a message and check it carries the expected tag
+async fn expect_reply(conn: &mut NodeConnection, tag: u64) -> Result<Vec<Value>> {
+    let reply = conn.recv(PROTOCOL_TX_MONITOR).await?;
+    match reply {
+        Value::Array(items) if items.first().and_then(Value::as_u64) == Some(tag) => Ok(items),
+        other => bail!("Unexpected LocalTxMonitor reply: {:?}", other),
+    }
+}
+
+/// Parse a hard-fork-wrapped transaction: `[era, #6.24(bytes .cbor tx)]`
+fn parse_mempool_tx(value: &Value) -> Result<MempoolTx> {
+    let tx_bytes = match value.as_array() {
+        Some([_era, Value::Tag(24, inner)]) => inner.as_bytes(),
+        _ => None,
+    }
+    .context("Unexpected transaction encoding")?;
+
+    let (tx_hash, fee) = parse_tx(tx_bytes)?;
+    Ok(MempoolTx {
+        tx_hash,
+        size: tx_bytes.len(),
+        fee,
+    })
+}
+
+/// Extract the tx ID and fee from a serialized transaction `[body, witnesses, ...]`
+fn parse_tx(tx_bytes: &[u8]) -> Result<(String, Option<u64>)> {
+    let mut decoder = Decoder::new(tx_bytes);
+    decoder.array().context("Transaction is not an array")?;
+
+    // The tx ID is the hash of the body exactly as serialized
+    let start = decoder.position();
+    let body = decoder
+        .value()
+        .context("Failed to decode transaction body")?;
+    let body_bytes = &tx_bytes[start..decoder.position()];
+
+    let hash = blake2b_simd::Params::new().hash_length(32).hash(body_bytes);
+    let fee = body.map_get(2).and_then(Value::as_u64);
+
+    Ok((hex_encode(hash.as_bytes()), fee))
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal tx: [{0: [], 1: [], 2: 170000}, {}, true, null]
+    fn sample_tx() -> Vec<u8> {
+        vec![
+            0x84, 0xa3, 0x00, 0x80, 0x01, 0x80, 0x02, 0x1a, 0x00, 0x02, 0x98, 0x10, 0xa0, 0xf5,
+            0xf6,
+        ]
+    }
+
+    #[test]
+    fn test_parse_tx_fee_and_hash() {
+        let tx = sample_tx();
+        let (hash, fee) = parse_tx(&tx).unwrap();
+        assert_eq!(fee, Some(170000));
+
+        let expected = blake2b_simd::Params::new().hash_length(32).hash(&tx[1..12]);
+        assert_eq!(hash, hex_encode(expected.as_bytes()));
+        assert_eq!(hash.len(), 64);
+    }
+
+    #[test]
+    fn test_parse_mempool_tx_wrapper() {
+        let tx = sample_tx();
+        let wrapped = Value::Array(vec![
+            Value::Uint(6),
+            Value::Tag(24, Box::new(Value::Bytes(tx.clone()))),
+        ]);
+        let parsed = parse_mempool_tx(&wrapped).unwrap();
+        assert_eq!(parsed.size, tx.len());
+        assert_eq!(parsed.fee, Some(170000));
+        assert!(parsed.fee_per_byte().unwrap() > 10000.0);
+
+        assert!(parse_mempool_tx(&Value::Uint(1)).is_err());
+    }
+}
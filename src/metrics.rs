@@ -3,13 +3,24 @@
 //! This module handles connecting to a Cardano node's Prometheus endpoint
 //! and parsing the metrics into structured data.
 
-use anyhow::Result;
+use crate::config::{EndpointOptions, PanelKind};
+use crate::demo::{DemoNode, DemoProfile};
+use anyhow::{Context, Result};
+use reqwest::header::{HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hasher};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::debug;
 
 /// P2P (peer-to-peer) network statistics
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct P2PStats {
     /// Whether P2P is enabled on this node
     pub enabled: Option<bool>,
@@ -19,10 +30,114 @@ pub struct P2PStats {
     pub warm_peers: Option<u64>,
     /// Number of hot peers (actively used)
     pub hot_peers: Option<u64>,
+    /// Peer selection governor targets (where the node exports them)
+    pub targets: PeerTargets,
+    /// Big-ledger, bootstrap and shared peers and churn (newer nodes)
+    pub governance: PeerGovernance,
+}
+
+/// Known, established and active counts for one group of peers
+///
+/// Cumulative like `PeerTargets`: established peers are also known ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerGroupCounts {
+    pub known: Option<u64>,
+    pub established: Option<u64>,
+    pub active: Option<u64>,
+}
+
+impl PeerGroupCounts {
+    /// Whether any count is set
+    pub fn is_set(&self) -> bool {
+        self.known.is_some() || self.established.is_some() || self.active.is_some()
+    }
+}
+
+/// Peers added and dropped by the churn governor's last cycles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChurnCounts {
+    pub increased: Option<u64>,
+    pub decreased: Option<u64>,
+}
+
+impl ChurnCounts {
+    /// Whether either count is set
+    pub fn is_set(&self) -> bool {
+        self.increased.is_some() || self.decreased.is_some()
+    }
+}
+
+/// Peer selection governor detail exported by recent ouroboros-network
+/// versions (`peerSelection_*BigLedgerPeers`, `*BootstrapPeers`,
+/// `*SharedPeers`, `PeerSharing*` and `peerSelection_churn_*`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerGovernance {
+    /// Peers taken from the ledger's largest stake pools
+    pub big_ledger: PeerGroupCounts,
+    /// Trusted bootstrap peers (used while the node syncs in Genesis mode)
+    pub bootstrap: PeerGroupCounts,
+    /// Peers learned from other nodes through peer sharing
+    pub shared: PeerGroupCounts,
+    /// Peers asked for in peer sharing requests to other nodes
+    pub sharing_requested: Option<u64>,
+    /// Peers received in answer to peer sharing requests
+    pub sharing_received: Option<u64>,
+    /// Churn of known peers (big-ledger ones included, as for the others)
+    pub churn_known: ChurnCounts,
+    /// Churn of established peers
+    pub churn_established: ChurnCounts,
+    /// Churn of active (hot) peers
+    pub churn_active: ChurnCounts,
+}
+
+impl PeerGovernance {
+    /// Whether the node exports any of these metrics
+    pub fn is_set(&self) -> bool {
+        self.big_ledger.is_set()
+            || self.bootstrap.is_set()
+            || self.shared.is_set()
+            || self.sharing_requested.is_some()
+            || self.sharing_received.is_some()
+            || self.churn_known.is_set()
+            || self.churn_established.is_set()
+            || self.churn_active.is_set()
+    }
+}
+
+/// Peer selection governor targets, as in the node config's
+/// `TargetNumberOf*Peers` settings
+///
+/// Targets are cumulative: known peers include established ones, which
+/// include active (hot) ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerTargets {
+    pub known: Option<u64>,
+    pub established: Option<u64>,
+    pub active: Option<u64>,
+}
+
+impl PeerTargets {
+    /// Whether any target is set
+    pub fn is_set(&self) -> bool {
+        self.known.is_some() || self.established.is_some() || self.active.is_some()
+    }
+
+    /// Targets per peer state: (hot, warm, cold)
+    pub fn by_state(&self) -> (Option<u64>, Option<u64>, Option<u64>) {
+        let warm = self
+            .established
+            .zip(self.active)
+            .map(|(e, a)| e.saturating_sub(a));
+        let cold = self
+            .known
+            .zip(self.established)
+            .map(|(k, e)| k.saturating_sub(e));
+        (self.active, warm, cold)
+    }
 }
 
 /// Detected node implementation type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum NodeType {
     #[default]
     CardanoNode,
@@ -31,6 +146,19 @@ pub enum NodeType {
     Unknown,
 }
 
+impl NodeType {
+    /// Dingo and Amaru metrics decide over the cardano_node_ compatibility
+    /// metrics they also export
+    fn precedence(self) -> u8 {
+        match self {
+            NodeType::Unknown => 0,
+            NodeType::CardanoNode => 1,
+            NodeType::Amaru => 2,
+            NodeType::Dingo => 3,
+        }
+    }
+}
+
 impl std::fmt::Display for NodeType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -43,7 +171,7 @@ impl std::fmt::Display for NodeType {
 }
 
 /// Build information from cardano_node_metrics_cardano_build_info
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BuildInfo {
     /// Full version string (e.g., "10.6.1")
     pub version: Option<String>,
@@ -78,7 +206,7 @@ impl BuildInfo {
 }
 
 /// Parsed metrics from a Cardano node (matches nview PromMetrics)
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NodeMetrics {
     /// Detected node type
     pub node_type: NodeType,
@@ -116,6 +244,8 @@ pub struct NodeMetrics {
     pub block_delay_s: Option<f64>,
     /// Blocks served
     pub blocks_served: Option<u64>,
+    /// Headers served to downstream peers (chain sync)
+    pub headers_served: Option<u64>,
     /// Blocks received late
     pub blocks_late: Option<u64>,
     /// Block delay CDF at 1s
@@ -130,10 +260,13 @@ pub struct NodeMetrics {
     pub uptime_seconds: Option<f64>,
     /// Sync progress percentage (0-100)
     pub sync_progress: Option<f64>,
+    /// Ledger replay progress percentage while the node starts (0-100)
+    pub replay_progress: Option<f64>,
     /// Whether we successfully connected to the node
     pub connected: bool,
-    /// Raw metrics for debugging/advanced display
-    pub raw: HashMap<String, f64>,
+    /// Raw metrics for custom rows and the focus view, limited to the
+    /// `raw_metrics` allowlist; known names are borrowed, not allocated
+    pub raw: HashMap<Cow<'static, str>, f64>,
     // KES (Key Evolving Signature) metrics - critical for block producers
     /// Current KES period
     pub kes_period: Option<u64>,
@@ -172,37 +305,459 @@ pub struct NodeMetrics {
     pub full_duplex_connections: Option<u64>,
     /// Unidirectional connections
     pub unidirectional_connections: Option<u64>,
+    /// Inbound connections the connection manager may prune when over its limits
+    pub prunable_connections: Option<u64>,
+    /// Connection manager error counters, summed (where exported)
+    pub connection_errors: Option<u64>,
+    /// Failed mini-protocol handshakes, summed (where exported)
+    pub handshake_failures: Option<u64>,
+}
+
+impl NodeMetrics {
+    /// Look up a raw metric by name, with or without the
+    /// `cardano_node_metrics_` prefix
+    pub fn raw_value(&self, name: &str) -> Option<f64> {
+        self.raw
+            .get(name)
+            .or_else(|| {
+                self.raw
+                    .get(format!("cardano_node_metrics_{}", name).as_str())
+            })
+            .copied()
+    }
+}
+
+/// Which metrics are kept in `NodeMetrics::raw` (`raw_metrics` in the config)
+///
+/// Patterns are exact names or prefixes ending in `*`; `"*"` keeps everything.
+/// Structured fields are filled in regardless.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawMetricFilter {
+    patterns: Vec<String>,
+}
+
+impl Default for RawMetricFilter {
+    fn default() -> Self {
+        Self::new(["*"])
+    }
+}
+
+impl RawMetricFilter {
+    pub fn new<S: Into<String>>(patterns: impl IntoIterator<Item = S>) -> Self {
+        Self {
+            patterns: patterns.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Also keep `name`, with or without the `cardano_node_metrics_` prefix
+    /// (for custom metric rows)
+    pub fn with_name(mut self, name: &str) -> Self {
+        if !self.retains(name) {
+            self.patterns.push(name.to_string());
+        }
+        let prefixed = format!("cardano_node_metrics_{}", name);
+        if !self.retains(&prefixed) {
+            self.patterns.push(prefixed);
+        }
+        self
+    }
+
+    /// Also keep each of `names`, as spelled (the metrics the explain popup
+    /// shows, so it doesn't report them as not exported)
+    pub fn with_exact<'a>(mut self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        for name in names {
+            if !self.retains(name) {
+                self.patterns.push(name.to_string());
+            }
+        }
+        self
+    }
+
+    /// Whether a metric is kept
+    pub fn retains(&self, name: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => name == pattern,
+            })
+    }
+}
+
+/// Why a scrape failed, so the UI can suggest a fix and alerts can tell
+/// a down node from a misconfigured endpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum FetchError {
+    #[error("DNS lookup failed for {0}")]
+    Dns(String),
+    #[error("connection refused")]
+    ConnectionRefused,
+    #[error("timed out")]
+    Timeout,
+    #[error("HTTP {0}")]
+    Http(u16),
+    /// The endpoint answered, but not with Prometheus text
+    #[error("not Prometheus metrics ({0})")]
+    Parse(String),
+    /// The SSH tunnel to the endpoint is down
+    #[error("{0}")]
+    Tunnel(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl FetchError {
+    /// What to check, for the footer and alerts
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            FetchError::Dns(_) => Some("check the host name"),
+            FetchError::ConnectionRefused => Some("port closed — is hasPrometheus enabled?"),
+            FetchError::Timeout => Some("no answer — check the address and firewall"),
+            FetchError::Http(401 | 403) => Some("check basic_auth or bearer_token"),
+            FetchError::Http(404) => Some("wrong path — the node serves /metrics"),
+            FetchError::Parse(_) => Some("wrong port? (Prometheus is 12798 by default)"),
+            _ => None,
+        }
+    }
+
+    /// Whether the node (or the host) didn't answer at all, as opposed to an
+    /// endpoint answering with something unusable
+    pub fn is_unreachable(&self) -> bool {
+        matches!(
+            self,
+            FetchError::Dns(_)
+                | FetchError::ConnectionRefused
+                | FetchError::Timeout
+                | FetchError::Tunnel(_)
+                | FetchError::Other(_)
+        )
+    }
+}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            return FetchError::Timeout;
+        }
+        if let Some(status) = e.status() {
+            return FetchError::Http(status.as_u16());
+        }
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+                match io.kind() {
+                    std::io::ErrorKind::ConnectionRefused => return FetchError::ConnectionRefused,
+                    std::io::ErrorKind::TimedOut => return FetchError::Timeout,
+                    _ => {}
+                }
+            }
+            let text = cause.to_string();
+            if text.contains("dns error") || text.contains("failed to lookup address") {
+                let host = e.url().and_then(|u| u.host_str()).unwrap_or_default();
+                return FetchError::Dns(host.to_string());
+            }
+            source = cause.source();
+        }
+        FetchError::Other(format!("{:#}", anyhow::Error::from(e)))
+    }
+}
+
+/// A successful scrape
+#[derive(Debug, Clone)]
+pub enum Sample {
+    /// Metrics from a payload the node hasn't sent before
+    New(NodeMetrics),
+    /// The node answered 304 or repeated its last payload: it's reachable
+    /// but hasn't published anything new, so there is nothing to record
+    Unchanged(NodeMetrics),
+}
+
+impl Sample {
+    pub fn is_new(&self) -> bool {
+        matches!(self, Sample::New(_))
+    }
+
+    pub fn into_metrics(self) -> NodeMetrics {
+        match self {
+            Sample::New(metrics) | Sample::Unchanged(metrics) => metrics,
+        }
+    }
 }
 
 /// Metrics client for fetching Prometheus data
 pub struct MetricsClient {
     client: reqwest::Client,
     url: String,
+    /// Health endpoint, polled to tell "metrics down" from "node down"
+    health_url: Option<reqwest::Url>,
+    options: EndpointOptions,
+    /// Simulated node answering instead of `url` (`--demo`)
+    demo: Option<DemoNode>,
+    /// Followed sview and the node of its `/state` this client reads
+    /// (`--follow`)
+    followed: Option<(Arc<crate::follow::Leader>, String)>,
+    /// Last response, to skip parsing a payload that hasn't changed
+    cache: Mutex<ScrapeCache>,
+    /// Metrics kept in `NodeMetrics::raw`
+    raw_filter: RawMetricFilter,
+}
+
+/// The previous scrape of an endpoint
+///
+/// The node refreshes its metrics less often than sview may poll, and with
+/// histograms enabled the payload runs to hundreds of KB. An identical body
+/// (by hash), or a 304 answer to a conditional request when the endpoint
+/// sends validators, reuses the previous parse. The body buffer is kept so
+/// each scrape reads into already allocated memory.
+#[derive(Debug, Default)]
+struct ScrapeCache {
+    body: Vec<u8>,
+    hash: Option<u64>,
+    metrics: Option<NodeMetrics>,
+    etag: Option<HeaderValue>,
+    last_modified: Option<HeaderValue>,
+    /// Payloads parsed so far (the rest were unchanged)
+    parses: u64,
+}
+
+impl ScrapeCache {
+    /// Metrics for the payload in `body`, parsing it only if it changed
+    fn metrics(&mut self, filter: &RawMetricFilter) -> Result<NodeMetrics, FetchError> {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&self.body);
+        let hash = hasher.finish();
+        match &self.metrics {
+            Some(metrics) if self.hash == Some(hash) => Ok(metrics.clone()),
+            _ => {
+                let text = String::from_utf8_lossy(&self.body);
+                check_payload(&text)?;
+                let metrics = parse_prometheus_metrics_with(&text, filter);
+                self.hash = Some(hash);
+                self.metrics = Some(metrics.clone());
+                self.parses += 1;
+                Ok(metrics)
+            }
+        }
+    }
 }
 
 impl MetricsClient {
-    /// Create a new metrics client
-    pub fn new(url: String, timeout: Duration) -> Self {
-        let client = reqwest::Client::builder()
-            .timeout(timeout)
-            .build()
-            .expect("Failed to create HTTP client");
+    /// Create a new metrics client with the node's TLS and auth settings
+    ///
+    /// With `unix_socket`, every request is sent over that socket instead of TCP.
+    pub fn new(
+        url: String,
+        timeout: Duration,
+        options: &EndpointOptions,
+        unix_socket: Option<&Path>,
+    ) -> Result<Self> {
+        Self::build(url, timeout, options, |builder| match unix_socket {
+            Some(path) => builder.unix_socket(path),
+            None => builder,
+        })
+    }
 
-        Self { client, url }
+    /// A client connecting to `addr` (a tunnel's local end) for `host`,
+    /// which `url` names; TLS still verifies the certificate against `host`
+    pub fn through_tunnel(
+        url: String,
+        timeout: Duration,
+        options: &EndpointOptions,
+        host: &str,
+        addr: SocketAddr,
+    ) -> Result<Self> {
+        Self::build(url, timeout, options, |builder| builder.resolve(host, addr))
+    }
+
+    fn build(
+        url: String,
+        timeout: Duration,
+        options: &EndpointOptions,
+        route: impl FnOnce(reqwest::ClientBuilder) -> reqwest::ClientBuilder,
+    ) -> Result<Self> {
+        let mut builder = route(
+            reqwest::Client::builder()
+                .timeout(timeout)
+                .danger_accept_invalid_certs(options.insecure_skip_verify),
+        );
+
+        if let Some(ca_cert) = &options.ca_cert {
+            let pem = fs::read(ca_cert)
+                .with_context(|| format!("Failed to read CA certificate {}", ca_cert.display()))?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .with_context(|| format!("Invalid CA certificate {}", ca_cert.display()))?;
+            builder = builder.add_root_certificate(cert);
+        }
+        if let Some(proxy) = &options.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .with_context(|| format!("Invalid proxy URL '{}'", proxy))?;
+            builder = builder.proxy(proxy);
+        }
+
+        // A path is relative to the metrics endpoint, so it follows the same
+        // tunnel or socket
+        let health_url = match options.health_url.as_deref() {
+            Some(path) if path.starts_with('/') => Some(reqwest::Url::parse(&url)?.join(path)?),
+            Some(health_url) => Some(
+                reqwest::Url::parse(health_url)
+                    .with_context(|| format!("Invalid health_url '{}'", health_url))?,
+            ),
+            None => None,
+        };
+
+        let client = builder.build().context("Failed to create HTTP client")?;
+        Ok(Self {
+            client,
+            url,
+            health_url,
+            options: options.clone(),
+            demo: None,
+            followed: None,
+            cache: Mutex::default(),
+            raw_filter: RawMetricFilter::default(),
+        })
+    }
+
+    /// A client reading the node `name` from the sview at `leader`
+    pub fn follow(leader: &str, name: &str, timeout: Duration) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            url: crate::follow::state_url(leader),
+            health_url: None,
+            options: EndpointOptions::default(),
+            demo: None,
+            followed: Some((
+                crate::follow::Leader::get(leader, timeout)?,
+                name.to_string(),
+            )),
+            cache: Mutex::default(),
+            raw_filter: RawMetricFilter::default(),
+        })
+    }
+
+    /// A client whose scrapes are answered by a simulated node
+    pub fn demo(profile: DemoProfile) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: String::new(),
+            health_url: None,
+            options: EndpointOptions::default(),
+            demo: Some(DemoNode::new(profile)),
+            followed: None,
+            cache: Mutex::default(),
+            raw_filter: RawMetricFilter::default(),
+        }
+    }
+
+    /// Keep only the metrics `filter` allows in `NodeMetrics::raw`
+    pub fn with_raw_filter(mut self, filter: RawMetricFilter) -> Self {
+        self.raw_filter = filter;
+        self
+    }
+
+    /// Whether a health endpoint is configured
+    pub fn has_health_check(&self) -> bool {
+        self.health_url.is_some()
+    }
+
+    /// Poll the health endpoint; any 2xx response means the node is up
+    pub async fn check_health(&self) -> Result<()> {
+        let Some(url) = &self.health_url else {
+            anyhow::bail!("No health endpoint configured");
+        };
+        self.authorize(self.client.get(url.clone()))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Add the configured credentials to a request
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if let Some(auth) = &self.options.basic_auth {
+            request.basic_auth(&auth.user, Some(&auth.pass))
+        } else if let Some(token) = &self.options.bearer_token {
+            request.bearer_auth(token)
+        } else {
+            request
+        }
     }
 
     /// Fetch and parse metrics from the node
-    pub async fn fetch(&self) -> Result<NodeMetrics> {
-        let response = self.client.get(&self.url).send().await?;
-        let text = response.text().await?;
-        Ok(parse_prometheus_metrics(&text))
+    pub async fn fetch(&self) -> Result<Sample, FetchError> {
+        if let Some(demo) = &self.demo {
+            let text = demo.scrape().map_err(|_| FetchError::ConnectionRefused)?;
+            return Ok(Sample::New(parse_prometheus_metrics_with(
+                &text,
+                &self.raw_filter,
+            )));
+        }
+        if let Some((leader, name)) = &self.followed {
+            return leader.state().await?.node_metrics(name).map(Sample::New);
+        }
+
+        // Take the buffer so the lock isn't held across the request
+        let (mut body, request) = {
+            let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            let mut request = self.authorize(self.client.get(&self.url));
+            if cache.metrics.is_some() {
+                if let Some(etag) = &cache.etag {
+                    request = request.header(IF_NONE_MATCH, etag.clone());
+                }
+                if let Some(modified) = &cache.last_modified {
+                    request = request.header(IF_MODIFIED_SINCE, modified.clone());
+                }
+            }
+            (std::mem::take(&mut cache.body), request)
+        };
+
+        let mut response = request.send().await?.error_for_status()?;
+        let not_modified = response.status() == StatusCode::NOT_MODIFIED;
+        let etag = response.headers().get(ETAG).cloned();
+        let last_modified = response.headers().get(LAST_MODIFIED).cloned();
+        body.clear();
+        if !not_modified {
+            while let Some(chunk) = response.chunk().await? {
+                body.extend_from_slice(&chunk);
+            }
+        }
+
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        if not_modified {
+            cache.body = body;
+            return cache
+                .metrics
+                .clone()
+                .map(Sample::Unchanged)
+                .ok_or_else(|| FetchError::Http(StatusCode::NOT_MODIFIED.as_u16()));
+        }
+        cache.body = body;
+        cache.etag = etag;
+        cache.last_modified = last_modified;
+        let parses = cache.parses;
+        let metrics = cache.metrics(&self.raw_filter)?;
+        if cache.parses == parses {
+            debug!("Metrics from {} unchanged, reusing last parse", self.url);
+            return Ok(Sample::Unchanged(metrics));
+        }
+        Ok(Sample::New(metrics))
     }
 }
 
+/// Parse Prometheus text format into NodeMetrics, keeping every raw metric
+pub fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
+    parse_prometheus_metrics_with(text, &RawMetricFilter::default())
+}
+
 /// Parse Prometheus text format into NodeMetrics
-fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
+///
+/// Works on slices of `text`; a raw entry is only allocated for a metric the
+/// filter keeps and that isn't in `KNOWN_METRICS`.
+#[allow(clippy::collapsible_match)]
+pub fn parse_prometheus_metrics_with(text: &str, filter: &RawMetricFilter) -> NodeMetrics {
     let mut metrics = NodeMetrics {
         connected: true,
+        node_type: NodeType::Unknown,
         ..Default::default()
     };
 
@@ -214,17 +769,32 @@ fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
 
         // Check for build_info metric (has labels with version info)
         // Metric format: metric_name {key="value",...} value
+        // cardano-node exports cardano_node_metrics_cardano_build_info; other
+        // implementations follow the <prefix>_build_info convention. Go's own
+        // go_build_info carries the toolchain's module version, not the node's.
         let trimmed = line.trim();
-        if trimmed.starts_with("cardano_node_metrics_cardano_build_info") && trimmed.contains('{') {
+        let metric_name = trimmed.split(['{', ' ']).next().unwrap_or_default();
+        if metric_name.ends_with("_build_info")
+            && !metric_name.starts_with("go_")
+            && trimmed.contains('{')
+        {
             if let Some(build_info) = parse_build_info_labels(trimmed) {
-                metrics.build_info = build_info;
+                if metrics.build_info.version.is_none() {
+                    metrics.build_info = build_info;
+                }
             }
             continue;
         }
 
         // Parse metric line: metric_name{labels} value
         if let Some((name, value)) = parse_metric_line(line) {
-            metrics.raw.insert(name.clone(), value);
+            let node_type = node_type_of(name);
+            if node_type.precedence() > metrics.node_type.precedence() {
+                metrics.node_type = node_type;
+            }
+            if filter.retains(name) {
+                metrics.raw.insert(intern(name), value);
+            }
 
             // Log interesting metrics for debugging
             if name.contains("Uptime")
@@ -239,7 +809,7 @@ fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
             }
 
             // Map known metrics to structured fields (matches nview PromMetrics names)
-            match name.as_str() {
+            match name {
                 // Block/Chain metrics
                 "cardano_node_metrics_blockNum_int" => {
                     metrics.block_height = Some(value as u64);
@@ -334,6 +904,13 @@ fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
                 | "cardano_node_metrics_served_block_count" => {
                     metrics.blocks_served = Some(value as u64);
                 }
+                // served.header: _counter_int (legacy) or _counter (new tracing)
+                "cardano_node_metrics_served_header_counter_int"
+                | "cardano_node_metrics_served_header_counter"
+                | "cardano_node_metrics_served_header_count_int"
+                | "cardano_node_metrics_served_header_count" => {
+                    metrics.headers_served = Some(value as u64);
+                }
                 // lateblocks is a counter - emitted when delay > 5s
                 "cardano_node_metrics_blockfetchclient_lateblocks"
                 | "cardano_node_metrics_blockfetchclient_lateblocks_int"
@@ -367,6 +944,11 @@ fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
                     // Convert nanoseconds to seconds
                     metrics.uptime_seconds = Some(value / 1_000_000_000.0);
                 }
+                // Reported as 0-100 while the ledger is replayed after a restart
+                "cardano_node_metrics_blockReplayProgress_real"
+                | "cardano_node_metrics_blockReplayProgress_double" => {
+                    metrics.replay_progress = Some(value.clamp(0.0, 100.0));
+                }
 
                 // Connection manager metrics (official names from nview)
                 "cardano_node_metrics_connectionManager_incomingConns" => {
@@ -381,6 +963,24 @@ fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
                 "cardano_node_metrics_connectionManager_unidirectionalConns" => {
                     metrics.unidirectional_connections = Some(value as u64);
                 }
+                "cardano_node_metrics_connectionManager_prunableConns"
+                | "cardano_node_metrics_connectionManager_prunableConns_int" => {
+                    metrics.prunable_connections = Some(value as u64);
+                }
+                // cardano-node itself doesn't export these error counters;
+                // the implementations and exporters that do are summed
+                "cardano_node_metrics_connectionManager_connectionErrors"
+                | "cardano_node_metrics_connectionManager_connectionErrors_counter"
+                | "cardano_node_metrics_connectionManager_handlerErrors"
+                | "cardano_node_metrics_connectionManager_handlerErrors_counter" => {
+                    metrics.connection_errors =
+                        Some(metrics.connection_errors.unwrap_or(0) + value as u64);
+                }
+                "cardano_node_metrics_handshake_failures"
+                | "cardano_node_metrics_handshake_failures_total" => {
+                    metrics.handshake_failures =
+                        Some(metrics.handshake_failures.unwrap_or(0) + value as u64);
+                }
                 // Legacy fullDuplexConns name for compatibility
                 "cardano_node_metrics_connectionManager_fullDuplexConns" => {
                     if metrics.full_duplex_connections.is_none() {
@@ -417,6 +1017,20 @@ fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
                     metrics.p2p.hot_peers = Some(value as u64);
                 }
 
+                // Peer selection targets (not exported by every node version)
+                "cardano_node_metrics_peerSelection_targetNumberOfKnownPeers"
+                | "cardano_node_metrics_peerSelection_TargetKnown_int" => {
+                    metrics.p2p.targets.known = Some(value as u64);
+                }
+                "cardano_node_metrics_peerSelection_targetNumberOfEstablishedPeers"
+                | "cardano_node_metrics_peerSelection_TargetEstablished_int" => {
+                    metrics.p2p.targets.established = Some(value as u64);
+                }
+                "cardano_node_metrics_peerSelection_targetNumberOfActivePeers"
+                | "cardano_node_metrics_peerSelection_TargetActive_int" => {
+                    metrics.p2p.targets.active = Some(value as u64);
+                }
+
                 // KES (Key Evolving Signature) metrics
                 "cardano_node_metrics_currentKESPeriod_int" => {
                     if value >= 0.0 && value.is_finite() {
@@ -476,7 +1090,9 @@ fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
 
                 // Log unrecognized cardano_node_metrics for debugging
                 other if other.starts_with("cardano_node_metrics_") => {
-                    debug!("Unrecognized metric: {} = {}", other, value);
+                    if !parse_peer_governance(&mut metrics.p2p.governance, other, value) {
+                        debug!("Unrecognized metric: {} = {}", other, value);
+                    }
                 }
 
                 _ => {}
@@ -484,9 +1100,6 @@ fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
         }
     }
 
-    // Detect node type based on available metrics
-    metrics.node_type = detect_node_type(&metrics.raw);
-
     // Calculate uptime from nodeStartTime if available
     if let Some(start_time) = metrics.node_start_time {
         let now = match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
@@ -562,31 +1175,50 @@ fn parse_prometheus_metrics(text: &str) -> NodeMetrics {
     }
 
     // Log available metrics if in debug mode
-    let available_metrics: Vec<&str> = metrics
-        .raw
-        .keys()
-        .filter(|k| {
-            k.contains("Uptime")
-                || k.contains("upTime")
-                || k.contains("cpu")
-                || k.contains("Mempool")
-                || k.contains("memory")
-                || k.contains("Memory")
-                || k.contains("connection")
-                || k.contains("Connection")
-        })
-        .map(|s| s.as_str())
-        .collect();
+    if tracing::enabled!(tracing::Level::DEBUG) {
+        let available_metrics: Vec<&str> = metrics
+            .raw
+            .keys()
+            .filter(|k| {
+                k.contains("Uptime")
+                    || k.contains("upTime")
+                    || k.contains("cpu")
+                    || k.contains("Mempool")
+                    || k.contains("memory")
+                    || k.contains("Memory")
+                    || k.contains("connection")
+                    || k.contains("Connection")
+            })
+            .map(|s| s.as_ref())
+            .collect();
 
-    if !available_metrics.is_empty() {
-        debug!("Available resource metrics: {:?}", available_metrics);
+        if !available_metrics.is_empty() {
+            debug!("Available resource metrics: {:?}", available_metrics);
+        }
     }
 
     metrics
 }
 
+/// Reject a payload whose first sample isn't a metric line (an HTML page,
+/// the EKG JSON endpoint, an empty body)
+fn check_payload(text: &str) -> Result<(), FetchError> {
+    let first = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'));
+    match first {
+        None => Err(FetchError::Parse("empty response".to_string())),
+        Some(line) if parse_metric_line(line).is_none() => Err(FetchError::Parse(format!(
+            "starts with '{}'",
+            line.chars().take(20).collect::<String>()
+        ))),
+        Some(_) => Ok(()),
+    }
+}
+
 /// Parse a single Prometheus metric line
-fn parse_metric_line(line: &str) -> Option<(String, f64)> {
+fn parse_metric_line(line: &str) -> Option<(&str, f64)> {
     // Handle lines with labels: metric_name{label="value"} 123.45
     // And simple lines: metric_name 123.45
 
@@ -594,7 +1226,7 @@ fn parse_metric_line(line: &str) -> Option<(String, f64)> {
 
     // Find the metric name (everything before '{' or ' ')
     let name_end = line.find('{').or_else(|| line.find(' '))?;
-    let name = line[..name_end].to_string();
+    let name = &line[..name_end];
 
     // Find the value (last space-separated element)
     let value_str = line.rsplit_once(' ')?.1;
@@ -612,6 +1244,7 @@ fn parse_build_info_labels(line: &str) -> Option<BuildInfo> {
     let labels_str = &line[start + 1..end];
 
     let mut build_info = BuildInfo::default();
+    let mut version_parts: [Option<&str>; 3] = [None; 3];
 
     // Parse each label: key="value"
     // Handle comma-separated labels, being careful with quoted values
@@ -627,11 +1260,24 @@ fn parse_build_info_labels(line: &str) -> Option<BuildInfo> {
                 "os_name" => build_info.os_name = Some(value.to_string()),
                 "compiler_name" => build_info.compiler_name = Some(value.to_string()),
                 "compiler_version" => build_info.compiler_version = Some(value.to_string()),
+                "version_major" => version_parts[0] = Some(value),
+                "version_minor" => version_parts[1] = Some(value),
+                "version_patch" => version_parts[2] = Some(value),
                 _ => {} // Ignore other labels
             }
         }
     }
 
+    // Some builds only label the numeric components
+    if build_info.version.is_none() {
+        if let [Some(major), Some(minor), patch] = version_parts {
+            build_info.version = Some(match patch {
+                Some(patch) => format!("{}.{}.{}", major, minor, patch),
+                None => format!("{}.{}", major, minor),
+            });
+        }
+    }
+
     // Only return if we got at least a version
     if build_info.version.is_some() {
         Some(build_info)
@@ -640,30 +1286,538 @@ fn parse_build_info_labels(line: &str) -> Option<BuildInfo> {
     }
 }
 
-/// Detect the node implementation type based on available metrics
-fn detect_node_type(metrics: &HashMap<String, f64>) -> NodeType {
-    // Check for Dingo-specific metrics
-    if metrics.keys().any(|k| k.starts_with("dingo_")) {
-        return NodeType::Dingo;
+/// Metric names the parser maps to fields, sorted for binary search.
+/// Raw entries for these borrow the name instead of allocating it.
+const KNOWN_METRICS: &[&str] = &[
+    "cardano_node_metrics_Forge_adopted_int",
+    "cardano_node_metrics_Forge_didnt_adopt_int",
+    "cardano_node_metrics_Forge_forge_about_to_lead_int",
+    "cardano_node_metrics_Forge_node_is_leader_int",
+    "cardano_node_metrics_Mem_resident_int",
+    "cardano_node_metrics_RTS_cpuNs",
+    "cardano_node_metrics_RTS_cpuNs_int",
+    "cardano_node_metrics_RTS_cpu_ns",
+    "cardano_node_metrics_RTS_gcHeapBytes_int",
+    "cardano_node_metrics_RTS_gcLiveBytes_int",
+    "cardano_node_metrics_RTS_gcMajorNum_int",
+    "cardano_node_metrics_RTS_gcMinorNum_int",
+    "cardano_node_metrics_Stat_startTime",
+    "cardano_node_metrics_blockNum_int",
+    "cardano_node_metrics_blockReplayProgress_double",
+    "cardano_node_metrics_blockReplayProgress_real",
+    "cardano_node_metrics_blockfetchclient_blockdelay",
+    "cardano_node_metrics_blockfetchclient_blockdelay_cdfFive",
+    "cardano_node_metrics_blockfetchclient_blockdelay_cdfFive_real",
+    "cardano_node_metrics_blockfetchclient_blockdelay_cdfOne",
+    "cardano_node_metrics_blockfetchclient_blockdelay_cdfOne_real",
+    "cardano_node_metrics_blockfetchclient_blockdelay_cdfThree",
+    "cardano_node_metrics_blockfetchclient_blockdelay_cdfThree_real",
+    "cardano_node_metrics_blockfetchclient_blockdelay_real",
+    "cardano_node_metrics_blockfetchclient_blockdelay_s",
+    "cardano_node_metrics_blockfetchclient_lateblocks",
+    "cardano_node_metrics_blockfetchclient_lateblocks_counter",
+    "cardano_node_metrics_blockfetchclient_lateblocks_int",
+    "cardano_node_metrics_blocksForged_int",
+    "cardano_node_metrics_connectedPeers_int",
+    "cardano_node_metrics_connectionManager_connectionErrors",
+    "cardano_node_metrics_connectionManager_connectionErrors_counter",
+    "cardano_node_metrics_connectionManager_duplexConns",
+    "cardano_node_metrics_connectionManager_fullDuplexConns",
+    "cardano_node_metrics_connectionManager_handlerErrors",
+    "cardano_node_metrics_connectionManager_handlerErrors_counter",
+    "cardano_node_metrics_connectionManager_incomingConns",
+    "cardano_node_metrics_connectionManager_outgoingConns",
+    "cardano_node_metrics_connectionManager_prunableConns",
+    "cardano_node_metrics_connectionManager_prunableConns_int",
+    "cardano_node_metrics_connectionManager_unidirectionalConns",
+    "cardano_node_metrics_currentKESPeriod_int",
+    "cardano_node_metrics_density_real",
+    "cardano_node_metrics_epoch_int",
+    "cardano_node_metrics_forging_enabled_int",
+    "cardano_node_metrics_forks",
+    "cardano_node_metrics_forks_counter",
+    "cardano_node_metrics_forks_int",
+    "cardano_node_metrics_handshake_failures",
+    "cardano_node_metrics_handshake_failures_total",
+    "cardano_node_metrics_mempoolBytes_int",
+    "cardano_node_metrics_nodeIsLeader_int",
+    "cardano_node_metrics_nodeStartTime_int",
+    "cardano_node_metrics_node_start_time_int",
+    "cardano_node_metrics_opCertCounterOnChain_int",
+    "cardano_node_metrics_opCertCounterOnDisk_int",
+    "cardano_node_metrics_operationalCertificateExpiryKESPeriod_int",
+    "cardano_node_metrics_operationalCertificateStartKESPeriod_int",
+    "cardano_node_metrics_p2p_coldPeersCount_int",
+    "cardano_node_metrics_p2p_enabled_int",
+    "cardano_node_metrics_p2p_hotPeersCount_int",
+    "cardano_node_metrics_p2p_warmPeersCount_int",
+    "cardano_node_metrics_peerSelection_Cold_int",
+    "cardano_node_metrics_peerSelection_Hot_int",
+    "cardano_node_metrics_peerSelection_TargetActive_int",
+    "cardano_node_metrics_peerSelection_TargetEstablished_int",
+    "cardano_node_metrics_peerSelection_TargetKnown_int",
+    "cardano_node_metrics_peerSelection_Warm_int",
+    "cardano_node_metrics_peerSelection_cold",
+    "cardano_node_metrics_peerSelection_hot",
+    "cardano_node_metrics_peerSelection_targetNumberOfActivePeers",
+    "cardano_node_metrics_peerSelection_targetNumberOfEstablishedPeers",
+    "cardano_node_metrics_peerSelection_targetNumberOfKnownPeers",
+    "cardano_node_metrics_peerSelection_warm",
+    "cardano_node_metrics_remainingKESPeriods_int",
+    "cardano_node_metrics_served_block_count",
+    "cardano_node_metrics_served_block_count_counter",
+    "cardano_node_metrics_served_block_count_int",
+    "cardano_node_metrics_served_block_counter",
+    "cardano_node_metrics_served_header_count",
+    "cardano_node_metrics_served_header_count_int",
+    "cardano_node_metrics_served_header_counter",
+    "cardano_node_metrics_served_header_counter_int",
+    "cardano_node_metrics_slotInEpoch_int",
+    "cardano_node_metrics_slotNum_int",
+    "cardano_node_metrics_slotsMissedNum_int",
+    "cardano_node_metrics_slotsMissed_int",
+    "cardano_node_metrics_txsInMempool_int",
+    "cardano_node_metrics_txsProcessedNum",
+    "cardano_node_metrics_txsProcessedNum_counter",
+    "cardano_node_metrics_txsProcessedNum_int",
+    "cardano_node_metrics_upTime_ns",
+    "rts_gc_cpu_ms",
+];
+
+/// Map a `peerSelection_*` governor metric onto `governance`, returning
+/// whether the name was one of them
+///
+/// The names are the tracer's CamelCase ones, with or without the `_int`
+/// suffix older nodes add. Churn is exported per action
+/// (`churn_IncreasedActivePeers`, `churn_DecreasedKnownBigLedgerPeers`, ...)
+/// and summed per peer state.
+fn parse_peer_governance(governance: &mut PeerGovernance, name: &str, value: f64) -> bool {
+    let Some(key) = name.strip_prefix("cardano_node_metrics_peerSelection_") else {
+        return false;
+    };
+    let key = key.strip_suffix("_int").unwrap_or(key);
+    let value = value as u64;
+    match key {
+        "PeerSharingRequested" => {
+            governance.sharing_requested = Some(value);
+            return true;
+        }
+        "PeerSharingReceived" => {
+            governance.sharing_received = Some(value);
+            return true;
+        }
+        _ => {}
     }
+    if let Some(action) = key.strip_prefix("churn_") {
+        let (increased, peers) = if let Some(peers) = action.strip_prefix("Increased") {
+            (true, peers)
+        } else if let Some(peers) = action.strip_prefix("Decreased") {
+            (false, peers)
+        } else {
+            return false;
+        };
+        let churn = if peers.starts_with("Known") {
+            &mut governance.churn_known
+        } else if peers.starts_with("Established") {
+            &mut governance.churn_established
+        } else if peers.starts_with("Active") {
+            &mut governance.churn_active
+        } else {
+            return false;
+        };
+        let total = if increased {
+            &mut churn.increased
+        } else {
+            &mut churn.decreased
+        };
+        *total = Some(total.unwrap_or(0) + value);
+        return true;
+    }
+    let (group, rest) = if let Some(rest) = key.strip_suffix("BigLedgerPeers") {
+        (&mut governance.big_ledger, rest)
+    } else if let Some(rest) = key.strip_suffix("BootstrapPeers") {
+        (&mut governance.bootstrap, rest)
+    } else if let Some(rest) = key.strip_suffix("SharedPeers") {
+        (&mut governance.shared, rest)
+    } else {
+        return false;
+    };
+    let count = match rest {
+        "Known" => &mut group.known,
+        "Established" => &mut group.established,
+        "Active" => &mut group.active,
+        _ => return false,
+    };
+    *count = Some(value);
+    true
+}
 
-    // Check for Amaru-specific metrics
-    if metrics.keys().any(|k| k.starts_with("amaru_")) {
-        return NodeType::Amaru;
+/// Key for a raw entry: the static name for known metrics, else a copy
+fn intern(name: &str) -> Cow<'static, str> {
+    match KNOWN_METRICS.binary_search(&name) {
+        Ok(i) => Cow::Borrowed(KNOWN_METRICS[i]),
+        Err(_) => Cow::Owned(name.to_string()),
     }
+}
 
-    // Check for standard cardano-node metrics
-    if metrics.keys().any(|k| k.starts_with("cardano_node_")) {
-        return NodeType::CardanoNode;
+/// Detect the node implementation type based on available metrics
+fn node_type_of(name: &str) -> NodeType {
+    if name.starts_with("dingo_") {
+        NodeType::Dingo
+    } else if name.starts_with("amaru_") {
+        NodeType::Amaru
+    } else if name.starts_with("cardano_node_") {
+        NodeType::CardanoNode
+    } else {
+        NodeType::Unknown
     }
+}
 
-    NodeType::Unknown
+/// Dashboard panel a raw metric belongs to (for the focus view)
+pub fn metric_panel(name: &str) -> PanelKind {
+    let name = name.to_ascii_lowercase();
+    let any = |parts: &[&str]| parts.iter().any(|p| name.contains(p));
+    if any(&[
+        "forge",
+        "forging",
+        "kes",
+        "opcert",
+        "operationalcertificate",
+        "leader",
+        "adopt",
+        "abouttolead",
+        "slotsmissed",
+    ]) {
+        PanelKind::Forging
+    } else if name.starts_with("rts")
+        || any(&[
+            "_rts_",
+            "mem_",
+            "_gc",
+            "cpu",
+            "heap",
+            "mempool",
+            "uptime",
+            "starttime",
+        ])
+    {
+        PanelKind::Resources
+    } else if any(&[
+        "peer",
+        "p2p",
+        "conn",
+        "inboundgovernor",
+        "blockfetch",
+        "served",
+        "chainsync",
+        "root",
+        "churn",
+    ]) {
+        PanelKind::Network
+    } else {
+        PanelKind::Chain
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_raw_value_lookup() {
+        let mut metrics = NodeMetrics::default();
+        metrics
+            .raw
+            .insert("cardano_node_metrics_served_header_counter".into(), 42.0);
+        metrics.raw.insert("rts_gc_num_gcs".into(), 7.0);
+
+        assert_eq!(
+            metrics.raw_value("cardano_node_metrics_served_header_counter"),
+            Some(42.0)
+        );
+        assert_eq!(metrics.raw_value("served_header_counter"), Some(42.0));
+        assert_eq!(metrics.raw_value("rts_gc_num_gcs"), Some(7.0));
+        assert_eq!(metrics.raw_value("missing"), None);
+    }
+
+    #[test]
+    fn test_metric_panel() {
+        for (name, panel) in [
+            ("cardano_node_metrics_blockNum_int", PanelKind::Chain),
+            ("cardano_node_metrics_density_real", PanelKind::Chain),
+            ("cardano_node_metrics_peerSelection_hot", PanelKind::Network),
+            (
+                "cardano_node_metrics_connectionManager_incomingConns",
+                PanelKind::Network,
+            ),
+            (
+                "cardano_node_metrics_served_block_count_int",
+                PanelKind::Network,
+            ),
+            (
+                "cardano_node_metrics_RTS_gcLiveBytes_int",
+                PanelKind::Resources,
+            ),
+            ("rts_gc_cpu_ms", PanelKind::Resources),
+            (
+                "cardano_node_metrics_txsInMempool_int",
+                PanelKind::Resources,
+            ),
+            (
+                "cardano_node_metrics_remainingKESPeriods_int",
+                PanelKind::Forging,
+            ),
+            ("cardano_node_metrics_Forge_adopted_int", PanelKind::Forging),
+        ] {
+            assert_eq!(metric_panel(name), panel, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_health_url() {
+        let relative = EndpointOptions {
+            health_url: Some("/health".to_string()),
+            ..Default::default()
+        };
+        let client = MetricsClient::new(
+            "http://10.0.0.1:12798/metrics".to_string(),
+            Duration::from_secs(3),
+            &relative,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            client.health_url.as_ref().map(|u| u.as_str()),
+            Some("http://10.0.0.1:12798/health")
+        );
+
+        let absolute = EndpointOptions {
+            health_url: Some("http://10.0.0.1:3000/ready".to_string()),
+            ..Default::default()
+        };
+        let client = MetricsClient::new(
+            "http://10.0.0.1:12798/metrics".to_string(),
+            Duration::from_secs(3),
+            &absolute,
+            None,
+        )
+        .unwrap();
+        assert!(client.has_health_check());
+        assert_eq!(client.health_url.unwrap().port(), Some(3000));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_over_unix_socket() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket = dir.path().join("metrics.sock");
+        let listener = tokio::net::UnixListener::bind(&socket).unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).await.unwrap();
+            let body = "cardano_node_metrics_blockNum_int 12345\n";
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let client = MetricsClient::new(
+            "http://localhost/metrics".to_string(),
+            Duration::from_secs(3),
+            &EndpointOptions::default(),
+            Some(&socket),
+        )
+        .unwrap();
+        let metrics = client.fetch().await.unwrap().into_metrics();
+        assert_eq!(metrics.block_height, Some(12345));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_reuses_unchanged_payload() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            for _ in 0..3 {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 1024];
+                let n = stream.read(&mut request).await.unwrap();
+                let request = String::from_utf8_lossy(&request[..n]).to_lowercase();
+                let response = if request.contains("if-none-match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n".to_string()
+                } else {
+                    let body = "cardano_node_metrics_blockNum_int 12345\n";
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    )
+                };
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let client = MetricsClient::new(
+            format!("http://127.0.0.1:{}/metrics", port),
+            Duration::from_secs(3),
+            &EndpointOptions::default(),
+            None,
+        )
+        .unwrap();
+        let first = client.fetch().await.unwrap();
+        assert!(first.is_new());
+        let second = client.fetch().await.unwrap();
+        assert!(!second.is_new());
+        assert_eq!(second.into_metrics().block_height, Some(12345));
+        assert_eq!(client.cache.lock().unwrap().parses, 1);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_error_classes() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let client = |port: u16| {
+            MetricsClient::new(
+                format!("http://127.0.0.1:{}/metrics", port),
+                Duration::from_secs(3),
+                &EndpointOptions::default(),
+                None,
+            )
+            .unwrap()
+        };
+
+        // Nothing listening on a port that was just released
+        let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let closed_port = closed.local_addr().unwrap().port();
+        drop(closed);
+        let refused = client(closed_port).fetch().await.unwrap_err();
+        assert_eq!(refused, FetchError::ConnectionRefused);
+        assert!(refused.hint().unwrap().contains("hasPrometheus"));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            for response in [
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                "HTTP/1.1 200 OK\r\nContent-Length: 15\r\nConnection: close\r\n\r\n<!DOCTYPE html>",
+            ] {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).await.unwrap();
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+        let client = client(port);
+        assert_eq!(client.fetch().await.unwrap_err(), FetchError::Http(404));
+        let not_metrics = client.fetch().await.unwrap_err();
+        assert!(matches!(not_metrics, FetchError::Parse(_)));
+        assert!(!not_metrics.is_unreachable());
+    }
+
+    #[test]
+    fn test_raw_metric_filter() {
+        let text = "cardano_node_metrics_blockNum_int 12345\n\
+                    cardano_node_metrics_served_header_counter 42\n\
+                    rts_gc_num_gcs 7\n";
+        let filter = RawMetricFilter::new(["rts_*"]).with_name("served_header_counter");
+        let metrics = parse_prometheus_metrics_with(text, &filter);
+        // Structured fields and the node type don't depend on the allowlist
+        assert_eq!(metrics.block_height, Some(12345));
+        assert_eq!(metrics.node_type, NodeType::CardanoNode);
+        assert_eq!(metrics.raw.len(), 2);
+        assert_eq!(metrics.raw_value("served_header_counter"), Some(42.0));
+        assert_eq!(metrics.raw_value("rts_gc_num_gcs"), Some(7.0));
+        let exact = RawMetricFilter::new([""; 0]).with_exact(["rts_gc_num_gcs"]);
+        assert!(exact.retains("rts_gc_num_gcs"));
+        assert!(!exact.retains("rts_gc_num_gcs_total"));
+
+        let all = parse_prometheus_metrics(text);
+        assert_eq!(all.raw.len(), 3);
+        assert!(matches!(
+            all.raw.get_key_value("cardano_node_metrics_blockNum_int"),
+            Some((Cow::Borrowed(_), _))
+        ));
+        assert!(KNOWN_METRICS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_known_metrics_match_parser() {
+        let fields = |text: &str| {
+            let mut metrics = parse_prometheus_metrics_with(text, &RawMetricFilter::new([""; 0]));
+            metrics.node_type = NodeType::Unknown;
+            format!("{:?}", metrics)
+        };
+        let none = fields("");
+
+        // Every known name fills a field...
+        for name in KNOWN_METRICS {
+            assert_ne!(
+                fields(&format!("{} 2", name)),
+                none,
+                "{} isn't parsed",
+                name
+            );
+        }
+
+        // ...and every plain metric the demo node exports that fills one is known
+        let demo = crate::demo::render(DemoProfile::BlockProducer, 1000).unwrap();
+        for line in demo
+            .lines()
+            .filter(|l| !l.starts_with('#') && !l.contains('{'))
+        {
+            let name = line.split_whitespace().next().unwrap_or_default();
+            if parse_peer_governance(&mut PeerGovernance::default(), name, 1.0) {
+                continue;
+            }
+            if fields(line) != none {
+                assert!(
+                    KNOWN_METRICS.contains(&name),
+                    "{} missing from KNOWN_METRICS",
+                    name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_scrape_cache_skips_unchanged_body() {
+        let mut cache = ScrapeCache {
+            body: b"cardano_node_metrics_blockNum_int 1\n".to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(
+            cache
+                .metrics(&RawMetricFilter::default())
+                .unwrap()
+                .block_height,
+            Some(1)
+        );
+        assert_eq!(
+            cache
+                .metrics(&RawMetricFilter::default())
+                .unwrap()
+                .block_height,
+            Some(1)
+        );
+        assert_eq!(cache.parses, 1);
+
+        cache.body = b"cardano_node_metrics_blockNum_int 2\n".to_vec();
+        assert_eq!(
+            cache
+                .metrics(&RawMetricFilter::default())
+                .unwrap()
+                .block_height,
+            Some(2)
+        );
+        assert_eq!(cache.parses, 2);
+    }
+
     #[test]
     fn test_parse_metric_line_simple() {
         let (name, value) = parse_metric_line("cardano_node_metrics_blockNum_int 12345").unwrap();
@@ -706,6 +1860,16 @@ cardano_node_metrics_upTime_ns 86400000000000
         assert_eq!(metrics.uptime_seconds, Some(86400.0));
     }
 
+    #[test]
+    fn test_parse_replay_progress() {
+        let text = r#"
+cardano_node_metrics_blockReplayProgress_real 45.5
+"#;
+        let metrics = parse_prometheus_metrics(text);
+        assert_eq!(metrics.replay_progress, Some(45.5));
+        assert_eq!(metrics.block_height, None);
+    }
+
     #[test]
     fn test_parse_kes_metrics() {
         let text = r#"
@@ -742,6 +1906,105 @@ cardano_node_metrics_connectionManager_unidirectionalConns 8
         assert_eq!(metrics.unidirectional_connections, Some(8));
     }
 
+    #[test]
+    fn test_parse_served_counters() {
+        let text = r#"
+cardano_node_metrics_served_block_count_int 120
+cardano_node_metrics_served_header_counter_int 4800
+cardano_node_metrics_blockfetchclient_lateblocks 2
+"#;
+        let metrics = parse_prometheus_metrics(text);
+        assert_eq!(metrics.blocks_served, Some(120));
+        assert_eq!(metrics.headers_served, Some(4800));
+        assert_eq!(metrics.blocks_late, Some(2));
+
+        let metrics = parse_prometheus_metrics("cardano_node_metrics_served_header_counter 7");
+        assert_eq!(metrics.headers_served, Some(7));
+    }
+
+    #[test]
+    fn test_parse_connection_errors() {
+        let text = r#"
+cardano_node_metrics_connectionManager_prunableConns 3
+cardano_node_metrics_connectionManager_connectionErrors 7
+cardano_node_metrics_connectionManager_handlerErrors_counter 2
+cardano_node_metrics_handshake_failures_total 4
+cardano_node_metrics_connectionManager_incomingConns 10
+"#;
+        let metrics = parse_prometheus_metrics(text);
+        assert_eq!(metrics.prunable_connections, Some(3));
+        assert_eq!(metrics.connection_errors, Some(9));
+        assert_eq!(metrics.handshake_failures, Some(4));
+        assert_eq!(metrics.incoming_connections, Some(10));
+
+        let metrics = parse_prometheus_metrics("cardano_node_metrics_blockNum_int 1");
+        assert_eq!(metrics.connection_errors, None);
+        assert_eq!(metrics.handshake_failures, None);
+    }
+
+    #[test]
+    fn test_parse_peer_governance() {
+        let text = r#"
+cardano_node_metrics_peerSelection_KnownBigLedgerPeers 60
+cardano_node_metrics_peerSelection_EstablishedBigLedgerPeers 15
+cardano_node_metrics_peerSelection_ActiveBigLedgerPeers 5
+cardano_node_metrics_peerSelection_KnownBootstrapPeers_int 0
+cardano_node_metrics_peerSelection_KnownSharedPeers 34
+cardano_node_metrics_peerSelection_EstablishedSharedPeers 6
+cardano_node_metrics_peerSelection_PeerSharingRequested 20
+cardano_node_metrics_peerSelection_PeerSharingReceived_int 17
+cardano_node_metrics_peerSelection_churn_IncreasedActivePeers 2
+cardano_node_metrics_peerSelection_churn_IncreasedActiveBigLedgerPeers 1
+cardano_node_metrics_peerSelection_churn_IncreasedKnownPeers 10
+cardano_node_metrics_peerSelection_churn_DecreasedActivePeers 3
+cardano_node_metrics_peerSelection_ColdBigLedgerPeersPromotions 4
+"#;
+        let governance = parse_prometheus_metrics(text).p2p.governance;
+        assert!(governance.is_set());
+        assert_eq!(
+            governance.big_ledger,
+            PeerGroupCounts {
+                known: Some(60),
+                established: Some(15),
+                active: Some(5),
+            }
+        );
+        assert_eq!(governance.bootstrap.known, Some(0));
+        assert_eq!(governance.bootstrap.active, None);
+        assert_eq!(governance.shared.known, Some(34));
+        assert_eq!(governance.shared.established, Some(6));
+        assert_eq!(governance.sharing_requested, Some(20));
+        assert_eq!(governance.sharing_received, Some(17));
+        assert_eq!(
+            governance.churn_active,
+            ChurnCounts {
+                increased: Some(3),
+                decreased: Some(3),
+            }
+        );
+        assert_eq!(governance.churn_known.increased, Some(10));
+        assert_eq!(governance.churn_known.decreased, None);
+        assert!(!governance.churn_established.is_set());
+
+        let governance = parse_prometheus_metrics("cardano_node_metrics_p2p_enabled_int 1")
+            .p2p
+            .governance;
+        assert!(!governance.is_set());
+    }
+
+    #[test]
+    fn test_parse_peer_targets() {
+        let text = r#"
+cardano_node_metrics_peerSelection_targetNumberOfKnownPeers 150
+cardano_node_metrics_peerSelection_targetNumberOfEstablishedPeers 40
+cardano_node_metrics_peerSelection_targetNumberOfActivePeers 20
+"#;
+        let targets = parse_prometheus_metrics(text).p2p.targets;
+        assert!(targets.is_set());
+        assert_eq!(targets.by_state(), (Some(20), Some(20), Some(110)));
+        assert!(!PeerTargets::default().is_set());
+    }
+
     #[test]
     fn test_parse_build_info() {
         // Note: Real cardano-node output has a space before the {
@@ -769,4 +2032,21 @@ cardano_node_metrics_blockNum_int 10500000
         // Ensure other metrics still parsed correctly
         assert_eq!(metrics.block_height, Some(10500000));
     }
+
+    #[test]
+    fn test_parse_build_info_variants() {
+        // Only the numeric components labelled
+        let metrics = parse_prometheus_metrics(
+            r#"cardano_node_metrics_cardano_build_info{version_major="10",version_minor="1",version_patch="4"} 1"#,
+        );
+        assert_eq!(metrics.build_info.version, Some("10.1.4".to_string()));
+
+        // Other implementations; Go's toolchain info is ignored
+        let text = r#"
+go_build_info{checksum="",path="github.com/blinklabs-io/dingo",version="(devel)"} 1
+dingo_build_info{version="0.4.2",revision="abc123"} 1
+"#;
+        let metrics = parse_prometheus_metrics(text);
+        assert_eq!(metrics.build_info.version, Some("0.4.2".to_string()));
+    }
 }
@@ -0,0 +1,276 @@
+//! MQTT publisher for node health and alerts (`[global.mqtt]`)
+//!
+//! After every refresh each node's health ("ok", "warning" or "critical") and
+//! tip age in seconds are published as retained messages, so status LEDs and
+//! home-automation dashboards always see the latest state, and each new alert
+//! is published once as JSON. Topics are templates where `{node}` is replaced
+//! by the node name (lowercased, with anything but letters, digits, `-` and
+//! `_` turned into `_`). The connection runs in the background and reconnects
+//! on its own; messages queued while the broker is away may be dropped.
+
+use crate::alerts::Alert;
+use crate::app::HealthStatus;
+use crate::status::health_label;
+use rumqttc::{AsyncClient, ConnectionError, Event, MqttOptions, Packet, QoS};
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Publishes waiting to be sent before new ones are dropped
+const QUEUE_CAPACITY: usize = 256;
+
+/// Wait before reconnecting to an unreachable broker
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// `[global.mqtt]` in the config file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MqttConfig {
+    /// Broker host; publishing is off without it
+    pub host: Option<String>,
+    /// Broker port
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Client ID sent to the broker; a random "sview-..." ID per connection
+    /// if unset, so several sview instances can share a broker (a broker
+    /// disconnects the older of two clients with the same ID)
+    pub client_id: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Topic for the node's health
+    #[serde(default = "default_health_topic")]
+    pub health_topic: String,
+    /// Topic for the node's tip age in seconds
+    #[serde(default = "default_tip_age_topic")]
+    pub tip_age_topic: String,
+    /// Topic for new alerts
+    #[serde(default = "default_alert_topic")]
+    pub alert_topic: String,
+}
+
+fn default_port() -> u16 {
+    1883
+}
+
+/// A client ID no other sview instance is likely to use
+fn random_client_id() -> String {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    format!("sview-{:016x}", hasher.finish())
+}
+
+fn default_health_topic() -> String {
+    "sview/{node}/health".to_string()
+}
+
+fn default_tip_age_topic() -> String {
+    "sview/{node}/tip_age".to_string()
+}
+
+fn default_alert_topic() -> String {
+    "sview/{node}/alert".to_string()
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            host: None,
+            port: default_port(),
+            client_id: None,
+            username: None,
+            password: None,
+            health_topic: default_health_topic(),
+            tip_age_topic: default_tip_age_topic(),
+            alert_topic: default_alert_topic(),
+        }
+    }
+}
+
+/// Connection to the configured broker
+pub struct MqttPublisher {
+    client: AsyncClient,
+    config: MqttConfig,
+}
+
+impl MqttPublisher {
+    /// Connect in the background, or `None` when publishing is not configured
+    pub fn new(config: &MqttConfig) -> Option<Self> {
+        let host = config.host.as_deref()?;
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return None;
+        };
+        let client_id = config.client_id.clone().unwrap_or_else(random_client_id);
+        let mut options = MqttOptions::new(client_id, host, config.port);
+        options.set_keep_alive(KEEP_ALIVE);
+        if let Some(username) = &config.username {
+            options.set_credentials(username, config.password.clone().unwrap_or_default());
+        }
+        let (client, mut eventloop) = AsyncClient::new(options, QUEUE_CAPACITY);
+
+        let broker = format!("{}:{}", host, config.port);
+        runtime.spawn(async move {
+            let mut connected = false;
+            loop {
+                match eventloop.poll().await {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        debug!("Connected to MQTT broker {}", broker);
+                        connected = true;
+                    }
+                    Ok(_) => {}
+                    // The publisher was dropped (config reload or exit)
+                    Err(ConnectionError::RequestsDone) => break,
+                    Err(e) => {
+                        // Only the first failure of an outage is worth a warning
+                        if connected {
+                            warn!("MQTT broker {} disconnected: {}", broker, e);
+                        } else {
+                            debug!("MQTT broker {} unreachable: {}", broker, e);
+                        }
+                        connected = false;
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                }
+            }
+        });
+
+        Some(Self {
+            client,
+            config: config.clone(),
+        })
+    }
+
+    /// Whether this publisher was created from `config`
+    pub fn is_for(&self, config: &MqttConfig) -> bool {
+        self.config == *config
+    }
+
+    /// Publish a node's health and tip age (retained)
+    pub fn publish_node(&self, node: &str, health: HealthStatus, tip_age_secs: Option<u64>) {
+        self.publish(
+            topic(&self.config.health_topic, node),
+            health_label(health).to_string(),
+            true,
+        );
+        if let Some(age) = tip_age_secs {
+            self.publish(
+                topic(&self.config.tip_age_topic, node),
+                age.to_string(),
+                true,
+            );
+        }
+    }
+
+    /// Publish a new alert as JSON
+    pub fn publish_alert(&self, alert: &Alert) {
+        match serde_json::to_string(alert) {
+            Ok(json) => self.publish(
+                topic(&self.config.alert_topic, &alert.node_name),
+                json,
+                false,
+            ),
+            Err(e) => warn!("Failed to encode alert for MQTT: {}", e),
+        }
+    }
+
+    fn publish(&self, topic: String, payload: String, retain: bool) {
+        if let Err(e) = self
+            .client
+            .try_publish(&topic, QoS::AtLeastOnce, retain, payload)
+        {
+            debug!("Dropped MQTT message for {}: {}", topic, e);
+        }
+    }
+}
+
+/// A topic template with `{node}` filled in
+pub fn topic(template: &str, node: &str) -> String {
+    let node: String = node
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    template.replace("{node}", &node)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::AlertSeverity;
+
+    #[test]
+    fn test_topic() {
+        assert_eq!(
+            topic("sview/{node}/health", "Relay 1"),
+            "sview/relay_1/health"
+        );
+        assert_eq!(topic("pool/{node}", "bp/#+"), "pool/bp___");
+        assert_eq!(topic("sview/all", "bp"), "sview/all");
+        assert!(MqttPublisher::new(&MqttConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_config_defaults() {
+        let config: MqttConfig = toml::from_str("host = \"broker.lan\"").unwrap();
+        assert_eq!(config.host.as_deref(), Some("broker.lan"));
+        assert_eq!(config.port, 1883);
+        assert_eq!(config.alert_topic, "sview/{node}/alert");
+        assert_eq!(config.client_id, None);
+        assert_ne!(random_client_id(), random_client_id());
+        assert!(random_client_id().starts_with("sview-"));
+    }
+
+    #[tokio::test]
+    async fn test_publishes_to_broker() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let received = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut data = Vec::new();
+            let mut buf = [0u8; 1024];
+            let mut acked = false;
+            while !String::from_utf8_lossy(&data).contains("Peer Drop") {
+                let n = stream.read(&mut buf).await.unwrap();
+                data.extend_from_slice(&buf[..n]);
+                if !acked {
+                    // CONNACK, session not present, accepted
+                    stream.write_all(&[0x20, 0x02, 0x00, 0x00]).await.unwrap();
+                    acked = true;
+                }
+            }
+            String::from_utf8_lossy(&data).to_string()
+        });
+
+        let config = MqttConfig {
+            host: Some("127.0.0.1".to_string()),
+            port,
+            ..Default::default()
+        };
+        let publisher = MqttPublisher::new(&config).unwrap();
+        publisher.publish_node("Relay 1", HealthStatus::Warning, Some(42));
+        publisher.publish_alert(&Alert {
+            timestamp: 1_700_000_000,
+            node_name: "Relay 1".to_string(),
+            severity: AlertSeverity::Warning,
+            title: "Peer Drop".to_string(),
+            message: "Hot peers fell to 6".to_string(),
+            resolves: None,
+        });
+
+        let data = received.await.unwrap();
+        assert!(data.contains("sview/relay_1/health"));
+        assert!(data.contains("warning"));
+        assert!(data.contains("sview/relay_1/tip_age"));
+        assert!(data.contains("sview/relay_1/alert"));
+        assert!(data.contains("\"severity\":\"Warning\""));
+    }
+}
@@ -0,0 +1,222 @@
+//! Form state for adding a node from the TUI
+//!
+//! Holds the text typed into the "add node" overlay, moves focus between
+//! fields and validates the input before a node is started.
+
+use crate::config::NodeRole;
+
+/// Fields of the add-node form, in focus order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormField {
+    Name,
+    Host,
+    Port,
+    Role,
+}
+
+impl FormField {
+    pub const ALL: [FormField; 4] = [
+        FormField::Name,
+        FormField::Host,
+        FormField::Port,
+        FormField::Role,
+    ];
+
+    /// Label shown next to the field
+    pub fn label(self) -> &'static str {
+        match self {
+            FormField::Name => "Name",
+            FormField::Host => "Host",
+            FormField::Port => "Port",
+            FormField::Role => "Role",
+        }
+    }
+}
+
+/// Step of the add-node dialog
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormStep {
+    /// Editing the fields
+    Editing,
+    /// Node added; asking whether to save it to the config file
+    ConfirmSave,
+}
+
+/// A validated node definition from the form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeInput {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub role: NodeRole,
+}
+
+/// State of the add-node overlay
+#[derive(Debug, Clone)]
+pub struct NodeForm {
+    pub name: String,
+    pub host: String,
+    pub port: String,
+    pub role: NodeRole,
+    /// Index into `FormField::ALL`
+    pub focused: usize,
+    pub step: FormStep,
+    /// Validation error from the last submit
+    pub error: Option<String>,
+    /// Input whose connection test failed during setup; submitting it
+    /// unchanged saves it anyway
+    pub unreachable: Option<NodeInput>,
+}
+
+impl Default for NodeForm {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            host: "127.0.0.1".to_string(),
+            port: "12798".to_string(),
+            role: NodeRole::Relay,
+            focused: 0,
+            step: FormStep::Editing,
+            error: None,
+            unreachable: None,
+        }
+    }
+}
+
+impl NodeForm {
+    /// Currently focused field
+    pub fn focused_field(&self) -> FormField {
+        FormField::ALL[self.focused]
+    }
+
+    /// Move focus to the next field (wrapping)
+    pub fn focus_next(&mut self) {
+        self.focused = (self.focused + 1) % FormField::ALL.len();
+    }
+
+    /// Move focus to the previous field (wrapping)
+    pub fn focus_prev(&mut self) {
+        self.focused = (self.focused + FormField::ALL.len() - 1) % FormField::ALL.len();
+    }
+
+    /// Display value of a field
+    pub fn value(&self, field: FormField) -> String {
+        match field {
+            FormField::Name => self.name.clone(),
+            FormField::Host => self.host.clone(),
+            FormField::Port => self.port.clone(),
+            FormField::Role => match self.role {
+                NodeRole::Relay => "relay".to_string(),
+                NodeRole::Bp => "bp (block producer)".to_string(),
+            },
+        }
+    }
+
+    /// Type a character into the focused field
+    pub fn input(&mut self, c: char) {
+        match self.focused_field() {
+            FormField::Name => self.name.push(c),
+            FormField::Host if !c.is_whitespace() => self.host.push(c),
+            FormField::Port if c.is_ascii_digit() && self.port.len() < 5 => self.port.push(c),
+            FormField::Role if c == ' ' => self.toggle_role(),
+            _ => {}
+        }
+        self.error = None;
+    }
+
+    /// Delete the last character of the focused field
+    pub fn backspace(&mut self) {
+        match self.focused_field() {
+            FormField::Name => {
+                self.name.pop();
+            }
+            FormField::Host => {
+                self.host.pop();
+            }
+            FormField::Port => {
+                self.port.pop();
+            }
+            FormField::Role => {}
+        }
+    }
+
+    /// Switch between relay and block producer
+    pub fn toggle_role(&mut self) {
+        self.role = match self.role {
+            NodeRole::Relay => NodeRole::Bp,
+            NodeRole::Bp => NodeRole::Relay,
+        };
+    }
+
+    /// Validate the form against the names of existing nodes
+    pub fn validate(&self, existing_names: &[&str]) -> Result<NodeInput, String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err("Name is required".to_string());
+        }
+        if existing_names.contains(&name) {
+            return Err(format!("A node named '{}' already exists", name));
+        }
+
+        let host = self.host.trim();
+        if host.is_empty() {
+            return Err("Host is required".to_string());
+        }
+
+        let port = match self.port.parse::<u16>() {
+            Ok(port) if port > 0 => port,
+            _ => return Err("Port must be between 1 and 65535".to_string()),
+        };
+
+        Ok(NodeInput {
+            name: name.to_string(),
+            host: host.to_string(),
+            port,
+            role: self.role,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_form_input_and_validate() {
+        let mut form = NodeForm::default();
+        assert_eq!(form.validate(&[]), Err("Name is required".to_string()));
+
+        for c in "Relay 2".chars() {
+            form.input(c);
+        }
+        form.focus_next();
+        form.focus_next();
+        form.backspace();
+        form.input('9');
+        form.input('x'); // ignored: ports are digits only
+        form.focus_next();
+        form.input(' ');
+
+        let input = form.validate(&["Relay 1"]).unwrap();
+        assert_eq!(input.name, "Relay 2");
+        assert_eq!(input.host, "127.0.0.1");
+        assert_eq!(input.port, 12799);
+        assert_eq!(input.role, NodeRole::Bp);
+
+        assert!(form.validate(&["Relay 2"]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_port() {
+        let form = NodeForm {
+            name: "Relay".to_string(),
+            port: "70000".to_string(),
+            ..Default::default()
+        };
+        assert!(form.validate(&[]).unwrap_err().contains("Port"));
+
+        let mut form = form;
+        form.focus_prev();
+        assert_eq!(form.focused_field(), FormField::Role);
+    }
+}
@@ -0,0 +1,182 @@
+//! Node-to-client connection over the cardano-node local socket
+//!
+//! Implements the Ouroboros multiplexer framing and version handshake so
+//! local mini-protocols (e.g. LocalTxMonitor) can be queried directly from
+//! the node, for data that isn't exported via Prometheus.
+
+use crate::cbor::{self, CborError, Value};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+#[cfg(unix)]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tracing::debug;
+
+/// Handshake mini-protocol number
+const PROTOCOL_HANDSHAKE: u16 = 0;
+
+/// Node-to-client versions we propose (V16-V19, bit 15 marks node-to-client)
+const N2C_VERSIONS: [u64; 4] = [32784, 32785, 32786, 32787];
+
+/// Upper bound on buffered bytes for one protocol before giving up
+const MAX_BUFFERED_BYTES: usize = 4 * 1024 * 1024;
+
+/// Get the network magic for a well-known network name
+pub fn network_magic(network: &str) -> Option<u64> {
+    match network {
+        "mainnet" => Some(764824073),
+        "preprod" => Some(1),
+        "preview" => Some(2),
+        "sanchonet" => Some(4),
+        _ => None,
+    }
+}
+
+/// Multiplexed node-to-client connection
+pub struct NodeConnection {
+    #[cfg(unix)]
+    stream: tokio::net::UnixStream,
+    /// Received payload bytes not yet decoded, per mini-protocol
+    buffers: HashMap<u16, Vec<u8>>,
+    started: Instant,
+}
+
+impl NodeConnection {
+    /// Connect to the node socket and complete the version handshake
+    #[cfg(unix)]
+    pub async fn connect(socket_path: &Path, magic: u64) -> Result<Self> {
+        let stream = tokio::net::UnixStream::connect(socket_path)
+            .await
+            .with_context(|| format!("Failed to connect to {}", socket_path.display()))?;
+
+        let mut conn = Self {
+            stream,
+            buffers: HashMap::new(),
+            started: Instant::now(),
+        };
+        conn.handshake(magic).await?;
+        Ok(conn)
+    }
+
+    /// Local sockets are only available on Unix platforms
+    #[cfg(not(unix))]
+    pub async fn connect(_socket_path: &Path, _magic: u64) -> Result<Self> {
+        bail!("Node socket connections are only supported on Unix")
+    }
+
+    /// Propose our supported versions and check the node accepted one
+    async fn handshake(&mut self, magic: u64) -> Result<()> {
+        let mut msg = Vec::new();
+        cbor::encode_array(&mut msg, 2);
+        cbor::encode_uint(&mut msg, 0); // MsgProposeVersions
+        cbor::encode_map(&mut msg, N2C_VERSIONS.len() as u64);
+        for version in N2C_VERSIONS {
+            cbor::encode_uint(&mut msg, version);
+            cbor::encode_array(&mut msg, 2);
+            cbor::encode_uint(&mut msg, magic);
+            cbor::encode_bool(&mut msg, false); // not a version query
+        }
+        self.send(PROTOCOL_HANDSHAKE, &msg).await?;
+
+        let reply = self.recv(PROTOCOL_HANDSHAKE).await?;
+        let items = reply.as_array().unwrap_or_default();
+        match items.first().and_then(Value::as_u64) {
+            Some(1) => {
+                debug!("Node accepted handshake version {:?}", items.get(1));
+                Ok(())
+            }
+            Some(2) => bail!("Node refused handshake: {:?}", items.get(1)),
+            _ => bail!("Unexpected handshake reply: {:?}", reply),
+        }
+    }
+
+    /// Send one message on a mini-protocol as initiator
+    pub async fn send(&mut self, protocol: u16, payload: &[u8]) -> Result<()> {
+        let len = u16::try_from(payload.len()).context("Message too large for one segment")?;
+        let timestamp = self.started.elapsed().as_micros() as u32;
+
+        let mut segment = Vec::with_capacity(8 + payload.len());
+        segment.extend_from_slice(&timestamp.to_be_bytes());
+        segment.extend_from_slice(&protocol.to_be_bytes());
+        segment.extend_from_slice(&len.to_be_bytes());
+        segment.extend_from_slice(payload);
+
+        self.write_all(&segment).await
+    }
+
+    /// Receive the next complete message on a mini-protocol
+    pub async fn recv(&mut self, protocol: u16) -> Result<Value> {
+        loop {
+            if let Some(buffer) = self.buffers.get_mut(&protocol) {
+                match cbor::decode(buffer) {
+                    Ok((value, used)) => {
+                        buffer.drain(..used);
+                        return Ok(value);
+                    }
+                    Err(CborError::Incomplete) => {}
+                    Err(e) => return Err(e).context("Malformed message from node"),
+                }
+                if buffer.len() > MAX_BUFFERED_BYTES {
+                    bail!("Message from node exceeds {} bytes", MAX_BUFFERED_BYTES);
+                }
+            }
+
+            self.read_segment().await?;
+        }
+    }
+
+    /// Read one mux segment and append its payload to the protocol's buffer
+    async fn read_segment(&mut self) -> Result<()> {
+        let mut header = [0u8; 8];
+        self.read_exact(&mut header).await?;
+
+        // Responder segments have the mode bit (0x8000) set
+        let protocol = u16::from_be_bytes([header[4], header[5]]) & 0x7fff;
+        let len = u16::from_be_bytes([header[6], header[7]]) as usize;
+
+        let mut payload = vec![0u8; len];
+        self.read_exact(&mut payload).await?;
+        self.buffers.entry(protocol).or_default().extend(payload);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    async fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+        self.stream
+            .write_all(bytes)
+            .await
+            .context("Failed to write to node socket")
+    }
+
+    #[cfg(unix)]
+    async fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        self.stream
+            .read_exact(buf)
+            .await
+            .context("Node socket closed")?;
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    async fn write_all(&mut self, _bytes: &[u8]) -> Result<()> {
+        bail!("Node socket connections are only supported on Unix")
+    }
+
+    #[cfg(not(unix))]
+    async fn read_exact(&mut self, _buf: &mut [u8]) -> Result<()> {
+        bail!("Node socket connections are only supported on Unix")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_magic() {
+        assert_eq!(network_magic("mainnet"), Some(764824073));
+        assert_eq!(network_magic("preview"), Some(2));
+        assert_eq!(network_magic("devnet"), None);
+    }
+}
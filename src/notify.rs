@@ -0,0 +1,532 @@
+//! Push notifications for alerts (`[[notify]]`)
+//!
+//! Each `[[notify]]` table in the config file is a channel that new alerts
+//! at or above its `min_severity` are sent to:
+//!
+//! - `ntfy`: publishes to an ntfy.sh (or self-hosted ntfy) topic URL, with the
+//!   alert title as the notification title and the severity mapped to an ntfy
+//!   priority, so critical alerts can break through do-not-disturb.
+//! - `home_assistant`: posts the alert as JSON to a Home Assistant webhook
+//!   (`/api/webhook/<id>`), where an automation can forward it to the
+//!   companion app or flash a light.
+//! - `bell`: rings the terminal bell, for an operator with sview open in a
+//!   corner of the screen.
+//! - `desktop`: shows a desktop notification through the platform's
+//!   notification service (D-Bus on Linux and the BSDs, Notification Center
+//!   on macOS, toasts on Windows). Needs the `desktop-notify` cargo feature
+//!   (on by default).
+//!
+//! The local channels (`bell`, `desktop`) default to critical alerts only.
+//! A channel's `quiet_hours` raise its minimum severity for part of the day
+//! (critical only by default), in the configured `timezone`.
+//! Sends run in the background; a failed send is logged and not retried.
+
+use crate::alerts::{Alert, AlertSeverity};
+use crate::timezone::DisplayTimezone;
+use serde::Deserialize;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Longest a single send may take
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Notification service behind a channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyKind {
+    /// ntfy topic URL (e.g. "https://ntfy.sh/my-pool-alerts")
+    Ntfy,
+    /// Home Assistant webhook URL
+    #[serde(alias = "homeassistant")]
+    HomeAssistant,
+    /// Terminal bell
+    Bell,
+    /// Desktop notification
+    Desktop,
+}
+
+impl NotifyKind {
+    /// Whether alerts are delivered on this machine rather than to a URL
+    pub fn is_local(self) -> bool {
+        matches!(self, Self::Bell | Self::Desktop)
+    }
+}
+
+/// A `[[notify]]` table in the config file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct NotifyChannel {
+    /// Name to refer to the channel by; defaults to the kind
+    pub name: Option<String>,
+    pub kind: NotifyKind,
+    /// Where alerts are sent (not used by local channels)
+    #[serde(default)]
+    pub url: String,
+    /// Access token, sent as `Authorization: Bearer ...` (ntfy)
+    pub token: Option<String>,
+    /// Least severe alert that is sent; defaults to warning, or critical for
+    /// local channels
+    pub min_severity: Option<AlertSeverity>,
+    /// Time of day when fewer alerts are sent, e.g. "00:00-07:00"
+    pub quiet_hours: Option<QuietHours>,
+    /// Least severe alert sent during quiet hours
+    #[serde(default = "default_quiet_min_severity")]
+    pub quiet_min_severity: AlertSeverity,
+}
+
+fn default_quiet_min_severity() -> AlertSeverity {
+    AlertSeverity::Critical
+}
+
+/// A daily time range, which may wrap past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct QuietHours {
+    /// Start, in minutes after midnight
+    start: u32,
+    /// End (exclusive), in minutes after midnight
+    end: u32,
+}
+
+impl QuietHours {
+    /// Whether a time of day (minutes after midnight) falls in the range
+    pub fn contains(&self, minute: u32) -> bool {
+        if self.start <= self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+impl FromStr for QuietHours {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |time: &str| {
+            let (hours, minutes) = time.trim().split_once(':')?;
+            let (hours, minutes) = (hours.parse::<u32>().ok()?, minutes.parse::<u32>().ok()?);
+            (hours <= 24 && minutes < 60 && hours * 60 + minutes <= 1440)
+                .then_some(hours * 60 + minutes)
+        };
+        s.split_once('-')
+            .and_then(|(start, end)| Some((parse(start)?, parse(end)?)))
+            .map(|(start, end)| Self { start, end })
+            .ok_or_else(|| {
+                format!(
+                    "invalid quiet_hours '{}' (expected a range like \"00:00-07:00\")",
+                    s
+                )
+            })
+    }
+}
+
+impl TryFrom<String> for QuietHours {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl NotifyChannel {
+    /// The channel's name, or its kind when unnamed
+    pub fn label(&self) -> &str {
+        match (&self.name, self.kind) {
+            (Some(name), _) => name,
+            (None, NotifyKind::Ntfy) => "ntfy",
+            (None, NotifyKind::HomeAssistant) => "home_assistant",
+            (None, NotifyKind::Bell) => "bell",
+            (None, NotifyKind::Desktop) => "desktop",
+        }
+    }
+
+    /// Least severe alert that is sent
+    pub fn min_severity(&self) -> AlertSeverity {
+        match self.min_severity {
+            Some(severity) => severity,
+            None if self.kind.is_local() => AlertSeverity::Critical,
+            None => AlertSeverity::Warning,
+        }
+    }
+
+    /// Least severe alert that is sent at a time of day (minutes after
+    /// midnight), taking quiet hours into account
+    pub fn min_severity_at(&self, minute: u32) -> AlertSeverity {
+        match self.quiet_hours {
+            Some(quiet) if quiet.contains(minute) => {
+                self.min_severity().max(self.quiet_min_severity)
+            }
+            _ => self.min_severity(),
+        }
+    }
+}
+
+/// Sends alerts to every configured channel
+pub struct Notifier {
+    client: reqwest::Client,
+    channels: Vec<NotifyChannel>,
+    /// Timezone quiet hours are given in
+    timezone: DisplayTimezone,
+}
+
+impl Notifier {
+    /// A notifier for `channels`, or `None` when there are none
+    pub fn new(channels: &[NotifyChannel], timezone: DisplayTimezone) -> Option<Self> {
+        if channels.is_empty() {
+            return None;
+        }
+        let client = match reqwest::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .user_agent(concat!("sview/", env!("CARGO_PKG_VERSION")))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Notifications disabled: {}", e);
+                return None;
+            }
+        };
+        Some(Self {
+            client,
+            channels: channels.to_vec(),
+            timezone,
+        })
+    }
+
+    /// Whether this notifier was created from `channels` and `timezone`
+    pub fn is_for(&self, channels: &[NotifyChannel], timezone: DisplayTimezone) -> bool {
+        self.channels == channels && self.timezone == timezone
+    }
+
+    /// Send an alert to each channel it is severe enough for, in the
+    /// background; `route` limits it to the named channels
+    pub fn send(&self, alert: &Alert, route: Option<&[String]>) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let minute = self.timezone.minute_of_day(alert.timestamp);
+        for channel in &self.channels {
+            if route.is_some_and(|names| !names.iter().any(|n| n == channel.label())) {
+                continue;
+            }
+            // Resolution events go wherever the alert they end went
+            if alert.resolves.unwrap_or(alert.severity) < channel.min_severity_at(minute) {
+                continue;
+            }
+            match channel.kind {
+                NotifyKind::Bell => ring_bell(),
+                #[cfg(feature = "desktop-notify")]
+                NotifyKind::Desktop => {
+                    let notification = desktop_notification(alert);
+                    let title = alert.title.clone();
+                    runtime.spawn_blocking(move || match notification.show() {
+                        Ok(_) => debug!("Sent '{}' to the desktop", title),
+                        Err(e) => warn!("Failed to show '{}' on the desktop: {}", title, e),
+                    });
+                }
+                // Refused when the config is loaded
+                #[cfg(not(feature = "desktop-notify"))]
+                NotifyKind::Desktop => {}
+                NotifyKind::Ntfy | NotifyKind::HomeAssistant => {
+                    let Some(request) = self.request(channel, alert) else {
+                        continue;
+                    };
+                    let (label, title) = (channel.label().to_string(), alert.title.clone());
+                    runtime.spawn(async move {
+                        match request.send().await.and_then(|r| r.error_for_status()) {
+                            Ok(_) => debug!("Sent '{}' to {}", title, label),
+                            Err(e) => warn!("Failed to send '{}' to {}: {}", title, label, e),
+                        }
+                    });
+                }
+            }
+        }
+    }
+
+    /// The HTTP request for a remote channel, or `None` for a local one
+    fn request(&self, channel: &NotifyChannel, alert: &Alert) -> Option<reqwest::RequestBuilder> {
+        let request = match channel.kind {
+            NotifyKind::Ntfy => {
+                // Published as JSON: header values can't carry non-ASCII
+                // node names or titles
+                let (server, topic) = ntfy_server_and_topic(&channel.url);
+                let mut request = self.client.post(server).json(&serde_json::json!({
+                    "topic": topic,
+                    "title": format!("{}: {}", alert.node_name, alert.title),
+                    "message": alert.message,
+                    "priority": ntfy_priority(alert.severity),
+                    "tags": [ntfy_tag(alert)],
+                }));
+                if let Some(token) = &channel.token {
+                    request = request.header("Authorization", format!("Bearer {}", token));
+                }
+                request
+            }
+            NotifyKind::HomeAssistant => self.client.post(&channel.url).json(alert),
+            NotifyKind::Bell | NotifyKind::Desktop => return None,
+        };
+        Some(request)
+    }
+}
+
+/// Ring the terminal bell; the TUI owns the terminal, so this doesn't
+/// disturb the screen
+fn ring_bell() {
+    use std::io::Write;
+    let mut stdout = std::io::stdout();
+    if let Err(e) = stdout.write_all(b"\x07").and_then(|_| stdout.flush()) {
+        debug!("Failed to ring the terminal bell: {}", e);
+    }
+}
+
+/// The desktop notification for an alert
+#[cfg(feature = "desktop-notify")]
+fn desktop_notification(alert: &Alert) -> notify_rust::Notification {
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .appname("sview")
+        .summary(&format!("{}: {}", alert.node_name, alert.title))
+        .body(&alert.message);
+    #[cfg(all(unix, not(target_os = "macos")))]
+    notification.urgency(match alert.severity {
+        AlertSeverity::Info => notify_rust::Urgency::Low,
+        AlertSeverity::Warning => notify_rust::Urgency::Normal,
+        AlertSeverity::Critical => notify_rust::Urgency::Critical,
+    });
+    notification
+}
+
+/// The server URL and topic of an ntfy topic URL
+/// ("https://ntfy.sh/my-topic" is topic "my-topic" on "https://ntfy.sh")
+fn ntfy_server_and_topic(url: &str) -> (&str, &str) {
+    url.trim_end_matches('/')
+        .rsplit_once('/')
+        .unwrap_or((url, ""))
+}
+
+/// ntfy priority (1 min to 5 max) for a severity
+pub fn ntfy_priority(severity: AlertSeverity) -> u8 {
+    match severity {
+        AlertSeverity::Info => 2,
+        AlertSeverity::Warning => 3,
+        AlertSeverity::Critical => 5,
+    }
+}
+
+/// ntfy tag, shown as an emoji before the title
+fn ntfy_tag(alert: &Alert) -> &'static str {
+    if alert.resolves.is_some() {
+        return "white_check_mark";
+    }
+    match alert.severity {
+        AlertSeverity::Info => "information_source",
+        AlertSeverity::Warning => "warning",
+        AlertSeverity::Critical => "rotating_light",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(severity: AlertSeverity) -> Alert {
+        Alert {
+            timestamp: 1_700_000_000,
+            node_name: "bp".to_string(),
+            severity,
+            title: "KES Expiring".to_string(),
+            message: "3 KES periods left".to_string(),
+            resolves: None,
+        }
+    }
+
+    #[test]
+    fn test_channel_config() {
+        #[derive(Deserialize)]
+        struct File {
+            notify: Vec<NotifyChannel>,
+        }
+        let file: File = toml::from_str(
+            r#"
+            [[notify]]
+            kind = "ntfy"
+            url = "https://ntfy.sh/pool"
+
+            [[notify]]
+            name = "house"
+            kind = "home_assistant"
+            url = "http://ha.local:8123/api/webhook/sview"
+            min_severity = "critical"
+
+            [[notify]]
+            kind = "desktop"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(file.notify[0].label(), "ntfy");
+        assert_eq!(file.notify[0].min_severity(), AlertSeverity::Warning);
+        assert_eq!(file.notify[1].label(), "house");
+        assert_eq!(file.notify[1].min_severity(), AlertSeverity::Critical);
+        assert_eq!(file.notify[2].label(), "desktop");
+        assert!(file.notify[2].url.is_empty());
+        assert_eq!(file.notify[2].min_severity(), AlertSeverity::Critical);
+        assert!(Notifier::new(&[], DisplayTimezone::Utc).is_none());
+    }
+
+    #[test]
+    fn test_quiet_hours() {
+        let night: QuietHours = "23:30-07:00".parse().unwrap();
+        assert!(night.contains(23 * 60 + 45));
+        assert!(night.contains(3 * 60));
+        assert!(!night.contains(7 * 60));
+        assert!(!night.contains(12 * 60));
+        let lunch: QuietHours = "12:00-13:00".parse().unwrap();
+        assert!(lunch.contains(12 * 60 + 30));
+        assert!(!lunch.contains(11 * 60));
+        assert!("7-9".parse::<QuietHours>().is_err());
+        assert!("00:00-25:00".parse::<QuietHours>().is_err());
+
+        let channel: NotifyChannel = toml::from_str(
+            r#"
+            kind = "ntfy"
+            url = "https://ntfy.sh/pool"
+            quiet_hours = "00:00-07:00"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(channel.min_severity_at(3 * 60), AlertSeverity::Critical);
+        assert_eq!(channel.min_severity_at(9 * 60), AlertSeverity::Warning);
+    }
+
+    #[test]
+    fn test_ntfy_priority() {
+        assert_eq!(ntfy_priority(AlertSeverity::Info), 2);
+        assert_eq!(ntfy_priority(AlertSeverity::Warning), 3);
+        assert_eq!(ntfy_priority(AlertSeverity::Critical), 5);
+    }
+
+    #[tokio::test]
+    async fn test_send_to_ntfy() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let received = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).ends_with('}') {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap().to_lowercase()
+        });
+
+        let channels = [
+            NotifyChannel {
+                name: None,
+                kind: NotifyKind::Ntfy,
+                url: format!("http://127.0.0.1:{}/pool", port),
+                token: Some("tk_secret".to_string()),
+                min_severity: Some(AlertSeverity::Warning),
+                quiet_hours: None,
+                quiet_min_severity: AlertSeverity::Critical,
+            },
+            // Filtered out by severity; would fail the test if it connected
+            NotifyChannel {
+                name: Some("house".to_string()),
+                kind: NotifyKind::HomeAssistant,
+                url: format!("http://127.0.0.1:{}/api/webhook/sview", port),
+                token: None,
+                min_severity: Some(AlertSeverity::Critical),
+                quiet_hours: None,
+                quiet_min_severity: AlertSeverity::Critical,
+            },
+        ];
+        let notifier = Notifier::new(&channels, DisplayTimezone::Utc).unwrap();
+        notifier.send(&alert(AlertSeverity::Info), None);
+        // Routed away from both channels
+        notifier.send(
+            &alert(AlertSeverity::Critical),
+            Some(&["pager".to_string()]),
+        );
+        notifier.send(&alert(AlertSeverity::Warning), None);
+
+        let request = received.await.unwrap();
+        assert!(request.starts_with("post / "));
+        assert!(request.contains(r#""topic":"pool""#));
+        assert!(request.contains(r#""title":"bp: kes expiring""#));
+        assert!(request.contains(r#""priority":3"#));
+        assert!(request.contains("authorization: bearer tk_secret"));
+    }
+
+    #[test]
+    fn test_ntfy_request() {
+        assert_eq!(
+            ntfy_server_and_topic("https://ntfy.sh/pool-alerts/"),
+            ("https://ntfy.sh", "pool-alerts")
+        );
+        assert_eq!(
+            ntfy_server_and_topic("https://example.com/ntfy/pool"),
+            ("https://example.com/ntfy", "pool")
+        );
+
+        let channel = NotifyChannel {
+            name: None,
+            kind: NotifyKind::Ntfy,
+            url: "https://ntfy.sh/pool-alerts".to_string(),
+            token: None,
+            min_severity: None,
+            quiet_hours: None,
+            quiet_min_severity: AlertSeverity::Critical,
+        };
+        let notifier = Notifier::new(std::slice::from_ref(&channel), DisplayTimezone::Utc).unwrap();
+        let alert = Alert {
+            node_name: "Relé ₳".to_string(),
+            ..alert(AlertSeverity::Warning)
+        };
+        let request = notifier.request(&channel, &alert).unwrap().build().unwrap();
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body["title"], "Relé ₳: KES Expiring");
+        assert_eq!(body["tags"][0], "warning");
+    }
+
+    #[test]
+    #[cfg(feature = "desktop-notify")]
+    fn test_desktop_notification() {
+        let notification = desktop_notification(&alert(AlertSeverity::Critical));
+        assert_eq!(notification.appname, "sview");
+        assert_eq!(notification.summary, "bp: KES Expiring");
+        assert_eq!(notification.body, "3 KES periods left");
+    }
+
+    #[test]
+    fn test_home_assistant_request() {
+        let channel = NotifyChannel {
+            name: None,
+            kind: NotifyKind::HomeAssistant,
+            url: "http://ha.local:8123/api/webhook/sview".to_string(),
+            token: None,
+            min_severity: Some(AlertSeverity::Warning),
+            quiet_hours: None,
+            quiet_min_severity: AlertSeverity::Critical,
+        };
+        let notifier = Notifier::new(std::slice::from_ref(&channel), DisplayTimezone::Utc).unwrap();
+        let request = notifier
+            .request(&channel, &alert(AlertSeverity::Critical))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.url().path(), "/api/webhook/sview");
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        assert_eq!(body["severity"], "Critical");
+        assert_eq!(body["title"], "KES Expiring");
+    }
+}
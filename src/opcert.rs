@@ -0,0 +1,152 @@
+//! Operational certificate check via `cardano-cli`
+//!
+//! The node only exports `opCertCounterOnDisk`/`OnChain` in some versions and
+//! configurations. When a node has `opcert_path` set, sview runs
+//! `cardano-cli query kes-period-info` against its socket instead, the check
+//! operators otherwise run by hand after every KES rotation.
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Duration;
+
+/// Longest a single `cardano-cli` run may take
+const QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Fields of `kes-period-info --out-file` used by sview
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct KesPeriodInfo {
+    #[serde(rename = "qKesOnDiskOperationalCertificateNumber")]
+    pub counter_on_disk: u64,
+    /// `None` until the pool has minted a block with this cold key
+    #[serde(rename = "qKesNodeStateOperationalCertificateNumber")]
+    pub counter_on_chain: Option<u64>,
+    #[serde(rename = "qKesCurrentKesPeriod")]
+    pub current_kes_period: Option<u64>,
+    #[serde(rename = "qKesStartKesInterval")]
+    pub start_kes_period: Option<u64>,
+    #[serde(rename = "qKesEndKesInterval")]
+    pub end_kes_period: Option<u64>,
+}
+
+/// How the on-disk counter relates to the one in the ledger
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterStatus {
+    /// Matches the chain, or no block minted with this cold key yet
+    Valid,
+    /// One ahead of the chain: a new certificate not yet used for a block
+    Rotated,
+    /// Blocks forged with this certificate will be rejected
+    Mismatch,
+}
+
+/// Compare opcert counters; the node accepts the chain's counter or one more
+pub fn counter_status(on_disk: u64, on_chain: Option<u64>) -> CounterStatus {
+    match on_chain {
+        None => CounterStatus::Valid,
+        Some(chain) if on_disk == chain => CounterStatus::Valid,
+        Some(chain) if on_disk == chain + 1 => CounterStatus::Rotated,
+        Some(_) => CounterStatus::Mismatch,
+    }
+}
+
+/// Run `cardano-cli query kes-period-info` for an operational certificate
+pub async fn query_kes_period_info(
+    cli_path: &Path,
+    opcert_path: &Path,
+    socket_path: &Path,
+    network_magic: u64,
+) -> Result<KesPeriodInfo> {
+    // A private directory, so no other user can plant or read the output
+    let out_dir = tempfile::Builder::new()
+        .prefix("sview-kes-period-info-")
+        .tempdir()
+        .context("Failed to create a temporary directory")?;
+    let out_file = out_dir.path().join("kes-period-info.json");
+
+    let mut command = tokio::process::Command::new(cli_path);
+    command
+        .args(["query", "kes-period-info", "--op-cert-file"])
+        .arg(opcert_path)
+        .arg("--socket-path")
+        .arg(socket_path)
+        .args(network_args(network_magic))
+        .arg("--out-file")
+        .arg(&out_file)
+        .env("CARDANO_NODE_SOCKET_PATH", socket_path)
+        .kill_on_drop(true);
+
+    let output = tokio::time::timeout(QUERY_TIMEOUT, command.output())
+        .await
+        .context("cardano-cli timed out")?
+        .with_context(|| format!("Failed to run {}", cli_path.display()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "cardano-cli exited with {}: {}",
+            output.status,
+            stderr.lines().last().unwrap_or_default().trim()
+        );
+    }
+
+    let json = std::fs::read_to_string(&out_file)
+        .with_context(|| format!("Failed to read {}", out_file.display()))?;
+    parse_kes_period_info(&json)
+}
+
+/// `--mainnet` or `--testnet-magic N`
+fn network_args(magic: u64) -> Vec<String> {
+    if magic == 764824073 {
+        vec!["--mainnet".to_string()]
+    } else {
+        vec!["--testnet-magic".to_string(), magic.to_string()]
+    }
+}
+
+fn parse_kes_period_info(json: &str) -> Result<KesPeriodInfo> {
+    serde_json::from_str(json).context("Unexpected kes-period-info output")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_kes_period_info() {
+        let info = parse_kes_period_info(
+            r#"{
+                "qKesCurrentKesPeriod": 404,
+                "qKesEndKesInterval": 434,
+                "qKesKesKeyExpiry": null,
+                "qKesMaxKESEvolutions": 62,
+                "qKesNodeStateOperationalCertificateNumber": 6,
+                "qKesOnDiskOperationalCertificateNumber": 7,
+                "qKesRemainingSlotsInKesPeriod": 3723330,
+                "qKesSlotsPerKesPeriod": 129600,
+                "qKesStartKesInterval": 372
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(info.counter_on_disk, 7);
+        assert_eq!(info.counter_on_chain, Some(6));
+        assert_eq!(info.start_kes_period, Some(372));
+
+        let fresh = parse_kes_period_info(
+            r#"{"qKesOnDiskOperationalCertificateNumber": 0,
+                "qKesNodeStateOperationalCertificateNumber": null}"#,
+        )
+        .unwrap();
+        assert_eq!(fresh.counter_on_chain, None);
+        assert!(parse_kes_period_info("{}").is_err());
+    }
+
+    #[test]
+    fn test_counter_status() {
+        assert_eq!(counter_status(6, Some(6)), CounterStatus::Valid);
+        assert_eq!(counter_status(7, Some(6)), CounterStatus::Rotated);
+        assert_eq!(counter_status(5, Some(6)), CounterStatus::Mismatch);
+        assert_eq!(counter_status(9, Some(6)), CounterStatus::Mismatch);
+        assert_eq!(counter_status(0, None), CounterStatus::Valid);
+        assert_eq!(network_args(1), vec!["--testnet-magic", "1"]);
+    }
+}
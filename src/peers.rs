@@ -1,35 +1,26 @@
 //! Peer monitoring and statistics
 //!
-//! Tracks connected peers and their statistics based on Prometheus metrics.
-//! Inspired by nview's peer monitoring approach but adapted for Prometheus-based metrics.
-
-use std::collections::HashMap;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-/// Peer connection direction
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[allow(dead_code)]
-pub enum PeerDirection {
-    /// Incoming connection
-    Incoming,
-    /// Outgoing connection
-    Outgoing,
-    /// Bidirectional/Duplex connection
-    Duplex,
-}
+//! The peer model is the socket table's `PeerConnection`. Where the node's
+//! log shows which connections run ChainSync, connections are enriched with
+//! their hot/warm state; statistics fall back to the node's Prometheus
+//! counts when the sockets can't be inspected.
 
-impl std::fmt::Display for PeerDirection {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            PeerDirection::Incoming => write!(f, "incoming"),
-            PeerDirection::Outgoing => write!(f, "outgoing"),
-            PeerDirection::Duplex => write!(f, "duplex"),
-        }
-    }
-}
+use crate::metrics::NodeMetrics;
+use crate::propagation::PropagationTracker;
+use crate::sockets::{parse_address, PeerConnection};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Socket scans further apart than this restart connection age tracking,
+/// since connections may have come and gone in between
+const MAX_SCAN_GAP: Duration = Duration::from_secs(300);
+
+/// Recent blocks whose announcing peers count as hot
+const HOT_PEER_BLOCKS: usize = 20;
 
 /// Peer state/temperature
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum PeerState {
     /// Cold peer - known but not yet promoted
     Cold,
@@ -62,6 +53,40 @@ pub enum LatencyBucket {
     High,
     /// Unreachable/timeout
     Unreachable,
+    /// No RTT and not probed yet
+    Unprobed,
+}
+
+impl LatencyBucket {
+    /// All buckets, fastest first
+    pub const ALL: [LatencyBucket; 6] = [
+        LatencyBucket::VeryLow,
+        LatencyBucket::Low,
+        LatencyBucket::Medium,
+        LatencyBucket::High,
+        LatencyBucket::Unreachable,
+        LatencyBucket::Unprobed,
+    ];
+
+    /// Bucket for a connection's RTT; no RTT counts as unreachable
+    pub fn from_rtt(rtt_ms: Option<f64>) -> Self {
+        match rtt_ms {
+            Some(rtt) if rtt < 50.0 => LatencyBucket::VeryLow,
+            Some(rtt) if rtt < 100.0 => LatencyBucket::Low,
+            Some(rtt) if rtt < 200.0 => LatencyBucket::Medium,
+            Some(_) => LatencyBucket::High,
+            None => LatencyBucket::Unreachable,
+        }
+    }
+
+    /// Bucket for a connection: without an RTT it is unreachable only once
+    /// a probe tried it
+    pub fn of(peer: &PeerConnection) -> Self {
+        match peer.rtt_ms {
+            None if !peer.rtt_probed => LatencyBucket::Unprobed,
+            rtt => Self::from_rtt(rtt),
+        }
+    }
 }
 
 impl std::fmt::Display for LatencyBucket {
@@ -72,148 +97,148 @@ impl std::fmt::Display for LatencyBucket {
             LatencyBucket::Medium => write!(f, "100-200ms"),
             LatencyBucket::High => write!(f, ">200ms"),
             LatencyBucket::Unreachable => write!(f, "unreachable"),
+            LatencyBucket::Unprobed => write!(f, "—"),
         }
     }
 }
 
-/// Individual peer information
-#[derive(Debug, Clone)]
-#[allow(dead_code)]
-pub struct Peer {
-    /// Peer IP address (if available)
-    pub ip: Option<String>,
-    /// Peer port
-    pub port: Option<u16>,
-    /// Connection direction
-    pub direction: PeerDirection,
-    /// Peer state (cold/warm/hot)
-    pub state: Option<PeerState>,
-    /// Round-trip time in milliseconds
-    pub rtt_ms: Option<u64>,
-    /// Geolocation (country code)
-    pub location: Option<String>,
-    /// Last updated timestamp
-    pub updated_at: u64,
-}
-
-impl Peer {
-    /// Create a new peer with minimal info
-    pub fn new(direction: PeerDirection) -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-
-        Self {
-            ip: None,
-            port: None,
-            direction,
-            state: None,
-            rtt_ms: None,
-            location: None,
-            updated_at: now,
+/// Set each connection's hot/warm state from the node's log
+///
+/// Only hot peers run ChainSync with the node, so a connection that announced
+/// one of the recent blocks is hot; other outgoing connections are warm.
+/// Incoming connections that don't send headers belong to the other side's
+/// governor and stay unknown, as does everything without log data.
+pub fn classify_connections(peers: &mut [PeerConnection], propagation: &PropagationTracker) {
+    if propagation.block_count() == 0 {
+        for peer in peers.iter_mut() {
+            peer.peer_state = None;
         }
+        return;
     }
-
-    /// Get latency bucket for this peer's RTT
-    pub fn latency_bucket(&self) -> LatencyBucket {
-        match self.rtt_ms {
-            Some(rtt) if rtt < 50 => LatencyBucket::VeryLow,
-            Some(rtt) if rtt < 100 => LatencyBucket::Low,
-            Some(rtt) if rtt < 200 => LatencyBucket::Medium,
-            Some(rtt) if rtt < 99999 => LatencyBucket::High,
-            _ => LatencyBucket::Unreachable,
-        }
+    // Log addresses are "ip:port", IPv6 sometimes in brackets
+    let hot: HashSet<(String, u16)> = propagation
+        .recent_peers(HOT_PEER_BLOCKS)
+        .iter()
+        .filter_map(|address| parse_address(address))
+        .collect();
+    for peer in peers.iter_mut() {
+        peer.peer_state = if hot.contains(&(peer.ip.clone(), peer.port)) {
+            Some(PeerState::Hot)
+        } else if !peer.incoming {
+            Some(PeerState::Warm)
+        } else {
+            None
+        };
     }
 }
 
 /// Peer statistics aggregation
-#[derive(Debug, Clone, Default)]
+///
+/// Built from the socket table's connections where available, otherwise
+/// from the node's Prometheus connection counts (without latency data).
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct PeerStats {
-    /// Total peers by state
+    /// Peers by state, as counted by the node's peer selection governor
     pub peers_by_state: HashMap<PeerState, u64>,
-    /// Total peers by direction
-    pub peers_by_direction: HashMap<PeerDirection, u64>,
+    /// Incoming connections
+    pub incoming: u64,
+    /// Outgoing connections
+    pub outgoing: u64,
+    /// Connections used in both directions (Prometheus only)
+    pub duplex: Option<u64>,
     /// Peers by latency bucket
     pub peers_by_latency: HashMap<LatencyBucket, u64>,
     /// Average RTT in milliseconds
     pub avg_rtt_ms: u64,
-    /// Sum of all RTTs (for calculating average)
-    pub rtt_sum: u64,
-    /// Count of reachable peers
+    /// Count of peers with an RTT
     pub reachable_count: u64,
-    /// Count of unreachable peers
+    /// Count of peers without an RTT
     pub unreachable_count: u64,
-    /// Percentage of peers in each bucket
-    pub latency_percentages: HashMap<LatencyBucket, f32>,
 }
 
 impl PeerStats {
-    /// Calculate statistics from a list of peers
-    pub fn from_peers(peers: &[Peer]) -> Self {
+    /// Statistics for a node: from its socket connections if any were
+    /// found, else from its connection counts
+    pub fn from_node(connections: &[PeerConnection], metrics: &NodeMetrics) -> Self {
+        let mut stats = if connections.is_empty() {
+            Self::from_counts(metrics)
+        } else {
+            Self::from_connections(connections)
+        };
+        for (state, count) in [
+            (PeerState::Hot, metrics.p2p.hot_peers),
+            (PeerState::Warm, metrics.p2p.warm_peers),
+            (PeerState::Cold, metrics.p2p.cold_peers),
+        ] {
+            if let Some(count) = count {
+                stats.peers_by_state.insert(state, count);
+            }
+        }
+        stats
+    }
+
+    /// Calculate statistics from the socket table's connections
+    pub fn from_connections(connections: &[PeerConnection]) -> Self {
         let mut stats = Self::default();
+        let mut rtt_sum = 0.0;
 
-        for peer in peers {
-            // Count by state
-            if let Some(state) = peer.state {
+        for peer in connections {
+            if peer.incoming {
+                stats.incoming += 1;
+            } else {
+                stats.outgoing += 1;
+            }
+            if let Some(state) = peer.peer_state {
                 *stats.peers_by_state.entry(state).or_insert(0) += 1;
             }
 
-            // Count by direction
-            *stats.peers_by_direction.entry(peer.direction).or_insert(0) += 1;
-
-            // Count by latency
-            let bucket = peer.latency_bucket();
+            let bucket = LatencyBucket::of(peer);
             *stats.peers_by_latency.entry(bucket).or_insert(0) += 1;
-
-            // RTT aggregation
-            if let Some(rtt) = peer.rtt_ms {
-                if rtt < 99999 {
-                    stats.rtt_sum += rtt;
+            match peer.rtt_ms {
+                Some(rtt) => {
+                    rtt_sum += rtt;
                     stats.reachable_count += 1;
-                } else {
-                    stats.unreachable_count += 1;
                 }
+                None => stats.unreachable_count += 1,
             }
         }
 
-        // Calculate averages
         if stats.reachable_count > 0 {
-            stats.avg_rtt_ms = stats.rtt_sum / stats.reachable_count;
+            stats.avg_rtt_ms = (rtt_sum / stats.reachable_count as f64) as u64;
         }
+        stats
+    }
 
-        // Calculate percentages
-        let total_reachable = stats.reachable_count as f32;
-        if total_reachable > 0.0 {
-            for (bucket, count) in &stats.peers_by_latency {
-                if *bucket != LatencyBucket::Unreachable {
-                    stats
-                        .latency_percentages
-                        .insert(*bucket, (*count as f32) / total_reachable * 100.0);
-                }
-            }
-            stats.latency_percentages.insert(
-                LatencyBucket::Unreachable,
-                (stats.unreachable_count as f32)
-                    / (stats.reachable_count as f32 + stats.unreachable_count as f32)
-                    * 100.0,
-            );
+    /// Calculate statistics from the node's connection counts
+    pub fn from_counts(metrics: &NodeMetrics) -> Self {
+        Self {
+            incoming: metrics.incoming_connections.unwrap_or(0),
+            outgoing: metrics.outgoing_connections.unwrap_or(0),
+            duplex: metrics.full_duplex_connections,
+            ..Self::default()
         }
+    }
 
-        stats
+    /// Peers in a latency bucket
+    pub fn latency_count(&self, bucket: LatencyBucket) -> u64 {
+        self.peers_by_latency.get(&bucket).copied().unwrap_or(0)
+    }
+
+    /// Peers in a state
+    pub fn state_count(&self, state: PeerState) -> u64 {
+        self.peers_by_state.get(&state).copied().unwrap_or(0)
     }
 
     /// Get text summary of peer statistics
     #[allow(dead_code)]
     pub fn summary(&self) -> String {
         format!(
-            "Peers: Hot={} Warm={} Cold={} | Dir: In={} Out={} Duplex={} | RTT: Avg={}ms | Reachable={} Unreachable={}",
-            self.peers_by_state.get(&PeerState::Hot).unwrap_or(&0),
-            self.peers_by_state.get(&PeerState::Warm).unwrap_or(&0),
-            self.peers_by_state.get(&PeerState::Cold).unwrap_or(&0),
-            self.peers_by_direction.get(&PeerDirection::Incoming).unwrap_or(&0),
-            self.peers_by_direction.get(&PeerDirection::Outgoing).unwrap_or(&0),
-            self.peers_by_direction.get(&PeerDirection::Duplex).unwrap_or(&0),
+            "Peers: Hot={} Warm={} Cold={} | Dir: In={} Out={} | RTT: Avg={}ms | Reachable={} Unreachable={}",
+            self.state_count(PeerState::Hot),
+            self.state_count(PeerState::Warm),
+            self.state_count(PeerState::Cold),
+            self.incoming,
+            self.outgoing,
             self.avg_rtt_ms,
             self.reachable_count,
             self.unreachable_count,
@@ -221,102 +246,247 @@ impl PeerStats {
     }
 }
 
-/// Peer monitor for tracking peer state and statistics
+/// When each connection (remote address and port) was first seen in
+/// consecutive socket scans
 #[derive(Debug, Clone, Default)]
-pub struct PeerMonitor {
-    /// List of tracked peers
-    peers: Vec<Peer>,
-    /// Cached statistics
-    stats: PeerStats,
-    /// Last update timestamp
-    last_updated: u64,
+pub struct ConnectionAges {
+    first_seen: HashMap<(String, u16), Instant>,
+    /// When tracking (re)started; connections seen then were already open
+    tracking_since: Option<Instant>,
+    last_scan: Option<Instant>,
 }
 
-impl PeerMonitor {
-    /// Create a new peer monitor
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    /// Update peer list from metrics
-    pub fn update_from_metrics(
-        &mut self,
-        hot_peers: Option<u64>,
-        warm_peers: Option<u64>,
-        cold_peers: Option<u64>,
-        incoming_conns: Option<u64>,
-        outgoing_conns: Option<u64>,
-        duplex_conns: Option<u64>,
-    ) {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-
-        self.peers.clear();
-
-        // Create peers for each hot peer
-        if let Some(count) = hot_peers {
-            for _ in 0..count {
-                let mut peer = Peer::new(PeerDirection::Duplex); // Default, will be refined
-                peer.state = Some(PeerState::Hot);
-                peer.updated_at = now;
-                self.peers.push(peer);
-            }
+impl ConnectionAges {
+    /// Record a scan: connections missing from it are forgotten, so one that
+    /// reconnects starts again from zero
+    pub fn update(&mut self, peers: &[PeerConnection], now: Instant) {
+        let continuous = self
+            .last_scan
+            .is_some_and(|last| now.saturating_duration_since(last) <= MAX_SCAN_GAP);
+        if !continuous {
+            self.first_seen.clear();
+            self.tracking_since = Some(now);
+        }
+        self.first_seen
+            .retain(|(ip, port), _| peers.iter().any(|p| p.ip == *ip && p.port == *port));
+        for peer in peers {
+            self.first_seen
+                .entry((peer.ip.clone(), peer.port))
+                .or_insert(now);
         }
+        self.last_scan = Some(now);
+    }
 
-        // Create peers for each warm peer
-        if let Some(count) = warm_peers {
-            for _ in 0..count {
-                let mut peer = Peer::new(PeerDirection::Duplex);
-                peer.state = Some(PeerState::Warm);
-                peer.updated_at = now;
-                self.peers.push(peer);
-            }
+    /// How long the connection has been open, and whether that is only a
+    /// lower bound (it was already open when tracking started)
+    pub fn age(&self, peer: &PeerConnection, now: Instant) -> Option<(Duration, bool)> {
+        let first = *self.first_seen.get(&(peer.ip.clone(), peer.port))?;
+        Some((
+            now.saturating_duration_since(first),
+            Some(first) == self.tracking_since,
+        ))
+    }
+}
+
+/// How the peer list groups connections
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PeerGrouping {
+    /// One row per connection
+    #[default]
+    None,
+    /// By IPv4 /24 (IPv6 /48)
+    Subnet,
+    /// By autonomous system (or ISP) from GeoIP data
+    Network,
+}
+
+impl PeerGrouping {
+    /// Cycle to the next grouping
+    pub fn next(self) -> Self {
+        match self {
+            PeerGrouping::None => PeerGrouping::Subnet,
+            PeerGrouping::Subnet => PeerGrouping::Network,
+            PeerGrouping::Network => PeerGrouping::None,
         }
+    }
 
-        // Create peers for each cold peer
-        if let Some(count) = cold_peers {
-            for _ in 0..count {
-                let mut peer = Peer::new(PeerDirection::Incoming);
-                peer.state = Some(PeerState::Cold);
-                peer.updated_at = now;
-                self.peers.push(peer);
-            }
+    /// Column heading for the group key
+    pub fn label(&self) -> &'static str {
+        match self {
+            PeerGrouping::None => "PEER",
+            PeerGrouping::Subnet => "SUBNET",
+            PeerGrouping::Network => "NETWORK",
         }
+    }
+}
 
-        // Update direction counts (simplified: we don't have individual peer IPs from Prometheus)
-        // In a full implementation with socket inspection, we'd merge these properly
-        let _ = (incoming_conns, outgoing_conns, duplex_conns);
+/// Connections sharing a subnet or network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerGroup {
+    pub key: String,
+    pub incoming: usize,
+    pub outgoing: usize,
+}
 
-        // Recalculate statistics
-        self.stats = PeerStats::from_peers(&self.peers);
-        self.last_updated = now;
+impl PeerGroup {
+    pub fn total(&self) -> usize {
+        self.incoming + self.outgoing
     }
+}
 
-    /// Get current statistics
-    #[allow(dead_code)]
-    pub fn stats(&self) -> &PeerStats {
-        &self.stats
+/// Subnet an address belongs to: /24 for IPv4, /48 for IPv6
+pub fn subnet_of(ip: &str) -> String {
+    match ip.parse::<std::net::IpAddr>() {
+        Ok(std::net::IpAddr::V4(v4)) => {
+            let [a, b, c, _] = v4.octets();
+            format!("{}.{}.{}.0/24", a, b, c)
+        }
+        Ok(std::net::IpAddr::V6(v6)) => {
+            let s = v6.segments();
+            format!("{:x}:{:x}:{:x}::/48", s[0], s[1], s[2])
+        }
+        Err(_) => ip.to_string(),
     }
+}
 
-    /// Get peer list
-    #[allow(dead_code)]
-    pub fn peers(&self) -> &[Peer] {
-        &self.peers
+/// Group connections, largest groups first
+///
+/// `networks` maps IPs to their network name; unresolved IPs are grouped
+/// under "Unknown".
+pub fn group_peers(
+    peers: &[PeerConnection],
+    grouping: PeerGrouping,
+    networks: &HashMap<String, String>,
+) -> Vec<PeerGroup> {
+    let mut groups: HashMap<String, PeerGroup> = HashMap::new();
+    for peer in peers {
+        let key = match grouping {
+            PeerGrouping::None => format!("{}:{}", peer.ip, peer.port),
+            PeerGrouping::Subnet => subnet_of(&peer.ip),
+            PeerGrouping::Network => networks
+                .get(&peer.ip)
+                .cloned()
+                .unwrap_or_else(|| "Unknown".to_string()),
+        };
+        let group = groups.entry(key.clone()).or_insert(PeerGroup {
+            key,
+            incoming: 0,
+            outgoing: 0,
+        });
+        if peer.incoming {
+            group.incoming += 1;
+        } else {
+            group.outgoing += 1;
+        }
     }
 
-    /// Get peer count
-    #[allow(dead_code)]
-    pub fn count(&self) -> u64 {
-        self.peers.len() as u64
+    let mut groups: Vec<PeerGroup> = groups.into_values().collect();
+    groups.sort_by(|a, b| b.total().cmp(&a.total()).then_with(|| a.key.cmp(&b.key)));
+    groups
+}
+
+/// How connections spread across networks (autonomous systems or ISPs)
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct NetworkDiversity {
+    /// Connections counted
+    pub connections: usize,
+    /// Connections whose network is known
+    pub resolved: usize,
+    /// Distinct known networks
+    pub networks: usize,
+    /// Largest networks and their connection counts, largest first
+    pub top: Vec<(String, usize)>,
+}
+
+impl NetworkDiversity {
+    /// Share of all counted connections, in percent
+    pub fn percent(&self, count: usize) -> usize {
+        (count * 100).checked_div(self.connections).unwrap_or(0)
     }
+}
 
-    /// Get last update timestamp
-    #[allow(dead_code)]
-    pub fn last_updated(&self) -> u64 {
-        self.last_updated
+/// Summarize the networks `peers` belong to, keeping the `top` largest
+pub fn network_diversity(
+    peers: &[PeerConnection],
+    networks: &HashMap<String, String>,
+    top: usize,
+) -> NetworkDiversity {
+    let groups: Vec<PeerGroup> = group_peers(peers, PeerGrouping::Network, networks)
+        .into_iter()
+        .filter(|g| g.key != "Unknown")
+        .collect();
+    NetworkDiversity {
+        connections: peers.len(),
+        resolved: groups.iter().map(PeerGroup::total).sum(),
+        networks: groups.len(),
+        top: groups
+            .into_iter()
+            .take(top)
+            .map(|g| {
+                let total = g.total();
+                (g.key, total)
+            })
+            .collect(),
+    }
+}
+
+/// Describe connections concentrated in one subnet or network
+///
+/// Returns a message for the largest offending group, if any: a /24 with at
+/// least `subnet_limit` incoming connections, a known network holding at
+/// least `network_percent` of them (only checked with 20+ incoming, since a
+/// handful of peers on one provider is normal), or a known network holding
+/// at least `outgoing_network_percent` of outgoing connections (with 10+
+/// outgoing). Outgoing connections are the node's hot and warm peers, so
+/// those concentrated on one provider share its outages.
+pub fn concentration_warning(
+    peers: &[PeerConnection],
+    networks: &HashMap<String, String>,
+    subnet_limit: usize,
+    network_percent: u8,
+    outgoing_network_percent: u8,
+) -> Option<String> {
+    let incoming: Vec<PeerConnection> = peers.iter().filter(|p| p.incoming).cloned().collect();
+
+    if subnet_limit > 0 {
+        if let Some(group) = group_peers(&incoming, PeerGrouping::Subnet, networks).first() {
+            if group.incoming >= subnet_limit {
+                return Some(format!(
+                    "{} incoming connections from {}",
+                    group.incoming, group.key
+                ));
+            }
+        }
+    }
+
+    if network_percent > 0 && incoming.len() >= 20 {
+        let diversity = network_diversity(&incoming, networks, 1);
+        if let Some((network, count)) = diversity.top.first() {
+            let percent = diversity.percent(*count);
+            if percent >= network_percent as usize {
+                return Some(format!(
+                    "{}% of incoming connections ({}) from {}",
+                    percent, count, network
+                ));
+            }
+        }
+    }
+
+    let outgoing: Vec<PeerConnection> = peers.iter().filter(|p| !p.incoming).cloned().collect();
+    if outgoing_network_percent > 0 && outgoing.len() >= 10 {
+        let diversity = network_diversity(&outgoing, networks, 1);
+        if let Some((network, count)) = diversity.top.first() {
+            let percent = diversity.percent(*count);
+            if percent >= outgoing_network_percent as usize {
+                return Some(format!(
+                    "{}% of outgoing connections ({}) to {}",
+                    percent, count, network
+                ));
+            }
+        }
     }
+
+    None
 }
 
 #[cfg(test)]
@@ -324,84 +494,249 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_peer_creation() {
-        let peer = Peer::new(PeerDirection::Incoming);
-        assert_eq!(peer.direction, PeerDirection::Incoming);
-        assert_eq!(peer.state, None);
+    fn test_latency_bucket() {
+        assert_eq!(LatencyBucket::from_rtt(Some(25.0)), LatencyBucket::VeryLow);
+        assert_eq!(LatencyBucket::from_rtt(Some(49.9)), LatencyBucket::VeryLow);
+        assert_eq!(LatencyBucket::from_rtt(Some(75.0)), LatencyBucket::Low);
+        assert_eq!(LatencyBucket::from_rtt(Some(150.0)), LatencyBucket::Medium);
+        assert_eq!(LatencyBucket::from_rtt(Some(300.0)), LatencyBucket::High);
+        assert_eq!(LatencyBucket::from_rtt(None), LatencyBucket::Unreachable);
     }
 
     #[test]
-    fn test_latency_bucket() {
-        let mut peer = Peer::new(PeerDirection::Outgoing);
-        peer.rtt_ms = Some(25);
-        assert_eq!(peer.latency_bucket(), LatencyBucket::VeryLow);
-
-        peer.rtt_ms = Some(75);
-        assert_eq!(peer.latency_bucket(), LatencyBucket::Low);
-
-        peer.rtt_ms = Some(150);
-        assert_eq!(peer.latency_bucket(), LatencyBucket::Medium);
+    fn test_peer_stats_from_connections() {
+        let mut peers = vec![
+            connection("203.0.113.1", true),
+            connection("203.0.113.2", false),
+            connection("203.0.113.3", false),
+            connection("203.0.113.4", false),
+        ];
+        peers[0].rtt_ms = Some(49.9);
+        peers[1].rtt_ms = Some(120.0);
+        peers[2].rtt_ms = Some(250.0);
+        peers[1].peer_state = Some(PeerState::Hot);
+        let stats = PeerStats::from_connections(&peers);
+        assert_eq!(stats.latency_count(LatencyBucket::VeryLow), 1);
+        assert_eq!(stats.latency_count(LatencyBucket::Low), 0);
+        assert_eq!(stats.latency_count(LatencyBucket::Medium), 1);
+        assert_eq!(stats.latency_count(LatencyBucket::High), 1);
+        // Not probed yet, then probed without an answer
+        assert_eq!(stats.latency_count(LatencyBucket::Unprobed), 1);
+        assert_eq!(stats.latency_count(LatencyBucket::Unreachable), 0);
+        peers[3].rtt_probed = true;
+        let stats = PeerStats::from_connections(&peers);
+        assert_eq!(stats.latency_count(LatencyBucket::Unprobed), 0);
+        assert_eq!(stats.latency_count(LatencyBucket::Unreachable), 1);
+        assert_eq!((stats.incoming, stats.outgoing), (1, 3));
+        assert_eq!((stats.reachable_count, stats.unreachable_count), (3, 1));
+        assert_eq!(stats.avg_rtt_ms, 139);
+        assert_eq!(stats.state_count(PeerState::Hot), 1);
+    }
 
-        peer.rtt_ms = Some(300);
-        assert_eq!(peer.latency_bucket(), LatencyBucket::High);
+    #[test]
+    fn test_peer_stats_from_node() {
+        let mut metrics = NodeMetrics {
+            incoming_connections: Some(8),
+            outgoing_connections: Some(12),
+            full_duplex_connections: Some(5),
+            ..Default::default()
+        };
+        metrics.p2p.hot_peers = Some(5);
+        metrics.p2p.warm_peers = Some(10);
+        metrics.p2p.cold_peers = Some(20);
+
+        // Without sockets the counts are used
+        let stats = PeerStats::from_node(&[], &metrics);
+        assert_eq!((stats.incoming, stats.outgoing), (8, 12));
+        assert_eq!(stats.duplex, Some(5));
+        assert_eq!(stats.state_count(PeerState::Hot), 5);
+        assert_eq!(stats.state_count(PeerState::Warm), 10);
+        assert_eq!(stats.state_count(PeerState::Cold), 20);
+        assert!(stats.peers_by_latency.is_empty());
+
+        // Connections win for directions and latency; the governor's state
+        // counts are kept
+        let peers = vec![connection("203.0.113.1", true)];
+        let stats = PeerStats::from_node(&peers, &metrics);
+        assert_eq!((stats.incoming, stats.outgoing), (1, 0));
+        assert_eq!(stats.latency_count(LatencyBucket::Unprobed), 1);
+        assert_eq!(stats.state_count(PeerState::Cold), 20);
     }
 
     #[test]
-    fn test_peer_stats() {
-        let mut peers = vec![];
-        for i in 0..10 {
-            let mut peer = Peer::new(if i < 5 {
-                PeerDirection::Incoming
-            } else {
-                PeerDirection::Outgoing
+    fn test_classify_connections() {
+        use crate::propagation::ArrivalEvent;
+
+        let mut peers = vec![
+            connection("203.0.113.1", false),
+            connection("203.0.113.2", false),
+            connection("198.51.100.1", true),
+            connection("2001:db8::1", true),
+        ];
+        let mut propagation = PropagationTracker::default();
+        classify_connections(&mut peers, &propagation);
+        assert!(peers.iter().all(|p| p.peer_state.is_none()));
+
+        for peer in ["203.0.113.1:3001", "[2001:db8::1]:3001"] {
+            propagation.record(ArrivalEvent {
+                peer: peer.to_string(),
+                block: "8a3f1c".to_string(),
+                at_ms: 1_000,
             });
-            peer.state = Some(if i < 3 {
-                PeerState::Hot
-            } else if i < 7 {
-                PeerState::Warm
-            } else {
-                PeerState::Cold
-            });
-            peer.rtt_ms = Some((i * 20) as u64);
-            peers.push(peer);
         }
+        classify_connections(&mut peers, &propagation);
+        let states: Vec<Option<PeerState>> = peers.iter().map(|p| p.peer_state).collect();
+        assert_eq!(
+            states,
+            vec![
+                Some(PeerState::Hot),
+                Some(PeerState::Warm),
+                None,
+                Some(PeerState::Hot)
+            ]
+        );
+    }
+
+    fn connection(ip: &str, incoming: bool) -> PeerConnection {
+        PeerConnection {
+            ip: ip.to_string(),
+            port: 3001,
+            local_port: 3001,
+            incoming,
+            rtt_ms: None,
+            rtt_source: None,
+            rtt_probed: false,
+            state: "ESTABLISHED".to_string(),
+            recv_q: 0,
+            send_q: 0,
+            bytes_sent: None,
+            bytes_received: None,
+            send_rate: None,
+            recv_rate: None,
+            peer_state: None,
+        }
+    }
 
-        let stats = PeerStats::from_peers(&peers);
-        assert_eq!(stats.peers_by_state.get(&PeerState::Hot).unwrap_or(&0), &3);
-        assert_eq!(stats.peers_by_state.get(&PeerState::Warm).unwrap_or(&0), &4);
-        assert_eq!(stats.peers_by_state.get(&PeerState::Cold).unwrap_or(&0), &3);
-        assert_eq!(stats.reachable_count, 10);
+    #[test]
+    fn test_connection_ages() {
+        let start = Instant::now();
+        let a = connection("203.0.113.1", true);
+        let b = connection("203.0.113.2", false);
+        let mut ages = ConnectionAges::default();
+        ages.update(std::slice::from_ref(&a), start);
+        assert_eq!(ages.age(&a, start), Some((Duration::ZERO, true)));
+
+        // b appears a minute later; a was open before tracking started
+        let later = start + Duration::from_secs(60);
+        ages.update(&[a.clone(), b.clone()], later);
+        assert_eq!(ages.age(&a, later), Some((Duration::from_secs(60), true)));
+        assert_eq!(ages.age(&b, later), Some((Duration::ZERO, false)));
+
+        // a drops and comes back: its age restarts
+        ages.update(std::slice::from_ref(&b), later + Duration::from_secs(60));
+        let back = later + Duration::from_secs(120);
+        ages.update(&[a.clone(), b.clone()], back);
+        assert_eq!(ages.age(&a, back), Some((Duration::ZERO, false)));
+        assert_eq!(ages.age(&b, back), Some((Duration::from_secs(120), false)));
+
+        // After a long gap everything counts as already open
+        let resumed = back + MAX_SCAN_GAP + Duration::from_secs(1);
+        ages.update(std::slice::from_ref(&b), resumed);
+        assert_eq!(ages.age(&b, resumed), Some((Duration::ZERO, true)));
+        assert_eq!(ages.age(&a, resumed), None);
     }
 
     #[test]
-    fn test_peer_monitor() {
-        let mut monitor = PeerMonitor::new();
-        monitor.update_from_metrics(Some(5), Some(10), Some(20), Some(8), Some(12), Some(15));
+    fn test_group_peers() {
+        assert_eq!(subnet_of("203.0.113.77"), "203.0.113.0/24");
+        assert_eq!(subnet_of("2001:db8:1:2::5"), "2001:db8:1::/48");
+
+        let peers = vec![
+            connection("203.0.113.1", true),
+            connection("203.0.113.2", true),
+            connection("203.0.113.3", false),
+            connection("198.51.100.1", true),
+        ];
+        let groups = group_peers(&peers, PeerGrouping::Subnet, &HashMap::new());
+        assert_eq!(groups[0].key, "203.0.113.0/24");
+        assert_eq!((groups[0].incoming, groups[0].outgoing), (2, 1));
+
+        let networks = HashMap::from([("198.51.100.1".to_string(), "AS64500 Example".to_string())]);
+        let groups = group_peers(&peers, PeerGrouping::Network, &networks);
+        assert_eq!(groups[0].key, "Unknown");
+        assert_eq!(groups[1].key, "AS64500 Example");
+    }
 
-        assert_eq!(monitor.count(), 35);
+    #[test]
+    fn test_concentration_warning() {
+        let mut peers: Vec<PeerConnection> = (1..=5)
+            .map(|i| connection(&format!("203.0.113.{}", i), true))
+            .collect();
+        let networks = HashMap::new();
+        assert!(concentration_warning(&peers, &networks, 5, 50, 50)
+            .unwrap()
+            .contains("203.0.113.0/24"));
+        assert_eq!(concentration_warning(&peers, &networks, 6, 50, 50), None);
+
+        // 20 incoming from distinct subnets, 12 of them on one network
+        peers = (1..=20)
+            .map(|i| connection(&format!("198.51.{}.1", i), true))
+            .collect();
+        let networks: HashMap<String, String> = (1..=12)
+            .map(|i| (format!("198.51.{}.1", i), "AS64500 Example".to_string()))
+            .collect();
+        assert_eq!(
+            concentration_warning(&peers, &networks, 5, 50, 50).as_deref(),
+            Some("60% of incoming connections (12) from AS64500 Example")
+        );
+        assert_eq!(concentration_warning(&peers, &networks, 5, 0, 50), None);
+
+        // Outgoing: 6 of 10 on one network reaches 50%, but not 70%
+        let outgoing: Vec<PeerConnection> = (1..=10)
+            .map(|i| connection(&format!("198.51.{}.1", i), false))
+            .collect();
+        let networks: HashMap<String, String> = (1..=6)
+            .map(|i| (format!("198.51.{}.1", i), "AS24940 Hetzner".to_string()))
+            .collect();
         assert_eq!(
-            monitor
-                .stats()
-                .peers_by_state
-                .get(&PeerState::Hot)
-                .unwrap_or(&0),
-            &5
+            concentration_warning(&outgoing, &networks, 5, 50, 50).as_deref(),
+            Some("60% of outgoing connections (6) to AS24940 Hetzner")
         );
+        assert!(concentration_warning(&outgoing, &networks, 5, 50, 60).is_some());
+        assert_eq!(concentration_warning(&outgoing, &networks, 5, 50, 70), None);
         assert_eq!(
-            monitor
-                .stats()
-                .peers_by_state
-                .get(&PeerState::Warm)
-                .unwrap_or(&0),
-            &10
+            concentration_warning(&outgoing[..9], &networks, 5, 50, 50),
+            None
         );
+    }
+
+    #[test]
+    fn test_network_diversity() {
+        let peers: Vec<PeerConnection> = (1..=8)
+            .map(|i| connection(&format!("198.51.{}.1", i), i % 2 == 0))
+            .collect();
+        let networks: HashMap<String, String> = (1..=7)
+            .map(|i| {
+                let network = match i {
+                    1..=4 => "AS24940 Hetzner",
+                    5 | 6 => "AS16509 Amazon",
+                    _ => "AS14061 DigitalOcean",
+                };
+                (format!("198.51.{}.1", i), network.to_string())
+            })
+            .collect();
+        let diversity = network_diversity(&peers, &networks, 2);
+        assert_eq!(diversity.connections, 8);
+        assert_eq!(diversity.resolved, 7);
+        assert_eq!(diversity.networks, 3);
         assert_eq!(
-            monitor
-                .stats()
-                .peers_by_state
-                .get(&PeerState::Cold)
-                .unwrap_or(&0),
-            &20
+            diversity.top,
+            vec![
+                ("AS24940 Hetzner".to_string(), 4),
+                ("AS16509 Amazon".to_string(), 2)
+            ]
         );
+        assert_eq!(diversity.percent(4), 50);
+        assert_eq!(NetworkDiversity::default().percent(1), 0);
     }
 }
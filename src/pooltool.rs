@@ -0,0 +1,335 @@
+//! Tip submission to pooltool.io (`[nodes.pooltool]`)
+//!
+//! The same report `cncli sendtip` makes: each time the node adopts a new
+//! tip, its block number, slot and hash are posted to pooltool's `sendstats`
+//! endpoint, which compares them with the tips other pools report to rank
+//! how quickly blocks reach this node. The block hash only appears in the
+//! node's log (the `AddedToCurrentChain` trace), so nodes need `log_file`;
+//! a tip is sent once the metrics report the same slot as the log.
+//!
+//! Sends run in the background. The last answer from pooltool, with its
+//! ranking of the pools that reported the block, is kept for the dashboard;
+//! failures are logged and not retried.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// pooltool's tip endpoint
+const SENDSTATS_URL: &str = "https://api.pooltool.io/v0/sendstats";
+
+/// Longest a single send may take
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `[nodes.pooltool]` in the config file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct PooltoolConfig {
+    /// pooltool API key (from your pooltool.io profile)
+    pub api_key: String,
+    /// Pool ID in hex, as pooltool shows it
+    pub pool_id: String,
+    /// Node identifier reported with each tip
+    #[serde(default)]
+    pub node_id: String,
+}
+
+/// A chain tip as reported to pooltool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tip {
+    pub block_no: u64,
+    pub slot_no: u64,
+    pub block_hash: String,
+}
+
+/// A pool's place among those that reported a block
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TipRank {
+    pub rank: u64,
+    pub pool_id: String,
+}
+
+/// Result of the last submission
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendStatus {
+    /// pooltool accepted the tip; its message, if any, and its ranking of
+    /// the pools that reported the block, best first
+    Accepted {
+        block_no: u64,
+        message: String,
+        ranking: Vec<TipRank>,
+    },
+    /// The request failed or pooltool rejected it
+    Failed(String),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SendStats<'a> {
+    api_key: &'a str,
+    pool_id: &'a str,
+    data: TipData<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TipData<'a> {
+    node_id: &'a str,
+    version: &'a str,
+    at: String,
+    block_no: u64,
+    slot_no: u64,
+    block_hash: &'a str,
+    platform: &'static str,
+}
+
+/// Sends one node's tips to pooltool
+#[derive(Debug, Clone)]
+pub struct PooltoolClient {
+    client: reqwest::Client,
+    url: String,
+    config: PooltoolConfig,
+    status: Arc<Mutex<Option<SendStatus>>>,
+}
+
+impl PooltoolClient {
+    pub fn new(config: &PooltoolConfig) -> Option<Self> {
+        Self::with_url(config, SENDSTATS_URL)
+    }
+
+    fn with_url(config: &PooltoolConfig, url: &str) -> Option<Self> {
+        let client = match reqwest::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .user_agent(concat!("sview/", env!("CARGO_PKG_VERSION")))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("pooltool submission disabled: {}", e);
+                return None;
+            }
+        };
+        Some(Self {
+            client,
+            url: url.to_string(),
+            config: config.clone(),
+            status: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Whether this client was created from `config`
+    pub fn is_for(&self, config: &PooltoolConfig) -> bool {
+        self.config == *config
+    }
+
+    /// This pool's rank in the last accepted tip's ranking, with the number
+    /// of pools ranked
+    pub fn rank(&self) -> Option<(u64, usize)> {
+        let Some(SendStatus::Accepted { ranking, .. }) = self.status() else {
+            return None;
+        };
+        let own = ranking
+            .iter()
+            .find(|entry| entry.pool_id.eq_ignore_ascii_case(&self.config.pool_id))?;
+        Some((own.rank, ranking.len()))
+    }
+
+    /// Result of the last completed submission
+    pub fn status(&self) -> Option<SendStatus> {
+        self.status.lock().ok()?.clone()
+    }
+
+    /// Submit a tip in the background
+    pub fn send(&self, tip: &Tip, version: &str) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let body = SendStats {
+            api_key: &self.config.api_key,
+            pool_id: &self.config.pool_id,
+            data: TipData {
+                node_id: &self.config.node_id,
+                version,
+                at: chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                block_no: tip.block_no,
+                slot_no: tip.slot_no,
+                block_hash: &tip.block_hash,
+                platform: "sview",
+            },
+        };
+        let request = self.client.post(&self.url).json(&body);
+        let status = self.status.clone();
+        let block_no = tip.block_no;
+        runtime.spawn(async move {
+            let result = match request.send().await {
+                Ok(response) => response
+                    .json::<serde_json::Value>()
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| parse_response(&json)),
+                Err(e) => Err(e.to_string()),
+            };
+            let result = match result {
+                Ok((message, ranking)) => {
+                    debug!("pooltool accepted block {}: {}", block_no, message);
+                    SendStatus::Accepted {
+                        block_no,
+                        message,
+                        ranking,
+                    }
+                }
+                Err(e) => {
+                    warn!("pooltool rejected block {}: {}", block_no, e);
+                    SendStatus::Failed(e)
+                }
+            };
+            if let Ok(mut status) = status.lock() {
+                *status = Some(result);
+            }
+        });
+    }
+}
+
+/// pooltool's message and ranking (`data.ranking`, sorted by rank) for an
+/// accepted tip, or why it was rejected
+fn parse_response(json: &serde_json::Value) -> Result<(String, Vec<TipRank>), String> {
+    let message = json
+        .get("message")
+        .and_then(|m| m.as_str())
+        .unwrap_or_default()
+        .to_string();
+    match json.get("success").and_then(|s| s.as_bool()) {
+        Some(true) => {
+            let mut ranking: Vec<TipRank> = json
+                .pointer("/data/ranking")
+                .and_then(|r| serde_json::from_value(r.clone()).ok())
+                .unwrap_or_default();
+            // Not necessarily in order in the answer
+            ranking.sort_by_key(|entry| entry.rank);
+            Ok((message, ranking))
+        }
+        _ if message.is_empty() => Err("unexpected response".to_string()),
+        _ => Err(message),
+    }
+}
+
+/// The new tip from an `AddedToCurrentChain` log line: (block hash, slot)
+pub fn parse_new_tip(line: &str) -> Option<(String, u64)> {
+    // Cheap filter before parsing JSON
+    if !line.contains("AddedToCurrentChain") {
+        return None;
+    }
+    let json: serde_json::Value = serde_json::from_str(line).ok()?;
+    // Legacy and new tracing both give the tip as "hash@slot"
+    let newtip = json.get("data")?.get("newtip")?.as_str()?;
+    let (hash, slot) = newtip.split_once('@')?;
+    Some((hash.to_string(), slot.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_new_tip() {
+        let legacy = r#"{"at":"2024-05-12T10:11:12.34Z","data":{"kind":"TraceAddBlockEvent.AddedToCurrentChain","newtip":"8a3f1c@123456789"}}"#;
+        assert_eq!(
+            parse_new_tip(legacy),
+            Some(("8a3f1c".to_string(), 123_456_789))
+        );
+        let tracing = r#"{"at":"2024-05-12T10:11:12.34Z","ns":"ChainDB.AddBlockEvent.AddedToCurrentChain","data":{"kind":"AddedToCurrentChain","newtip":"b7e2@42"}}"#;
+        assert_eq!(parse_new_tip(tracing), Some(("b7e2".to_string(), 42)));
+        assert_eq!(parse_new_tip(r#"{"data":{"kind":"Other"}}"#), None);
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let ok = serde_json::json!({"success": true, "message": "Tip recorded"});
+        assert_eq!(
+            parse_response(&ok),
+            Ok(("Tip recorded".to_string(), Vec::new()))
+        );
+        let ranked = serde_json::json!({
+            "success": true,
+            "message": "",
+            "data": {"ranking": [
+                {"rank": 3, "poolId": "00beef"},
+                {"rank": 1, "poolId": "00cafe"},
+                {"rank": 2, "poolId": "00f00d"},
+            ]},
+        });
+        let (_, ranking) = parse_response(&ranked).unwrap();
+        let ranks: Vec<u64> = ranking.iter().map(|entry| entry.rank).collect();
+        assert_eq!(ranks, [1, 2, 3]);
+        assert_eq!(ranking[2].pool_id, "00beef");
+        let rejected = serde_json::json!({"success": false, "message": "Invalid API key"});
+        assert_eq!(
+            parse_response(&rejected),
+            Err("Invalid API key".to_string())
+        );
+        assert!(parse_response(&serde_json::json!({})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_tip() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let received = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).ends_with("}}") {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let body = r#"{"success":true,"message":"ok","data":{"ranking":[{"rank":2,"poolId":"00BEEF"},{"rank":1,"poolId":"00cafe"}]}}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let config = PooltoolConfig {
+            api_key: "key-123".to_string(),
+            pool_id: "00beef".to_string(),
+            node_id: String::new(),
+        };
+        let url = format!("http://127.0.0.1:{}/v0/sendstats", port);
+        let client = PooltoolClient::with_url(&config, &url).unwrap();
+        client.send(
+            &Tip {
+                block_no: 10_500_000,
+                slot_no: 123_456_789,
+                block_hash: "8a3f1c".to_string(),
+            },
+            "10.1.4",
+        );
+
+        let request = received.await.unwrap();
+        let body = request.split("\r\n\r\n").nth(1).unwrap();
+        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(json["apiKey"], "key-123");
+        assert_eq!(json["poolId"], "00beef");
+        assert_eq!(json["data"]["blockNo"], 10_500_000);
+        assert_eq!(json["data"]["blockHash"], "8a3f1c");
+        assert_eq!(json["data"]["version"], "10.1.4");
+
+        for _ in 0..50 {
+            if client.status().is_some() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(matches!(
+            client.status(),
+            Some(SendStatus::Accepted { block_no: 10_500_000, ref message, .. }) if message == "ok"
+        ));
+        assert_eq!(client.rank(), Some((2, 2)));
+    }
+}
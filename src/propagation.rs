@@ -0,0 +1,342 @@
+//! Per-peer block propagation from the node's log
+//!
+//! With the ChainSync client and BlockFetch client tracers enabled, the node
+//! logs a line each time a peer announces a header (`DownloadedHeader`) or
+//! delivers a block (`CompletedBlockFetch`). Tailing the JSON log and
+//! comparing arrival times per block shows which upstream peers deliver new
+//! blocks first and how far behind the others are.
+//!
+//! Both the legacy (iohk-monitoring) and the new tracing system's JSON
+//! formats are understood.
+
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Blocks kept for the ranking
+const MAX_TRACKED_BLOCKS: usize = 200;
+
+/// How much existing log is read when sview starts
+const INITIAL_BACKLOG_BYTES: u64 = 1024 * 1024;
+
+/// Upper bound on log data read per poll
+const MAX_READ_BYTES: u64 = 8 * 1024 * 1024;
+
+/// A peer announcing or delivering a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrivalEvent {
+    /// Remote address ("ip:port")
+    pub peer: String,
+    /// Block (header) hash
+    pub block: String,
+    /// Log timestamp in milliseconds since the Unix epoch
+    pub at_ms: i64,
+}
+
+/// Parse a JSON log line into an arrival event, if it is one
+pub fn parse_log_line(line: &str) -> Option<ArrivalEvent> {
+    // Cheap filter before parsing JSON
+    if !line.contains("DownloadedHeader") && !line.contains("CompletedBlockFetch") {
+        return None;
+    }
+    let json: serde_json::Value = serde_json::from_str(line).ok()?;
+    let data = json.get("data")?;
+    let kind = data.get("kind")?.as_str()?;
+    if !kind.ends_with("DownloadedHeader") && !kind.ends_with("CompletedBlockFetch") {
+        return None;
+    }
+
+    let block = match data.get("block")? {
+        serde_json::Value::String(hash) => hash.clone(),
+        other => other.get("hash")?.as_str()?.to_string(),
+    };
+    Some(ArrivalEvent {
+        peer: parse_peer(data.get("peer")?)?,
+        block,
+        at_ms: parse_timestamp_ms(json.get("at")?.as_str()?)?,
+    })
+}
+
+/// Remote address from the different peer encodings
+fn parse_peer(peer: &serde_json::Value) -> Option<String> {
+    // New tracing: {"connectionId": "<local> <remote>"}
+    if let Some(conn) = peer.get("connectionId").and_then(|v| v.as_str()) {
+        return conn.split_whitespace().last().map(String::from);
+    }
+    // Legacy: {"remote": {"addr": "1.2.3.4", "port": "3001"}}
+    if let Some(remote) = peer.get("remote") {
+        let addr = remote.get("addr")?.as_str()?;
+        let port = match remote.get("port")? {
+            serde_json::Value::String(p) => p.clone(),
+            other => other.to_string(),
+        };
+        // Bracket IPv6 so the port can be split off again
+        if addr.contains(':') {
+            return Some(format!("[{}]:{}", addr, port));
+        }
+        return Some(format!("{}:{}", addr, port));
+    }
+    // Show instance: "ConnectionId {localAddress = ..., remoteAddress = 1.2.3.4:3001}"
+    let text = peer.as_str()?;
+    let remote = text.split("remoteAddress = ").nth(1)?;
+    Some(remote.trim_end_matches('}').trim().to_string())
+}
+
+/// Parse an RFC 3339 timestamp ("2024-05-12T10:11:12.345Z") to milliseconds
+fn parse_timestamp_ms(s: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|at| at.timestamp_millis())
+}
+
+/// Delivery statistics for one peer
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeerPropagation {
+    /// Remote address ("ip:port")
+    pub peer: String,
+    /// Blocks this peer announced or delivered
+    pub blocks: usize,
+    /// Blocks this peer was first to announce
+    pub first: usize,
+    /// Mean delay behind the first peer, in milliseconds
+    pub avg_delay_ms: f64,
+    /// Median delay behind the first peer, in milliseconds
+    pub median_delay_ms: i64,
+}
+
+/// Arrival times of recent blocks per peer
+#[derive(Debug, Default)]
+pub struct PropagationTracker {
+    /// (block hash, peer -> earliest arrival in ms), oldest first
+    blocks: VecDeque<(String, HashMap<String, i64>)>,
+}
+
+impl PropagationTracker {
+    pub fn record(&mut self, event: ArrivalEvent) {
+        let arrivals = match self
+            .blocks
+            .iter_mut()
+            .rev()
+            .find(|(b, _)| *b == event.block)
+        {
+            Some((_, arrivals)) => arrivals,
+            None => {
+                if self.blocks.len() >= MAX_TRACKED_BLOCKS {
+                    self.blocks.pop_front();
+                }
+                self.blocks.push_back((event.block, HashMap::new()));
+                &mut self.blocks.back_mut().expect("just pushed").1
+            }
+        };
+        let at = arrivals.entry(event.peer).or_insert(event.at_ms);
+        *at = (*at).min(event.at_ms);
+    }
+
+    /// Number of blocks in the window
+    pub fn block_count(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Peers ("ip:port") that announced or delivered one of the last `blocks` blocks
+    pub fn recent_peers(&self, blocks: usize) -> HashSet<String> {
+        self.blocks
+            .iter()
+            .rev()
+            .take(blocks)
+            .flat_map(|(_, arrivals)| arrivals.keys().cloned())
+            .collect()
+    }
+
+    /// Peers ranked by how often they deliver first, then by mean delay
+    pub fn ranking(&self) -> Vec<PeerPropagation> {
+        let mut delays: HashMap<&str, Vec<i64>> = HashMap::new();
+        for (_, arrivals) in &self.blocks {
+            let Some(first) = arrivals.values().min() else {
+                continue;
+            };
+            for (peer, at) in arrivals {
+                delays.entry(peer).or_default().push(at - first);
+            }
+        }
+
+        let mut ranking: Vec<PeerPropagation> = delays
+            .into_iter()
+            .map(|(peer, mut delays)| {
+                delays.sort_unstable();
+                PeerPropagation {
+                    peer: peer.to_string(),
+                    blocks: delays.len(),
+                    first: delays.iter().filter(|d| **d == 0).count(),
+                    avg_delay_ms: delays.iter().sum::<i64>() as f64 / delays.len() as f64,
+                    median_delay_ms: delays[delays.len() / 2],
+                }
+            })
+            .collect();
+        ranking.sort_by(|a, b| {
+            b.first.cmp(&a.first).then_with(|| {
+                a.avg_delay_ms
+                    .partial_cmp(&b.avg_delay_ms)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+        });
+        ranking
+    }
+}
+
+/// Incremental reader for a growing log file
+///
+/// Starts near the end of the file, and starts over if the file shrinks
+/// (rotated or truncated).
+#[derive(Debug)]
+pub struct LogTail {
+    path: PathBuf,
+    offset: Option<u64>,
+    /// Bytes after the last newline, kept raw so a character split across
+    /// reads is decoded once the line is complete
+    partial: Vec<u8>,
+}
+
+impl LogTail {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: None,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Complete lines appended since the last call
+    pub fn read_lines(&mut self) -> Result<Vec<String>> {
+        let mut file = File::open(&self.path)
+            .with_context(|| format!("Failed to open log file {}", self.path.display()))?;
+        let len = file.metadata()?.len();
+
+        let (start, skip_first) = match self.offset {
+            Some(offset) if offset <= len => (offset, false),
+            Some(_) => {
+                self.partial.clear();
+                (0, false)
+            }
+            None => {
+                let start = len.saturating_sub(INITIAL_BACKLOG_BYTES);
+                (start, start > 0)
+            }
+        };
+
+        file.seek(SeekFrom::Start(start))?;
+        let mut buf = Vec::new();
+        file.take(MAX_READ_BYTES).read_to_end(&mut buf)?;
+        self.offset = Some(start + buf.len() as u64);
+
+        let mut bytes = std::mem::take(&mut self.partial);
+        bytes.extend_from_slice(&buf);
+        let mut pieces: Vec<&[u8]> = bytes.split(|&b| b == b'\n').collect();
+        // The last piece is incomplete (empty if the chunk ended on a newline)
+        self.partial = pieces.pop().unwrap_or_default().to_vec();
+        let mut lines: Vec<String> = pieces
+            .into_iter()
+            .map(|line| String::from_utf8_lossy(line).into_owned())
+            .collect();
+        if skip_first && !lines.is_empty() {
+            lines.remove(0);
+        }
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const NEW_HEADER: &str = r#"{"at":"2024-05-12T10:11:12.345678Z","ns":"ChainSync.Client.DownloadedHeader","data":{"block":"abc123","blockNo":100,"kind":"DownloadedHeader","peer":{"connectionId":"10.0.0.1:3001 203.0.113.5:3001"},"slot":5000},"sev":"Info"}"#;
+    const LEGACY_FETCH: &str = r#"{"at":"2024-05-12T10:11:12.90Z","ns":["cardano.node.BlockFetchClient"],"data":{"block":"abc123","delay":0.9,"kind":"CompletedBlockFetch","peer":{"local":{"addr":"10.0.0.1","port":"3001"},"remote":{"addr":"198.51.100.7","port":"6000"}},"size":900}}"#;
+
+    #[test]
+    fn test_parse_log_line() {
+        assert_eq!(
+            parse_log_line(NEW_HEADER),
+            Some(ArrivalEvent {
+                peer: "203.0.113.5:3001".to_string(),
+                block: "abc123".to_string(),
+                at_ms: 1_715_508_672_345,
+            })
+        );
+        let fetch = parse_log_line(LEGACY_FETCH).unwrap();
+        assert_eq!(fetch.peer, "198.51.100.7:6000");
+        assert_eq!(fetch.at_ms, 1_715_508_672_900);
+        let ipv6 = LEGACY_FETCH.replace("198.51.100.7", "2001:db8::7");
+        assert_eq!(parse_log_line(&ipv6).unwrap().peer, "[2001:db8::7]:6000");
+
+        assert_eq!(
+            parse_log_line(r#"{"data":{"kind":"TraceAddBlockEvent"}}"#),
+            None
+        );
+        assert_eq!(parse_timestamp_ms("1970-01-01T00:00:01Z"), Some(1000));
+        assert_eq!(
+            parse_timestamp_ms("1970-01-01T02:00:01.5+02:00"),
+            Some(1500)
+        );
+        assert_eq!(parse_timestamp_ms("1970-01-01 00:00:01"), None);
+    }
+
+    #[test]
+    fn test_ranking() {
+        let mut tracker = PropagationTracker::default();
+        for (peer, block, at_ms) in [
+            ("a", "b1", 1000),
+            ("b", "b1", 1300),
+            ("b", "b2", 2000),
+            ("a", "b2", 2100),
+            ("a", "b3", 3000),
+            ("b", "b3", 3500),
+            ("a", "b3", 3600),
+        ] {
+            tracker.record(ArrivalEvent {
+                peer: peer.to_string(),
+                block: block.to_string(),
+                at_ms,
+            });
+        }
+
+        let ranking = tracker.ranking();
+        assert_eq!(tracker.block_count(), 3);
+        assert_eq!(ranking[0].peer, "a");
+        assert_eq!((ranking[0].blocks, ranking[0].first), (3, 2));
+        assert!((ranking[0].avg_delay_ms - 100.0 / 3.0).abs() < 1e-9);
+        assert_eq!(ranking[1].median_delay_ms, 300);
+    }
+
+    #[test]
+    fn test_log_tail() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("node.json");
+        std::fs::write(&path, "old line\n").unwrap();
+
+        let mut tail = LogTail::new(&path);
+        assert_eq!(tail.read_lines().unwrap(), vec!["old line"]);
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        write!(file, "one\ntw").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["one"]);
+        writeln!(file, "o").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["two"]);
+
+        // A character split across reads survives
+        let pool = "pool \u{2713}\n".as_bytes();
+        file.write_all(&pool[..6]).unwrap();
+        assert!(tail.read_lines().unwrap().is_empty());
+        file.write_all(&pool[6..]).unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["pool \u{2713}"]);
+
+        // Rotation: the file starts over
+        std::fs::write(&path, "new\n").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["new"]);
+    }
+}
@@ -0,0 +1,135 @@
+//! Network tip reference (`[global.reference_tip]`)
+//!
+//! Tip age and sync progress compare a node's tip with the wall clock, which
+//! can't tell a quiet stretch of the chain from a node that fell behind. With
+//! a reference, each node's tip is compared with the tip seen elsewhere:
+//! the chain API (Koios, or Blockfrost with `blockfrost_project_id`) or a
+//! trusted node from `[[nodes]]`. The difference is the node's lag in blocks,
+//! and in seconds from the slots between the two tips.
+//!
+//! The chain API is polled every `interval_secs`; a trusted node is read
+//! after every refresh. A node that has moved past a stale reference counts
+//! as level with it.
+
+use serde::Deserialize;
+
+/// Where the reference tip comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceSource {
+    /// Koios or Blockfrost, as configured for pool data
+    Api,
+    /// The monitored node named by `node`
+    Node,
+}
+
+/// `[global.reference_tip]` in the config file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReferenceTipConfig {
+    /// Reference source; off when unset
+    pub source: Option<ReferenceSource>,
+    /// Name of the trusted node (`source = "node"`)
+    pub node: Option<String>,
+    /// Seconds between chain API lookups
+    #[serde(default = "default_interval")]
+    pub interval_secs: u64,
+    /// Blocks behind the reference that raise an alert
+    #[serde(default = "default_max_lag_blocks")]
+    pub max_lag_blocks: u64,
+}
+
+fn default_interval() -> u64 {
+    30
+}
+
+fn default_max_lag_blocks() -> u64 {
+    3
+}
+
+impl Default for ReferenceTipConfig {
+    fn default() -> Self {
+        Self {
+            source: None,
+            node: None,
+            interval_secs: default_interval(),
+            max_lag_blocks: default_max_lag_blocks(),
+        }
+    }
+}
+
+/// A network tip to compare nodes against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReferenceTip {
+    pub block_height: u64,
+    pub slot: u64,
+}
+
+impl ReferenceTip {
+    /// Blocks and slots a tip at `block_height`/`slot` trails this one by
+    /// (zero when level or ahead)
+    pub fn lag(&self, block_height: u64, slot: u64) -> (u64, u64) {
+        (
+            self.block_height.saturating_sub(block_height),
+            self.slot.saturating_sub(slot),
+        )
+    }
+}
+
+/// The tip from Koios `/tip` (a one-element array)
+pub fn parse_koios_tip(json: &serde_json::Value) -> Option<ReferenceTip> {
+    let tip = json.get(0)?;
+    Some(ReferenceTip {
+        // `block_no` before Koios v1
+        block_height: tip
+            .get("block_height")
+            .or_else(|| tip.get("block_no"))?
+            .as_u64()?,
+        slot: tip.get("abs_slot")?.as_u64()?,
+    })
+}
+
+/// The tip from Blockfrost `/blocks/latest`
+pub fn parse_blockfrost_tip(json: &serde_json::Value) -> Option<ReferenceTip> {
+    Some(ReferenceTip {
+        block_height: json.get("height")?.as_u64()?,
+        slot: json.get("slot")?.as_u64()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tips() {
+        let koios = serde_json::json!([{
+            "hash": "8a3f1c",
+            "epoch_no": 530,
+            "abs_slot": 140_000_620,
+            "epoch_slot": 280_620,
+            "block_height": 11_200_030,
+            "block_time": 1_731_566_911
+        }]);
+        let tip = parse_koios_tip(&koios).unwrap();
+        assert_eq!(tip.block_height, 11_200_030);
+        assert_eq!(tip.slot, 140_000_620);
+        assert!(parse_koios_tip(&serde_json::json!([])).is_none());
+
+        let blockfrost =
+            serde_json::json!({"height": 11_200_030, "slot": 140_000_620, "hash": "8a3f1c"});
+        assert_eq!(parse_blockfrost_tip(&blockfrost), Some(tip));
+
+        assert_eq!(tip.lag(11_200_027, 140_000_560), (3, 60));
+        assert_eq!(tip.lag(11_200_031, 140_000_640), (0, 0));
+    }
+
+    #[test]
+    fn test_config() {
+        let config: ReferenceTipConfig =
+            toml::from_str("source = \"node\"\nnode = \"Relay 2\"").unwrap();
+        assert_eq!(config.source, Some(ReferenceSource::Node));
+        assert_eq!(config.interval_secs, 30);
+        assert_eq!(config.max_lag_blocks, 3);
+        assert!(ReferenceTipConfig::default().source.is_none());
+    }
+}
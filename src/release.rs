@@ -0,0 +1,120 @@
+//! Latest cardano-node release check (`check_node_release = true`)
+//!
+//! Looks up the newest cardano-node release on GitHub at most once a day,
+//! caching the answer in the data directory so restarts don't query again,
+//! and compares it with each node's version from `cardano_build_info`.
+
+use crate::hardfork::compare_versions;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// GitHub API endpoint for the newest non-prerelease cardano-node release
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/IntersectMBO/cardano-node/releases/latest";
+
+/// How long a looked-up release is trusted
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(86400);
+
+/// The newest cardano-node release
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatestRelease {
+    /// Version without a leading "v" (e.g. "10.1.4")
+    pub version: String,
+    /// Release page
+    pub url: Option<String>,
+    /// Unix timestamp of the lookup
+    pub checked_at: u64,
+}
+
+impl LatestRelease {
+    /// Whether a node running `version` is behind this release
+    pub fn is_newer_than(&self, version: &str) -> bool {
+        compare_versions(version, &self.version) == Ordering::Less
+    }
+}
+
+/// Where the last lookup is cached
+pub fn cache_path() -> PathBuf {
+    crate::storage::get_data_dir().join("latest_release.json")
+}
+
+/// The cached release if it was looked up within `CHECK_INTERVAL`, else a fresh one
+pub async fn latest_release(cache: &Path, now: u64) -> Result<LatestRelease> {
+    let cached = std::fs::read_to_string(cache)
+        .ok()
+        .and_then(|json| serde_json::from_str::<LatestRelease>(&json).ok())
+        .filter(|r| now.saturating_sub(r.checked_at) < CHECK_INTERVAL.as_secs());
+    if let Some(release) = cached {
+        return Ok(release);
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent(concat!("sview/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+    let response = client
+        .get(LATEST_RELEASE_URL)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .context("GitHub release request failed")?
+        .error_for_status()
+        .context("GitHub release request failed")?;
+    let json: serde_json::Value = response.json().await?;
+    let release = parse_release(&json, now).context("Unexpected GitHub release response")?;
+
+    if let Some(dir) = cache.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(cache, serde_json::to_string(&release)?)
+        .with_context(|| format!("Failed to write {}", cache.display()))?;
+    Ok(release)
+}
+
+fn parse_release(json: &serde_json::Value, now: u64) -> Option<LatestRelease> {
+    let tag = json.get("tag_name")?.as_str()?;
+    Some(LatestRelease {
+        version: tag.trim_start_matches('v').to_string(),
+        url: json
+            .get("html_url")
+            .and_then(|u| u.as_str())
+            .map(String::from),
+        checked_at: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_release() {
+        let json = serde_json::json!({
+            "tag_name": "10.1.4",
+            "html_url": "https://github.com/IntersectMBO/cardano-node/releases/tag/10.1.4",
+            "prerelease": false
+        });
+        let release = parse_release(&json, 1000).unwrap();
+        assert_eq!(release.version, "10.1.4");
+        assert_eq!(release.checked_at, 1000);
+        assert!(release.is_newer_than("10.1.3"));
+        assert!(!release.is_newer_than("10.1.4"));
+        assert!(parse_release(&serde_json::json!({}), 1000).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fresh_cache_is_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = dir.path().join("latest_release.json");
+        let release = LatestRelease {
+            version: "10.1.4".to_string(),
+            url: None,
+            checked_at: 1000,
+        };
+        std::fs::write(&cache, serde_json::to_string(&release).unwrap()).unwrap();
+        assert_eq!(latest_release(&cache, 2000).await.unwrap(), release);
+    }
+}
@@ -0,0 +1,428 @@
+//! Reports built from stored history (`sview report sla --range 30d`)
+//!
+//! The SLA report combines each node's snapshots with its recorded outages
+//! into uptime, longest outage, average peers and tip age, and blocks forged,
+//! printed as a text table, JSON or a Markdown table for delegator reports.
+//!
+//! Uptime only covers the time sview was collecting: the window starts at the
+//! node's first sample or outage in the range, and gaps in the history longer
+//! than `MAX_SAMPLE_GAP_SECS` (sview wasn't running) are left out.
+
+use crate::config::AppConfig;
+use crate::format::format_duration;
+use crate::storage::{MetricSnapshot, Outage, StorageManager};
+use crate::timezone::DisplayTimezone;
+use anyhow::Result;
+use serde::Serialize;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Longest gap between samples still counted as monitored time
+const MAX_SAMPLE_GAP_SECS: u64 = 900;
+
+/// Report to produce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportKind {
+    /// Per-node availability: uptime, outages, peers, tip age, blocks forged
+    Sla,
+}
+
+/// Output format for --report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ReportFormat {
+    #[default]
+    Text,
+    Json,
+    Markdown,
+}
+
+/// A report requested on the command line
+#[derive(Debug, Clone)]
+pub struct ReportRequest {
+    pub kind: ReportKind,
+    pub format: ReportFormat,
+    /// How far back the report reaches
+    pub range: Duration,
+}
+
+/// Availability figures for one node
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SlaRow {
+    pub node: String,
+    /// Start of the data within the range (unix seconds)
+    pub monitored_since: Option<u64>,
+    /// Seconds sview was collecting data within the range
+    pub monitored_secs: u64,
+    pub uptime_percent: Option<f64>,
+    pub downtime_secs: u64,
+    pub outages: usize,
+    pub longest_outage_secs: u64,
+    pub avg_peers: Option<f64>,
+    pub avg_tip_age_secs: Option<f64>,
+    /// Blocks forged and adopted (`None` for nodes that don't report forging)
+    pub blocks_forged: Option<u64>,
+}
+
+/// The whole report, as written in JSON
+#[derive(Debug, Serialize)]
+struct SlaReport {
+    generated_at: u64,
+    range_secs: u64,
+    nodes: Vec<SlaRow>,
+}
+
+/// Build the requested report for every configured node and print it
+pub fn run(app_config: &AppConfig, request: &ReportRequest) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let range_secs = request.range.as_secs();
+    let since = now.saturating_sub(range_secs);
+
+    let mut rows = Vec::new();
+    for node in &app_config.nodes {
+        let storage = StorageManager::new(&node.name).with_policy(node.retention);
+        let snapshots = match storage.load_history(usize::MAX) {
+            Ok(snapshots) => snapshots,
+            Err(e) => {
+                eprintln!("  [{}] Failed to load history: {}", node.name, e);
+                continue;
+            }
+        };
+        let outages = match storage.load_outages(since) {
+            Ok(outages) => outages,
+            Err(e) => {
+                eprintln!("  [{}] Failed to load outages: {}", node.name, e);
+                continue;
+            }
+        };
+        let max_gap = MAX_SAMPLE_GAP_SECS.max(2 * node.retention.sample_interval_secs);
+        rows.push(sla_row(
+            &node.name, &snapshots, &outages, since, now, max_gap,
+        ));
+    }
+
+    let output = match request.kind {
+        ReportKind::Sla => match request.format {
+            ReportFormat::Text => sla_text(&rows, range_secs, app_config.timezone),
+            ReportFormat::Markdown => sla_markdown(&rows, range_secs, app_config.timezone),
+            ReportFormat::Json => {
+                let report = SlaReport {
+                    generated_at: now,
+                    range_secs,
+                    nodes: rows,
+                };
+                serde_json::to_string_pretty(&report)? + "\n"
+            }
+        },
+    };
+    print!("{}", output);
+    Ok(())
+}
+
+/// Summarize one node's snapshots and outages between `since` and `now`
+///
+/// Gaps longer than `max_gap` between samples and outages don't count as
+/// monitored time.
+pub fn sla_row(
+    node: &str,
+    snapshots: &[MetricSnapshot],
+    outages: &[Outage],
+    since: u64,
+    now: u64,
+    max_gap: u64,
+) -> SlaRow {
+    let snapshots: Vec<&MetricSnapshot> = snapshots
+        .iter()
+        .filter(|s| s.timestamp >= since && s.timestamp <= now)
+        .collect();
+    // Outages clipped to the range
+    let outages: Vec<(u64, u64)> = outages
+        .iter()
+        .filter(|o| o.end >= since && o.start <= now)
+        .map(|o| (o.start.max(since), o.end.min(now)))
+        .collect();
+
+    let mut row = SlaRow {
+        node: node.to_string(),
+        ..Default::default()
+    };
+    row.monitored_since = snapshots
+        .iter()
+        .map(|s| s.timestamp)
+        .chain(outages.iter().map(|(start, _)| *start))
+        .min();
+    if row.monitored_since.is_none() {
+        return row;
+    }
+    row.monitored_secs = observed_secs(&snapshots, &outages, now, max_gap);
+
+    row.outages = outages.len();
+    row.downtime_secs = outages.iter().map(|(start, end)| end - start).sum();
+    row.longest_outage_secs = outages
+        .iter()
+        .map(|(start, end)| end - start)
+        .max()
+        .unwrap_or(0);
+    if row.monitored_secs > 0 {
+        let up = row.monitored_secs.saturating_sub(row.downtime_secs);
+        row.uptime_percent = Some(up as f64 / row.monitored_secs as f64 * 100.0);
+    }
+
+    row.avg_peers = mean(snapshots.iter().filter_map(|s| s.peers_connected));
+    row.avg_tip_age_secs = mean(snapshots.iter().filter_map(|s| s.tip_age_secs));
+    row.blocks_forged = counter_increase(snapshots.iter().filter_map(|s| s.blocks_adopted));
+    row
+}
+
+/// Seconds covered by samples and outages, leaving out gaps over `max_gap`
+fn observed_secs(
+    snapshots: &[&MetricSnapshot],
+    outages: &[(u64, u64)],
+    now: u64,
+    max_gap: u64,
+) -> u64 {
+    let mut spans: Vec<(u64, u64)> = snapshots
+        .iter()
+        .map(|s| (s.timestamp, (s.timestamp + s.span_secs).min(now)))
+        .chain(outages.iter().copied())
+        // Still collecting unless the last sample is too old
+        .chain(std::iter::once((now, now)))
+        .collect();
+    spans.sort_unstable();
+
+    let mut observed = 0;
+    let (mut start, mut end) = spans[0];
+    for &(span_start, span_end) in &spans[1..] {
+        if span_start > end + max_gap {
+            observed += end - start;
+            start = span_start;
+        }
+        end = end.max(span_end);
+    }
+    observed + (end - start)
+}
+
+fn mean(values: impl Iterator<Item = u64>) -> Option<f64> {
+    let (sum, count) = values.fold((0u64, 0u64), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum as f64 / count as f64)
+}
+
+/// Total increase of a counter that restarts from zero with the node
+fn counter_increase(values: impl Iterator<Item = u64>) -> Option<u64> {
+    let mut total = None;
+    let mut previous: Option<u64> = None;
+    for value in values {
+        let increase = match previous {
+            Some(prev) if value >= prev => value - prev,
+            // Restarted: everything since the restart counts
+            Some(_) => value,
+            None => 0,
+        };
+        total = Some(total.unwrap_or(0) + increase);
+        previous = Some(value);
+    }
+    total
+}
+
+fn sla_text(rows: &[SlaRow], range_secs: u64, timezone: DisplayTimezone) -> String {
+    let mut out = format!("SLA report: last {}\n\n", format_range(range_secs));
+    out.push_str(&format!(
+        "{:<20} {:>8} {:>7} {:>9} {:>9} {:>9} {:>8} {:>7}  MONITORED SINCE\n",
+        "NODE", "UPTIME", "OUTAGES", "LONGEST", "DOWNTIME", "AVG PEERS", "TIP AGE", "FORGED"
+    ));
+    for row in rows {
+        out.push_str(&format!(
+            "{:<20} {:>8} {:>7} {:>9} {:>9} {:>9} {:>8} {:>7}  {}\n",
+            row.node,
+            format_uptime(row.uptime_percent),
+            row.outages,
+            format_duration(row.longest_outage_secs),
+            format_duration(row.downtime_secs),
+            format_opt(row.avg_peers, 1, ""),
+            format_opt(row.avg_tip_age_secs, 0, "s"),
+            format_forged(row.blocks_forged),
+            format_since(row.monitored_since, timezone),
+        ));
+    }
+    out
+}
+
+fn sla_markdown(rows: &[SlaRow], range_secs: u64, timezone: DisplayTimezone) -> String {
+    let mut out = format!("## SLA report: last {}\n\n", format_range(range_secs));
+    out.push_str(
+        "| Node | Uptime | Outages | Longest outage | Downtime | Avg peers | Avg tip age | Blocks forged | Monitored since |\n",
+    );
+    out.push_str("|---|---:|---:|---:|---:|---:|---:|---:|---|\n");
+    for row in rows {
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {} | {} |\n",
+            row.node,
+            format_uptime(row.uptime_percent),
+            row.outages,
+            format_duration(row.longest_outage_secs),
+            format_duration(row.downtime_secs),
+            format_opt(row.avg_peers, 1, ""),
+            format_opt(row.avg_tip_age_secs, 0, "s"),
+            format_forged(row.blocks_forged),
+            format_since(row.monitored_since, timezone),
+        ));
+    }
+    out
+}
+
+fn format_range(secs: u64) -> String {
+    if secs.is_multiple_of(86400) {
+        format!("{} day(s)", secs / 86400)
+    } else {
+        format!("{} hour(s)", secs / 3600)
+    }
+}
+
+fn format_uptime(percent: Option<f64>) -> String {
+    percent
+        .map(|p| format!("{:.3}%", p))
+        .unwrap_or_else(|| "—".to_string())
+}
+
+fn format_opt(value: Option<f64>, decimals: usize, unit: &str) -> String {
+    value
+        .map(|v| format!("{:.*}{}", decimals, v, unit))
+        .unwrap_or_else(|| "—".to_string())
+}
+
+fn format_forged(blocks: Option<u64>) -> String {
+    blocks
+        .map(|b| b.to_string())
+        .unwrap_or_else(|| "—".to_string())
+}
+
+fn format_since(since: Option<u64>, timezone: DisplayTimezone) -> String {
+    since
+        .map(|ts| timezone.datetime(ts))
+        .unwrap_or_else(|| "no data".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(timestamp: u64, peers: u64, tip_age: u64, adopted: u64) -> MetricSnapshot {
+        MetricSnapshot {
+            timestamp,
+            peers_connected: Some(peers),
+            tip_age_secs: Some(tip_age),
+            blocks_adopted: Some(adopted),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_sla_row() {
+        let now = 1_700_000_000;
+        let since = now - 10_000;
+        let snapshots = vec![
+            snapshot(since - 500, 99, 99, 1), // before the range
+            snapshot(now - 8_000, 10, 20, 5),
+            snapshot(now - 4_000, 20, 40, 7),
+            snapshot(now - 1_000, 30, 30, 2), // node restarted
+        ];
+        let outages = vec![
+            Outage {
+                start: now - 6_000,
+                end: now - 5_800,
+            },
+            Outage {
+                start: now - 3_000,
+                end: now - 2_400,
+            },
+        ];
+
+        let row = sla_row("relay1", &snapshots, &outages, since, now, 3600);
+        assert_eq!(row.monitored_since, Some(now - 8_000));
+        assert_eq!(row.outages, 2);
+        assert_eq!(row.downtime_secs, 800);
+        assert_eq!(row.longest_outage_secs, 600);
+        assert_eq!(row.uptime_percent, Some(90.0));
+        assert_eq!(row.avg_peers, Some(20.0));
+        assert_eq!(row.avg_tip_age_secs, Some(30.0));
+        assert_eq!(row.blocks_forged, Some(4));
+
+        let empty = sla_row("relay2", &[], &[], since, now, 3600);
+        assert_eq!(empty.uptime_percent, None);
+        assert_eq!(empty.blocks_forged, None);
+    }
+
+    #[test]
+    fn test_outage_clipped_to_range() {
+        let now = 1_700_000_000;
+        let since = now - 1_000;
+        let outages = vec![Outage {
+            start: since - 500,
+            end: since + 100,
+        }];
+        let row = sla_row(
+            "bp",
+            &[snapshot(now - 10, 5, 1, 0)],
+            &outages,
+            since,
+            now,
+            MAX_SAMPLE_GAP_SECS,
+        );
+        assert_eq!(row.monitored_since, Some(since));
+        assert_eq!(row.downtime_secs, 100);
+        assert_eq!(row.uptime_percent, Some(90.0));
+    }
+
+    #[test]
+    fn test_unobserved_time_excluded() {
+        let now = 1_700_000_000;
+        let since = now - 10_000;
+        // sview ran for the first 4000s and the last 2000s of the range
+        let snapshots: Vec<MetricSnapshot> = (since..=now - 6_000)
+            .step_by(100)
+            .chain((now - 2_000..=now).step_by(100))
+            .map(|ts| snapshot(ts, 10, 5, 0))
+            .collect();
+        let outages = vec![Outage {
+            start: now - 1_000,
+            end: now - 700,
+        }];
+
+        let row = sla_row(
+            "relay1",
+            &snapshots,
+            &outages,
+            since,
+            now,
+            MAX_SAMPLE_GAP_SECS,
+        );
+        assert_eq!(row.monitored_since, Some(since));
+        assert_eq!(row.monitored_secs, 6_000);
+        assert_eq!(row.downtime_secs, 300);
+        assert_eq!(row.uptime_percent, Some(95.0));
+
+        // An hourly aggregate covers its whole hour
+        let hourly = MetricSnapshot {
+            span_secs: 3600,
+            ..snapshot(now - 7_200, 10, 5, 0)
+        };
+        let row = sla_row("relay1", &[hourly], &[], since, now, MAX_SAMPLE_GAP_SECS);
+        assert_eq!(row.monitored_secs, 3600);
+    }
+
+    #[test]
+    fn test_markdown_table() {
+        let row = SlaRow {
+            node: "bp".to_string(),
+            uptime_percent: Some(99.5),
+            outages: 1,
+            longest_outage_secs: 125,
+            downtime_secs: 125,
+            ..Default::default()
+        };
+        let md = sla_markdown(&[row], 30 * 86400, DisplayTimezone::Utc);
+        assert!(md.starts_with("## SLA report: last 30 day(s)"));
+        assert!(md.contains("| bp | 99.500% | 1 | 2m 5s | 2m 5s | — | — | — | no data |"));
+    }
+}
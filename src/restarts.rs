@@ -0,0 +1,162 @@
+//! Node restart history
+//!
+//! Each start time the node reports (`nodeStartTime`, or the scrape time less
+//! the uptime for nodes that only export `upTime`) is recorded in the data
+//! directory. A start time that differs from the last recorded one is a
+//! restart, including restarts that happened while sview wasn't running, so
+//! the counts hold up as evidence for an unstable host.
+
+use crate::metrics::NodeMetrics;
+use serde::{Deserialize, Serialize};
+
+/// Start times closer than this to the recorded one are the same start (the
+/// uptime-derived start time moves with scrape latency)
+const SAME_START_SECS: u64 = 60;
+
+/// A node start, as recorded in the restart history
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeStart {
+    /// Unix timestamp the node started at
+    pub start_time: u64,
+    /// Start recorded before this one; `None` for the first start seen
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_start: Option<u64>,
+}
+
+impl NodeStart {
+    /// Whether this start followed an earlier recorded one
+    pub fn is_restart(&self) -> bool {
+        self.previous_start.is_some()
+    }
+
+    /// Time from the previous start to this one: the previous run plus any
+    /// downtime before the restart
+    pub fn previous_run_secs(&self) -> Option<u64> {
+        Some(self.start_time.saturating_sub(self.previous_start?))
+    }
+}
+
+/// Start time reported by a metrics sample taken at `now`
+pub fn start_time(metrics: &NodeMetrics, now: u64) -> Option<u64> {
+    metrics
+        .node_start_time
+        .or_else(|| Some(now.saturating_sub(metrics.uptime_seconds? as u64)))
+}
+
+/// Recorded starts of one node
+#[derive(Debug, Clone, Default)]
+pub struct RestartHistory {
+    /// Oldest first
+    starts: Vec<NodeStart>,
+}
+
+impl RestartHistory {
+    /// History from recorded starts (oldest first)
+    pub fn new(starts: Vec<NodeStart>) -> Self {
+        Self { starts }
+    }
+
+    /// Feed the start time from a scrape; returns the start to record when
+    /// it's one not seen before
+    pub fn observe(&mut self, start_time: u64) -> Option<NodeStart> {
+        let previous = self.starts.last().map(|last| last.start_time);
+        if previous.is_some_and(|prev| prev.abs_diff(start_time) < SAME_START_SECS) {
+            return None;
+        }
+        let start = NodeStart {
+            start_time,
+            previous_start: previous,
+        };
+        self.starts.push(start);
+        Some(start)
+    }
+
+    /// The current run's start, if one has been seen
+    pub fn last(&self) -> Option<&NodeStart> {
+        self.starts.last()
+    }
+
+    /// Restarts at or after `since`
+    pub fn restarts_since(&self, since: u64) -> usize {
+        self.starts
+            .iter()
+            .filter(|s| s.is_restart() && s.start_time >= since)
+            .count()
+    }
+
+    /// Restarts per day over the `days` days up to `now` (oldest first)
+    pub fn daily_restarts(&self, now: u64, days: u64) -> Vec<usize> {
+        let since = now.saturating_sub(days * 86400);
+        let mut counts = vec![0; days as usize];
+        for start in self.starts.iter().filter(|s| s.is_restart()) {
+            if start.start_time < since || start.start_time > now {
+                continue;
+            }
+            let day = ((start.start_time - since) / 86400).min(days.saturating_sub(1));
+            counts[day as usize] += 1;
+        }
+        counts
+    }
+
+    /// Longest run recorded between two starts
+    pub fn longest_run_secs(&self) -> Option<u64> {
+        self.starts
+            .iter()
+            .filter_map(NodeStart::previous_run_secs)
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86400;
+
+    #[test]
+    fn test_observe() {
+        let mut history = RestartHistory::default();
+        let first = history.observe(1_000_000).unwrap();
+        assert!(!first.is_restart());
+        // Same start, seen again a little off
+        assert!(history.observe(1_000_002).is_none());
+        assert!(history.observe(999_990).is_none());
+
+        let restart = history.observe(1_000_000 + 3 * DAY).unwrap();
+        assert_eq!(restart.previous_start, Some(1_000_000));
+        assert_eq!(restart.previous_run_secs(), Some(3 * DAY));
+        assert_eq!(history.last(), Some(&restart));
+    }
+
+    #[test]
+    fn test_counts() {
+        let now = 100 * DAY;
+        let mut history = RestartHistory::new(vec![NodeStart {
+            start_time: now - 40 * DAY,
+            previous_start: None,
+        }]);
+        history.observe(now - 20 * DAY);
+        history.observe(now - 5 * DAY);
+        history.observe(now - 5 * DAY + 3600);
+        history.observe(now - 60);
+
+        assert_eq!(history.restarts_since(now - 7 * DAY), 3);
+        assert_eq!(history.restarts_since(now - 30 * DAY), 4);
+        assert_eq!(history.longest_run_secs(), Some(20 * DAY));
+
+        let daily = history.daily_restarts(now, 7);
+        assert_eq!(daily, vec![0, 0, 2, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_start_time() {
+        let mut metrics = NodeMetrics {
+            uptime_seconds: Some(3600.0),
+            ..Default::default()
+        };
+        assert_eq!(start_time(&metrics, 10_000), Some(6_400));
+        metrics.node_start_time = Some(6_395);
+        assert_eq!(start_time(&metrics, 10_000), Some(6_395));
+        assert_eq!(start_time(&NodeMetrics::default(), 10_000), None);
+    }
+}
@@ -0,0 +1,137 @@
+//! UI state kept across restarts
+//!
+//! On exit the selected node, theme, open view, peer grouping, comparison
+//! window, collapsed groups and any `:silence` still running are written to
+//! `session.json` in the data directory, and restored at the next start so a
+//! restart doesn't reset the working context. Overlays (help, settings,
+//! forms, the command line) aren't restored. A missing or unreadable file
+//! starts from the defaults.
+
+use crate::app::AppMode;
+use crate::peers::PeerGrouping;
+use crate::themes::Theme;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// UI state saved on exit
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    /// Name of the selected node
+    pub node: Option<String>,
+    pub theme: Theme,
+    /// Open view; overlays are saved as the dashboard
+    pub mode: AppMode,
+    /// Expanded panel of the focus view
+    pub focused_panel: usize,
+    pub peer_grouping: PeerGrouping,
+    /// Index into `BENCHMARK_WINDOWS_HOURS`
+    pub benchmark_window: usize,
+    /// Node groups collapsed to a single tab (sorted)
+    pub collapsed_groups: Vec<String>,
+    /// Unix timestamp alerts are silenced until
+    pub alerts_silenced_until: Option<u64>,
+}
+
+/// The view to reopen for a mode open at exit
+pub fn restored_mode(mode: AppMode) -> AppMode {
+    match mode {
+        AppMode::Peers | AppMode::PeerDetail => AppMode::Peers,
+        AppMode::Graphs
+        | AppMode::EpochSummary
+        | AppMode::Benchmark
+        | AppMode::Mempool
+        | AppMode::Propagation
+        | AppMode::Incidents
+        | AppMode::Focus => mode,
+        AppMode::Normal
+        | AppMode::Help
+        | AppMode::Settings
+        | AppMode::AddNode
+        | AppMode::Command
+        | AppMode::Setup
+        | AppMode::MetricInfo => AppMode::Normal,
+    }
+}
+
+/// Where the session is kept
+pub fn path() -> PathBuf {
+    crate::storage::get_data_dir().join("session.json")
+}
+
+/// The saved session, or the defaults if there is none or it can't be read
+pub fn load(path: &Path) -> Session {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// Save `session` to `path`
+///
+/// Written to a temporary file in the same directory and renamed into place,
+/// so a crash mid-write or another sview exiting at the same time can't leave
+/// a half-written file.
+pub fn save(path: &Path, session: &Session) -> Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)?;
+    let mut file = tempfile::NamedTempFile::new_in(dir)
+        .with_context(|| format!("Failed to create a temporary file in {}", dir.display()))?;
+    file.write_all(serde_json::to_string_pretty(session)?.as_bytes())
+        .with_context(|| format!("Failed to write {}", file.path().display()))?;
+    file.persist(path)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sview").join("session.json");
+        assert_eq!(load(&path), Session::default());
+
+        let session = Session {
+            node: Some("Relay 2".to_string()),
+            theme: Theme::Monokai,
+            mode: AppMode::Graphs,
+            focused_panel: 2,
+            peer_grouping: PeerGrouping::Network,
+            benchmark_window: 3,
+            collapsed_groups: vec!["relays".to_string()],
+            alerts_silenced_until: Some(1_700_003_600),
+        };
+        save(&path, &session).unwrap();
+        assert_eq!(load(&path), session);
+        // Saving again replaces the file and leaves no temporary files behind
+        save(&path, &Session::default()).unwrap();
+        assert_eq!(load(&path), Session::default());
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+        save(&path, &session).unwrap();
+
+        // Fields added later fall back to their defaults
+        std::fs::write(&path, r#"{"theme": "lightcool"}"#).unwrap();
+        let partial = load(&path);
+        assert_eq!(partial.theme, Theme::LightCool);
+        assert_eq!(partial.node, None);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(load(&path), Session::default());
+    }
+
+    #[test]
+    fn test_restored_mode() {
+        assert_eq!(restored_mode(AppMode::PeerDetail), AppMode::Peers);
+        assert_eq!(restored_mode(AppMode::Incidents), AppMode::Incidents);
+        assert_eq!(restored_mode(AppMode::Settings), AppMode::Normal);
+        assert_eq!(restored_mode(AppMode::Command), AppMode::Normal);
+    }
+}
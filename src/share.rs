@@ -0,0 +1,160 @@
+//! Sharing what's on screen: clipboard copy and screenshots
+//!
+//! Copying uses the OSC 52 escape sequence, so it works over SSH without a
+//! local clipboard daemon as long as the terminal allows it (tmux needs
+//! `set -g set-clipboard on`). Screenshots dump the last rendered frame as
+//! plain text and as ANSI-colored text for pasting into incident channels.
+
+use crate::storage::{get_data_dir, sanitize_node_name};
+use anyhow::{Context, Result};
+use base64::Engine;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier, Style};
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Put text on the terminal's clipboard
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52(text, std::env::var_os("TMUX").is_some()).as_bytes())?;
+    stdout.flush()?;
+    Ok(())
+}
+
+/// OSC 52 "set clipboard" sequence, wrapped for tmux passthrough if needed
+fn osc52(text: &str, tmux: bool) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let sequence = format!("\x1b]52;c;{}\x07", encoded);
+    if tmux {
+        format!("\x1bPtmux;\x1b{}\x1b\\", sequence)
+    } else {
+        sequence
+    }
+}
+
+/// Write a rendered frame to `screenshots/` as `.txt` and `.ans` files
+///
+/// Returns the path of the plain-text file.
+pub fn save_screenshot(buffer: &Buffer, node_name: &str) -> Result<PathBuf> {
+    let dir = get_data_dir().join("screenshots");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let base = dir.join(format!("{}-{}", sanitize_node_name(node_name), stamp));
+
+    let text_path = base.with_extension("txt");
+    std::fs::write(&text_path, buffer_to_text(buffer))
+        .with_context(|| format!("Failed to write {}", text_path.display()))?;
+    let ansi_path = base.with_extension("ans");
+    std::fs::write(&ansi_path, buffer_to_ansi(buffer))
+        .with_context(|| format!("Failed to write {}", ansi_path.display()))?;
+    Ok(text_path)
+}
+
+/// Frame contents as plain text, one line per row
+fn buffer_to_text(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let mut line = String::new();
+        for x in 0..buffer.area.width {
+            line.push_str(buffer[(buffer.area.x + x, buffer.area.y + y)].symbol());
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Frame contents with SGR color and style sequences
+fn buffer_to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let mut current: Option<Style> = None;
+        for x in 0..buffer.area.width {
+            let cell = &buffer[(buffer.area.x + x, buffer.area.y + y)];
+            let style = cell.style();
+            if current != Some(style) {
+                out.push_str(&sgr(style));
+                current = Some(style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+    out
+}
+
+/// SGR sequence selecting a style from a clean state
+fn sgr(style: Style) -> String {
+    let mut codes = String::from("0");
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            let _ = write!(codes, ";{}", code);
+        }
+    }
+    if let Some(fg) = style.fg.and_then(|c| color_code(c, 30)) {
+        let _ = write!(codes, ";{}", fg);
+    }
+    if let Some(bg) = style.bg.and_then(|c| color_code(c, 40)) {
+        let _ = write!(codes, ";{}", bg);
+    }
+    format!("\x1b[{}m", codes)
+}
+
+/// SGR parameters for a color (`base` is 30 for foreground, 40 for background)
+fn color_code(color: Color, base: u8) -> Option<String> {
+    let basic = |n: u8| Some((base + n).to_string());
+    let bright = |n: u8| Some((base + 60 + n).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => basic(0),
+        Color::Red => basic(1),
+        Color::Green => basic(2),
+        Color::Yellow => basic(3),
+        Color::Blue => basic(4),
+        Color::Magenta => basic(5),
+        Color::Cyan => basic(6),
+        Color::Gray => basic(7),
+        Color::DarkGray => bright(0),
+        Color::LightRed => bright(1),
+        Color::LightGreen => bright(2),
+        Color::LightYellow => bright(3),
+        Color::LightBlue => bright(4),
+        Color::LightMagenta => bright(5),
+        Color::LightCyan => bright(6),
+        Color::White => bright(7),
+        Color::Indexed(i) => Some(format!("{};5;{}", base + 8, i)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+
+    #[test]
+    fn test_osc52() {
+        assert_eq!(osc52("hi", false), "\x1b]52;c;aGk=\x07");
+        assert_eq!(osc52("hi", true), "\x1bPtmux;\x1b\x1b]52;c;aGk=\x07\x1b\\");
+    }
+
+    #[test]
+    fn test_buffer_dumps() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buffer.set_string(0, 0, "Tip", Style::default().fg(Color::Rgb(1, 2, 3)));
+        buffer.set_string(0, 1, "ok", Style::default().add_modifier(Modifier::BOLD));
+
+        assert_eq!(buffer_to_text(&buffer), "Tip\nok\n");
+        let ansi = buffer_to_ansi(&buffer);
+        assert!(ansi.starts_with("\x1b[0;38;2;1;2;3mTip\x1b[0m   \x1b[0m\n"));
+        assert!(ansi.contains("\x1b[0;1mok"));
+    }
+}
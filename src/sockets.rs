@@ -4,11 +4,51 @@
 //! - Linux: uses `ss` command
 //! - macOS: uses `lsof` command
 
+use crate::config::RttProbeMode;
+use crate::peers::PeerState;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::sync::OnceCell;
 use tracing::{debug, warn};
 
+/// Timeout for a single TCP connect probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Probes in flight at once, so a large peer list doesn't open hundreds of
+/// sockets or `ping` processes together
+const MAX_CONCURRENT_PROBES: usize = 32;
+
+/// Where a peer's RTT measurement came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RttSource {
+    /// Kernel TCP statistics of the live connection (`ss -i`)
+    Kernel,
+    /// Time to complete (or be refused) a fresh TCP connect
+    TcpConnect,
+    /// ICMP echo via the system `ping` command
+    Icmp,
+}
+
+impl RttSource {
+    /// Short label shown next to the measurement
+    pub fn label(self) -> &'static str {
+        match self {
+            RttSource::Kernel => "kernel",
+            RttSource::TcpConnect => "tcp rtt",
+            RttSource::Icmp => "ping",
+        }
+    }
+}
+
+/// Whether the system `ping` command works for this user (checked once)
+static PING_AVAILABLE: OnceCell<bool> = OnceCell::const_new();
+
 /// Information about a connected peer from socket inspection
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(dead_code)]
 pub struct PeerConnection {
     /// Peer IP address
@@ -19,14 +59,31 @@ pub struct PeerConnection {
     pub local_port: u16,
     /// Connection direction (true = incoming, false = outgoing)
     pub incoming: bool,
-    /// Round-trip time in milliseconds (from ss)
+    /// Round-trip time in milliseconds (from ss, or an active probe)
     pub rtt_ms: Option<f64>,
+    /// How `rtt_ms` was measured
+    pub rtt_source: Option<RttSource>,
+    /// Whether an active probe tried to measure the RTT (a probed peer
+    /// without one didn't answer)
+    #[serde(default)]
+    pub rtt_probed: bool,
     /// Connection state
     pub state: String,
     /// Receive queue bytes
     pub recv_q: u64,
     /// Send queue bytes
     pub send_q: u64,
+    /// Total bytes sent and acknowledged on this connection (from ss)
+    pub bytes_sent: Option<u64>,
+    /// Total bytes received on this connection (from ss)
+    pub bytes_received: Option<u64>,
+    /// Send rate in bytes/s since the previous sample
+    pub send_rate: Option<f64>,
+    /// Receive rate in bytes/s since the previous sample
+    pub recv_rate: Option<f64>,
+    /// Hot or warm, where the node's log shows it (see `peers::classify_connections`)
+    #[serde(default)]
+    pub peer_state: Option<PeerState>,
 }
 
 impl PeerConnection {
@@ -40,26 +97,118 @@ impl PeerConnection {
     }
 }
 
+/// Which connections on the host are the node's peers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerFilter {
+    /// The node's metrics port, never a peer connection
+    pub prom_port: u16,
+    /// The node's P2P listening ports (`p2p_port`). When set, only
+    /// connections on these local ports count (a P2P node makes its outbound
+    /// connections from the listening port too); otherwise well-known ports
+    /// and the metrics port are left out and everything else is kept.
+    pub p2p_ports: Vec<u16>,
+}
+
+impl PeerFilter {
+    /// Whether a connection between these ports may be a peer
+    fn accepts(&self, local_port: u16, peer_port: u16) -> bool {
+        if !self.p2p_ports.is_empty() {
+            return self.p2p_ports.contains(&local_port);
+        }
+        !(EXCLUDED_PORTS.contains(&local_port)
+            || EXCLUDED_PORTS.contains(&peer_port)
+            || local_port == self.prom_port
+            || peer_port == self.prom_port)
+    }
+
+    /// Whether a connection on these ports was dialed in
+    ///
+    /// The node's listening ports are `p2p_ports`, else the host's
+    /// `listening` ports. A connection on one of them from an ephemeral peer
+    /// port was accepted; one to the peer's listening port was dialed out,
+    /// even from our listening port as a P2P node does. With neither known
+    /// it guesses from the usual relay port ranges.
+    fn incoming(&self, local_port: u16, peer_port: u16, listening: &[u16]) -> bool {
+        let listen_ports = if self.p2p_ports.is_empty() {
+            listening
+        } else {
+            &self.p2p_ports
+        };
+        if listen_ports.is_empty() {
+            return local_port > 10000 || (3000..4000).contains(&local_port);
+        }
+        listen_ports.contains(&local_port) && peer_port >= EPHEMERAL_PORTS_START
+    }
+
+    /// Drop the connections this filter doesn't accept, e.g. from an agent
+    /// that only knows the metrics port
+    pub fn retain(&self, peers: &mut Vec<PeerConnection>) {
+        peers.retain(|p| self.accepts(p.local_port, p.port));
+    }
+}
+
 /// Discover peer connections for a Cardano node
 ///
 /// Uses system tools to inspect TCP connections:
 /// - Linux: `ss -tni state established`
 /// - macOS: `lsof -i -n -P`
 ///
-/// Filters to likely Cardano P2P connections by excluding localhost,
-/// well-known ports, and metrics port.
-pub fn discover_peers(prom_port: u16) -> Vec<PeerConnection> {
+/// Filters to likely Cardano P2P connections by excluding localhost and
+/// the ports `filter` rules out.
+pub fn discover_peers(filter: &PeerFilter) -> Vec<PeerConnection> {
     // Detect OS and use appropriate command
     if cfg!(target_os = "macos") {
-        discover_peers_macos(prom_port)
+        discover_peers_macos(filter)
     } else {
-        discover_peers_linux(prom_port)
+        discover_peers_linux(filter)
     }
 }
 
+/// TCP ports the host listens on, to tell incoming connections from
+/// outgoing ones (empty if they can't be listed)
+fn listening_ports() -> Vec<u16> {
+    let output = if cfg!(target_os = "macos") {
+        Command::new("lsof")
+            .args(["-iTCP", "-sTCP:LISTEN", "-n", "-P"])
+            .output()
+    } else {
+        Command::new("ss").args(["-Htln"]).output()
+    };
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_listening_ports(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => {
+            debug!("Couldn't list listening ports");
+            Vec::new()
+        }
+    }
+}
+
+/// Parse the local ports from `ss -Htln` or `lsof -iTCP -sTCP:LISTEN` output
+fn parse_listening_ports(output: &str) -> Vec<u16> {
+    let mut ports: Vec<u16> = output
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            // ss: State Recv-Q Send-Q Local Peer; lsof: ... NAME (LISTEN)
+            let local = if parts.first() == Some(&"LISTEN") {
+                parts.get(3)?
+            } else {
+                parts.iter().rev().nth(1)?
+            };
+            local.rsplit_once(':')?.1.parse().ok()
+        })
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
 /// Discover peers on macOS using lsof
-fn discover_peers_macos(prom_port: u16) -> Vec<PeerConnection> {
+fn discover_peers_macos(filter: &PeerFilter) -> Vec<PeerConnection> {
     let mut peers = Vec::new();
+    let listening = listening_ports();
 
     // Use lsof to get TCP connections
     // -i = network connections, -n = no DNS resolution, -P = no port names
@@ -125,21 +274,16 @@ fn discover_peers_macos(prom_port: u16) -> Vec<PeerConnection> {
         };
 
         // Skip localhost connections
-        if peer_ip == "127.0.0.1" || peer_ip == "::1" || peer_ip.starts_with("localhost") {
+        if is_loopback(&peer_ip) {
             continue;
         }
 
-        // Skip well-known ports and metrics port
-        if EXCLUDED_PORTS.contains(&peer_port)
-            || EXCLUDED_PORTS.contains(&local_port)
-            || peer_port == prom_port
-            || local_port == prom_port
-        {
+        // Skip well-known ports and metrics port, or other than the P2P ports
+        if !filter.accepts(local_port, peer_port) {
             continue;
         }
 
-        // Determine direction: if local port > 10000, likely outgoing
-        let incoming = local_port < 10000 || local_port == 3001 || local_port == 6000;
+        let incoming = filter.incoming(local_port, peer_port, &listening);
 
         peers.push(PeerConnection {
             ip: peer_ip,
@@ -147,9 +291,16 @@ fn discover_peers_macos(prom_port: u16) -> Vec<PeerConnection> {
             local_port,
             incoming,
             rtt_ms: None, // lsof doesn't provide RTT
+            rtt_source: None,
+            rtt_probed: false,
             state: "ESTABLISHED".to_string(),
             recv_q: 0,
             send_q: 0,
+            bytes_sent: None,
+            bytes_received: None,
+            send_rate: None,
+            recv_rate: None,
+            peer_state: None,
         });
     }
 
@@ -158,8 +309,9 @@ fn discover_peers_macos(prom_port: u16) -> Vec<PeerConnection> {
 }
 
 /// Discover peers on Linux using ss
-fn discover_peers_linux(prom_port: u16) -> Vec<PeerConnection> {
+fn discover_peers_linux(filter: &PeerFilter) -> Vec<PeerConnection> {
     let mut peers = Vec::new();
+    let listening = listening_ports();
 
     // Use ss to get TCP connections with extended info
     // -t = TCP, -n = numeric, -i = internal TCP info (includes RTT)
@@ -213,8 +365,9 @@ fn discover_peers_linux(prom_port: u16) -> Vec<PeerConnection> {
                     &prev_peer,
                     prev_recv_q,
                     prev_send_q,
-                    None,
-                    prom_port,
+                    "",
+                    filter,
+                    &listening,
                 ) {
                     peers.push(conn);
                 }
@@ -222,11 +375,10 @@ fn discover_peers_linux(prom_port: u16) -> Vec<PeerConnection> {
 
             current_line = Some((local, peer, recv_q, send_q));
         } else if current_line.is_some() {
-            // This is extended info line - look for RTT
-            let rtt = parse_rtt(line);
-
+            // This is extended info line - RTT and byte counters
             if let Some((local, peer, recv_q, send_q)) = current_line.take() {
-                if let Some(conn) = parse_connection(&local, &peer, recv_q, send_q, rtt, prom_port)
+                if let Some(conn) =
+                    parse_connection(&local, &peer, recv_q, send_q, line, filter, &listening)
                 {
                     peers.push(conn);
                 }
@@ -236,7 +388,8 @@ fn discover_peers_linux(prom_port: u16) -> Vec<PeerConnection> {
 
     // Process last connection
     if let Some((local, peer, recv_q, send_q)) = current_line {
-        if let Some(conn) = parse_connection(&local, &peer, recv_q, send_q, None, prom_port) {
+        if let Some(conn) = parse_connection(&local, &peer, recv_q, send_q, "", filter, &listening)
+        {
             peers.push(conn);
         }
     }
@@ -261,6 +414,12 @@ fn parse_rtt(line: &str) -> Option<f64> {
     None
 }
 
+/// Parse a byte counter such as `bytes_acked:12345` from ss extended info
+fn parse_counter(line: &str, name: &str) -> Option<u64> {
+    line.split_whitespace()
+        .find_map(|part| part.strip_prefix(name)?.strip_prefix(':')?.parse().ok())
+}
+
 /// Well-known ports to exclude from peer discovery
 const EXCLUDED_PORTS: &[u16] = &[
     22,    // SSH
@@ -273,73 +432,392 @@ const EXCLUDED_PORTS: &[u16] = &[
     12798, // Cardano default prom port
 ];
 
+/// Start of the ephemeral port range (Linux default) that clients connect from
+const EPHEMERAL_PORTS_START: u16 = 32768;
+
 /// Parse a connection from local/peer address strings
 fn parse_connection(
     local: &str,
     peer: &str,
     recv_q: u64,
     send_q: u64,
-    rtt: Option<f64>,
-    prom_port: u16,
+    info: &str,
+    filter: &PeerFilter,
+    listening: &[u16],
 ) -> Option<PeerConnection> {
     // Parse addresses - format is either IP:port or [IPv6]:port
     let (local_ip, local_port) = parse_address(local)?;
     let (peer_ip, peer_port) = parse_address(peer)?;
 
     // Skip localhost connections
-    if peer_ip == "127.0.0.1"
-        || peer_ip == "::1"
-        || local_ip == "127.0.0.1"
-        || local_ip == "::1"
-        || peer_ip.starts_with("127.")
-    {
+    if is_loopback(&peer_ip) || is_loopback(&local_ip) {
         return None;
     }
 
-    // Skip connections on excluded well-known ports
-    if EXCLUDED_PORTS.contains(&local_port) || EXCLUDED_PORTS.contains(&peer_port) {
+    // Skip well-known ports and the prom port (metrics, not P2P), or
+    // anything but the node's P2P ports
+    if !filter.accepts(local_port, peer_port) {
         return None;
     }
 
-    // Skip connections on the prom port (metrics, not P2P)
-    if local_port == prom_port || peer_port == prom_port {
-        return None;
-    }
-
-    // Cardano P2P typically uses ports in range 3000-3999 or higher custom ports
-    // Incoming = peer connected to a high local port (we're listening)
-    // Outgoing = we connected to peer's listening port
-    // Heuristic: if local port > peer port, likely incoming; otherwise outgoing
-    let incoming = local_port > 10000 || (3000..4000).contains(&local_port);
+    let incoming = filter.incoming(local_port, peer_port, listening);
 
+    let rtt = parse_rtt(info);
     Some(PeerConnection {
         ip: peer_ip,
         port: peer_port,
         local_port,
         incoming,
         rtt_ms: rtt,
+        rtt_source: rtt.map(|_| RttSource::Kernel),
+        rtt_probed: false,
         state: "ESTABLISHED".to_string(),
         recv_q,
         send_q,
+        bytes_sent: parse_counter(info, "bytes_acked"),
+        bytes_received: parse_counter(info, "bytes_received"),
+        send_rate: None,
+        recv_rate: None,
+        peer_state: None,
+    })
+}
+
+/// Update byte counters from a fresh discovery taken `elapsed` after the last one
+///
+/// Computes per-connection throughput while keeping the list itself (order,
+/// probed RTTs) stable. Connections missing from `latest` lose their rates.
+pub fn update_traffic(peers: &mut [PeerConnection], latest: &[PeerConnection], elapsed: Duration) {
+    let secs = elapsed.as_secs_f64();
+    for peer in peers.iter_mut() {
+        let Some(now) = latest
+            .iter()
+            .find(|p| p.ip == peer.ip && p.port == peer.port && p.local_port == peer.local_port)
+        else {
+            peer.send_rate = None;
+            peer.recv_rate = None;
+            continue;
+        };
+
+        let rate = |now: Option<u64>, before: Option<u64>| {
+            if secs <= 0.0 {
+                return None;
+            }
+            Some(now?.checked_sub(before?)? as f64 / secs)
+        };
+        peer.send_rate = rate(now.bytes_sent, peer.bytes_sent);
+        peer.recv_rate = rate(now.bytes_received, peer.bytes_received);
+        peer.bytes_sent = now.bytes_sent;
+        peer.bytes_received = now.bytes_received;
+    }
+}
+
+/// Total bytes received and sent on all non-loopback interfaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterfaceCounters {
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+/// Read interface counters (Linux `/proc/net/dev`; None elsewhere)
+pub fn read_interface_counters() -> Option<InterfaceCounters> {
+    let text = std::fs::read_to_string("/proc/net/dev").ok()?;
+    parse_proc_net_dev(&text)
+}
+
+/// Sum receive/transmit bytes from `/proc/net/dev`, skipping loopback
+fn parse_proc_net_dev(text: &str) -> Option<InterfaceCounters> {
+    let mut counters = InterfaceCounters {
+        rx_bytes: 0,
+        tx_bytes: 0,
+    };
+    let mut found = false;
+    for line in text.lines().skip(2) {
+        let Some((name, stats)) = line.split_once(':') else {
+            continue;
+        };
+        if name.trim() == "lo" {
+            continue;
+        }
+        // Receive: bytes packets errs drop fifo frame compressed multicast
+        // Transmit: bytes ...
+        let fields: Vec<u64> = stats
+            .split_whitespace()
+            .filter_map(|f| f.parse().ok())
+            .collect();
+        if fields.len() >= 9 {
+            counters.rx_bytes += fields[0];
+            counters.tx_bytes += fields[8];
+            found = true;
+        }
+    }
+    found.then_some(counters)
+}
+
+/// Probes started and finished so far, shared with the UI while a peer
+/// refresh runs in the background
+#[derive(Debug, Default)]
+pub struct ProbeProgress {
+    started: AtomicUsize,
+    finished: AtomicUsize,
+}
+
+impl ProbeProgress {
+    /// (finished, started) probes
+    pub fn counts(&self) -> (usize, usize) {
+        (
+            self.finished.load(Ordering::Relaxed),
+            self.started.load(Ordering::Relaxed),
+        )
+    }
+
+    fn start(&self, probes: usize) {
+        self.started.fetch_add(probes, Ordering::Relaxed);
+    }
+
+    fn finish(&self) {
+        self.finished.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Fill in peer latency by timing TCP connects, like gLiveView's fallback
+///
+/// `Missing` probes only peers without a kernel RTT; `All` replaces every
+/// measurement. Up to `MAX_CONCURRENT_PROBES` run at once.
+pub async fn probe_tcp_rtt(
+    peers: &mut [PeerConnection],
+    mode: RttProbeMode,
+    progress: &ProbeProgress,
+) {
+    let targets: Vec<(usize, SocketAddr)> = peers
+        .iter()
+        .enumerate()
+        .filter(|(_, peer)| match mode {
+            RttProbeMode::Off => false,
+            RttProbeMode::Missing => peer.rtt_ms.is_none(),
+            RttProbeMode::All => true,
+        })
+        .filter_map(|(i, peer)| {
+            let ip: IpAddr = peer.ip.parse().ok()?;
+            Some((i, SocketAddr::new(ip, peer.port)))
+        })
+        .collect();
+
+    for (i, rtt) in run_probes(targets, tcp_connect_rtt, progress).await {
+        peers[i].rtt_probed = true;
+        if let Some(rtt) = rtt {
+            peers[i].rtt_ms = Some(rtt);
+            peers[i].rtt_source = Some(RttSource::TcpConnect);
+        }
+    }
+}
+
+/// Run `probe` for each (peer index, target), at most `MAX_CONCURRENT_PROBES`
+/// at a time, and return the measurements by peer index
+async fn run_probes<T, F, Fut>(
+    targets: Vec<(usize, T)>,
+    probe: F,
+    progress: &ProbeProgress,
+) -> Vec<(usize, Option<f64>)>
+where
+    F: Fn(T) -> Fut,
+    Fut: std::future::Future<Output = Option<f64>> + Send + 'static,
+{
+    progress.start(targets.len());
+    let mut results = Vec::with_capacity(targets.len());
+    let mut probes = tokio::task::JoinSet::new();
+    let mut collect = |result: Option<Result<(usize, Option<f64>), _>>| {
+        if let Some(result) = result {
+            progress.finish();
+            if let Ok(measurement) = result {
+                results.push(measurement);
+            }
+        }
+    };
+    for (i, target) in targets {
+        if probes.len() >= MAX_CONCURRENT_PROBES {
+            collect(probes.join_next().await);
+        }
+        let probe = probe(target);
+        probes.spawn(async move { (i, probe.await) });
+    }
+    while let Some(result) = probes.join_next().await {
+        collect(Some(result));
+    }
+    results
+}
+
+/// Time a TCP connect in milliseconds
+///
+/// A refused connection (e.g. to an incoming peer's ephemeral port) also takes
+/// one round trip, so it counts as a measurement.
+async fn tcp_connect_rtt(addr: SocketAddr) -> Option<f64> {
+    let started = Instant::now();
+    let result = tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(addr)).await;
+    let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+    match result {
+        Ok(Ok(_)) => Some(elapsed_ms),
+        Ok(Err(e)) if e.kind() == std::io::ErrorKind::ConnectionRefused => Some(elapsed_ms),
+        _ => None,
+    }
+}
+
+/// Fill in latency for peers still without an RTT using ICMP echo
+///
+/// Uses the system `ping` command, which has the privileges raw ICMP sockets
+/// need. If `ping` is missing or not permitted, this does nothing.
+pub async fn probe_icmp_rtt(peers: &mut [PeerConnection], progress: &ProbeProgress) {
+    let available = *PING_AVAILABLE
+        .get_or_init(|| async {
+            let ok = ping_rtt("127.0.0.1".parse().expect("valid IP"))
+                .await
+                .is_some();
+            if !ok {
+                debug!("ICMP ping unavailable; skipping ping fallback");
+            }
+            ok
+        })
+        .await;
+    if !available {
+        return;
+    }
+
+    let targets: Vec<(usize, IpAddr)> = peers
+        .iter()
+        .enumerate()
+        .filter(|(_, peer)| peer.rtt_ms.is_none())
+        .filter_map(|(i, peer)| Some((i, peer.ip.parse().ok()?)))
+        .collect();
+    for (i, rtt) in run_probes(targets, ping_rtt, progress).await {
+        peers[i].rtt_probed = true;
+        if let Some(rtt) = rtt {
+            peers[i].rtt_ms = Some(rtt);
+            peers[i].rtt_source = Some(RttSource::Icmp);
+        }
+    }
+}
+
+/// Send one ICMP echo with the system `ping` and return the RTT in milliseconds
+async fn ping_rtt(ip: IpAddr) -> Option<f64> {
+    let ip_str = ip.to_string();
+    let mut command = if cfg!(target_os = "macos") {
+        // macOS: separate ping6 binary, -t is the overall timeout in seconds
+        let mut command = tokio::process::Command::new(if ip.is_ipv6() { "ping6" } else { "ping" });
+        if ip.is_ipv4() {
+            command.args(["-t", "2"]);
+        }
+        command
+    } else {
+        let mut command = tokio::process::Command::new("ping");
+        command.args(["-n", "-W", "2"]);
+        command
+    };
+    command.args(["-c", "1", &ip_str]).kill_on_drop(true);
+
+    let output = tokio::time::timeout(PROBE_TIMEOUT * 2, command.output())
+        .await
+        .ok()?
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_ping_time(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Extract the RTT from ping output (`time=12.3 ms` or `time<1 ms`)
+fn parse_ping_time(output: &str) -> Option<f64> {
+    let start = output.find("time=").or_else(|| output.find("time<"))? + 5;
+    let value: String = output[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    value.parse().ok()
+}
+
+/// Look up the host name of an IP with the system resolver
+///
+/// Uses `getent hosts` (`host` on macOS), so /etc/hosts and the configured
+/// DNS servers both count. None if the address has no PTR record or the
+/// lookup fails.
+pub async fn reverse_dns(ip: IpAddr) -> Option<String> {
+    let ip_str = ip.to_string();
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = tokio::process::Command::new("host");
+        command.args(["-W", "2", &ip_str]);
+        command
+    } else {
+        let mut command = tokio::process::Command::new("getent");
+        command.args(["hosts", &ip_str]);
+        command
+    };
+    command.kill_on_drop(true);
+
+    let output = tokio::time::timeout(PROBE_TIMEOUT * 2, command.output())
+        .await
+        .ok()?
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if cfg!(target_os = "macos") {
+        parse_host_output(&stdout)
+    } else {
+        parse_getent_hosts(&stdout)
+    }
+}
+
+/// Host name from `getent hosts` output (`2001:db8::1  relay1.example.com`)
+fn parse_getent_hosts(output: &str) -> Option<String> {
+    output
+        .lines()
+        .next()?
+        .split_whitespace()
+        .nth(1)
+        .map(String::from)
+}
+
+/// Host name from `host` output (`... domain name pointer relay1.example.com.`)
+fn parse_host_output(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let name = line.split("domain name pointer ").nth(1)?;
+        Some(name.trim().trim_end_matches('.').to_string())
     })
 }
 
 /// Parse IP:port or [IPv6]:port format
-fn parse_address(addr: &str) -> Option<(String, u16)> {
-    // Handle IPv6 [addr]:port format
-    if addr.starts_with('[') {
-        let bracket_end = addr.find(']')?;
-        let ip = addr[1..bracket_end].to_string();
-        let port_str = addr.get(bracket_end + 2..)?; // Skip ']:'
-        let port = port_str.parse().ok()?;
-        return Some((ip, port));
+///
+/// IPv6 addresses come back in canonical form without a zone index
+/// (`fe80::1%eth0`), and IPv4-mapped ones (`::ffff:192.0.2.1`) as plain
+/// IPv4, so a peer has the same key whichever socket family it used.
+pub fn parse_address(addr: &str) -> Option<(String, u16)> {
+    let (ip, port) = match addr.strip_prefix('[') {
+        // ss puts an interface scope after the bracket: [fe80::1]%eth0:3001
+        Some(rest) => {
+            let (ip, rest) = rest.split_once(']')?;
+            (ip, rest.rsplit_once(':')?.1)
+        }
+        // IPv4, or IPv6 from older ss versions without brackets
+        None => addr.rsplit_once(':')?,
+    };
+    Some((normalize_ip(ip), port.parse().ok()?))
+}
+
+/// Canonical text of an IP address, as described for [`parse_address`]
+pub fn normalize_ip(ip: &str) -> String {
+    let ip = ip.split('%').next().unwrap_or(ip);
+    match ip.parse::<IpAddr>() {
+        Ok(IpAddr::V6(v6)) => match v6.to_ipv4_mapped() {
+            Some(v4) => v4.to_string(),
+            None => v6.to_string(),
+        },
+        Ok(v4) => v4.to_string(),
+        Err(_) => ip.to_string(),
     }
+}
 
-    // Handle IPv4 addr:port format
-    let colon_pos = addr.rfind(':')?;
-    let ip = addr[..colon_pos].to_string();
-    let port = addr[colon_pos + 1..].parse().ok()?;
-    Some((ip, port))
+/// Whether an address is this host (127.0.0.0/8 or ::1)
+fn is_loopback(ip: &str) -> bool {
+    ip.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
 }
 
 #[cfg(test)]
@@ -358,6 +836,81 @@ mod tests {
         let (ip, port) = parse_address("[::1]:12798").unwrap();
         assert_eq!(ip, "::1");
         assert_eq!(port, 12798);
+
+        // Canonical form, so the same peer from ss and the log match
+        assert_eq!(
+            parse_address("[2001:0db8:0000::0001]:3001"),
+            Some(("2001:db8::1".to_string(), 3001))
+        );
+        // Zone index inside or after the brackets
+        assert_eq!(
+            parse_address("[fe80::1%eth0]:3001"),
+            Some(("fe80::1".to_string(), 3001))
+        );
+        assert_eq!(
+            parse_address("[fe80::1]%eth0:3001"),
+            Some(("fe80::1".to_string(), 3001))
+        );
+        // IPv4 peers on a dual-stack socket, with and without brackets
+        assert_eq!(
+            parse_address("[::ffff:192.0.2.10]:3001"),
+            Some(("192.0.2.10".to_string(), 3001))
+        );
+        assert_eq!(
+            parse_address("::ffff:192.0.2.10:3001"),
+            Some(("192.0.2.10".to_string(), 3001))
+        );
+        assert_eq!(parse_address("[2001:db8::1]"), None);
+    }
+
+    #[test]
+    fn test_parse_connection_ipv6() {
+        let filter = PeerFilter {
+            prom_port: 12798,
+            p2p_ports: Vec::new(),
+        };
+        let conn = parse_connection(
+            "[2001:db8::10]:3001",
+            "[2001:db8:5::20]:51234",
+            0,
+            0,
+            "",
+            &filter,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(conn.ip, "2001:db8:5::20");
+        assert_eq!(conn.port, 51234);
+        assert!(conn.incoming);
+        // Loopback over IPv6 or as a mapped IPv4 address
+        assert!(parse_connection("[::1]:3001", "[::1]:51234", 0, 0, "", &filter, &[]).is_none());
+        assert!(parse_connection(
+            "[::ffff:127.0.0.1]:3001",
+            "[::ffff:127.0.0.1]:51234",
+            0,
+            0,
+            "",
+            &filter,
+            &[]
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_parse_reverse_dns() {
+        assert_eq!(
+            parse_getent_hosts("2001:db8::1      relay1.example.com\n"),
+            Some("relay1.example.com".to_string())
+        );
+        assert_eq!(parse_getent_hosts(""), None);
+        assert_eq!(
+            parse_host_output("1.2.0.192.in-addr.arpa domain name pointer relay1.example.com.\n"),
+            Some("relay1.example.com".to_string())
+        );
+        assert_eq!(
+            parse_host_output("Host 1.2.0.192.in-addr.arpa. not found: 3(NXDOMAIN)\n"),
+            None
+        );
     }
 
     #[test]
@@ -366,4 +919,188 @@ mod tests {
         assert_eq!(parse_rtt("cubic rtt:25.5/10.2 ato:40"), Some(25.5));
         assert_eq!(parse_rtt("no rtt here"), None);
     }
+
+    #[test]
+    fn test_peer_filter() {
+        let filter = PeerFilter {
+            prom_port: 12798,
+            p2p_ports: Vec::new(),
+        };
+        assert!(filter.accepts(3001, 45000));
+        assert!(filter.accepts(51000, 6000));
+        assert!(!filter.accepts(12798, 51000));
+        assert!(!filter.accepts(22, 51000));
+
+        // Only the listening ports, even ones that are otherwise excluded
+        let filter = PeerFilter {
+            prom_port: 12798,
+            p2p_ports: vec![6000, 8080],
+        };
+        assert!(filter.accepts(6000, 3001));
+        assert!(filter.accepts(8080, 51000));
+        assert!(!filter.accepts(3001, 45000));
+        assert!(!filter.accepts(51000, 6000));
+        assert!(
+            parse_connection("10.0.0.1:3001", "10.0.0.2:45000", 0, 0, "", &filter, &[]).is_none()
+        );
+        assert!(
+            parse_connection("10.0.0.1:6000", "10.0.0.2:3001", 0, 0, "", &filter, &[]).is_some()
+        );
+
+        // Direction from the listening port: dialed in from an ephemeral
+        // port, dialed out to the peer's listening port
+        assert!(filter.incoming(6000, 51000, &[]));
+        assert!(!filter.incoming(6000, 3001, &[]));
+        assert!(!filter.incoming(40000, 6000, &[]));
+
+        // Without p2p_port, the host's listening ports decide
+        let filter = PeerFilter {
+            prom_port: 12798,
+            p2p_ports: Vec::new(),
+        };
+        assert!(filter.incoming(6000, 51000, &[6000, 12798]));
+        assert!(!filter.incoming(51000, 6000, &[6000, 12798]));
+        assert!(!filter.incoming(3001, 6000, &[6000]));
+    }
+
+    #[test]
+    fn test_parse_listening_ports() {
+        let ss = "LISTEN 0      4096         0.0.0.0:6000      0.0.0.0:*\n\
+                  LISTEN 0      4096       127.0.0.1:12798     0.0.0.0:*\n\
+                  LISTEN 0      4096            [::]:6000         [::]:*\n";
+        assert_eq!(parse_listening_ports(ss), vec![6000, 12798]);
+
+        let lsof = "COMMAND   PID USER   FD   TYPE DEVICE SIZE/OFF NODE NAME\n\
+                    cardano-n 512 node   20u  IPv4 0x1234      0t0  TCP *:3001 (LISTEN)\n";
+        assert_eq!(parse_listening_ports(lsof), vec![3001]);
+    }
+
+    #[test]
+    fn test_parse_connection_counters() {
+        let info = "cubic wscale:7,7 rtt:1.875/0.625 bytes_sent:5000 bytes_acked:4800 bytes_received:900000 segs_out:10";
+        let filter = PeerFilter {
+            prom_port: 12798,
+            p2p_ports: Vec::new(),
+        };
+        let conn =
+            parse_connection("10.0.0.1:3001", "10.0.0.2:45000", 0, 0, info, &filter, &[]).unwrap();
+        assert_eq!(conn.rtt_ms, Some(1.875));
+        assert_eq!(conn.bytes_sent, Some(4800));
+        assert_eq!(conn.bytes_received, Some(900000));
+
+        let mut later = conn.clone();
+        later.bytes_sent = Some(6800);
+        later.bytes_received = Some(1_000_000);
+        let mut peers = vec![conn];
+        update_traffic(&mut peers, &[later], Duration::from_secs(2));
+        assert_eq!(peers[0].send_rate, Some(1000.0));
+        assert_eq!(peers[0].recv_rate, Some(50000.0));
+        assert_eq!(peers[0].bytes_sent, Some(6800));
+
+        update_traffic(&mut peers, &[], Duration::from_secs(2));
+        assert_eq!(peers[0].send_rate, None);
+    }
+
+    #[test]
+    fn test_parse_proc_net_dev() {
+        let text = "Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 5000      50    0    0    0     0          0         0     5000      50    0    0    0     0       0          0
+  eth0: 1000000   900    0    0    0     0          0         0   250000     700    0    0    0     0       0          0
+  eth1:    2000    10    0    0    0     0          0         0     1000      10    0    0    0     0       0          0
+";
+        assert_eq!(
+            parse_proc_net_dev(text),
+            Some(InterfaceCounters {
+                rx_bytes: 1_002_000,
+                tx_bytes: 251_000,
+            })
+        );
+        assert_eq!(parse_proc_net_dev(""), None);
+    }
+
+    #[test]
+    fn test_parse_ping_time() {
+        let linux = "PING 1.1.1.1 (1.1.1.1) 56(84) bytes of data.\n64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms\n";
+        assert_eq!(parse_ping_time(linux), Some(12.3));
+        let macos = "64 bytes from 1.1.1.1: icmp_seq=0 ttl=57 time=8.512 ms";
+        assert_eq!(parse_ping_time(macos), Some(8.512));
+        assert_eq!(parse_ping_time("64 bytes from ::1: time<1 ms"), Some(1.0));
+        assert_eq!(parse_ping_time("Request timeout for icmp_seq 0"), None);
+        assert_eq!(RttSource::Icmp.label(), "ping");
+    }
+
+    #[tokio::test]
+    async fn test_probe_tcp_rtt() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let open_port = listener.local_addr().unwrap().port();
+        let peer = |port, rtt_ms: Option<f64>| PeerConnection {
+            ip: "127.0.0.1".to_string(),
+            port,
+            local_port: 3001,
+            incoming: false,
+            rtt_ms,
+            rtt_source: rtt_ms.map(|_| RttSource::Kernel),
+            rtt_probed: false,
+            state: "ESTABLISHED".to_string(),
+            recv_q: 0,
+            send_q: 0,
+            bytes_sent: None,
+            bytes_received: None,
+            send_rate: None,
+            recv_rate: None,
+            peer_state: None,
+        };
+        let mut peers = vec![peer(open_port, None), peer(open_port, Some(42.0))];
+
+        let progress = ProbeProgress::default();
+        probe_tcp_rtt(&mut peers, RttProbeMode::Off, &progress).await;
+        assert_eq!(peers[0].rtt_ms, None);
+        assert_eq!(progress.counts(), (0, 0));
+
+        assert!(!peers[0].rtt_probed);
+
+        probe_tcp_rtt(&mut peers, RttProbeMode::Missing, &progress).await;
+        assert_eq!(peers[0].rtt_source, Some(RttSource::TcpConnect));
+        assert!(peers[0].rtt_ms.is_some() && peers[0].rtt_probed);
+        assert_eq!(peers[1].rtt_ms, Some(42.0));
+        assert!(!peers[1].rtt_probed);
+        assert_eq!(progress.counts(), (1, 1));
+
+        probe_tcp_rtt(&mut peers, RttProbeMode::All, &progress).await;
+        assert_eq!(peers[1].rtt_source, Some(RttSource::TcpConnect));
+        assert_eq!(progress.counts(), (3, 3));
+    }
+
+    #[tokio::test]
+    async fn test_run_probes_caps_concurrency() {
+        use std::sync::Arc;
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let targets: Vec<(usize, usize)> = (0..MAX_CONCURRENT_PROBES * 3).map(|i| (i, i)).collect();
+        let progress = ProbeProgress::default();
+        let results = run_probes(
+            targets,
+            |i| {
+                let in_flight = in_flight.clone();
+                let peak = peak.clone();
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    Some(i as f64)
+                }
+            },
+            &progress,
+        )
+        .await;
+        assert_eq!(results.len(), MAX_CONCURRENT_PROBES * 3);
+        assert!(results.iter().all(|&(i, rtt)| rtt == Some(i as f64)));
+        assert_eq!(peak.load(Ordering::SeqCst), MAX_CONCURRENT_PROBES);
+        assert_eq!(
+            progress.counts(),
+            (MAX_CONCURRENT_PROBES * 3, MAX_CONCURRENT_PROBES * 3)
+        );
+    }
 }
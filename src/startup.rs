@@ -0,0 +1,297 @@
+//! Node startup detection
+//!
+//! After a restart the node validates its immutable database and replays the
+//! ledger from the last snapshot before it reports any chain metrics, which
+//! can take from minutes to hours. Without chain metrics every health check
+//! looks critical, so this tracker recognises the phase from the
+//! `blockReplayProgress` metric, the node log (`ValidatedChunk` and
+//! `ReplayedBlock`/`LedgerReplay` traces), or, failing both, a node that
+//! answers with its uptime but no block height yet. A replay gauge left
+//! below 100% is ignored once the block height or sync progress advances.
+
+use crate::metrics::NodeMetrics;
+
+/// A log event older than this no longer counts as current progress
+const LOG_EVENT_MAX_AGE_SECS: u64 = 300;
+
+/// What a starting node is busy with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartupPhase {
+    /// Up, but no chain metrics and no progress reported yet
+    Starting,
+    /// Checking the immutable database chunk by chunk
+    ValidatingChunks,
+    /// Re-applying blocks on top of the last ledger snapshot
+    ReplayingLedger,
+}
+
+impl StartupPhase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StartupPhase::Starting => "starting up",
+            StartupPhase::ValidatingChunks => "validating chunks",
+            StartupPhase::ReplayingLedger => "replaying ledger",
+        }
+    }
+}
+
+/// Current startup state, for display
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StartupStatus {
+    pub phase: StartupPhase,
+    /// Percentage done, when the node reports it
+    pub progress: Option<f64>,
+    /// Estimated seconds until the phase completes
+    pub eta_secs: Option<u64>,
+    /// Unix timestamp the phase was first seen
+    pub since: u64,
+}
+
+/// A progress reading parsed from the node log
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StartupEvent {
+    pub phase: StartupPhase,
+    pub progress: Option<f64>,
+}
+
+/// Parse a JSON log line into a startup progress event, if it is one
+pub fn parse_log_line(line: &str) -> Option<StartupEvent> {
+    // Cheap filter before parsing JSON
+    let phase = if line.contains("ValidatedChunk") {
+        StartupPhase::ValidatingChunks
+    } else if line.contains("ReplayedBlock") || line.contains("LedgerReplay") {
+        StartupPhase::ReplayingLedger
+    } else {
+        return None;
+    };
+    let json: serde_json::Value = serde_json::from_str(line).ok()?;
+    let data = json.get("data")?;
+
+    // Legacy tracing: "Replayed block: slot 123 out of 456. Progress: 27.0%"
+    let from_text = json
+        .get("msg")
+        .or_else(|| data.get("message"))
+        .and_then(|m| m.as_str())
+        .and_then(parse_progress_text);
+    // New tracing: {"kind": "LedgerReplay", "slot": 123, "tip": 456}
+    let from_slots = match (
+        data.get("slot").and_then(|v| v.as_u64()),
+        data.get("tip").and_then(|v| v.as_u64()),
+    ) {
+        (Some(slot), Some(tip)) if tip > 0 => Some(slot as f64 / tip as f64 * 100.0),
+        _ => None,
+    };
+    Some(StartupEvent {
+        phase,
+        progress: from_text.or(from_slots).map(|p| p.clamp(0.0, 100.0)),
+    })
+}
+
+/// Extract N from "... Progress: N%"
+fn parse_progress_text(text: &str) -> Option<f64> {
+    let rest = &text[text.find("Progress:")? + "Progress:".len()..];
+    rest.trim_start().split('%').next()?.trim().parse().ok()
+}
+
+/// Follows a node through startup until it reports chain metrics
+#[derive(Debug, Clone, Default)]
+pub struct StartupTracker {
+    status: Option<StartupStatus>,
+    /// First progress reading of the current phase (value, unix time)
+    first_reading: Option<(f64, u64)>,
+    /// Latest progress event from the log and when it was seen
+    log_event: Option<(StartupEvent, u64)>,
+    /// Block height and sync progress of the previous sample
+    last_chain: (Option<u64>, Option<f64>),
+    /// The chain has moved since the node started reporting it
+    chain_moved: bool,
+}
+
+impl StartupTracker {
+    /// Record a progress event read from the node log
+    pub fn observe(&mut self, event: StartupEvent, now: u64) {
+        self.log_event = Some((event, now));
+    }
+
+    /// Feed a metrics sample; returns whether the node is still starting
+    pub fn update(&mut self, metrics: &NodeMetrics, now: u64) -> bool {
+        let log_event = self
+            .log_event
+            .filter(|(_, seen)| now.saturating_sub(*seen) <= LOG_EVENT_MAX_AGE_SECS)
+            .map(|(event, _)| event);
+
+        // The replay gauge can stay below 100% after startup; a chain that
+        // moves means the node is past it
+        let (last_height, last_sync) = self.last_chain;
+        let advanced = matches!((last_height, metrics.block_height), (Some(a), Some(b)) if b > a)
+            || matches!((last_sync, metrics.sync_progress), (Some(a), Some(b)) if b > a);
+        self.chain_moved = metrics.block_height.is_some() && (self.chain_moved || advanced);
+        self.last_chain = (metrics.block_height, metrics.sync_progress);
+
+        let reading = if !metrics.connected {
+            None
+        } else if let Some(progress) = metrics
+            .replay_progress
+            .filter(|p| *p < 100.0 && !self.chain_moved)
+        {
+            Some((StartupPhase::ReplayingLedger, Some(progress)))
+        } else if metrics.block_height.is_some() {
+            None
+        } else if let Some(event) = log_event {
+            Some((event.phase, event.progress))
+        } else if metrics.uptime_seconds.is_some() {
+            Some((StartupPhase::Starting, None))
+        } else {
+            None
+        };
+
+        let Some((phase, progress)) = reading else {
+            self.status = None;
+            self.first_reading = None;
+            self.log_event = None;
+            return false;
+        };
+
+        let since = match self.status {
+            Some(status) if status.phase == phase => status.since,
+            _ => {
+                self.first_reading = None;
+                now
+            }
+        };
+        if let Some(progress) = progress {
+            self.first_reading.get_or_insert((progress, now));
+        }
+        let eta_secs = match (progress, self.first_reading) {
+            (Some(progress), Some(first)) => eta(first, (progress, now)),
+            _ => None,
+        };
+        self.status = Some(StartupStatus {
+            phase,
+            progress,
+            eta_secs,
+            since,
+        });
+        true
+    }
+
+    /// The current startup state, or `None` once the node is up
+    pub fn status(&self) -> Option<StartupStatus> {
+        self.status
+    }
+}
+
+/// Seconds to reach 100% at the rate between two readings
+fn eta((first, first_at): (f64, u64), (latest, latest_at): (f64, u64)) -> Option<u64> {
+    let elapsed = latest_at.checked_sub(first_at).filter(|e| *e > 0)?;
+    let rate = (latest - first) / elapsed as f64;
+    (rate > 0.0).then(|| ((100.0 - latest) / rate).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(replay: Option<f64>, height: Option<u64>) -> NodeMetrics {
+        NodeMetrics {
+            connected: true,
+            replay_progress: replay,
+            block_height: height,
+            uptime_seconds: Some(60.0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_log_line() {
+        let legacy = r#"{"at":"2024-05-01T10:00:00.00Z","data":{"kind":"TraceLedgerReplayEvent.ReplayedBlock","slot":123},"msg":"Replayed block: slot 123 out of 456. Progress: 27.03%"}"#;
+        assert_eq!(
+            parse_log_line(legacy),
+            Some(StartupEvent {
+                phase: StartupPhase::ReplayingLedger,
+                progress: Some(27.03),
+            })
+        );
+
+        let new = r#"{"at":"2024-05-01T10:00:00.00Z","ns":"ChainDB.ReplayBlock.LedgerReplay","data":{"kind":"LedgerReplay","slot":250,"tip":1000}}"#;
+        assert_eq!(parse_log_line(new).unwrap().progress, Some(25.0));
+
+        let chunk = r#"{"at":"2024-05-01T10:00:00.00Z","data":{"kind":"TraceImmutableDBEvent.ValidatedChunk"}}"#;
+        assert_eq!(
+            parse_log_line(chunk),
+            Some(StartupEvent {
+                phase: StartupPhase::ValidatingChunks,
+                progress: None,
+            })
+        );
+
+        assert_eq!(
+            parse_log_line(r#"{"data":{"kind":"AddedToCurrentChain"}}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_replay_progress_and_eta() {
+        let mut tracker = StartupTracker::default();
+        assert!(tracker.update(&sample(Some(10.0), None), 1000));
+        assert_eq!(tracker.status().unwrap().eta_secs, None);
+
+        assert!(tracker.update(&sample(Some(20.0), None), 1100));
+        let status = tracker.status().unwrap();
+        assert_eq!(status.phase, StartupPhase::ReplayingLedger);
+        assert_eq!(status.since, 1000);
+        // 10% per 100s leaves 800s for the remaining 80%
+        assert_eq!(status.eta_secs, Some(800));
+
+        // Chain metrics appear: startup is over
+        assert!(!tracker.update(&sample(None, Some(10_000_000)), 1200));
+        assert_eq!(tracker.status(), None);
+    }
+
+    #[test]
+    fn test_stale_replay_gauge() {
+        let mut tracker = StartupTracker::default();
+        assert!(tracker.update(&sample(Some(99.9), Some(10_000_000)), 1000));
+
+        // The gauge is stuck below 100% but blocks keep arriving
+        assert!(!tracker.update(&sample(Some(99.9), Some(10_000_001)), 1020));
+        assert_eq!(tracker.status(), None);
+        assert!(!tracker.update(&sample(Some(99.9), Some(10_000_001)), 1022));
+
+        // Sync progress moving counts too
+        let mut tracker = StartupTracker::default();
+        let mut syncing = sample(Some(99.9), Some(5_000_000));
+        syncing.sync_progress = Some(80.0);
+        assert!(tracker.update(&syncing, 1000));
+        syncing.sync_progress = Some(80.5);
+        assert!(!tracker.update(&syncing, 1010));
+    }
+
+    #[test]
+    fn test_log_and_fallback_phases() {
+        let mut tracker = StartupTracker::default();
+        assert!(tracker.update(&sample(None, None), 1000));
+        assert_eq!(tracker.status().unwrap().phase, StartupPhase::Starting);
+
+        tracker.observe(
+            StartupEvent {
+                phase: StartupPhase::ValidatingChunks,
+                progress: Some(50.0),
+            },
+            1010,
+        );
+        assert!(tracker.update(&sample(None, None), 1010));
+        let status = tracker.status().unwrap();
+        assert_eq!(status.phase, StartupPhase::ValidatingChunks);
+        assert_eq!(status.since, 1010);
+        assert_eq!(status.progress, Some(50.0));
+
+        // A stale log event falls back to the metrics heuristic
+        assert!(tracker.update(&sample(None, None), 1010 + LOG_EVENT_MAX_AGE_SECS + 1));
+        assert_eq!(tracker.status().unwrap().phase, StartupPhase::Starting);
+
+        let offline = NodeMetrics::default();
+        assert!(!tracker.update(&offline, 2000));
+    }
+}
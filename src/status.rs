@@ -0,0 +1,218 @@
+//! One-shot node status (`sview status`)
+//!
+//! Fetches metrics once from every configured node and prints one line per
+//! node, or JSON with `--json`, using the same health checks and thresholds as
+//! the dashboard. The exit code is the worst health found (0 healthy,
+//! 1 warning, 2 critical or unreachable), so cron jobs and CI health checks
+//! can act on it directly. Nodes are only scraped: a status check saves no
+//! history and raises, records and sends no alerts.
+
+use crate::app::{HealthStatus, NodeState};
+use crate::config::AppConfig;
+use crate::format::format_bytes;
+use anyhow::Result;
+use serde::Serialize;
+use std::io::IsTerminal;
+
+/// Status of one node after a single scrape
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusRow {
+    pub node: String,
+    pub role: String,
+    pub endpoint: String,
+    pub health: &'static str,
+    pub connected: bool,
+    pub block_height: Option<u64>,
+    pub epoch: Option<u64>,
+    pub sync_progress: Option<f64>,
+    pub peers: Option<u64>,
+    pub memory_bytes: Option<u64>,
+    pub kes_remaining: Option<u64>,
+    /// Seconds since the last block (needs two scrapes)
+    pub tip_age_secs: Option<u64>,
+    /// Checks that are not healthy (e.g. "peers", "kes")
+    pub issues: Vec<&'static str>,
+    /// Why the scrape failed, if it did
+    pub error: Option<String>,
+}
+
+impl StatusRow {
+    /// The row for a node and its overall health
+    pub fn from_node(node: &NodeState) -> (Self, HealthStatus) {
+        let health = node.overall_health();
+        let metrics = &node.metrics;
+        let issues = if metrics.connected {
+            [
+                ("peers", node.peer_health()),
+                ("sync", node.sync_health()),
+                ("memory", node.memory_health()),
+                ("kes", node.kes_health()),
+            ]
+            .into_iter()
+            .filter(|(_, status)| *status != HealthStatus::Good)
+            .map(|(name, _)| name)
+            .collect()
+        } else {
+            Vec::new()
+        };
+        let row = Self {
+            node: node.config.node_name.clone(),
+            role: node.role.to_string(),
+            endpoint: node.config.endpoint_label(),
+            health: health_label(health),
+            connected: metrics.connected,
+            block_height: metrics.block_height,
+            epoch: metrics.epoch,
+            sync_progress: metrics.sync_progress,
+            peers: metrics.peers_connected,
+            memory_bytes: metrics.memory_used,
+            kes_remaining: metrics.kes_remaining,
+            tip_age_secs: node.tip_age_secs(),
+            issues,
+            error: node.error_text(),
+        };
+        (row, health)
+    }
+}
+
+/// Fetch every node once, print the status and return the exit code
+pub async fn run(app_config: &AppConfig, json: bool) -> Result<i32> {
+    let mut rows = Vec::new();
+    let mut worst = HealthStatus::Good;
+    for node_config in &app_config.nodes {
+        let mut node = NodeState::new(node_config, app_config);
+        node.scrape().await;
+        let (row, health) = StatusRow::from_node(&node);
+        worst = worst.max(health);
+        rows.push(row);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+    } else {
+        let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+        print!("{}", status_text(&rows, color));
+    }
+    Ok(exit_code(worst))
+}
+
+/// 0 healthy, 1 warning, 2 critical
+pub fn exit_code(health: HealthStatus) -> i32 {
+    match health {
+        HealthStatus::Good => 0,
+        HealthStatus::Warning => 1,
+        HealthStatus::Critical => 2,
+    }
+}
+
+pub fn health_label(health: HealthStatus) -> &'static str {
+    match health {
+        HealthStatus::Good => "ok",
+        HealthStatus::Warning => "warning",
+        HealthStatus::Critical => "critical",
+    }
+}
+
+/// The status table, with the health column colored when `color` is set
+fn status_text(rows: &[StatusRow], color: bool) -> String {
+    let mut out = format!(
+        "{:<20} {:<5} {:<8} {:>12} {:>5} {:>7} {:>5} {:>8} {:>4}  NOTES\n",
+        "NODE", "ROLE", "HEALTH", "BLOCK", "EPOCH", "SYNC", "PEERS", "MEMORY", "KES"
+    );
+    for row in rows {
+        // Pad before coloring so escape codes don't break the alignment
+        let health = format!("{:<8}", row.health);
+        let health = match (color, row.health) {
+            (false, _) => health,
+            (true, "ok") => format!("\x1b[32m{}\x1b[0m", health),
+            (true, "warning") => format!("\x1b[33m{}\x1b[0m", health),
+            (true, _) => format!("\x1b[31m{}\x1b[0m", health),
+        };
+        let notes = match &row.error {
+            Some(error) => error.clone(),
+            None => row.issues.join(", "),
+        };
+        out.push_str(&format!(
+            "{:<20} {:<5} {} {:>12} {:>5} {:>7} {:>5} {:>8} {:>4}  {}\n",
+            row.node,
+            row.role,
+            health,
+            opt(row.block_height),
+            opt(row.epoch),
+            row.sync_progress
+                .map(|p| format!("{:.1}%", p))
+                .unwrap_or_else(|| "—".to_string()),
+            opt(row.peers),
+            format_bytes(row.memory_bytes),
+            opt(row.kes_remaining),
+            notes,
+        ));
+    }
+    out
+}
+
+fn opt(value: Option<u64>) -> String {
+    value
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "—".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(health: &'static str) -> StatusRow {
+        StatusRow {
+            node: "relay1".to_string(),
+            role: "Relay".to_string(),
+            endpoint: "10.0.0.1:12798".to_string(),
+            health,
+            connected: true,
+            block_height: Some(11_200_000),
+            epoch: Some(530),
+            sync_progress: Some(100.0),
+            peers: Some(3),
+            memory_bytes: Some(5_600_000_000),
+            kes_remaining: None,
+            tip_age_secs: None,
+            issues: vec!["peers"],
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_status_text() {
+        let offline = StatusRow {
+            node: "bp".to_string(),
+            connected: false,
+            block_height: None,
+            issues: Vec::new(),
+            error: Some("connection refused".to_string()),
+            ..row("critical")
+        };
+        let text = status_text(&[row("warning"), offline.clone()], false);
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("NODE"));
+        assert_eq!(
+            lines[1],
+            "relay1               Relay warning      11200000   530  100.0%     3  5.22 GB    —  peers"
+        );
+        assert!(lines[2].starts_with("bp                   Relay critical            —"));
+        assert!(lines[2].ends_with("connection refused"));
+
+        // Colors don't shift the columns after the health field
+        let colored = status_text(&[offline], true);
+        assert!(colored.contains("\x1b[31mcritical\x1b[0m            —"));
+    }
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(exit_code(HealthStatus::Good), 0);
+        assert_eq!(exit_code(HealthStatus::Warning), 1);
+        assert_eq!(exit_code(HealthStatus::Critical), 2);
+        assert_eq!(
+            HealthStatus::Good.max(HealthStatus::Critical),
+            HealthStatus::Critical
+        );
+    }
+}
@@ -1,33 +1,130 @@
 //! Persistent metric history storage
 //!
 //! This module handles disk persistence of metric snapshots for long-term
-//! trend analysis across sessions. Data is stored as compressed JSON files
-//! organized by node and date.
+//! trend analysis across sessions. Storage is tiered:
 //!
-//! Storage location: ~/.local/share/sview/history/{node_name}/YYYY/MM/DD.json.gz
-
+//! - Full-resolution samples (one per refresh) for the last 24 hours, in an
+//!   append-only raw file
+//! - 5-minute aggregates (min/max/avg) for 30 days, in compressed daily files
+//! - Hourly aggregates beyond that, up to the retention period
+//!
+//! Raw samples: ~/.local/share/sview/history/{node_name}/raw.jsonl
+//! Aggregates: ~/.local/share/sview/history/{node_name}/YYYY/MM/DD.json.gz
+//! Epoch summaries: ~/.local/share/sview/epochs/{node_name}.json
+//! Alert events: ~/.local/share/sview/alerts/{node_name}.jsonl
+//! Outages: ~/.local/share/sview/outages/{node_name}.jsonl
+//! Pool stake: ~/.local/share/sview/stake/{node_name}.jsonl
+//! Node starts: ~/.local/share/sview/restarts/{node_name}.jsonl
+
+use crate::alerts::Alert;
+use crate::epochs::EpochSummary;
 use crate::history::MetricsHistory;
+use crate::influx::InfluxSink;
 use crate::metrics::NodeMetrics;
+use crate::restarts::NodeStart;
+use crate::timezone::{utc_date, utc_midnight, DisplayTimezone};
 use anyhow::{Context, Result};
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tracing::{debug, info, warn};
 
 /// Default retention period in days
-const DEFAULT_RETENTION_DAYS: u64 = 30;
-
-/// Minimum interval between saved samples (1 hour in seconds)
-const MIN_SAMPLE_INTERVAL_SECS: u64 = 3600;
+pub const DEFAULT_RETENTION_DAYS: u64 = 30;
+
+/// Default interval between saved samples (0 = every refresh)
+pub const DEFAULT_SAMPLE_INTERVAL_SECS: u64 = 0;
+
+/// How long full-resolution samples are kept before aggregation
+const RAW_RETENTION_SECS: u64 = 86400;
+
+/// Resolution of the aggregated tier kept for `FINE_RETENTION_DAYS`
+const FINE_RESOLUTION_SECS: u64 = 300;
+
+/// Days 5-minute aggregates are kept before being reduced to hourly
+const FINE_RETENTION_DAYS: u64 = 30;
+
+/// Resolution of the oldest tier
+const COARSE_RESOLUTION_SECS: u64 = 3600;
+
+/// How often raw samples are compacted into aggregates
+const COMPACTION_INTERVAL_SECS: u64 = 3600;
+
+/// Snapshot fields that are not metrics (skipped when aggregating)
+const NON_METRIC_FIELDS: [&str; 5] = ["version", "timestamp", "span_secs", "min", "max"];
+
+/// Current snapshot schema version
+///
+/// 1: chain, peer, memory, mempool, KES and TPS metrics
+/// 2: adds forging, forks, density, block delay CDFs, GC and connection counts
+/// 3: adds CPU usage
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+/// CSV export columns, in row order; new columns go at the end so scripts
+/// reading columns by position keep working
+const CSV_COLUMNS: [&str; 41] = [
+    "timestamp",
+    "datetime",
+    "span_secs",
+    "block_height",
+    "slot_num",
+    "epoch",
+    "slot_in_epoch",
+    "peers_connected",
+    "memory_used_bytes",
+    "mempool_txs",
+    "mempool_bytes",
+    "sync_progress",
+    "kes_period",
+    "kes_remaining",
+    "tps",
+    "density",
+    "tx_processed",
+    "forks",
+    "block_delay_s",
+    "block_delay_cdf_1s",
+    "block_delay_cdf_3s",
+    "block_delay_cdf_5s",
+    "blocks_served",
+    "blocks_late",
+    "blocks_adopted",
+    "blocks_didnt_adopt",
+    "about_to_lead",
+    "missed_slots",
+    "memory_heap_bytes",
+    "gc_minor",
+    "gc_major",
+    "cpu_ms",
+    "uptime_seconds",
+    "incoming_connections",
+    "outgoing_connections",
+    "full_duplex_connections",
+    "unidirectional_connections",
+    "hot_peers",
+    "warm_peers",
+    "cold_peers",
+    "cpu_percent",
+];
+
+/// Number of epoch summaries kept per node (~500 days on mainnet)
+const MAX_EPOCH_SUMMARIES: usize = 100;
 
 /// A single metric snapshot for persistence
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Fields added after version 1 default to `None` when reading older files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MetricSnapshot {
+    /// Schema version the snapshot was written with
+    #[serde(default = "legacy_snapshot_version")]
+    pub version: u32,
     /// Unix timestamp in seconds
     pub timestamp: u64,
     /// Block height
@@ -40,6 +137,8 @@ pub struct MetricSnapshot {
     pub slot_in_epoch: Option<u64>,
     /// Connected peers count
     pub peers_connected: Option<u64>,
+    /// Seconds since the node last saw a new block
+    pub tip_age_secs: Option<u64>,
     /// Memory usage in bytes
     pub memory_used: Option<u64>,
     /// Transactions in mempool
@@ -52,6 +151,128 @@ pub struct MetricSnapshot {
     pub kes_period: Option<u64>,
     /// KES remaining periods
     pub kes_remaining: Option<u64>,
+    /// Transactions per second (average over the last hour when available)
+    pub tps: Option<f64>,
+    /// Chain density (0-1)
+    pub density: Option<f64>,
+    /// Transactions processed since node start
+    pub tx_processed: Option<u64>,
+    /// Number of forks
+    pub forks: Option<u64>,
+    /// Block fetch delay in seconds
+    pub block_delay_s: Option<f64>,
+    /// Share of blocks fetched within 1s (0-1)
+    pub block_delay_cdf_1s: Option<f64>,
+    /// Share of blocks fetched within 3s (0-1)
+    pub block_delay_cdf_3s: Option<f64>,
+    /// Share of blocks fetched within 5s (0-1)
+    pub block_delay_cdf_5s: Option<f64>,
+    /// Blocks served to peers
+    pub blocks_served: Option<u64>,
+    /// Blocks received late
+    pub blocks_late: Option<u64>,
+    /// Blocks forged and adopted
+    pub blocks_adopted: Option<u64>,
+    /// Blocks forged but not adopted
+    pub blocks_didnt_adopt: Option<u64>,
+    /// Slots the node was leader for
+    pub about_to_lead: Option<u64>,
+    /// Missed leader slots
+    pub missed_slots: Option<u64>,
+    /// GHC heap size in bytes
+    pub memory_heap: Option<u64>,
+    /// GC minor collections
+    pub gc_minor: Option<u64>,
+    /// GC major collections
+    pub gc_major: Option<u64>,
+    /// CPU time in milliseconds
+    pub cpu_ms: Option<u64>,
+    /// CPU usage in percent of one core
+    pub cpu_percent: Option<f64>,
+    /// Node uptime in seconds
+    pub uptime_seconds: Option<f64>,
+    /// Incoming connections
+    pub incoming_connections: Option<u64>,
+    /// Outgoing connections
+    pub outgoing_connections: Option<u64>,
+    /// Full duplex connections
+    pub full_duplex_connections: Option<u64>,
+    /// Unidirectional connections
+    pub unidirectional_connections: Option<u64>,
+    /// P2P hot peers
+    pub hot_peers: Option<u64>,
+    /// P2P warm peers
+    pub warm_peers: Option<u64>,
+    /// P2P cold peers
+    pub cold_peers: Option<u64>,
+    /// Seconds this sample aggregates, starting at `timestamp` (0 = single sample)
+    #[serde(skip_serializing_if = "is_zero")]
+    pub span_secs: u64,
+    /// Per-metric minimum over the span (aggregates only)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub min: BTreeMap<String, f64>,
+    /// Per-metric maximum over the span (aggregates only)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub max: BTreeMap<String, f64>,
+}
+
+fn legacy_daily_version() -> u32 {
+    1
+}
+
+/// Upgrade a daily file's JSON to the current version, one version at a time
+///
+/// Returns the file and whether it was migrated. Files from a newer sview are
+/// rejected rather than parsed with fields silently dropped.
+fn migrate_daily(mut value: serde_json::Value) -> Result<(DailySnapshots, bool)> {
+    let mut version = value
+        .get("version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(1) as u32;
+    if version > DAILY_FILE_VERSION {
+        anyhow::bail!(
+            "file version {} is newer than supported ({})",
+            version,
+            DAILY_FILE_VERSION
+        );
+    }
+    if version == 0 {
+        anyhow::bail!("invalid file version 0");
+    }
+    let migrated = version < DAILY_FILE_VERSION;
+    while version < DAILY_FILE_VERSION {
+        MIGRATIONS[version as usize - 1](&mut value);
+        version += 1;
+    }
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert("version".to_string(), version.into());
+    }
+    let daily = serde_json::from_value(value)?;
+    Ok((daily, migrated))
+}
+
+/// Version 1 snapshots carry no schema version: tag them as schema 1
+fn migrate_v1_to_v2(value: &mut serde_json::Value) {
+    let Some(snapshots) = value
+        .get_mut("snapshots")
+        .and_then(serde_json::Value::as_array_mut)
+    else {
+        return;
+    };
+    for snapshot in snapshots {
+        if let Some(fields) = snapshot.as_object_mut() {
+            fields.entry("version").or_insert(1.into());
+        }
+    }
+}
+
+/// Snapshots written before versioning only held the version 1 fields
+fn legacy_snapshot_version() -> u32 {
+    1
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
 }
 
 impl MetricSnapshot {
@@ -66,41 +287,141 @@ impl MetricSnapshot {
         };
 
         Self {
+            version: SNAPSHOT_VERSION,
             timestamp,
             block_height: metrics.block_height,
             slot_num: metrics.slot_num,
             epoch: metrics.epoch,
             slot_in_epoch: metrics.slot_in_epoch,
             peers_connected: metrics.peers_connected,
+            tip_age_secs: None,
             memory_used: metrics.memory_used,
             mempool_txs: metrics.mempool_txs,
             mempool_bytes: metrics.mempool_bytes,
             sync_progress: metrics.sync_progress,
             kes_period: metrics.kes_period,
             kes_remaining: metrics.kes_remaining,
+            tps: None,
+            density: metrics.density,
+            tx_processed: metrics.tx_processed,
+            forks: metrics.forks,
+            block_delay_s: metrics.block_delay_s,
+            block_delay_cdf_1s: metrics.block_delay_cdf_1s,
+            block_delay_cdf_3s: metrics.block_delay_cdf_3s,
+            block_delay_cdf_5s: metrics.block_delay_cdf_5s,
+            blocks_served: metrics.blocks_served,
+            blocks_late: metrics.blocks_late,
+            blocks_adopted: metrics.blocks_adopted,
+            blocks_didnt_adopt: metrics.blocks_didnt_adopt,
+            about_to_lead: metrics.about_to_lead,
+            missed_slots: metrics.missed_slots,
+            memory_heap: metrics.memory_heap,
+            gc_minor: metrics.gc_minor,
+            gc_major: metrics.gc_major,
+            cpu_ms: metrics.cpu_ms,
+            cpu_percent: None,
+            uptime_seconds: metrics.uptime_seconds,
+            incoming_connections: metrics.incoming_connections,
+            outgoing_connections: metrics.outgoing_connections,
+            full_duplex_connections: metrics.full_duplex_connections,
+            unidirectional_connections: metrics.unidirectional_connections,
+            hot_peers: metrics.p2p.hot_peers,
+            warm_peers: metrics.p2p.warm_peers,
+            cold_peers: metrics.p2p.cold_peers,
+            span_secs: 0,
+            min: BTreeMap::new(),
+            max: BTreeMap::new(),
         }
     }
 }
 
-/// Daily file containing hourly samples
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// A period during which the node's metrics couldn't be scraped
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Outage {
+    /// Unix timestamp of the first failed scrape
+    pub start: u64,
+    /// Unix timestamp of the first successful scrape after it
+    pub end: u64,
+}
+
+/// The pool's stake from one chain API lookup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StakeSnapshot {
+    /// Unix timestamp of the lookup
+    pub timestamp: u64,
+    /// Live stake in lovelace
+    pub live_stake: u64,
+    /// Active stake in lovelace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_stake: Option<u64>,
+    /// Number of live delegators
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub live_delegators: Option<u64>,
+}
+
+/// Current daily file format version
+///
+/// 1: unversioned files with hourly, unversioned snapshots
+/// 2: tiered aggregates (`span_secs`, min/max) with versioned snapshots
+pub const DAILY_FILE_VERSION: u32 = 2;
+
+/// Upgrades from each daily file version to the next; `MIGRATIONS[0]`
+/// takes version 1 to 2
+const MIGRATIONS: [fn(&mut serde_json::Value); 1] = [migrate_v1_to_v2];
+
+/// Daily file containing aggregated samples
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailySnapshots {
+    /// File format version
+    #[serde(default = "legacy_daily_version")]
+    pub version: u32,
     /// Node name for verification
     pub node_name: String,
     /// Snapshots for this day
     pub snapshots: Vec<MetricSnapshot>,
 }
 
+/// How long history is kept and how often samples are saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Days of history kept on disk
+    pub retention_days: u64,
+    /// Minimum seconds between saved samples
+    pub sample_interval_secs: u64,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            retention_days: DEFAULT_RETENTION_DAYS,
+            sample_interval_secs: DEFAULT_SAMPLE_INTERVAL_SECS,
+        }
+    }
+}
+
+/// Disk usage of sview's data directory and the filesystem it lives on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Total size of the data directory in bytes
+    pub data_dir_bytes: u64,
+    /// Free space available on the filesystem in bytes (if `df` is available)
+    pub fs_available_bytes: Option<u64>,
+}
+
 /// Storage manager for persistent metric history
 pub struct StorageManager {
     /// Base directory for all storage
     base_dir: PathBuf,
     /// Node name (sanitized for filesystem)
     node_name: String,
-    /// Retention period
-    retention_days: u64,
-    /// Last save timestamp (to enforce hourly sampling)
+    /// Retention period and sampling interval
+    policy: RetentionPolicy,
+    /// Last save timestamp (to enforce the sampling interval)
     last_save_timestamp: Option<u64>,
+    /// Last compaction of raw samples into aggregates
+    last_compaction_timestamp: Option<u64>,
+    /// Where saved snapshots are also pushed (`[global.influx]`)
+    sink: Option<InfluxSink>,
 }
 
 impl StorageManager {
@@ -117,18 +438,47 @@ impl StorageManager {
         Self {
             base_dir,
             node_name: sanitized_name,
-            retention_days: DEFAULT_RETENTION_DAYS,
+            policy: RetentionPolicy::default(),
             last_save_timestamp: None,
+            last_compaction_timestamp: None,
+            sink: None,
         }
     }
 
-    /// Set custom retention period
-    #[allow(dead_code)]
-    pub fn with_retention_days(mut self, days: u64) -> Self {
-        self.retention_days = days;
+    /// Set the retention period and sampling interval
+    pub fn with_policy(mut self, policy: RetentionPolicy) -> Self {
+        self.policy = policy;
         self
     }
 
+    /// Change the retention period and sampling interval (on config reload)
+    pub fn set_policy(&mut self, policy: RetentionPolicy) {
+        self.policy = policy;
+    }
+
+    /// Also push every saved snapshot to a time-series database
+    pub fn with_sink(mut self, sink: Option<InfluxSink>) -> Self {
+        self.sink = sink;
+        self
+    }
+
+    /// Change where snapshots are pushed (on config reload)
+    pub fn set_sink(&mut self, sink: Option<InfluxSink>) {
+        self.sink = sink;
+    }
+
+    pub fn policy(&self) -> RetentionPolicy {
+        self.policy
+    }
+
+    /// Get the file holding full-resolution samples
+    fn raw_file(&self) -> PathBuf {
+        self.base_dir
+            .join("history")
+            .join(&self.node_name)
+            .join("raw.jsonl")
+    }
+
     /// Get the directory path for a specific date
     fn date_dir(&self, year: u32, month: u32) -> PathBuf {
         self.base_dir
@@ -144,27 +494,24 @@ impl StorageManager {
             .join(format!("{:02}.json.gz", day))
     }
 
-    /// Get current date components
-    fn current_date() -> (u32, u32, u32) {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
-        timestamp_to_date(now)
-    }
-
     /// Save a metric snapshot to disk
     ///
-    /// Only saves if enough time has passed since the last save (hourly sampling)
-    pub fn save_snapshot(&mut self, metrics: &NodeMetrics) -> Result<bool> {
+    /// Only saves if the sampling interval has passed since the last save
+    pub fn save_snapshot(
+        &mut self,
+        metrics: &NodeMetrics,
+        tps: Option<f64>,
+        tip_age_secs: Option<u64>,
+        cpu_percent: Option<f64>,
+    ) -> Result<bool> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
 
-        // Check if we should save (hourly sampling)
+        // Check if we should save (sampling interval)
         if let Some(last) = self.last_save_timestamp {
-            if now - last < MIN_SAMPLE_INTERVAL_SECS {
+            if now.saturating_sub(last) < self.policy.sample_interval_secs {
                 debug!("Skipping save - not enough time elapsed since last save");
                 return Ok(false);
             }
@@ -176,36 +523,25 @@ impl StorageManager {
             return Ok(false);
         }
 
-        let snapshot = MetricSnapshot::from_metrics(metrics);
-        let (year, month, day) = Self::current_date();
-
-        // Ensure directory exists
-        let dir = self.date_dir(year, month);
-        fs::create_dir_all(&dir)
-            .with_context(|| format!("Failed to create storage directory: {:?}", dir))?;
-
-        // Load existing daily file or create new
-        let file_path = self.date_file(year, month, day);
-        let mut daily = self.load_daily_file(&file_path).unwrap_or_else(|e| {
-            debug!("Creating new daily file (previous load failed: {})", e);
-            DailySnapshots {
-                node_name: self.node_name.clone(),
-                snapshots: Vec::new(),
-            }
-        });
-
-        // Append new snapshot
-        daily.snapshots.push(snapshot);
-
-        // Write back
-        self.write_daily_file(&file_path, &daily)?;
+        let mut snapshot = MetricSnapshot::from_metrics(metrics);
+        snapshot.tps = tps;
+        snapshot.tip_age_secs = tip_age_secs;
+        snapshot.cpu_percent = cpu_percent;
+        self.append_raw(&snapshot)?;
         self.last_save_timestamp = Some(now);
+        if let Some(sink) = &self.sink {
+            sink.push(&snapshot);
+        }
 
-        info!(
-            "Saved metric snapshot for '{}' ({} total samples today)",
-            self.node_name,
-            daily.snapshots.len()
-        );
+        let compaction_due = self
+            .last_compaction_timestamp
+            .map(|last| now.saturating_sub(last) >= COMPACTION_INTERVAL_SECS)
+            .unwrap_or(true);
+        if compaction_due {
+            if let Err(e) = self.compact(now) {
+                warn!("Failed to compact history for '{}': {}", self.node_name, e);
+            }
+        }
 
         Ok(true)
     }
@@ -221,9 +557,9 @@ impl StorageManager {
             .as_secs();
 
         // Iterate over the last retention_days
-        for days_ago in 0..self.retention_days {
+        for days_ago in 0..self.policy.retention_days {
             let target_ts = now.saturating_sub(days_ago * 86400);
-            let (year, month, day) = timestamp_to_date(target_ts);
+            let (year, month, day) = utc_date(target_ts);
             let file_path = self.date_file(year, month, day);
 
             if file_path.exists() {
@@ -240,6 +576,11 @@ impl StorageManager {
             }
         }
 
+        match self.load_raw() {
+            Ok(raw) => all_snapshots.extend(raw),
+            Err(e) => warn!("Failed to load raw samples: {}", e),
+        }
+
         // Sort by timestamp (oldest first) and limit
         all_snapshots.sort_by_key(|s| s.timestamp);
         if all_snapshots.len() > max_samples {
@@ -279,6 +620,13 @@ impl StorageManager {
             if let Some(v) = snapshot.sync_progress {
                 history.sync_progress.push(v);
             }
+            if let Some(v) = snapshot.cpu_percent {
+                history.cpu_usage.push(v);
+            }
+            if let Some(v) = snapshot.tps {
+                history.tps.push(v);
+                history.tps_stats.record(snapshot.timestamp, v);
+            }
         }
 
         Ok(())
@@ -290,7 +638,12 @@ impl StorageManager {
             .duration_since(UNIX_EPOCH)
             .unwrap_or_default()
             .as_secs();
-        let cutoff = now.saturating_sub(self.retention_days * 86400);
+        let cutoff = now.saturating_sub(self.policy.retention_days * 86400);
+
+        self.prune_alerts(cutoff)?;
+        self.prune_outages(cutoff)?;
+        self.prune_stake(cutoff)?;
+        self.prune_starts(cutoff)?;
 
         let history_dir = self.base_dir.join("history").join(&self.node_name);
         if !history_dir.exists() {
@@ -354,8 +707,186 @@ impl StorageManager {
         Ok(removed_count)
     }
 
-    /// Load a daily file
+    /// Append a full-resolution sample to the raw file
+    fn append_raw(&self, snapshot: &MetricSnapshot) -> Result<()> {
+        let path = self.raw_file();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create storage directory: {:?}", dir))?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {:?}", path))?;
+        let line =
+            serde_json::to_string(snapshot).with_context(|| "Failed to serialize snapshot")?;
+        writeln!(file, "{}", line).with_context(|| format!("Failed to write {:?}", path))?;
+        Ok(())
+    }
+
+    /// Load full-resolution samples (unreadable lines are skipped)
+    fn load_raw(&self) -> Result<Vec<MetricSnapshot>> {
+        let path = self.raw_file();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to open {:?}", path))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+
+    /// Replace the raw file's contents
+    fn write_raw(&self, snapshots: &[MetricSnapshot]) -> Result<()> {
+        let path = self.raw_file();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create storage directory: {:?}", dir))?;
+        }
+        let tmp = path.with_extension("jsonl.tmp");
+        let mut out = String::new();
+        for snapshot in snapshots {
+            out.push_str(
+                &serde_json::to_string(snapshot).with_context(|| "Failed to serialize snapshot")?,
+            );
+            out.push('\n');
+        }
+        fs::write(&tmp, out).with_context(|| format!("Failed to write {:?}", tmp))?;
+        fs::rename(&tmp, &path).with_context(|| format!("Failed to replace {:?}", path))?;
+        Ok(())
+    }
+
+    /// Move raw samples older than 24h into 5-minute aggregates, and reduce
+    /// days older than 30 days to hourly aggregates
+    pub fn compact(&mut self, now: u64) -> Result<()> {
+        let raw = self.load_raw()?;
+        // Cut on a bucket boundary so no 5-minute bucket is split
+        let mut boundary = now.saturating_sub(RAW_RETENTION_SECS);
+        boundary -= boundary % FINE_RESOLUTION_SECS;
+        let (old, recent): (Vec<_>, Vec<_>) = raw.into_iter().partition(|s| s.timestamp < boundary);
+
+        if !old.is_empty() {
+            let mut by_day: BTreeMap<(u32, u32, u32), Vec<MetricSnapshot>> = BTreeMap::new();
+            for snapshot in old {
+                by_day
+                    .entry(utc_date(snapshot.timestamp))
+                    .or_default()
+                    .push(snapshot);
+            }
+            for ((year, month, day), samples) in by_day {
+                self.merge_into_day(year, month, day, downsample(&samples, FINE_RESOLUTION_SECS))?;
+            }
+            self.write_raw(&recent)?;
+        }
+
+        // The first pass checks every day past the fine tier; later passes
+        // only the days that have aged into it since
+        let last_day = if self.last_compaction_timestamp.is_some() {
+            FINE_RETENTION_DAYS + 2
+        } else {
+            self.policy.retention_days
+        };
+        for days_ago in FINE_RETENTION_DAYS..last_day {
+            let (year, month, day) = utc_date(now.saturating_sub(days_ago * 86400));
+            let path = self.date_file(year, month, day);
+            if !path.exists() {
+                continue;
+            }
+            let mut daily = self.load_daily_file(&path)?;
+            if daily
+                .snapshots
+                .iter()
+                .any(|s| s.span_secs < COARSE_RESOLUTION_SECS)
+            {
+                daily.snapshots = downsample(&daily.snapshots, COARSE_RESOLUTION_SECS);
+                self.write_daily_file(&path, &daily)?;
+            }
+        }
+
+        self.last_compaction_timestamp = Some(now);
+        Ok(())
+    }
+
+    /// Store samples from an external source in the aggregated tiers
+    ///
+    /// Samples are aggregated to 5 minutes, or hourly when older than the
+    /// 5-minute tier. Buckets already in history are kept as they are.
+    /// Returns the number of stored aggregates.
+    pub fn import_snapshots(&self, snapshots: Vec<MetricSnapshot>) -> Result<usize> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let fine_cutoff = now.saturating_sub(FINE_RETENTION_DAYS * 86400);
+
+        let mut by_day: BTreeMap<(u32, u32, u32), Vec<MetricSnapshot>> = BTreeMap::new();
+        for snapshot in snapshots {
+            by_day
+                .entry(utc_date(snapshot.timestamp))
+                .or_default()
+                .push(snapshot);
+        }
+
+        let mut stored = 0;
+        for ((year, month, day), samples) in by_day {
+            let resolution = if utc_midnight(year, month, day).unwrap_or(0) < fine_cutoff {
+                COARSE_RESOLUTION_SECS
+            } else {
+                FINE_RESOLUTION_SECS
+            };
+            let aggregated = downsample(&samples, resolution);
+            stored += aggregated.len();
+            self.merge_into_day(year, month, day, aggregated)?;
+        }
+        Ok(stored)
+    }
+
+    /// Add aggregated samples to a daily file
+    fn merge_into_day(
+        &self,
+        year: u32,
+        month: u32,
+        day: u32,
+        samples: Vec<MetricSnapshot>,
+    ) -> Result<()> {
+        let dir = self.date_dir(year, month);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create storage directory: {:?}", dir))?;
+
+        let file_path = self.date_file(year, month, day);
+        let mut daily = if file_path.exists() {
+            self.load_daily_file(&file_path)?
+        } else {
+            DailySnapshots {
+                version: DAILY_FILE_VERSION,
+                node_name: self.node_name.clone(),
+                snapshots: Vec::new(),
+            }
+        };
+        daily.snapshots.extend(samples);
+        // Stable sort keeps existing samples ahead of re-imported duplicates
+        daily.snapshots.sort_by_key(|s| s.timestamp);
+        daily.snapshots.dedup_by_key(|s| s.timestamp);
+        self.write_daily_file(&file_path, &daily)?;
+
+        debug!(
+            "Compacted samples into {:?} ({} total)",
+            file_path,
+            daily.snapshots.len()
+        );
+        Ok(())
+    }
+
+    /// Load a daily file, upgrading older file versions
     fn load_daily_file(&self, path: &std::path::Path) -> Result<DailySnapshots> {
+        Ok(self.read_daily_file(path)?.0)
+    }
+
+    /// Load a daily file and report whether it needed migration
+    fn read_daily_file(&self, path: &std::path::Path) -> Result<(DailySnapshots, bool)> {
         let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
         let reader = BufReader::new(file);
         let mut decoder = GzDecoder::new(reader);
@@ -363,27 +894,92 @@ impl StorageManager {
         decoder
             .read_to_string(&mut json_str)
             .with_context(|| format!("Failed to decompress {:?}", path))?;
-        let daily: DailySnapshots = serde_json::from_str(&json_str)
+        let value: serde_json::Value = serde_json::from_str(&json_str)
             .with_context(|| format!("Failed to parse {:?}", path))?;
-        Ok(daily)
+        migrate_daily(value).with_context(|| format!("Failed to migrate {:?}", path))
+    }
+
+    /// Rewrite daily files from older versions in the current format
+    ///
+    /// A marker file records the version the history was last migrated to,
+    /// so this only walks the files once per format change. It is only
+    /// written once every file has migrated, so skipped files are retried.
+    /// Files are replaced atomically (see `write_daily_file`), so an
+    /// interrupted migration leaves the old-format file to retry; its
+    /// temporary file is removed on the next run.
+    pub fn migrate_files(&self) -> Result<usize> {
+        let history_dir = self.base_dir.join("history").join(&self.node_name);
+        if !history_dir.exists() {
+            return Ok(0);
+        }
+        let marker = history_dir.join(".version");
+        if fs::read_to_string(&marker).ok().as_deref() == Some(&DAILY_FILE_VERSION.to_string()) {
+            return Ok(0);
+        }
+
+        let mut migrated_count = 0;
+        let mut skipped = false;
+        for path in daily_files(&history_dir)? {
+            let _ = fs::remove_file(path.with_extension("gz.tmp"));
+            let (daily, migrated) = match self.read_daily_file(&path) {
+                Ok(result) => result,
+                Err(e) => {
+                    warn!("Skipping {:?}: {:#}", path, e);
+                    skipped = true;
+                    continue;
+                }
+            };
+            if migrated {
+                self.write_daily_file(&path, &daily)?;
+                migrated_count += 1;
+            }
+        }
+        if !skipped {
+            fs::write(&marker, DAILY_FILE_VERSION.to_string())
+                .with_context(|| format!("Failed to write {:?}", marker))?;
+        }
+
+        if migrated_count > 0 {
+            info!(
+                "Migrated {} history files for '{}' to version {}",
+                migrated_count, self.node_name, DAILY_FILE_VERSION
+            );
+        }
+        Ok(migrated_count)
     }
 
     /// Write a daily file
+    ///
+    /// Written to a `.tmp` file and renamed over the original, so a crash or
+    /// full disk mid-write leaves the previous contents in place.
     fn write_daily_file(&self, path: &std::path::Path, daily: &DailySnapshots) -> Result<()> {
-        let file = File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+        let tmp = path.with_extension("gz.tmp");
+        let file = File::create(&tmp).with_context(|| format!("Failed to create {:?}", tmp))?;
         let writer = BufWriter::new(file);
         let mut encoder = GzEncoder::new(writer, Compression::default());
         let json_str =
             serde_json::to_string(daily).with_context(|| "Failed to serialize snapshots")?;
         encoder
             .write_all(json_str.as_bytes())
-            .with_context(|| format!("Failed to write {:?}", path))?;
-        encoder.finish()?;
+            .with_context(|| format!("Failed to write {:?}", tmp))?;
+        encoder
+            .finish()?
+            .into_inner()
+            .map_err(|e| e.into_error())
+            .and_then(|file| file.sync_all())
+            .with_context(|| format!("Failed to write {:?}", tmp))?;
+        fs::rename(&tmp, path).with_context(|| format!("Failed to replace {:?}", path))?;
         Ok(())
     }
 
     /// Export all historical data to CSV
-    pub fn export_to_csv(&self, output_path: &std::path::Path) -> Result<usize> {
+    ///
+    /// The `datetime` column is rendered in `timezone`.
+    pub fn export_to_csv(
+        &self,
+        output_path: &std::path::Path,
+        timezone: DisplayTimezone,
+    ) -> Result<usize> {
         let snapshots = self.load_history(usize::MAX)?;
 
         let mut writer = BufWriter::new(
@@ -392,19 +988,14 @@ impl StorageManager {
         );
 
         // Write header
-        writeln!(
-            writer,
-            "timestamp,datetime,block_height,slot_num,epoch,slot_in_epoch,peers_connected,memory_used_bytes,mempool_txs,mempool_bytes,sync_progress,kes_period,kes_remaining"
-        )?;
+        writeln!(writer, "{}", CSV_COLUMNS.join(","))?;
 
         // Write data rows
         for snapshot in &snapshots {
-            let datetime = timestamp_to_iso8601(snapshot.timestamp);
-            writeln!(
-                writer,
-                "{},{},{},{},{},{},{},{},{},{},{},{},{}",
-                snapshot.timestamp,
-                datetime,
+            let row = [
+                snapshot.timestamp.to_string(),
+                timezone.iso8601(snapshot.timestamp),
+                snapshot.span_secs.to_string(),
                 opt_to_csv(snapshot.block_height),
                 opt_to_csv(snapshot.slot_num),
                 opt_to_csv(snapshot.epoch),
@@ -416,7 +1007,35 @@ impl StorageManager {
                 opt_f64_to_csv(snapshot.sync_progress),
                 opt_to_csv(snapshot.kes_period),
                 opt_to_csv(snapshot.kes_remaining),
-            )?;
+                opt_f64_to_csv(snapshot.tps),
+                opt_ratio_to_csv(snapshot.density),
+                opt_to_csv(snapshot.tx_processed),
+                opt_to_csv(snapshot.forks),
+                opt_ratio_to_csv(snapshot.block_delay_s),
+                opt_ratio_to_csv(snapshot.block_delay_cdf_1s),
+                opt_ratio_to_csv(snapshot.block_delay_cdf_3s),
+                opt_ratio_to_csv(snapshot.block_delay_cdf_5s),
+                opt_to_csv(snapshot.blocks_served),
+                opt_to_csv(snapshot.blocks_late),
+                opt_to_csv(snapshot.blocks_adopted),
+                opt_to_csv(snapshot.blocks_didnt_adopt),
+                opt_to_csv(snapshot.about_to_lead),
+                opt_to_csv(snapshot.missed_slots),
+                opt_to_csv(snapshot.memory_heap),
+                opt_to_csv(snapshot.gc_minor),
+                opt_to_csv(snapshot.gc_major),
+                opt_to_csv(snapshot.cpu_ms),
+                opt_f64_to_csv(snapshot.uptime_seconds),
+                opt_to_csv(snapshot.incoming_connections),
+                opt_to_csv(snapshot.outgoing_connections),
+                opt_to_csv(snapshot.full_duplex_connections),
+                opt_to_csv(snapshot.unidirectional_connections),
+                opt_to_csv(snapshot.hot_peers),
+                opt_to_csv(snapshot.warm_peers),
+                opt_to_csv(snapshot.cold_peers),
+                opt_f64_to_csv(snapshot.cpu_percent),
+            ];
+            writeln!(writer, "{}", row.join(","))?;
         }
 
         writer.flush()?;
@@ -428,17 +1047,469 @@ impl StorageManager {
 
         Ok(snapshots.len())
     }
+
+    /// Path of this node's epoch summary file
+    fn epochs_file(&self) -> PathBuf {
+        self.base_dir
+            .join("epochs")
+            .join(format!("{}.json", self.node_name))
+    }
+
+    /// Record a finished epoch's summary (replacing any earlier entry for it)
+    pub fn save_epoch_summary(&self, summary: &EpochSummary) -> Result<()> {
+        let mut summaries = self.load_epoch_summaries()?;
+        summaries.retain(|s| s.epoch != summary.epoch);
+        summaries.push(summary.clone());
+        summaries.sort_by_key(|s| s.epoch);
+        if summaries.len() > MAX_EPOCH_SUMMARIES {
+            summaries.drain(..summaries.len() - MAX_EPOCH_SUMMARIES);
+        }
+
+        let path = self.epochs_file();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create storage directory: {:?}", dir))?;
+        }
+        let json_str = serde_json::to_string_pretty(&summaries)
+            .with_context(|| "Failed to serialize epoch summaries")?;
+        fs::write(&path, json_str).with_context(|| format!("Failed to write {:?}", path))?;
+
+        info!(
+            "Saved epoch {} summary for '{}'",
+            summary.epoch, self.node_name
+        );
+        Ok(())
+    }
+
+    /// Load recorded epoch summaries (oldest first)
+    pub fn load_epoch_summaries(&self) -> Result<Vec<EpochSummary>> {
+        let path = self.epochs_file();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let json_str =
+            fs::read_to_string(&path).with_context(|| format!("Failed to open {:?}", path))?;
+        serde_json::from_str(&json_str).with_context(|| format!("Failed to parse {:?}", path))
+    }
+
+    /// Path of this node's alert event file (one JSON alert per line)
+    fn alerts_file(&self) -> PathBuf {
+        self.base_dir
+            .join("alerts")
+            .join(format!("{}.jsonl", self.node_name))
+    }
+
+    /// Append alert events to this node's alert history
+    pub fn record_alerts(&self, alerts: &[Alert]) -> Result<()> {
+        if alerts.is_empty() {
+            return Ok(());
+        }
+        let path = self.alerts_file();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create storage directory: {:?}", dir))?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {:?}", path))?;
+        for alert in alerts {
+            let line = serde_json::to_string(alert).with_context(|| "Failed to serialize alert")?;
+            writeln!(file, "{}", line).with_context(|| format!("Failed to write {:?}", path))?;
+        }
+        Ok(())
+    }
+
+    /// Load recorded alerts raised at or after `since` (oldest first)
+    ///
+    /// Unreadable lines (e.g. a partial write) are skipped.
+    pub fn load_alerts(&self, since: u64) -> Result<Vec<Alert>> {
+        let path = self.alerts_file();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to open {:?}", path))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Alert>(line).ok())
+            .filter(|alert| alert.timestamp >= since)
+            .collect())
+    }
+
+    /// Drop recorded alerts older than `cutoff`
+    fn prune_alerts(&self, cutoff: u64) -> Result<()> {
+        let path = self.alerts_file();
+        if !path.exists() {
+            return Ok(());
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to open {:?}", path))?;
+        let kept: Vec<&str> = content
+            .lines()
+            .filter(|line| {
+                serde_json::from_str::<Alert>(line).is_ok_and(|alert| alert.timestamp >= cutoff)
+            })
+            .collect();
+        if kept.len() != content.lines().count() {
+            let mut out = kept.join("\n");
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            fs::write(&path, out).with_context(|| format!("Failed to write {:?}", path))?;
+        }
+        Ok(())
+    }
+
+    /// Path of this node's outage file (one JSON outage per line)
+    fn outages_file(&self) -> PathBuf {
+        self.base_dir
+            .join("outages")
+            .join(format!("{}.jsonl", self.node_name))
+    }
+
+    /// Append an ended outage to this node's outage history
+    pub fn record_outage(&self, outage: &Outage) -> Result<()> {
+        let path = self.outages_file();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create storage directory: {:?}", dir))?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {:?}", path))?;
+        let line = serde_json::to_string(outage).with_context(|| "Failed to serialize outage")?;
+        writeln!(file, "{}", line).with_context(|| format!("Failed to write {:?}", path))?;
+        Ok(())
+    }
+
+    /// Load recorded outages that ended at or after `since` (oldest first)
+    pub fn load_outages(&self, since: u64) -> Result<Vec<Outage>> {
+        let path = self.outages_file();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to open {:?}", path))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Outage>(line).ok())
+            .filter(|outage| outage.end >= since)
+            .collect())
+    }
+
+    /// Drop recorded outages that ended before `cutoff`
+    fn prune_outages(&self, cutoff: u64) -> Result<()> {
+        let path = self.outages_file();
+        if !path.exists() {
+            return Ok(());
+        }
+        let kept = self.load_outages(cutoff)?;
+        let mut out = String::new();
+        for outage in &kept {
+            out.push_str(&serde_json::to_string(outage)?);
+            out.push('\n');
+        }
+        fs::write(&path, out).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    /// Path of this node's stake file (one JSON snapshot per line)
+    fn stake_file(&self) -> PathBuf {
+        self.base_dir
+            .join("stake")
+            .join(format!("{}.jsonl", self.node_name))
+    }
+
+    /// Append a stake snapshot to this node's stake history
+    pub fn record_stake(&self, snapshot: &StakeSnapshot) -> Result<()> {
+        let path = self.stake_file();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create storage directory: {:?}", dir))?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {:?}", path))?;
+        let line = serde_json::to_string(snapshot)
+            .with_context(|| "Failed to serialize stake snapshot")?;
+        writeln!(file, "{}", line).with_context(|| format!("Failed to write {:?}", path))?;
+        Ok(())
+    }
+
+    /// Load stake snapshots taken at or after `since` (oldest first)
+    pub fn load_stake(&self, since: u64) -> Result<Vec<StakeSnapshot>> {
+        let path = self.stake_file();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to open {:?}", path))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<StakeSnapshot>(line).ok())
+            .filter(|snapshot| snapshot.timestamp >= since)
+            .collect())
+    }
+
+    /// Drop stake snapshots taken before `cutoff`
+    fn prune_stake(&self, cutoff: u64) -> Result<()> {
+        let path = self.stake_file();
+        if !path.exists() {
+            return Ok(());
+        }
+        let kept = self.load_stake(cutoff)?;
+        let mut out = String::new();
+        for snapshot in &kept {
+            out.push_str(&serde_json::to_string(snapshot)?);
+            out.push('\n');
+        }
+        fs::write(&path, out).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    /// Path of this node's start history (one JSON start per line)
+    fn starts_file(&self) -> PathBuf {
+        self.base_dir
+            .join("restarts")
+            .join(format!("{}.jsonl", self.node_name))
+    }
+
+    /// Append a node start to this node's restart history
+    pub fn record_start(&self, start: &NodeStart) -> Result<()> {
+        let path = self.starts_file();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create storage directory: {:?}", dir))?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open {:?}", path))?;
+        let line =
+            serde_json::to_string(start).with_context(|| "Failed to serialize node start")?;
+        writeln!(file, "{}", line).with_context(|| format!("Failed to write {:?}", path))?;
+        Ok(())
+    }
+
+    /// Load recorded node starts (oldest first)
+    pub fn load_starts(&self) -> Result<Vec<NodeStart>> {
+        let path = self.starts_file();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content =
+            fs::read_to_string(&path).with_context(|| format!("Failed to open {:?}", path))?;
+        Ok(content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<NodeStart>(line).ok())
+            .collect())
+    }
+
+    /// Drop node starts before `cutoff`, keeping the latest so the current
+    /// run isn't taken for a new start
+    fn prune_starts(&self, cutoff: u64) -> Result<()> {
+        let path = self.starts_file();
+        if !path.exists() {
+            return Ok(());
+        }
+        let starts = self.load_starts()?;
+        let mut out = String::new();
+        for (i, start) in starts.iter().enumerate() {
+            if start.start_time >= cutoff || i + 1 == starts.len() {
+                out.push_str(&serde_json::to_string(start)?);
+                out.push('\n');
+            }
+        }
+        fs::write(&path, out).with_context(|| format!("Failed to write {:?}", path))
+    }
+
+    /// Size of this node's metric history on disk in bytes
+    pub fn history_size(&self) -> u64 {
+        dir_size(&self.history_dir())
+    }
+
+    /// Directory holding every node's data
+    pub fn data_dir(&self) -> &std::path::Path {
+        &self.base_dir
+    }
+
+    /// Directory holding this node's metric history
+    pub fn history_dir(&self) -> PathBuf {
+        self.base_dir.join("history").join(&self.node_name)
+    }
+}
+
+/// Measure a data directory's size and the free space on its filesystem
+///
+/// Walks the directory and runs `df`, so call it off the async runtime.
+pub fn disk_usage(data_dir: &std::path::Path) -> DiskUsage {
+    DiskUsage {
+        data_dir_bytes: dir_size(data_dir),
+        fs_available_bytes: filesystem_available(data_dir),
+    }
+}
+
+/// Aggregate samples into one per `span_secs` bucket
+///
+/// Each aggregate holds the average of every metric (rounded for integer
+/// metrics) plus its minimum and maximum over the bucket. Samples that are
+/// already aggregates contribute their own min/max.
+fn downsample(samples: &[MetricSnapshot], span_secs: u64) -> Vec<MetricSnapshot> {
+    let mut buckets: BTreeMap<u64, Vec<&MetricSnapshot>> = BTreeMap::new();
+    for sample in samples {
+        buckets
+            .entry(sample.timestamp - sample.timestamp % span_secs)
+            .or_default()
+            .push(sample);
+    }
+    buckets
+        .into_iter()
+        .filter_map(|(start, group)| aggregate(&group, start, span_secs))
+        .collect()
+}
+
+/// Combine samples from one bucket into a single aggregate
+fn aggregate(group: &[&MetricSnapshot], start: u64, span_secs: u64) -> Option<MetricSnapshot> {
+    // (sum, count, all values integers)
+    let mut sums: BTreeMap<String, (f64, usize, bool)> = BTreeMap::new();
+    let mut min: BTreeMap<String, f64> = BTreeMap::new();
+    let mut max: BTreeMap<String, f64> = BTreeMap::new();
+
+    for sample in group {
+        let serde_json::Value::Object(fields) = serde_json::to_value(sample).ok()? else {
+            return None;
+        };
+        for (key, value) in fields {
+            if NON_METRIC_FIELDS.contains(&key.as_str()) {
+                continue;
+            }
+            let Some(v) = value.as_f64() else {
+                continue;
+            };
+            let lo = sample.min.get(&key).copied().unwrap_or(v);
+            let hi = sample.max.get(&key).copied().unwrap_or(v);
+            min.entry(key.clone())
+                .and_modify(|m| *m = m.min(lo))
+                .or_insert(lo);
+            max.entry(key.clone())
+                .and_modify(|m| *m = m.max(hi))
+                .or_insert(hi);
+            let entry = sums.entry(key).or_insert((0.0, 0, true));
+            entry.0 += v;
+            entry.1 += 1;
+            entry.2 &= value.is_u64();
+        }
+    }
+
+    let mut fields = serde_json::Map::new();
+    fields.insert("timestamp".to_string(), start.into());
+    for (key, (sum, count, integer)) in sums {
+        let mean = sum / count as f64;
+        let value = if integer {
+            serde_json::Value::from(mean.round() as u64)
+        } else {
+            serde_json::Value::from(mean)
+        };
+        fields.insert(key, value);
+    }
+
+    let mut snapshot: MetricSnapshot =
+        serde_json::from_value(serde_json::Value::Object(fields)).ok()?;
+    snapshot.version = SNAPSHOT_VERSION;
+    snapshot.span_secs = span_secs;
+    snapshot.min = min;
+    snapshot.max = max;
+    Some(snapshot)
+}
+
+/// All daily files under a node's history directory (`YYYY/MM/DD.json.gz`)
+fn daily_files(history_dir: &std::path::Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for year in fs::read_dir(history_dir)?.flatten() {
+        if !year.path().is_dir() {
+            continue;
+        }
+        for month in fs::read_dir(year.path())?.flatten() {
+            if !month.path().is_dir() {
+                continue;
+            }
+            for day in fs::read_dir(month.path())?.flatten() {
+                if parse_date_from_path(&day.path()).is_some() {
+                    files.push(day.path());
+                }
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Recursively sum file sizes under a directory
+pub fn dir_size(path: &std::path::Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Query free space for the filesystem holding `path` using `df -Pk`
+fn filesystem_available(path: &std::path::Path) -> Option<u64> {
+    // The data dir may not exist yet; df needs an existing path
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(existing)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        debug!("df failed for {:?}", existing);
+        return None;
+    }
+
+    parse_df_available(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse the available bytes from POSIX `df -Pk` output
+fn parse_df_available(output: &str) -> Option<u64> {
+    // Filesystem 1024-blocks Used Available Capacity Mounted on
+    let line = output.lines().nth(1)?;
+    let available_kb: u64 = line.split_whitespace().nth(3)?.parse().ok()?;
+    Some(available_kb * 1024)
+}
+
+/// Data directory set for this run (demo mode keeps its files separate)
+static DATA_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use `path` instead of the platform data directory for this run
+pub fn set_data_dir(path: PathBuf) {
+    let _ = DATA_DIR_OVERRIDE.set(path);
 }
 
 /// Get the data directory for sview
-fn get_data_dir() -> PathBuf {
+pub fn get_data_dir() -> PathBuf {
+    if let Some(dir) = DATA_DIR_OVERRIDE.get() {
+        return dir.clone();
+    }
     dirs::data_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("sview")
 }
 
 /// Sanitize node name for use in filesystem paths
-fn sanitize_node_name(name: &str) -> String {
+pub fn sanitize_node_name(name: &str) -> String {
     name.chars()
         .map(|c| {
             if c.is_alphanumeric() || c == '-' || c == '_' {
@@ -451,62 +1522,10 @@ fn sanitize_node_name(name: &str) -> String {
         .to_lowercase()
 }
 
-/// Convert Unix timestamp to (year, month, day)
-fn timestamp_to_date(ts: u64) -> (u32, u32, u32) {
-    // Simple implementation - doesn't handle all edge cases but works for reasonable dates
-    let days_since_epoch = ts / 86400;
-    let mut remaining_days = days_since_epoch as i64;
-
-    let mut year = 1970;
-    loop {
-        let days_in_year = if is_leap_year(year) { 366 } else { 365 };
-        if remaining_days < days_in_year {
-            break;
-        }
-        remaining_days -= days_in_year;
-        year += 1;
-    }
-
-    let days_in_months: [i64; 12] = if is_leap_year(year) {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-
-    let mut month = 1;
-    for days in days_in_months {
-        if remaining_days < days {
-            break;
-        }
-        remaining_days -= days;
-        month += 1;
-    }
-
-    let day = remaining_days as u32 + 1;
-    (year, month, day)
-}
-
-#[allow(clippy::manual_is_multiple_of)]
-fn is_leap_year(year: u32) -> bool {
-    (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
-}
-
-/// Convert timestamp to ISO8601 datetime string
-fn timestamp_to_iso8601(ts: u64) -> String {
-    let (year, month, day) = timestamp_to_date(ts);
-    let seconds_in_day = ts % 86400;
-    let hour = seconds_in_day / 3600;
-    let minute = (seconds_in_day % 3600) / 60;
-    let second = seconds_in_day % 60;
-    format!(
-        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
-        year, month, day, hour, minute, second
-    )
-}
-
 /// Parse date from file path and convert to timestamp
 fn parse_date_from_path(path: &std::path::Path) -> Option<u64> {
-    let file_name = path.file_stem()?.to_str()?;
+    // `DD.json.gz`: file_stem would only strip the `.gz`
+    let file_name = path.file_name()?.to_str()?.strip_suffix(".json.gz")?;
     let day: u32 = file_name.parse().ok()?;
 
     let month_dir = path.parent()?;
@@ -515,33 +1534,7 @@ fn parse_date_from_path(path: &std::path::Path) -> Option<u64> {
     let year_dir = month_dir.parent()?;
     let year: u32 = year_dir.file_name()?.to_str()?.parse().ok()?;
 
-    Some(date_to_timestamp(year, month, day))
-}
-
-/// Convert (year, month, day) to Unix timestamp
-fn date_to_timestamp(year: u32, month: u32, day: u32) -> u64 {
-    let mut days: u64 = 0;
-
-    // Add days for years since 1970
-    for y in 1970..year {
-        days += if is_leap_year(y) { 366 } else { 365 };
-    }
-
-    // Add days for months in current year
-    let days_in_months: [u64; 12] = if is_leap_year(year) {
-        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    } else {
-        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]
-    };
-
-    for &days_in_month in days_in_months.iter().take((month - 1) as usize) {
-        days += days_in_month;
-    }
-
-    // Add days in current month (day - 1 because day 1 = 0 extra days)
-    days += (day - 1) as u64;
-
-    days * 86400
+    utc_midnight(year, month, day)
 }
 
 /// Convert Option<u64> to CSV string
@@ -549,6 +1542,11 @@ fn opt_to_csv(opt: Option<u64>) -> String {
     opt.map(|v| v.to_string()).unwrap_or_default()
 }
 
+/// Convert a ratio (density, CDF) to a CSV string, keeping precision
+fn opt_ratio_to_csv(opt: Option<f64>) -> String {
+    opt.map(|v| format!("{:.4}", v)).unwrap_or_default()
+}
+
 /// Convert Option<f64> to CSV string
 fn opt_f64_to_csv(opt: Option<f64>) -> String {
     opt.map(|v| format!("{:.2}", v)).unwrap_or_default()
@@ -585,30 +1583,6 @@ mod tests {
         assert_eq!(sanitize_node_name("Node@123"), "node_123");
     }
 
-    #[test]
-    fn test_timestamp_to_date() {
-        // 2024-01-15
-        let (y, m, d) = timestamp_to_date(1705276800);
-        assert_eq!((y, m, d), (2024, 1, 15));
-
-        // 2023-12-31
-        let (y, m, d) = timestamp_to_date(1704067199);
-        assert_eq!((y, m, d), (2023, 12, 31));
-    }
-
-    #[test]
-    fn test_date_to_timestamp() {
-        let ts = date_to_timestamp(2024, 1, 15);
-        let (y, m, d) = timestamp_to_date(ts);
-        assert_eq!((y, m, d), (2024, 1, 15));
-    }
-
-    #[test]
-    fn test_timestamp_to_iso8601() {
-        let iso = timestamp_to_iso8601(1705276800);
-        assert!(iso.starts_with("2024-01-15T"));
-    }
-
     #[test]
     fn test_metric_snapshot_from_metrics() {
         let metrics = create_test_metrics();
@@ -632,16 +1606,16 @@ mod tests {
         let metrics = create_test_metrics();
 
         // Save should succeed
-        let saved = manager.save_snapshot(&metrics).unwrap();
+        let saved = manager.save_snapshot(&metrics, None, None, None).unwrap();
         assert!(saved);
 
-        // Immediate second save should skip (hourly limit)
-        let saved2 = manager.save_snapshot(&metrics).unwrap();
-        assert!(!saved2);
+        // Every refresh is kept at full resolution by default
+        let saved2 = manager.save_snapshot(&metrics, None, None, None).unwrap();
+        assert!(saved2);
 
         // Load history
         let history = manager.load_history(100).unwrap();
-        assert_eq!(history.len(), 1);
+        assert_eq!(history.len(), 2);
         assert_eq!(history[0].block_height, Some(10500000));
     }
 
@@ -653,13 +1627,18 @@ mod tests {
         manager.base_dir = temp_dir.path().to_path_buf();
 
         let metrics = create_test_metrics();
-        manager.save_snapshot(&metrics).unwrap();
+        manager
+            .save_snapshot(&metrics, Some(1.5), None, Some(42.5))
+            .unwrap();
 
         let mut history = MetricsHistory::new(100);
         manager.populate_history(&mut history, 100).unwrap();
 
         assert_eq!(history.block_height.len(), 1);
         assert_eq!(history.peers_connected.len(), 1);
+        assert_eq!(history.tps.current(), Some(1.5));
+        assert_eq!(history.tps_stats.peak_24h(), Some(1.5));
+        assert_eq!(history.cpu_usage.current(), Some(42.5));
     }
 
     #[test]
@@ -670,15 +1649,418 @@ mod tests {
         manager.base_dir = temp_dir.path().to_path_buf();
 
         let metrics = create_test_metrics();
-        manager.save_snapshot(&metrics).unwrap();
+        manager.save_snapshot(&metrics, None, None, None).unwrap();
 
         let csv_path = temp_dir.path().join("export.csv");
-        let count = manager.export_to_csv(&csv_path).unwrap();
+        let count = manager
+            .export_to_csv(&csv_path, DisplayTimezone::Utc)
+            .unwrap();
         assert_eq!(count, 1);
 
         let csv_content = fs::read_to_string(&csv_path).unwrap();
         assert!(csv_content.contains("timestamp,datetime"));
         assert!(csv_content.contains("10500000"));
+        assert!(csv_content
+            .lines()
+            .next()
+            .unwrap()
+            .ends_with(",cold_peers,cpu_percent"));
+        // Every row has one value per column
+        for line in csv_content.lines() {
+            assert_eq!(line.split(',').count(), CSV_COLUMNS.len());
+        }
+    }
+
+    const V1_DAILY_FILE: &str = r#"{"node_name":"test_node","snapshots":[{"timestamp":1700000000,"block_height":10500000,"slot_num":null,"epoch":450,"slot_in_epoch":null,"peers_connected":12,"memory_used":null,"mempool_txs":null,"mempool_bytes":null,"sync_progress":100.0,"kes_period":null,"kes_remaining":null}]}"#;
+
+    #[test]
+    fn test_migrate_v1_to_v2() {
+        let value: serde_json::Value = serde_json::from_str(V1_DAILY_FILE).unwrap();
+        let (daily, migrated) = migrate_daily(value).unwrap();
+        assert!(migrated);
+        assert_eq!(daily.version, DAILY_FILE_VERSION);
+        assert_eq!(daily.snapshots.len(), 1);
+        assert_eq!(daily.snapshots[0].version, 1);
+        assert_eq!(daily.snapshots[0].block_height, Some(10500000));
+
+        // Current files load unchanged
+        let current = serde_json::to_value(&daily).unwrap();
+        let (_, migrated) = migrate_daily(current).unwrap();
+        assert!(!migrated);
+
+        // Files from a newer version are rejected
+        let newer = serde_json::json!({"version": DAILY_FILE_VERSION + 1, "node_name": "x", "snapshots": []});
+        assert!(migrate_daily(newer).is_err());
+        let zero = serde_json::json!({"version": 0, "node_name": "x", "snapshots": []});
+        assert!(migrate_daily(zero).is_err());
+    }
+
+    #[test]
+    fn test_migrate_files_rewrites_once() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Node");
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        let dir = manager.date_dir(2023, 11);
+        fs::create_dir_all(&dir).unwrap();
+        let path = manager.date_file(2023, 11, 14);
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(V1_DAILY_FILE.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+        // Left behind by an interrupted earlier run
+        fs::write(path.with_extension("gz.tmp"), "partial").unwrap();
+
+        assert_eq!(manager.migrate_files().unwrap(), 1);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        let (daily, migrated) = manager.read_daily_file(&path).unwrap();
+        assert!(!migrated);
+        assert_eq!(daily.version, DAILY_FILE_VERSION);
+
+        // The marker skips the walk next time
+        assert_eq!(manager.migrate_files().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_migrate_files_retries_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Node");
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        fs::create_dir_all(manager.date_dir(2023, 11)).unwrap();
+        let path = manager.date_file(2023, 11, 14);
+        fs::write(&path, b"not gzip").unwrap();
+        assert_eq!(manager.migrate_files().unwrap(), 0);
+        let history_dir = manager.base_dir.join("history").join(&manager.node_name);
+        assert!(history_dir.exists());
+        assert!(!history_dir.join(".version").exists());
+    }
+
+    #[test]
+    fn test_legacy_snapshot_deserializes() {
+        let json = r#"{"timestamp":1700000000,"block_height":10500000,"slot_num":null,"epoch":450,"slot_in_epoch":null,"peers_connected":12,"memory_used":null,"mempool_txs":null,"mempool_bytes":null,"sync_progress":100.0,"kes_period":null,"kes_remaining":null}"#;
+        let snapshot: MetricSnapshot = serde_json::from_str(json).unwrap();
+        assert_eq!(snapshot.version, 1);
+        assert_eq!(snapshot.block_height, Some(10500000));
+        assert_eq!(snapshot.tps, None);
+        assert_eq!(snapshot.forks, None);
+        assert_eq!(snapshot.span_secs, 0);
+
+        let current = MetricSnapshot::from_metrics(&create_test_metrics());
+        let roundtrip: MetricSnapshot =
+            serde_json::from_str(&serde_json::to_string(&current).unwrap()).unwrap();
+        assert_eq!(roundtrip.version, SNAPSHOT_VERSION);
+    }
+
+    #[test]
+    fn test_epoch_summaries_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Node");
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        assert!(manager.load_epoch_summaries().unwrap().is_empty());
+
+        let mut summary = EpochSummary {
+            epoch: 451,
+            first_seen: 1000,
+            last_seen: 2000,
+            blocks_seen: 21000,
+            blocks_forged: 3,
+            missed_slots: 0,
+            avg_peers: Some(12.5),
+            restarts: 0,
+            partial: false,
+        };
+        manager.save_epoch_summary(&summary).unwrap();
+        summary.epoch = 450;
+        manager.save_epoch_summary(&summary).unwrap();
+        // Re-saving an epoch replaces it
+        manager.save_epoch_summary(&summary).unwrap();
+
+        let loaded = manager.load_epoch_summaries().unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].epoch, 450);
+        assert_eq!(loaded[1].blocks_forged, 3);
+    }
+
+    #[test]
+    fn test_alerts_roundtrip_and_prune() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Node");
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        assert!(manager.load_alerts(0).unwrap().is_empty());
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let alert = |timestamp| Alert {
+            timestamp,
+            node_name: "Test Node".to_string(),
+            severity: crate::alerts::AlertSeverity::Warning,
+            title: "Low Peer Count".to_string(),
+            message: "Only 1 peer(s) connected".to_string(),
+            resolves: None,
+        };
+        let old = now - 90 * 86400;
+        manager.record_alerts(&[alert(old), alert(now)]).unwrap();
+
+        assert_eq!(manager.load_alerts(0).unwrap().len(), 2);
+        assert_eq!(manager.load_alerts(now).unwrap(), vec![alert(now)]);
+
+        // Cleanup drops alerts beyond the retention period
+        manager.cleanup_old_data().unwrap();
+        assert_eq!(manager.load_alerts(0).unwrap(), vec![alert(now)]);
+    }
+
+    #[test]
+    fn test_outages_roundtrip_and_prune() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Node");
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let old = Outage {
+            start: now - 90 * 86400,
+            end: now - 90 * 86400 + 60,
+        };
+        let recent = Outage {
+            start: now - 600,
+            end: now - 300,
+        };
+        manager.record_outage(&old).unwrap();
+        manager.record_outage(&recent).unwrap();
+
+        assert_eq!(manager.load_outages(0).unwrap(), vec![old, recent]);
+        assert_eq!(manager.load_outages(now - 400).unwrap(), vec![recent]);
+
+        manager.cleanup_old_data().unwrap();
+        assert_eq!(manager.load_outages(0).unwrap(), vec![recent]);
+    }
+
+    #[test]
+    fn test_starts_roundtrip_and_prune() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Relay");
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let first = NodeStart {
+            start_time: now - 90 * 86400,
+            previous_start: None,
+        };
+        let old = NodeStart {
+            start_time: now - 60 * 86400,
+            previous_start: Some(first.start_time),
+        };
+        let current = NodeStart {
+            start_time: now - 45 * 86400,
+            previous_start: Some(old.start_time),
+        };
+        manager.record_start(&first).unwrap();
+        manager.record_start(&old).unwrap();
+        manager.record_start(&current).unwrap();
+        assert_eq!(manager.load_starts().unwrap(), vec![first, old, current]);
+
+        // Past the retention period, but still the current run
+        manager.cleanup_old_data().unwrap();
+        assert_eq!(manager.load_starts().unwrap(), vec![current]);
+    }
+
+    #[test]
+    fn test_stake_roundtrip_and_prune() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test BP");
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let old = StakeSnapshot {
+            timestamp: now - 90 * 86400,
+            live_stake: 10_000_000_000_000,
+            active_stake: None,
+            live_delegators: None,
+        };
+        let recent = StakeSnapshot {
+            timestamp: now - 600,
+            live_stake: 12_500_000_000_000,
+            active_stake: Some(12_000_000_000_000),
+            live_delegators: Some(321),
+        };
+        manager.record_stake(&old).unwrap();
+        manager.record_stake(&recent).unwrap();
+
+        assert_eq!(manager.load_stake(0).unwrap(), vec![old, recent]);
+        manager.cleanup_old_data().unwrap();
+        assert_eq!(manager.load_stake(0).unwrap(), vec![recent]);
+    }
+
+    #[test]
+    fn test_parse_df_available() {
+        let output = "Filesystem     1024-blocks     Used Available Capacity Mounted on\n\
+                      /dev/vda1         20509264 18350000   1110000      95% /\n";
+        assert_eq!(parse_df_available(output), Some(1110000 * 1024));
+        assert_eq!(parse_df_available(""), None);
+    }
+
+    #[test]
+    fn test_disk_usage_counts_history() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Node");
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        assert_eq!(disk_usage(manager.data_dir()).data_dir_bytes, 0);
+        assert_eq!(manager.history_size(), 0);
+        manager
+            .save_snapshot(&create_test_metrics(), None, None, None)
+            .unwrap();
+        assert!(disk_usage(manager.data_dir()).data_dir_bytes > 0);
+        assert_eq!(
+            manager.history_size(),
+            disk_usage(manager.data_dir()).data_dir_bytes
+        );
+    }
+
+    #[test]
+    fn test_sample_interval_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Node").with_policy(RetentionPolicy {
+            retention_days: 7,
+            sample_interval_secs: 3600,
+        });
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        let metrics = create_test_metrics();
+        assert!(manager.save_snapshot(&metrics, None, None, None).unwrap());
+        // Second save within the interval is skipped
+        assert!(!manager.save_snapshot(&metrics, None, None, None).unwrap());
+        assert_eq!(manager.load_history(100).unwrap().len(), 1);
+    }
+
+    fn sample(timestamp: u64, peers: u64, sync: f64) -> MetricSnapshot {
+        let mut snapshot = MetricSnapshot::from_metrics(&create_test_metrics());
+        snapshot.timestamp = timestamp;
+        snapshot.peers_connected = Some(peers);
+        snapshot.sync_progress = Some(sync);
+        snapshot
+    }
+
+    #[test]
+    fn test_downsample() {
+        let samples = vec![
+            sample(600, 10, 99.0),
+            sample(700, 20, 100.0),
+            sample(899, 31, 100.0),
+            sample(900, 5, 98.0),
+        ];
+        let aggregated = downsample(&samples, 300);
+        assert_eq!(aggregated.len(), 2);
+
+        let first = &aggregated[0];
+        assert_eq!(first.timestamp, 600);
+        assert_eq!(first.span_secs, 300);
+        assert_eq!(first.peers_connected, Some(20));
+        assert_eq!(first.min["peers_connected"], 10.0);
+        assert_eq!(first.max["peers_connected"], 31.0);
+        assert!((first.sync_progress.unwrap() - 99.666).abs() < 0.01);
+        assert_eq!(aggregated[1].peers_connected, Some(5));
+
+        // Re-aggregating keeps the extremes of the finer tier
+        let hourly = downsample(&aggregated, 3600);
+        assert_eq!(hourly.len(), 1);
+        assert_eq!(hourly[0].min["peers_connected"], 5.0);
+        assert_eq!(hourly[0].max["peers_connected"], 31.0);
+    }
+
+    #[test]
+    fn test_import_snapshots_skips_existing() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Node");
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let start = now - 2 * 86400;
+        let start = start - start % 86400;
+        let imported: Vec<MetricSnapshot> = (0..12)
+            .map(|i| sample(start + i * 300, 10, 100.0))
+            .collect();
+
+        assert_eq!(manager.import_snapshots(imported.clone()).unwrap(), 12);
+        manager.import_snapshots(imported).unwrap();
+
+        let (year, month, day) = utc_date(start);
+        let daily = manager
+            .load_daily_file(&manager.date_file(year, month, day))
+            .unwrap();
+        assert_eq!(daily.snapshots.len(), 12);
+    }
+
+    #[test]
+    fn test_compaction_tiers() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = StorageManager::new("Test Node").with_policy(RetentionPolicy {
+            retention_days: 90,
+            sample_interval_secs: 0,
+        });
+        manager.base_dir = temp_dir.path().to_path_buf();
+
+        // A fixed midday time, so the samples below stay within one day
+        let now = 1_700_000_000 - 1_700_000_000 % 86400 + 43200;
+        let two_days_ago = now - 2 * 86400;
+        let raw: Vec<MetricSnapshot> = (0..60)
+            .map(|i| sample(two_days_ago + i * 10, 10 + i, 100.0))
+            .chain((0..5).map(|i| sample(now - i * 2, 50, 100.0)))
+            .collect();
+        manager.write_raw(&raw).unwrap();
+
+        // An old day still at 5-minute resolution
+        let old_day = now - 40 * 86400;
+        let (year, month, day) = utc_date(old_day);
+        let fine: Vec<MetricSnapshot> = (0..24)
+            .map(|i| sample(old_day - old_day % 3600 + i * 300, 10, 100.0))
+            .collect();
+        manager
+            .merge_into_day(year, month, day, downsample(&fine, 300))
+            .unwrap();
+
+        manager.compact(now).unwrap();
+
+        // Recent samples stay at full resolution
+        assert_eq!(manager.load_raw().unwrap().len(), 5);
+
+        // Samples from two days ago become two 5-minute aggregates
+        let (year, month, day) = utc_date(two_days_ago);
+        let daily = manager
+            .load_daily_file(&manager.date_file(year, month, day))
+            .unwrap();
+        assert_eq!(daily.snapshots.len(), 2);
+        assert!(daily.snapshots.iter().all(|s| s.span_secs == 300));
+        assert_eq!(daily.snapshots[0].min["peers_connected"], 10.0);
+
+        // The old day is reduced to hourly
+        let (year, month, day) = utc_date(old_day);
+        let daily = manager
+            .load_daily_file(&manager.date_file(year, month, day))
+            .unwrap();
+        assert_eq!(daily.snapshots.len(), 2);
+        assert!(daily.snapshots.iter().all(|s| s.span_secs == 3600));
+        // Rewritten through a temporary file that is renamed into place
+        let names: Vec<_> = fs::read_dir(manager.date_dir(year, month))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert_eq!(names, vec![format!("{:02}.json.gz", day)]);
     }
 
     #[test]
@@ -691,7 +2073,7 @@ mod tests {
         let mut metrics = create_test_metrics();
         metrics.connected = false;
 
-        let saved = manager.save_snapshot(&metrics).unwrap();
+        let saved = manager.save_snapshot(&metrics, None, None, None).unwrap();
         assert!(!saved);
     }
 }
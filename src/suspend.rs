@@ -0,0 +1,116 @@
+//! Terminal setup and job control (Ctrl+Z suspend, `fg` resume)
+//!
+//! Raw mode turns off the terminal's own Ctrl+Z handling, so the key arrives
+//! as an event and sview stops itself. The terminal is restored first (the
+//! shell would otherwise be left in raw mode on the alternate screen) and set
+//! up again with a full redraw when the process is continued. A `SIGTSTP`
+//! sent from elsewhere (e.g. `kill -TSTP`) takes the same path, and a
+//! `SIGCONT` after an external `SIGSTOP` re-enters the TUI.
+
+use anyhow::Result;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::prelude::*;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Switch to raw mode on the alternate screen
+pub fn enter_terminal() -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)
+}
+
+/// Return the terminal to the state the shell expects
+pub fn leave_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)
+}
+
+/// Job control signals received since the last check
+#[derive(Default)]
+pub struct JobControl {
+    stop_requested: Arc<AtomicBool>,
+    continued: Arc<AtomicBool>,
+    /// sview stopped itself, so the `SIGCONT` that follows was already
+    /// handled by [`JobControl::suspend`]
+    self_stopped: AtomicBool,
+}
+
+impl JobControl {
+    /// Listen for `SIGTSTP` and `SIGCONT` (no-op on non-Unix platforms)
+    pub fn install() -> Self {
+        let job_control = Self::default();
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            for (raw, flag) in [
+                (libc::SIGTSTP, &job_control.stop_requested),
+                (libc::SIGCONT, &job_control.continued),
+            ] {
+                match signal(SignalKind::from_raw(raw)) {
+                    Ok(mut stream) => {
+                        let flag = Arc::clone(flag);
+                        tokio::spawn(async move {
+                            while stream.recv().await.is_some() {
+                                flag.store(true, Ordering::SeqCst);
+                            }
+                        });
+                    }
+                    Err(e) => tracing::warn!("Failed to listen for signal {}: {}", raw, e),
+                }
+            }
+        }
+        job_control
+    }
+
+    /// Whether a `SIGTSTP` arrived
+    pub fn take_stop(&self) -> bool {
+        self.stop_requested.swap(false, Ordering::SeqCst)
+    }
+
+    /// Whether the process was continued after an external stop, and the
+    /// TUI needs setting up again
+    pub fn take_continued(&self) -> bool {
+        self.continued.swap(false, Ordering::SeqCst)
+            && !self.self_stopped.swap(false, Ordering::SeqCst)
+    }
+
+    /// Restore the terminal, stop the process, and set the TUI up again
+    /// once the shell continues it
+    pub fn suspend<B: Backend>(&self, terminal: &mut Terminal<B>) -> Result<()> {
+        if !cfg!(unix) {
+            return Ok(());
+        }
+        leave_terminal()?;
+        terminal.show_cursor()?;
+        self.self_stopped.store(true, Ordering::SeqCst);
+        stop_self();
+        resume(terminal)
+    }
+}
+
+/// Re-enter the TUI and force a full redraw
+pub fn resume<B: Backend>(terminal: &mut Terminal<B>) -> Result<()> {
+    enter_terminal()?;
+    terminal.clear()?;
+    Ok(())
+}
+
+/// Stop until `SIGCONT`
+///
+/// `SIGSTOP` rather than `SIGTSTP`, since the `SIGTSTP` handler installed by
+/// [`JobControl`] replaces the default stop action.
+#[cfg(unix)]
+fn stop_self() {
+    // SAFETY: raise has no memory-safety preconditions
+    unsafe {
+        libc::raise(libc::SIGSTOP);
+    }
+}
+
+#[cfg(not(unix))]
+fn stop_self() {}
@@ -31,7 +31,6 @@ pub enum Theme {
 
 impl Theme {
     /// Get all available themes
-    #[allow(dead_code)]
     pub fn all() -> &'static [Theme] {
         &[
             Theme::DarkDefault,
@@ -73,6 +72,21 @@ impl Theme {
         }
     }
 
+    /// Look up a theme by name, e.g. `dark-teal` or `Dark Teal`
+    pub fn from_name(name: &str) -> Option<Theme> {
+        let key = |s: &str| -> String {
+            s.chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .map(|c| c.to_ascii_lowercase())
+                .collect()
+        };
+        let wanted = key(name);
+        Theme::all()
+            .iter()
+            .copied()
+            .find(|theme| key(theme.display_name()) == wanted)
+    }
+
     /// Get color palette for this theme
     pub fn palette(&self) -> Palette {
         match self {
@@ -300,6 +314,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_theme_from_name() {
+        assert_eq!(Theme::from_name("dark-teal"), Some(Theme::DarkTeal));
+        assert_eq!(Theme::from_name("Light Cool"), Some(Theme::LightCool));
+        assert_eq!(Theme::from_name("monokai"), Some(Theme::Monokai));
+        assert_eq!(Theme::from_name("solarized"), None);
+    }
+
     #[test]
     fn test_palette_colors_are_distinct() {
         let palette = Theme::DarkDefault.palette();
@@ -0,0 +1,176 @@
+//! Date conversion and timezone-aware timestamp display
+//!
+//! Storage is organized by UTC date; timestamps shown to the user (TUI,
+//! CSV export, alert log and report) use the configured `timezone`:
+//! the system's local zone by default, UTC, or a fixed offset.
+
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, TimeZone, Timelike, Utc};
+use serde::Deserialize;
+use std::str::FromStr;
+
+/// Timezone used to display timestamps
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(try_from = "String")]
+pub enum DisplayTimezone {
+    /// The system's local timezone
+    #[default]
+    Local,
+    Utc,
+    /// A fixed offset from UTC, e.g. `+02:00`
+    Fixed(FixedOffset),
+}
+
+impl FromStr for DisplayTimezone {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "local" => Ok(Self::Local),
+            "utc" | "z" => Ok(Self::Utc),
+            offset => parse_offset(offset).map(Self::Fixed).ok_or_else(|| {
+                format!(
+                    "invalid timezone '{}' (expected \"local\", \"utc\" or an offset like \"+02:00\")",
+                    s
+                )
+            }),
+        }
+    }
+}
+
+impl TryFrom<String> for DisplayTimezone {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl DisplayTimezone {
+    /// Format a Unix timestamp with a chrono format string
+    fn format(&self, ts: u64, fmt: &str) -> String {
+        let Some(utc) = DateTime::<Utc>::from_timestamp(ts as i64, 0) else {
+            return "—".to_string();
+        };
+        match self {
+            Self::Local => utc.with_timezone(&Local).format(fmt).to_string(),
+            Self::Utc => utc.format(fmt).to_string(),
+            Self::Fixed(offset) => utc.with_timezone(offset).format(fmt).to_string(),
+        }
+    }
+
+    /// ISO 8601 date and time with the zone offset (`Z` for UTC)
+    pub fn iso8601(&self, ts: u64) -> String {
+        match self {
+            Self::Utc => self.format(ts, "%Y-%m-%dT%H:%M:%SZ"),
+            _ => self.format(ts, "%Y-%m-%dT%H:%M:%S%:z"),
+        }
+    }
+
+    /// Short date and time for tables, e.g. `2024-01-15 14:32`
+    pub fn datetime(&self, ts: u64) -> String {
+        self.format(ts, "%Y-%m-%d %H:%M")
+    }
+
+    /// Time of day, e.g. `14:32`
+    pub fn clock(&self, ts: u64) -> String {
+        self.format(ts, "%H:%M")
+    }
+
+    /// Minutes since midnight of a Unix timestamp
+    pub fn minute_of_day(&self, ts: u64) -> u32 {
+        let Some(utc) = DateTime::<Utc>::from_timestamp(ts as i64, 0) else {
+            return 0;
+        };
+        let time = match self {
+            Self::Local => utc.with_timezone(&Local).time(),
+            Self::Utc => utc.time(),
+            Self::Fixed(offset) => utc.with_timezone(offset).time(),
+        };
+        time.hour() * 60 + time.minute()
+    }
+
+    /// Time of day with seconds, e.g. `14:32:07`
+    pub fn clock_secs(&self, ts: u64) -> String {
+        self.format(ts, "%H:%M:%S")
+    }
+}
+
+/// Parse `+HH:MM`, `-HH:MM` or `+HH`
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let (sign, rest) = match s.as_bytes().first()? {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((h, m)) => (h.parse::<i32>().ok()?, m.parse::<i32>().ok()?),
+        None => (rest.parse::<i32>().ok()?, 0),
+    };
+    if minutes >= 60 {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// UTC calendar date (year, month, day) of a Unix timestamp
+pub fn utc_date(ts: u64) -> (u32, u32, u32) {
+    let date = Utc
+        .timestamp_opt(ts as i64, 0)
+        .single()
+        .unwrap_or_default()
+        .date_naive();
+    (date.year() as u32, date.month(), date.day())
+}
+
+/// Unix timestamp of UTC midnight on a date (`None` for invalid dates)
+pub fn utc_midnight(year: u32, month: u32, day: u32) -> Option<u64> {
+    let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_date_conversions() {
+        assert_eq!(utc_date(1705276800), (2024, 1, 15));
+        assert_eq!(utc_date(1704067199), (2023, 12, 31));
+        assert_eq!(utc_date(1709164800), (2024, 2, 29));
+
+        assert_eq!(utc_midnight(2024, 1, 15), Some(1705276800));
+        assert_eq!(utc_midnight(2023, 2, 29), None);
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!("local".parse(), Ok(DisplayTimezone::Local));
+        assert_eq!("UTC".parse(), Ok(DisplayTimezone::Utc));
+        assert_eq!(
+            "+02:00".parse(),
+            Ok(DisplayTimezone::Fixed(FixedOffset::east_opt(7200).unwrap()))
+        );
+        assert_eq!(
+            "-05".parse(),
+            Ok(DisplayTimezone::Fixed(
+                FixedOffset::east_opt(-18000).unwrap()
+            ))
+        );
+        assert!("Mars/Olympus".parse::<DisplayTimezone>().is_err());
+        assert!("+02:75".parse::<DisplayTimezone>().is_err());
+    }
+
+    #[test]
+    fn test_format_in_timezone() {
+        assert_eq!(
+            DisplayTimezone::Utc.iso8601(1705276800),
+            "2024-01-15T00:00:00Z"
+        );
+        let cest: DisplayTimezone = "+02:00".parse().unwrap();
+        assert_eq!(cest.iso8601(1705276800), "2024-01-15T02:00:00+02:00");
+        assert_eq!(cest.datetime(1705276800), "2024-01-15 02:00");
+        assert_eq!(cest.clock(1705276800), "02:00");
+        assert_eq!(cest.clock_secs(1705276807), "02:00:07");
+        assert_eq!(cest.minute_of_day(1705276800 + 90 * 60), 210);
+    }
+}
@@ -0,0 +1,289 @@
+//! SSH local port forwarding for firewalled metrics endpoints
+//!
+//! When a node's metrics port is only reachable through a bastion host, sview
+//! runs `ssh -N -L` itself and scrapes the forwarded local port. The ssh
+//! process is restarted if it exits (with a short backoff) and killed when the
+//! tunnel is dropped. Scrapes wait until the forwarded port accepts
+//! connections, and a picked local port that another process took in the
+//! meantime is swapped for a new one.
+//!
+//! For a metrics host given by name, requests still name that host and are
+//! only connected to the local port, so TLS verifies the certificate against
+//! it. A host given as an IP address is requested as `127.0.0.1`.
+
+use crate::config::{metrics_url, EndpointOptions, Scheme, SshTunnelConfig};
+use crate::metrics::MetricsClient;
+use anyhow::{Context, Result};
+use std::net::{IpAddr, SocketAddr, TcpListener};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Minimum time between ssh restarts
+const RESTART_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Interval between checks of the forwarded port while ssh starts
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// ssh starts tried with fresh local ports before giving up
+const PORT_ATTEMPTS: usize = 3;
+
+/// A managed `ssh -L` process forwarding a local port to a node's metrics port
+pub struct SshTunnel {
+    config: SshTunnelConfig,
+    /// Metrics host and port as seen from the bastion
+    remote_host: String,
+    remote_port: u16,
+    /// Local port the metrics endpoint is forwarded to
+    local_port: u16,
+    /// The local port was picked here, not configured
+    auto_port: bool,
+    /// The local port changed since `take_port_changed` was last called
+    port_changed: bool,
+    /// The running ssh has accepted a connection on the local port
+    ready: bool,
+    child: Option<Child>,
+    last_start: Option<Instant>,
+}
+
+impl SshTunnel {
+    /// Prepare a tunnel to `remote_host:remote_port`, reserving a local port
+    pub fn new(config: &SshTunnelConfig, remote_host: &str, remote_port: u16) -> Result<Self> {
+        let local_port = match config.local_port {
+            Some(port) => port,
+            None => free_local_port()?,
+        };
+        Ok(Self {
+            config: config.clone(),
+            remote_host: remote_host.to_string(),
+            remote_port,
+            local_port,
+            auto_port: config.local_port.is_none(),
+            port_changed: false,
+            ready: false,
+            child: None,
+            last_start: None,
+        })
+    }
+
+    /// Local end of the tunnel
+    pub fn local_addr(&self) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], self.local_port))
+    }
+
+    /// Metrics URL requested through the tunnel, and the host name to connect
+    /// to the local port for (`None` when the URL already names it)
+    fn route(&self, scheme: Scheme) -> (String, Option<&str>) {
+        if self.remote_host.parse::<IpAddr>().is_ok() {
+            (metrics_url(scheme, "127.0.0.1", self.local_port), None)
+        } else {
+            (
+                metrics_url(scheme, &self.remote_host, self.remote_port),
+                Some(&self.remote_host),
+            )
+        }
+    }
+
+    /// Client scraping the metrics endpoint through the tunnel
+    pub fn metrics_client(
+        &self,
+        timeout: Duration,
+        options: &EndpointOptions,
+    ) -> Result<MetricsClient> {
+        match self.route(options.scheme) {
+            (url, Some(host)) => {
+                MetricsClient::through_tunnel(url, timeout, options, host, self.local_addr())
+            }
+            (url, None) => MetricsClient::new(url, timeout, options, None),
+        }
+    }
+
+    /// Whether the local port changed (so clients need rebuilding) since
+    /// the last call
+    pub fn take_port_changed(&mut self) -> bool {
+        std::mem::take(&mut self.port_changed)
+    }
+
+    /// Short description for error messages
+    pub fn describe(&self) -> String {
+        format!("via {}", self.config.destination)
+    }
+
+    /// Start ssh if it isn't running, respecting the restart backoff
+    pub fn ensure_running(&mut self) -> Result<()> {
+        if let Some(child) = &mut self.child {
+            match child.try_wait() {
+                Ok(None) => return Ok(()),
+                Ok(Some(status)) => {
+                    self.child = None;
+                    if self
+                        .last_start
+                        .is_some_and(|t| t.elapsed() < RESTART_BACKOFF)
+                    {
+                        anyhow::bail!("SSH tunnel {} exited ({})", self.describe(), status);
+                    }
+                }
+                Err(e) => {
+                    self.child = None;
+                    return Err(e).context("Failed to check SSH tunnel process");
+                }
+            }
+        } else if self
+            .last_start
+            .is_some_and(|t| t.elapsed() < RESTART_BACKOFF)
+        {
+            anyhow::bail!("SSH tunnel {} is restarting", self.describe());
+        }
+
+        self.start()
+    }
+
+    /// Start ssh, on a fresh local port if an earlier ssh on a picked one
+    /// exited (its port may have been taken)
+    fn start(&mut self) -> Result<()> {
+        if self.auto_port && self.last_start.is_some() {
+            self.local_port = free_local_port()?;
+            self.port_changed = true;
+        }
+        self.ready = false;
+        self.last_start = Some(Instant::now());
+        let child = Command::new("ssh")
+            .args(self.ssh_args())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to start ssh (is OpenSSH installed?)")?;
+        self.child = Some(child);
+        Ok(())
+    }
+
+    /// Start ssh if needed and wait until the forwarded port accepts
+    /// connections
+    ///
+    /// ssh on a picked port that exits while starting is retried on a fresh
+    /// port, since another process may have bound the port first.
+    pub async fn ensure_ready(&mut self, timeout: Duration) -> Result<()> {
+        self.ensure_running()?;
+        if self.ready {
+            return Ok(());
+        }
+        let deadline = tokio::time::Instant::now() + timeout;
+        let mut attempts = 1;
+        loop {
+            let connect = tokio::net::TcpStream::connect(self.local_addr());
+            if let Ok(Ok(_)) = tokio::time::timeout(READY_POLL_INTERVAL, connect).await {
+                self.ready = true;
+                return Ok(());
+            }
+            if let Some(Ok(Some(status))) = self.child.as_mut().map(Child::try_wait) {
+                self.child = None;
+                if !self.auto_port || attempts >= PORT_ATTEMPTS {
+                    anyhow::bail!("SSH tunnel {} exited ({})", self.describe(), status);
+                }
+                attempts += 1;
+                self.start()?;
+            }
+            if tokio::time::Instant::now() >= deadline {
+                anyhow::bail!(
+                    "SSH tunnel {} not ready after {:?}",
+                    self.describe(),
+                    timeout
+                );
+            }
+            tokio::time::sleep(READY_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Arguments for the ssh command
+    fn ssh_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "-N".to_string(),
+            "-o".to_string(),
+            "ExitOnForwardFailure=yes".to_string(),
+            "-o".to_string(),
+            "BatchMode=yes".to_string(),
+            "-o".to_string(),
+            "ServerAliveInterval=15".to_string(),
+            "-L".to_string(),
+            format!(
+                "127.0.0.1:{}:{}:{}",
+                self.local_port, self.remote_host, self.remote_port
+            ),
+        ];
+        if let Some(port) = self.config.ssh_port {
+            args.push("-p".to_string());
+            args.push(port.to_string());
+        }
+        if let Some(identity) = &self.config.identity_file {
+            args.push("-i".to_string());
+            args.push(identity.display().to_string());
+        }
+        args.push(self.config.destination.clone());
+        args
+    }
+}
+
+impl Drop for SshTunnel {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Ask the OS for an unused local port
+fn free_local_port() -> Result<u16> {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).context("Failed to reserve a local port")?;
+    Ok(listener.local_addr()?.port())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_ssh_args() {
+        let config = SshTunnelConfig {
+            destination: "cardano@bastion.example.com".to_string(),
+            ssh_port: Some(2222),
+            identity_file: Some(PathBuf::from("/home/me/.ssh/id_ed25519")),
+            local_port: Some(22798),
+        };
+        let tunnel = SshTunnel::new(&config, "10.0.0.2", 12798).unwrap();
+        let args = tunnel.ssh_args();
+
+        assert!(args.contains(&"127.0.0.1:22798:10.0.0.2:12798".to_string()));
+        assert_eq!(args.last().unwrap(), "cardano@bastion.example.com");
+        assert!(args.windows(2).any(|w| w == ["-p", "2222"]));
+        assert_eq!(
+            tunnel.route(Scheme::Http),
+            ("http://127.0.0.1:22798/metrics".to_string(), None)
+        );
+
+        // A named host stays in the URL, for TLS verification
+        let tunnel = SshTunnel::new(&config, "relay1.internal", 12798).unwrap();
+        assert_eq!(
+            tunnel.route(Scheme::Https),
+            (
+                "https://relay1.internal:12798/metrics".to_string(),
+                Some("relay1.internal")
+            )
+        );
+        assert_eq!(tunnel.local_addr().port(), 22798);
+    }
+
+    #[test]
+    fn test_free_local_port() {
+        let config = SshTunnelConfig {
+            destination: "bastion".to_string(),
+            ssh_port: None,
+            identity_file: None,
+            local_port: None,
+        };
+        let tunnel = SshTunnel::new(&config, "127.0.0.1", 12798).unwrap();
+        assert_ne!(tunnel.local_port, 0);
+        assert!(!tunnel.ssh_args().contains(&"-p".to_string()));
+    }
+}
@@ -2,12 +2,30 @@
 //!
 //! This module handles all TUI rendering using ratatui.
 
-use crate::app::{App, AppMode, HealthStatus};
+use crate::alerts::{DENSITY_CRITICAL_RATIO, DENSITY_WARNING_RATIO};
+use crate::app::{App, AppMode, ConnectionState, HealthStatus, NodeState, NodeTab};
+use crate::cncli::{ForgeResult, SlotOutcome};
+use crate::config::PanelKind;
+use crate::format::{format_ada, format_bytes, format_duration, format_number};
+use crate::history::RateTracker;
+use crate::keys::{Action, KeyBindings};
+use crate::metrics::{ChurnCounts, PeerGroupCounts, PeerTargets};
+use crate::opcert::{counter_status, CounterStatus};
+use crate::peers::{
+    group_peers, network_diversity, LatencyBucket, NetworkDiversity, PeerGrouping, PeerState,
+    PeerStats,
+};
+use crate::pooltool::SendStatus;
+use crate::startup::StartupStatus;
+use crate::storage::StakeSnapshot;
 use crate::themes::Palette;
+use crate::timezone::DisplayTimezone;
 use ratatui::{
     prelude::*,
     symbols,
-    widgets::{Block, Borders, Cell, Clear, Gauge, Paragraph, Row, Sparkline, Table, Tabs, Wrap},
+    widgets::{
+        Block, Borders, Cell, Clear, Gauge, Padding, Paragraph, Row, Sparkline, Table, Tabs, Wrap,
+    },
 };
 
 /// Main draw function - renders the entire UI
@@ -20,6 +38,7 @@ pub fn draw(frame: &mut Frame, app: &App) {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
+                Constraint::Length(1), // Fleet summary
                 Constraint::Length(3), // Node tabs
                 Constraint::Length(3), // Header with status indicators
                 Constraint::Min(10),   // Main content
@@ -39,8 +58,9 @@ pub fn draw(frame: &mut Frame, app: &App) {
 
     // Draw node tabs if multi-node mode
     let (header_area, main_area, footer_area) = if app.is_multi_node() {
-        draw_node_tabs(frame, chunks[0], app, &palette);
-        (chunks[1], chunks[2], chunks[3])
+        draw_fleet_strip(frame, chunks[0], app, &palette);
+        draw_node_tabs(frame, chunks[1], app, &palette);
+        (chunks[2], chunks[3], chunks[4])
     } else {
         (chunks[0], chunks[1], chunks[2])
     };
@@ -48,15 +68,21 @@ pub fn draw(frame: &mut Frame, app: &App) {
     // Draw header with health indicators
     draw_header(frame, header_area, app, &palette);
 
-    // Draw main content area
-    draw_main_content(frame, main_area, app, &palette);
+    // Draw main content area (or a single expanded panel)
+    if app.mode == AppMode::Focus {
+        draw_focus_view(frame, main_area, app, &palette);
+    } else if let Some(status) = app.current_node().startup.status() {
+        draw_startup_panel(frame, main_area, app, status, &palette);
+    } else {
+        draw_main_content(frame, main_area, app, &palette);
+    }
 
     // Draw footer
     draw_footer(frame, footer_area, app, &palette);
 
     // Draw help overlay if in help mode
     if app.mode == AppMode::Help {
-        draw_help_popup(frame, area, app.is_multi_node(), &palette);
+        draw_help_popup(frame, area, app, &palette);
     }
 
     // Draw peers overlay if in peers mode
@@ -73,33 +99,320 @@ pub fn draw(frame: &mut Frame, app: &App) {
     if app.mode == AppMode::Graphs {
         draw_graphs_view(frame, area, app, &palette);
     }
+
+    // Draw epoch summary overlay if in epoch summary mode
+    if app.mode == AppMode::EpochSummary {
+        draw_epoch_summary_view(frame, area, app, &palette);
+    }
+
+    // Draw incidents overlay if in incidents mode
+    if app.mode == AppMode::Incidents {
+        draw_incidents_view(frame, area, app, &palette);
+    }
+
+    // Draw node comparison overlay if in benchmark mode
+    if app.mode == AppMode::Benchmark {
+        draw_benchmark_view(frame, area, app, &palette);
+    }
+
+    // Draw add-node form if in add-node mode
+    if app.mode == AppMode::AddNode {
+        draw_add_node_view(frame, area, app, &palette);
+    }
+
+    // Draw first-run setup over everything else
+    if app.mode == AppMode::Setup {
+        draw_setup_view(frame, area, app, &palette);
+    }
+
+    // Draw settings overlay if in settings mode
+    if app.mode == AppMode::Settings {
+        draw_settings_view(frame, area, app, &palette);
+    }
+
+    // Draw mempool overlay if in mempool mode
+    if app.mode == AppMode::Mempool {
+        draw_mempool_view(frame, area, app, &palette);
+    }
+
+    // Draw block propagation overlay if in propagation mode
+    if app.mode == AppMode::Propagation {
+        draw_propagation_view(frame, area, app, &palette);
+    }
+
+    // Draw the selected metric's explanation
+    if app.mode == AppMode::MetricInfo {
+        draw_metric_info(frame, area, app, &palette);
+    }
+}
+
+/// Labels of the dashboard's metric rows in the order ↑/↓ moves through
+/// them: panel by panel, each column top to bottom
+pub fn metric_labels(app: &App) -> Vec<String> {
+    let node = app.current_node();
+    if node.startup.status().is_some() {
+        return Vec::new();
+    }
+    let palette = app.theme.palette();
+    let layout = &app.app_config.layout;
+    let forging_panel = layout.iter().flatten().any(|p| *p == PanelKind::Forging);
+    layout
+        .iter()
+        .flatten()
+        .flat_map(|panel| match panel {
+            PanelKind::Chain => chain_metric_rows(node, !forging_panel, &palette),
+            PanelKind::Network => network_metric_rows(node, &palette),
+            PanelKind::Resources => resource_metric_rows(node, &palette),
+            PanelKind::Forging => forging_rows(node, &palette),
+            PanelKind::Governance => governance_rows(node, &palette),
+            // A per-epoch table rather than labelled rows
+            PanelKind::Rewards => Vec::new(),
+        })
+        .map(|row| row.label)
+        .collect()
+}
+
+/// Draw the explanation of the selected dashboard row: its meaning, healthy
+/// range, source metrics with their current values, and the kept history
+fn draw_metric_info(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let popup_area = centered_rect(60, 60, area);
+    frame.render_widget(Clear, popup_area);
+
+    let node = app.current_node();
+    let label = app.selected_metric.clone().unwrap_or_default();
+    let heading = |title: &'static str| {
+        Line::from(Span::styled(
+            title,
+            Style::default().bold().underlined().fg(palette.primary),
+        ))
+    };
+    let muted = Style::default().fg(palette.text_muted);
+    let text = Style::default().fg(palette.text);
+
+    let mut lines = Vec::new();
+    match crate::glossary::lookup(&label) {
+        Some(help) => {
+            lines.push(Line::from(Span::styled(help.description, text)));
+            lines.push(Line::from(""));
+            lines.push(heading("Healthy"));
+            lines.push(Line::from(Span::styled(help.healthy, text)));
+            if let Some(thresholds) = threshold_summary(&label, node) {
+                lines.push(Line::from(Span::styled(thresholds, muted)));
+            }
+
+            if let Some(series) = help.history {
+                let series = series(&node.history);
+                if let (Some(current), Some(min), Some(max), Some(avg)) =
+                    (series.current(), series.min(), series.max(), series.avg())
+                {
+                    lines.push(Line::from(""));
+                    lines.push(heading("History"));
+                    let value = |v: f64| format_history_value(&label, v);
+                    lines.push(Line::from(Span::styled(
+                        format!(
+                            "Current {}  Min {}  Max {}  Avg {}",
+                            value(current),
+                            value(min),
+                            value(max),
+                            value(avg)
+                        ),
+                        text,
+                    )));
+                    lines.push(Line::from(Span::styled(
+                        format!("over the last {} refreshes", series.len()),
+                        muted,
+                    )));
+                }
+            }
+
+            lines.push(Line::from(""));
+            lines.push(heading("Prometheus"));
+            if help.prometheus.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "Not a node metric: sview derives this row from other sources.",
+                    muted,
+                )));
+            }
+            for name in help.prometheus {
+                let value = match node.metrics.raw.get(*name) {
+                    Some(value) if value.fract() == 0.0 && value.abs() < 1e15 => {
+                        format_number(*value as u64)
+                    }
+                    Some(value) => format!("{:.4}", value),
+                    None => "not exported".to_string(),
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(*name, text),
+                    Span::styled(format!(" = {}", value), muted),
+                ]));
+            }
+        }
+        None => lines.push(Line::from(Span::styled(
+            "No description for this row. Custom metrics show the value of the Prometheus metric named in the config file.",
+            muted,
+        ))),
+    }
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", label))
+            .title_bottom(Line::from(" [↑↓] previous/next metric | [Esc] close ").centered())
+            .border_style(Style::default().fg(palette.primary))
+            .padding(Padding::horizontal(1)),
+    );
+    frame.render_widget(paragraph, popup_area);
+}
+
+/// The node's configured thresholds for rows that have them
+fn threshold_summary(label: &str, node: &NodeState) -> Option<String> {
+    let t = &node.thresholds;
+    match label {
+        "Connected" => Some(format!(
+            "This node: warning below {} peers, critical below {}",
+            t.peers_warning, t.peers_critical
+        )),
+        "Memory Used" => Some(format!(
+            "This node: warning at {:.1} GB, critical at {:.1} GB",
+            t.memory_warning_gb, t.memory_critical_gb
+        )),
+        "CPU" => Some(format!(
+            "This node: warning at {:.0}%, critical at {:.0}% of all cores",
+            t.cpu_warning_percent, t.cpu_critical_percent
+        )),
+        "Tip Age" => Some(format!(
+            "This node: warning at {}s, critical at {}s",
+            t.tip_age_warning_secs, t.tip_age_critical_secs
+        )),
+        "Density" => {
+            let expected = node.config.active_slots_coeff;
+            Some(format!(
+                "This node: warning below {:.4}, critical below {:.4}",
+                expected * DENSITY_WARNING_RATIO,
+                expected * DENSITY_CRITICAL_RATIO
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// A history value in the unit of its row
+fn format_history_value(label: &str, value: f64) -> String {
+    match label {
+        "Memory Used" => format_bytes(Some(value as u64)),
+        "CPU" => format!("{:.0}%", value),
+        "TPS" => format!("{:.2}", value),
+        _ => format_number(value.round() as u64),
+    }
+}
+
+/// Draw the one-line fleet summary above the node tabs
+fn draw_fleet_strip(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let fleet = app.fleet_summary();
+    let separator = || Span::styled("  │  ", Style::default().fg(palette.border));
+    let label = |text: &'static str| Span::styled(text, Style::default().fg(palette.text_muted));
+
+    let online_color = if fleet.online == fleet.total {
+        palette.healthy
+    } else if fleet.online == 0 {
+        palette.critical
+    } else {
+        palette.warning
+    };
+    let (lag_text, lag_color) = match &fleet.worst_lag {
+        Some((name, lag)) => (
+            format!("-{} ({})", lag, name),
+            if *lag > 2 {
+                palette.critical
+            } else {
+                palette.warning
+            },
+        ),
+        None if fleet.max_tip.is_some() => ("in sync".to_string(), palette.healthy),
+        None => ("—".to_string(), palette.text_muted),
+    };
+
+    let mut spans = vec![
+        Span::styled(" Fleet ", Style::default().fg(palette.primary).bold()),
+        label("Online: "),
+        Span::styled(
+            format!("{}/{}", fleet.online, fleet.total),
+            Style::default().fg(online_color),
+        ),
+        separator(),
+        label("Tip: "),
+        Span::styled(
+            fleet
+                .max_tip
+                .map(format_number)
+                .unwrap_or_else(|| "—".to_string()),
+            Style::default().fg(palette.text),
+        ),
+        separator(),
+        label("Worst lag: "),
+        Span::styled(lag_text, Style::default().fg(lag_color)),
+        separator(),
+        label("Peers: "),
+        Span::styled(
+            fleet.total_peers.to_string(),
+            Style::default().fg(palette.text),
+        ),
+    ];
+    if fleet.critical_nodes > 0 && app.alerts_silenced_for().is_none() {
+        spans.push(separator());
+        spans.push(Span::styled(
+            format!(
+                "⚠ {} node{} with critical alerts",
+                fleet.critical_nodes,
+                if fleet.critical_nodes == 1 { "" } else { "s" }
+            ),
+            Style::default().fg(palette.critical).bold(),
+        ));
+    }
+
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
 }
 
 /// Draw the node selection tabs
 fn draw_node_tabs(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
-    let titles: Vec<Line> = app
-        .nodes
+    let tabs = app.node_tabs();
+    let selected = tabs
         .iter()
-        .enumerate()
-        .map(|(i, node)| {
-            let health_color = health_to_color(node.overall_health(), palette);
-            let indicator = if node.metrics.connected { "●" } else { "○" };
-            let role_suffix = match node.role {
-                crate::config::NodeRole::Bp => " BP",
-                crate::config::NodeRole::Relay => "",
-            };
-            Line::from(vec![
-                Span::styled(indicator, Style::default().fg(health_color)),
-                Span::raw(" "),
-                Span::styled(
-                    format!("{}{}", node.config.node_name, role_suffix),
-                    Style::default().fg(palette.text),
-                ),
-                Span::styled(
-                    format!(" [{}]", i + 1),
-                    Style::default().fg(palette.text_muted),
-                ),
-            ])
+        .position(|tab| match tab {
+            NodeTab::Node(i) => *i == app.selected_node,
+            NodeTab::Group {
+                nodes, collapsed, ..
+            } => *collapsed && nodes.contains(&app.selected_node),
+        })
+        .unwrap_or(0);
+
+    let titles: Vec<Line> = tabs
+        .iter()
+        .map(|tab| match tab {
+            NodeTab::Node(i) => node_tab_title(app, *i, palette),
+            NodeTab::Group {
+                name,
+                nodes,
+                collapsed,
+            } => {
+                let (health, online) = app.group_health(nodes);
+                let mut spans = vec![
+                    Span::styled(
+                        if *collapsed { "▸ " } else { "▾ " },
+                        Style::default().fg(palette.text_muted),
+                    ),
+                    Span::styled(name.clone(), Style::default().fg(palette.secondary).bold()),
+                    Span::raw(" "),
+                    Span::styled("●", Style::default().fg(health_to_color(health, palette))),
+                ];
+                if *collapsed {
+                    spans.push(Span::styled(
+                        format!(" {}/{}", online, nodes.len()),
+                        Style::default().fg(palette.text_muted),
+                    ));
+                }
+                Line::from(spans)
+            }
         })
         .collect();
 
@@ -110,7 +423,7 @@ fn draw_node_tabs(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
                 .title(" Nodes ")
                 .border_style(Style::default().fg(palette.border)),
         )
-        .select(app.selected_node)
+        .select(selected)
         .style(Style::default().fg(palette.text))
         .highlight_style(
             Style::default()
@@ -122,6 +435,37 @@ fn draw_node_tabs(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
     frame.render_widget(tabs, area);
 }
 
+/// Tab title for a single node
+fn node_tab_title<'a>(app: &App, index: usize, palette: &Palette) -> Line<'a> {
+    let node = &app.nodes[index];
+    let health_color = health_to_color(node.overall_health(), palette);
+    let indicator = match node.connection_state() {
+        ConnectionState::Online => "●",
+        ConnectionState::Degraded | ConnectionState::MetricsDown => "◐",
+        ConnectionState::Offline => "○",
+    };
+    let role_suffix = match node.role {
+        crate::config::NodeRole::Bp => " BP",
+        crate::config::NodeRole::Relay => "",
+    };
+    Line::from(vec![
+        Span::styled(indicator, Style::default().fg(health_color)),
+        Span::raw(" "),
+        Span::styled(
+            format!("{}{}", node.config.node_name, role_suffix),
+            Style::default().fg(palette.text),
+        ),
+        Span::styled(
+            format!(" {}", node.config.network),
+            Style::default().fg(palette.secondary),
+        ),
+        Span::styled(
+            format!(" [{}]", index + 1),
+            Style::default().fg(palette.text_muted),
+        ),
+    ])
+}
+
 /// Draw the header section with health indicators
 fn draw_header(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
     let node = app.current_node();
@@ -133,10 +477,37 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
     let tip_health = node.tip_health();
     let mem_health = node.memory_health();
 
-    let status_indicator = if node.metrics.connected {
-        Span::styled("● ONLINE", Style::default().fg(palette.healthy).bold())
-    } else {
-        Span::styled("○ OFFLINE", Style::default().fg(palette.critical).bold())
+    let status_indicator = match node.connection_state() {
+        ConnectionState::Online => match node.startup.status() {
+            Some(startup) => Span::styled(
+                format!("◐ STARTING: {}", startup_summary(startup)),
+                Style::default().fg(palette.warning).bold(),
+            ),
+            None => Span::styled("● ONLINE", Style::default().fg(palette.healthy).bold()),
+        },
+        ConnectionState::Degraded => Span::styled(
+            format!(
+                "◐ DEGRADED ({} failed scrape{})",
+                node.consecutive_failures,
+                if node.consecutive_failures == 1 {
+                    ""
+                } else {
+                    "s"
+                }
+            ),
+            Style::default().fg(palette.warning).bold(),
+        ),
+        ConnectionState::MetricsDown => Span::styled(
+            "◐ METRICS DOWN",
+            Style::default().fg(palette.warning).bold(),
+        ),
+        ConnectionState::Offline => Span::styled(
+            match node.down_for() {
+                Some(secs) => format!("○ OFFLINE (for {})", format_duration(secs)),
+                None => "○ OFFLINE".to_string(),
+            },
+            Style::default().fg(palette.critical).bold(),
+        ),
     };
 
     let role_badge = match node.role {
@@ -181,12 +552,27 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
         .map(|p| p.to_string())
         .unwrap_or_else(|| "—".to_string());
 
-    // Check for critical alerts
-    let alert_span = if let Some(alert) = node.alert_manager.latest_critical() {
+    // Check for critical alerts (unless silenced with `:silence`)
+    let alert_span = if let Some(remaining) = app.alerts_silenced_for() {
+        vec![
+            Span::raw("  │  "),
+            Span::styled(
+                format!(
+                    "🔕 alerts silenced ({})",
+                    format_interval(remaining.as_secs().div_ceil(60) * 60)
+                ),
+                Style::default().fg(palette.text_muted),
+            ),
+        ]
+    } else if let Some(alert) = node.alert_manager.latest_critical() {
         vec![
             Span::raw("  │  "),
             Span::styled(
-                format!("⚠ {} ", alert.title),
+                format!(
+                    "⚠ {} ({}) ",
+                    alert.title,
+                    app.app_config.timezone.clock(alert.timestamp)
+                ),
                 Style::default().fg(palette.critical).bold(),
             ),
         ]
@@ -195,15 +581,23 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
     };
 
     // Build version string: prefer auto-detected from Prometheus, then config, then node type
-    let version_span = if let Some(version) = metrics.build_info.short_version() {
-        // Auto-detected from cardano_node_metrics_cardano_build_info
-        vec![
-            Span::styled(
-                format!("v{}", version),
-                Style::default().fg(palette.text_muted),
-            ),
-            Span::raw("  "),
-        ]
+    let version_span = if let Some(version) = node.node_version() {
+        // From the build_info metric, else the node's configured version
+        let mut spans = vec![Span::styled(
+            format!("v{}", version),
+            Style::default().fg(palette.text_muted),
+        )];
+        if let Some(latest) = node.latest_release.as_ref().filter(|r| {
+            node.cardano_node_version()
+                .is_some_and(|v| r.is_newer_than(v))
+        }) {
+            spans.push(Span::styled(
+                format!(" (update: {})", latest.version),
+                Style::default().fg(palette.warning),
+            ));
+        }
+        spans.push(Span::raw("  "));
+        spans
     } else if metrics.node_type != crate::metrics::NodeType::CardanoNode {
         // Show detected node type if not standard cardano-node
         vec![
@@ -250,40 +644,386 @@ fn draw_header(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
 
     let header_text = Line::from(header_spans);
 
-    let header = Paragraph::new(header_text).block(
-        Block::default()
-            .borders(Borders::ALL)
-            .title(format!(" sview — {} ", node.config.network))
-            .border_style(Style::default().fg(palette.border)),
-    );
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" sview — {} ", node.config.network))
+        .border_style(Style::default().fg(palette.border));
+    if let Some(leader) = &app.app_config.follow {
+        block = block.title_top(
+            Line::from(Span::styled(
+                format!(" ⇄ following {} (read-only) ", leader),
+                Style::default().fg(palette.tertiary).bold(),
+            ))
+            .right_aligned(),
+        );
+    }
+    if app.paused {
+        block = block.title_top(
+            Line::from(Span::styled(
+                format!(
+                    " ⏸ PAUSED ({} to resume) ",
+                    app.app_config.keys.short_label(Action::Pause)
+                ),
+                Style::default().fg(palette.warning).bold(),
+            ))
+            .right_aligned(),
+        );
+    }
+    if let (Some(fork), Some(secs)) = (&node.hard_fork, node.hard_fork_secs()) {
+        let version = node.cardano_node_version();
+        let (text, color) = if fork.requires_upgrade(version) {
+            (
+                format!(
+                    " ⑂ {} in {} — upgrade to {} (running {}) ",
+                    fork.describe(),
+                    format_time_remaining(secs),
+                    fork.min_node_version.as_deref().unwrap_or_default(),
+                    version.unwrap_or_default()
+                ),
+                palette.warning,
+            )
+        } else {
+            (
+                format!(" ⑂ {} in {} ", fork.describe(), format_time_remaining(secs)),
+                palette.tertiary,
+            )
+        };
+        block = block.title_bottom(
+            Line::from(Span::styled(text, Style::default().fg(color).bold())).centered(),
+        );
+    }
+    if let Some(title) = leader_slot_title(node, palette) {
+        block = block.title_top(title.centered());
+    }
+    let header = Paragraph::new(header_text).block(block);
 
     frame.render_widget(header, area);
 }
 
+/// Countdown to the next leader slot, the forging window during it and the
+/// Forge metrics' verdict after it
+fn leader_slot_title<'a>(node: &NodeState, palette: &Palette) -> Option<Line<'a>> {
+    let watch = node.leader_watch.as_ref()?;
+    let current = node.network_slot()?;
+    let (text, style) = match watch.result {
+        None if watch.forging(current) => (
+            format!(" ⚒ FORGING WINDOW — slot {} ", watch.slot),
+            Style::default()
+                .fg(palette.background)
+                .bg(palette.warning)
+                .bold(),
+        ),
+        None => (
+            format!(
+                " ⏱ Next leader slot in {} (slot {}) ",
+                format_clock_countdown(
+                    ((watch.slot - current) as f64 * node.config.slot_length_secs) as u64
+                ),
+                watch.slot
+            ),
+            Style::default().fg(palette.tertiary).bold(),
+        ),
+        Some((ForgeResult::Adopted, _)) => (
+            format!(" ✓ Block adopted — slot {} ", watch.slot),
+            Style::default().fg(palette.healthy).bold(),
+        ),
+        Some((ForgeResult::NotAdopted, _)) => (
+            format!(" ✗ Block forged but not adopted — slot {} ", watch.slot),
+            Style::default().fg(palette.critical).bold(),
+        ),
+        Some((ForgeResult::NoBlock, _)) => (
+            format!(" ✗ No block forged — slot {} ", watch.slot),
+            Style::default().fg(palette.critical).bold(),
+        ),
+    };
+    Some(Line::from(Span::styled(text, style)))
+}
+
 /// Draw the main content area
 fn draw_main_content(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
-    // 3 equal columns, each with gauge + metrics
+    // Columns of panels from the configured layout (default: chain, network, resources)
+    let layout = &app.app_config.layout;
+    let column_count = layout.len() as u32;
     let columns = Layout::default()
         .direction(Direction::Horizontal)
+        .constraints(
+            layout
+                .iter()
+                .map(|_| Constraint::Ratio(1, column_count))
+                .collect::<Vec<_>>(),
+        )
+        .split(area);
+
+    // Forging rows move out of the Chain panel when a dedicated panel is placed
+    let forging_panel = layout.iter().flatten().any(|p| *p == PanelKind::Forging);
+
+    for (panels, column_area) in layout.iter().zip(columns.iter()) {
+        let panel_count = panels.len() as u32;
+        let cells = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                panels
+                    .iter()
+                    .map(|_| Constraint::Ratio(1, panel_count))
+                    .collect::<Vec<_>>(),
+            )
+            .split(*column_area);
+
+        for (panel, cell) in panels.iter().zip(cells.iter()) {
+            match panel {
+                PanelKind::Chain => draw_chain_column(frame, *cell, app, !forging_panel, palette),
+                PanelKind::Network => draw_network_column(frame, *cell, app, palette),
+                PanelKind::Resources => draw_resources_column(frame, *cell, app, palette),
+                PanelKind::Forging => draw_forging_panel(frame, *cell, app, palette),
+                PanelKind::Rewards => draw_rewards_panel(frame, *cell, app, palette),
+                PanelKind::Governance => draw_governance_panel(frame, *cell, app, palette),
+            }
+        }
+    }
+}
+
+/// Startup phase with progress and ETA, e.g. "replaying ledger 45.0% (ETA 12m)"
+fn startup_summary(status: StartupStatus) -> String {
+    let mut summary = status.phase.label().to_string();
+    if let Some(progress) = status.progress {
+        summary.push_str(&format!(" {:.1}%", progress));
+    }
+    if let Some(eta) = status.eta_secs {
+        summary.push_str(&format!(" (ETA {})", format_time_remaining(eta)));
+    }
+    summary
+}
+
+/// Shown instead of the panels while the node validates its database or
+/// replays the ledger and has no chain metrics yet
+fn draw_startup_panel(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    status: StartupStatus,
+    palette: &Palette,
+) {
+    let node = app.current_node();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Node Starting ")
+        .border_style(Style::default().fg(palette.warning));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
         .constraints([
-            Constraint::Ratio(1, 3), // Chain column
-            Constraint::Ratio(1, 3), // Network column
-            Constraint::Ratio(1, 3), // Resources column
+            Constraint::Length(1),
+            Constraint::Length(3),
+            Constraint::Min(4),
+        ])
+        .split(inner);
+
+    let progress = status.progress.unwrap_or(0.0);
+    let label = match status.progress {
+        Some(progress) => format!("{} {:.1}%", status.phase.label(), progress),
+        None => format!("{} …", status.phase.label()),
+    };
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(palette.border)),
+        )
+        .gauge_style(Style::default().fg(palette.primary).bg(palette.gauge_bg))
+        .ratio(progress / 100.0)
+        .label(Span::styled(
+            label,
+            Style::default().fg(palette.gauge_label).bold(),
+        ));
+    frame.render_widget(gauge, chunks[1]);
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let row = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(
+                format!("  {:<14}", label),
+                Style::default().fg(palette.text_muted),
+            ),
+            Span::styled(value, Style::default().fg(palette.text)),
         ])
+    };
+    let lines = vec![
+        row("Phase", status.phase.label().to_string()),
+        row(
+            "ETA",
+            status
+                .eta_secs
+                .map(format_time_remaining)
+                .unwrap_or_else(|| "estimating…".to_string()),
+        ),
+        row(
+            "In phase for",
+            format_duration(now.saturating_sub(status.since)),
+        ),
+        row("Node uptime", format_uptime(node.metrics.uptime_seconds)),
+        Line::from(""),
+        Line::from(Span::styled(
+            "  Health checks and alerts resume once the node reports chain metrics.",
+            Style::default().fg(palette.text_muted),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), chunks[2]);
+}
+
+/// Draw one panel expanded: its regular contents, every raw metric in its
+/// category, and larger history sparklines
+fn draw_focus_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let node = app.current_node();
+    let panel = app.focused_panel_kind();
+    let history = &node.history;
+
+    // Larger sparklines of the panel's history (scaled to integers)
+    let series: Vec<(&str, Vec<f64>)> = match panel {
+        PanelKind::Chain => {
+            let heights = history.block_height.as_slice_f64();
+            let blocks = heights.windows(2).map(|w| (w[1] - w[0]).max(0.0)).collect();
+            vec![
+                (
+                    "TPS (×100)",
+                    history
+                        .tps
+                        .as_slice_f64()
+                        .iter()
+                        .map(|v| v * 100.0)
+                        .collect(),
+                ),
+                ("Blocks per Refresh", blocks),
+            ]
+        }
+        PanelKind::Network => vec![
+            ("Connected Peers", history.peers_connected.as_slice_f64()),
+            ("Hot Peers", history.p2p_hot_peers.as_slice_f64()),
+        ],
+        PanelKind::Resources => vec![
+            (
+                "Memory Used (MB)",
+                history
+                    .memory_used
+                    .as_slice_f64()
+                    .iter()
+                    .map(|v| v / 1_048_576.0)
+                    .collect(),
+            ),
+            ("CPU %", history.cpu_usage.as_slice_f64()),
+            ("Mempool Txs", history.mempool_txs.as_slice_f64()),
+        ],
+        PanelKind::Forging | PanelKind::Rewards | PanelKind::Governance => vec![],
+    };
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
         .split(area);
 
-    draw_chain_column(frame, columns[0], app, palette);
-    draw_network_column(frame, columns[1], app, palette);
-    draw_resources_column(frame, columns[2], app, palette);
+    match panel {
+        PanelKind::Chain => draw_chain_column(frame, columns[0], app, true, palette),
+        PanelKind::Network => draw_network_column(frame, columns[0], app, palette),
+        PanelKind::Resources => draw_resources_column(frame, columns[0], app, palette),
+        PanelKind::Forging => draw_forging_panel(frame, columns[0], app, palette),
+        PanelKind::Rewards => draw_rewards_panel(frame, columns[0], app, palette),
+        PanelKind::Governance => draw_governance_panel(frame, columns[0], app, palette),
+    }
+
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(if series.is_empty() {
+            vec![Constraint::Min(5)]
+        } else {
+            vec![Constraint::Min(5), Constraint::Percentage(45)]
+        })
+        .split(columns[1]);
+
+    // Every raw metric in the panel's category
+    let metrics = app.focused_raw_metrics();
+    let visible = right[0].height.saturating_sub(2) as usize;
+    let scroll = app.focus_scroll.min(metrics.len().saturating_sub(visible));
+    let rows: Vec<Row> = metrics
+        .iter()
+        .skip(scroll)
+        .map(|(name, value)| {
+            let label = name.strip_prefix("cardano_node_metrics_").unwrap_or(name);
+            let value = if value.fract() == 0.0 && value.abs() < 1e15 {
+                format_number(*value as u64)
+            } else {
+                format!("{:.4}", value)
+            };
+            Row::new(vec![
+                Cell::from(label.to_string()).style(Style::default().fg(palette.text_muted)),
+                Cell::from(value).style(Style::default().fg(palette.text)),
+            ])
+        })
+        .collect();
+    let table = Table::new(
+        rows,
+        [Constraint::Percentage(70), Constraint::Percentage(30)],
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                " {} — {} raw metrics (j/k scroll, ←/→ panel, Esc back) ",
+                panel.title(),
+                metrics.len()
+            ))
+            .border_style(Style::default().fg(palette.border)),
+    );
+    frame.render_widget(table, right[0]);
+
+    if series.is_empty() {
+        return;
+    }
+    let chart_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![
+            Constraint::Ratio(1, series.len() as u32);
+            series.len()
+        ])
+        .split(right[1]);
+    for ((title, values), chart_area) in series.iter().zip(chart_areas.iter()) {
+        let data: Vec<u64> = values.iter().map(|v| v.round().max(0.0) as u64).collect();
+        let range = match (data.iter().min(), data.iter().max(), data.last()) {
+            (Some(min), Some(max), Some(last)) => {
+                format!(" {}: {} (min {}, max {}) ", title, last, min, max)
+            }
+            _ => format!(" {} ", title),
+        };
+        let sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(range)
+                    .border_style(Style::default().fg(palette.border)),
+            )
+            .data(&data)
+            .style(Style::default().fg(palette.sparkline));
+        frame.render_widget(sparkline, *chart_area);
+    }
 }
 
 /// Draw chain column (epoch gauge + chain metrics)
-fn draw_chain_column(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+fn draw_chain_column(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    include_forging: bool,
+    palette: &Palette,
+) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3), // Epoch gauge
             Constraint::Min(5),    // Chain metrics
+            Constraint::Length(3), // TPS sparkline
         ])
         .split(area);
 
@@ -326,10 +1066,29 @@ fn draw_chain_column(frame: &mut Frame, area: Rect, app: &App, palette: &Palette
     frame.render_widget(gauge, chunks[0]);
 
     // Chain metrics
-    draw_chain_metrics(frame, chunks[1], app, palette);
-}
+    draw_chain_metrics(frame, chunks[1], app, include_forging, palette);
 
-/// Draw network column (sync gauge + network metrics)
+    // TPS sparkline (scaled to hundredths so fractional rates keep their shape)
+    let tps_data: Vec<u64> = node
+        .history
+        .tps
+        .as_slice_f64()
+        .iter()
+        .map(|v| (v * 100.0).round() as u64)
+        .collect();
+    let tps_sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" TPS ")
+                .border_style(Style::default().fg(palette.border)),
+        )
+        .data(&tps_data)
+        .style(Style::default().fg(palette.sparkline));
+    frame.render_widget(tps_sparkline, chunks[2]);
+}
+
+/// Draw network column (sync gauge + network metrics)
 fn draw_network_column(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -381,6 +1140,7 @@ fn draw_resources_column(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
         .constraints([
             Constraint::Length(3), // Memory gauge
             Constraint::Min(5),    // Resource metrics
+            Constraint::Length(3), // CPU gauge
         ])
         .split(area);
 
@@ -420,16 +1180,45 @@ fn draw_resources_column(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
 
     frame.render_widget(gauge, chunks[0]);
 
+    // CPU usage gauge, filled by the share of all the host's cores; the label
+    // is in percent of one core, so multi-threaded nodes can exceed 100%
+    let cpu = node.cpu_percent();
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" CPU ")
+                .border_style(Style::default().fg(palette.border)),
+        )
+        .gauge_style(
+            Style::default()
+                .fg(health_to_color(node.cpu_health(), palette))
+                .bg(palette.gauge_bg),
+        )
+        .ratio(
+            node.cpu_load_percent()
+                .map_or(0.0, |pct| (pct / 100.0).clamp(0.0, 1.0)),
+        )
+        .label(Span::styled(
+            format_cpu(cpu),
+            Style::default().fg(palette.gauge_label).bold(),
+        ));
+    frame.render_widget(gauge, chunks[2]);
+
     // Resource metrics
     draw_resource_metrics(frame, chunks[1], app, palette);
 }
 
-/// Draw chain metrics table
-fn draw_chain_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
-    let node = app.current_node();
+/// Rows of the Chain panel
+fn chain_metric_rows(
+    node: &NodeState,
+    include_forging: bool,
+    palette: &Palette,
+) -> Vec<MetricRow<'static>> {
     let metrics = &node.metrics;
     let tip_health = node.tip_health();
-    let kes_health = node.kes_health();
+    let slot_rate_health = node.slot_rate_health();
+    let density_health = node.density_health();
 
     let mut rows = vec![
         create_metric_row_with_trend(
@@ -440,24 +1229,146 @@ fn draw_chain_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Palett
         ),
         create_health_row(
             "Tip Age",
-            format_tip_age(node.tip_age_secs()),
+            with_measurement_delay(format_tip_age(node.tip_age_secs()), node),
             tip_health,
             palette,
         ),
+    ];
+    if node.reference_tip.is_some() {
+        rows.push(create_health_row(
+            "Network Lag",
+            format_network_lag(node.network_lag()),
+            node.network_lag_health(),
+            palette,
+        ));
+    }
+    rows.extend([
         create_metric_row("Slot", format_metric_u64(metrics.slot_num), palette),
         create_metric_row(
             "Slot in Epoch",
             format_metric_u64(metrics.slot_in_epoch),
             palette,
         ),
-        create_metric_row("Density", format_density(metrics.density), palette),
+        create_health_row(
+            "Slot Rate",
+            format_slot_rate(node.slot_rate(), node.slot_rate_ratio(), slot_rate_health),
+            slot_rate_health,
+            palette,
+        ),
+        create_metric_row(
+            "Blocks/min",
+            node.blocks_per_minute()
+                .map(|b| format!("{:.2}", b))
+                .unwrap_or_else(|| "—".to_string()),
+            palette,
+        ),
+        create_health_row(
+            "Density",
+            format_density_with_hour(metrics.density, node.density_1h()),
+            density_health,
+            palette,
+        ),
         create_metric_row(
             "TX Processed",
             format_metric_u64(metrics.tx_processed),
             palette,
         ),
+        create_metric_row(
+            "TPS",
+            format_tps(
+                node.history.tps.current(),
+                node.history.tps_stats.avg_1h(),
+                node.history.tps_stats.peak_24h(),
+            ),
+            palette,
+        ),
         create_metric_row("Forks", format_metric_u64(metrics.forks), palette),
-    ];
+    ]);
+
+    // Add pool info from chain API if configured
+    if let Some(pool) = &node.pool_info {
+        let pool_label = match &pool.ticker {
+            Some(ticker) => format!(
+                "[{}] {} blocks",
+                ticker,
+                format_metric_u64(pool.block_count)
+            ),
+            None => format!("{} blocks", format_metric_u64(pool.block_count)),
+        };
+        rows.push(create_metric_row("Pool", pool_label, palette));
+        rows.push(create_metric_row(
+            "Live Stake",
+            format_ada(pool.live_stake),
+            palette,
+        ));
+        if let Some(trend) = format_stake_trend(&node.stake_history) {
+            rows.push(create_metric_row("Stake Trend", trend, palette));
+        }
+    }
+    if let Some(pooltool) = &node.pooltool {
+        let (value, health) = match (&node.log_file, pooltool.status()) {
+            (None, _) => ("needs log_file".to_string(), HealthStatus::Warning),
+            (Some(_), None) => ("waiting for a tip".to_string(), HealthStatus::Good),
+            (
+                Some(_),
+                Some(SendStatus::Accepted {
+                    block_no, message, ..
+                }),
+            ) => {
+                let detail = match pooltool.rank() {
+                    Some((rank, ranked)) => format!("rank {}/{}", rank, ranked),
+                    None => message,
+                };
+                (
+                    format!("✓ #{} {}", format_number(block_no), detail)
+                        .trim_end()
+                        .to_string(),
+                    HealthStatus::Good,
+                )
+            }
+            (Some(_), Some(SendStatus::Failed(error))) => {
+                (format!("✗ {}", error), HealthStatus::Warning)
+            }
+        };
+        rows.push(create_health_row("Pooltool", value, health, palette));
+    }
+
+    rows.extend(custom_metric_rows(node, PanelKind::Chain, palette));
+    if include_forging {
+        rows.extend(forging_rows(node, palette));
+    }
+    rows
+}
+
+/// Draw chain metrics table
+fn draw_chain_metrics(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    include_forging: bool,
+    palette: &Palette,
+) {
+    let rows = chain_metric_rows(app.current_node(), include_forging, palette);
+
+    let table = Table::new(
+        highlight_selected(rows, app, palette),
+        [Constraint::Percentage(50), Constraint::Percentage(50)],
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Chain ")
+            .border_style(Style::default().fg(palette.border)),
+    );
+
+    frame.render_widget(table, area);
+}
+
+/// Build block production rows (KES, OpCert, forging stats)
+fn forging_rows<'a>(node: &NodeState, palette: &Palette) -> Vec<MetricRow<'a>> {
+    let metrics = &node.metrics;
+    let kes_health = node.kes_health();
+    let mut rows = Vec::new();
 
     // Add KES row only if available (block producer)
     if metrics.kes_remaining.is_some() {
@@ -470,20 +1381,18 @@ fn draw_chain_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Palett
     }
 
     // Add OpCert validation if available (block producer)
-    if let (Some(disk), Some(chain)) = (metrics.op_cert_counter_disk, metrics.op_cert_counter_chain)
-    {
-        let (op_cert_status, op_cert_health) = if disk == chain {
-            (format!("✓ {} (valid)", disk), HealthStatus::Good)
-        } else if disk > chain {
-            (
-                format!("⚠ disk:{} chain:{}", disk, chain),
-                HealthStatus::Warning,
-            )
-        } else {
-            (
-                format!("✗ disk:{} < chain:{}", disk, chain),
+    if let Some((disk, chain)) = node.op_cert_counters() {
+        let (op_cert_status, op_cert_health) = match (counter_status(disk, chain), chain) {
+            (_, None) => (format!("✓ {} (no blocks yet)", disk), HealthStatus::Good),
+            (CounterStatus::Valid, _) => (format!("✓ {} (valid)", disk), HealthStatus::Good),
+            (CounterStatus::Rotated, Some(chain)) => (
+                format!("✓ {} (new, chain:{})", disk, chain),
+                HealthStatus::Good,
+            ),
+            (CounterStatus::Mismatch, Some(chain)) => (
+                format!("✗ disk:{} chain:{}", disk, chain),
                 HealthStatus::Critical,
-            )
+            ),
         };
         rows.push(create_health_row(
             "OpCert",
@@ -491,6 +1400,13 @@ fn draw_chain_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Palett
             op_cert_health,
             palette,
         ));
+    } else if let Some(error) = &node.opcert_error {
+        rows.push(create_health_row(
+            "OpCert",
+            error.clone(),
+            HealthStatus::Warning,
+            palette,
+        ));
     }
 
     // Add forging metrics if available
@@ -525,23 +1441,281 @@ fn draw_chain_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Palett
         ));
     }
 
+    // Leader schedule and block outcomes from cncli (if configured)
+    if let Some(cncli) = &node.cncli {
+        let lost = cncli.count(SlotOutcome::Missed) + cncli.count(SlotOutcome::Orphaned);
+        rows.push(create_health_row(
+            "Leader Slots",
+            format!(
+                "{} ({} adopted, {} lost)",
+                cncli.slots.len(),
+                cncli.count(SlotOutcome::Adopted),
+                lost
+            ),
+            if lost > 0 {
+                HealthStatus::Warning
+            } else {
+                HealthStatus::Good
+            },
+            palette,
+        ));
+        let next_slot = metrics
+            .slot_num
+            .and_then(|current| Some((cncli.next_slot(current)?, current)));
+        let next_slot_str = match next_slot {
+            Some((next, current)) => format!(
+                "in {} (slot {})",
+                format_time_remaining(
+                    ((next - current) as f64 * node.config.slot_length_secs) as u64
+                ),
+                next
+            ),
+            None => format!("none in epoch {}", cncli.epoch),
+        };
+        rows.push(create_metric_row("Next Slot", next_slot_str, palette));
+    } else if let Some(error) = &node.cncli_error {
+        rows.push(create_health_row(
+            "cncli",
+            error.clone(),
+            HealthStatus::Warning,
+            palette,
+        ));
+    }
+
+    rows.extend(custom_metric_rows(node, PanelKind::Forging, palette));
+    rows
+}
+
+/// Draw the dedicated forging panel (when placed in the layout)
+fn draw_forging_panel(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let node = app.current_node();
+    let mut rows = forging_rows(node, palette);
+
+    if rows.is_empty() {
+        rows.push(create_metric_row(
+            "Forging",
+            "No block production metrics".to_string(),
+            palette,
+        ));
+    }
+
     let table = Table::new(
-        rows,
+        highlight_selected(rows, app, palette),
         [Constraint::Percentage(50), Constraint::Percentage(50)],
     )
     .block(
         Block::default()
             .borders(Borders::ALL)
-            .title(" Chain ")
+            .title(" Forging ")
             .border_style(Style::default().fg(palette.border)),
     );
 
     frame.render_widget(table, area);
 }
 
-/// Draw network and peer metrics
-fn draw_network_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+/// Draw the pool's per-epoch rewards and ROS with min/max/avg
+fn draw_rewards_panel(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
     let node = app.current_node();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Rewards ")
+        .border_style(Style::default().fg(palette.border));
+
+    if node.rewards.is_empty() {
+        let message = if node.pool_id.is_some() {
+            "Waiting for the chain API…"
+        } else {
+            "Set pool_id to show rewards"
+        };
+        let paragraph = Paragraph::new(Line::from(Span::styled(
+            format!(" {}", message),
+            Style::default().fg(palette.text_muted).italic(),
+        )))
+        .block(block);
+        frame.render_widget(paragraph, area);
+        return;
+    }
+
+    let header = Row::new(
+        ["EPOCH", "BLOCKS", "REWARDS", "ROS"]
+            .into_iter()
+            .map(|h| Cell::from(Span::styled(h, Style::default().fg(palette.primary).bold()))),
+    );
+    let mut rows: Vec<Row> = node
+        .rewards
+        .iter()
+        .map(|reward| {
+            Row::new(vec![
+                Cell::from(reward.epoch.to_string()),
+                Cell::from(format_metric_u64(reward.blocks)),
+                Cell::from(format_ada(reward.delegator_rewards)),
+                Cell::from(format_ros(reward.ros)),
+            ])
+            .style(Style::default().fg(palette.text))
+        })
+        .collect();
+
+    let ros: Vec<f64> = node.rewards.iter().filter_map(|r| r.ros).collect();
+    if let Some((min, max, avg)) = ros_stats(&ros) {
+        let muted = Style::default().fg(palette.text_muted);
+        rows.push(Row::new(vec![Cell::from("")]));
+        for (label, value) in [("min", min), ("max", max), ("avg", avg)] {
+            rows.push(Row::new(vec![
+                Cell::from(Span::styled(label, muted)),
+                Cell::from(""),
+                Cell::from(""),
+                Cell::from(Span::styled(
+                    format_ros(Some(value)),
+                    Style::default().fg(palette.primary),
+                )),
+            ]));
+        }
+    }
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(6),
+            Constraint::Length(7),
+            Constraint::Min(9),
+            Constraint::Length(7),
+        ],
+    )
+    .header(header)
+    .block(block);
+    frame.render_widget(table, area);
+}
+
+/// Minimum, maximum and average of per-epoch ROS values
+fn ros_stats(ros: &[f64]) -> Option<(f64, f64, f64)> {
+    if ros.is_empty() {
+        return None;
+    }
+    let min = ros.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = ros.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let avg = ros.iter().sum::<f64>() / ros.len() as f64;
+    Some((min, max, avg))
+}
+
+fn format_ros(ros: Option<f64>) -> String {
+    match ros {
+        Some(r) => format!("{:.2}%", r),
+        None => "—".to_string(),
+    }
+}
+
+/// Rows of the P2P Governance panel: the peer selection governor's peer
+/// sharing, big-ledger and bootstrap peers and churn
+fn governance_rows(node: &NodeState, palette: &Palette) -> Vec<MetricRow<'static>> {
+    let governance = &node.metrics.p2p.governance;
+    let mut rows = Vec::new();
+
+    if governance.is_set() {
+        // Peer sharing works once other nodes have told us about peers
+        let (sharing, sharing_health) = match governance.shared.known {
+            Some(0) => ("none learned yet".to_string(), HealthStatus::Warning),
+            Some(_) => (format_peer_group(&governance.shared), HealthStatus::Good),
+            None => ("—".to_string(), HealthStatus::Good),
+        };
+        rows.push(create_health_row(
+            "Peer Sharing",
+            sharing,
+            sharing_health,
+            palette,
+        ));
+        rows.push(create_metric_row(
+            "Big Ledger",
+            format_peer_group(&governance.big_ledger),
+            palette,
+        ));
+        rows.push(create_metric_row(
+            "Bootstrap",
+            format_peer_group(&governance.bootstrap),
+            palette,
+        ));
+        if let Some(requested) = governance.sharing_requested {
+            // Requests answered with nothing: peer sharing is off on the
+            // other side or blocked
+            let received = governance.sharing_received.unwrap_or(0);
+            let health = if requested > 0 && received == 0 {
+                HealthStatus::Warning
+            } else {
+                HealthStatus::Good
+            };
+            rows.push(create_health_row(
+                "Shared Peers",
+                format!("{} asked · {} received", requested, received),
+                health,
+                palette,
+            ));
+        }
+        for (label, churn) in [
+            ("Known Churn", &governance.churn_known),
+            ("Established Churn", &governance.churn_established),
+            ("Active Churn", &governance.churn_active),
+        ] {
+            if churn.is_set() {
+                rows.push(create_metric_row(label, format_churn(churn), palette));
+            }
+        }
+    }
+    rows.extend(custom_metric_rows(node, PanelKind::Governance, palette));
+    rows
+}
+
+/// Draw the P2P Governance panel
+fn draw_governance_panel(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let mut rows = governance_rows(app.current_node(), palette);
+
+    if rows.is_empty() {
+        rows.push(create_metric_row(
+            "Governor",
+            "No peer governance metrics".to_string(),
+            palette,
+        ));
+    }
+
+    let table = Table::new(
+        highlight_selected(rows, app, palette),
+        [Constraint::Percentage(40), Constraint::Percentage(60)],
+    )
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" P2P Governance ")
+            .border_style(Style::default().fg(palette.border)),
+    );
+
+    frame.render_widget(table, area);
+}
+
+/// Peers added and dropped, e.g. "+10 / -3"
+fn format_churn(churn: &ChurnCounts) -> String {
+    format!(
+        "+{} / -{}",
+        churn.increased.unwrap_or(0),
+        churn.decreased.unwrap_or(0)
+    )
+}
+
+/// Known/established/active counts, e.g. "34 known · 6 est · 2 hot"
+fn format_peer_group(group: &PeerGroupCounts) -> String {
+    if !group.is_set() {
+        return "—".to_string();
+    }
+    [
+        (group.known, "known"),
+        (group.established, "est"),
+        (group.active, "hot"),
+    ]
+    .into_iter()
+    .filter_map(|(count, label)| count.map(|n| format!("{} {}", n, label)))
+    .collect::<Vec<_>>()
+    .join(" · ")
+}
+
+/// Rows of the Network & Peers panel
+fn network_metric_rows(node: &NodeState, palette: &Palette) -> Vec<MetricRow<'static>> {
     let metrics = &node.metrics;
     let peer_health = node.peer_health();
     let peer_trend = node.history.peers_connected.trend();
@@ -555,8 +1729,24 @@ fn draw_network_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
         connected_value
     };
 
-    let rows = vec![
+    let scrape_rtt = match (node.scrape_rtt(), node.scrape_rtt_stats()) {
+        (Some(rtt), Some((avg, max))) => format!(
+            "{} (avg {} / max {})",
+            format_latency(rtt),
+            format_latency(avg),
+            format_latency(max)
+        ),
+        _ => "—".to_string(),
+    };
+
+    let mut rows = vec![
         create_health_row("Connected", connected_with_trend, peer_health, palette),
+        create_health_row(
+            "Scrape RTT",
+            scrape_rtt,
+            node.scrape_latency_health(),
+            palette,
+        ),
         create_metric_row(
             "Incoming",
             format_metric_u64(metrics.incoming_connections),
@@ -572,7 +1762,40 @@ fn draw_network_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
             format_metric_u64(metrics.full_duplex_connections),
             palette,
         ),
-        create_metric_row(
+    ];
+    if let Some(prunable) = metrics.prunable_connections {
+        rows.push(create_metric_row("Prunable", prunable.to_string(), palette));
+    }
+    // Only shown where the node exports them; both count towards the alert
+    for (label, count, rate) in [
+        (
+            "Conn Errors",
+            metrics.connection_errors,
+            &node.history.connection_error_rate,
+        ),
+        (
+            "Handshake Fails",
+            metrics.handshake_failures,
+            &node.history.handshake_failure_rate,
+        ),
+    ] {
+        let Some(count) = count else {
+            continue;
+        };
+        let value = match rate.per_minute() {
+            Some(per_min) => format!("{} ({})", format_compact(count), format_per_minute(per_min)),
+            None => format_compact(count),
+        };
+        rows.push(create_health_row(
+            label,
+            value,
+            node.connection_error_health(),
+            palette,
+        ));
+    }
+    match node.peer_targets() {
+        Some(targets) => rows.extend(peer_target_rows(metrics, targets, palette)),
+        None => rows.push(create_metric_row(
             "Peer Dist",
             format_peer_distribution(
                 metrics.p2p.hot_peers,
@@ -580,18 +1803,32 @@ fn draw_network_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
                 metrics.p2p.cold_peers,
             ),
             palette,
-        ),
+        )),
+    }
+    rows.extend([
         create_metric_row(
             "Block Delay",
             format_block_delay(metrics.block_delay_s),
             palette,
         ),
-        create_metric_row(
+        create_counter_row(
             "Blks Served",
-            format_metric_u64(metrics.blocks_served),
+            metrics.blocks_served,
+            &node.history.blocks_served_rate,
             palette,
         ),
-        create_late_blocks_row(metrics.blocks_late, palette),
+    ]);
+    // Only shown where the node exports it
+    if metrics.headers_served.is_some() {
+        rows.push(create_counter_row(
+            "Hdrs Served",
+            metrics.headers_served,
+            &node.history.headers_served_rate,
+            palette,
+        ));
+    }
+    rows.extend([
+        create_late_blocks_row(metrics.blocks_late, &node.history.blocks_late_rate, palette),
         create_metric_row(
             "Prop ≤1s",
             format_cdf_percent(metrics.block_delay_cdf_1s),
@@ -607,10 +1844,33 @@ fn draw_network_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
             format_cdf_percent(metrics.block_delay_cdf_5s),
             palette,
         ),
-    ];
+    ]);
+
+    // cncli's synced tip, compared with the node's
+    if let Some((block, _)) = node.cncli.as_ref().and_then(|c| c.tip) {
+        let behind = metrics.block_height.map(|h| h.saturating_sub(block));
+        let (status, health) = match behind {
+            Some(0) | None => ("synced".to_string(), HealthStatus::Good),
+            Some(n) if n <= 2 => (format!("{} behind", n), HealthStatus::Good),
+            Some(n) => (format!("{} behind", n), HealthStatus::Warning),
+        };
+        rows.push(create_health_row(
+            "cncli Tip",
+            format!("{} ({})", format_number(block), status),
+            health,
+            palette,
+        ));
+    }
+    rows.extend(custom_metric_rows(node, PanelKind::Network, palette));
+    rows
+}
+
+/// Draw network and peer metrics
+fn draw_network_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let rows = network_metric_rows(app.current_node(), palette);
 
     let table = Table::new(
-        rows,
+        highlight_selected(rows, app, palette),
         [Constraint::Percentage(55), Constraint::Percentage(45)],
     )
     .block(
@@ -623,14 +1883,64 @@ fn draw_network_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
     frame.render_widget(table, area);
 }
 
-/// Draw resource metrics table
-fn draw_resource_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
-    let node = app.current_node();
+/// Hot/warm/cold peer counts against the peer selection governor's targets
+fn peer_target_rows(
+    metrics: &crate::metrics::NodeMetrics,
+    targets: PeerTargets,
+    palette: &Palette,
+) -> Vec<MetricRow<'static>> {
+    let (hot_target, warm_target, cold_target) = targets.by_state();
+    [
+        ("Hot Peers", metrics.p2p.hot_peers, hot_target),
+        ("Warm Peers", metrics.p2p.warm_peers, warm_target),
+        ("Cold Peers", metrics.p2p.cold_peers, cold_target),
+    ]
+    .into_iter()
+    .map(|(label, actual, target)| match (actual, target) {
+        (Some(actual), Some(target)) => {
+            let deviation = if target > 0 {
+                format!(
+                    " ({:+.0}%)",
+                    (actual as f64 - target as f64) / target as f64 * 100.0
+                )
+            } else {
+                String::new()
+            };
+            create_health_row(
+                label,
+                format!("{} / {}{}", actual, target, deviation),
+                NodeState::peer_target_health(actual, target),
+                palette,
+            )
+        }
+        (actual, _) => create_metric_row(label, format_metric_u64(actual), palette),
+    })
+    .collect()
+}
+
+/// Rows of the Resources panel
+fn resource_metric_rows(node: &NodeState, palette: &Palette) -> Vec<MetricRow<'static>> {
     let metrics = &node.metrics;
     let memory_health = node.memory_health();
 
-    let rows = vec![
-        create_metric_row("Uptime", format_uptime(metrics.uptime_seconds), palette),
+    let mut rows = vec![create_metric_row(
+        "Uptime",
+        format_uptime(metrics.uptime_seconds),
+        palette,
+    )];
+    // Once a start time has been recorded
+    if node.restarts.last().is_some() {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        rows.push(create_metric_row(
+            "Restarts",
+            format_restart_counts(node, now),
+            palette,
+        ));
+    }
+    rows.extend([
         create_health_row(
             "Memory Used",
             format_bytes(metrics.memory_used),
@@ -638,18 +1948,41 @@ fn draw_resource_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
             palette,
         ),
         create_metric_row("Memory Heap", format_bytes(metrics.memory_heap), palette),
-        create_metric_row("GC Minor", format_metric_u64(metrics.gc_minor), palette),
-        create_metric_row("GC Major", format_metric_u64(metrics.gc_major), palette),
-        create_metric_row(
-            "Mempool TXs",
+        create_health_row(
+            "CPU",
+            format_cpu(node.cpu_percent()),
+            node.cpu_health(),
+            palette,
+        ),
+        create_counter_row(
+            "GC Minor",
+            metrics.gc_minor,
+            &node.history.gc_minor_rate,
+            palette,
+        ),
+        create_counter_row(
+            "GC Major",
+            metrics.gc_major,
+            &node.history.gc_major_rate,
+            palette,
+        ),
+        create_metric_row(
+            "Mempool TXs",
             format_metric_u64(metrics.mempool_txs),
             palette,
         ),
         create_metric_row("Mempool Size", format_bytes(metrics.mempool_bytes), palette),
-    ];
+    ]);
+    rows.extend(custom_metric_rows(node, PanelKind::Resources, palette));
+    rows
+}
+
+/// Draw resource metrics table
+fn draw_resource_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let rows = resource_metric_rows(app.current_node(), palette);
 
     let table = Table::new(
-        rows,
+        highlight_selected(rows, app, palette),
         [Constraint::Percentage(55), Constraint::Percentage(45)],
     )
     .block(
@@ -664,43 +1997,68 @@ fn draw_resource_metrics(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
 
 /// Draw the footer with help hints and last update time
 fn draw_footer(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    if app.mode == AppMode::Command {
+        draw_command_line(frame, area, app, palette);
+        return;
+    }
+
     let node = app.current_node();
 
     // Build footer spans
     let mut spans = vec![];
 
     // Show error if present
-    if let Some(ref error) = node.last_error {
+    if let Some(error) = node.error_text() {
         spans.push(Span::styled(
-            format!(" ⚠ {} ", truncate_string(error, 50)),
+            format!(" ⚠ {} ", truncate_string(&error, 80)),
             Style::default().fg(palette.critical),
         ));
         spans.push(Span::raw(" │ "));
     }
 
-    // Help hints
-    spans.extend(vec![
-        Span::styled(" q", Style::default().fg(palette.tertiary)),
-        Span::raw(" quit "),
-        Span::styled("r", Style::default().fg(palette.tertiary)),
-        Span::raw(" refresh "),
-        Span::styled("p", Style::default().fg(palette.tertiary)),
-        Span::raw(" peers "),
-        Span::styled("g", Style::default().fg(palette.tertiary)),
-        Span::raw(" graphs "),
-        Span::styled("t", Style::default().fg(palette.tertiary)),
-        Span::raw(" theme "),
-        Span::styled("?", Style::default().fg(palette.tertiary)),
-        Span::raw(" help"),
-    ]);
+    // Show transient status (e.g. config reload result)
+    if let Some(status) = app.status_message() {
+        spans.push(Span::styled(
+            format!(" {} ", truncate_string(status, 60)),
+            Style::default().fg(palette.tertiary),
+        ));
+        spans.push(Span::raw(" │ "));
+    }
+
+    // Help hints, from the active keybindings
+    let keys = &app.app_config.keys;
+    let mut hints = vec![
+        (Action::Quit, "quit"),
+        (Action::Refresh, "refresh"),
+        (Action::Peers, "peers"),
+        (Action::Graphs, "graphs"),
+        (Action::Mempool, "mempool"),
+        (Action::Epochs, "epochs"),
+        (Action::Settings, "settings"),
+        (Action::Compare, "compare"),
+        (Action::Theme, "theme"),
+        (Action::Pause, "pause"),
+        (Action::Help, "help"),
+    ];
+    if app.is_multi_node() {
+        hints.push((Action::NextNode, "next"));
+    }
+    for (action, name) in hints {
+        let key = keys.short_label(action);
+        if key.is_empty() {
+            continue;
+        }
+        spans.push(Span::styled(
+            format!(" {}", key),
+            Style::default().fg(palette.tertiary),
+        ));
+        spans.push(Span::raw(format!(" {}", name)));
+    }
 
-    // Add node switching hints if multi-node
+    // Add node number hint if multi-node
     if app.is_multi_node() {
         spans.extend(vec![
-            Span::raw(" │ "),
-            Span::styled("Tab", Style::default().fg(palette.tertiary)),
-            Span::raw(" next "),
-            Span::styled("1-9", Style::default().fg(palette.tertiary)),
+            Span::styled(" 1-9", Style::default().fg(palette.tertiary)),
             Span::raw(" select"),
         ]);
     }
@@ -715,7 +2073,7 @@ fn draw_footer(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
         };
         spans.push(Span::raw(" │ "));
         spans.push(Span::styled(
-            format!("Updated {}", update_str),
+            with_measurement_delay(format!("Updated {}", update_str), node),
             Style::default().fg(palette.text_muted),
         ));
     }
@@ -728,161 +2086,1325 @@ fn draw_footer(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
     ));
 
     let footer = Paragraph::new(Line::from(spans));
-    frame.render_widget(footer, area);
+    if !app.app_config.status_clock {
+        frame.render_widget(footer, area);
+        return;
+    }
+
+    // Live ticker on the right, redrawn every second between refreshes
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let ticker = Line::from(Span::styled(
+        status_ticker(app, now),
+        Style::default().fg(palette.text_muted),
+    ));
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(ticker.width() as u16),
+        ])
+        .split(area);
+    frame.render_widget(footer, chunks[0]);
+    frame.render_widget(ticker, chunks[1]);
+}
+
+/// Wall clock, network slot and next-epoch countdown, e.g.
+/// " 14:32:07 │ Slot 123,456,789 │ E513 in 2d 04:11:09 "
+fn status_ticker(app: &App, now: u64) -> String {
+    let node = app.current_node();
+    let mut parts = vec![app.app_config.timezone.clock_secs(now)];
+    if let Some(slot) = node.network_slot() {
+        parts.push(format!("Slot {}", format_number(slot)));
+    }
+    if let Some((epoch, secs)) = node.next_epoch_countdown() {
+        parts.push(format!("E{} in {}", epoch, format_clock_countdown(secs)));
+    }
+    format!(" {} ", parts.join(" │ "))
+}
+
+/// Draw the `:` command line in place of the footer, with matching commands
+fn draw_command_line(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let typed = app.command_input.split_whitespace().next().unwrap_or("");
+    let hints: Vec<String> = crate::command::COMMANDS
+        .iter()
+        .filter(|(name, _)| name.starts_with(typed) || typed.starts_with(name))
+        .map(|(name, usage)| format!(":{} {}", name, usage).trim_end().to_string())
+        .collect();
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled(":", Style::default().fg(palette.tertiary).bold()),
+            Span::raw(app.command_input.as_str()),
+            Span::styled("█", Style::default().fg(palette.tertiary)),
+        ]),
+        Line::from(Span::styled(
+            format!(" {}   (Enter run, Esc cancel)", hints.join("  ")),
+            Style::default().fg(palette.text_muted),
+        )),
+    ];
+    frame.render_widget(Paragraph::new(lines), area);
 }
 
 /// Draw the help popup overlay
-fn draw_help_popup(frame: &mut Frame, area: Rect, is_multi_node: bool, palette: &Palette) {
+fn draw_help_popup(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let is_multi_node = app.is_multi_node();
+    let keys = &app.app_config.keys;
     let popup_area = centered_rect(65, if is_multi_node { 85 } else { 75 }, area);
 
     // Clear the background
     frame.render_widget(Clear, popup_area);
 
-    let mut help_lines = vec![
+    // Shortcut lines follow the active keybindings
+    let shortcut = |label: String, description: &'static str| {
+        Line::from(vec![
+            Span::styled(
+                format!("  {:<10}", label),
+                Style::default().fg(palette.tertiary),
+            ),
+            Span::raw(description),
+        ])
+    };
+    let action_line = |action: Action| shortcut(keys.label(action), action.description());
+    let heading = |title: &'static str| {
+        Line::from(Span::styled(
+            title,
+            Style::default().bold().underlined().fg(palette.primary),
+        ))
+    };
+
+    let mut help_lines = vec![heading("Global Shortcuts"), Line::from("")];
+    help_lines.extend(
+        [
+            Action::Quit,
+            Action::Refresh,
+            Action::Help,
+            Action::CommandLine,
+            Action::Pause,
+            Action::Copy,
+            Action::Screenshot,
+        ]
+        .map(action_line),
+    );
+    help_lines.push(shortcut("Ctrl+Z".to_string(), "Suspend (resume with fg)"));
+    help_lines.extend([Line::from(""), heading("Views"), Line::from("")]);
+    help_lines.extend(
+        [
+            Action::Theme,
+            Action::Focus,
+            Action::Peers,
+            Action::Graphs,
+            Action::Mempool,
+            Action::Propagation,
+            Action::Epochs,
+            Action::Incidents,
+            Action::Settings,
+            Action::Compare,
+            Action::ReloadConfig,
+            Action::AddNode,
+        ]
+        .map(action_line),
+    );
+    help_lines.push(shortcut(
+        "↑ ↓".to_string(),
+        "Select a metric row (Enter explains it)",
+    ));
+
+    // Add multi-node shortcuts if applicable
+    if is_multi_node {
+        help_lines.extend([
+            Line::from(""),
+            heading("Multi-Node Navigation"),
+            Line::from(""),
+            action_line(Action::NextNode),
+            action_line(Action::PrevNode),
+            shortcut("1-9".to_string(), "Select node by number"),
+            action_line(Action::ToggleGroup),
+        ]);
+    }
+
+    // Peer list shortcuts
+    help_lines.push(Line::from(""));
+    help_lines.push(Line::from(Span::styled(
+        "Peer List (when open)",
+        Style::default().bold().underlined().fg(palette.primary),
+    )));
+    help_lines.push(Line::from(""));
+    help_lines.push(Line::from(vec![
+        Span::styled("  ↑ ↓, j k  ", Style::default().fg(palette.tertiary)),
+        Span::raw("Navigate peer list"),
+    ]));
+    help_lines.push(Line::from(vec![
+        Span::styled("  Enter, →  ", Style::default().fg(palette.tertiary)),
+        Span::raw("View peer details"),
+    ]));
+    help_lines.push(Line::from(vec![
+        Span::styled("  ←, BS     ", Style::default().fg(palette.tertiary)),
+        Span::raw("Back to peer list"),
+    ]));
+    help_lines.extend([
+        action_line(Action::Ban),
+        shortcut(keys.label(Action::Refresh), "Refresh peer list"),
+        shortcut(
+            keys.label(Action::Graphs),
+            "Group peers by subnet / network",
+        ),
+    ]);
+
+    help_lines.extend(vec![
+        Line::from(""),
         Line::from(Span::styled(
-            "Global Shortcuts",
+            "Health Indicators",
             Style::default().bold().underlined().fg(palette.primary),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("  q, Esc    ", Style::default().fg(palette.tertiary)),
-            Span::raw("Quit / Close overlay"),
+            Span::styled("  ● Healthy   ", Style::default().fg(palette.healthy)),
+            Span::raw("Operating normally"),
         ]),
         Line::from(vec![
-            Span::styled("  r         ", Style::default().fg(palette.tertiary)),
-            Span::raw("Force refresh metrics"),
+            Span::styled("  ● Warning   ", Style::default().fg(palette.warning)),
+            Span::raw("Needs attention"),
         ]),
         Line::from(vec![
-            Span::styled("  ?         ", Style::default().fg(palette.tertiary)),
-            Span::raw("Toggle this help"),
+            Span::styled("  ● Critical  ", Style::default().fg(palette.critical)),
+            Span::raw("Action required"),
         ]),
         Line::from(""),
         Line::from(Span::styled(
-            "Views",
+            "Press any key to close",
+            Style::default().fg(palette.text_muted).italic(),
+        )),
+    ]);
+
+    let help = Paragraph::new(help_lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Help ")
+                .border_style(Style::default().fg(palette.primary)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(help, popup_area);
+}
+
+/// `[key] what` hint for an overlay, empty when `action` has no key
+fn key_hint(keys: &KeyBindings, action: Action, what: &str) -> String {
+    match keys.short_label(action) {
+        key if key.is_empty() => String::new(),
+        key => format!("[{}] {}", key, what),
+    }
+}
+
+/// Hint for closing an overlay opened with `action`
+fn close_hint(keys: &KeyBindings, action: Action) -> String {
+    match keys.short_label(action) {
+        key if key.is_empty() || key == "Esc" => "[Esc] close".to_string(),
+        key => format!("[{}/Esc] close", key),
+    }
+}
+
+/// Bottom title for an overlay, skipping empty hints
+fn hint_line(hints: &[String]) -> Line<'static> {
+    let hints: Vec<&str> = hints
+        .iter()
+        .map(String::as_str)
+        .filter(|hint| !hint.is_empty())
+        .collect();
+    Line::from(format!(" {} ", hints.join(" | "))).centered()
+}
+
+/// Create a centered rectangle for popups
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let popup_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(r);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(popup_layout[1])[1]
+}
+
+// ============================================================================
+// Add node view
+// ============================================================================
+
+/// Draw the form for adding a node at runtime
+fn draw_add_node_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    use crate::node_form::FormStep;
+
+    let popup_area = centered_rect(50, 50, area);
+
+    // Clear the background
+    frame.render_widget(Clear, popup_area);
+
+    let form = &app.node_form;
+    let editing = form.step == FormStep::Editing;
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "New Node",
             Style::default().bold().underlined().fg(palette.primary),
         )),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("  t         ", Style::default().fg(palette.tertiary)),
-            Span::raw("Cycle color theme"),
-        ]),
-        Line::from(vec![
-            Span::styled("  p         ", Style::default().fg(palette.tertiary)),
-            Span::raw("Toggle peer connections"),
-        ]),
-        Line::from(vec![
-            Span::styled("  g         ", Style::default().fg(palette.tertiary)),
-            Span::raw("Toggle historical graphs"),
-        ]),
     ];
+    lines.extend(node_form_fields(form, editing, palette));
 
-    // Add multi-node shortcuts if applicable
-    if is_multi_node {
-        help_lines.push(Line::from(""));
-        help_lines.push(Line::from(Span::styled(
-            "Multi-Node Navigation",
+    lines.push(Line::from(""));
+    match form.step {
+        FormStep::Editing => {
+            if let Some(error) = &form.error {
+                lines.push(Line::from(Span::styled(
+                    error.clone(),
+                    Style::default().fg(palette.critical),
+                )));
+            }
+        }
+        FormStep::ConfirmSave => {
+            lines.push(Line::from(Span::styled(
+                "Node added and monitoring started.",
+                Style::default().fg(palette.healthy),
+            )));
+            lines.push(Line::from(vec![
+                Span::raw("Save it to the config file? "),
+                Span::styled("[y/n]", Style::default().fg(palette.tertiary).bold()),
+            ]));
+        }
+    }
+
+    let hints = match form.step {
+        FormStep::Editing => " [Tab/↑↓] field | [Space/←→] role | [Enter] add | [Esc] cancel ",
+        FormStep::ConfirmSave => " [y] save | [n/Esc] keep for this session ",
+    };
+
+    let dialog = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Add Node ")
+                .title_bottom(Line::from(hints).centered())
+                .border_style(Style::default().fg(palette.primary)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(dialog, popup_area);
+}
+
+/// Lines for the name/host/port/role fields of the node form
+fn node_form_fields(
+    form: &crate::node_form::NodeForm,
+    editing: bool,
+    palette: &Palette,
+) -> Vec<Line<'static>> {
+    use crate::node_form::FormField;
+
+    let mut lines = Vec::new();
+    for (idx, field) in FormField::ALL.iter().enumerate() {
+        let focused = editing && idx == form.focused;
+        let selector = if focused { "▶ " } else { "  " };
+        let value_style = if focused {
+            Style::default()
+                .fg(palette.text)
+                .bold()
+                .bg(palette.gauge_bg)
+        } else {
+            Style::default().fg(palette.text)
+        };
+        // Show a cursor at the end of focused text fields
+        let cursor = if focused && *field != FormField::Role {
+            "▏"
+        } else {
+            ""
+        };
+        lines.push(Line::from(vec![
+            Span::styled(selector, Style::default().fg(palette.primary)),
+            Span::styled(
+                format!("{:<6}", field.label()),
+                Style::default().fg(palette.text_muted),
+            ),
+            Span::styled(format!(" {}{} ", form.value(*field), cursor), value_style),
+        ]));
+    }
+    lines
+}
+
+/// Draw the first-run setup screen, shown when there is no config file and
+/// nothing answers on the default endpoint
+fn draw_setup_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    frame.render_widget(Clear, area);
+    let popup_area = centered_rect(60, 60, area);
+
+    let form = &app.node_form;
+    let muted = Style::default().fg(palette.text_muted);
+    let no_config = match &app.app_config.config_path {
+        Some(path) => format!("No config file found at {}", path.display()),
+        None => "No config file found".to_string(),
+    };
+    let default_node = app.current_node();
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Welcome to sview",
             Style::default().bold().underlined().fg(palette.primary),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(no_config, muted)),
+        Line::from(Span::styled(
+            format!(
+                "and no node answers on {}:{}.",
+                default_node.config.prom_host, default_node.config.prom_port
+            ),
+            muted,
+        )),
+        Line::from(Span::styled(
+            "Enter the Prometheus endpoint of a node to monitor:",
+            muted,
+        )),
+        Line::from(""),
+    ];
+    lines.extend(node_form_fields(form, true, palette));
+    lines.push(Line::from(""));
+    if let Some(error) = &form.error {
+        lines.push(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(palette.critical),
         )));
-        help_lines.push(Line::from(""));
-        help_lines.push(Line::from(vec![
-            Span::styled("  Tab       ", Style::default().fg(palette.tertiary)),
-            Span::raw("Next node"),
-        ]));
-        help_lines.push(Line::from(vec![
-            Span::styled("  Shift+Tab ", Style::default().fg(palette.tertiary)),
-            Span::raw("Previous node"),
-        ]));
-        help_lines.push(Line::from(vec![
-            Span::styled("  ← →       ", Style::default().fg(palette.tertiary)),
-            Span::raw("Switch nodes"),
-        ]));
-        help_lines.push(Line::from(vec![
-            Span::styled("  1-9       ", Style::default().fg(palette.tertiary)),
-            Span::raw("Select node by number"),
+    }
+
+    let dialog = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Setup ")
+                .title_bottom(
+                    Line::from(" [Tab/↑↓] field | [←→] role | [Enter] test & save | [Esc] skip ")
+                        .centered(),
+                )
+                .border_style(Style::default().fg(palette.primary)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(dialog, popup_area);
+}
+
+// ============================================================================
+// Settings view
+// ============================================================================
+
+/// Draw the health threshold editor for the current node
+fn draw_settings_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    use crate::config::ThresholdField;
+
+    let popup_area = centered_rect(50, 50, area);
+
+    // Clear the background
+    frame.render_widget(Clear, popup_area);
+
+    let node = app.current_node();
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Health Thresholds",
+            Style::default().bold().underlined().fg(palette.primary),
+        )),
+        Line::from(""),
+    ];
+
+    for (idx, field) in ThresholdField::ALL.iter().enumerate() {
+        let selected = idx == app.settings_selected;
+        let selector = if selected { "▶ " } else { "  " };
+        let value_style = if selected {
+            Style::default()
+                .fg(palette.text)
+                .bold()
+                .bg(palette.gauge_bg)
+        } else {
+            Style::default().fg(palette.text)
+        };
+        lines.push(Line::from(vec![
+            Span::styled(selector, Style::default().fg(palette.primary)),
+            Span::styled(
+                format!("{:<22}", field.label()),
+                Style::default().fg(palette.text_muted),
+            ),
+            Span::styled(
+                format!(" {} ", node.thresholds.display(*field)),
+                value_style,
+            ),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Changes apply immediately to this node.",
+        Style::default().fg(palette.text_muted).italic(),
+    )));
+
+    let policy = node.storage().policy();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Storage",
+        Style::default().bold().underlined().fg(palette.primary),
+    )));
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("  {:<22}", "History on disk"),
+            Style::default().fg(palette.text_muted),
+        ),
+        Span::styled(
+            format!(" {}", format_bytes(node.history_bytes)),
+            Style::default().fg(palette.text),
+        ),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("  {:<22}", "Data directory"),
+            Style::default().fg(palette.text_muted),
+        ),
+        Span::styled(
+            format!(
+                " {} ({} free)",
+                format_bytes(node.disk_usage.map(|u| u.data_dir_bytes)),
+                format_bytes(node.disk_usage.and_then(|u| u.fs_available_bytes))
+            ),
+            Style::default().fg(palette.text),
+        ),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("  {:<22}", "Retention"),
+            Style::default().fg(palette.text_muted),
+        ),
+        Span::styled(
+            format!(" {} days", policy.retention_days),
+            Style::default().fg(palette.text),
+        ),
+    ]));
+    let raw_rate = if policy.sample_interval_secs == 0 {
+        "every refresh".to_string()
+    } else {
+        format!("every {}", format_interval(policy.sample_interval_secs))
+    };
+    lines.push(Line::from(Span::styled(
+        format!(
+            "  {} for 24h, 5m min/max/avg for 30d, hourly after",
+            raw_rate
+        ),
+        Style::default().fg(palette.text_muted).italic(),
+    )));
+
+    if let Some(status) = &app.settings_status {
+        lines.push(Line::from(Span::styled(
+            status.clone(),
+            Style::default().fg(palette.tertiary),
+        )));
+    }
+
+    let settings = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Settings — {} ", node.config.node_name))
+                .title_bottom(hint_line(&[
+                    "[↑↓] select".to_string(),
+                    "[←→] adjust".to_string(),
+                    key_hint(
+                        &app.app_config.keys,
+                        Action::SaveSettings,
+                        "write to config",
+                    ),
+                    close_hint(&app.app_config.keys, Action::Settings),
+                ]))
+                .border_style(Style::default().fg(palette.primary)),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(settings, popup_area);
+}
+
+// ============================================================================
+// Incidents view
+// ============================================================================
+
+/// Draw the incidents overlay: node restarts, then recent fork and rollback
+/// events
+fn draw_incidents_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let popup_area = centered_rect(80, 75, area);
+
+    // Clear the background
+    frame.render_widget(Clear, popup_area);
+
+    let node = app.current_node();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let title = format!(
+        " Incidents — {} fork(s) in the last hour ",
+        node.forks.count_since(now.saturating_sub(3600))
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .title_bottom(hint_line(&[close_hint(
+            &app.app_config.keys,
+            Action::Incidents,
+        )]))
+        .border_style(Style::default().fg(palette.primary));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Min(3)])
+        .split(inner);
+    frame.render_widget(
+        Paragraph::new(restart_summary_lines(
+            node,
+            now,
+            app.app_config.timezone,
+            palette,
+        )),
+        chunks[0],
+    );
+
+    let visible_rows = chunks[1].height.saturating_sub(2) as usize;
+    let timezone = app.app_config.timezone;
+    let fmt_height = |h: Option<u64>| h.map(format_number).unwrap_or_else(|| "—".to_string());
+
+    let mut rows: Vec<Row> = node
+        .forks
+        .recent()
+        .take(visible_rows)
+        .map(|event| {
+            let (kind, detail, style) = match event.rollback_depth() {
+                Some(depth) => (
+                    "Rollback",
+                    format!(
+                        "{} block{} rolled back",
+                        depth,
+                        if depth == 1 { "" } else { "s" }
+                    ),
+                    Style::default().fg(palette.warning),
+                ),
+                None => (
+                    "Fork",
+                    format!(
+                        "{} chain switch{}",
+                        event.new_forks,
+                        if event.new_forks == 1 { "" } else { "es" }
+                    ),
+                    Style::default().fg(palette.text),
+                ),
+            };
+            Row::new(vec![
+                Cell::from(timezone.datetime(event.timestamp)),
+                Cell::from(Span::styled(kind, style.bold())),
+                Cell::from(format!(
+                    "{} → {}",
+                    fmt_height(event.height_before),
+                    fmt_height(event.height_after)
+                )),
+                Cell::from(
+                    event
+                        .slot
+                        .map(format_number)
+                        .unwrap_or_else(|| "—".to_string()),
+                ),
+                Cell::from(event.total_forks.to_string()),
+                Cell::from(Span::styled(detail, style)),
+            ])
+        })
+        .collect();
+
+    if rows.is_empty() {
+        rows.push(Row::new(vec![
+            Cell::from(""),
+            Cell::from(Span::styled(
+                "No forks seen since sview started",
+                Style::default().fg(palette.text_muted).italic(),
+            )),
+        ]));
+    }
+
+    let header = Row::new(
+        ["TIME", "EVENT", "HEIGHT", "SLOT", "TOTAL", "DETAIL"]
+            .into_iter()
+            .map(|h| Cell::from(Span::styled(h, Style::default().fg(palette.primary).bold()))),
+    )
+    .bottom_margin(1);
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(20), // TIME
+            Constraint::Length(9),  // EVENT
+            Constraint::Length(22), // HEIGHT
+            Constraint::Length(13), // SLOT
+            Constraint::Length(7),  // TOTAL
+            Constraint::Min(20),    // DETAIL
+        ],
+    )
+    .header(header);
+
+    frame.render_widget(table, chunks[1]);
+}
+
+/// Restart counts, a 30-day timeline and the last start, for the top of the
+/// incidents view
+fn restart_summary_lines(
+    node: &NodeState,
+    now: u64,
+    timezone: DisplayTimezone,
+    palette: &Palette,
+) -> Vec<Line<'static>> {
+    let label = |text: &'static str| {
+        Span::styled(
+            format!("{:<10}", text),
+            Style::default().fg(palette.primary).bold(),
+        )
+    };
+    let muted = |text: String| Span::styled(text, Style::default().fg(palette.text_muted));
+
+    let Some(last) = node.restarts.last() else {
+        return vec![Line::from(vec![
+            label("Restarts"),
+            Span::styled(
+                "No node start time reported yet",
+                Style::default().fg(palette.text_muted).italic(),
+            ),
+        ])];
+    };
+
+    let mut counts = format_restart_counts(node, now);
+    if let Some(longest) = node.restarts.longest_run_secs() {
+        counts.push_str(&format!(
+            ", longest run {}",
+            format_uptime(Some(longest as f64))
+        ));
+    }
+    let timeline_style = if node.restarts.restarts_since(now.saturating_sub(7 * 86400)) > 0 {
+        Style::default().fg(palette.warning)
+    } else {
+        Style::default().fg(palette.text)
+    };
+    let previous = match last.previous_run_secs() {
+        Some(secs) => format!(" (restart after {})", format_uptime(Some(secs as f64))),
+        None => " (first start recorded)".to_string(),
+    };
+
+    vec![
+        Line::from(vec![label("Restarts"), Span::raw(counts)]),
+        Line::from(vec![
+            label(""),
+            muted("30d ago ".to_string()),
+            Span::styled(
+                restart_timeline(&node.restarts.daily_restarts(now, 30)),
+                timeline_style,
+            ),
+            muted(" today".to_string()),
+        ]),
+        Line::from(vec![
+            label("Started"),
+            Span::raw(timezone.datetime(last.start_time)),
+            muted(previous),
+        ]),
+    ]
+}
+
+// ============================================================================
+// Epoch summary view
+// ============================================================================
+
+/// Draw per-epoch summary statistics (current epoch first)
+fn draw_epoch_summary_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let popup_area = centered_rect(80, 75, area);
+
+    // Clear the background
+    frame.render_widget(Clear, popup_area);
+
+    let node = app.current_node();
+    let visible_rows = popup_area.height.saturating_sub(4) as usize;
+
+    let current = node.epoch_tracker.current();
+    let summaries = current
+        .iter()
+        .map(|s| (s, true))
+        .chain(node.epoch_summaries.iter().rev().map(|s| (s, false)));
+
+    let mut rows: Vec<Row> = Vec::new();
+    for (summary, in_progress) in summaries.take(visible_rows) {
+        let observed = summary.last_seen.saturating_sub(summary.first_seen);
+        let status = if in_progress {
+            Span::styled("in progress", Style::default().fg(palette.primary))
+        } else if summary.partial {
+            Span::styled("partial", Style::default().fg(palette.warning))
+        } else {
+            Span::styled("complete", Style::default().fg(palette.healthy))
+        };
+        let restart_style = if summary.restarts > 0 {
+            Style::default().fg(palette.warning)
+        } else {
+            Style::default().fg(palette.text)
+        };
+        let missed_style = if summary.missed_slots > 0 {
+            Style::default().fg(palette.warning)
+        } else {
+            Style::default().fg(palette.text)
+        };
+
+        rows.push(Row::new(vec![
+            Cell::from(Span::styled(
+                summary.epoch.to_string(),
+                Style::default().fg(palette.text).bold(),
+            )),
+            Cell::from(format_number(summary.blocks_seen)),
+            Cell::from(Span::styled(
+                summary.blocks_forged.to_string(),
+                Style::default().fg(palette.tertiary),
+            )),
+            Cell::from(Span::styled(summary.missed_slots.to_string(), missed_style)),
+            Cell::from(
+                summary
+                    .avg_peers
+                    .map(|p| format!("{:.1}", p))
+                    .unwrap_or_else(|| "—".to_string()),
+            ),
+            Cell::from(Span::styled(summary.restarts.to_string(), restart_style)),
+            Cell::from(format_time_remaining(observed)),
+            Cell::from(status),
+        ]));
+    }
+
+    if rows.is_empty() {
+        rows.push(Row::new(vec![
+            Cell::from(""),
+            Cell::from(Span::styled(
+                "No epoch data yet",
+                Style::default().fg(palette.text_muted).italic(),
+            )),
+        ]));
+    }
+
+    let header = Row::new(
+        [
+            "EPOCH",
+            "BLOCKS",
+            "FORGED",
+            "MISSED",
+            "AVG PEERS",
+            "RESTARTS",
+            "OBSERVED",
+            "STATUS",
+        ]
+        .into_iter()
+        .map(|h| Cell::from(Span::styled(h, Style::default().fg(palette.primary).bold()))),
+    )
+    .bottom_margin(1);
+
+    let title = match node.epoch_time_remaining() {
+        Some(secs) => format!(
+            " Epoch Summary — next boundary in {} ",
+            format_time_remaining(secs)
+        ),
+        None => " Epoch Summary ".to_string(),
+    };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(7),  // EPOCH
+            Constraint::Length(9),  // BLOCKS
+            Constraint::Length(8),  // FORGED
+            Constraint::Length(8),  // MISSED
+            Constraint::Length(10), // AVG PEERS
+            Constraint::Length(9),  // RESTARTS
+            Constraint::Length(10), // OBSERVED
+            Constraint::Min(11),    // STATUS
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_bottom(hint_line(&[close_hint(
+                &app.app_config.keys,
+                Action::Epochs,
+            )]))
+            .border_style(Style::default().fg(palette.primary)),
+    );
+
+    frame.render_widget(table, popup_area);
+}
+
+// ============================================================================
+// Node comparison view
+// ============================================================================
+
+/// Draw the node comparison table, ranked best first
+fn draw_benchmark_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let popup_area = centered_rect(90, 75, area);
+
+    // Clear the background
+    frame.render_widget(Clear, popup_area);
+
+    let hours = app.benchmark_hours();
+    let ranking = app.benchmark_ranking();
+    let window_minutes = (hours * 60) as f64;
+    let ranked_count = ranking
+        .iter()
+        .filter(|(_, b)| b.minutes_covered > 0)
+        .count();
+
+    let fmt_pct = |v: Option<f64>| {
+        v.map(|v| format!("{:.1}%", v * 100.0))
+            .unwrap_or_else(|| "—".to_string())
+    };
+
+    let rows: Vec<Row> = ranking
+        .iter()
+        .enumerate()
+        .map(|(rank, (idx, bench))| {
+            let node = &app.nodes[*idx];
+            // Flag the worst-ranked node when there's something to compare against
+            let underperformer = ranked_count > 1 && rank + 1 == ranked_count;
+            let name_style = if underperformer {
+                Style::default().fg(palette.warning).bold()
+            } else {
+                Style::default().fg(palette.text).bold()
+            };
+            let role = match node.role {
+                crate::config::NodeRole::Bp => "BP",
+                crate::config::NodeRole::Relay => "Relay",
+            };
+            let restart_style = if bench.restarts > 0 {
+                Style::default().fg(palette.warning)
+            } else {
+                Style::default().fg(palette.text)
+            };
+            let down_style = if bench.downtime_secs > 0 {
+                Style::default().fg(palette.critical)
+            } else {
+                Style::default().fg(palette.text)
+            };
+            let coverage = (bench.minutes_covered as f64 / window_minutes * 100.0).min(100.0);
+
+            Row::new(vec![
+                Cell::from(format!("#{}", rank + 1)),
+                Cell::from(Span::styled(node.config.node_name.clone(), name_style)),
+                Cell::from(Span::styled(role, Style::default().fg(palette.text_muted))),
+                Cell::from(Span::styled(
+                    node.node_version().unwrap_or("—").to_string(),
+                    Style::default().fg(palette.text_muted),
+                )),
+                Cell::from(
+                    bench
+                        .avg_tip_age
+                        .map(|s| format!("{:.0}s", s))
+                        .unwrap_or_else(|| "—".to_string()),
+                ),
+                Cell::from(fmt_pct(bench.cdf_1s)),
+                Cell::from(Span::styled(
+                    fmt_pct(bench.cdf_3s),
+                    Style::default().fg(palette.tertiary),
+                )),
+                Cell::from(fmt_pct(bench.cdf_5s)),
+                Cell::from(
+                    bench
+                        .avg_peers
+                        .map(|p| format!("{:.1}", p))
+                        .unwrap_or_else(|| "—".to_string()),
+                ),
+                Cell::from(
+                    bench
+                        .min_peers
+                        .map(|p| p.to_string())
+                        .unwrap_or_else(|| "—".to_string()),
+                ),
+                Cell::from(Span::styled(bench.restarts.to_string(), restart_style)),
+                Cell::from(Span::styled(
+                    format_duration(bench.downtime_secs),
+                    down_style,
+                )),
+                Cell::from(Span::styled(
+                    format!("{:.0}%", coverage),
+                    Style::default().fg(palette.text_muted),
+                )),
+            ])
+        })
+        .collect();
+
+    let header = Row::new(
+        [
+            "RANK",
+            "NODE",
+            "ROLE",
+            "VERSION",
+            "TIP AGE",
+            "≤1s",
+            "≤3s",
+            "≤5s",
+            "AVG PEERS",
+            "MIN",
+            "RESTARTS",
+            "DOWN",
+            "COVERAGE",
+        ]
+        .into_iter()
+        .map(|h| Cell::from(Span::styled(h, Style::default().fg(palette.primary).bold()))),
+    )
+    .bottom_margin(1);
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(5),  // RANK
+            Constraint::Min(14),    // NODE
+            Constraint::Length(6),  // ROLE
+            Constraint::Length(8),  // VERSION
+            Constraint::Length(8),  // TIP AGE
+            Constraint::Length(7),  // ≤1s
+            Constraint::Length(7),  // ≤3s
+            Constraint::Length(7),  // ≤5s
+            Constraint::Length(10), // AVG PEERS
+            Constraint::Length(5),  // MIN
+            Constraint::Length(9),  // RESTARTS
+            Constraint::Length(8),  // DOWN
+            Constraint::Length(9),  // COVERAGE
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" Node Comparison — last {}h ", hours))
+            .title_bottom(hint_line(&[
+                "[←→] window".to_string(),
+                close_hint(&app.app_config.keys, Action::Compare),
+            ]))
+            .border_style(Style::default().fg(palette.primary)),
+    );
+
+    frame.render_widget(table, popup_area);
+}
+
+// ============================================================================
+// Block propagation view
+// ============================================================================
+
+/// Draw the per-peer "first to deliver" ranking (from the node log)
+fn draw_propagation_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let popup_area = centered_rect(80, 80, area);
+
+    // Clear the background
+    frame.render_widget(Clear, popup_area);
+
+    let node = app.current_node();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title_bottom(hint_line(&[
+            "[↑↓] scroll".to_string(),
+            close_hint(&app.app_config.keys, Action::Propagation),
+        ]))
+        .border_style(Style::default().fg(palette.primary));
+
+    let ranking = node.propagation.ranking();
+    if ranking.is_empty() {
+        let message = match (&node.log_file, &node.log_error) {
+            (None, _) => "Set log_file for this node to the node's JSON log, with the ChainSyncClient and BlockFetchClient tracers enabled.".to_string(),
+            (Some(_), Some(error)) => format!("⚠ {}", error),
+            (Some(path), None) => format!(
+                "No header or block fetch events in {} yet. Check that the ChainSyncClient and BlockFetchClient tracers are enabled.",
+                path.display()
+            ),
+        };
+        let msg = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("  {}", message),
+                Style::default().fg(palette.text_muted).italic(),
+            )),
+        ])
+        .block(block.title(" Block Propagation "))
+        .wrap(Wrap { trim: false });
+        frame.render_widget(msg, popup_area);
+        return;
+    }
+
+    // Calculate visible rows (popup height - borders - header - header margin)
+    let visible_rows = popup_area.height.saturating_sub(4) as usize;
+    let scroll = app.propagation_scroll.min(ranking.len().saturating_sub(1));
+    let blocks = node.propagation.block_count();
+    // What the other columns, their spacing and the borders leave
+    let peer_width = (popup_area.width as usize)
+        .saturating_sub(59 + 6 + 2)
+        .max(22);
+
+    let rows: Vec<Row> = ranking
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(visible_rows)
+        .map(|(idx, peer)| {
+            let ip = crate::sockets::parse_address(&peer.peer)
+                .map(|(ip, _)| ip)
+                .unwrap_or_else(|| peer.peer.clone());
+            let location = app
+                .peer_locations
+                .get(&ip)
+                .cloned()
+                .unwrap_or_else(|| "—".to_string());
+            let delay_style = match peer.avg_delay_ms {
+                d if d < 200.0 => Style::default().fg(palette.healthy),
+                d if d < 1000.0 => Style::default().fg(palette.warning),
+                _ => Style::default().fg(palette.critical),
+            };
+            Row::new(vec![
+                Cell::from(Span::styled(
+                    format!("{}", idx + 1),
+                    Style::default().fg(palette.text_muted),
+                )),
+                Cell::from(Span::styled(
+                    shorten_address(&peer.peer, peer_width),
+                    Style::default().fg(palette.text),
+                )),
+                Cell::from(Span::styled(
+                    location,
+                    Style::default().fg(palette.tertiary),
+                )),
+                Cell::from(Span::styled(
+                    format!("{}/{}", peer.blocks, blocks),
+                    Style::default().fg(palette.text_muted),
+                )),
+                Cell::from(Span::styled(
+                    format!(
+                        "{} ({:.0}%)",
+                        peer.first,
+                        peer.first as f64 / peer.blocks as f64 * 100.0
+                    ),
+                    Style::default().fg(palette.primary),
+                )),
+                Cell::from(Span::styled(
+                    format!("{:.0}ms", peer.avg_delay_ms),
+                    delay_style,
+                )),
+                Cell::from(Span::styled(
+                    format!("{}ms", peer.median_delay_ms),
+                    Style::default().fg(palette.text_muted),
+                )),
+            ])
+        })
+        .collect();
+
+    let header_style = Style::default().fg(palette.primary).bold();
+    let header = Row::new(
+        [
+            "#", "PEER", "LOCATION", "BLOCKS", "FIRST", "AVG LATE", "MEDIAN",
+        ]
+        .into_iter()
+        .map(|h| Cell::from(Span::styled(h, header_style))),
+    )
+    .bottom_margin(1);
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(4),  // Rank
+            Constraint::Min(22),    // Peer
+            Constraint::Length(16), // Location
+            Constraint::Length(9),  // Blocks
+            Constraint::Length(11), // First
+            Constraint::Length(10), // Avg
+            Constraint::Length(9),  // Median
+        ],
+    )
+    .header(header)
+    .block(block.title(format!(
+        " Block Propagation — {} peers over the last {} blocks ",
+        ranking.len(),
+        blocks
+    )));
+
+    frame.render_widget(table, popup_area);
+}
+
+// ============================================================================
+// Mempool view
+// ============================================================================
+
+/// Draw the mempool transaction list (from LocalTxMonitor)
+fn draw_mempool_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let popup_area = centered_rect(90, 85, area);
+
+    // Clear the background
+    frame.render_widget(Clear, popup_area);
+
+    let node = app.current_node();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title_bottom(hint_line(&[
+            "[↑↓] scroll".to_string(),
+            close_hint(&app.app_config.keys, Action::Mempool),
+            key_hint(&app.app_config.keys, Action::Refresh, "refresh"),
+        ]))
+        .border_style(Style::default().fg(palette.primary));
+
+    let snapshot = match (&node.mempool, &node.mempool_error) {
+        (_, Some(error)) => {
+            let msg = Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(
+                    format!("  ⚠ {}", error),
+                    Style::default().fg(palette.warning),
+                )),
+                Line::from(""),
+                Line::from(Span::styled(
+                    "  Set socket_path for this node (or --socket-path) to inspect mempool transactions.",
+                    Style::default().fg(palette.text_muted).italic(),
+                )),
+            ])
+            .block(block.title(" Mempool "))
+            .wrap(Wrap { trim: false });
+            frame.render_widget(msg, popup_area);
+            return;
+        }
+        (Some(snapshot), None) => snapshot,
+        (None, None) => {
+            let msg = Paragraph::new("  Querying mempool...").block(block.title(" Mempool "));
+            frame.render_widget(msg, popup_area);
+            return;
+        }
+    };
+
+    // Calculate visible rows (popup height - borders - header - header margin)
+    let visible_rows = popup_area.height.saturating_sub(4) as usize;
+    let scroll = app.mempool_scroll.min(snapshot.txs.len().saturating_sub(1));
+
+    let mut rows: Vec<Row> = Vec::new();
+    if snapshot.txs.is_empty() {
+        rows.push(Row::new(vec![
+            Cell::from(""),
+            Cell::from(Span::styled(
+                "Mempool is empty",
+                Style::default().fg(palette.text_muted).italic(),
+            )),
+        ]));
+    }
+
+    for (idx, tx) in snapshot
+        .txs
+        .iter()
+        .enumerate()
+        .skip(scroll)
+        .take(visible_rows)
+    {
+        let fee_per_byte = tx
+            .fee_per_byte()
+            .map(|f| format!("{:.1}", f))
+            .unwrap_or_else(|| "—".to_string());
+
+        rows.push(Row::new(vec![
+            Cell::from(Span::styled(
+                format!("{}", idx + 1),
+                Style::default().fg(palette.text_muted),
+            )),
+            Cell::from(Span::styled(
+                tx.tx_hash.clone(),
+                Style::default().fg(palette.text),
+            )),
+            Cell::from(Span::styled(
+                format_bytes(Some(tx.size as u64)),
+                Style::default().fg(palette.secondary),
+            )),
+            Cell::from(Span::styled(
+                format_ada(tx.fee),
+                Style::default().fg(palette.tertiary),
+            )),
+            Cell::from(Span::styled(
+                fee_per_byte,
+                Style::default().fg(palette.text_muted),
+            )),
         ]));
     }
 
-    // Peer list shortcuts
-    help_lines.push(Line::from(""));
-    help_lines.push(Line::from(Span::styled(
-        "Peer List (when open)",
-        Style::default().bold().underlined().fg(palette.primary),
-    )));
-    help_lines.push(Line::from(""));
-    help_lines.push(Line::from(vec![
-        Span::styled("  ↑ ↓, j k  ", Style::default().fg(palette.tertiary)),
-        Span::raw("Navigate peer list"),
-    ]));
-    help_lines.push(Line::from(vec![
-        Span::styled("  Enter, →  ", Style::default().fg(palette.tertiary)),
-        Span::raw("View peer details"),
-    ]));
-    help_lines.push(Line::from(vec![
-        Span::styled("  ←, BS     ", Style::default().fg(palette.tertiary)),
-        Span::raw("Back to peer list"),
-    ]));
-    help_lines.push(Line::from(vec![
-        Span::styled("  r         ", Style::default().fg(palette.tertiary)),
-        Span::raw("Refresh peer list"),
-    ]));
+    let fill = match (snapshot.size_bytes, snapshot.capacity_bytes) {
+        (Some(size), Some(capacity)) if capacity > 0 => format!(
+            " — {} / {} ({:.0}%)",
+            format_bytes(Some(size)),
+            format_bytes(Some(capacity)),
+            size as f64 / capacity as f64 * 100.0
+        ),
+        _ => String::new(),
+    };
+    let count = snapshot
+        .tx_count
+        .unwrap_or(snapshot.txs.len() as u64)
+        .to_string();
+    let truncated = if snapshot.truncated {
+        format!(" (showing first {})", snapshot.txs.len())
+    } else {
+        String::new()
+    };
+    let title = format!(
+        " Mempool — {} txs{}{} — slot {} ",
+        count,
+        truncated,
+        fill,
+        format_number(snapshot.slot)
+    );
 
-    help_lines.extend(vec![
-        Line::from(""),
-        Line::from(Span::styled(
-            "Health Indicators",
-            Style::default().bold().underlined().fg(palette.primary),
+    let header = Row::new(vec![
+        Cell::from(Span::styled(
+            "#",
+            Style::default().fg(palette.primary).bold(),
         )),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("  ● Healthy   ", Style::default().fg(palette.healthy)),
-            Span::raw("Operating normally"),
-        ]),
-        Line::from(vec![
-            Span::styled("  ● Warning   ", Style::default().fg(palette.warning)),
-            Span::raw("Needs attention"),
-        ]),
-        Line::from(vec![
-            Span::styled("  ● Critical  ", Style::default().fg(palette.critical)),
-            Span::raw("Action required"),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Press any key to close",
-            Style::default().fg(palette.text_muted).italic(),
+        Cell::from(Span::styled(
+            "TX HASH",
+            Style::default().fg(palette.primary).bold(),
         )),
-    ]);
-
-    let help = Paragraph::new(help_lines)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(" Help ")
-                .border_style(Style::default().fg(palette.primary)),
-        )
-        .wrap(Wrap { trim: false });
-
-    frame.render_widget(help, popup_area);
-}
+        Cell::from(Span::styled(
+            "SIZE",
+            Style::default().fg(palette.primary).bold(),
+        )),
+        Cell::from(Span::styled(
+            "FEE",
+            Style::default().fg(palette.primary).bold(),
+        )),
+        Cell::from(Span::styled(
+            "LOVELACE/B",
+            Style::default().fg(palette.primary).bold(),
+        )),
+    ])
+    .bottom_margin(1);
 
-/// Create a centered rectangle for popups
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(5),  // #
+            Constraint::Min(20),    // TX HASH
+            Constraint::Length(10), // SIZE
+            Constraint::Length(14), // FEE
+            Constraint::Length(11), // LOVELACE/B
+        ],
+    )
+    .header(header)
+    .block(block.title(title));
 
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1]
+    frame.render_widget(table, popup_area);
 }
 
 // ============================================================================
@@ -902,6 +3424,27 @@ fn draw_peers_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette)
     }
 }
 
+/// Spinner and RTT probe progress while the node's peer list refreshes in
+/// the background, e.g. " — ⠹ probing 12/40" (empty otherwise)
+fn peer_refresh_indicator(node: &NodeState) -> String {
+    node.peer_refresh_progress()
+        .map(|(elapsed, probes)| format_refresh_indicator(elapsed, probes))
+        .unwrap_or_default()
+}
+
+fn format_refresh_indicator(
+    elapsed: std::time::Duration,
+    (finished, started): (usize, usize),
+) -> String {
+    const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    let frame = SPINNER[(elapsed.as_millis() / 100) as usize % SPINNER.len()];
+    if started > 0 {
+        format!(" — {} probing {}/{}", frame, finished, started)
+    } else {
+        format!(" — {} scanning", frame)
+    }
+}
+
 /// Draw full peer view with individual peer details (local mode)
 fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
     // Use 90% of screen for peer list
@@ -913,8 +3456,61 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
     let node = app.current_node();
     let peers = &node.peer_connections;
 
+    // Total host network I/O gauge above the table once two samples exist
+    let table_area = match node.interface_rates {
+        Some((rx, tx)) => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(0)])
+                .split(popup_area);
+            let ratio = if node.interface_peak_rate > 0.0 {
+                ((rx + tx) / node.interface_peak_rate).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let gauge = Gauge::default()
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(" Network I/O ")
+                        .border_style(Style::default().fg(palette.primary)),
+                )
+                .gauge_style(Style::default().fg(palette.secondary).bg(palette.gauge_bg))
+                .ratio(ratio)
+                .label(Span::styled(
+                    format!(
+                        "↓ {}  ↑ {}  (peak {})",
+                        format_rate(Some(rx)),
+                        format_rate(Some(tx)),
+                        format_rate(Some(node.interface_peak_rate))
+                    ),
+                    Style::default().fg(palette.gauge_label).bold(),
+                ));
+            frame.render_widget(gauge, chunks[0]);
+            chunks[1]
+        }
+        None => popup_area,
+    };
+
+    // Latency histogram below the list when there's room
+    let table_area = if table_area.height >= 20 {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(8)])
+            .split(table_area);
+        draw_latency_histogram(frame, chunks[1], node, palette);
+        chunks[0]
+    } else {
+        table_area
+    };
+
     // Calculate visible rows (popup height - borders - header - footer - header margin)
-    let visible_rows = popup_area.height.saturating_sub(6) as usize;
+    let visible_rows = table_area.height.saturating_sub(6) as usize;
+
+    if app.peer_grouping != PeerGrouping::None {
+        draw_peer_groups(frame, table_area, app, visible_rows, palette);
+        return;
+    }
 
     // Build table rows
     let mut rows: Vec<Row> = Vec::new();
@@ -941,15 +3537,29 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
         rows.push(Row::new(vec![
             Cell::from(""),
             Cell::from(Span::styled(
-                "No peer connections found. Press 'r' to refresh.",
+                if node.peer_refreshing() {
+                    "Scanning peer connections…"
+                } else {
+                    "No peer connections found. Press 'r' to refresh."
+                },
                 Style::default().fg(palette.text_muted).italic(),
             )),
             Cell::from(""),
             Cell::from(""),
             Cell::from(""),
             Cell::from(""),
+            Cell::from(""),
+            Cell::from(""),
+            Cell::from(""),
         ]));
     } else {
+        let now = std::time::Instant::now();
+        // The IP column gets what the fixed columns, their spacing and the
+        // borders leave; long IPv6 addresses are shortened to fit
+        let ip_width = (table_area.width as usize)
+            .saturating_sub(76 + 9 + 2)
+            .max(15);
+
         // Apply scroll offset - only render visible rows
         let scroll_offset = app.peer_list_scroll;
         let end_idx = (scroll_offset + visible_rows).min(sorted_peers.len());
@@ -982,6 +3592,11 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
                 None => Style::default().fg(palette.text_muted),
             };
 
+            let bandwidth_str = match (peer.recv_rate, peer.send_rate) {
+                (None, None) => "—".to_string(),
+                (rx, tx) => format!("↓{} ↑{}", format_rate_short(rx), format_rate_short(tx)),
+            };
+
             let queue_str = if peer.recv_q > 0 || peer.send_q > 0 {
                 format!("R:{} S:{}", peer.recv_q, peer.send_q)
             } else {
@@ -995,25 +3610,45 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
                 .cloned()
                 .unwrap_or_else(|| "—".to_string());
 
+            let age = node.peer_ages.age(peer, now);
+            let age_style = match age {
+                Some((age, false)) if age.as_secs() < 60 => Style::default().fg(palette.warning),
+                Some(_) => Style::default().fg(palette.text),
+                None => Style::default().fg(palette.text_muted),
+            };
+
             // Selection indicator
             let selector = if is_selected { "▶" } else { " " };
 
             let mut row = Row::new(vec![
                 Cell::from(Span::styled(selector, Style::default().fg(palette.primary))),
                 Cell::from(Span::styled(peer.direction_str().to_string(), dir_style)),
-                Cell::from(Span::styled(
-                    peer.ip.clone(),
-                    Style::default().fg(palette.text),
-                )),
+                Cell::from(peer_state_span(peer.peer_state, palette)),
+                Cell::from(if app.blocklist.contains(&peer.ip) {
+                    Span::styled(
+                        format!("{} ⛔", shorten_address(&peer.ip, ip_width - 3)),
+                        Style::default().fg(palette.critical).bold(),
+                    )
+                } else {
+                    Span::styled(
+                        shorten_address(&peer.ip, ip_width),
+                        Style::default().fg(palette.text),
+                    )
+                }),
                 Cell::from(Span::styled(
                     peer.port.to_string(),
                     Style::default().fg(palette.text_muted),
                 )),
+                Cell::from(Span::styled(format_connection_age(age), age_style)),
                 Cell::from(Span::styled(
                     location,
                     Style::default().fg(palette.tertiary),
                 )),
                 Cell::from(Span::styled(rtt_str, rtt_style)),
+                Cell::from(Span::styled(
+                    bandwidth_str,
+                    Style::default().fg(palette.text),
+                )),
                 Cell::from(Span::styled(
                     queue_str,
                     Style::default().fg(palette.text_muted),
@@ -1054,12 +3689,13 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
     };
 
     let title = format!(
-        " Peer Connections — {} total (IN: {} OUT: {}) — Avg RTT: {:.1}ms{} ",
+        " Peer Connections — {} total (IN: {} OUT: {}) — Avg RTT: {:.1}ms{}{} ",
         peers.len(),
         incoming_count,
         outgoing_count,
         avg_rtt,
-        scroll_indicator
+        scroll_indicator,
+        peer_refresh_indicator(node)
     );
 
     // Create header row
@@ -1069,6 +3705,10 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
             "DIR",
             Style::default().fg(palette.primary).bold(),
         )),
+        Cell::from(Span::styled(
+            "STATE",
+            Style::default().fg(palette.primary).bold(),
+        )),
         Cell::from(Span::styled(
             "IP ADDRESS",
             Style::default().fg(palette.primary).bold(),
@@ -1077,6 +3717,10 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
             "PORT",
             Style::default().fg(palette.primary).bold(),
         )),
+        Cell::from(Span::styled(
+            "AGE",
+            Style::default().fg(palette.primary).bold(),
+        )),
         Cell::from(Span::styled(
             "LOCATION",
             Style::default().fg(palette.primary).bold(),
@@ -1085,6 +3729,10 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
             "RTT",
             Style::default().fg(palette.primary).bold(),
         )),
+        Cell::from(Span::styled(
+            "BANDWIDTH",
+            Style::default().fg(palette.primary).bold(),
+        )),
         Cell::from(Span::styled(
             "QUEUE",
             Style::default().fg(palette.primary).bold(),
@@ -1098,10 +3746,13 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
         [
             Constraint::Length(2),  // Selection
             Constraint::Length(4),  // DIR
+            Constraint::Length(6),  // STATE
             Constraint::Min(15),    // IP
             Constraint::Length(6),  // PORT
+            Constraint::Length(8),  // AGE
             Constraint::Length(16), // LOCATION
             Constraint::Length(10), // RTT
+            Constraint::Length(14), // BANDWIDTH
             Constraint::Length(10), // QUEUE
         ],
     )
@@ -1110,14 +3761,284 @@ fn draw_peers_view_full(frame: &mut Frame, area: Rect, app: &App, palette: &Pale
         Block::default()
             .borders(Borders::ALL)
             .title(title)
-            .title_bottom(
-                Line::from(" [↑↓] select | [Enter] details | [p/Esc] close | [r] refresh ")
-                    .centered(),
-            )
+            .title_bottom(hint_line(&[
+                "[↑↓] select".to_string(),
+                "[Enter] details".to_string(),
+                key_hint(&app.app_config.keys, Action::Graphs, "group"),
+                close_hint(&app.app_config.keys, Action::Peers),
+                key_hint(&app.app_config.keys, Action::Refresh, "refresh"),
+            ]))
             .border_style(Style::default().fg(palette.primary)),
     );
 
-    frame.render_widget(table, popup_area);
+    frame.render_widget(table, table_area);
+}
+
+/// A connection's hot/warm state, "—" where unknown
+fn peer_state_span(state: Option<PeerState>, palette: &Palette) -> Span<'static> {
+    match state {
+        Some(state) => Span::styled(
+            state.to_string(),
+            Style::default().fg(match state {
+                PeerState::Hot => palette.healthy,
+                PeerState::Warm => palette.warning,
+                PeerState::Cold => palette.text_muted,
+            }),
+        ),
+        None => Span::styled("—", Style::default().fg(palette.text_muted)),
+    }
+}
+
+/// Draw a bar per latency bucket with its share of the connections
+fn draw_latency_histogram(frame: &mut Frame, area: Rect, node: &NodeState, palette: &Palette) {
+    let stats = PeerStats::from_node(&node.peer_connections, &node.metrics);
+    let total = node.peer_connections.len() as u64;
+    let max = LatencyBucket::ALL
+        .iter()
+        .map(|bucket| stats.latency_count(*bucket))
+        .max()
+        .unwrap_or(0);
+    // Borders, the bucket label and the count, at most 40 cells
+    let bar_width = (area.width as usize).saturating_sub(2 + 13 + 12).min(40);
+
+    let lines: Vec<Line> = LatencyBucket::ALL
+        .iter()
+        .map(|bucket| {
+            let count = stats.latency_count(*bucket);
+            let color = match bucket {
+                LatencyBucket::VeryLow => palette.healthy,
+                LatencyBucket::Low => palette.warning,
+                LatencyBucket::Medium | LatencyBucket::High => palette.critical,
+                LatencyBucket::Unreachable | LatencyBucket::Unprobed => palette.text_muted,
+            };
+            let percent = (count * 100).checked_div(total).unwrap_or(0);
+            Line::from(vec![
+                Span::styled(
+                    format!(" {:<12}", bucket.to_string()),
+                    Style::default().fg(palette.text_muted),
+                ),
+                Span::styled(
+                    histogram_bar(count, max, bar_width),
+                    Style::default().fg(color),
+                ),
+                Span::styled(
+                    format!(" {} ({}%)", count, percent),
+                    Style::default().fg(palette.text),
+                ),
+            ])
+        })
+        .collect();
+
+    let title = if stats.reachable_count > 0 {
+        format!(" Latency — avg {}ms ", stats.avg_rtt_ms)
+    } else {
+        " Latency ".to_string()
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(palette.primary));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Bar of `count` scaled so `max` fills `width` cells; any non-zero count
+/// gets at least one cell
+fn histogram_bar(count: u64, max: u64, width: usize) -> String {
+    if max == 0 {
+        return String::new();
+    }
+    let cells = (count as usize * width).div_ceil(max as usize);
+    "█".repeat(cells)
+}
+
+/// Draw the peer list grouped by subnet or network
+fn draw_peer_groups(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    visible_rows: usize,
+    palette: &Palette,
+) {
+    let node = app.current_node();
+    let groups = group_peers(
+        &node.peer_connections,
+        app.peer_grouping,
+        &app.peer_networks,
+    );
+
+    // Network diversity summary above the network groups
+    let (area, visible_rows) = if app.peer_grouping == PeerGrouping::Network {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(0)])
+            .split(area);
+        draw_network_diversity(frame, chunks[0], app, palette);
+        (chunks[1], visible_rows.saturating_sub(4))
+    } else {
+        (area, visible_rows)
+    };
+    let max_total = groups.first().map(|g| g.total()).unwrap_or(0).max(1);
+
+    let rows: Vec<Row> = groups
+        .iter()
+        .enumerate()
+        .skip(app.peer_list_scroll)
+        .take(visible_rows)
+        .map(|(idx, group)| {
+            let is_selected = idx == app.peer_list_selected;
+            let selector = if is_selected { "▶" } else { " " };
+            // Proportional bar, 20 cells for the largest group
+            let bar = "█".repeat((group.total() * 20).div_ceil(max_total));
+            let in_style = if group.incoming >= 5 {
+                Style::default().fg(palette.warning)
+            } else {
+                Style::default().fg(palette.primary)
+            };
+            let mut row = Row::new(vec![
+                Cell::from(Span::styled(selector, Style::default().fg(palette.primary))),
+                Cell::from(Span::styled(
+                    group.key.clone(),
+                    Style::default().fg(palette.text),
+                )),
+                Cell::from(Span::styled(group.incoming.to_string(), in_style)),
+                Cell::from(Span::styled(
+                    group.outgoing.to_string(),
+                    Style::default().fg(palette.secondary),
+                )),
+                Cell::from(Span::styled(bar, Style::default().fg(palette.tertiary))),
+            ]);
+            if is_selected {
+                row = row.style(Style::default().bg(palette.gauge_bg));
+            }
+            row
+        })
+        .collect();
+
+    let header_style = Style::default().fg(palette.primary).bold();
+    let header = Row::new(vec![
+        Cell::from(" "),
+        Cell::from(Span::styled(app.peer_grouping.label(), header_style)),
+        Cell::from(Span::styled("IN", header_style)),
+        Cell::from(Span::styled("OUT", header_style)),
+        Cell::from(Span::styled("CONNECTIONS", header_style)),
+    ])
+    .bottom_margin(1);
+
+    let title = format!(
+        " Peer Connections by {} — {} groups, {} connections{} ",
+        match app.peer_grouping {
+            PeerGrouping::Network => "Network",
+            _ => "Subnet",
+        },
+        groups.len(),
+        node.peer_connections.len(),
+        peer_refresh_indicator(node)
+    );
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(2),  // Selection
+            Constraint::Min(20),    // Group
+            Constraint::Length(5),  // IN
+            Constraint::Length(5),  // OUT
+            Constraint::Length(22), // Bar
+        ],
+    )
+    .header(header)
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .title_bottom(hint_line(&[
+                "[↑↓] select".to_string(),
+                key_hint(&app.app_config.keys, Action::Graphs, "group"),
+                close_hint(&app.app_config.keys, Action::Peers),
+                key_hint(&app.app_config.keys, Action::Refresh, "refresh"),
+            ]))
+            .border_style(Style::default().fg(palette.primary)),
+    );
+
+    frame.render_widget(table, area);
+}
+
+/// Draw the top networks of all and of outgoing connections, warning when
+/// one network holds too many of the node's outgoing (hot and warm) peers
+fn draw_network_diversity(frame: &mut Frame, area: Rect, app: &App, palette: &Palette) {
+    let peers = &app.current_node().peer_connections;
+    let outgoing: Vec<_> = peers.iter().filter(|p| !p.incoming).cloned().collect();
+    let all = network_diversity(peers, &app.peer_networks, 3);
+    let out = network_diversity(&outgoing, &app.peer_networks, 3);
+
+    let limit = app.app_config.peer_outgoing_network_alert_percent as usize;
+    let concentrated = limit > 0
+        && out
+            .top
+            .first()
+            .is_some_and(|(_, count)| out.percent(*count) >= limit);
+    let out_style = if concentrated {
+        Style::default().fg(palette.warning)
+    } else {
+        Style::default().fg(palette.text)
+    };
+
+    let label_style = Style::default().fg(palette.text_muted);
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("All       ", label_style),
+            Span::styled(format_diversity(&all), Style::default().fg(palette.text)),
+        ]),
+        Line::from(vec![
+            Span::styled("Outgoing  ", label_style),
+            Span::styled(
+                format!(
+                    "{}{}",
+                    format_diversity(&out),
+                    if concentrated {
+                        "  ⚠ concentrated"
+                    } else {
+                        ""
+                    }
+                ),
+                out_style,
+            ),
+        ]),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Network Diversity ")
+        .border_style(Style::default().fg(palette.primary));
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// One line of the diversity summary, e.g. "22 connections, 9 networks —
+/// AS24940 Hetzner 55% · AS16509 Amazon 14% (3 unresolved)"
+fn format_diversity(diversity: &NetworkDiversity) -> String {
+    if diversity.connections == 0 {
+        return "no connections".to_string();
+    }
+    if diversity.networks == 0 {
+        return format!(
+            "{} connections, networks not resolved yet",
+            diversity.connections
+        );
+    }
+    let top: Vec<String> = diversity
+        .top
+        .iter()
+        .map(|(network, count)| format!("{} {}%", network, diversity.percent(*count)))
+        .collect();
+    let mut line = format!(
+        "{} connections, {} networks — {}",
+        diversity.connections,
+        diversity.networks,
+        top.join(" · ")
+    );
+    let unresolved = diversity.connections - diversity.resolved;
+    if unresolved > 0 {
+        line.push_str(&format!(" ({} unresolved)", unresolved));
+    }
+    line
 }
 
 /// Draw Prometheus-only peer view with aggregate stats (remote mode)
@@ -1129,18 +4050,18 @@ fn draw_peers_view_prometheus(frame: &mut Frame, area: Rect, app: &App, palette:
     frame.render_widget(Clear, popup_area);
 
     let node = app.current_node();
-    let metrics = &node.metrics;
+    let stats = PeerStats::from_node(&node.peer_connections, &node.metrics);
 
     // Get connection counts
-    let incoming = metrics.incoming_connections.unwrap_or(0);
-    let outgoing = metrics.outgoing_connections.unwrap_or(0);
-    let duplex = metrics.full_duplex_connections.unwrap_or(0);
-    let unidirectional = metrics.unidirectional_connections.unwrap_or(0);
+    let incoming = stats.incoming;
+    let outgoing = stats.outgoing;
+    let duplex = stats.duplex.unwrap_or(0);
+    let unidirectional = node.metrics.unidirectional_connections.unwrap_or(0);
 
     // Get peer state counts
-    let cold = metrics.p2p.cold_peers.unwrap_or(0);
-    let warm = metrics.p2p.warm_peers.unwrap_or(0);
-    let hot = metrics.p2p.hot_peers.unwrap_or(0);
+    let cold = stats.state_count(PeerState::Cold);
+    let warm = stats.state_count(PeerState::Warm);
+    let hot = stats.state_count(PeerState::Hot);
     let total_peers = cold + warm + hot;
 
     // Calculate max for bar scaling
@@ -1235,22 +4156,36 @@ fn draw_peers_view_prometheus(frame: &mut Frame, area: Rect, app: &App, palette:
 
     lines.push(Line::from(""));
     lines.push(Line::from(""));
-    lines.push(Line::from(Span::styled(
-        "  ⓘ Detailed peer info (IP, RTT, location) requires",
-        Style::default().fg(palette.text_muted).italic(),
-    )));
-    lines.push(Line::from(Span::styled(
-        "    running sview on the same machine as the node.",
-        Style::default().fg(palette.text_muted).italic(),
-    )));
+    if let Some(error) = &node.agent_error {
+        lines.push(Line::from(Span::styled(
+            format!("  ⚠ Agent: {}", error),
+            Style::default().fg(palette.warning),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            "  ⓘ Detailed peer info (IP, RTT, location) requires",
+            Style::default().fg(palette.text_muted).italic(),
+        )));
+        lines.push(Line::from(Span::styled(
+            "    sview or `sview agent` on the node's machine.",
+            Style::default().fg(palette.text_muted).italic(),
+        )));
+    }
 
-    let title = format!(" Peers (Prometheus) — {} total ", total_peers);
+    let title = format!(
+        " Peers (Prometheus) — {} total{} ",
+        total_peers,
+        peer_refresh_indicator(node)
+    );
 
     let paragraph = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
             .title(title)
-            .title_bottom(Line::from(" [p/Esc] close | [r] refresh ").centered())
+            .title_bottom(hint_line(&[
+                close_hint(&app.app_config.keys, Action::Peers),
+                key_hint(&app.app_config.keys, Action::Refresh, "refresh"),
+            ]))
             .border_style(Style::default().fg(palette.primary)),
     );
 
@@ -1285,6 +4220,26 @@ fn draw_peer_detail_view(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
         .cloned()
         .unwrap_or_else(|| "Unknown".to_string());
 
+    let hostname = match app.peer_hostname(&peer.ip) {
+        Some(name) => Span::styled(name.to_string(), Style::default().fg(palette.text)),
+        _ if app.hostname_pending(&peer.ip) => {
+            Span::styled("resolving…", Style::default().fg(palette.text_muted))
+        }
+        _ => Span::styled("—", Style::default().fg(palette.text_muted)),
+    };
+
+    let age = app
+        .current_node()
+        .peer_ages
+        .age(&peer, std::time::Instant::now());
+    let connected = match age {
+        Some((_, true)) => format!(
+            "{} (open before scanning started)",
+            format_connection_age(age)
+        ),
+        _ => format_connection_age(age),
+    };
+
     // Build detail lines
     let mut lines = vec![
         Line::from(Span::styled(
@@ -1296,6 +4251,10 @@ fn draw_peer_detail_view(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
             Span::styled("  IP Address:    ", Style::default().fg(palette.text_muted)),
             Span::styled(&peer.ip, Style::default().fg(palette.text).bold()),
         ]),
+        Line::from(vec![
+            Span::styled("  Host Name:     ", Style::default().fg(palette.text_muted)),
+            hostname,
+        ]),
         Line::from(vec![
             Span::styled("  Port:          ", Style::default().fg(palette.text_muted)),
             Span::styled(peer.port.to_string(), Style::default().fg(palette.text)),
@@ -1304,6 +4263,10 @@ fn draw_peer_detail_view(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
             Span::styled("  Location:      ", Style::default().fg(palette.text_muted)),
             Span::styled(location, Style::default().fg(palette.tertiary)),
         ]),
+        Line::from(vec![
+            Span::styled("  Connected:     ", Style::default().fg(palette.text_muted)),
+            Span::styled(connected, Style::default().fg(palette.text)),
+        ]),
         Line::from(vec![
             Span::styled("  Direction:     ", Style::default().fg(palette.text_muted)),
             Span::styled(
@@ -1336,15 +4299,40 @@ fn draw_peer_detail_view(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
         None => ("Not available".to_string(), palette.text_muted),
     };
 
-    lines.push(Line::from(vec![
+    let mut rtt_line = vec![
         Span::styled("  RTT Latency:   ", Style::default().fg(palette.text_muted)),
         Span::styled(rtt_str, Style::default().fg(rtt_color)),
-    ]));
+    ];
+    if let Some(source) = peer.rtt_source {
+        rtt_line.push(Span::styled(
+            format!(" [{}]", source.label()),
+            Style::default().fg(palette.text_muted),
+        ));
+    }
+    lines.push(Line::from(rtt_line));
 
     lines.push(Line::from(vec![
         Span::styled("  State:         ", Style::default().fg(palette.text_muted)),
         Span::styled(&peer.state, Style::default().fg(palette.healthy)),
     ]));
+    lines.push(Line::from(vec![
+        Span::styled("  Peer State:    ", Style::default().fg(palette.text_muted)),
+        peer_state_span(peer.peer_state, palette),
+    ]));
+
+    for (label, total, rate) in [
+        ("  Received:      ", peer.bytes_received, peer.recv_rate),
+        ("  Sent:          ", peer.bytes_sent, peer.send_rate),
+    ] {
+        let mut value = format_bytes(total);
+        if rate.is_some() {
+            value = format!("{} ({})", value, format_rate(rate));
+        }
+        lines.push(Line::from(vec![
+            Span::styled(label, Style::default().fg(palette.text_muted)),
+            Span::styled(value, Style::default().fg(palette.text)),
+        ]));
+    }
 
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
@@ -1377,9 +4365,36 @@ fn draw_peer_detail_view(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
         ),
     ]));
 
+    if app.blocklist.contains(&peer.ip) {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Blocklist",
+            Style::default().bold().underlined().fg(palette.critical),
+        )));
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled("  Banned in:     ", Style::default().fg(palette.text_muted)),
+            Span::styled(
+                app.blocklist.path().display().to_string(),
+                Style::default().fg(palette.text),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled("  Firewall:      ", Style::default().fg(palette.text_muted)),
+            Span::styled(
+                app.app_config.firewall.ban_command(&peer.ip),
+                Style::default().fg(palette.warning),
+            ),
+        ]));
+    }
+
     lines.push(Line::from(""));
+    let ban = match app.app_config.keys.short_label(Action::Ban) {
+        key if key.is_empty() => String::new(),
+        key => format!(", [{}] to ban/unban", key),
+    };
     lines.push(Line::from(Span::styled(
-        "Press [Backspace] or [←] to go back",
+        format!("Press [Backspace] or [←] to go back{}", ban),
         Style::default().fg(palette.text_muted).italic(),
     )));
 
@@ -1399,11 +4414,77 @@ fn draw_peer_detail_view(frame: &mut Frame, area: Rect, app: &App, palette: &Pal
 // Table row helpers
 // ============================================================================
 
-fn create_metric_row<'a>(label: &'a str, value: String, palette: &Palette) -> Row<'a> {
-    Row::new(vec![
-        Cell::from(Span::styled(label, Style::default().fg(palette.text_muted))),
-        Cell::from(Span::styled(value, Style::default().fg(palette.text))),
-    ])
+/// A label/value row of a dashboard panel
+///
+/// Keeps its label so the rows can be listed for ↑/↓ selection and the
+/// selected one highlighted and explained.
+struct MetricRow<'a> {
+    label: String,
+    row: Row<'a>,
+}
+
+impl<'a> MetricRow<'a> {
+    fn new(label: &str, row: Row<'a>) -> Self {
+        Self {
+            label: label.to_string(),
+            row,
+        }
+    }
+}
+
+impl<'a> From<MetricRow<'a>> for Row<'a> {
+    fn from(metric: MetricRow<'a>) -> Self {
+        metric.row
+    }
+}
+
+/// Table rows for a panel, with the row selected for drill-down highlighted
+fn highlight_selected<'a>(rows: Vec<MetricRow<'a>>, app: &App, palette: &Palette) -> Vec<Row<'a>> {
+    rows.into_iter()
+        .map(|metric| {
+            if app.selected_metric.as_deref() == Some(metric.label.as_str()) {
+                metric.row.style(Style::default().bg(palette.gauge_bg))
+            } else {
+                metric.row
+            }
+        })
+        .collect()
+}
+
+fn create_metric_row<'a>(label: &'a str, value: String, palette: &Palette) -> MetricRow<'a> {
+    MetricRow::new(
+        label,
+        Row::new(vec![
+            Cell::from(Span::styled(label, Style::default().fg(palette.text_muted))),
+            Cell::from(Span::styled(value, Style::default().fg(palette.text))),
+        ]),
+    )
+}
+
+/// Rows for the custom metrics pinned to a panel
+fn custom_metric_rows(
+    node: &NodeState,
+    panel: PanelKind,
+    palette: &Palette,
+) -> Vec<MetricRow<'static>> {
+    node.custom_metric_values(panel)
+        .into_iter()
+        .map(|(label, value)| {
+            MetricRow::new(
+                label,
+                Row::new(vec![
+                    Cell::from(Span::styled(
+                        label.to_string(),
+                        Style::default().fg(palette.text_muted),
+                    )),
+                    Cell::from(Span::styled(
+                        value.unwrap_or_else(|| "—".to_string()),
+                        Style::default().fg(palette.text),
+                    )),
+                ]),
+            )
+        })
+        .collect()
 }
 
 fn create_metric_row_with_trend<'a>(
@@ -1411,20 +4492,59 @@ fn create_metric_row_with_trend<'a>(
     value: String,
     trend: Option<f64>,
     palette: &Palette,
-) -> Row<'a> {
+) -> MetricRow<'a> {
     let (trend_indicator, trend_color) = format_trend(trend, palette);
     let value_with_trend = if !trend_indicator.is_empty() {
         format!("{} {}", value, trend_indicator)
     } else {
         value
     };
-    Row::new(vec![
-        Cell::from(Span::styled(label, Style::default().fg(palette.text_muted))),
-        Cell::from(Span::styled(
-            value_with_trend,
-            Style::default().fg(trend_color),
-        )),
-    ])
+    MetricRow::new(
+        label,
+        Row::new(vec![
+            Cell::from(Span::styled(label, Style::default().fg(palette.text_muted))),
+            Cell::from(Span::styled(
+                value_with_trend,
+                Style::default().fg(trend_color),
+            )),
+        ]),
+    )
+}
+
+/// Create a row for a counter with its recent rate and a trend arrow,
+/// e.g. "1.2k (4.1/min) ↑"
+fn create_counter_row<'a>(
+    label: &'a str,
+    value: Option<u64>,
+    rate: &RateTracker,
+    palette: &Palette,
+) -> MetricRow<'a> {
+    let Some(count) = value else {
+        return create_metric_row(label, "—".to_string(), palette);
+    };
+    let mut value = format_compact(count);
+    if let Some(per_min) = rate.per_minute() {
+        value = format!("{} ({})", value, format_per_minute(per_min));
+    }
+    // A faster or slower counter isn't good or bad in itself, so the arrow stays neutral
+    let (arrow, _) = format_trend(rate.trend(), palette);
+    MetricRow::new(
+        label,
+        Row::new(vec![
+            Cell::from(Span::styled(label, Style::default().fg(palette.text_muted))),
+            Cell::from(Line::from(vec![
+                Span::styled(value, Style::default().fg(palette.text)),
+                Span::styled(
+                    if arrow.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" {}", arrow)
+                    },
+                    Style::default().fg(palette.text_muted),
+                ),
+            ])),
+        ]),
+    )
 }
 
 /// Format a trend value into an indicator arrow and color
@@ -1442,28 +4562,41 @@ fn create_health_row<'a>(
     value: String,
     health: HealthStatus,
     palette: &Palette,
-) -> Row<'a> {
+) -> MetricRow<'a> {
     let color = health_to_color(health, palette);
-    Row::new(vec![
-        Cell::from(Span::styled(label, Style::default().fg(color))),
-        Cell::from(Span::styled(value, Style::default().fg(color))),
-    ])
+    MetricRow::new(
+        label,
+        Row::new(vec![
+            Cell::from(Span::styled(label, Style::default().fg(color))),
+            Cell::from(Span::styled(value, Style::default().fg(color))),
+        ]),
+    )
 }
 
-/// Create a row for late blocks with health-based coloring
-/// 0 late blocks = good, 1-10 = warning, >10 = critical
-fn create_late_blocks_row(blocks_late: Option<u64>, palette: &Palette) -> Row<'static> {
-    let (value, health) = match blocks_late {
-        Some(0) => ("0".to_string(), HealthStatus::Good),
-        Some(n) if n <= 10 => (n.to_string(), HealthStatus::Warning),
-        Some(n) => (n.to_string(), HealthStatus::Critical),
-        None => ("—".to_string(), HealthStatus::Good),
+/// Create a row for late blocks, colored by the rate over the last minute
+///
+/// The count since start only grows, so a long-running node would stay red
+/// after one bad hour: no late blocks lately is good, any is a warning, and
+/// one a minute or more (a third of mainnet blocks) is critical.
+fn create_late_blocks_row(
+    blocks_late: Option<u64>,
+    rate: &RateTracker,
+    palette: &Palette,
+) -> MetricRow<'static> {
+    let Some(count) = blocks_late else {
+        return create_health_row("Blks Late", "—".to_string(), HealthStatus::Good, palette);
     };
-    let color = health_to_color(health, palette);
-    Row::new(vec![
-        Cell::from(Span::styled("Blks Late", Style::default().fg(color))),
-        Cell::from(Span::styled(value, Style::default().fg(color))),
-    ])
+    let per_min = rate.per_minute().filter(|r| *r > 0.0);
+    let health = match per_min {
+        Some(r) if r >= 1.0 => HealthStatus::Critical,
+        Some(_) => HealthStatus::Warning,
+        None => HealthStatus::Good,
+    };
+    let value = match per_min {
+        Some(r) => format!("{} ({})", format_compact(count), format_per_minute(r)),
+        None => format_compact(count),
+    };
+    create_health_row("Blks Late", value, health, palette)
 }
 
 // ============================================================================
@@ -1482,28 +4615,119 @@ fn format_metric_u64(value: Option<u64>) -> String {
     value.map(format_number).unwrap_or_else(|| "—".to_string())
 }
 
-fn format_number(n: u64) -> String {
-    let s = n.to_string();
-    let mut result = String::new();
-    for (i, c) in s.chars().rev().enumerate() {
-        if i > 0 && i % 3 == 0 {
-            result.push(',');
-        }
-        result.push(c);
+/// Large counts abbreviated: 1234 -> "1.2k", 5600000 -> "5.6M"
+fn format_compact(n: u64) -> String {
+    match n {
+        n if n >= 1_000_000_000 => format!("{:.1}G", n as f64 / 1e9),
+        n if n >= 1_000_000 => format!("{:.1}M", n as f64 / 1e6),
+        n if n >= 1_000 => format!("{:.1}k", n as f64 / 1e3),
+        n => n.to_string(),
+    }
+}
+
+/// Per-minute rate with one decimal ("4.1/min")
+fn format_per_minute(per_min: f64) -> String {
+    if per_min >= 100.0 {
+        format!("{:.0}/min", per_min)
+    } else {
+        format!("{:.1}/min", per_min)
+    }
+}
+
+/// Format the lag behind the reference tip, e.g. "3 blocks (57s)"
+fn format_network_lag(lag: Option<(u64, u64)>) -> String {
+    match lag {
+        Some((0, _)) => "at tip".to_string(),
+        Some((1, secs)) => format!("1 block ({}s)", secs),
+        Some((blocks, secs)) => format!("{} blocks ({}s)", blocks, secs),
+        None => "—".to_string(),
+    }
+}
+
+/// Format CPU usage in percent of one core
+fn format_cpu(percent: Option<f64>) -> String {
+    match percent {
+        Some(pct) => format!("{:.0}%", pct),
+        None => "—".to_string(),
+    }
+}
+
+/// Format a throughput in bytes/s
+fn format_rate(bytes_per_sec: Option<f64>) -> String {
+    match bytes_per_sec {
+        Some(r) if r >= 1_048_576.0 => format!("{:.1} MB/s", r / 1_048_576.0),
+        Some(r) if r >= 1024.0 => format!("{:.1} KB/s", r / 1024.0),
+        Some(r) => format!("{:.0} B/s", r),
+        None => "—".to_string(),
     }
-    result.chars().rev().collect()
 }
 
-fn format_bytes(bytes: Option<u64>) -> String {
-    match bytes {
-        Some(b) if b >= 1_073_741_824 => format!("{:.2} GB", b as f64 / 1_073_741_824.0),
-        Some(b) if b >= 1_048_576 => format!("{:.2} MB", b as f64 / 1_048_576.0),
-        Some(b) if b >= 1024 => format!("{:.2} KB", b as f64 / 1024.0),
-        Some(b) => format!("{} B", b),
+/// Compact throughput for table columns (bytes/s with K/M suffix)
+fn format_rate_short(bytes_per_sec: Option<f64>) -> String {
+    match bytes_per_sec {
+        Some(r) if r >= 1_048_576.0 => format!("{:.1}M", r / 1_048_576.0),
+        Some(r) if r >= 1024.0 => format!("{:.0}K", r / 1024.0),
+        Some(r) => format!("{:.0}", r),
         None => "—".to_string(),
     }
 }
 
+/// Live stake sparkline and net change over the recorded stake history
+fn format_stake_trend(history: &[StakeSnapshot]) -> Option<String> {
+    let (first, last) = (history.first()?, history.last()?);
+    if history.len() < 2 {
+        return None;
+    }
+    let stakes: Vec<u64> = history.iter().map(|s| s.live_stake).collect();
+    let change = format_ada(Some(last.live_stake.abs_diff(first.live_stake)));
+    let sign = if last.live_stake >= first.live_stake {
+        '+'
+    } else {
+        '-'
+    };
+    let days = last
+        .timestamp
+        .saturating_sub(first.timestamp)
+        .div_ceil(86400)
+        .max(1);
+    Some(format!(
+        "{} {}{} {}d",
+        text_sparkline(&stakes, 8),
+        sign,
+        change,
+        days
+    ))
+}
+
+/// One-line sparkline of up to `width` block characters, scaled from the
+/// smallest to the largest value (the most recent values when there are more)
+fn text_sparkline(values: &[u64], width: usize) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let values = &values[values.len().saturating_sub(width)..];
+    let (Some(min), Some(max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let range = (max - min).max(1) as f64;
+    values
+        .iter()
+        .map(|v| BARS[(((v - min) as f64 / range) * 7.0).round() as usize])
+        .collect()
+}
+
+/// How long a connection has been open, e.g. "42s", "1h 5m" or "≥3d 4h"
+/// when it was already open as tracking started
+fn format_connection_age(age: Option<(std::time::Duration, bool)>) -> String {
+    let Some((age, at_least)) = age else {
+        return "—".to_string();
+    };
+    let age = format_duration(age.as_secs());
+    if at_least {
+        format!("≥{}", age)
+    } else {
+        age
+    }
+}
+
 fn format_uptime(seconds: Option<f64>) -> String {
     match seconds {
         Some(s) => {
@@ -1534,6 +4758,36 @@ fn format_kes_remaining(periods: Option<u64>) -> String {
     }
 }
 
+/// Format a latency duration compactly (e.g. "85ms", "1.2s")
+fn format_latency(d: std::time::Duration) -> String {
+    if d.as_millis() < 1000 {
+        format!("{}ms", d.as_millis())
+    } else {
+        format!("{:.1}s", d.as_secs_f64())
+    }
+}
+
+/// Annotate a time-sensitive value with the scrape delay for remote nodes,
+/// so slow observations aren't mistaken for a slow node
+fn with_measurement_delay(value: String, node: &NodeState) -> String {
+    match node.measurement_delay() {
+        Some(delay) if value != "—" => format!("{} ±{}", value, format_latency(delay)),
+        _ => value,
+    }
+}
+
+/// Format TPS as "current (1h avg / 24h peak)"
+fn format_tps(current: Option<f64>, avg_1h: Option<f64>, peak_24h: Option<f64>) -> String {
+    let fmt = |v: Option<f64>| {
+        v.map(|v| format!("{:.2}", v))
+            .unwrap_or_else(|| "—".to_string())
+    };
+    match current {
+        Some(_) => format!("{} ({}/{})", fmt(current), fmt(avg_1h), fmt(peak_24h)),
+        None => "—".to_string(),
+    }
+}
+
 fn format_tip_age(seconds: Option<u64>) -> String {
     match seconds {
         Some(s) if s < 60 => format!("{}s ago", s),
@@ -1543,6 +4797,17 @@ fn format_tip_age(seconds: Option<u64>) -> String {
     }
 }
 
+/// Format a sampling interval (e.g. "30s", "5m", "1h")
+fn format_interval(seconds: u64) -> String {
+    if seconds >= 3600 && seconds.is_multiple_of(3600) {
+        format!("{}h", seconds / 3600)
+    } else if seconds >= 60 && seconds.is_multiple_of(60) {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
 fn truncate_string(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
@@ -1551,6 +4816,22 @@ fn truncate_string(s: &str, max_len: usize) -> String {
     }
 }
 
+/// Shorten an address to `max_len` by replacing its middle with "…"
+///
+/// Unlike truncating, this keeps the end of an IPv6 address (and the port),
+/// which is what tells peers in the same network apart.
+fn shorten_address(address: &str, max_len: usize) -> String {
+    let len = address.chars().count();
+    if len <= max_len || max_len < 5 {
+        return address.to_string();
+    }
+    let tail = (max_len - 1) / 2;
+    let head = max_len - 1 - tail;
+    let start: String = address.chars().take(head).collect();
+    let end: String = address.chars().skip(len - tail).collect();
+    format!("{}…{}", start, end)
+}
+
 fn format_time_remaining(seconds: u64) -> String {
     let days = seconds / 86400;
     let hours = (seconds % 86400) / 3600;
@@ -1565,6 +4846,63 @@ fn format_time_remaining(seconds: u64) -> String {
     }
 }
 
+/// Countdown to the second: `04:11:09`, or `2d 04:11:09` beyond a day
+fn format_clock_countdown(seconds: u64) -> String {
+    let clock = format!(
+        "{:02}:{:02}:{:02}",
+        (seconds % 86400) / 3600,
+        (seconds % 3600) / 60,
+        seconds % 60
+    );
+    match seconds / 86400 {
+        0 => clock,
+        days => format!("{}d {}", days, clock),
+    }
+}
+
+/// Restarts in the 7 and 30 days before `now` (Unix seconds)
+fn format_restart_counts(node: &NodeState, now: u64) -> String {
+    format!(
+        "{} (7d) · {} (30d)",
+        node.restarts.restarts_since(now.saturating_sub(7 * 86400)),
+        node.restarts.restarts_since(now.saturating_sub(30 * 86400))
+    )
+}
+
+/// One character per day: `·` without restarts, else the count (`+` for ten
+/// or more)
+fn restart_timeline(daily: &[usize]) -> String {
+    daily
+        .iter()
+        .map(|&count| match count {
+            0 => '·',
+            1..=9 => char::from_digit(count as u32, 10).unwrap_or('+'),
+            _ => '+',
+        })
+        .collect()
+}
+
+/// Slot rate with a note when it is off the expected one slot per slot length
+fn format_slot_rate(rate: Option<f64>, ratio: Option<f64>, health: HealthStatus) -> String {
+    let (Some(rate), Some(ratio)) = (rate, ratio) else {
+        return "—".to_string();
+    };
+    match health {
+        HealthStatus::Good => format!("{:.2}/s", rate),
+        HealthStatus::Critical => format!("{:.2}/s (stalled)", rate),
+        HealthStatus::Warning if ratio < 1.0 => format!("{:.2}/s (behind)", rate),
+        HealthStatus::Warning => format!("{:.2}/s (catching up)", rate),
+    }
+}
+
+/// Node-reported density followed by the density observed over the last hour
+fn format_density_with_hour(density: Option<f64>, density_1h: Option<f64>) -> String {
+    match density_1h {
+        Some(hour) => format!("{} (1h {:.4})", format_density(density), hour),
+        None => format_density(density),
+    }
+}
+
 fn format_density(density: Option<f64>) -> String {
     match density {
         Some(d) => format!("{:.4}", d),
@@ -1762,3 +5100,587 @@ fn draw_graphs_view(frame: &mut Frame, area: Rect, app: &App, palette: &Palette)
         .alignment(Alignment::Center);
     frame.render_widget(help_para, chunks[5]);
 }
+
+#[cfg(test)]
+mod tests {
+    //! Snapshot tests: render `draw()` for representative states into a
+    //! `TestBackend` and compare the text with `src/snapshots/<name>.txt`.
+    //! Metrics come from the demo simulation, so every run renders the same
+    //! frame. After an intended layout change, regenerate the snapshots with
+    //! `UPDATE_SNAPSHOTS=1 cargo test ui::tests` and review the diff.
+
+    use super::*;
+    use crate::config::{AppConfig, CliArgs};
+    use crate::demo;
+    use crate::metrics::{parse_prometheus_metrics, FetchError};
+    use crate::sockets::PeerConnection;
+    use clap::Parser;
+    use ratatui::backend::TestBackend;
+    use std::path::PathBuf;
+
+    const WIDTH: u16 = 120;
+    const HEIGHT: u16 = 40;
+    /// Seconds into the demo: one fork recorded, peers not dropped
+    const ELAPSED: u64 = 600;
+
+    /// An app with the named demo nodes, each showing its simulated metrics
+    fn demo_app(names: &[&str]) -> App {
+        crate::storage::set_data_dir(
+            std::env::temp_dir().join(format!("sview-ui-tests-{}", std::process::id())),
+        );
+        let mut file_config = demo::file_config();
+        // The footer clock would differ on every run
+        file_config.global.status_clock = false;
+        file_config
+            .nodes
+            .retain(|n| names.contains(&n.name.as_str()));
+        let hosts: Vec<String> = file_config.nodes.iter().map(|n| n.host.clone()).collect();
+        let mut config =
+            AppConfig::resolve(CliArgs::parse_from(["sview"]), None, file_config).unwrap();
+        // As with --demo, slots follow the demo node rather than mainnet's clock
+        for node in &mut config.nodes {
+            node.network_params.slot_zero_time = None;
+        }
+
+        let mut app = App::new(config);
+        for (node, host) in app.nodes.iter_mut().zip(&hosts) {
+            let profile = demo::profile(host).unwrap();
+            match demo::render(profile, ELAPSED) {
+                Ok(text) => {
+                    node.metrics = parse_prometheus_metrics(&text);
+                    // Derived from the wall clock, so pinned
+                    node.metrics.sync_progress = Some(100.0);
+                }
+                Err(_) => {
+                    node.metrics.connected = false;
+                    node.last_error = Some(FetchError::ConnectionRefused);
+                    node.consecutive_failures = 10;
+                }
+            }
+            node.fetch_count = 1;
+        }
+        app
+    }
+
+    fn render(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).unwrap();
+        terminal.draw(|frame| draw(frame, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                let line: String = (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect();
+                format!("{}\n", line.trim_end())
+            })
+            .collect()
+    }
+
+    fn assert_snapshot(name: &str, app: &App) {
+        let actual = render(app);
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("src/snapshots")
+            .join(format!("{}.txt", name));
+        if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, &actual).unwrap();
+            return;
+        }
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!(
+                "missing snapshot {}; run with UPDATE_SNAPSHOTS=1 to create it",
+                path.display()
+            )
+        });
+        assert!(
+            expected == actual,
+            "{} changed; run with UPDATE_SNAPSHOTS=1 to accept\n--- expected\n{}--- actual\n{}",
+            path.display(),
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_single_node() {
+        assert_snapshot("single_node", &demo_app(&["Demo Relay 1"]));
+    }
+
+    #[test]
+    fn test_multi_node() {
+        let app = demo_app(&["Demo BP", "Demo Relay 1", "Demo Relay 2", "Demo Offline"]);
+        assert_snapshot("multi_node", &app);
+    }
+
+    #[test]
+    fn test_block_producer_kes_warning() {
+        let app = demo_app(&["Demo BP"]);
+        assert_eq!(app.current_node().kes_health(), HealthStatus::Warning);
+        assert_snapshot("bp_kes_warning", &app);
+    }
+
+    #[test]
+    fn test_disconnected_node() {
+        let mut app = demo_app(&["Demo BP", "Demo Offline"]);
+        app.select_node(1);
+        assert_eq!(
+            app.current_node().connection_state(),
+            ConnectionState::Offline
+        );
+        assert_snapshot("disconnected_node", &app);
+    }
+
+    #[test]
+    fn test_peers_view() {
+        let mut app = demo_app(&["Demo Relay 1"]);
+        let peer = |ip: &str, port, incoming, rtt_ms| PeerConnection {
+            ip: ip.to_string(),
+            port,
+            local_port: if incoming { 3001 } else { 40000 + port % 1000 },
+            incoming,
+            rtt_ms,
+            rtt_source: None,
+            rtt_probed: false,
+            state: "ESTAB".to_string(),
+            recv_q: 0,
+            send_q: 0,
+            bytes_sent: Some(120_000_000),
+            bytes_received: Some(450_000_000),
+            send_rate: None,
+            recv_rate: None,
+            peer_state: None,
+        };
+        app.nodes[0].peer_connections = vec![
+            peer("203.0.113.10", 3001, false, Some(12.5)),
+            peer("198.51.100.7", 6000, false, Some(85.0)),
+            peer("192.0.2.44", 51234, true, Some(210.0)),
+            peer("192.0.2.45", 51876, true, None),
+        ];
+        app.mode = AppMode::Peers;
+        assert_snapshot("peers_view", &app);
+    }
+
+    #[test]
+    fn test_overlay_hints_follow_bindings() {
+        use serde::Deserialize;
+        let mut app = demo_app(&["Demo Relay 1"]);
+        app.mode = AppMode::Peers;
+        let out = render(&app);
+        assert!(out.contains("[g] group | [p/Esc] close | [r] refresh"));
+
+        let config: toml::Value = toml::from_str("peers = \"P\"\nrefresh = \"F5\"").unwrap();
+        app.app_config.keys = KeyBindings::deserialize(config).unwrap();
+        let out = render(&app);
+        assert!(out.contains("[g] group | [P/Esc] close | [F5] refresh"));
+
+        let config: toml::Value = toml::from_str("incidents = \"I\"").unwrap();
+        app.app_config.keys = KeyBindings::deserialize(config).unwrap();
+        app.mode = AppMode::Incidents;
+        assert!(render(&app).contains("[I/Esc] close"));
+    }
+
+    #[test]
+    fn test_metric_drill_down() {
+        let mut app = demo_app(&["Demo BP"]);
+        let labels = metric_labels(&app);
+        assert_eq!(labels.first().map(String::as_str), Some("Block Height"));
+        for label in &labels {
+            assert!(
+                crate::glossary::lookup(label).is_some(),
+                "no glossary entry for {}",
+                label
+            );
+        }
+
+        // The first step selects the first (or last) row, then moves and stops at the ends
+        app.select_metric(&labels, -1);
+        assert_eq!(app.selected_metric.as_ref(), labels.last());
+        app.select_metric(&labels, 1);
+        assert_eq!(app.selected_metric.as_ref(), labels.last());
+        app.clear_metric_selection();
+        app.select_metric(&labels, 1);
+        app.select_metric(&labels, 1);
+        assert_eq!(app.selected_metric.as_deref(), Some("Tip Age"));
+
+        while app.selected_metric.as_deref() != Some("Density") {
+            app.select_metric(&labels, 1);
+        }
+        app.toggle_metric_info();
+        assert_eq!(app.mode, AppMode::MetricInfo);
+        assert_snapshot("metric_info", &app);
+        app.clear_metric_selection();
+        assert_eq!(app.mode, AppMode::Normal);
+    }
+
+    #[test]
+    fn test_glossary_covers_rows() {
+        use crate::chain_api::PoolInfo;
+        use crate::cncli::CncliSnapshot;
+        use crate::metrics::{ChurnCounts, PeerGroupCounts};
+        use crate::pooltool::{PooltoolClient, PooltoolConfig};
+        use crate::reference_tip::ReferenceTip;
+        use crate::storage::StakeSnapshot;
+
+        // Every conditional row switched on, then the alternatives
+        // shown when their data is missing
+        let mut app = demo_app(&["Demo BP", "Demo Relay 1"]);
+        for node in &mut app.nodes {
+            let metrics = &mut node.metrics;
+            metrics.kes_remaining = Some(40);
+            metrics.op_cert_counter_disk = Some(3);
+            metrics.op_cert_counter_chain = Some(3);
+            metrics.forging_enabled = Some(true);
+            metrics.blocks_adopted = Some(12);
+            metrics.missed_slots = Some(1);
+            metrics.prunable_connections = Some(2);
+            metrics.connection_errors = Some(7);
+            metrics.handshake_failures = Some(4);
+            metrics.headers_served = Some(100);
+            metrics.p2p.targets.active = Some(20);
+            let governance = &mut metrics.p2p.governance;
+            governance.shared = PeerGroupCounts {
+                known: Some(34),
+                ..Default::default()
+            };
+            governance.sharing_requested = Some(20);
+            governance.sharing_received = Some(17);
+            let churn = ChurnCounts {
+                increased: Some(2),
+                decreased: Some(1),
+            };
+            governance.churn_known = churn;
+            governance.churn_established = churn;
+            governance.churn_active = churn;
+            node.reference_tip = Some(ReferenceTip {
+                block_height: 1,
+                slot: 1,
+            });
+            node.pool_info = Some(PoolInfo::default());
+            node.stake_history = [1, 2]
+                .map(|timestamp| StakeSnapshot {
+                    timestamp,
+                    live_stake: 1_000_000,
+                    active_stake: None,
+                    live_delegators: None,
+                })
+                .to_vec();
+            node.pooltool = PooltoolClient::new(&PooltoolConfig {
+                api_key: "key".to_string(),
+                pool_id: "pool".to_string(),
+                node_id: String::new(),
+            });
+            node.cncli = Some(CncliSnapshot {
+                epoch: 500,
+                pool_id: "pool".to_string(),
+                slots: Vec::new(),
+                tip: Some((1, 1)),
+            });
+            node.restarts.observe(1_700_000_000);
+        }
+
+        let fallback = &mut app.nodes[1];
+        fallback.metrics.op_cert_counter_disk = None;
+        fallback.opcert_error = Some("no opcert".to_string());
+        fallback.cncli = None;
+        fallback.cncli_error = Some("no database".to_string());
+        fallback.metrics.p2p.targets = Default::default();
+
+        let palette = app.theme.palette();
+        let mut labels = Vec::new();
+        for node in &app.nodes {
+            labels.extend(
+                chain_metric_rows(node, true, &palette)
+                    .into_iter()
+                    .chain(network_metric_rows(node, &palette))
+                    .chain(resource_metric_rows(node, &palette))
+                    .chain(governance_rows(node, &palette))
+                    .map(|row| row.label),
+            );
+        }
+        for label in [
+            "Network Lag",
+            "Stake Trend",
+            "Pooltool",
+            "Missed Slots",
+            "Next Slot",
+            "cncli",
+            "Handshake Fails",
+            "Hot Peers",
+            "Peer Dist",
+            "cncli Tip",
+            "Restarts",
+            "Shared Peers",
+            "Active Churn",
+        ] {
+            assert!(labels.iter().any(|l| l == label), "{} not rendered", label);
+        }
+        for label in &labels {
+            assert!(
+                crate::glossary::lookup(label).is_some(),
+                "no glossary entry for {}",
+                label
+            );
+        }
+    }
+
+    #[test]
+    fn test_setup_view() {
+        let mut app = demo_app(&["Demo Offline"]);
+        app.open_setup();
+        for c in "relay1".chars() {
+            app.node_form.input(c);
+        }
+        app.node_form.error = Some("connection refused — Enter again to save anyway".into());
+        assert_snapshot("setup_view", &app);
+    }
+
+    #[test]
+    fn test_stake_trend() {
+        assert_eq!(text_sparkline(&[1, 2, 3, 4, 5, 6, 7, 8], 8), "▁▂▃▄▅▆▇█");
+        assert_eq!(text_sparkline(&[0, 9, 5, 5], 2), "▁▁");
+        assert_eq!(text_sparkline(&[], 8), "");
+
+        let ada = 1_000_000;
+        let snapshot = |day: u64, stake: u64| StakeSnapshot {
+            timestamp: 1_700_000_000 + day * 86400,
+            live_stake: stake * ada,
+            active_stake: None,
+            live_delegators: None,
+        };
+        assert_eq!(format_stake_trend(&[snapshot(0, 10_000_000)]), None);
+        let history = [
+            snapshot(0, 10_000_000),
+            snapshot(3, 12_000_000),
+            snapshot(6, 9_500_000),
+        ];
+        assert_eq!(format_stake_trend(&history).unwrap(), "▂█▁ -500.0K ₳ 6d");
+    }
+
+    #[test]
+    fn test_format_refresh_indicator() {
+        assert_eq!(
+            format_refresh_indicator(std::time::Duration::from_millis(50), (0, 0)),
+            " — ⠋ scanning"
+        );
+        assert_eq!(
+            format_refresh_indicator(std::time::Duration::from_millis(1250), (12, 40)),
+            " — ⠹ probing 12/40"
+        );
+    }
+
+    #[test]
+    fn test_status_ticker() {
+        let mut app = demo_app(&["Demo Relay 1"]);
+        app.app_config.timezone = crate::timezone::DisplayTimezone::Utc;
+        let node = app.current_node();
+        let slot = node.metrics.slot_num.unwrap();
+        let epoch = node.metrics.epoch.unwrap();
+        let remaining = node.config.epoch_length - node.metrics.slot_in_epoch.unwrap();
+        assert_eq!(
+            status_ticker(&app, 1705276807),
+            format!(
+                " 00:00:07 │ Slot {} │ E{} in {} ",
+                format_number(slot),
+                epoch + 1,
+                format_clock_countdown(remaining)
+            )
+        );
+
+        assert_eq!(format_clock_countdown(59), "00:00:59");
+        assert_eq!(format_clock_countdown(4 * 3600 + 11 * 60 + 9), "04:11:09");
+        assert_eq!(format_clock_countdown(2 * 86400 + 3600), "2d 01:00:00");
+    }
+
+    #[test]
+    fn test_leader_slot_title() {
+        use crate::cncli::LeaderWatch;
+
+        let mut app = demo_app(&["Demo BP"]);
+        let palette = app.theme.palette();
+        let node = &mut app.nodes[0];
+        assert!(leader_slot_title(node, &palette).is_none());
+
+        let slot = node.metrics.slot_num.unwrap();
+        let title = |node: &NodeState| leader_slot_title(node, &palette).unwrap().to_string();
+        node.leader_watch = Some(LeaderWatch::new(slot + 3725, Some(3), None));
+        assert_eq!(
+            title(node),
+            format!(" ⏱ Next leader slot in 01:02:05 (slot {}) ", slot + 3725)
+        );
+
+        let mut watch = LeaderWatch::new(slot, Some(3), None);
+        node.leader_watch = Some(watch.clone());
+        assert_eq!(title(node), format!(" ⚒ FORGING WINDOW — slot {} ", slot));
+
+        watch.observe(slot + 2, Some(4), None);
+        node.leader_watch = Some(watch);
+        assert_eq!(title(node), format!(" ✓ Block adopted — slot {} ", slot));
+    }
+
+    #[test]
+    fn test_fleet_worst_lag() {
+        let mut app = demo_app(&["Demo Relay 1", "Demo Relay 2"]);
+        app.nodes[0].metrics.block_height = Some(10_500_000);
+        app.nodes[1].metrics.block_height = Some(10_499_990);
+        assert_eq!(
+            app.fleet_summary().worst_lag,
+            Some(("Demo Relay 2".to_string(), 10))
+        );
+
+        // A node on another network isn't behind mainnet's tip
+        app.nodes[1].config.network = "preprod".to_string();
+        app.nodes[1].metrics.block_height = Some(3_000_000);
+        assert_eq!(app.fleet_summary().worst_lag, None);
+    }
+
+    #[test]
+    fn test_refresh_override_survives_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let mut app = demo_app(&["Demo Relay 1"]);
+        app.app_config.config_path = Some(path.clone());
+
+        app.command_input = "refresh 7".to_string();
+        app.run_command();
+        std::fs::write(
+            &path,
+            "[[nodes]]\nname = \"Relay\"\n\n[[nodes]]\nname = \"BP\"\n",
+        )
+        .unwrap();
+        app.reload_config();
+        assert_eq!(app.nodes.len(), 2);
+        assert_eq!(
+            app.app_config.refresh_interval,
+            std::time::Duration::from_secs(7)
+        );
+        assert!(app
+            .nodes
+            .iter()
+            .all(|n| n.config.refresh_interval_secs == 7));
+    }
+
+    #[test]
+    fn test_network_lag_row() {
+        use crate::reference_tip::ReferenceTip;
+
+        let mut app = demo_app(&["Demo Relay 1"]);
+        assert!(!render(&app).contains("Network Lag"));
+
+        let node = &mut app.nodes[0];
+        node.reference_tip = Some(ReferenceTip {
+            block_height: node.metrics.block_height.unwrap() + 3,
+            slot: node.metrics.slot_num.unwrap() + 60,
+        });
+        assert_eq!(node.network_lag(), Some((3, 60)));
+        assert_eq!(node.network_lag_health(), HealthStatus::Warning);
+        assert!(render(&app).contains("Network Lag         3 blocks (60s)"));
+
+        assert_eq!(format_network_lag(Some((0, 0))), "at tip");
+        assert_eq!(format_network_lag(Some((1, 18))), "1 block (18s)");
+    }
+
+    #[test]
+    fn test_restart_history() {
+        let mut app = demo_app(&["Demo Relay 1"]);
+        assert!(!render(&app).contains("Restarts"));
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let node = &mut app.nodes[0];
+        node.restarts.observe(now - 10 * 86400);
+        node.restarts.observe(now - 86400 - 3600);
+        assert!(render(&app).contains("Restarts             1 (7d) · 1 (30d)"));
+
+        app.toggle_incidents();
+        let text = render(&app);
+        assert!(text.contains("longest run 8d 23h 0m"));
+        assert!(text.contains("(restart after 8d 23h 0m)"));
+        assert!(text.contains("30d ago ····························1· today"));
+
+        assert_eq!(restart_timeline(&[0, 1, 0, 12]), "·1·+");
+        // Counted from the time passed in, not the clock
+        assert_eq!(
+            format_restart_counts(&app.nodes[0], now + 6 * 86400),
+            "0 (7d) · 1 (30d)"
+        );
+    }
+
+    #[test]
+    fn test_format_connection_age() {
+        let age = |secs, at_least| Some((std::time::Duration::from_secs(secs), at_least));
+        assert_eq!(format_connection_age(None), "—");
+        assert_eq!(format_connection_age(age(42, false)), "42s");
+        assert_eq!(format_connection_age(age(612, false)), "10m 12s");
+        assert_eq!(format_connection_age(age(3900, false)), "1h 5m");
+        assert_eq!(format_connection_age(age(273_600, true)), "≥3d 4h");
+    }
+
+    #[test]
+    fn test_shorten_address() {
+        assert_eq!(shorten_address("192.0.2.10", 15), "192.0.2.10");
+        let ipv6 = "2001:db8:1234:5678:9abc:def0:1234:5678";
+        assert_eq!(shorten_address(ipv6, 39), ipv6);
+        let short = shorten_address(ipv6, 21);
+        assert_eq!(short, "2001:db8:1…:1234:5678");
+        assert_eq!(short.chars().count(), 21);
+        // Propagation view keeps the port
+        assert!(shorten_address(&format!("[{}]:3001", ipv6), 22).ends_with("5678]:3001"));
+    }
+
+    #[test]
+    fn test_histogram_bar() {
+        assert_eq!(histogram_bar(0, 0, 10), "");
+        assert_eq!(histogram_bar(10, 10, 10), "██████████");
+        assert_eq!(histogram_bar(5, 10, 10), "█████");
+        assert_eq!(histogram_bar(1, 100, 10), "█");
+        assert_eq!(histogram_bar(0, 100, 10), "");
+    }
+
+    #[test]
+    fn test_format_diversity() {
+        assert_eq!(
+            format_diversity(&NetworkDiversity::default()),
+            "no connections"
+        );
+        let diversity = NetworkDiversity {
+            connections: 22,
+            resolved: 19,
+            networks: 9,
+            top: vec![
+                ("AS24940 Hetzner".to_string(), 12),
+                ("AS16509 Amazon".to_string(), 3),
+            ],
+        };
+        assert_eq!(
+            format_diversity(&diversity),
+            "22 connections, 9 networks — AS24940 Hetzner 54% · AS16509 Amazon 13% (3 unresolved)"
+        );
+    }
+
+    #[test]
+    fn test_format_peer_group() {
+        assert_eq!(format_peer_group(&PeerGroupCounts::default()), "—");
+        let group = PeerGroupCounts {
+            known: Some(34),
+            established: Some(6),
+            active: None,
+        };
+        assert_eq!(format_peer_group(&group), "34 known · 6 est");
+        let churn = ChurnCounts {
+            increased: Some(10),
+            decreased: None,
+        };
+        assert_eq!(format_churn(&churn), "+10 / -0");
+    }
+
+    #[test]
+    fn test_ros_stats() {
+        assert_eq!(ros_stats(&[]), None);
+        let (min, max, avg) = ros_stats(&[3.0, 4.5, 6.0]).unwrap();
+        assert_eq!((min, max, avg), (3.0, 6.0, 4.5));
+        assert_eq!(format_ros(Some(4.745)), "4.75%");
+        assert_eq!(format_ros(None), "—");
+    }
+}
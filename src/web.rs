@@ -0,0 +1,862 @@
+//! Read-only web dashboard (`sview --web 127.0.0.1:8080`)
+//!
+//! While the TUI runs, a minimal HTTP server serves one HTML page with each
+//! node's health, gauges, key metrics, peers and the alerts of the last day,
+//! so the rest of the team can glance at the fleet from a browser or phone.
+//! The page is rendered from the app state after every refresh and reloads
+//! itself at the refresh interval; there are no scripts, forms or actions.
+//! It has no authentication, so bind it to localhost or a trusted network.
+//!
+//! `/ws` is a WebSocket feed of the same data for external dashboards and
+//! bots: after every refresh one `{"type": "node", ...}` message per node
+//! (the fields of `sview status --json` plus a timestamp), and one
+//! `{"type": "alert", ...}` message per alert as it is raised. New
+//! subscribers first get the latest `node` message of every node.
+//!
+//! `/state` is every node's latest scrape as JSON, read by followers
+//! (`sview --follow`, see `follow`). Nothing here is authenticated: whoever
+//! reaches the address reads every node's metrics and scrape errors.
+
+use crate::alerts::{Alert, AlertSeverity};
+use crate::app::{App, HealthStatus, NodeState};
+use crate::follow::SharedState;
+use crate::format::{format_bytes, format_number};
+use crate::status::{health_label, StatusRow};
+use anyhow::{Context, Result};
+use base64::Engine;
+use serde::Serialize;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc};
+use tracing::debug;
+
+/// Largest request head read before the connection is dropped
+const MAX_REQUEST_BYTES: usize = 8192;
+
+/// Longest a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Pause after a failed accept (e.g. out of file descriptors) so the
+/// listener doesn't spin
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Peers listed per node
+const MAX_PEERS: usize = 15;
+
+/// How far back the alert list reaches
+const ALERT_WINDOW_SECS: u64 = 86400;
+
+/// Feed events buffered per subscriber before a slow one starts missing them
+const FEED_CAPACITY: usize = 256;
+
+/// Largest frame accepted from a feed client (they only send pings and closes)
+const MAX_CLIENT_FRAME_BYTES: u64 = 4096;
+
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xa;
+/// Close status for a frame that breaks the protocol
+const CLOSE_PROTOCOL_ERROR: u16 = 1002;
+
+/// The running web server; the page it serves is replaced with `update`
+pub struct WebServer {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+}
+
+/// State read by the connection tasks
+struct Shared {
+    page: RwLock<String>,
+    /// Every node's latest state as JSON, for `sview --follow`
+    state: RwLock<String>,
+    /// Latest `node` event of every node, sent to new feed subscribers
+    nodes: RwLock<Vec<String>>,
+    /// Events for `/ws` subscribers
+    events: broadcast::Sender<String>,
+}
+
+/// One message of the `/ws` feed
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum FeedEvent<'a> {
+    /// A node's state after a refresh
+    Node {
+        timestamp: u64,
+        #[serde(flatten)]
+        status: &'a StatusRow,
+    },
+    /// A newly raised alert
+    Alert(&'a Alert),
+}
+
+impl WebServer {
+    /// Listen on `addr` and serve in the background
+    pub fn bind(addr: SocketAddr) -> Result<Self> {
+        let listener = std::net::TcpListener::bind(addr)
+            .with_context(|| format!("Failed to listen on {} for --web", addr))?;
+        listener.set_nonblocking(true)?;
+        let listener = tokio::net::TcpListener::from_std(listener)?;
+        let addr = listener.local_addr()?;
+        let shared = Arc::new(Shared {
+            page: RwLock::new(String::from("Starting...")),
+            state: RwLock::new(String::new()),
+            nodes: RwLock::new(Vec::new()),
+            events: broadcast::channel(FEED_CAPACITY).0,
+        });
+
+        let server = Arc::clone(&shared);
+        tokio::spawn(async move {
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        debug!("Web accept failed: {}", e);
+                        tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                        continue;
+                    }
+                };
+                let shared = Arc::clone(&server);
+                tokio::spawn(async move {
+                    if let Err(e) = serve(stream, &shared).await {
+                        debug!("Web request from {} failed: {}", peer, e);
+                    }
+                });
+            }
+        });
+        Ok(Self { addr, shared })
+    }
+
+    /// Address the server listens on
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Re-render the page and send every node's state to feed subscribers
+    pub fn update(&self, app: &App) {
+        let html = render(app);
+        if let Ok(mut page) = self.shared.page.write() {
+            *page = html;
+        }
+        if let Ok(json) = serde_json::to_string(&SharedState::from_app(app)) {
+            if let Ok(mut state) = self.shared.state.write() {
+                *state = json;
+            }
+        }
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let nodes: Vec<String> = app
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let (status, _) = StatusRow::from_node(node);
+                serde_json::to_string(&FeedEvent::Node {
+                    timestamp,
+                    status: &status,
+                })
+                .ok()
+            })
+            .collect();
+        for event in &nodes {
+            // Fails only when nobody is subscribed
+            let _ = self.shared.events.send(event.clone());
+        }
+        if let Ok(mut latest) = self.shared.nodes.write() {
+            *latest = nodes;
+        }
+    }
+
+    /// Send a new alert to feed subscribers
+    pub fn publish_alert(&self, alert: &Alert) {
+        if let Ok(event) = serde_json::to_string(&FeedEvent::Alert(alert)) {
+            let _ = self.shared.events.send(event);
+        }
+    }
+}
+
+/// Answer one request; `/ws` connections stay open for the feed
+async fn serve(mut stream: TcpStream, shared: &Shared) -> std::io::Result<()> {
+    let Some(request) = read_request(&mut stream).await? else {
+        return Ok(());
+    };
+    let (method, path) = (request.method.as_str(), request.path.as_str());
+
+    let reply = match (method, path) {
+        ("GET" | "HEAD", "/") => {
+            let html = shared.page.read().map(|p| p.clone()).unwrap_or_default();
+            let mut reply = response(200, "text/html; charset=utf-8", &html);
+            if method == "HEAD" {
+                let end = reply.windows(4).position(|w| w == b"\r\n\r\n").unwrap_or(0);
+                reply.truncate(end + 4);
+            }
+            reply
+        }
+        ("GET", "/state") => {
+            let json = shared.state.read().map(|s| s.clone()).unwrap_or_default();
+            response(200, "application/json", &json)
+        }
+        ("GET", "/ws") => {
+            let upgrade = request
+                .header("Upgrade")
+                .is_some_and(|u| u.eq_ignore_ascii_case("websocket"));
+            match (upgrade, request.header("Sec-WebSocket-Key")) {
+                _ if !same_origin(&request) => response(403, "text/plain", "Cross-origin"),
+                (true, Some(key)) => return feed(stream, shared, key).await,
+                _ => response(400, "text/plain", "WebSocket upgrade required"),
+            }
+        }
+        ("GET" | "HEAD", _) => response(404, "text/plain", "Not found"),
+        _ => response(405, "text/plain", "Read-only"),
+    };
+    stream.write_all(&reply).await?;
+    stream.shutdown().await
+}
+
+/// Whether a browser's `Origin` is this server, so pages elsewhere can't
+/// subscribe with the operator's network access; clients that send no
+/// `Origin` aren't browsers and are let through
+fn same_origin(request: &Request) -> bool {
+    let Some(origin) = request.header("Origin") else {
+        return true;
+    };
+    let origin_host = origin
+        .split_once("://")
+        .map_or(origin, |(_, rest)| rest)
+        .trim_end_matches('/');
+    request
+        .header("Host")
+        .is_some_and(|host| host.eq_ignore_ascii_case(origin_host))
+}
+
+/// Complete the WebSocket handshake and stream events until the client leaves
+async fn feed(stream: TcpStream, shared: &Shared, key: &str) -> std::io::Result<()> {
+    let mut events = shared.events.subscribe();
+    let latest = shared.nodes.read().map(|n| n.clone()).unwrap_or_default();
+    let (mut reader, mut writer) = stream.into_split();
+    writer
+        .write_all(
+            format!(
+                "HTTP/1.1 101 Switching Protocols\r\n\
+                 Upgrade: websocket\r\n\
+                 Connection: Upgrade\r\n\
+                 Sec-WebSocket-Accept: {}\r\n\r\n",
+                accept_key(key)
+            )
+            .as_bytes(),
+        )
+        .await?;
+    for event in latest {
+        writer
+            .write_all(&frame(OPCODE_TEXT, event.as_bytes()))
+            .await?;
+    }
+
+    // The reader answers pings and closes, and closes with a protocol error
+    // on a frame breaking the rules; the channel closes when it stops
+    let (control, mut replies) = mpsc::channel::<(u8, Vec<u8>)>(4);
+    tokio::spawn(async move {
+        loop {
+            let (opcode, payload) = match read_frame(&mut reader, true).await {
+                Ok(frame) => frame,
+                Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+                    let _ = control
+                        .send((OPCODE_CLOSE, CLOSE_PROTOCOL_ERROR.to_be_bytes().to_vec()))
+                        .await;
+                    break;
+                }
+                Err(_) => break,
+            };
+            let reply = match opcode {
+                OPCODE_PING => (OPCODE_PONG, payload),
+                OPCODE_CLOSE => (OPCODE_CLOSE, payload),
+                _ => continue,
+            };
+            if control.send(reply).await.is_err() || opcode == OPCODE_CLOSE {
+                break;
+            }
+        }
+    });
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => writer.write_all(&frame(OPCODE_TEXT, event.as_bytes())).await?,
+                // A slow client misses events rather than holding up the rest
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            reply = replies.recv() => match reply {
+                Some((OPCODE_CLOSE, payload)) => {
+                    writer.write_all(&frame(OPCODE_CLOSE, &payload)).await?;
+                    break;
+                }
+                Some((opcode, payload)) => writer.write_all(&frame(opcode, &payload)).await?,
+                None => break,
+            },
+        }
+    }
+    writer.shutdown().await
+}
+
+/// `Sec-WebSocket-Accept` for a client key (RFC 6455, section 4.2.2)
+fn accept_key(key: &str) -> String {
+    let mut context = ring::digest::Context::new(&ring::digest::SHA1_FOR_LEGACY_USE_ONLY);
+    context.update(key.as_bytes());
+    context.update(WEBSOCKET_GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(context.finish())
+}
+
+/// An unmasked server frame
+fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 10);
+    out.push(0x80 | opcode);
+    match payload.len() {
+        len if len < 126 => out.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            out.push(126);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        len => {
+            out.push(127);
+            out.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(payload);
+    out
+}
+
+/// Read one frame, returning its opcode and unmasked payload
+///
+/// Client frames must be masked (RFC 6455, section 5.1): with `client`, an
+/// unmasked frame is an `InvalidData` error, like an oversized one.
+async fn read_frame<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    client: bool,
+) -> std::io::Result<(u8, Vec<u8>)> {
+    let mut header = [0u8; 2];
+    reader.read_exact(&mut header).await?;
+    let opcode = header[0] & 0x0f;
+    let masked = header[1] & 0x80 != 0;
+    if client && !masked {
+        return Err(std::io::ErrorKind::InvalidData.into());
+    }
+    let len = match header[1] & 0x7f {
+        126 => {
+            let mut len = [0u8; 2];
+            reader.read_exact(&mut len).await?;
+            u16::from_be_bytes(len) as u64
+        }
+        127 => {
+            let mut len = [0u8; 8];
+            reader.read_exact(&mut len).await?;
+            u64::from_be_bytes(len)
+        }
+        len => len as u64,
+    };
+    if len > MAX_CLIENT_FRAME_BYTES {
+        return Err(std::io::ErrorKind::InvalidData.into());
+    }
+    let mut mask = [0u8; 4];
+    if masked {
+        reader.read_exact(&mut mask).await?;
+    }
+    let mut payload = vec![0u8; len as usize];
+    reader.read_exact(&mut payload).await?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((opcode, payload))
+}
+
+/// Request line and headers of an HTTP request
+pub struct Request {
+    pub method: String,
+    /// Path without the query string
+    pub path: String,
+    head: String,
+}
+
+impl Request {
+    /// Value of a header (case-insensitive name)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim().eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+}
+
+/// Read a request head; `None` if the client hung up before sending one
+pub async fn read_request<S: AsyncRead + Unpin>(
+    stream: &mut S,
+) -> std::io::Result<Option<Request>> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_BYTES {
+            return Err(std::io::ErrorKind::InvalidData.into());
+        }
+        let n = tokio::time::timeout(READ_TIMEOUT, stream.read(&mut buf))
+            .await
+            .map_err(|_| std::io::ErrorKind::TimedOut)??;
+        if n == 0 {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let head = String::from_utf8_lossy(&head).into_owned();
+    let mut request_line = head.lines().next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default().to_string();
+    Ok(Some(Request { method, path, head }))
+}
+
+/// A complete response that closes the connection
+pub fn response(status: u16, content_type: &str, body: &str) -> Vec<u8> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    format!(
+        "HTTP/1.1 {} {}\r\n\
+         Content-Type: {}\r\n\
+         Content-Length: {}\r\n\
+         Cache-Control: no-store\r\n\
+         X-Content-Type-Options: nosniff\r\n\
+         Content-Security-Policy: default-src 'none'; style-src 'unsafe-inline'\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        reason,
+        content_type,
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
+
+/// The dashboard page for the current app state
+pub fn render(app: &App) -> String {
+    let fleet = app.fleet_summary();
+    let refresh = app.app_config.refresh_interval.as_secs().max(1);
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <meta http-equiv=\"refresh\" content=\"{}\">\n<title>sview: {}/{} online</title>\n\
+         <style>{}</style>\n</head>\n<body>\n",
+        refresh, fleet.online, fleet.total, STYLE
+    );
+    let _ = writeln!(
+        html,
+        "<header><h1>sview</h1><span>{}/{} nodes online · tip {} · updated {}</span></header>",
+        fleet.online,
+        fleet.total,
+        fleet
+            .max_tip
+            .map(format_number)
+            .unwrap_or_else(|| "—".to_string()),
+        escape(&app.app_config.timezone.clock(now))
+    );
+
+    html.push_str("<main>\n");
+    for node in &app.nodes {
+        render_node(&mut html, node);
+    }
+    html.push_str("</main>\n");
+
+    let mut alerts: Vec<_> = app
+        .nodes
+        .iter()
+        .flat_map(|n| {
+            n.alert_manager
+                .alerts_since(now.saturating_sub(ALERT_WINDOW_SECS))
+        })
+        .collect();
+    alerts.sort_by_key(|a| std::cmp::Reverse(a.timestamp));
+    html.push_str("<section><h2>Alerts (last 24 hours)</h2>\n");
+    if alerts.is_empty() {
+        html.push_str("<p class=\"muted\">No alerts.</p>\n");
+    } else {
+        html.push_str("<table>\n");
+        for alert in alerts {
+            let class = match alert.severity {
+                AlertSeverity::Info => "ok",
+                AlertSeverity::Warning => "warning",
+                AlertSeverity::Critical => "critical",
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td><b>{}</b> {}</td></tr>",
+                escape(&app.app_config.timezone.datetime(alert.timestamp)),
+                class,
+                alert.severity,
+                escape(&alert.node_name),
+                escape(&alert.title),
+                escape(&alert.message)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</section>\n</body>\n</html>\n");
+    html
+}
+
+fn render_node(html: &mut String, node: &NodeState) {
+    let m = &node.metrics;
+    let health = node.overall_health();
+    let _ = writeln!(
+        html,
+        "<article>\n<h2><span class=\"dot {}\"></span>{} <small>{} · {} · {}</small></h2>",
+        health_label(health),
+        escape(&node.config.node_name),
+        node.role,
+        escape(&node.config.network),
+        escape(node.status_text())
+    );
+    if let Some(error) = node.error_text() {
+        let _ = writeln!(html, "<p class=\"critical\">{}</p>", escape(&error));
+    }
+
+    gauge(
+        html,
+        "Sync",
+        m.sync_progress,
+        format!("{:.2}%", m.sync_progress.unwrap_or(0.0)),
+        node.sync_health(),
+    );
+    gauge(
+        html,
+        "Epoch",
+        node.epoch_progress(),
+        match m.epoch {
+            Some(epoch) => format!("{} · {:.1}%", epoch, node.epoch_progress().unwrap_or(0.0)),
+            None => "—".to_string(),
+        },
+        HealthStatus::Good,
+    );
+    let memory_ratio = match (m.memory_used, m.memory_heap) {
+        (Some(used), Some(heap)) if heap > 0 => {
+            Some((used as f64 / heap as f64 * 100.0).min(100.0))
+        }
+        _ => None,
+    };
+    gauge(
+        html,
+        "Memory",
+        memory_ratio,
+        format_bytes(m.memory_used),
+        node.memory_health(),
+    );
+
+    html.push_str("<table>\n");
+    let mut row = |label: &str, value: Option<String>| {
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            label,
+            escape(&value.unwrap_or_else(|| "—".to_string()))
+        );
+    };
+    row("Block", m.block_height.map(format_number));
+    row("Slot", m.slot_num.map(format_number));
+    row("Tip age", node.tip_age_secs().map(|s| format!("{}s", s)));
+    row(
+        "Peers",
+        m.peers_connected
+            .map(|p| match (m.p2p.hot_peers, m.p2p.warm_peers) {
+                (Some(hot), Some(warm)) => format!("{} (hot {}, warm {})", p, hot, warm),
+                _ => p.to_string(),
+            }),
+    );
+    row("Incoming", m.incoming_connections.map(|c| c.to_string()));
+    row(
+        "KES left",
+        m.kes_remaining.map(|k| format!("{} periods", k)),
+    );
+    row("Version", node.node_version().map(String::from));
+    html.push_str("</table>\n");
+
+    if !node.peer_connections.is_empty() {
+        let _ = writeln!(
+            html,
+            "<details><summary>{} peer connections</summary>\n<table>",
+            node.peer_connections.len()
+        );
+        for peer in node.peer_connections.iter().take(MAX_PEERS) {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}:{}</td><td>{}</td><td>{}</td></tr>",
+                escape(&peer.ip),
+                peer.port,
+                if peer.incoming { "in" } else { "out" },
+                peer.rtt_ms
+                    .map(|r| format!("{:.0} ms", r))
+                    .unwrap_or_else(|| "—".to_string())
+            );
+        }
+        html.push_str("</table></details>\n");
+    }
+    html.push_str("</article>\n");
+}
+
+/// A labelled bar; `percent` is `None` when there is nothing to show
+fn gauge(html: &mut String, label: &str, percent: Option<f64>, text: String, health: HealthStatus) {
+    let (width, text) = match percent {
+        Some(p) => (p.clamp(0.0, 100.0), text),
+        None => (0.0, "—".to_string()),
+    };
+    let _ = writeln!(
+        html,
+        "<div class=\"gauge\"><span>{}</span><div class=\"bar\"><div class=\"{}\" style=\"width:{:.1}%\"></div></div><span>{}</span></div>",
+        label,
+        health_label(health),
+        width,
+        escape(&text)
+    );
+}
+
+/// Escape text for HTML
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+const STYLE: &str = "\
+body{font-family:system-ui,sans-serif;background:#111;color:#ddd;margin:0 auto;max-width:1100px;padding:1em}\
+header{display:flex;gap:1em;align-items:baseline}h1{margin:0}\
+main{display:grid;grid-template-columns:repeat(auto-fill,minmax(320px,1fr));gap:1em;margin:1em 0}\
+article,section{background:#1b1b1b;border:1px solid #333;border-radius:6px;padding:.8em}\
+h2{font-size:1.1em;margin:0 0 .6em}small,.muted{color:#888;font-weight:normal}\
+table{border-collapse:collapse;width:100%}th{text-align:left;font-weight:normal;color:#888}\
+td,th{padding:.15em .4em;vertical-align:top}\
+.gauge{display:grid;grid-template-columns:4.5em 1fr 7em;gap:.5em;align-items:center;margin:.3em 0}\
+.bar{background:#333;height:.7em;border-radius:3px}.bar div{height:100%;border-radius:3px}\
+.dot{display:inline-block;width:.7em;height:.7em;border-radius:50%;margin-right:.4em}\
+.bar .ok,.dot.ok{background:#3a3}.bar .warning,.dot.warning{background:#d90}.bar .critical,.dot.critical{background:#d33}\
+td.ok{color:#3a3}td.warning,p.warning{color:#d90}td.critical,p.critical{color:#d33}\
+details{margin-top:.5em}summary{cursor:pointer;color:#888}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AppConfig, CliArgs};
+    use crate::demo;
+    use crate::metrics::{parse_prometheus_metrics, FetchError};
+    use clap::Parser;
+
+    fn demo_app() -> App {
+        crate::storage::set_data_dir(
+            std::env::temp_dir().join(format!("sview-web-tests-{}", std::process::id())),
+        );
+        let file_config = demo::file_config();
+        let hosts: Vec<String> = file_config.nodes.iter().map(|n| n.host.clone()).collect();
+        let config = AppConfig::resolve(CliArgs::parse_from(["sview"]), None, file_config).unwrap();
+        let mut app = App::new(config);
+        for (node, host) in app.nodes.iter_mut().zip(&hosts) {
+            match demo::render(demo::profile(host).unwrap(), 600) {
+                Ok(text) => node.metrics = parse_prometheus_metrics(&text),
+                Err(_) => {
+                    node.metrics.connected = false;
+                    node.last_error = Some(FetchError::ConnectionRefused);
+                }
+            }
+        }
+        app
+    }
+
+    #[test]
+    fn test_render() {
+        let html = render(&demo_app());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<meta http-equiv=\"refresh\" content=\"2\">"));
+        assert!(html.contains("3/4 nodes online"));
+        assert!(html.contains("Demo BP <small>BP · mainnet · Connected</small>"));
+        assert!(html.contains("<tr><th>Block</th><td>11,200,030</td></tr>"));
+        assert!(html.contains("<tr><th>KES left</th><td>7 periods</td></tr>"));
+        assert!(html.contains("Demo Offline <small>Relay · mainnet"));
+        assert!(html.contains("No alerts."));
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<b>\"Relay\" & 'BP'</b>"),
+            "&lt;b&gt;&quot;Relay&quot; &amp; &#39;BP&#39;&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn test_accept_key() {
+        // Example from RFC 6455
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+        assert_eq!(frame(OPCODE_TEXT, b"hi"), vec![0x81, 2, b'h', b'i']);
+        assert_eq!(&frame(OPCODE_TEXT, &[0; 300])[..4], &[0x81, 126, 1, 44]);
+    }
+
+    #[tokio::test]
+    async fn test_feed() {
+        let server = WebServer::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        server.update(&demo_app());
+
+        let mut stream = TcpStream::connect(server.addr()).await.unwrap();
+        stream
+            .write_all(
+                b"GET /ws HTTP/1.1\r\nHost: sview\r\nUpgrade: websocket\r\n\
+                  Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+                  Sec-WebSocket-Version: 13\r\n\r\n",
+            )
+            .await
+            .unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+        let head = String::from_utf8(head).unwrap();
+        assert!(head.starts_with("HTTP/1.1 101 "));
+        assert!(head.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo="));
+
+        // The latest state of each demo node first
+        let mut nodes = Vec::new();
+        for _ in 0..4 {
+            let (opcode, payload) = read_frame(&mut stream, false).await.unwrap();
+            assert_eq!(opcode, OPCODE_TEXT);
+            nodes.push(serde_json::from_slice::<serde_json::Value>(&payload).unwrap());
+        }
+        assert_eq!(nodes[0]["type"], "node");
+        assert_eq!(nodes[0]["node"], "Demo BP");
+        assert_eq!(nodes[0]["block_height"], 11_200_030);
+        assert_eq!(nodes[3]["connected"], false);
+
+        server.publish_alert(&Alert {
+            timestamp: 1_700_000_000,
+            node_name: "Demo BP".to_string(),
+            severity: AlertSeverity::Critical,
+            title: "KES Expiring".to_string(),
+            message: "7 KES periods left".to_string(),
+            resolves: None,
+        });
+        let (_, payload) = read_frame(&mut stream, false).await.unwrap();
+        let alert: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        assert_eq!(alert["type"], "alert");
+        assert_eq!(alert["title"], "KES Expiring");
+
+        // A masked close from the client is answered and ends the feed
+        stream.write_all(&[0x88, 0x80, 1, 2, 3, 4]).await.unwrap();
+        let (opcode, _) = read_frame(&mut stream, false).await.unwrap();
+        assert_eq!(opcode, OPCODE_CLOSE);
+
+        // An unmasked client frame is a protocol error
+        let mut stream = subscribe(&server, "").await;
+        for _ in 0..4 {
+            read_frame(&mut stream, false).await.unwrap();
+        }
+        stream.write_all(&[0x89, 0x00]).await.unwrap();
+        let (opcode, payload) = read_frame(&mut stream, false).await.unwrap();
+        assert_eq!(opcode, OPCODE_CLOSE);
+        assert_eq!(payload, CLOSE_PROTOCOL_ERROR.to_be_bytes());
+    }
+
+    /// Open a feed connection, returning it after the handshake response
+    async fn subscribe(server: &WebServer, extra_headers: &str) -> TcpStream {
+        let mut stream = TcpStream::connect(server.addr()).await.unwrap();
+        stream
+            .write_all(
+                format!(
+                    "GET /ws HTTP/1.1\r\nHost: {}\r\nUpgrade: websocket\r\n\
+                     Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+                     {}Sec-WebSocket-Version: 13\r\n\r\n",
+                    server.addr(),
+                    extra_headers
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+        assert!(String::from_utf8(head)
+            .unwrap()
+            .starts_with("HTTP/1.1 101 "));
+        stream
+    }
+
+    #[tokio::test]
+    async fn test_feed_origin() {
+        let server = WebServer::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let same = format!("Origin: http://{}\r\n", server.addr());
+        subscribe(&server, &same).await;
+
+        let client = reqwest::Client::new();
+        let foreign = client
+            .get(format!("http://{}/ws", server.addr()))
+            .header("Origin", "https://evil.example")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(foreign.status(), 403);
+    }
+
+    #[tokio::test]
+    async fn test_serves_page() {
+        let server = WebServer::bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        server.update(&demo_app());
+        let base = format!("http://{}", server.addr());
+        let client = reqwest::Client::new();
+
+        let page = client.get(&base).send().await.unwrap();
+        assert_eq!(page.status(), 200);
+        assert_eq!(page.headers()["content-type"], "text/html; charset=utf-8");
+        assert!(page.text().await.unwrap().contains("Demo Relay 1"));
+
+        let state = crate::follow::fetch_state(&client, &format!("{}/state", base))
+            .await
+            .unwrap();
+        assert!(state.node_metrics("Demo Relay 1").unwrap().connected);
+
+        let missing = client.get(format!("{}/admin", base)).send().await.unwrap();
+        assert_eq!(missing.status(), 404);
+        let post = client.post(&base).send().await.unwrap();
+        assert_eq!(post.status(), 405);
+        let plain = client.get(format!("{}/ws", base)).send().await.unwrap();
+        assert_eq!(plain.status(), 400);
+    }
+}